@@ -0,0 +1,96 @@
+/// The position of a single output channel.
+///
+/// The names follow the usual speaker naming of muxers and players, the AAC surround channels (`Ls` / `Rs`) of
+/// the 5.1 layouts map to [`BackLeft`](Self::BackLeft) / [`BackRight`](Self::BackRight).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelPosition {
+    /// Front left (FL)
+    FrontLeft,
+    /// Front right (FR)
+    FrontRight,
+    /// Front center (C)
+    FrontCenter,
+    /// Low frequency effects (LFE)
+    LowFrequency,
+    /// Back left (BL)
+    BackLeft,
+    /// Back right (BR)
+    BackRight,
+    /// Front left of center (FLC)
+    FrontLeftOfCenter,
+    /// Front right of center (FRC)
+    FrontRightOfCenter,
+    /// Back center (BC)
+    BackCenter,
+    /// Side left (SL)
+    SideLeft,
+    /// Side right (SR)
+    SideRight,
+    /// Top front left (TFL)
+    TopFrontLeft,
+    /// Top front right (TFR)
+    TopFrontRight,
+    /// A channel without a well known position.
+    Unknown,
+}
+
+impl ChannelPosition {
+    /// Returns the channel layout of a channel configuration, in the order in which the channels are decoded.
+    ///
+    /// Returns `None` for channel configuration `0` (the layout is defined by a program config element) and for
+    /// reserved or unsupported channel configurations.
+    ///
+    /// ISO/IEC 14496-3:2019(E) - 1.6.3.5 (Table 1.19)
+    pub const fn from_channel_configuration(channel_configuration: u8) -> Option<&'static [ChannelPosition]> {
+        use ChannelPosition::*;
+
+        match channel_configuration {
+            1 => Some(&[FrontCenter]),
+            2 => Some(&[FrontLeft, FrontRight]),
+            3 => Some(&[FrontCenter, FrontLeft, FrontRight]),
+            4 => Some(&[FrontCenter, FrontLeft, FrontRight, BackCenter]),
+            5 => Some(&[FrontCenter, FrontLeft, FrontRight, BackLeft, BackRight]),
+            6 => Some(&[FrontCenter, FrontLeft, FrontRight, BackLeft, BackRight, LowFrequency]),
+            7 => Some(&[
+                FrontCenter,
+                FrontLeftOfCenter,
+                FrontRightOfCenter,
+                FrontLeft,
+                FrontRight,
+                BackLeft,
+                BackRight,
+                LowFrequency,
+            ]),
+            11 => Some(&[
+                FrontCenter,
+                FrontLeft,
+                FrontRight,
+                BackLeft,
+                BackRight,
+                BackCenter,
+                LowFrequency,
+            ]),
+            12 => Some(&[
+                FrontCenter,
+                FrontLeft,
+                FrontRight,
+                SideLeft,
+                SideRight,
+                BackLeft,
+                BackRight,
+                LowFrequency,
+            ]),
+            14 => Some(&[
+                FrontCenter,
+                FrontLeft,
+                FrontRight,
+                BackLeft,
+                BackRight,
+                LowFrequency,
+                TopFrontLeft,
+                TopFrontRight,
+            ]),
+            _ => None,
+        }
+    }
+}
@@ -18,14 +18,20 @@ use std::io;
 
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
-use scuffle_bytes_util::BitReader;
+use scuffle_bytes_util::{BitReader, BitWriter};
+
+mod channel;
+mod pce;
+
+pub use channel::ChannelPosition;
+pub use pce::{ChannelElement, CouplingChannelElement, MatrixMixdown, ProgramConfigElement};
 
 /// A Partial Audio Specific Config
 /// ISO/IEC 14496-3:2019(E) - 1.6
 ///
 /// This struct does not represent the full AudioSpecificConfig, it only
 /// represents the top few fields.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[must_use]
 pub struct PartialAudioSpecificConfig {
     /// Audio Object Type
@@ -34,6 +40,10 @@ pub struct PartialAudioSpecificConfig {
     pub sampling_frequency: u32,
     /// Channel Configuration
     pub channel_configuration: u8,
+    /// The program config element describing the channel layout.
+    ///
+    /// Only present if the channel configuration is `0`.
+    pub program_config_element: Option<ProgramConfigElement>,
 }
 
 /// SBR Audio Object Type
@@ -152,6 +162,25 @@ impl SampleFrequencyIndex {
 }
 
 impl PartialAudioSpecificConfig {
+    /// Returns the codec string of the stream as used in the `codecs` parameter of HLS and DASH playlists,
+    /// for example `mp4a.40.2` for AAC LC.
+    ///
+    /// RFC 6381 - 3.3
+    pub fn codec_string(&self) -> String {
+        format!("mp4a.40.{}", self.audio_object_type.as_u16())
+    }
+
+    /// Returns the channel layout of the stream, in the order in which the channels are decoded.
+    ///
+    /// The layout is taken from the program config element if the channel configuration is `0`.
+    /// Returns `None` if the channel configuration is reserved or unsupported.
+    pub fn channel_layout(&self) -> Option<Vec<ChannelPosition>> {
+        match &self.program_config_element {
+            Some(pce) => Some(pce.channel_layout()),
+            None => ChannelPosition::from_channel_configuration(self.channel_configuration).map(|layout| layout.to_vec()),
+        }
+    }
+
     /// Parse the Audio Specific Config from given bytes
     /// The implementation is based on ISO/IEC 14496-3:2019(E) - 1.6.2.1 (Table
     /// 1.19) This does not parse the entire AAC Data, it only parses the
@@ -159,37 +188,171 @@ impl PartialAudioSpecificConfig {
     /// - Audio Object Type
     /// - Sampling Frequency
     /// - Channel Configuration
+    /// - Program Config Element (only if the channel configuration is `0`)
     pub fn parse(data: &[u8]) -> io::Result<Self> {
         let mut bitreader = BitReader::new_from_slice(data);
 
-        // GetAudioObjectType() # ISO/IEC 14496-3:2019(E) - 1.6.2.1 (Table 1.20)
-        let mut audio_object_type = bitreader.read_bits(5)? as u16;
-        if audio_object_type == 31 {
-            audio_object_type = 32 + bitreader.read_bits(6)? as u16;
-        }
-
-        // The table calls for us to read a 4-bit value. If the value is type FreqEscape
-        // (0xF), we need to read 24 bits to get the sampling frequency.
-        let sampling_frequency_index = SampleFrequencyIndex::from_u8(bitreader.read_bits(4)? as u8)
-            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid sampling frequency index"))?;
-
-        let sampling_frequency = match sampling_frequency_index {
-            // Uses the extended sampling frequency to represent the freq as a non-common value
-            SampleFrequencyIndex::FreqEscape => bitreader.read_bits(24)? as u32,
-            _ => sampling_frequency_index
-                .to_freq()
-                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid sampling frequency index"))?,
-        };
+        let audio_object_type = read_audio_object_type(&mut bitreader)?;
+        let sampling_frequency = read_sampling_frequency(&mut bitreader)?;
 
         // 4 Bits to get the channel configuration
         let channel_configuration = bitreader.read_bits(4)? as u8;
 
+        let program_config_element = if channel_configuration == 0 {
+            // Explicitly signalled SBR / PS carry the object type of the underlying core codec.
+            // ISO/IEC 14496-3:2019(E) - 1.6.2.1 (Table 1.19)
+            let core_object_type = if matches!(audio_object_type, 5 | 29) {
+                read_sampling_frequency(&mut bitreader)?;
+                read_audio_object_type(&mut bitreader)?
+            } else {
+                audio_object_type
+            };
+
+            if matches!(core_object_type, 1..=4 | 6 | 7 | 17 | 19..=23) {
+                // GASpecificConfig() # ISO/IEC 14496-3:2019(E) - 4.4.1 (Table 4.1)
+                let _frame_length_flag = bitreader.read_bit()?;
+                let depends_on_core_coder = bitreader.read_bit()?;
+                if depends_on_core_coder {
+                    let _core_coder_delay = bitreader.read_bits(14)?;
+                }
+                let _extension_flag = bitreader.read_bit()?;
+
+                Some(ProgramConfigElement::parse(&mut bitreader)?)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
         Ok(Self {
             audio_object_type: audio_object_type.into(),
             sampling_frequency,
             channel_configuration,
+            program_config_element,
         })
     }
+
+    /// Muxes the Audio Specific Config to the given writer.
+    ///
+    /// This writes the fields of this struct followed by a `GASpecificConfig` without a core coder or extensions,
+    /// so only the general audio object types without error resilience (`1..=4`, `6` and `7`) are supported.
+    /// If the channel configuration is `0` the program config element is required.
+    ///
+    /// ISO/IEC 14496-3:2019(E) - 1.6.2.1 (Table 1.19)
+    pub fn mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        let mut bitwriter = BitWriter::new(writer);
+
+        bitwriter.write_bits(self.core_object_type()? as u64, 5)?;
+        write_sampling_frequency(&mut bitwriter, self.sampling_frequency)?;
+        bitwriter.write_bits(self.channel_configuration as u64, 4)?;
+        self.write_ga_specific_config(&mut bitwriter)?;
+
+        bitwriter.finish()?;
+
+        Ok(())
+    }
+
+    /// Muxes the Audio Specific Config with explicit hierarchical SBR signalling to the given writer.
+    ///
+    /// The audio object type of this struct is written as the core object type of the SBR stream (object type `5`),
+    /// the sampling frequency is the one of the core codec and `extension_sampling_frequency` the output sampling
+    /// frequency of the SBR tool, usually twice the core sampling frequency.
+    ///
+    /// See [`Self::mux`] for the supported core object types.
+    ///
+    /// ISO/IEC 14496-3:2019(E) - 1.6.2.1 (Table 1.19)
+    pub fn mux_with_sbr<T: io::Write>(&self, extension_sampling_frequency: u32, writer: &mut T) -> io::Result<()> {
+        let core_object_type = self.core_object_type()?;
+        let mut bitwriter = BitWriter::new(writer);
+
+        bitwriter.write_bits(5, 5)?; // SBR
+        write_sampling_frequency(&mut bitwriter, self.sampling_frequency)?;
+        bitwriter.write_bits(self.channel_configuration as u64, 4)?;
+        write_sampling_frequency(&mut bitwriter, extension_sampling_frequency)?;
+        bitwriter.write_bits(core_object_type as u64, 5)?;
+        self.write_ga_specific_config(&mut bitwriter)?;
+
+        bitwriter.finish()?;
+
+        Ok(())
+    }
+
+    /// The supported object types are all below 31, so they never need the escape of GetAudioObjectType().
+    fn core_object_type(&self) -> io::Result<u16> {
+        match self.audio_object_type.as_u16() {
+            object_type @ (1..=4 | 6 | 7) => Ok(object_type),
+            object_type => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("audio object type {object_type} is not supported"),
+            )),
+        }
+    }
+
+    /// GASpecificConfig() # ISO/IEC 14496-3:2019(E) - 4.4.1 (Table 4.1)
+    fn write_ga_specific_config<T: io::Write>(&self, bitwriter: &mut BitWriter<T>) -> io::Result<()> {
+        bitwriter.write_bit(false)?; // frame length flag
+        bitwriter.write_bit(false)?; // depends on core coder
+        bitwriter.write_bit(false)?; // extension flag
+
+        if self.channel_configuration == 0 {
+            self.program_config_element
+                .as_ref()
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "channel configuration 0 requires a program config element",
+                    )
+                })?
+                .mux(bitwriter)?;
+        }
+
+        if self.audio_object_type.as_u16() == 6 {
+            bitwriter.write_bits(0, 3)?; // layer nr
+        }
+
+        Ok(())
+    }
+}
+
+/// GetAudioObjectType() # ISO/IEC 14496-3:2019(E) - 1.6.2.1 (Table 1.20)
+fn read_audio_object_type<T: io::Read>(bitreader: &mut BitReader<T>) -> io::Result<u16> {
+    let mut audio_object_type = bitreader.read_bits(5)? as u16;
+    if audio_object_type == 31 {
+        audio_object_type = 32 + bitreader.read_bits(6)? as u16;
+    }
+
+    Ok(audio_object_type)
+}
+
+fn read_sampling_frequency<T: io::Read>(bitreader: &mut BitReader<T>) -> io::Result<u32> {
+    // The table calls for us to read a 4-bit value. If the value is type FreqEscape
+    // (0xF), we need to read 24 bits to get the sampling frequency.
+    let sampling_frequency_index = SampleFrequencyIndex::from_u8(bitreader.read_bits(4)? as u8)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid sampling frequency index"))?;
+
+    match sampling_frequency_index {
+        // Uses the extended sampling frequency to represent the freq as a non-common value
+        SampleFrequencyIndex::FreqEscape => Ok(bitreader.read_bits(24)? as u32),
+        _ => sampling_frequency_index
+            .to_freq()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid sampling frequency index")),
+    }
+}
+
+fn write_sampling_frequency<T: io::Write>(bitwriter: &mut BitWriter<T>, sampling_frequency: u32) -> io::Result<()> {
+    // Common frequencies are written as their index, all others are escaped and written as 24 bits.
+    let sampling_frequency_index = (0..SampleFrequencyIndex::FreqReserved as u8)
+        .filter_map(SampleFrequencyIndex::from_u8)
+        .find(|idx| idx.to_freq() == Some(sampling_frequency));
+
+    match sampling_frequency_index {
+        Some(idx) => bitwriter.write_bits(idx as u64, 4),
+        None => {
+            bitwriter.write_bits(SampleFrequencyIndex::FreqEscape as u64, 4)?;
+            bitwriter.write_bits(sampling_frequency as u64, 24)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -207,6 +370,266 @@ mod tests {
         assert_eq!(config.audio_object_type, AudioObjectType::AacLowComplexity);
         assert_eq!(config.sampling_frequency, 44100);
         assert_eq!(config.channel_configuration, 2);
+        assert_eq!(config.codec_string(), "mp4a.40.2");
+    }
+
+    /// Writes an AAC LC AudioSpecificConfig with channel configuration 0 and a 5.1 program config element.
+    fn pce_5_1_config(audio_object_type: u64) -> Vec<u8> {
+        let mut writer = scuffle_bytes_util::BitWriter::new(Vec::new());
+
+        writer.write_bits(audio_object_type, 5).unwrap();
+        writer.write_bits(SampleFrequencyIndex::Freq48000 as u64, 4).unwrap();
+        writer.write_bits(0, 4).unwrap(); // channel configuration
+        if audio_object_type == 5 {
+            writer.write_bits(SampleFrequencyIndex::Freq96000 as u64, 4).unwrap(); // extension sampling frequency
+            writer.write_bits(2, 5).unwrap(); // core audio object type
+        }
+
+        // GASpecificConfig
+        writer.write_bit(false).unwrap(); // frame length flag
+        writer.write_bit(true).unwrap(); // depends on core coder
+        writer.write_bits(0, 14).unwrap(); // core coder delay
+        writer.write_bit(false).unwrap(); // extension flag
+
+        // program_config_element
+        writer.write_bits(0, 4).unwrap(); // element instance tag
+        writer.write_bits(1, 2).unwrap(); // object type
+        writer.write_bits(SampleFrequencyIndex::Freq48000 as u64, 4).unwrap();
+        writer.write_bits(2, 4).unwrap(); // front
+        writer.write_bits(0, 4).unwrap(); // side
+        writer.write_bits(1, 4).unwrap(); // back
+        writer.write_bits(1, 2).unwrap(); // lfe
+        writer.write_bits(0, 3).unwrap(); // assoc data
+        writer.write_bits(1, 4).unwrap(); // valid cc
+        writer.write_bit(false).unwrap(); // mono mixdown
+        writer.write_bit(true).unwrap(); // stereo mixdown
+        writer.write_bits(3, 4).unwrap();
+        writer.write_bit(true).unwrap(); // matrix mixdown
+        writer.write_bits(2, 2).unwrap();
+        writer.write_bit(true).unwrap();
+        writer.write_bits(0b0_0000, 5).unwrap(); // front SCE
+        writer.write_bits(0b1_0000, 5).unwrap(); // front CPE
+        writer.write_bits(0b1_0001, 5).unwrap(); // back CPE
+        writer.write_bits(0b0000, 4).unwrap(); // lfe
+        writer.write_bits(0b1_0010, 5).unwrap(); // cc
+        writer.align().unwrap();
+        writer.write_bits(2, 8).unwrap(); // comment field bytes
+        writer.write_bits(u64::from(b'h'), 8).unwrap();
+        writer.write_bits(u64::from(b'i'), 8).unwrap();
+
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_aac_config_mux() {
+        let config = PartialAudioSpecificConfig {
+            audio_object_type: AudioObjectType::AacLowComplexity,
+            sampling_frequency: 44100,
+            channel_configuration: 2,
+            program_config_element: None,
+        };
+
+        let mut buf = Vec::new();
+        config.mux(&mut buf).unwrap();
+        assert_eq!(buf, [0x12, 0x10]);
+        assert_eq!(PartialAudioSpecificConfig::parse(&buf).unwrap(), config);
+
+        // Uncommon sampling frequencies are escaped.
+        let config = PartialAudioSpecificConfig {
+            sampling_frequency: 12345,
+            ..config
+        };
+        let mut buf = Vec::new();
+        config.mux(&mut buf).unwrap();
+        assert_eq!(buf.len(), 5);
+        assert_eq!(PartialAudioSpecificConfig::parse(&buf).unwrap(), config);
+
+        // The program config element is written for channel configuration 0.
+        let config = PartialAudioSpecificConfig::parse(&pce_5_1_config(2)).unwrap();
+        let mut buf = Vec::new();
+        config.mux(&mut buf).unwrap();
+        assert_eq!(PartialAudioSpecificConfig::parse(&buf).unwrap(), config);
+
+        let err = PartialAudioSpecificConfig {
+            program_config_element: None,
+            ..config
+        }
+        .mux(&mut Vec::new())
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let err = PartialAudioSpecificConfig {
+            audio_object_type: AudioObjectType::Unknown(5),
+            sampling_frequency: 44100,
+            channel_configuration: 2,
+            program_config_element: None,
+        }
+        .mux(&mut Vec::new())
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_aac_config_mux_with_sbr() {
+        let config = PartialAudioSpecificConfig {
+            audio_object_type: AudioObjectType::AacLowComplexity,
+            sampling_frequency: 24000,
+            channel_configuration: 2,
+            program_config_element: None,
+        };
+
+        let mut buf = Vec::new();
+        config.mux_with_sbr(48000, &mut buf).unwrap();
+        // 25 bits: object type 5, 24kHz, 2 channels, 48kHz, object type 2 and the GASpecificConfig flags.
+        assert_eq!(buf, [0x2b, 0x11, 0x88, 0x00]);
+
+        let parsed = PartialAudioSpecificConfig::parse(&buf).unwrap();
+        assert_eq!(parsed.audio_object_type, AudioObjectType::Unknown(5));
+        assert_eq!(parsed.sampling_frequency, 24000);
+        assert_eq!(parsed.channel_configuration, 2);
+
+        // The program config element follows the core object type.
+        let config = PartialAudioSpecificConfig::parse(&pce_5_1_config(2)).unwrap();
+        let mut buf = Vec::new();
+        config.mux_with_sbr(96000, &mut buf).unwrap();
+        let parsed = PartialAudioSpecificConfig::parse(&buf).unwrap();
+        assert_eq!(parsed.audio_object_type, AudioObjectType::Unknown(5));
+        assert_eq!(parsed.program_config_element, config.program_config_element);
+
+        let err = parsed.mux_with_sbr(96000, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_aac_config_parse_pce() {
+        let config = PartialAudioSpecificConfig::parse(&pce_5_1_config(2)).unwrap();
+        assert_eq!(config.audio_object_type, AudioObjectType::AacLowComplexity);
+        assert_eq!(config.sampling_frequency, 48000);
+        assert_eq!(config.channel_configuration, 0);
+
+        let pce = config
+            .program_config_element
+            .as_ref()
+            .expect("missing program config element");
+        assert_eq!(
+            pce,
+            &ProgramConfigElement {
+                element_instance_tag: 0,
+                object_type: 1,
+                sampling_frequency_index: 3,
+                front_elements: vec![
+                    ChannelElement {
+                        is_cpe: false,
+                        tag_select: 0
+                    },
+                    ChannelElement {
+                        is_cpe: true,
+                        tag_select: 0
+                    },
+                ],
+                side_elements: vec![],
+                back_elements: vec![ChannelElement {
+                    is_cpe: true,
+                    tag_select: 1
+                }],
+                lfe_elements: vec![0],
+                assoc_data_elements: vec![],
+                cc_elements: vec![CouplingChannelElement {
+                    is_ind_sw: true,
+                    tag_select: 2
+                }],
+                mono_mixdown_element_number: None,
+                stereo_mixdown_element_number: Some(3),
+                matrix_mixdown: Some(MatrixMixdown {
+                    idx: 2,
+                    pseudo_surround_enable: true
+                }),
+                comment: b"hi".to_vec(),
+            }
+        );
+        assert_eq!(pce.channel_count(), 6);
+        assert_eq!(
+            config.channel_layout().unwrap(),
+            ChannelPosition::from_channel_configuration(6).unwrap()
+        );
+
+        // Explicitly signalled SBR wraps the same GASpecificConfig.
+        let config = PartialAudioSpecificConfig::parse(&pce_5_1_config(5)).unwrap();
+        assert_eq!(config.audio_object_type, AudioObjectType::Unknown(5));
+        assert_eq!(config.program_config_element.unwrap().channel_count(), 6);
+
+        // A truncated program config element is an error.
+        let data = pce_5_1_config(2);
+        assert!(PartialAudioSpecificConfig::parse(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_channel_layout() {
+        use ChannelPosition::*;
+
+        let stereo = PartialAudioSpecificConfig::parse(&[0x12, 0x10]).unwrap();
+        assert_eq!(stereo.program_config_element, None);
+        assert_eq!(stereo.channel_layout().unwrap(), [FrontLeft, FrontRight]);
+
+        for (config, channels) in [
+            (1, 1),
+            (2, 2),
+            (3, 3),
+            (4, 4),
+            (5, 5),
+            (6, 6),
+            (7, 8),
+            (11, 7),
+            (12, 8),
+            (14, 8),
+        ] {
+            let layout = ChannelPosition::from_channel_configuration(config).unwrap();
+            assert_eq!(layout.len(), channels, "channel configuration {config}");
+            assert_eq!(layout.iter().filter(|p| **p == LowFrequency).count(), (channels > 5) as usize);
+        }
+
+        for config in [0, 8, 9, 10, 13, 15] {
+            assert_eq!(ChannelPosition::from_channel_configuration(config), None);
+        }
+
+        let element = |is_cpe| ChannelElement { is_cpe, tag_select: 0 };
+        let pce = ProgramConfigElement {
+            element_instance_tag: 0,
+            object_type: 1,
+            sampling_frequency_index: 3,
+            front_elements: vec![element(false), element(true), element(true), element(true)],
+            side_elements: vec![element(true), element(false)],
+            back_elements: vec![element(true), element(true), element(false)],
+            lfe_elements: vec![0],
+            assoc_data_elements: vec![],
+            cc_elements: vec![],
+            mono_mixdown_element_number: None,
+            stereo_mixdown_element_number: None,
+            matrix_mixdown: None,
+            comment: vec![],
+        };
+        assert_eq!(pce.channel_count(), 16);
+        assert_eq!(
+            pce.channel_layout(),
+            [
+                FrontCenter,
+                Unknown,
+                Unknown,
+                FrontLeftOfCenter,
+                FrontRightOfCenter,
+                FrontLeft,
+                FrontRight,
+                SideLeft,
+                SideRight,
+                Unknown,
+                BackLeft,
+                BackRight,
+                Unknown,
+                Unknown,
+                BackCenter,
+                LowFrequency,
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,287 @@
+use std::io;
+
+use scuffle_bytes_util::{BitReader, BitWriter};
+
+use crate::ChannelPosition;
+
+/// A channel element referenced by a program config element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelElement {
+    /// Whether the element is a channel pair element (two channels) or a single channel element (one channel).
+    pub is_cpe: bool,
+    /// The instance tag of the element.
+    pub tag_select: u8,
+}
+
+/// A coupling channel element referenced by a program config element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CouplingChannelElement {
+    /// Whether the element is an independently switched coupling channel element.
+    pub is_ind_sw: bool,
+    /// The instance tag of the element.
+    pub tag_select: u8,
+}
+
+/// The matrix downmix coefficients signalled by a program config element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatrixMixdown {
+    /// The index of the downmix coefficient.
+    pub idx: u8,
+    /// Whether pseudo surround is enabled.
+    pub pseudo_surround_enable: bool,
+}
+
+/// Program Config Element
+/// ISO/IEC 14496-3:2019(E) - 4.4.1.1 (Table 4.2)
+///
+/// Describes the channel layout of a stream with channel configuration `0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramConfigElement {
+    /// The instance tag of this element.
+    pub element_instance_tag: u8,
+    /// The object type of the program.
+    pub object_type: u8,
+    /// The sampling frequency index of the program.
+    pub sampling_frequency_index: u8,
+    /// The front channel elements, ordered from the center outwards.
+    pub front_elements: Vec<ChannelElement>,
+    /// The side channel elements, ordered from the front to the back.
+    pub side_elements: Vec<ChannelElement>,
+    /// The back channel elements, ordered from the sides to the center.
+    pub back_elements: Vec<ChannelElement>,
+    /// The instance tags of the LFE channel elements.
+    pub lfe_elements: Vec<u8>,
+    /// The instance tags of the associated data elements.
+    pub assoc_data_elements: Vec<u8>,
+    /// The coupling channel elements.
+    pub cc_elements: Vec<CouplingChannelElement>,
+    /// The element number of the mono downmix, if present.
+    pub mono_mixdown_element_number: Option<u8>,
+    /// The element number of the stereo downmix, if present.
+    pub stereo_mixdown_element_number: Option<u8>,
+    /// The matrix downmix, if present.
+    pub matrix_mixdown: Option<MatrixMixdown>,
+    /// The comment field.
+    pub comment: Vec<u8>,
+}
+
+impl ProgramConfigElement {
+    /// Parses a program config element.
+    ///
+    /// The byte alignment inside the element is relative to the start of the given reader, so the reader should start
+    /// at the beginning of the enclosing `AudioSpecificConfig` or raw data block.
+    pub fn parse<T: io::Read>(reader: &mut BitReader<T>) -> io::Result<Self> {
+        let element_instance_tag = reader.read_bits(4)? as u8;
+        let object_type = reader.read_bits(2)? as u8;
+        let sampling_frequency_index = reader.read_bits(4)? as u8;
+        let num_front_channel_elements = reader.read_bits(4)? as usize;
+        let num_side_channel_elements = reader.read_bits(4)? as usize;
+        let num_back_channel_elements = reader.read_bits(4)? as usize;
+        let num_lfe_channel_elements = reader.read_bits(2)? as usize;
+        let num_assoc_data_elements = reader.read_bits(3)? as usize;
+        let num_valid_cc_elements = reader.read_bits(4)? as usize;
+
+        let mono_mixdown_element_number = if reader.read_bit()? {
+            Some(reader.read_bits(4)? as u8)
+        } else {
+            None
+        };
+
+        let stereo_mixdown_element_number = if reader.read_bit()? {
+            Some(reader.read_bits(4)? as u8)
+        } else {
+            None
+        };
+
+        let matrix_mixdown = if reader.read_bit()? {
+            Some(MatrixMixdown {
+                idx: reader.read_bits(2)? as u8,
+                pseudo_surround_enable: reader.read_bit()?,
+            })
+        } else {
+            None
+        };
+
+        let mut read_channel_elements = |count: usize| -> io::Result<Vec<ChannelElement>> {
+            (0..count)
+                .map(|_| {
+                    Ok(ChannelElement {
+                        is_cpe: reader.read_bit()?,
+                        tag_select: reader.read_bits(4)? as u8,
+                    })
+                })
+                .collect()
+        };
+
+        let front_elements = read_channel_elements(num_front_channel_elements)?;
+        let side_elements = read_channel_elements(num_side_channel_elements)?;
+        let back_elements = read_channel_elements(num_back_channel_elements)?;
+
+        let lfe_elements = (0..num_lfe_channel_elements)
+            .map(|_| Ok(reader.read_bits(4)? as u8))
+            .collect::<io::Result<_>>()?;
+
+        let assoc_data_elements = (0..num_assoc_data_elements)
+            .map(|_| Ok(reader.read_bits(4)? as u8))
+            .collect::<io::Result<_>>()?;
+
+        let cc_elements = (0..num_valid_cc_elements)
+            .map(|_| {
+                Ok(CouplingChannelElement {
+                    is_ind_sw: reader.read_bit()?,
+                    tag_select: reader.read_bits(4)? as u8,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        reader.align()?;
+
+        let comment_field_bytes = reader.read_bits(8)? as usize;
+        let comment = (0..comment_field_bytes)
+            .map(|_| Ok(reader.read_bits(8)? as u8))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self {
+            element_instance_tag,
+            object_type,
+            sampling_frequency_index,
+            front_elements,
+            side_elements,
+            back_elements,
+            lfe_elements,
+            assoc_data_elements,
+            cc_elements,
+            mono_mixdown_element_number,
+            stereo_mixdown_element_number,
+            matrix_mixdown,
+            comment,
+        })
+    }
+
+    /// Muxes the program config element to the given writer.
+    ///
+    /// The byte alignment inside the element is relative to the start of the given writer, see [`Self::parse`].
+    pub fn mux<T: io::Write>(&self, writer: &mut BitWriter<T>) -> io::Result<()> {
+        writer.write_bits(self.element_instance_tag as u64, 4)?;
+        writer.write_bits(self.object_type as u64, 2)?;
+        writer.write_bits(self.sampling_frequency_index as u64, 4)?;
+        writer.write_bits(self.front_elements.len() as u64, 4)?;
+        writer.write_bits(self.side_elements.len() as u64, 4)?;
+        writer.write_bits(self.back_elements.len() as u64, 4)?;
+        writer.write_bits(self.lfe_elements.len() as u64, 2)?;
+        writer.write_bits(self.assoc_data_elements.len() as u64, 3)?;
+        writer.write_bits(self.cc_elements.len() as u64, 4)?;
+
+        for element_number in [self.mono_mixdown_element_number, self.stereo_mixdown_element_number] {
+            writer.write_bit(element_number.is_some())?;
+            if let Some(element_number) = element_number {
+                writer.write_bits(element_number as u64, 4)?;
+            }
+        }
+
+        writer.write_bit(self.matrix_mixdown.is_some())?;
+        if let Some(matrix_mixdown) = &self.matrix_mixdown {
+            writer.write_bits(matrix_mixdown.idx as u64, 2)?;
+            writer.write_bit(matrix_mixdown.pseudo_surround_enable)?;
+        }
+
+        for element in self
+            .front_elements
+            .iter()
+            .chain(&self.side_elements)
+            .chain(&self.back_elements)
+        {
+            writer.write_bit(element.is_cpe)?;
+            writer.write_bits(element.tag_select as u64, 4)?;
+        }
+
+        for tag_select in self.lfe_elements.iter().chain(&self.assoc_data_elements) {
+            writer.write_bits(*tag_select as u64, 4)?;
+        }
+
+        for element in &self.cc_elements {
+            writer.write_bit(element.is_ind_sw)?;
+            writer.write_bits(element.tag_select as u64, 4)?;
+        }
+
+        writer.align()?;
+
+        writer.write_bits(self.comment.len() as u64, 8)?;
+        for byte in &self.comment {
+            writer.write_bits(*byte as u64, 8)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of output channels described by this element.
+    pub fn channel_count(&self) -> usize {
+        self.front_elements
+            .iter()
+            .chain(&self.side_elements)
+            .chain(&self.back_elements)
+            .map(|e| if e.is_cpe { 2 } else { 1 })
+            .sum::<usize>()
+            + self.lfe_elements.len()
+    }
+
+    /// Returns the channel layout described by this element, in the order in which the channels are decoded.
+    ///
+    /// Channels that do not fit a well known position (for example a second pair of side channels) are reported as
+    /// [`ChannelPosition::Unknown`].
+    pub fn channel_layout(&self) -> Vec<ChannelPosition> {
+        let mut layout = Vec::with_capacity(self.channel_count());
+
+        // Front elements are ordered from the center outwards, so the last pair is the front left / right pair
+        // and the pair before it is the left / right of center pair.
+        let front_pairs = self.front_elements.iter().filter(|e| e.is_cpe).count();
+        let mut pair_idx = 0;
+        for (idx, element) in self.front_elements.iter().enumerate() {
+            if element.is_cpe {
+                pair_idx += 1;
+                layout.extend(match front_pairs - pair_idx {
+                    0 => [ChannelPosition::FrontLeft, ChannelPosition::FrontRight],
+                    1 => [ChannelPosition::FrontLeftOfCenter, ChannelPosition::FrontRightOfCenter],
+                    _ => [ChannelPosition::Unknown, ChannelPosition::Unknown],
+                });
+            } else if idx == 0 {
+                layout.push(ChannelPosition::FrontCenter);
+            } else {
+                layout.push(ChannelPosition::Unknown);
+            }
+        }
+
+        let mut side_pairs = 0;
+        for element in &self.side_elements {
+            if element.is_cpe {
+                side_pairs += 1;
+                layout.extend(match side_pairs {
+                    1 => [ChannelPosition::SideLeft, ChannelPosition::SideRight],
+                    _ => [ChannelPosition::Unknown, ChannelPosition::Unknown],
+                });
+            } else {
+                layout.push(ChannelPosition::Unknown);
+            }
+        }
+
+        // Back elements are ordered from the sides to the center, so a trailing single channel is the back center.
+        let mut back_pairs = 0;
+        for (idx, element) in self.back_elements.iter().enumerate() {
+            if element.is_cpe {
+                back_pairs += 1;
+                layout.extend(match back_pairs {
+                    1 => [ChannelPosition::BackLeft, ChannelPosition::BackRight],
+                    _ => [ChannelPosition::Unknown, ChannelPosition::Unknown],
+                });
+            } else if idx == self.back_elements.len() - 1 {
+                layout.push(ChannelPosition::BackCenter);
+            } else {
+                layout.push(ChannelPosition::Unknown);
+            }
+        }
+
+        layout.extend(self.lfe_elements.iter().map(|_| ChannelPosition::LowFrequency));
+
+        layout
+    }
+}
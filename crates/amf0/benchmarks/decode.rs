@@ -0,0 +1,89 @@
+use std::hint::black_box;
+use std::sync::LazyLock;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use scuffle_amf0::{Amf0Decoder, Amf0Encoder, Amf0Object, Amf0Value};
+use scuffle_bytes_util::StringInterner;
+
+const KEYS: &[&str] = &[
+    "app",
+    "type",
+    "flashVer",
+    "swfUrl",
+    "tcUrl",
+    "fpad",
+    "capabilities",
+    "audioCodecs",
+    "videoCodecs",
+    "videoFunction",
+    "pageUrl",
+    "objectEncoding",
+];
+
+static INTERNER: LazyLock<StringInterner> = LazyLock::new(|| KEYS.iter().copied().chain(["connect"]).collect());
+
+/// A `connect` command as sent by most RTMP clients.
+fn connect_command() -> Vec<u8> {
+    let object: Amf0Object = KEYS
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let value = if i % 2 == 0 {
+                Amf0Value::String("rtmp://localhost/live".into())
+            } else {
+                Amf0Value::Number(i as f64)
+            };
+
+            ((*key).into(), value)
+        })
+        .collect();
+
+    let mut buf = Vec::new();
+    let mut encoder = Amf0Encoder::new(&mut buf);
+    encoder.encode_string("connect").unwrap();
+    encoder.encode_number(1.0).unwrap();
+    encoder.encode_object(&object).unwrap();
+
+    buf
+}
+
+fn decode(c: &mut Criterion) {
+    let payload = connect_command();
+
+    let mut group = c.benchmark_group("decode");
+
+    group.bench_with_input(BenchmarkId::new("slice", "no-interner"), &payload, |b, payload| {
+        b.iter(|| {
+            let mut decoder = Amf0Decoder::from_slice(black_box(payload));
+            black_box(decoder.decode_all().unwrap());
+        });
+    });
+
+    group.bench_with_input(BenchmarkId::new("slice", "interner"), &payload, |b, payload| {
+        b.iter(|| {
+            let mut decoder = Amf0Decoder::from_slice(black_box(payload)).with_interner(&INTERNER);
+            black_box(decoder.decode_all().unwrap());
+        });
+    });
+
+    let payload = bytes::Bytes::from(payload);
+
+    group.bench_with_input(BenchmarkId::new("buf", "no-interner"), &payload, |b, payload| {
+        b.iter(|| {
+            let mut decoder = Amf0Decoder::from_buf(black_box(payload.clone()));
+            black_box(decoder.decode_all().unwrap());
+        });
+    });
+
+    group.bench_with_input(BenchmarkId::new("buf", "interner"), &payload, |b, payload| {
+        b.iter(|| {
+            let mut decoder = Amf0Decoder::from_buf(black_box(payload.clone())).with_interner(&INTERNER);
+            black_box(decoder.decode_all().unwrap());
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -1,13 +1,14 @@
 //! AMF0 decoder
 
 use std::io;
+use std::str::Utf8Error;
 
 use byteorder::{BigEndian, ReadBytesExt};
 use num_traits::FromPrimitive;
-use scuffle_bytes_util::StringCow;
 use scuffle_bytes_util::zero_copy::ZeroCopyReader;
+use scuffle_bytes_util::{StringCow, StringInterner, StringReader};
 
-use crate::{Amf0Array, Amf0Error, Amf0Marker, Amf0Object, Amf0Value};
+use crate::{Amf0Array, Amf0Date, Amf0Error, Amf0Marker, Amf0Object, Amf0Value};
 
 /// AMF0 decoder.
 ///
@@ -16,8 +17,16 @@ use crate::{Amf0Array, Amf0Error, Amf0Marker, Amf0Object, Amf0Value};
 pub struct Amf0Decoder<R> {
     pub(crate) reader: R,
     pub(crate) next_marker: Option<Amf0Marker>,
+    pub(crate) interner: Option<&'static StringInterner>,
+    pub(crate) strings: StringReader,
 }
 
+/// The maximum number of items that are allocated upfront when decoding arrays.
+///
+/// The size of an array is read from the input, so it can't be trusted.
+/// Larger arrays still decode fine, they just grow as their values are decoded.
+const MAX_PREALLOCATED_ITEMS: usize = 1024;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum ObjectHeader<'a> {
     Object,
@@ -38,6 +47,8 @@ where
         Self {
             reader: buf.into(),
             next_marker: None,
+            interner: None,
+            strings: StringReader::utf8(),
         }
     }
 }
@@ -51,6 +62,8 @@ where
         Self {
             reader: reader.into(),
             next_marker: None,
+            interner: None,
+            strings: StringReader::utf8(),
         }
     }
 }
@@ -61,10 +74,33 @@ impl<'a> Amf0Decoder<scuffle_bytes_util::zero_copy::Slice<'a>> {
         Self {
             reader: slice.into(),
             next_marker: None,
+            interner: None,
+            strings: StringReader::utf8(),
         }
     }
 }
 
+impl<R> Amf0Decoder<R> {
+    /// Use the given [`StringInterner`] to resolve strings.
+    ///
+    /// Strings found in the interner are returned as [`StringCow::StaticRef`]
+    /// instead of being copied out of the buffer, which avoids allocating for
+    /// frequently seen strings like object keys or command names.
+    pub fn with_interner(mut self, interner: &'static StringInterner) -> Self {
+        self.interner = Some(interner);
+        self
+    }
+
+    /// Limits the length of decoded strings and XML documents to `max_len` bytes.
+    ///
+    /// The length of a string is checked before it is read, so a hostile length prefix can not make the
+    /// decoder allocate large buffers when reading from a [`std::io::Read`].
+    pub fn with_max_string_len(mut self, max_len: usize) -> Self {
+        self.strings = self.strings.max_len(max_len);
+        self
+    }
+}
+
 impl<'a, R> Amf0Decoder<R>
 where
     R: ZeroCopyReader<'a>,
@@ -75,8 +111,10 @@ where
 
         match marker {
             Amf0Marker::Boolean => self.decode_boolean().map(Into::into),
-            Amf0Marker::Number | Amf0Marker::Date => self.decode_number().map(Into::into),
-            Amf0Marker::String | Amf0Marker::LongString | Amf0Marker::XmlDocument => self.decode_string().map(Into::into),
+            Amf0Marker::Number => self.decode_number().map(Into::into),
+            Amf0Marker::Date => self.decode_date().map(Into::into),
+            Amf0Marker::String | Amf0Marker::LongString => self.decode_string().map(Into::into),
+            Amf0Marker::XmlDocument => self.decode_xml_document().map(Amf0Value::XmlDocument),
             Amf0Marker::Null | Amf0Marker::Undefined => self.decode_null().map(|_| Amf0Value::Null),
             Amf0Marker::Object | Amf0Marker::TypedObject | Amf0Marker::EcmaArray => self.decode_object().map(Into::into),
             Amf0Marker::StrictArray => self.decode_strict_array().map(Into::into),
@@ -158,6 +196,16 @@ where
         Ok(number)
     }
 
+    /// Decode a date from the buffer.
+    pub fn decode_date(&mut self) -> Result<Amf0Date, Amf0Error> {
+        self.expect_marker(&[Amf0Marker::Date])?;
+
+        let millis = self.reader.as_std().read_f64::<BigEndian>()?;
+        let timezone = self.reader.as_std().read_i16::<BigEndian>()?;
+
+        Ok(Amf0Date { millis, timezone })
+    }
+
     /// Decode a boolean from the buffer.
     pub fn decode_boolean(&mut self) -> Result<bool, Amf0Error> {
         self.expect_marker(&[Amf0Marker::Boolean])?;
@@ -168,8 +216,22 @@ where
     pub(crate) fn decode_normal_string(&mut self) -> Result<StringCow<'a>, Amf0Error> {
         let len = self.reader.as_std().read_u16::<BigEndian>()? as usize;
 
-        let bytes = self.reader.try_read(len)?;
-        Ok(StringCow::from_bytes(bytes.into_bytes().try_into()?))
+        self.read_string(len)
+    }
+
+    fn read_string(&mut self, len: usize) -> Result<StringCow<'a>, Amf0Error> {
+        let bytes = self.strings.read_bytes(&mut self.reader, len)?;
+
+        if let Some(string) = self.interner.and_then(|interner| interner.get(bytes.as_bytes())) {
+            return Ok(StringCow::from_static(string));
+        }
+
+        self.strings.decode(bytes).map_err(
+            |err| match err.get_ref().and_then(|inner| inner.downcast_ref::<Utf8Error>()) {
+                Some(utf8_error) => Amf0Error::StringParseError(*utf8_error),
+                None => Amf0Error::Io(err),
+            },
+        )
     }
 
     /// Decode a string from the buffer.
@@ -185,8 +247,15 @@ where
             self.reader.as_std().read_u32::<BigEndian>()? as usize
         };
 
-        let bytes = self.reader.try_read(len)?;
-        Ok(StringCow::from_bytes(bytes.into_bytes().try_into()?))
+        self.read_string(len)
+    }
+
+    /// Decode an XML document from the buffer.
+    pub fn decode_xml_document(&mut self) -> Result<StringCow<'a>, Amf0Error> {
+        self.expect_marker(&[Amf0Marker::XmlDocument])?;
+
+        let len = self.reader.as_std().read_u32::<BigEndian>()? as usize;
+        self.read_string(len)
     }
 
     /// Decode a null value from the buffer.
@@ -268,7 +337,7 @@ where
                 Ok(object)
             }
             ObjectHeader::EcmaArray { size } => {
-                let mut object = Amf0Object::with_capacity(size as usize);
+                let mut object = Amf0Object::with_capacity((size as usize).min(MAX_PREALLOCATED_ITEMS));
 
                 for _ in 0..size {
                     // Object keys are not preceeded with a marker and are always normal strings
@@ -301,7 +370,7 @@ where
     pub fn decode_strict_array(&mut self) -> Result<Amf0Array<'a>, Amf0Error> {
         let size = self.decode_strict_array_header()? as usize;
 
-        let mut array = Vec::with_capacity(size);
+        let mut array = Vec::with_capacity(size.min(MAX_PREALLOCATED_ITEMS));
 
         for _ in 0..size {
             let value = self.decode_value()?;
@@ -338,8 +407,10 @@ impl<'de, R> std::iter::FusedIterator for Amf0DecoderStream<'_, 'de, R> where R:
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
+    use scuffle_bytes_util::{StringCow, StringInterner};
+
     use super::Amf0Decoder;
-    use crate::{Amf0Marker, Amf0Value};
+    use crate::{Amf0Error, Amf0Marker, Amf0Value};
 
     #[test]
     fn strict_array() {
@@ -377,8 +448,47 @@ mod tests {
         let mut decoder = Amf0Decoder::from_slice(&bytes);
         let object = decoder.decode_object().unwrap();
         assert_eq!(object.len(), 2);
-        assert_eq!(*object.get(&"abc".into()).unwrap(), Amf0Value::String("val".into()));
-        assert_eq!(*object.get(&"defg".into()).unwrap(), Amf0Value::Boolean(true));
+        assert_eq!(*object.get(&StringCow::from("abc")).unwrap(), Amf0Value::String("val".into()));
+        assert_eq!(*object.get(&StringCow::from("defg")).unwrap(), Amf0Value::Boolean(true));
+    }
+
+    #[test]
+    fn oversized_array_headers() {
+        // The sizes are read from the input, they must not be used to allocate memory upfront.
+        let bytes = [Amf0Marker::StrictArray as u8, 0xff, 0xff, 0xff, 0xff];
+        let mut decoder = Amf0Decoder::from_slice(&bytes);
+        assert!(decoder.decode_strict_array().is_err());
+
+        let bytes = [Amf0Marker::EcmaArray as u8, 0xff, 0xff, 0xff, 0xff];
+        let mut decoder = Amf0Decoder::from_slice(&bytes);
+        assert!(decoder.decode_object().is_err());
+    }
+
+    #[test]
+    fn invalid_utf8_string() {
+        let bytes = [Amf0Marker::String as u8, 0, 2, 0xff, 0xfe];
+        let mut decoder = Amf0Decoder::from_slice(&bytes);
+        assert!(matches!(decoder.decode_string(), Err(Amf0Error::StringParseError(_))));
+
+        // Truncated strings are still io errors.
+        let bytes = [Amf0Marker::String as u8, 0, 3, b'a'];
+        let mut decoder = Amf0Decoder::from_slice(&bytes);
+        assert!(matches!(decoder.decode_string(), Err(Amf0Error::Io(_))));
+    }
+
+    #[test]
+    fn max_string_len() {
+        let bytes = [Amf0Marker::String as u8, 0, 3, b'a', b'b', b'c'];
+        let mut decoder = Amf0Decoder::from_slice(&bytes).with_max_string_len(3);
+        assert_eq!(decoder.decode_string().unwrap(), "abc");
+
+        let mut decoder = Amf0Decoder::from_slice(&bytes).with_max_string_len(2);
+        assert!(matches!(decoder.decode_string(), Err(Amf0Error::Io(err)) if err.kind() == std::io::ErrorKind::InvalidData));
+
+        // The length is checked before the string is read.
+        let bytes = [Amf0Marker::LongString as u8, 0xff, 0xff, 0xff, 0xff];
+        let mut decoder = Amf0Decoder::from_reader(&bytes[..]).with_max_string_len(1024);
+        assert!(matches!(decoder.decode_value(), Err(Amf0Error::Io(err)) if err.kind() == std::io::ErrorKind::InvalidData));
     }
 
     #[test]
@@ -399,4 +509,34 @@ mod tests {
         assert_eq!(stream.next().unwrap().unwrap(), Amf0Value::Null);
         assert!(stream.next().is_none());
     }
+
+    #[test]
+    fn interner() {
+        static INTERNER: std::sync::LazyLock<StringInterner> =
+            std::sync::LazyLock::new(|| ["abc", "val"].into_iter().collect());
+
+        #[rustfmt::skip]
+        let bytes = [
+            Amf0Marker::Object as u8,
+            0, 3, b'a', b'b', b'c', // key
+            Amf0Marker::String as u8,
+            0, 3, b'v', b'a', b'l', // value
+            0, 4, b'd', b'e', b'f', b'g', // key
+            Amf0Marker::String as u8,
+            0, 3, b'x', b'y', b'z', // value
+            0, 0, Amf0Marker::ObjectEnd as u8,
+        ];
+
+        let mut decoder = Amf0Decoder::from_slice(&bytes).with_interner(&INTERNER);
+        let object = decoder.decode_object().unwrap();
+        assert_eq!(object.len(), 2);
+
+        let (key, value) = object.iter().find(|(key, _)| key.as_str() == "abc").unwrap();
+        assert!(matches!(key, StringCow::StaticRef("abc")));
+        assert!(matches!(value, Amf0Value::String(StringCow::StaticRef("val"))));
+
+        let (key, value) = object.iter().find(|(key, _)| key.as_str() == "defg").unwrap();
+        assert!(!matches!(key, StringCow::StaticRef(_)));
+        assert!(!matches!(value, Amf0Value::String(StringCow::StaticRef(_))));
+    }
 }
@@ -4,7 +4,59 @@ use std::io;
 
 use byteorder::{BigEndian, WriteBytesExt};
 
-use crate::{Amf0Array, Amf0Error, Amf0Marker, Amf0Object};
+use crate::{Amf0Array, Amf0Date, Amf0Error, Amf0Marker, Amf0Object};
+
+/// The order in which the keys of an [`Amf0Object`] are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Amf0KeyOrder {
+    /// Keys are written in the order they are given to the encoder.
+    ///
+    /// For an [`Amf0Object`] this is the order the keys were inserted or decoded in.
+    #[default]
+    Insertion,
+    /// Keys are sorted by their UTF-8 bytes before they are written.
+    Lexicographic,
+}
+
+/// How numbers are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Amf0NumberFormat {
+    /// The bits of the [`f64`] are written as they are.
+    #[default]
+    Preserve,
+    /// Values that compare equal are written with the same bits.
+    ///
+    /// All NaN values are written as the canonical quiet NaN (`0x7FF8000000000000`)
+    /// and negative zero is written as positive zero.
+    Canonical,
+}
+
+/// Options for the [`Amf0Encoder`].
+///
+/// The default options write values exactly as they are given.
+/// Use [`Amf0EncoderOptions::canonical`] to get byte-stable output,
+/// for example when hashing encoded metadata.
+///
+/// Only objects encoded with [`Amf0Encoder::encode_object`] are sorted.
+/// When using serde, keys are written in the order they are serialized,
+/// so use an ordered map (e.g. [`BTreeMap`](std::collections::BTreeMap)) to get a stable order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Amf0EncoderOptions {
+    /// The order in which object keys are written.
+    pub key_order: Amf0KeyOrder,
+    /// How numbers are written.
+    pub number_format: Amf0NumberFormat,
+}
+
+impl Amf0EncoderOptions {
+    /// Options that produce byte-stable output for equal values.
+    pub const fn canonical() -> Self {
+        Self {
+            key_order: Amf0KeyOrder::Lexicographic,
+            number_format: Amf0NumberFormat::Canonical,
+        }
+    }
+}
 
 /// AMF0 encoder.
 ///
@@ -12,12 +64,23 @@ use crate::{Amf0Array, Amf0Error, Amf0Marker, Amf0Object};
 #[derive(Debug)]
 pub struct Amf0Encoder<W> {
     writer: W,
+    options: Amf0EncoderOptions,
 }
 
 impl<W> Amf0Encoder<W> {
     /// Create a new encoder from a writer.
     pub fn new(writer: W) -> Self {
-        Amf0Encoder { writer }
+        Self::with_options(writer, Amf0EncoderOptions::default())
+    }
+
+    /// Create a new encoder from a writer with the given options.
+    pub fn with_options(writer: W, options: Amf0EncoderOptions) -> Self {
+        Amf0Encoder { writer, options }
+    }
+
+    /// Returns the options of this encoder.
+    pub fn options(&self) -> &Amf0EncoderOptions {
+        &self.options
     }
 }
 
@@ -33,7 +96,16 @@ where
     }
 
     /// Encode a [`f64`] as a AMF0 number value.
+    ///
+    /// See [`Amf0NumberFormat`] for how the value is written.
     pub fn encode_number(&mut self, value: f64) -> Result<(), Amf0Error> {
+        let value = match self.options.number_format {
+            Amf0NumberFormat::Preserve => value,
+            Amf0NumberFormat::Canonical if value.is_nan() => f64::NAN,
+            // -0.0 + 0.0 is 0.0
+            Amf0NumberFormat::Canonical => value + 0.0,
+        };
+
         self.writer.write_u8(Amf0Marker::Number as u8)?;
         self.writer.write_f64::<BigEndian>(value)?;
         Ok(())
@@ -64,6 +136,25 @@ where
         Ok(())
     }
 
+    /// Encode an [`Amf0Date`] as a AMF0 date value.
+    pub fn encode_date(&mut self, value: Amf0Date) -> Result<(), Amf0Error> {
+        self.writer.write_u8(Amf0Marker::Date as u8)?;
+        self.writer.write_f64::<BigEndian>(value.millis)?;
+        self.writer.write_i16::<BigEndian>(value.timezone)?;
+        Ok(())
+    }
+
+    /// Encode a [`&str`](str) as a AMF0 XML document value.
+    pub fn encode_xml_document(&mut self, value: &str) -> Result<(), Amf0Error> {
+        // This try_into fails if the length is greater than u32::MAX
+        let len: u32 = value.len().try_into()?;
+
+        self.writer.write_u8(Amf0Marker::XmlDocument as u8)?;
+        self.writer.write_u32::<BigEndian>(len)?;
+        self.writer.write_all(value.as_bytes())?;
+        Ok(())
+    }
+
     /// Encode AMF0 Null value.
     pub fn encode_null(&mut self) -> Result<(), Amf0Error> {
         self.writer.write_u8(Amf0Marker::Null as u8)?;
@@ -110,12 +201,28 @@ where
     }
 
     /// Encode an [`Amf0Object`] as an AMF0 Object value.
+    ///
+    /// See [`Amf0KeyOrder`] for the order in which the keys are written.
     pub fn encode_object(&mut self, values: &Amf0Object) -> Result<(), Amf0Error> {
         self.encode_object_header()?;
 
-        for (key, value) in values.iter() {
-            self.encode_object_key(key.as_str())?;
-            value.encode(self)?;
+        match self.options.key_order {
+            Amf0KeyOrder::Insertion => {
+                for (key, value) in values.iter() {
+                    self.encode_object_key(key.as_str())?;
+                    value.encode(self)?;
+                }
+            }
+            Amf0KeyOrder::Lexicographic => {
+                let mut entries: Vec<_> = values.iter().collect();
+                // Keys are unique, so an unstable sort is deterministic.
+                entries.sort_unstable_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+                for (key, value) in entries {
+                    self.encode_object_key(key.as_str())?;
+                    value.encode(self)?;
+                }
+            }
         }
 
         self.encode_object_trailer()?;
@@ -133,3 +240,120 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::{Amf0Encoder, Amf0EncoderOptions, Amf0KeyOrder, Amf0NumberFormat};
+    use crate::{Amf0Marker, Amf0Object, Amf0Value};
+
+    fn encode_number(value: f64, number_format: Amf0NumberFormat) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let options = Amf0EncoderOptions {
+            number_format,
+            ..Default::default()
+        };
+        Amf0Encoder::with_options(&mut buf, options).encode_number(value).unwrap();
+        buf
+    }
+
+    #[test]
+    fn number_format() {
+        let negative_nan = f64::from_bits(0xFFF8_0000_0000_0001);
+
+        let preserved = encode_number(negative_nan, Amf0NumberFormat::Preserve);
+        assert_eq!(preserved[1..], 0xFFF8_0000_0000_0001u64.to_be_bytes());
+
+        let canonical = encode_number(negative_nan, Amf0NumberFormat::Canonical);
+        assert_eq!(canonical[0], Amf0Marker::Number as u8);
+        assert_eq!(canonical[1..], 0x7FF8_0000_0000_0000u64.to_be_bytes());
+
+        assert_eq!(encode_number(-0.0, Amf0NumberFormat::Preserve)[1..], (-0.0f64).to_be_bytes());
+        assert_eq!(encode_number(-0.0, Amf0NumberFormat::Canonical)[1..], 0.0f64.to_be_bytes());
+        assert_eq!(encode_number(-1.5, Amf0NumberFormat::Canonical)[1..], (-1.5f64).to_be_bytes());
+        assert_eq!(
+            encode_number(f64::NEG_INFINITY, Amf0NumberFormat::Canonical)[1..],
+            f64::NEG_INFINITY.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn lexicographic_key_order() {
+        let object: Amf0Object = ["width", "height", "framerate", "audiocodecid", "Z", "a"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| (key.into(), Amf0Value::Number(i as f64)))
+            .collect();
+
+        let mut buf = Vec::new();
+        let mut encoder = Amf0Encoder::with_options(&mut buf, Amf0EncoderOptions::canonical());
+        assert_eq!(encoder.options().key_order, Amf0KeyOrder::Lexicographic);
+        encoder.encode_object(&object).unwrap();
+
+        let mut expected = vec![Amf0Marker::Object as u8];
+        for (key, value) in [
+            ("Z", 4.0f64),
+            ("a", 5.0),
+            ("audiocodecid", 3.0),
+            ("framerate", 2.0),
+            ("height", 1.0),
+            ("width", 0.0),
+        ] {
+            expected.extend_from_slice(&(key.len() as u16).to_be_bytes());
+            expected.extend_from_slice(key.as_bytes());
+            expected.push(Amf0Marker::Number as u8);
+            expected.extend_from_slice(&value.to_be_bytes());
+        }
+        expected.extend_from_slice(&[0, 0, Amf0Marker::ObjectEnd as u8]);
+
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn insertion_key_order() {
+        let object: Amf0Object = ["width", "height", "framerate"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| (key.into(), Amf0Value::Number(i as f64)))
+            .collect();
+
+        let mut buf = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut buf);
+        assert_eq!(encoder.options().key_order, Amf0KeyOrder::Insertion);
+        encoder.encode_object(&object).unwrap();
+
+        let mut expected = vec![Amf0Marker::Object as u8];
+        for (key, value) in [("width", 0.0f64), ("height", 1.0), ("framerate", 2.0)] {
+            expected.extend_from_slice(&(key.len() as u16).to_be_bytes());
+            expected.extend_from_slice(key.as_bytes());
+            expected.push(Amf0Marker::Number as u8);
+            expected.extend_from_slice(&value.to_be_bytes());
+        }
+        expected.extend_from_slice(&[0, 0, Amf0Marker::ObjectEnd as u8]);
+
+        assert_eq!(buf, expected);
+
+        // Decoding keeps the order as well.
+        let Amf0Value::Object(decoded) = crate::Amf0Decoder::from_slice(&buf).decode_value().unwrap() else {
+            panic!("expected an object");
+        };
+        assert!(decoded.keys().eq(object.keys()));
+    }
+
+    #[test]
+    fn canonical_output_is_stable() {
+        let encode = |object: &Amf0Object| {
+            let mut buf = Vec::new();
+            Amf0Encoder::with_options(&mut buf, Amf0EncoderOptions::canonical())
+                .encode_object(object)
+                .unwrap();
+            buf
+        };
+
+        let keys = (0..64).map(|i| format!("key{i}"));
+        let a: Amf0Object = keys.clone().map(|key| (key.into(), Amf0Value::Number(-0.0))).collect();
+        let b: Amf0Object = keys.rev().map(|key| (key.into(), Amf0Value::Number(0.0))).collect();
+
+        assert_eq!(encode(&a), encode(&b));
+    }
+}
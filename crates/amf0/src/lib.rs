@@ -58,11 +58,11 @@ pub mod value;
 #[cfg(feature = "serde")]
 pub use de::{from_buf, from_reader, from_slice};
 pub use decoder::Amf0Decoder;
-pub use encoder::Amf0Encoder;
+pub use encoder::{Amf0Encoder, Amf0EncoderOptions, Amf0KeyOrder, Amf0NumberFormat};
 pub use error::{Amf0Error, Result};
 #[cfg(feature = "serde")]
-pub use ser::{to_bytes, to_writer};
-pub use value::{Amf0Array, Amf0Object, Amf0Value};
+pub use ser::{to_bytes, to_writer, to_writer_with_options};
+pub use value::{Amf0Array, Amf0Date, Amf0Object, Amf0Value};
 
 /// AMF0 marker types.
 ///
@@ -9,14 +9,22 @@ use serde::ser::{
 };
 
 use crate::Amf0Error;
-use crate::encoder::Amf0Encoder;
+use crate::encoder::{Amf0Encoder, Amf0EncoderOptions};
 
 /// Serialize a value into a given writer.
 pub fn to_writer<W>(writer: W, value: &impl serde::Serialize) -> crate::Result<()>
 where
     W: io::Write,
 {
-    let mut serializer = Amf0Encoder::new(writer);
+    to_writer_with_options(writer, value, Amf0EncoderOptions::default())
+}
+
+/// Serialize a value into a given writer with the given encoder options.
+pub fn to_writer_with_options<W>(writer: W, value: &impl serde::Serialize, options: Amf0EncoderOptions) -> crate::Result<()>
+where
+    W: io::Write,
+{
+    let mut serializer = Amf0Encoder::with_options(writer, options);
     value.serialize(&mut serializer)
 }
 
@@ -551,7 +559,7 @@ mod tests {
 
     use serde_derive::Serialize;
 
-    use crate::{Amf0Error, Amf0Marker, Amf0Value, to_bytes};
+    use crate::{Amf0EncoderOptions, Amf0Error, Amf0Marker, Amf0Value, to_bytes, to_writer_with_options};
 
     #[test]
     fn string() {
@@ -588,6 +596,16 @@ mod tests {
         assert_eq!(bytes, expected);
     }
 
+    #[test]
+    fn number_with_options() {
+        let mut bytes = Vec::new();
+        to_writer_with_options(&mut bytes, &-0.0f64, Amf0EncoderOptions::canonical()).unwrap();
+
+        let mut expected = vec![Amf0Marker::Number as u8];
+        expected.extend(0.0f64.to_be_bytes());
+        assert_eq!(bytes, expected);
+    }
+
     #[test]
     fn bool() {
         let bytes = to_bytes(&true).unwrap();
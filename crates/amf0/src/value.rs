@@ -1,16 +1,18 @@
 //! AMF0 value types.
 
 use std::borrow::Cow;
-use std::collections::HashMap;
 use std::io;
 
+use indexmap::IndexMap;
 use scuffle_bytes_util::StringCow;
 
 use crate::Amf0Error;
 use crate::encoder::Amf0Encoder;
 
 /// Represents any AMF0 object.
-pub type Amf0Object<'a> = HashMap<StringCow<'a>, Amf0Value<'a>>;
+///
+/// Keys are kept in the order they were inserted, which is the order they were decoded in.
+pub type Amf0Object<'a> = IndexMap<StringCow<'a>, Amf0Value<'a>>;
 /// Represents any AMF0 array.
 pub type Amf0Array<'a> = Cow<'a, [Amf0Value<'a>]>;
 
@@ -29,6 +31,54 @@ pub enum Amf0Value<'a> {
     Null,
     /// AMF0 Array.
     Array(Amf0Array<'a>),
+    /// AMF0 Date.
+    Date(Amf0Date),
+    /// AMF0 XML Document.
+    XmlDocument(StringCow<'a>),
+}
+
+/// An AMF0 date.
+///
+/// Defined by:
+/// - AMF 0 spec, 2.13.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Amf0Date {
+    /// Milliseconds since the unix epoch, in UTC.
+    pub millis: f64,
+    /// The time zone as an offset from UTC in minutes.
+    ///
+    /// The AMF0 spec reserves this field and says it should be `0`,
+    /// but some encoders write the local time zone of the machine that created the value.
+    pub timezone: i16,
+}
+
+impl Amf0Date {
+    /// Create a new date in UTC from milliseconds since the unix epoch.
+    pub fn new(millis: f64) -> Self {
+        Self { millis, timezone: 0 }
+    }
+
+    /// Converts this date into a [`chrono::DateTime`] in its time zone.
+    ///
+    /// Returns `None` if the date or the time zone is out of range.
+    #[cfg(feature = "chrono")]
+    pub fn to_date_time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let date_time = chrono::DateTime::from_timestamp_millis(self.millis as i64)?;
+        let offset = chrono::FixedOffset::east_opt(self.timezone as i32 * 60)?;
+        Some(date_time.with_timezone(&offset))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for Amf0Date {
+    fn from(value: chrono::DateTime<Tz>) -> Self {
+        use chrono::Offset;
+
+        Self {
+            millis: value.timestamp_millis() as f64,
+            timezone: (value.offset().fix().local_minus_utc() / 60) as i16,
+        }
+    }
 }
 
 impl Amf0Value<'_> {
@@ -43,6 +93,8 @@ impl Amf0Value<'_> {
             }
             Amf0Value::Null => Amf0Value::Null,
             Amf0Value::Array(v) => Amf0Value::Array(v.into_owned().into_iter().map(|v| v.into_owned()).collect()),
+            Amf0Value::Date(v) => Amf0Value::Date(v),
+            Amf0Value::XmlDocument(v) => Amf0Value::XmlDocument(v.into_owned()),
         }
     }
 
@@ -55,6 +107,8 @@ impl Amf0Value<'_> {
             Amf0Value::Object(v) => encoder.encode_object(v),
             Amf0Value::Null => encoder.encode_null(),
             Amf0Value::Array(v) => encoder.encode_array(v),
+            Amf0Value::Date(v) => encoder.encode_date(*v),
+            Amf0Value::XmlDocument(v) => encoder.encode_xml_document(v.as_str()),
         }
     }
 }
@@ -71,6 +125,12 @@ impl From<bool> for Amf0Value<'_> {
     }
 }
 
+impl From<Amf0Date> for Amf0Value<'_> {
+    fn from(value: Amf0Date) -> Self {
+        Amf0Value::Date(value)
+    }
+}
+
 impl<'a> From<StringCow<'a>> for Amf0Value<'a> {
     fn from(value: StringCow<'a>) -> Self {
         Amf0Value::String(value)
@@ -223,7 +283,7 @@ impl<'de> serde::de::Deserialize<'de> for Amf0Value<'de> {
             where
                 A: serde::de::MapAccess<'de>,
             {
-                let mut object = HashMap::new();
+                let mut object = Amf0Object::new();
 
                 while let Some((key, value)) = map.next_entry()? {
                     object.insert(key, value);
@@ -274,6 +334,8 @@ impl serde::ser::Serialize for Amf0Value<'_> {
 
                 serde::ser::SerializeSeq::end(seq)
             }
+            Amf0Value::Date(v) => serializer.serialize_f64(v.millis),
+            Amf0Value::XmlDocument(v) => v.serialize(serializer),
         }
     }
 }
@@ -285,7 +347,7 @@ mod tests {
 
     use scuffle_bytes_util::StringCow;
 
-    use super::Amf0Value;
+    use super::{Amf0Date, Amf0Value};
     use crate::{Amf0Array, Amf0Decoder, Amf0Encoder, Amf0Error, Amf0Marker, Amf0Object};
 
     #[test]
@@ -351,6 +413,68 @@ mod tests {
         assert_eq!(value, Amf0Value::Boolean(false));
     }
 
+    #[test]
+    fn date() {
+        #[rustfmt::skip]
+        let bytes = [
+            Amf0Marker::Date as u8,
+            0x42, 0x75, 0x07, 0x0b, 0x63, 0x20, 0x00, 0x00, // millis
+            0xff, 0x10, // timezone
+        ];
+
+        let value = Amf0Decoder::from_slice(&bytes).decode_value().unwrap();
+        let date = Amf0Date {
+            millis: 1_445_000_000_000.0,
+            timezone: -240,
+        };
+        assert_eq!(value, Amf0Value::Date(date));
+
+        let mut encoded = Vec::new();
+        value.encode(&mut Amf0Encoder::new(&mut encoded)).unwrap();
+        assert_eq!(encoded, bytes);
+
+        // Dates can still be decoded as plain numbers.
+        assert_eq!(Amf0Decoder::from_slice(&bytes).decode_number().unwrap(), 1_445_000_000_000.0);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_chrono() {
+        let date = Amf0Date {
+            millis: 1_445_000_000_000.0,
+            timezone: -240,
+        };
+        let date_time = date.to_date_time().unwrap();
+        assert_eq!(date_time.to_rfc3339(), "2015-10-16T08:53:20-04:00");
+        assert_eq!(Amf0Date::from(date_time), date);
+
+        let out_of_range = Amf0Date {
+            millis: f64::MAX,
+            timezone: 0,
+        };
+        assert_eq!(out_of_range.to_date_time(), None);
+    }
+
+    #[test]
+    fn xml_document() {
+        #[rustfmt::skip]
+        let bytes = [
+            Amf0Marker::XmlDocument as u8,
+            0, 0, 0, 6, // length
+            b'<', b'a', b'/', b'>', b' ', b' ',
+        ];
+
+        let value = Amf0Decoder::from_slice(&bytes).decode_value().unwrap();
+        assert_eq!(value, Amf0Value::XmlDocument("<a/>  ".into()));
+
+        let mut encoded = Vec::new();
+        value.encode(&mut Amf0Encoder::new(&mut encoded)).unwrap();
+        assert_eq!(encoded, bytes);
+
+        // XML documents can still be decoded as plain strings.
+        assert_eq!(Amf0Decoder::from_slice(&bytes).decode_string().unwrap(), "<a/>  ");
+    }
+
     #[test]
     fn object() {
         #[rustfmt::skip]
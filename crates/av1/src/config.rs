@@ -1,9 +1,12 @@
 use std::io;
 
 use byteorder::ReadBytesExt;
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 use scuffle_bytes_util::{BitReader, BitWriter, BytesCursorExt};
 
+use crate::seq::SequenceHeaderObu;
+use crate::{ObuHeader, ObuType};
+
 /// AV1 Video Descriptor
 ///
 /// <https://aomediacodec.github.io/av1-mpeg2-ts/#av1-video-descriptor>
@@ -118,6 +121,29 @@ pub struct AV1CodecConfigurationRecord {
 }
 
 impl AV1CodecConfigurationRecord {
+    /// Creates a configuration record describing the given sequence header.
+    ///
+    /// `config_obu` should contain the sequence header OBU itself (with `obu_has_size_field` set),
+    /// optionally followed by metadata OBUs.
+    pub fn from_sequence_header(seq_obu: &SequenceHeaderObu, config_obu: Bytes) -> Self {
+        let color = &seq_obu.color_config;
+
+        Self {
+            seq_profile: seq_obu.seq_profile,
+            seq_level_idx_0: seq_obu.seq_level_idx_0(),
+            seq_tier_0: seq_obu.seq_tier_0(),
+            high_bitdepth: color.bit_depth > 8,
+            twelve_bit: color.bit_depth == 12,
+            monochrome: color.mono_chrome,
+            chroma_subsampling_x: color.subsampling_x,
+            chroma_subsampling_y: color.subsampling_y,
+            chroma_sample_position: color.chroma_sample_position,
+            hdr_wcg_idc: 0,
+            initial_presentation_delay_minus_one: None,
+            config_obu,
+        }
+    }
+
     /// Demuxes the AV1 Codec Configuration Record from the given reader.
     pub fn demux(reader: &mut io::Cursor<Bytes>) -> io::Result<Self> {
         let mut bit_reader = BitReader::new(reader);
@@ -180,6 +206,29 @@ impl AV1CodecConfigurationRecord {
         })
     }
 
+    /// Returns the short form of the codec string as used in the `codecs` parameter of HLS and DASH playlists,
+    /// for example `av01.0.08M.08`.
+    ///
+    /// The record does not contain the color description, use
+    /// [`SequenceHeaderObu::codec_string`](crate::seq::SequenceHeaderObu::codec_string) for the full form.
+    ///
+    /// <https://aomediacodec.github.io/av1-isobmff/#codecsparam>
+    pub fn codec_string(&self) -> String {
+        let bit_depth = match (self.high_bitdepth, self.twelve_bit) {
+            (true, true) => 12,
+            (true, false) => 10,
+            _ => 8,
+        };
+
+        format!(
+            "av01.{}.{:02}{}.{:02}",
+            self.seq_profile,
+            self.seq_level_idx_0,
+            if self.seq_tier_0 { 'H' } else { 'M' },
+            bit_depth,
+        )
+    }
+
     /// Returns the size of the AV1 Codec Configuration Record.
     pub fn size(&self) -> u64 {
         1 // marker, version
@@ -207,7 +256,8 @@ impl AV1CodecConfigurationRecord {
         bit_writer.write_bit(self.chroma_subsampling_y)?;
         bit_writer.write_bits(self.chroma_sample_position as u64, 2)?;
 
-        bit_writer.write_bits(0, 3)?; // reserved 3 bits
+        bit_writer.write_bits(self.hdr_wcg_idc as u64, 2)?;
+        bit_writer.write_bit(false)?; // reserved 1 bit
 
         if let Some(initial_presentation_delay_minus_one) = self.initial_presentation_delay_minus_one {
             bit_writer.write_bit(true)?;
@@ -221,6 +271,115 @@ impl AV1CodecConfigurationRecord {
 
         Ok(())
     }
+
+    /// Parses the sequence header OBU contained in `config_obu`, if there is one.
+    pub fn sequence_header(&self) -> io::Result<Option<SequenceHeaderObu>> {
+        let mut cursor = io::Cursor::new(self.config_obu.clone());
+
+        while cursor.has_remaining() {
+            let header = ObuHeader::parse(&mut cursor)?;
+            let size = header
+                .size
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "config OBUs must have obu_has_size_field set"))?;
+            let data = cursor.extract_bytes(size as usize)?;
+
+            match header.obu_type {
+                ObuType::SequenceHeader => {
+                    return SequenceHeaderObu::parse(header, &mut io::Cursor::new(data)).map(Some);
+                }
+                ObuType::Metadata => {}
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "config OBUs must only contain sequence header and metadata OBUs",
+                    ));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Validates the record.
+    ///
+    /// Checks that every field fits in its bit width and, if `config_obu` contains a sequence header, that the
+    /// record is consistent with it.
+    ///
+    /// <https://aomediacodec.github.io/av1-isobmff/#av1codecconfigurationbox-semantics>
+    pub fn validate(&self) -> io::Result<()> {
+        let invalid = |message: &'static str| Err(io::Error::new(io::ErrorKind::InvalidData, message));
+
+        if self.seq_profile > 0b111 {
+            return invalid("seq_profile must fit in 3 bits");
+        }
+
+        if self.seq_level_idx_0 > 0b11111 {
+            return invalid("seq_level_idx_0 must fit in 5 bits");
+        }
+
+        if self.chroma_sample_position > 0b11 {
+            return invalid("chroma_sample_position must fit in 2 bits");
+        }
+
+        if self.hdr_wcg_idc > 0b11 {
+            return invalid("hdr_wcg_idc must fit in 2 bits");
+        }
+
+        if self.initial_presentation_delay_minus_one.is_some_and(|delay| delay > 0b1111) {
+            return invalid("initial_presentation_delay_minus_one must fit in 4 bits");
+        }
+
+        if self.twelve_bit && !self.high_bitdepth {
+            return invalid("twelve_bit requires high_bitdepth");
+        }
+
+        // AV1-Spec-2 - 5.5.2, mono_chrome is not signalled in the high profile and inferred to be 0.
+        if self.monochrome && self.seq_profile == 1 {
+            return invalid("monochrome is not allowed in the high profile");
+        }
+
+        if self.monochrome && !(self.chroma_subsampling_x && self.chroma_subsampling_y) {
+            return invalid("monochrome requires chroma_subsampling_x and chroma_subsampling_y");
+        }
+
+        let Some(seq_obu) = self.sequence_header()? else {
+            return Ok(());
+        };
+
+        let expected = Self::from_sequence_header(&seq_obu, Bytes::new());
+
+        if self.seq_profile != expected.seq_profile {
+            return invalid("seq_profile does not match the sequence header");
+        }
+
+        if self.seq_level_idx_0 != expected.seq_level_idx_0 {
+            return invalid("seq_level_idx_0 does not match the sequence header");
+        }
+
+        if self.seq_tier_0 != expected.seq_tier_0 {
+            return invalid("seq_tier_0 does not match the sequence header");
+        }
+
+        if self.high_bitdepth != expected.high_bitdepth || self.twelve_bit != expected.twelve_bit {
+            return invalid("bit depth does not match the sequence header");
+        }
+
+        if self.monochrome != expected.monochrome {
+            return invalid("monochrome does not match the sequence header");
+        }
+
+        if self.chroma_subsampling_x != expected.chroma_subsampling_x
+            || self.chroma_subsampling_y != expected.chroma_subsampling_y
+        {
+            return invalid("chroma subsampling does not match the sequence header");
+        }
+
+        if self.chroma_sample_position != expected.chroma_sample_position {
+            return invalid("chroma_sample_position does not match the sequence header");
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -251,6 +410,7 @@ mod tests {
             config_obu: b"\n\x0f\0\0\0j\xef\xbf\xe1\xbc\x02\x19\x90\x10\x10\x10@",
         }
         "#);
+        assert_eq!(config.codec_string(), "av01.0.13M.08");
     }
 
     #[test]
@@ -345,6 +505,172 @@ mod tests {
         insta::assert_snapshot!(format!("{:?}", Bytes::from(buf)), @r#"b"\x81\0\0\x10HELLO FROM THE OBU""#);
     }
 
+    #[test]
+    fn test_config_mux_hdr_wcg_idc() {
+        let data = b"\x81\r\x0c\x9f\n\x0f\0\0\0j\xef\xbf\xe1\xbc\x02\x19\x90\x10\x10\x10@";
+
+        let config = AV1CodecConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap();
+        assert_eq!(config.hdr_wcg_idc, 2);
+
+        let mut buf = Vec::new();
+        config.mux(&mut buf).unwrap();
+        assert_eq!(buf, data);
+    }
+
+    #[test]
+    fn test_config_from_sequence_header() {
+        let data = b"\x81\r\x0c\0\n\x0f\0\0\0j\xef\xbf\xe1\xbc\x02\x19\x90\x10\x10\x10@";
+        let config = AV1CodecConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap();
+        config.validate().unwrap();
+
+        let seq_obu = config.sequence_header().unwrap().expect("missing sequence header");
+        let record = AV1CodecConfigurationRecord::from_sequence_header(&seq_obu, config.config_obu.clone());
+        assert_eq!(record, config);
+
+        let mut buf = Vec::new();
+        record.mux(&mut buf).unwrap();
+        assert_eq!(buf, data);
+        assert_eq!(record.size(), data.len() as u64);
+    }
+
+    #[test]
+    fn test_config_still_picture_monochrome() {
+        let mut bits = BitWriter::new(Vec::new());
+        bits.write_bits(0, 3).unwrap(); // seq_profile
+        bits.write_bit(true).unwrap(); // still_picture
+        bits.write_bit(true).unwrap(); // reduced_still_picture_header
+        bits.write_bits(31, 5).unwrap(); // seq_level_idx[0]
+        bits.write_bits(7, 4).unwrap(); // frame_width_bits_minus_1
+        bits.write_bits(7, 4).unwrap(); // frame_height_bits_minus_1
+        bits.write_bits(159, 8).unwrap(); // max_frame_width_minus_1
+        bits.write_bits(89, 8).unwrap(); // max_frame_height_minus_1
+        bits.write_bits(0, 6).unwrap(); // use_128x128_superblock .. enable_restoration
+        bits.write_bit(false).unwrap(); // high_bitdepth
+        bits.write_bit(true).unwrap(); // mono_chrome
+        bits.write_bit(false).unwrap(); // color_description_present_flag
+        bits.write_bit(true).unwrap(); // color_range
+        bits.write_bit(false).unwrap(); // film_grain_params_present
+        bits.write_bit(true).unwrap(); // trailing_one_bit
+        let payload = bits.finish().unwrap();
+
+        let mut config_obu = vec![0b0000_1010, payload.len() as u8]; // OBU_SEQUENCE_HEADER with obu_has_size_field
+        config_obu.extend(payload);
+        let config_obu = Bytes::from(config_obu);
+
+        let header = ObuHeader::parse(&mut io::Cursor::new(config_obu.clone())).unwrap();
+        let seq_obu = SequenceHeaderObu::parse(header, &mut io::Cursor::new(config_obu.slice(2..))).unwrap();
+        assert!(seq_obu.is_still_picture());
+        assert!(seq_obu.reduced_still_picture_header);
+        assert!(seq_obu.is_monochrome());
+        assert_eq!(seq_obu.color_config.num_planes, 1);
+        assert_eq!(seq_obu.max_frame_width, 160);
+        assert_eq!(seq_obu.max_frame_height, 90);
+        assert_eq!(seq_obu.seq_level_idx_0(), 31);
+        assert_eq!(seq_obu.codec_string(), "av01.0.31M.08.1.110.02.02.02.1");
+
+        let record = AV1CodecConfigurationRecord::from_sequence_header(&seq_obu, config_obu);
+        assert!(record.monochrome);
+        assert!(record.chroma_subsampling_x && record.chroma_subsampling_y);
+        record.validate().unwrap();
+
+        let mut buf = Vec::new();
+        record.mux(&mut buf).unwrap();
+        let config = AV1CodecConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from(buf))).unwrap();
+        assert_eq!(config, record);
+        config.validate().unwrap();
+        assert_eq!(config.sequence_header().unwrap(), Some(seq_obu));
+        assert_eq!(config.codec_string(), "av01.0.31M.08");
+
+        let high_profile = AV1CodecConfigurationRecord {
+            seq_profile: 1,
+            config_obu: Bytes::new(),
+            ..config.clone()
+        };
+        assert_eq!(
+            high_profile.validate().unwrap_err().to_string(),
+            "monochrome is not allowed in the high profile"
+        );
+
+        let chroma = AV1CodecConfigurationRecord {
+            chroma_subsampling_y: false,
+            config_obu: Bytes::new(),
+            ..config
+        };
+        assert_eq!(
+            chroma.validate().unwrap_err().to_string(),
+            "monochrome requires chroma_subsampling_x and chroma_subsampling_y"
+        );
+    }
+
+    #[test]
+    fn test_config_validate() {
+        let data = b"\x81\r\x0c\0\n\x0f\0\0\0j\xef\xbf\xe1\xbc\x02\x19\x90\x10\x10\x10@";
+        let config = AV1CodecConfigurationRecord::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap();
+
+        type Modify = fn(&mut AV1CodecConfigurationRecord);
+
+        let cases: [(Modify, &str); 12] = [
+            (|c| c.seq_profile = 8, "seq_profile must fit in 3 bits"),
+            (|c| c.seq_level_idx_0 = 32, "seq_level_idx_0 must fit in 5 bits"),
+            (|c| c.chroma_sample_position = 4, "chroma_sample_position must fit in 2 bits"),
+            (|c| c.hdr_wcg_idc = 4, "hdr_wcg_idc must fit in 2 bits"),
+            (
+                |c| c.initial_presentation_delay_minus_one = Some(16),
+                "initial_presentation_delay_minus_one must fit in 4 bits",
+            ),
+            (|c| c.twelve_bit = true, "twelve_bit requires high_bitdepth"),
+            (|c| c.seq_profile = 1, "seq_profile does not match the sequence header"),
+            (
+                |c| c.seq_level_idx_0 = 12,
+                "seq_level_idx_0 does not match the sequence header",
+            ),
+            (|c| c.seq_tier_0 = true, "seq_tier_0 does not match the sequence header"),
+            (|c| c.high_bitdepth = true, "bit depth does not match the sequence header"),
+            (|c| c.monochrome = true, "monochrome does not match the sequence header"),
+            (
+                |c| c.chroma_subsampling_y = false,
+                "chroma subsampling does not match the sequence header",
+            ),
+        ];
+
+        for (modify, message) in cases {
+            let mut config = config.clone();
+            modify(&mut config);
+
+            let err = config.validate().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), message);
+        }
+
+        let mut config = config.clone();
+        config.chroma_sample_position = 1;
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "chroma_sample_position does not match the sequence header"
+        );
+
+        // Without a sequence header only the field ranges are checked.
+        config.config_obu = Bytes::new();
+        config.validate().unwrap();
+        assert!(config.sequence_header().unwrap().is_none());
+
+        // Metadata OBUs are allowed, other OBUs are not.
+        config.config_obu = Bytes::from_static(b"\x2a\x01\x00");
+        config.validate().unwrap();
+
+        config.config_obu = Bytes::from_static(b"\x12\x00");
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "config OBUs must only contain sequence header and metadata OBUs"
+        );
+
+        config.config_obu = Bytes::from_static(b"\x08\x00");
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "config OBUs must have obu_has_size_field set"
+        );
+    }
+
     #[test]
     fn test_video_descriptor_demux() {
         let data = b"\x80\x04\x81\r\x0c\x3f\n\x0f\0\0\0j\xef\xbf\xe1\xbc\x02\x19\x90\x10\x10\x10@".to_vec();
@@ -437,12 +437,153 @@ impl ColorConfig {
     }
 }
 
+/// Tile info limits
+///
+/// The limits on the tile configuration of a frame with the maximum frame size of the sequence,
+/// derived the same way as in `tile_info()`.
+///
+/// AV1-Spec-2 - 5.9.15
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub struct TileInfo {
+    /// `sbCols`, the number of superblock columns
+    pub sb_cols: u64,
+    /// `sbRows`, the number of superblock rows
+    pub sb_rows: u64,
+    /// `sbSize`, the base 2 logarithm of the superblock size in pixels
+    pub sb_size_log2: u8,
+    /// `minLog2TileCols`
+    pub min_log2_tile_cols: u8,
+    /// `maxLog2TileCols`
+    pub max_log2_tile_cols: u8,
+    /// `maxLog2TileRows`
+    pub max_log2_tile_rows: u8,
+    /// `minLog2Tiles`
+    pub min_log2_tiles: u8,
+}
+
+impl TileInfo {
+    const MAX_TILE_AREA: u64 = 4096 * 2304;
+    const MAX_TILE_COLS: u64 = 64;
+    const MAX_TILE_ROWS: u64 = 64;
+    const MAX_TILE_WIDTH: u64 = 4096;
+
+    /// Computes the tile info limits for a frame of the given size.
+    pub fn new(frame_width: u64, frame_height: u64, use_128x128_superblock: bool) -> Self {
+        // AV1-Spec-2 - 7.20 (compute_image_size)
+        let mi_cols = 2 * ((frame_width + 7) >> 3);
+        let mi_rows = 2 * ((frame_height + 7) >> 3);
+
+        let (sb_cols, sb_rows, sb_shift) = if use_128x128_superblock {
+            ((mi_cols + 31) >> 5, (mi_rows + 31) >> 5, 5)
+        } else {
+            ((mi_cols + 15) >> 4, (mi_rows + 15) >> 4, 4)
+        };
+
+        let sb_size_log2 = sb_shift + 2;
+        let max_tile_width_sb = Self::MAX_TILE_WIDTH >> sb_size_log2;
+        let max_tile_area_sb = Self::MAX_TILE_AREA >> (2 * sb_size_log2);
+
+        let min_log2_tile_cols = tile_log2(max_tile_width_sb, sb_cols);
+        let max_log2_tile_cols = tile_log2(1, sb_cols.min(Self::MAX_TILE_COLS));
+        let max_log2_tile_rows = tile_log2(1, sb_rows.min(Self::MAX_TILE_ROWS));
+        let min_log2_tiles = min_log2_tile_cols.max(tile_log2(max_tile_area_sb, sb_rows * sb_cols));
+
+        Self {
+            sb_cols,
+            sb_rows,
+            sb_size_log2,
+            min_log2_tile_cols,
+            max_log2_tile_cols,
+            max_log2_tile_rows,
+            min_log2_tiles,
+        }
+    }
+}
+
+/// `tile_log2(blkSize, target)`, the smallest `k` such that `blkSize << k` is at least `target`.
+///
+/// AV1-Spec-2 - 5.9.16
+fn tile_log2(blk_size: u64, target: u64) -> u8 {
+    let mut k = 0;
+    while (blk_size << k) < target {
+        k += 1;
+    }
+    k
+}
+
 impl SequenceHeaderObu {
     /// Returns a reference to the header of the OBU.
     pub const fn header(&self) -> &ObuHeader {
         &self.header
     }
 
+    /// The `seq_level_idx` of the first operating point, as signalled in the `av1C` box and codec string.
+    pub fn seq_level_idx_0(&self) -> u8 {
+        self.operating_points.first().map_or(0, |op| op.seq_level_idx)
+    }
+
+    /// The `seq_tier` of the first operating point, as signalled in the `av1C` box and codec string.
+    pub fn seq_tier_0(&self) -> bool {
+        self.operating_points.first().is_some_and(|op| op.seq_tier)
+    }
+
+    /// The highest `seq_level_idx` of all operating points.
+    ///
+    /// A decoder has to support this level to decode every operating point of the sequence.
+    pub fn max_level(&self) -> u8 {
+        self.operating_points.iter().map(|op| op.seq_level_idx).max().unwrap_or(0)
+    }
+
+    /// Returns `true` if the sequence only has a luma plane.
+    pub fn is_monochrome(&self) -> bool {
+        self.color_config.mono_chrome
+    }
+
+    /// Returns `true` if the sequence contains a single coded frame, like a thumbnail or preview image.
+    ///
+    /// This is also the case for sequences with `reduced_still_picture_header`, which omit the timing info,
+    /// decoder model and all operating points but the first.
+    pub fn is_still_picture(&self) -> bool {
+        self.still_picture
+    }
+
+    /// Returns the codec string of the stream as used in the `codecs` parameter of HLS and DASH playlists,
+    /// for example `av01.0.04M.10.0.112.09.16.09.0`.
+    ///
+    /// This is the full form, including the color description.
+    ///
+    /// <https://aomediacodec.github.io/av1-isobmff/#codecsparam>
+    pub fn codec_string(&self) -> String {
+        let color = &self.color_config;
+        // The chroma sample position is only signalled for 4:2:0 subsampling.
+        let chroma_sample_position = if color.subsampling_x && color.subsampling_y {
+            color.chroma_sample_position
+        } else {
+            0
+        };
+
+        format!(
+            "av01.{}.{:02}{}.{:02}.{}.{}{}{}.{:02}.{:02}.{:02}.{}",
+            self.seq_profile,
+            self.seq_level_idx_0(),
+            if self.seq_tier_0() { 'H' } else { 'M' },
+            color.bit_depth,
+            color.mono_chrome as u8,
+            color.subsampling_x as u8,
+            color.subsampling_y as u8,
+            chroma_sample_position,
+            color.color_primaries,
+            color.transfer_characteristics,
+            color.matrix_coefficients,
+            color.full_color_range as u8,
+        )
+    }
+
+    /// The tile info limits for a frame with the maximum frame size of the sequence.
+    pub fn tile_info(&self) -> TileInfo {
+        TileInfo::new(self.max_frame_width, self.max_frame_height, self.use_128x128_superblock)
+    }
+
     /// Parses the sequence header from the given reader.
     ///
     /// The given header will be part of the returned struct and can be accessed through the [`SequenceHeaderObu::header`] function.
@@ -2053,4 +2194,67 @@ mod tests {
         }
         ");
     }
+
+    #[test]
+    fn test_seq_obu_helpers() {
+        let obu = b"\0\0\0j\xef\xbf\xe1\xbc\x02\x19\x90\x10\x10\x10@";
+
+        let header = ObuHeader {
+            obu_type: ObuType::SequenceHeader,
+            size: None,
+            extension_header: None,
+        };
+
+        let mut seq_header = SequenceHeaderObu::parse(header, &mut io::Cursor::new(obu)).unwrap();
+
+        assert_eq!(seq_header.seq_level_idx_0(), 13);
+        assert!(!seq_header.seq_tier_0());
+        assert_eq!(seq_header.max_level(), 13);
+        assert!(!seq_header.is_monochrome());
+        assert!(!seq_header.is_still_picture());
+        assert_eq!(seq_header.codec_string(), "av01.0.13M.08.0.110.01.01.01.0");
+        assert_eq!(
+            seq_header.tile_info(),
+            TileInfo {
+                sb_cols: 60,
+                sb_rows: 34,
+                sb_size_log2: 6,
+                min_log2_tile_cols: 0,
+                max_log2_tile_cols: 6,
+                max_log2_tile_rows: 6,
+                min_log2_tiles: 0,
+            }
+        );
+
+        seq_header.operating_points.push(OperatingPoint {
+            idc: 0x101,
+            seq_level_idx: 17,
+            seq_tier: true,
+            operating_parameters_info: None,
+            initial_display_delay: None,
+        });
+        assert_eq!(seq_header.seq_level_idx_0(), 13);
+        assert_eq!(seq_header.max_level(), 17);
+
+        seq_header.operating_points.clear();
+        assert_eq!(seq_header.seq_level_idx_0(), 0);
+        assert!(!seq_header.seq_tier_0());
+        assert_eq!(seq_header.max_level(), 0);
+    }
+
+    #[test]
+    fn test_tile_info_128x128_superblock() {
+        assert_eq!(
+            TileInfo::new(8192, 4352, true),
+            TileInfo {
+                sb_cols: 64,
+                sb_rows: 34,
+                sb_size_log2: 7,
+                min_log2_tile_cols: 1,
+                max_log2_tile_cols: 6,
+                max_log2_tile_rows: 6,
+                min_log2_tiles: 2,
+            }
+        );
+    }
 }
@@ -65,6 +65,22 @@ impl<Resp> BatchResponse<Resp> {
     }
 }
 
+/// The priority of items submitted to a [`Batcher`] or
+/// [`DataLoader`](crate::DataLoader)
+///
+/// Normal priority items wait for the batch to fill up or for the delay to
+/// elapse. High priority items flush the batch they were added to immediately,
+/// taking any normal priority items already waiting in it along.
+/// Flushed batches still respect the concurrency limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BatchPriority {
+    /// Wait for the batch to fill up or for the delay to elapse
+    #[default]
+    Normal,
+    /// Flush the batch immediately
+    High,
+}
+
 /// A trait for executing batches
 pub trait BatchExecutor {
     /// The incoming request type
@@ -155,6 +171,15 @@ impl<E> BatcherBuilder<E> {
     {
         Batcher::new(executor, self.batch_size, self.concurrency, self.delay)
     }
+
+    /// Build a [`BlockingBatcher`] backed by a dedicated runtime
+    #[inline]
+    pub fn build_blocking(self, executor: E) -> std::io::Result<BlockingBatcher<E>>
+    where
+        E: BatchExecutor + Send + Sync + 'static,
+    {
+        BlockingBatcher::new(executor, self.batch_size, self.concurrency, self.delay)
+    }
 }
 
 /// A batcher used to batch requests to a [`BatchExecutor`]
@@ -212,6 +237,19 @@ where
 
     /// Execute many requests
     pub async fn execute_many<I>(&self, items: I) -> Vec<Option<E::Response>>
+    where
+        I: IntoIterator<Item = E::Request>,
+    {
+        self.execute_many_with_priority(items, BatchPriority::Normal).await
+    }
+
+    /// Execute a single request with the given [`BatchPriority`]
+    pub async fn execute_with_priority(&self, item: E::Request, priority: BatchPriority) -> Option<E::Response> {
+        self.execute_many_with_priority(std::iter::once(item), priority).await.pop()?
+    }
+
+    /// Execute many requests with the given [`BatchPriority`]
+    pub async fn execute_many_with_priority<I>(&self, items: I, priority: BatchPriority) -> Vec<Option<E::Response>>
     where
         I: IntoIterator<Item = E::Request>,
     {
@@ -234,6 +272,10 @@ where
                     tokio::spawn(batch.take().unwrap().spawn(self.executor.clone()));
                 }
             }
+
+            if let Some(batch) = batch.take_if(|_| priority == BatchPriority::High && !responses.is_empty()) {
+                tokio::spawn(batch.spawn(self.executor.clone()));
+            }
         }
 
         let mut results = Vec::with_capacity(responses.len());
@@ -245,6 +287,86 @@ where
     }
 }
 
+/// A synchronous wrapper around a [`Batcher`] for non-async callers.
+///
+/// The batcher runs on a dedicated current-thread runtime which is driven by the
+/// callers of [`BlockingBatcher::execute_blocking`], so no background threads are spawned.
+/// It can be shared between threads, all of them will be batched together.
+///
+/// # Panics
+///
+/// The blocking methods panic when called from within an async context,
+/// use [`BlockingBatcher::batcher`] to access the async [`Batcher`] instead.
+/// For the same reason the batcher must not be dropped from within an async context.
+#[must_use = "batchers must be used to execute batches"]
+pub struct BlockingBatcher<E>
+where
+    E: BatchExecutor + Send + Sync + 'static,
+{
+    // Field order matters, the batcher must be dropped before the runtime.
+    batcher: Batcher<E>,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl<E> BlockingBatcher<E>
+where
+    E: BatchExecutor + Send + Sync + 'static,
+{
+    /// Create a new blocking batcher with its own runtime
+    pub fn new(executor: E, batch_size: usize, concurrency: usize, delay: std::time::Duration) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build()?;
+
+        let batcher = {
+            let _guard = runtime.enter();
+            Batcher::new(executor, batch_size, concurrency, delay)
+        };
+
+        Ok(Self { batcher, runtime })
+    }
+
+    /// Create a builder for a [`BlockingBatcher`]
+    pub const fn builder() -> BatcherBuilder<E> {
+        BatcherBuilder::new()
+    }
+
+    /// Execute a single request, blocking the current thread until it completes
+    pub fn execute_blocking(&self, item: E::Request) -> Option<E::Response> {
+        self.runtime.block_on(self.batcher.execute(item))
+    }
+
+    /// Execute many requests, blocking the current thread until they complete
+    pub fn execute_many_blocking<I>(&self, items: I) -> Vec<Option<E::Response>>
+    where
+        I: IntoIterator<Item = E::Request>,
+    {
+        self.runtime.block_on(self.batcher.execute_many(items))
+    }
+
+    /// Execute a single request with the given [`BatchPriority`], blocking the current thread until it completes
+    pub fn execute_with_priority_blocking(&self, item: E::Request, priority: BatchPriority) -> Option<E::Response> {
+        self.runtime.block_on(self.batcher.execute_with_priority(item, priority))
+    }
+
+    /// Execute many requests with the given [`BatchPriority`], blocking the current thread until they complete
+    pub fn execute_many_with_priority_blocking<I>(&self, items: I, priority: BatchPriority) -> Vec<Option<E::Response>>
+    where
+        I: IntoIterator<Item = E::Request>,
+    {
+        self.runtime
+            .block_on(self.batcher.execute_many_with_priority(items, priority))
+    }
+
+    /// The underlying async [`Batcher`]
+    pub const fn batcher(&self) -> &Batcher<E> {
+        &self.batcher
+    }
+
+    /// A handle to the runtime the batcher runs on
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        self.runtime.handle()
+    }
+}
+
 async fn batch_loop<E>(
     executor: Arc<E>,
     current_batch: Arc<tokio::sync::Mutex<Option<Batch<E>>>>,
@@ -537,6 +659,47 @@ mod tests {
         assert!(start.elapsed() < std::time::Duration::from_millis(20));
     }
 
+    #[cfg(not(valgrind))] // test is time-sensitive
+    #[tokio::test]
+    async fn priority() {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = TestExecutor {
+            values: HashMap::from_iter(vec![("a", 1), ("b", 2), ("c", 3)]),
+            delay: std::time::Duration::from_millis(5),
+            requests: requests.clone(),
+            capacity: 10,
+        };
+
+        let loader = BatcherBuilder::default()
+            .batch_size(10)
+            .concurrency(1)
+            .delay(std::time::Duration::from_millis(500))
+            .build(fetcher);
+
+        let start = std::time::Instant::now();
+        let (a, bc) = tokio::join!(loader.execute("a"), async {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            loader.execute_many_with_priority(vec!["b", "c"], BatchPriority::High).await
+        },);
+        assert_eq!(a, Some(1));
+        assert_eq!(bc, vec![Some(2), Some(3)]);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+
+        let start = std::time::Instant::now();
+        let a = loader.execute_with_priority("a", BatchPriority::High).await;
+        assert_eq!(a, Some(1));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+
+        let start = std::time::Instant::now();
+        let a = loader.execute_with_priority("a", BatchPriority::Normal).await;
+        assert_eq!(a, Some(1));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 3);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(500));
+    }
+
     #[cfg(not(valgrind))] // test is time-sensitive
     #[tokio::test]
     async fn no_deduplication() {
@@ -656,4 +819,69 @@ mod tests {
         assert!(start.elapsed() >= std::time::Duration::from_millis(5));
         assert!(start.elapsed() < std::time::Duration::from_millis(20));
     }
+
+    #[cfg(not(valgrind))] // test is time-sensitive
+    #[test]
+    fn blocking() {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = TestExecutor {
+            values: HashMap::from_iter(vec![("a", 1), ("b", 2), ("c", 3)]),
+            delay: std::time::Duration::from_millis(5),
+            requests: requests.clone(),
+            capacity: 2,
+        };
+
+        let loader = BlockingBatcher::builder()
+            .batch_size(2)
+            .concurrency(1)
+            .delay(std::time::Duration::from_millis(10))
+            .build_blocking(fetcher)
+            .unwrap();
+
+        let start = std::time::Instant::now();
+        let a = loader.execute_blocking("a");
+        assert_eq!(a, Some(1));
+        assert!(start.elapsed() >= std::time::Duration::from_millis(10));
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        let ab = loader.execute_many_blocking(vec!["a", "b", "c", "d"]);
+        assert_eq!(ab, vec![Some(1), Some(2), Some(3), None]);
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 3);
+    }
+
+    #[cfg(not(valgrind))] // test is time-sensitive
+    #[test]
+    fn blocking_threads() {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = TestExecutor {
+            values: HashMap::from_iter((0..10).map(|i| (i, i * 2))),
+            delay: std::time::Duration::from_millis(5),
+            requests: requests.clone(),
+            capacity: 10,
+        };
+
+        let loader = BlockingBatcher::builder()
+            .batch_size(10)
+            .concurrency(1)
+            .delay(std::time::Duration::from_millis(50))
+            .build_blocking(fetcher)
+            .unwrap();
+
+        let loader = &loader;
+        std::thread::scope(|s| {
+            let handles = (0..10)
+                .map(|i| s.spawn(move || loader.execute_blocking(i)))
+                .collect::<Vec<_>>();
+
+            for (i, handle) in handles.into_iter().enumerate() {
+                assert_eq!(handle.join().unwrap(), Some(i * 2));
+            }
+        });
+
+        // All threads are batched together.
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
 }
@@ -2,9 +2,11 @@
 //!
 //! Dataloaders should only be used for fetching data.
 //! If you need to batch writes, use a [`Batcher`](crate::batch::Batcher) instead.
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::future::Future;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+use crate::batch::BatchPriority;
 
 /// A trait for fetching data in batches
 pub trait DataLoaderFetcher {
@@ -24,6 +26,8 @@ pub struct DataLoaderBuilder<E> {
     batch_size: usize,
     concurrency: usize,
     delay: std::time::Duration,
+    cache_ttl: Option<std::time::Duration>,
+    cache_capacity: Option<usize>,
     _phantom: std::marker::PhantomData<E>,
 }
 
@@ -40,6 +44,8 @@ impl<E> DataLoaderBuilder<E> {
             batch_size: 1000,
             concurrency: 50,
             delay: std::time::Duration::from_millis(5),
+            cache_ttl: None,
+            cache_capacity: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -65,6 +71,24 @@ impl<E> DataLoaderBuilder<E> {
         self
     }
 
+    /// Cache loaded values for the given duration
+    ///
+    /// See [`DataLoader`] for details on caching.
+    #[inline]
+    pub const fn cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.with_cache_ttl(ttl);
+        self
+    }
+
+    /// Cache at most the given number of loaded values
+    ///
+    /// See [`DataLoader`] for details on caching.
+    #[inline]
+    pub const fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.with_cache_capacity(capacity);
+        self
+    }
+
     /// Set the batch size
     #[inline]
     pub const fn with_batch_size(&mut self, batch_size: usize) -> &mut Self {
@@ -86,17 +110,134 @@ impl<E> DataLoaderBuilder<E> {
         self
     }
 
+    /// Cache loaded values for the given duration
+    ///
+    /// See [`DataLoader`] for details on caching.
+    #[inline]
+    pub const fn with_cache_ttl(&mut self, ttl: std::time::Duration) -> &mut Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Cache at most the given number of loaded values
+    ///
+    /// See [`DataLoader`] for details on caching.
+    #[inline]
+    pub const fn with_cache_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
     /// Build the dataloader
     #[inline]
     pub fn build(self, executor: E) -> DataLoader<E>
     where
         E: DataLoaderFetcher + Send + Sync + 'static,
     {
-        DataLoader::new(executor, self.batch_size, self.concurrency, self.delay)
+        let mut loader = DataLoader::new(executor, self.batch_size, self.concurrency, self.delay);
+
+        if self.cache_ttl.is_some() || self.cache_capacity.is_some() {
+            loader.cache = Some(Mutex::new(Cache::new(self.cache_ttl, self.cache_capacity)));
+        }
+
+        loader
+    }
+}
+
+struct CacheEntry<V> {
+    value: Option<V>,
+    inserted_at: std::time::Instant,
+}
+
+/// A cache of loaded values, evicted in insertion order.
+struct Cache<K, V> {
+    entries: HashMap<K, CacheEntry<V>>,
+    /// The keys in insertion order, used to expire and evict entries.
+    /// Keys which were removed or inserted again are skipped.
+    order: VecDeque<(K, std::time::Instant)>,
+    ttl: Option<std::time::Duration>,
+    capacity: Option<usize>,
+}
+
+impl<K, V> Cache<K, V>
+where
+    K: Clone + Eq + std::hash::Hash,
+{
+    fn new(ttl: Option<std::time::Duration>, capacity: Option<usize>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            ttl,
+            capacity,
+        }
+    }
+
+    fn is_expired(&self, inserted_at: std::time::Instant) -> bool {
+        self.ttl.is_some_and(|ttl| inserted_at.elapsed() >= ttl)
+    }
+
+    /// Returns the cached value, `Some(None)` means the key is cached as not found.
+    fn get(&self, key: &K) -> Option<Option<&V>> {
+        let entry = self.entries.get(key)?;
+        if self.is_expired(entry.inserted_at) {
+            return None;
+        }
+
+        Some(entry.value.as_ref())
+    }
+
+    fn insert(&mut self, key: K, value: Option<V>) {
+        if self.capacity == Some(0) {
+            return;
+        }
+
+        let inserted_at = std::time::Instant::now();
+        self.entries.insert(key.clone(), CacheEntry { value, inserted_at });
+        self.order.push_back((key, inserted_at));
+
+        while let Some((key, inserted_at)) = self.order.front() {
+            let current = self.entries.get(key).is_some_and(|entry| entry.inserted_at == *inserted_at);
+            let evict = self.is_expired(*inserted_at) || self.capacity.is_some_and(|capacity| self.entries.len() > capacity);
+
+            if current && !evict {
+                break;
+            }
+
+            let (key, _) = self.order.pop_front().unwrap();
+            if current {
+                self.entries.remove(&key);
+            }
+        }
+
+        // Drop the skipped keys if they pile up.
+        if self.order.len() > self.entries.len() * 2 {
+            let entries = &self.entries;
+            self.order
+                .retain(|(key, inserted_at)| entries.get(key).is_some_and(|entry| entry.inserted_at == *inserted_at));
+        }
+    }
+
+    fn remove(&mut self, key: &K) {
+        self.entries.remove(key);
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
     }
 }
 
 /// A dataloader used to batch requests to a [`DataLoaderFetcher`]
+///
+/// ## Caching
+///
+/// If a cache TTL or capacity is set on the [`DataLoaderBuilder`], loaded
+/// values are cached so repeated loads of the same key do not fetch it again.
+/// Keys which were not found are cached as well, failed fetches are not.
+///
+/// When the capacity is reached the oldest entries are evicted first. Entries
+/// can be invalidated with [`DataLoader::clear`] and [`DataLoader::clear_all`]
+/// or added ahead of time with [`DataLoader::prime`].
 #[must_use = "dataloaders must be used to load data"]
 pub struct DataLoader<E>
 where
@@ -107,6 +248,7 @@ where
     semaphore: Arc<tokio::sync::Semaphore>,
     current_batch: Arc<tokio::sync::Mutex<Option<Batch<E>>>>,
     batch_size: usize,
+    cache: Option<Mutex<Cache<E::Key, E::Value>>>,
 }
 
 impl<E> DataLoader<E>
@@ -127,6 +269,7 @@ where
             semaphore,
             current_batch,
             batch_size: batch_size.max(1),
+            cache: None,
         }
     }
 
@@ -145,6 +288,17 @@ where
         Ok(self.load_many(std::iter::once(items)).await?.into_values().next())
     }
 
+    /// Load a single key with the given [`BatchPriority`]
+    ///
+    /// See [`DataLoader::load`] for details.
+    pub async fn load_with_priority(&self, item: E::Key, priority: BatchPriority) -> Result<Option<E::Value>, ()> {
+        Ok(self
+            .load_many_with_priority(std::iter::once(item), priority)
+            .await?
+            .into_values()
+            .next())
+    }
+
     /// Load many keys
     /// Can return an error if the underlying [`DataLoaderFetcher`] returns an
     /// error
@@ -152,6 +306,21 @@ where
     /// Returns a map of keys to values which may be incomplete if any of the
     /// keys were not found
     pub async fn load_many<I>(&self, items: I) -> Result<HashMap<E::Key, E::Value>, ()>
+    where
+        I: IntoIterator<Item = E::Key> + Send,
+    {
+        self.load_many_with_priority(items, BatchPriority::Normal).await
+    }
+
+    /// Load many keys with the given [`BatchPriority`]
+    ///
+    /// See [`DataLoader::load_many`] for details.
+    /// Keys served from the cache are not affected by the priority.
+    pub async fn load_many_with_priority<I>(
+        &self,
+        items: I,
+        priority: BatchPriority,
+    ) -> Result<HashMap<E::Key, E::Value>, ()>
     where
         I: IntoIterator<Item = E::Key> + Send,
     {
@@ -161,14 +330,23 @@ where
         }
 
         let mut waiters = Vec::<BatchWaiting<E::Key, E::Value>>::new();
+        let mut results = HashMap::new();
 
         let mut count = 0;
 
         {
             let mut new_batch = true;
             let mut batch = self.current_batch.lock().await;
+            let cache = self.cache.as_ref().map(|cache| cache.lock().unwrap());
 
             for item in items {
+                if let Some(cached) = cache.as_ref().and_then(|cache| cache.get(&item)) {
+                    if let Some(value) = cached {
+                        results.insert(item, value.clone());
+                    }
+                    continue;
+                }
+
                 if batch.is_none() {
                     batch.replace(Batch::new(self.semaphore.clone()));
                     new_batch = true;
@@ -194,11 +372,23 @@ where
                     tokio::spawn(batch.take().unwrap().spawn(self.executor.clone()));
                 }
             }
+
+            if let Some(batch) = batch.take_if(|_| priority == BatchPriority::High && !waiters.is_empty()) {
+                tokio::spawn(batch.spawn(self.executor.clone()));
+            }
         }
 
-        let mut results = HashMap::with_capacity(count);
+        results.reserve(count);
         for waiting in waiters {
             let result = waiting.result.wait().await?;
+
+            if let Some(cache) = &self.cache {
+                let mut cache = cache.lock().unwrap();
+                for key in &waiting.keys {
+                    cache.insert(key.clone(), result.get(key).cloned());
+                }
+            }
+
             results.extend(waiting.keys.into_iter().filter_map(|key| {
                 let value = result.get(&key)?.clone();
                 Some((key, value))
@@ -207,6 +397,37 @@ where
 
         Ok(results)
     }
+
+    /// Remove a key from the cache, so the next load fetches it again
+    ///
+    /// Does nothing if caching is disabled.
+    pub fn clear(&self, key: &E::Key) {
+        if let Some(cache) = &self.cache {
+            cache.lock().unwrap().remove(key);
+        }
+    }
+
+    /// Remove all keys from the cache
+    ///
+    /// Does nothing if caching is disabled.
+    pub fn clear_all(&self) {
+        if let Some(cache) = &self.cache {
+            cache.lock().unwrap().clear();
+        }
+    }
+
+    /// Add a value to the cache, so loading the key does not fetch it
+    ///
+    /// Does nothing if the key is already cached or caching is disabled.
+    /// To replace a cached value, [`clear`](DataLoader::clear) it first.
+    pub fn prime(&self, key: E::Key, value: E::Value) {
+        if let Some(cache) = &self.cache {
+            let mut cache = cache.lock().unwrap();
+            if cache.get(&key).is_none() {
+                cache.insert(key, Some(value));
+            }
+        }
+    }
 }
 
 async fn batch_loop<E>(
@@ -548,6 +769,41 @@ mod tests {
         assert!(start.elapsed() < std::time::Duration::from_millis(20));
     }
 
+    #[cfg(not(valgrind))] // test is time-sensitive
+    #[tokio::test]
+    async fn priority() {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = TestFetcher {
+            values: HashMap::from_iter(vec![("a", 1), ("b", 2), ("c", 3)]),
+            delay: std::time::Duration::from_millis(5),
+            requests: requests.clone(),
+            capacity: 10,
+        };
+
+        let loader = DataLoader::builder()
+            .batch_size(10)
+            .concurrency(1)
+            .delay(std::time::Duration::from_millis(500))
+            .build(fetcher);
+
+        let start = std::time::Instant::now();
+        let (a, bc) = tokio::join!(loader.load("a"), async {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            loader.load_many_with_priority(vec!["b", "c"], BatchPriority::High).await
+        });
+        assert_eq!(a.unwrap(), Some(1));
+        assert_eq!(bc.unwrap(), HashMap::from_iter(vec![("b", 2), ("c", 3)]));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+
+        let start = std::time::Instant::now();
+        let a = loader.load_with_priority("a", BatchPriority::High).await.unwrap();
+        assert_eq!(a, Some(1));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+    }
+
     #[cfg(not(valgrind))] // test is time-sensitive
     #[tokio::test]
     async fn deduplication() {
@@ -595,4 +851,114 @@ mod tests {
         assert!(start.elapsed() < std::time::Duration::from_millis(15));
         assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
     }
+
+    #[cfg(not(valgrind))] // test is time-sensitive
+    #[tokio::test]
+    async fn cache() {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = TestFetcher {
+            values: HashMap::from_iter(vec![("a", 1), ("b", 2), ("c", 3)]),
+            delay: std::time::Duration::from_millis(5),
+            requests: requests.clone(),
+            capacity: 10,
+        };
+
+        let loader = DataLoader::builder()
+            .batch_size(10)
+            .concurrency(1)
+            .cache_capacity(10)
+            .build(fetcher);
+
+        let ab = loader.load_many(vec!["a", "b", "unknown"]).await.unwrap();
+        assert_eq!(ab, HashMap::from_iter(vec![("a", 1), ("b", 2)]));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        // cached values and misses are not fetched again
+        assert_eq!(loader.load("a").await, Ok(Some(1)));
+        assert_eq!(loader.load("unknown").await, Ok(None));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        // only the uncached keys are fetched
+        let abc = loader.load_many(vec!["a", "b", "c"]).await.unwrap();
+        assert_eq!(abc, HashMap::from_iter(vec![("a", 1), ("b", 2), ("c", 3)]));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 2);
+
+        loader.clear(&"a");
+        assert_eq!(loader.load("a").await, Ok(Some(1)));
+        assert_eq!(loader.load("b").await, Ok(Some(2)));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 3);
+
+        loader.clear_all();
+        assert_eq!(loader.load("b").await, Ok(Some(2)));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 4);
+
+        // priming does not replace cached values
+        loader.prime("b", 20);
+        loader.prime("d", 4);
+        assert_eq!(loader.load("b").await, Ok(Some(2)));
+        assert_eq!(loader.load("d").await, Ok(Some(4)));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 4);
+    }
+
+    #[cfg(not(valgrind))] // test is time-sensitive
+    #[tokio::test]
+    async fn cache_ttl() {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let fetcher = TestFetcher {
+            values: HashMap::from_iter(vec![("a", 1)]),
+            delay: std::time::Duration::from_millis(5),
+            requests: requests.clone(),
+            capacity: 10,
+        };
+
+        let loader = DataLoader::builder()
+            .batch_size(10)
+            .concurrency(1)
+            .cache_ttl(std::time::Duration::from_millis(50))
+            .build(fetcher);
+
+        assert_eq!(loader.load("a").await, Ok(Some(1)));
+        assert_eq!(loader.load("a").await, Ok(Some(1)));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+
+        assert_eq!(loader.load("a").await, Ok(Some(1)));
+        assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn cache_capacity() {
+        let mut cache = Cache::new(None, Some(2));
+
+        cache.insert("a", Some(1));
+        cache.insert("b", Some(2));
+        cache.insert("c", None);
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.get(&"b"), Some(Some(&2)));
+        assert_eq!(cache.get(&"c"), Some(None));
+
+        // inserting a key again moves it to the back
+        cache.insert("b", Some(3));
+        cache.insert("d", Some(4));
+        assert_eq!(cache.get(&"b"), Some(Some(&3)));
+        assert_eq!(cache.get(&"c"), None);
+        assert_eq!(cache.get(&"d"), Some(Some(&4)));
+
+        cache.remove(&"b");
+        cache.insert("e", Some(5));
+        assert_eq!(cache.get(&"d"), Some(Some(&4)));
+        assert_eq!(cache.get(&"e"), Some(Some(&5)));
+
+        for _ in 0..10 {
+            cache.insert("e", Some(5));
+        }
+        assert!(cache.order.len() <= 4);
+
+        let mut cache = Cache::new(None, Some(0));
+        cache.insert("a", Some(1));
+        assert_eq!(cache.get(&"a"), None);
+    }
 }
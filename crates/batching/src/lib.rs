@@ -155,7 +155,7 @@
 pub mod batch;
 pub mod dataloader;
 
-pub use batch::{BatchExecutor, Batcher};
+pub use batch::{BatchExecutor, BatchPriority, Batcher, BlockingBatcher};
 pub use dataloader::{DataLoader, DataLoaderFetcher};
 
 /// Changelogs generated by [scuffle_changelog]
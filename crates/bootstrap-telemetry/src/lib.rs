@@ -8,6 +8,9 @@
 #![cfg_attr(feature = "docs", doc = document_features::document_features!())]
 //! See [`TelemetrySvc`] for more details.
 //!
+//! With the `preset` feature enabled, the `preset` module provides ready-made
+//! log, trace and metric pipelines which can be set up with a single call.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -107,6 +110,8 @@
 #![deny(unsafe_code)]
 #![deny(unreachable_pub)]
 
+use std::borrow::Cow;
+
 use anyhow::Context;
 use bytes::Bytes;
 #[cfg(feature = "opentelemetry-logs")]
@@ -122,6 +127,8 @@ pub use tracing_opentelemetry;
 
 #[cfg(feature = "opentelemetry")]
 pub mod opentelemetry;
+#[cfg(feature = "preset")]
+pub mod preset;
 
 /// The telemetry service.
 ///
@@ -136,7 +143,7 @@ pub mod opentelemetry;
 ///
 /// The server provides the following endpoints:
 ///
-/// ### `/health`
+/// ### `/health` and `/healthz`
 ///
 /// Health check endpoint.
 ///
@@ -145,6 +152,23 @@ pub mod opentelemetry;
 /// check returns an error, the endpoint returns `500 Internal Server Error`
 /// along with the error message.
 ///
+/// ### `/readyz`
+///
+/// Readiness check endpoint.
+///
+/// This endpoint calls the readiness check function provided by the config and
+/// responds with `200 OK` if the readiness check returns `Ok(())`. If the
+/// readiness check returns an error, the endpoint returns
+/// `503 Service Unavailable` along with the error message.
+///
+/// ### `/build-info`
+///
+/// Build information endpoint.
+///
+/// This endpoint responds with the [`BuildInfo`] provided by the config as a
+/// JSON object. It is only enabled if build information is provided through the
+/// config.
+///
 /// ### `/metrics`
 ///
 /// Metrics endpoint which can be used by Prometheus to scrape metrics.
@@ -164,6 +188,16 @@ pub mod opentelemetry;
 ///
 /// This endpoint is only enabled if the `pprof` feature flag is enabled.
 ///
+/// ### `/debug/pprof/profile` and `/debug/pprof/heap` (Unix only)
+///
+/// CPU and heap profile endpoints compatible with Go's `net/http/pprof`, so
+/// `go tool pprof` and Parca agents can scrape profiles unchanged.
+///
+/// See [`PprofService`](scuffle_pprof::http::PprofService) for details.
+///
+/// These endpoints are only enabled if the `pprof` feature flag is enabled and
+/// a pprof service is provided through the config.
+///
 /// ### `/opentelemetry/flush`
 ///
 /// OpenTelemetry flush endpoint.
@@ -172,6 +206,52 @@ pub mod opentelemetry;
 /// enabled and an OpenTelemetry config is provided through the config.
 pub struct TelemetrySvc;
 
+/// Build information served by the `/build-info` endpoint of the
+/// [`TelemetrySvc`].
+///
+/// This is a set of string key value pairs, use [`build_info!`] to create it
+/// with the name and version of the current crate.
+///
+/// ```rust
+/// let info = scuffle_bootstrap_telemetry::build_info!().with("commit", "0123abc");
+///
+/// assert_eq!(info.get("name"), Some("scuffle-bootstrap-telemetry"));
+/// assert_eq!(info.get("commit"), Some("0123abc"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildInfo {
+    entries: std::collections::BTreeMap<Cow<'static, str>, Cow<'static, str>>,
+}
+
+impl BuildInfo {
+    /// Create empty build information.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry, replacing any previous value of the key.
+    pub fn with(mut self, key: impl Into<Cow<'static, str>>, value: impl Into<Cow<'static, str>>) -> Self {
+        self.entries.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the value of the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(AsRef::as_ref)
+    }
+}
+
+/// Create [`BuildInfo`] with the `name` and `version` of the crate this macro
+/// is called from.
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::BuildInfo::new()
+            .with("name", ::std::env!("CARGO_PKG_NAME"))
+            .with("version", ::std::env!("CARGO_PKG_VERSION"))
+    };
+}
+
 /// Implement this trait to configure the telemetry service.
 pub trait TelemetryConfig: Global {
     /// Return true if the service is enabled.
@@ -196,6 +276,27 @@ pub trait TelemetryConfig: Global {
         std::future::ready(Ok(()))
     }
 
+    /// Return a readiness check to determine if the service is ready to accept
+    /// traffic.
+    ///
+    /// Unlike the health check, a failing readiness check signals that the
+    /// service is temporarily unable to serve requests, e.g. while it is still
+    /// warming up, and should not be restarted.
+    ///
+    /// Always ready by default.
+    fn ready_check(&self) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        std::future::ready(Ok(()))
+    }
+
+    /// Return the build information served by the `/build-info` http endpoint.
+    ///
+    /// See [`build_info!`] to create it from the metadata of the current crate.
+    ///
+    /// Disabled (`None`) by default.
+    fn build_info(&self) -> Option<BuildInfo> {
+        None
+    }
+
     /// Return a Prometheus metrics registry to scrape metrics from.
     ///
     /// Returning `Some` will enable the `/metrics` http endpoint which can be
@@ -207,6 +308,18 @@ pub trait TelemetryConfig: Global {
         None
     }
 
+    /// Return the service for the Go compatible `/debug/pprof/profile` and
+    /// `/debug/pprof/heap` http endpoints.
+    ///
+    /// Heap profiles need a heap profiler, see
+    /// [`PprofService::with_heap_profiler`](scuffle_pprof::http::PprofService::with_heap_profiler).
+    ///
+    /// Enabled with the default settings and without a heap profiler by default.
+    #[cfg(all(feature = "pprof", unix))]
+    fn pprof_service(&self) -> Option<scuffle_pprof::http::PprofService> {
+        Some(scuffle_pprof::http::PprofService::new())
+    }
+
     /// Pass an OpenTelemetry instance to the service.
     ///
     /// If provided the service will flush and shutdown the OpenTelemetry
@@ -232,11 +345,17 @@ impl<Global: TelemetryConfig> Service<Global> for TelemetrySvc {
                 let global = global.clone();
                 async move {
                     match req.uri().path() {
-                        "/health" => health_check(&global, req).await,
+                        "/health" | "/healthz" => health_check(&global, req).await,
+                        "/readyz" => ready_check(&global, req).await,
+                        "/build-info" => build_info(&global, req).await,
                         #[cfg(feature = "prometheus")]
                         "/metrics" => metrics(&global, req).await,
                         #[cfg(all(feature = "pprof", unix))]
                         "/pprof/cpu" => pprof(&global, req).await,
+                        #[cfg(all(feature = "pprof", unix))]
+                        scuffle_pprof::http::PROFILE_PATH | scuffle_pprof::http::HEAP_PATH => {
+                            pprof_service(&global, req).await
+                        }
                         #[cfg(feature = "opentelemetry")]
                         "/opentelemetry/flush" => opentelemetry_flush(&global).await,
                         _ => Ok(http::Response::builder()
@@ -288,6 +407,40 @@ async fn health_check<G: TelemetryConfig>(
     }
 }
 
+async fn ready_check<G: TelemetryConfig>(
+    global: &std::sync::Arc<G>,
+    _: http::Request<scuffle_http::body::IncomingBody>,
+) -> Result<http::Response<http_body_util::Full<Bytes>>, http::Error> {
+    if let Err(err) = global.ready_check().await {
+        tracing::warn!("ready check failed: {err}");
+        Ok(http::Response::builder()
+            .status(http::StatusCode::SERVICE_UNAVAILABLE)
+            .body(http_body_util::Full::new(format!("{err:#}").into()))?)
+    } else {
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .body(http_body_util::Full::new(Bytes::from_static(b"ok")))?)
+    }
+}
+
+async fn build_info<G: TelemetryConfig>(
+    global: &std::sync::Arc<G>,
+    _: http::Request<scuffle_http::body::IncomingBody>,
+) -> Result<http::Response<http_body_util::Full<Bytes>>, http::Error> {
+    if let Some(build_info) = global.build_info() {
+        let body = serde_json::to_vec(&build_info.entries).expect("string map is always serializable");
+
+        Ok(http::Response::builder()
+            .status(http::StatusCode::OK)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(http_body_util::Full::new(Bytes::from(body)))?)
+    } else {
+        Ok(http::Response::builder()
+            .status(http::StatusCode::NOT_FOUND)
+            .body(http_body_util::Full::new(Bytes::from_static(b"not found")))?)
+    }
+}
+
 #[cfg(feature = "prometheus")]
 async fn metrics<G: TelemetryConfig>(
     global: &std::sync::Arc<G>,
@@ -370,6 +523,23 @@ async fn pprof<G: TelemetryConfig>(
     }
 }
 
+#[cfg(unix)]
+#[cfg(feature = "pprof")]
+async fn pprof_service<G: TelemetryConfig>(
+    global: &std::sync::Arc<G>,
+    req: http::Request<scuffle_http::body::IncomingBody>,
+) -> Result<http::Response<http_body_util::Full<Bytes>>, http::Error> {
+    use scuffle_http::service::HttpService;
+
+    if let Some(mut pprof) = global.pprof_service() {
+        pprof.call(req).await
+    } else {
+        Ok(http::Response::builder()
+            .status(http::StatusCode::NOT_FOUND)
+            .body(http_body_util::Full::new(Bytes::from_static(b"not found")))?)
+    }
+}
+
 #[cfg(feature = "opentelemetry")]
 async fn opentelemetry_flush<G: TelemetryConfig>(
     global: &std::sync::Arc<G>,
@@ -426,7 +596,7 @@ mod tests {
     use opentelemetry_sdk::trace::SdkTracerProvider;
     use scuffle_bootstrap::{GlobalWithoutConfig, Service};
 
-    use crate::{TelemetryConfig, TelemetrySvc};
+    use crate::{BuildInfo, TelemetryConfig, TelemetrySvc};
 
     async fn request_metrics(addr: SocketAddr) -> reqwest::Result<String> {
         reqwest::get(format!("http://{addr}/metrics"))
@@ -438,7 +608,11 @@ mod tests {
     }
 
     async fn request_health(addr: SocketAddr) -> String {
-        reqwest::get(format!("http://{addr}/health"))
+        request_health_path(addr, "/health").await
+    }
+
+    async fn request_health_path(addr: SocketAddr, path: &str) -> String {
+        reqwest::get(format!("http://{addr}{path}"))
             .await
             .unwrap()
             .error_for_status()
@@ -518,6 +692,10 @@ mod tests {
             fn opentelemetry(&self) -> Option<&crate::opentelemetry::OpenTelemetry> {
                 Some(&self.open_telemetry)
             }
+
+            fn build_info(&self) -> Option<BuildInfo> {
+                Some(crate::build_info!().with("commit", "0123abc"))
+            }
         }
 
         #[scuffle_metrics::metrics]
@@ -546,6 +724,21 @@ mod tests {
 
         let health = request_health(bind_addr).await;
         assert_eq!(health, "ok");
+        assert_eq!(request_health_path(bind_addr, "/healthz").await, "ok");
+        assert_eq!(request_health_path(bind_addr, "/readyz").await, "ok");
+
+        let res = reqwest::get(format!("http://{bind_addr}/build-info")).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::OK);
+        assert_eq!(res.headers()["content-type"], "application/json");
+        let build_info: serde_json::Value = serde_json::from_str(&res.text().await.unwrap()).unwrap();
+        assert_eq!(
+            build_info,
+            serde_json::json!({
+                "commit": "0123abc",
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            })
+        );
 
         let metrics = request_metrics(bind_addr).await.expect("metrics failed");
         assert!(metrics.starts_with("# HELP target Information about the target\n"));
@@ -594,6 +787,16 @@ mod tests {
             let res = request_pprof(bind_addr, "100", "invalid").await.expect_err("error expected");
             assert!(res.is_status());
             assert_eq!(res.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+
+            let res = reqwest::get(format!("http://{bind_addr}/debug/pprof/profile?seconds=1"))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), reqwest::StatusCode::OK);
+            assert!(!res.bytes().await.unwrap().is_empty());
+
+            let res = reqwest::get(format!("http://{bind_addr}/debug/pprof/heap")).await.unwrap();
+            assert_eq!(res.status(), reqwest::StatusCode::NOT_IMPLEMENTED);
+            assert_eq!(res.headers()["x-go-pprof"], "1");
         }
 
         assert!(flush_opentelemetry(bind_addr).await.is_ok());
@@ -627,6 +830,10 @@ mod tests {
             fn bind_address(&self) -> Option<std::net::SocketAddr> {
                 Some(self.bind_addr)
             }
+
+            async fn ready_check(&self) -> Result<(), anyhow::Error> {
+                anyhow::bail!("warming up")
+            }
         }
 
         let global = <TestGlobal as GlobalWithoutConfig>::init().await.unwrap();
@@ -645,6 +852,13 @@ mod tests {
         assert!(res.is_status());
         assert_eq!(res.status(), Some(reqwest::StatusCode::NOT_FOUND));
 
+        let res = reqwest::get(format!("http://{bind_addr}/build-info")).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let res = reqwest::get(format!("http://{bind_addr}/readyz")).await.unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.text().await.unwrap(), "warming up");
+
         #[cfg(unix)]
         {
             let timer = std::time::Instant::now();
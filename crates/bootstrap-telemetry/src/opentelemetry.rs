@@ -9,7 +9,7 @@ pub use ::opentelemetry::*;
 #[derive(Debug, Default, Clone)]
 pub struct OpenTelemetry {
     #[cfg(feature = "opentelemetry-metrics")]
-    metrics: Option<opentelemetry_sdk::metrics::SdkMeterProvider>,
+    metrics: Vec<opentelemetry_sdk::metrics::SdkMeterProvider>,
     #[cfg(feature = "opentelemetry-traces")]
     traces: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
     #[cfg(feature = "opentelemetry-logs")]
@@ -35,7 +35,7 @@ impl OpenTelemetry {
         let mut enabled = false;
         #[cfg(feature = "opentelemetry-metrics")]
         {
-            enabled |= self.metrics.is_some();
+            enabled |= !self.metrics.is_empty();
         }
         #[cfg(feature = "opentelemetry-traces")]
         {
@@ -48,11 +48,11 @@ impl OpenTelemetry {
         enabled
     }
 
-    /// Sets the metrics provider.
+    /// Sets the metrics provider, replacing any previously set providers.
     #[cfg(feature = "opentelemetry-metrics")]
     pub fn with_metrics(self, metrics: impl Into<Option<opentelemetry_sdk::metrics::SdkMeterProvider>>) -> Self {
         Self {
-            metrics: metrics.into(),
+            metrics: metrics.into().into_iter().collect(),
             #[cfg(feature = "opentelemetry-traces")]
             traces: self.traces,
             #[cfg(feature = "opentelemetry-logs")]
@@ -60,6 +60,16 @@ impl OpenTelemetry {
         }
     }
 
+    /// Adds another metrics provider.
+    ///
+    /// Useful when metrics are recorded to multiple providers, for example
+    /// with `scuffle_metrics::fanout::FanOutMeterProvider`.
+    #[cfg(feature = "opentelemetry-metrics")]
+    pub fn with_additional_metrics(mut self, metrics: opentelemetry_sdk::metrics::SdkMeterProvider) -> Self {
+        self.metrics.push(metrics);
+        self
+    }
+
     /// Sets the traces provider.
     #[cfg(feature = "opentelemetry-traces")]
     pub fn with_traces(self, traces: impl Into<Option<opentelemetry_sdk::trace::SdkTracerProvider>>) -> Self {
@@ -89,7 +99,7 @@ impl OpenTelemetry {
     /// <div class="warning">Warning: This blocks the current thread.</div>
     pub fn flush(&self) -> Result<(), opentelemetry_sdk::error::OTelSdkError> {
         #[cfg(feature = "opentelemetry-metrics")]
-        if let Some(metrics) = &self.metrics {
+        for metrics in &self.metrics {
             metrics.force_flush()?;
         }
 
@@ -109,7 +119,7 @@ impl OpenTelemetry {
     /// Shuts down all metrics, traces, and logs.
     pub fn shutdown(&self) -> Result<(), opentelemetry_sdk::error::OTelSdkError> {
         #[cfg(feature = "opentelemetry-metrics")]
-        if let Some(metrics) = &self.metrics {
+        for metrics in &self.metrics {
             metrics.shutdown()?;
         }
 
@@ -0,0 +1,493 @@
+//! Ready-made telemetry presets.
+//!
+//! A preset wires up stdout logs, OpenTelemetry traces exported over OTLP and
+//! Prometheus metrics with a single call to [`Telemetry::init`], so services
+//! get consistent observability without assembling the pipeline by hand.
+//!
+//! Metrics can be exported to Prometheus and over OTLP at the same time, each
+//! with its own [`MetricsFilterConfig`]. This allows migrating dashboards
+//! between backends with configuration changes only.
+//!
+//! The [`PresetConfig`] can be deserialized, so it can be embedded in the
+//! service configuration.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use std::net::SocketAddr;
+//! use std::sync::Arc;
+//!
+//! use scuffle_bootstrap::global::GlobalWithoutConfig;
+//! use scuffle_bootstrap_telemetry::preset::{Preset, PresetConfig, Telemetry};
+//! use scuffle_bootstrap_telemetry::{opentelemetry, prometheus_client, TelemetryConfig, TelemetrySvc};
+//!
+//! struct Global {
+//!     telemetry: Telemetry,
+//! }
+//!
+//! impl GlobalWithoutConfig for Global {
+//!     async fn init() -> anyhow::Result<Arc<Self>> {
+//!         let telemetry = Telemetry::init(&PresetConfig {
+//!             preset: Preset::Production,
+//!             service_name: Some("my-service".into()),
+//!             ..Default::default()
+//!         })?;
+//!
+//!         Ok(Arc::new(Self { telemetry }))
+//!     }
+//! }
+//!
+//! impl TelemetryConfig for Global {
+//!     fn bind_address(&self) -> Option<SocketAddr> {
+//!         Some(SocketAddr::from(([127, 0, 0, 1], 8080)))
+//!     }
+//!
+//!     fn prometheus_metrics_registry(&self) -> Option<&prometheus_client::registry::Registry> {
+//!         self.telemetry.prometheus_metrics_registry()
+//!     }
+//!
+//!     fn opentelemetry(&self) -> Option<&opentelemetry::OpenTelemetry> {
+//!         Some(self.telemetry.opentelemetry())
+//!     }
+//! }
+//!
+//! scuffle_bootstrap::main! {
+//!     Global {
+//!         TelemetrySvc,
+//!     }
+//! };
+//! ```
+
+use anyhow::Context;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use scuffle_metrics::fanout::{FanOutMeterProvider, MetricFilter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::opentelemetry::OpenTelemetry;
+
+/// A telemetry preset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde_derive::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// Human readable logs and Prometheus metrics.
+    ///
+    /// Traces are only exported if an OTLP endpoint is configured.
+    #[default]
+    Development,
+    /// JSON logs, Prometheus metrics and traces exported over OTLP.
+    ///
+    /// If no OTLP endpoint is configured, the standard `OTEL_EXPORTER_OTLP_*`
+    /// environment variables are used.
+    Production,
+}
+
+/// The format of the logs written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde_derive::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Multi-line human readable logs.
+    Pretty,
+    /// Single-line human readable logs.
+    Compact,
+    /// One JSON object per line.
+    Json,
+    /// No logs are written to stdout.
+    Disabled,
+}
+
+/// Configuration for a telemetry [`Preset`].
+#[derive(Debug, Clone, PartialEq, Eq, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct PresetConfig {
+    /// The preset to use.
+    pub preset: Preset,
+    /// The service name attached to traces and metrics.
+    ///
+    /// Defaults to the `OTEL_SERVICE_NAME` environment variable.
+    pub service_name: Option<String>,
+    /// The filter for logs and traces, in the [`EnvFilter`] directive syntax.
+    pub log_filter: String,
+    /// Overrides the log format of the preset.
+    pub log_format: Option<LogFormat>,
+    /// The OTLP/HTTP endpoint to export traces to.
+    pub otlp_endpoint: Option<String>,
+    /// Whether to collect metrics for the Prometheus `/metrics` endpoint.
+    pub prometheus: bool,
+    /// The metrics collected for the Prometheus `/metrics` endpoint.
+    pub prometheus_filter: MetricsFilterConfig,
+    /// Whether to export metrics over OTLP.
+    ///
+    /// If no OTLP metrics endpoint is configured, the standard
+    /// `OTEL_EXPORTER_OTLP_*` environment variables are used.
+    pub otlp_metrics: bool,
+    /// The OTLP/HTTP endpoint to export metrics to.
+    ///
+    /// Setting this enables exporting metrics over OTLP.
+    pub otlp_metrics_endpoint: Option<String>,
+    /// The metrics exported over OTLP.
+    pub otlp_metrics_filter: MetricsFilterConfig,
+}
+
+impl Default for PresetConfig {
+    fn default() -> Self {
+        Self {
+            preset: Preset::default(),
+            service_name: None,
+            log_filter: "info".into(),
+            log_format: None,
+            otlp_endpoint: None,
+            prometheus: true,
+            prometheus_filter: MetricsFilterConfig::default(),
+            otlp_metrics: false,
+            otlp_metrics_endpoint: None,
+            otlp_metrics_filter: MetricsFilterConfig::default(),
+        }
+    }
+}
+
+impl PresetConfig {
+    /// Returns the log format, taking the preset into account.
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format.unwrap_or(match self.preset {
+            Preset::Development => LogFormat::Pretty,
+            Preset::Production => LogFormat::Json,
+        })
+    }
+
+    /// Returns true if traces are exported over OTLP, taking the preset into account.
+    pub fn otlp_traces(&self) -> bool {
+        self.otlp_endpoint.is_some() || self.preset == Preset::Production
+    }
+
+    /// Returns true if metrics are exported over OTLP.
+    pub fn otlp_metrics(&self) -> bool {
+        self.otlp_metrics || self.otlp_metrics_endpoint.is_some()
+    }
+}
+
+/// Filters the metrics recorded for one metrics exporter.
+///
+/// Metric names are matched by prefix. An empty filter exports everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde_derive::Deserialize)]
+#[serde(default)]
+pub struct MetricsFilterConfig {
+    /// Only export metrics whose name starts with one of these prefixes.
+    ///
+    /// All metrics are exported if empty.
+    pub include: Vec<String>,
+    /// Do not export metrics whose name starts with one of these prefixes.
+    pub exclude: Vec<String>,
+    /// Only export these attributes.
+    ///
+    /// All attributes are exported if empty.
+    pub allowed_attributes: Vec<String>,
+    /// Do not export these attributes.
+    pub denied_attributes: Vec<String>,
+}
+
+impl MetricsFilterConfig {
+    /// Returns the [`MetricFilter`] described by this config.
+    pub fn filter(&self) -> MetricFilter {
+        let filter = self
+            .include
+            .iter()
+            .fold(MetricFilter::new(), |f, prefix| f.include(prefix.clone()));
+        let filter = self.exclude.iter().fold(filter, |f, prefix| f.exclude(prefix.clone()));
+        let filter = self
+            .allowed_attributes
+            .iter()
+            .fold(filter, |f, key| f.allow_attribute(key.clone()));
+        self.denied_attributes
+            .iter()
+            .fold(filter, |f, key| f.deny_attribute(key.clone()))
+    }
+}
+
+/// The telemetry pipeline built from a [`PresetConfig`].
+#[derive(Debug)]
+pub struct Telemetry {
+    log_format: LogFormat,
+    log_filter: String,
+    prometheus: Option<prometheus_client::registry::Registry>,
+    metrics: Vec<SdkMeterProvider>,
+    meter_provider: Option<FanOutMeterProvider>,
+    traces: Option<SdkTracerProvider>,
+    opentelemetry: OpenTelemetry,
+}
+
+impl Telemetry {
+    /// Builds the telemetry pipeline without installing anything globally.
+    ///
+    /// Use [`Telemetry::init`] to also install it, or [`Telemetry::layer`] to
+    /// compose it into a custom subscriber.
+    pub fn new(config: &PresetConfig) -> anyhow::Result<Self> {
+        EnvFilter::try_new(&config.log_filter).context("log filter")?;
+
+        let mut resource = Resource::builder();
+        if let Some(service_name) = &config.service_name {
+            resource = resource.with_service_name(service_name.clone());
+        }
+        let resource = resource.build();
+
+        let mut metrics = Vec::new();
+        let mut meter_provider = FanOutMeterProvider::builder();
+
+        let prometheus = if config.prometheus {
+            let mut prometheus = prometheus_client::registry::Registry::default();
+            let exporter = scuffle_metrics::prometheus::exporter().build();
+            prometheus.register_collector(exporter.collector());
+
+            let provider = SdkMeterProvider::builder()
+                .with_reader(exporter)
+                .with_resource(resource.clone())
+                .build();
+            meter_provider = meter_provider.with_provider(provider.clone(), config.prometheus_filter.filter());
+            metrics.push(provider);
+
+            Some(prometheus)
+        } else {
+            None
+        };
+
+        if config.otlp_metrics() {
+            let mut exporter = opentelemetry_otlp::MetricExporter::builder().with_http();
+            if let Some(endpoint) = &config.otlp_metrics_endpoint {
+                exporter = exporter.with_endpoint(endpoint);
+            }
+            let exporter = exporter.build().context("otlp metric exporter")?;
+
+            let provider = SdkMeterProvider::builder()
+                .with_periodic_exporter(exporter)
+                .with_resource(resource.clone())
+                .build();
+            meter_provider = meter_provider.with_provider(provider.clone(), config.otlp_metrics_filter.filter());
+            metrics.push(provider);
+        }
+
+        let meter_provider = (!metrics.is_empty()).then(|| meter_provider.build());
+
+        let traces = if config.otlp_traces() {
+            let mut exporter = opentelemetry_otlp::SpanExporter::builder().with_http();
+            if let Some(endpoint) = &config.otlp_endpoint {
+                exporter = exporter.with_endpoint(endpoint);
+            }
+            let exporter = exporter.build().context("otlp span exporter")?;
+
+            Some(
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter)
+                    .with_resource(resource)
+                    .build(),
+            )
+        } else {
+            None
+        };
+
+        let opentelemetry = metrics
+            .iter()
+            .fold(OpenTelemetry::new(), |opentelemetry, metrics| {
+                opentelemetry.with_additional_metrics(metrics.clone())
+            })
+            .with_traces(traces.clone());
+
+        Ok(Self {
+            log_format: config.log_format(),
+            log_filter: config.log_filter.clone(),
+            prometheus,
+            metrics,
+            meter_provider,
+            traces,
+            opentelemetry,
+        })
+    }
+
+    /// Builds the telemetry pipeline and installs it globally.
+    ///
+    /// This sets the global OpenTelemetry meter and tracer providers and the
+    /// global tracing subscriber.
+    pub fn init(config: &PresetConfig) -> anyhow::Result<Self> {
+        let telemetry = Self::new(config)?;
+
+        if let Some(meter_provider) = &telemetry.meter_provider {
+            opentelemetry::global::set_meter_provider(meter_provider.clone());
+        }
+
+        if let Some(traces) = &telemetry.traces {
+            opentelemetry::global::set_tracer_provider(traces.clone());
+        }
+
+        tracing_subscriber::registry()
+            .with(telemetry.layer()?)
+            .try_init()
+            .context("set global tracing subscriber")?;
+
+        Ok(telemetry)
+    }
+
+    /// Returns a tracing layer which writes logs to stdout and exports traces,
+    /// filtered by the configured log filter.
+    pub fn layer<S>(&self) -> anyhow::Result<Box<dyn Layer<S> + Send + Sync>>
+    where
+        S: tracing::Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        let filter = EnvFilter::try_new(&self.log_filter).context("log filter")?;
+
+        let logs = match self.log_format {
+            LogFormat::Pretty => Some(tracing_subscriber::fmt::layer().pretty().boxed()),
+            LogFormat::Compact => Some(tracing_subscriber::fmt::layer().compact().boxed()),
+            LogFormat::Json => Some(tracing_subscriber::fmt::layer().json().boxed()),
+            LogFormat::Disabled => None,
+        };
+
+        let traces = self
+            .traces
+            .as_ref()
+            .map(|traces| tracing_opentelemetry::layer().with_tracer(traces.tracer(env!("CARGO_PKG_NAME"))));
+
+        Ok(Layer::and_then(logs, traces).with_filter(filter).boxed())
+    }
+
+    /// Returns the Prometheus metrics registry, if metrics are enabled.
+    pub fn prometheus_metrics_registry(&self) -> Option<&prometheus_client::registry::Registry> {
+        self.prometheus.as_ref()
+    }
+
+    /// Returns the meter provider which records metrics to all configured
+    /// exporters, if any metrics exporter is enabled.
+    pub fn meter_provider(&self) -> Option<&FanOutMeterProvider> {
+        self.meter_provider.as_ref()
+    }
+
+    /// Returns the OpenTelemetry providers, used to flush and shut them down.
+    pub fn opentelemetry(&self) -> &OpenTelemetry {
+        &self.opentelemetry
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use opentelemetry::metrics::MeterProvider;
+    use scuffle_metrics::fanout::MetricFilter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::{LogFormat, MetricsFilterConfig, Preset, PresetConfig, Telemetry};
+
+    #[test]
+    fn config() {
+        let config: PresetConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, PresetConfig::default());
+        assert_eq!(config.log_format(), LogFormat::Pretty);
+        assert!(!config.otlp_traces());
+        assert!(config.prometheus);
+
+        let config: PresetConfig = serde_json::from_str(r#"{"preset": "production", "service_name": "test"}"#).unwrap();
+        assert_eq!(config.preset, Preset::Production);
+        assert_eq!(config.service_name.as_deref(), Some("test"));
+        assert_eq!(config.log_format(), LogFormat::Json);
+        assert!(config.otlp_traces());
+
+        let config: PresetConfig =
+            serde_json::from_str(r#"{"log_format": "compact", "otlp_endpoint": "http://localhost:4318"}"#).unwrap();
+        assert_eq!(config.log_format(), LogFormat::Compact);
+        assert!(config.otlp_traces());
+        assert!(!config.otlp_metrics());
+
+        let config: PresetConfig = serde_json::from_str(
+            r#"{
+                "prometheus_filter": {"exclude": ["debug_"]},
+                "otlp_metrics_endpoint": "http://localhost:4318/v1/metrics",
+                "otlp_metrics_filter": {"include": ["http_"], "denied_attributes": ["path"]}
+            }"#,
+        )
+        .unwrap();
+        assert!(config.otlp_metrics());
+        assert_eq!(
+            config.prometheus_filter,
+            MetricsFilterConfig {
+                exclude: vec!["debug_".into()],
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            config.otlp_metrics_filter.filter(),
+            MetricFilter::new().include("http_").deny_attribute("path")
+        );
+    }
+
+    #[test]
+    fn development() {
+        let telemetry = Telemetry::new(&PresetConfig::default()).unwrap();
+        assert!(telemetry.prometheus_metrics_registry().is_some());
+        assert!(telemetry.opentelemetry().is_enabled());
+        assert!(telemetry.traces.is_none());
+
+        let subscriber = tracing_subscriber::registry().with(telemetry.layer().unwrap());
+        tracing::subscriber::with_default(subscriber, || tracing::info!("development"));
+
+        telemetry.opentelemetry().shutdown().unwrap();
+    }
+
+    #[test]
+    fn production() {
+        let telemetry = Telemetry::new(&PresetConfig {
+            preset: Preset::Production,
+            service_name: Some("test".into()),
+            log_format: Some(LogFormat::Disabled),
+            otlp_endpoint: Some("http://127.0.0.1:4318/v1/traces".into()),
+            prometheus: false,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(telemetry.prometheus_metrics_registry().is_none());
+        assert!(telemetry.metrics.is_empty());
+        assert!(telemetry.meter_provider().is_none());
+        assert!(telemetry.traces.is_some());
+        assert!(telemetry.opentelemetry().is_enabled());
+
+        let subscriber = tracing_subscriber::registry().with(telemetry.layer().unwrap());
+        tracing::subscriber::with_default(subscriber, || tracing::info_span!("production").in_scope(|| {}));
+    }
+
+    #[test]
+    fn multiple_metrics_exporters() {
+        let telemetry = Telemetry::new(&PresetConfig {
+            log_format: Some(LogFormat::Disabled),
+            prometheus_filter: MetricsFilterConfig {
+                exclude: vec!["debug_".into()],
+                ..Default::default()
+            },
+            otlp_metrics_endpoint: Some("http://127.0.0.1:4318/v1/metrics".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(telemetry.metrics.len(), 2);
+        assert!(telemetry.opentelemetry().is_enabled());
+
+        let meter = telemetry.meter_provider().unwrap().meter("test");
+        meter.u64_counter("http_requests").build().add(1, &[]);
+        meter.u64_counter("debug_requests").build().add(1, &[]);
+
+        let mut buf = String::new();
+        prometheus_client::encoding::text::encode(&mut buf, telemetry.prometheus_metrics_registry().unwrap()).unwrap();
+        assert!(buf.contains("http_requests"));
+        assert!(!buf.contains("debug_requests"));
+    }
+
+    #[test]
+    fn invalid_log_filter() {
+        let err = Telemetry::new(&PresetConfig {
+            log_filter: "[".into(),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "log filter");
+    }
+}
@@ -53,7 +53,7 @@ mod tests {
 
         let syntax_tree = prettyplease::unparse(&syn::parse_file(&output.to_string()).unwrap());
 
-        insta::assert_snapshot!(syntax_tree, @r##"
+        insta::assert_snapshot!(syntax_tree, @r#"
         #[automatically_derived]
         fn main() -> ::scuffle_bootstrap::prelude::anyhow::Result<()> {
             #[doc(hidden)]
@@ -63,7 +63,12 @@ mod tests {
                 <MyGlobal as ::scuffle_bootstrap::global::Global>::pre_init(),
                 "pre_init",
             )?;
-            let runtime = <MyGlobal as ::scuffle_bootstrap::global::Global>::tokio_runtime();
+            ::scuffle_bootstrap::__cli! {
+                MyGlobal { MyService, }
+            }
+            let runtime = <MyGlobal as ::scuffle_bootstrap::global::Global>::tokio_runtime(
+                ::scuffle_bootstrap::global::RuntimeConfig::new(),
+            );
             let config = ::scuffle_bootstrap::prelude::anyhow::Context::context(
                 runtime
                     .block_on(
@@ -75,7 +80,10 @@ mod tests {
             let mut shared_global = ::core::option::Option::None;
             let mut services_vec = ::std::vec::Vec::<
                 ::scuffle_bootstrap::service::NamedFuture<
-                    ::scuffle_bootstrap::prelude::tokio::task::JoinHandle<anyhow::Result<()>>,
+                    ::scuffle_bootstrap::prelude::futures::future::Join<
+                        ::std::future::Ready<::scuffle_bootstrap::service::ExitPolicy>,
+                        ::scuffle_bootstrap::prelude::tokio::task::JoinHandle<anyhow::Result<()>>,
+                    >,
                 >,
             >::new();
             let result = runtime
@@ -93,8 +101,13 @@ mod tests {
                         ) -> anyhow::Result<
                             Option<
                                 ::scuffle_bootstrap::service::NamedFuture<
-                                    ::scuffle_bootstrap::prelude::tokio::task::JoinHandle<
-                                        anyhow::Result<()>,
+                                    ::scuffle_bootstrap::prelude::futures::future::Join<
+                                        ::std::future::Ready<
+                                            ::scuffle_bootstrap::service::ExitPolicy,
+                                        >,
+                                        ::scuffle_bootstrap::prelude::tokio::task::JoinHandle<
+                                            anyhow::Result<()>,
+                                        >,
                                     >,
                                 >,
                             >,
@@ -110,14 +123,20 @@ mod tests {
                                     .await,
                                 name,
                             )? {
+                                let exit_policy = ::scuffle_bootstrap::service::Service::<
+                                    MyGlobal,
+                                >::exit_policy(&svc);
                                 Ok(
                                     Some(
                                         ::scuffle_bootstrap::service::NamedFuture::new(
                                             name,
-                                            ::scuffle_bootstrap::prelude::tokio::spawn(
-                                                ::scuffle_bootstrap::service::Service::<
-                                                    MyGlobal,
-                                                >::run(svc, global.clone(), ctx_handle.context()),
+                                            ::scuffle_bootstrap::prelude::futures::future::join(
+                                                ::std::future::ready(exit_policy),
+                                                ::scuffle_bootstrap::prelude::tokio::spawn(
+                                                    ::scuffle_bootstrap::service::Service::<
+                                                        MyGlobal,
+                                                    >::run(svc, global.clone(), ctx_handle.context()),
+                                                ),
                                             ),
                                         ),
                                     ),
@@ -136,23 +155,206 @@ mod tests {
                         .await?;
                     let mut remaining = services_vec;
                     while !remaining.is_empty() {
-                        let ((name, result), _, new_remaining) = ::scuffle_bootstrap::prelude::futures::future::select_all(
+                        let ((name, (exit_policy, result)), _, new_remaining) = ::scuffle_bootstrap::prelude::futures::future::select_all(
                                 remaining,
                             )
                             .await;
+                        let result = match result {
+                            ::core::result::Result::Ok(result) => result,
+                            ::core::result::Result::Err(err) => {
+                                ::core::result::Result::Err(
+                                    ::scuffle_bootstrap::prelude::anyhow::Error::from(err),
+                                )
+                            }
+                        };
                         let result = ::scuffle_bootstrap::prelude::anyhow::Context::context(
-                            ::scuffle_bootstrap::prelude::anyhow::Context::context(
-                                result,
-                                name,
-                            )?,
+                            result,
                             name,
                         );
-                        <MyGlobal as ::scuffle_bootstrap::global::Global>::on_service_exit(
-                                &global,
+                        match (
+                            <MyGlobal as ::scuffle_bootstrap::global::Global>::on_service_exit(
+                                    &global,
+                                    name,
+                                    result,
+                                )
+                                .await,
+                            exit_policy,
+                        ) {
+                            (
+                                ::core::result::Result::Err(_),
+                                ::scuffle_bootstrap::service::ExitPolicy::Ignore,
+                            ) => {}
+                            (result, _) => result?,
+                        }
+                        remaining = new_remaining;
+                    }
+                    ::scuffle_bootstrap::prelude::anyhow::Ok(())
+                });
+            let ::core::option::Option::Some(global) = shared_global else {
+                return result;
+            };
+            runtime
+                .block_on(
+                    <MyGlobal as ::scuffle_bootstrap::global::Global>::on_exit(&global, result),
+                )
+        }
+        "#);
+    }
+
+    #[test]
+    fn test_main_runtime_options() {
+        let input = quote::quote! {
+            #[bootstrap(current_thread, worker_threads = 4, max_blocking_threads = 16, thread_name = "worker", thread_stack_size = 1048576)]
+            MyGlobal {
+                MyService,
+            }
+        };
+
+        let output = match main_impl::impl_main(input) {
+            Ok(value) => value,
+            Err(err) => err.to_compile_error(),
+        };
+
+        let syntax_tree = prettyplease::unparse(&syn::parse_file(&output.to_string()).unwrap());
+
+        insta::assert_snapshot!(syntax_tree, @r#"
+        #[automatically_derived]
+        fn main() -> ::scuffle_bootstrap::prelude::anyhow::Result<()> {
+            #[doc(hidden)]
+            pub const fn impl_global<G: ::scuffle_bootstrap::global::Global>() {}
+            const _: () = impl_global::<MyGlobal>();
+            ::scuffle_bootstrap::prelude::anyhow::Context::context(
+                <MyGlobal as ::scuffle_bootstrap::global::Global>::pre_init(),
+                "pre_init",
+            )?;
+            ::scuffle_bootstrap::__cli! {
+                MyGlobal { MyService, }
+            }
+            let runtime = <MyGlobal as ::scuffle_bootstrap::global::Global>::tokio_runtime(
+                ::scuffle_bootstrap::global::RuntimeConfig::new()
+                    .current_thread(true)
+                    .worker_threads(4usize)
+                    .max_blocking_threads(16usize)
+                    .thread_name("worker")
+                    .thread_stack_size(1048576usize),
+            );
+            let config = ::scuffle_bootstrap::prelude::anyhow::Context::context(
+                runtime
+                    .block_on(
+                        <<MyGlobal as ::scuffle_bootstrap::global::Global>::Config as ::scuffle_bootstrap::config::ConfigParser>::parse(),
+                    ),
+                "config parse",
+            )?;
+            let ctx_handle = ::scuffle_bootstrap::prelude::scuffle_context::Handler::global();
+            let mut shared_global = ::core::option::Option::None;
+            let mut services_vec = ::std::vec::Vec::<
+                ::scuffle_bootstrap::service::NamedFuture<
+                    ::scuffle_bootstrap::prelude::futures::future::Join<
+                        ::std::future::Ready<::scuffle_bootstrap::service::ExitPolicy>,
+                        ::scuffle_bootstrap::prelude::tokio::task::JoinHandle<anyhow::Result<()>>,
+                    >,
+                >,
+            >::new();
+            let result = runtime
+                .block_on(async {
+                    let global = <MyGlobal as ::scuffle_bootstrap::global::Global>::init(config)
+                        .await?;
+                    shared_global = ::core::option::Option::Some(global.clone());
+                    {
+                        #[doc(hidden)]
+                        pub async fn spawn_service(
+                            svc: impl ::scuffle_bootstrap::service::Service<MyGlobal>,
+                            global: &::std::sync::Arc<MyGlobal>,
+                            ctx_handle: &::scuffle_bootstrap::prelude::scuffle_context::Handler,
+                            name: &'static str,
+                        ) -> anyhow::Result<
+                            Option<
+                                ::scuffle_bootstrap::service::NamedFuture<
+                                    ::scuffle_bootstrap::prelude::futures::future::Join<
+                                        ::std::future::Ready<
+                                            ::scuffle_bootstrap::service::ExitPolicy,
+                                        >,
+                                        ::scuffle_bootstrap::prelude::tokio::task::JoinHandle<
+                                            anyhow::Result<()>,
+                                        >,
+                                    >,
+                                >,
+                            >,
+                        > {
+                            let name = ::scuffle_bootstrap::service::Service::<
+                                MyGlobal,
+                            >::name(&svc)
+                                .unwrap_or_else(|| name);
+                            if ::scuffle_bootstrap::prelude::anyhow::Context::context(
+                                ::scuffle_bootstrap::service::Service::<
+                                    MyGlobal,
+                                >::enabled(&svc, &global)
+                                    .await,
                                 name,
-                                result,
+                            )? {
+                                let exit_policy = ::scuffle_bootstrap::service::Service::<
+                                    MyGlobal,
+                                >::exit_policy(&svc);
+                                Ok(
+                                    Some(
+                                        ::scuffle_bootstrap::service::NamedFuture::new(
+                                            name,
+                                            ::scuffle_bootstrap::prelude::futures::future::join(
+                                                ::std::future::ready(exit_policy),
+                                                ::scuffle_bootstrap::prelude::tokio::spawn(
+                                                    ::scuffle_bootstrap::service::Service::<
+                                                        MyGlobal,
+                                                    >::run(svc, global.clone(), ctx_handle.context()),
+                                                ),
+                                            ),
+                                        ),
+                                    ),
+                                )
+                            } else {
+                                Ok(None)
+                            }
+                        }
+                        let res = spawn_service(MyService, &global, &ctx_handle, "MyService")
+                            .await;
+                        if let Some(spawned) = res? {
+                            services_vec.push(spawned);
+                        }
+                    }
+                    <MyGlobal as ::scuffle_bootstrap::global::Global>::on_services_start(&global)
+                        .await?;
+                    let mut remaining = services_vec;
+                    while !remaining.is_empty() {
+                        let ((name, (exit_policy, result)), _, new_remaining) = ::scuffle_bootstrap::prelude::futures::future::select_all(
+                                remaining,
                             )
-                            .await?;
+                            .await;
+                        let result = match result {
+                            ::core::result::Result::Ok(result) => result,
+                            ::core::result::Result::Err(err) => {
+                                ::core::result::Result::Err(
+                                    ::scuffle_bootstrap::prelude::anyhow::Error::from(err),
+                                )
+                            }
+                        };
+                        let result = ::scuffle_bootstrap::prelude::anyhow::Context::context(
+                            result,
+                            name,
+                        );
+                        match (
+                            <MyGlobal as ::scuffle_bootstrap::global::Global>::on_service_exit(
+                                    &global,
+                                    name,
+                                    result,
+                                )
+                                .await,
+                            exit_policy,
+                        ) {
+                            (
+                                ::core::result::Result::Err(_),
+                                ::scuffle_bootstrap::service::ExitPolicy::Ignore,
+                            ) => {}
+                            (result, _) => result?,
+                        }
                         remaining = new_remaining;
                     }
                     ::scuffle_bootstrap::prelude::anyhow::Ok(())
@@ -165,6 +367,29 @@ mod tests {
                     <MyGlobal as ::scuffle_bootstrap::global::Global>::on_exit(&global, result),
                 )
         }
-        "##);
+        "#);
+    }
+
+    #[test]
+    fn test_main_runtime_options_invalid() {
+        let input = quote::quote! {
+            #[bootstrap(worker_threads = 0)]
+            MyGlobal {
+                MyService,
+            }
+        };
+
+        let err = main_impl::impl_main(input).unwrap_err();
+        assert_eq!(err.to_string(), "worker_threads must be greater than 0");
+
+        let input = quote::quote! {
+            #[bootstrap(max_blocking_threads = 0)]
+            MyGlobal {
+                MyService,
+            }
+        };
+
+        let err = main_impl::impl_main(input).unwrap_err();
+        assert_eq!(err.to_string(), "max_blocking_threads must be greater than 0");
     }
 }
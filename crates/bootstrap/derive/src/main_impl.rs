@@ -2,6 +2,7 @@
 
 use darling::FromMeta;
 use darling::ast::NestedMeta;
+use darling::util::SpannedValue;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::punctuated::Punctuated;
@@ -67,9 +68,30 @@ impl syn::parse::Parse for Item {
 #[darling(default)]
 struct ParseArgs {
     crate_path: syn::Path,
+    current_thread: darling::util::Flag,
+    worker_threads: Option<SpannedValue<usize>>,
+    max_blocking_threads: Option<SpannedValue<usize>>,
+    thread_name: Option<String>,
+    thread_stack_size: Option<usize>,
 }
 
 impl ParseArgs {
+    fn validate(self) -> syn::Result<Self> {
+        for (name, value) in [
+            ("worker_threads", &self.worker_threads),
+            ("max_blocking_threads", &self.max_blocking_threads),
+        ] {
+            match value {
+                Some(value) if **value == 0 => {
+                    return Err(syn::Error::new(value.span(), format!("{name} must be greater than 0")));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(self)
+    }
+
     fn from_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<Self> {
         let mut meta = Vec::new();
 
@@ -90,7 +112,7 @@ impl ParseArgs {
             }
         }
 
-        Ok(Self::from_list(&meta)?)
+        Self::from_list(&meta)?.validate()
     }
 }
 
@@ -98,6 +120,45 @@ impl Default for ParseArgs {
     fn default() -> Self {
         Self {
             crate_path: syn::parse_str("::scuffle_bootstrap").unwrap(),
+            current_thread: darling::util::Flag::default(),
+            worker_threads: None,
+            max_blocking_threads: None,
+            thread_name: None,
+            thread_stack_size: None,
+        }
+    }
+}
+
+impl ParseArgs {
+    /// Builds the runtime with [`Global::tokio_runtime`](scuffle_bootstrap::global::Global::tokio_runtime),
+    /// passing it a config with the options that are set.
+    fn runtime(&self, entry_as_global: &TokenStream) -> TokenStream {
+        let crate_path = &self.crate_path;
+
+        let mut options = Vec::new();
+
+        if self.current_thread.is_present() {
+            options.push(quote!(current_thread(true)));
+        }
+
+        if let Some(worker_threads) = self.worker_threads.as_deref() {
+            options.push(quote!(worker_threads(#worker_threads)));
+        }
+
+        if let Some(max_blocking_threads) = self.max_blocking_threads.as_deref() {
+            options.push(quote!(max_blocking_threads(#max_blocking_threads)));
+        }
+
+        if let Some(thread_name) = &self.thread_name {
+            options.push(quote!(thread_name(#thread_name)));
+        }
+
+        if let Some(thread_stack_size) = self.thread_stack_size {
+            options.push(quote!(thread_stack_size(#thread_stack_size)));
+        }
+
+        quote! {
+            #entry_as_global::tokio_runtime(#crate_path::global::RuntimeConfig::new()#(.#options)*)
         }
     }
 }
@@ -111,7 +172,7 @@ impl syn::parse::Parse for ParseArgs {
                 .parse_terminated(NestedMeta::parse, Token![,])?
                 .into_iter()
                 .collect::<Vec<_>>();
-            Ok(ParseArgs::from_list(&meta_list)?)
+            ParseArgs::from_list(&meta_list)?.validate()
         }
     }
 }
@@ -136,8 +197,7 @@ pub(crate) fn impl_main(input: TokenStream) -> Result<TokenStream, syn::Error> {
     let config_ident = Ident::new("config", Span::mixed_site());
     let shared_global_ident = Ident::new("shared_global", Span::mixed_site());
 
-    let handle_type =
-        quote!(#crate_path::service::NamedFuture<#crate_path::prelude::tokio::task::JoinHandle<anyhow::Result<()>>>);
+    let handle_type = quote!(#crate_path::service::NamedFuture<#crate_path::prelude::futures::future::Join<::std::future::Ready<#crate_path::service::ExitPolicy>, #crate_path::prelude::tokio::task::JoinHandle<anyhow::Result<()>>>>);
 
     let services = items.iter().filter(|item| item.item_kind == ItemKind::Service).map(|item| {
 		let expr = &item.expr;
@@ -156,12 +216,16 @@ pub(crate) fn impl_main(input: TokenStream) -> Result<TokenStream, syn::Error> {
 					global: &::std::sync::Arc<#entry>,
 					ctx_handle: &#crate_path::prelude::scuffle_context::Handler,
 					name: &'static str,
-				) -> anyhow::Result<Option<#crate_path::service::NamedFuture<#crate_path::prelude::tokio::task::JoinHandle<anyhow::Result<()>>>>> {
+				) -> anyhow::Result<Option<#handle_type>> {
 					let name = #service_type::name(&svc).unwrap_or_else(|| name);
 					if #crate_path::prelude::anyhow::Context::context(#service_type::enabled(&svc, &global).await, name)? {
+						let exit_policy = #service_type::exit_policy(&svc);
 						Ok(Some(#crate_path::service::NamedFuture::new(
 							name,
-							#crate_path::prelude::tokio::spawn(#service_type::run(svc, global.clone(), ctx_handle.context())),
+							#crate_path::prelude::futures::future::join(
+								::std::future::ready(exit_policy),
+								#crate_path::prelude::tokio::spawn(#service_type::run(svc, global.clone(), ctx_handle.context())),
+							),
 						)))
 					} else {
 						Ok(None)
@@ -181,10 +245,32 @@ pub(crate) fn impl_main(input: TokenStream) -> Result<TokenStream, syn::Error> {
         <#entry as #crate_path::global::Global>
     };
 
+    let runtime = options.runtime(&entry_as_global);
+
+    let cli_services = items.iter().filter(|item| item.item_kind == ItemKind::Service).map(|item| {
+        let expr = &item.expr;
+        let cfg_attrs = &item.cfg_attrs;
+
+        let expr = quote_spanned!(Span::mixed_site().located_at(expr.span()) => #expr);
+
+        quote! { #(#cfg_attrs)* #expr }
+    });
+
+    // Expands to nothing if the `cli` feature of the crate is disabled.
+    let cli = quote_spanned! { Span::mixed_site() =>
+        #crate_path::__cli! {
+            #entry {
+                #(#cli_services,)*
+            }
+        }
+    };
+
     let boilerplate = quote_spanned! { Span::mixed_site() =>
         #crate_path::prelude::anyhow::Context::context(#entry_as_global::pre_init(), "pre_init")?;
 
-        let #runtime_ident = #entry_as_global::tokio_runtime();
+        #cli
+
+        let #runtime_ident = #runtime;
 
         let #config_ident = #crate_path::prelude::anyhow::Context::context(
             #runtime_ident.block_on(
@@ -220,11 +306,19 @@ pub(crate) fn impl_main(input: TokenStream) -> Result<TokenStream, syn::Error> {
                 let mut remaining = #services_vec_ident;
 
                 while !remaining.is_empty() {
-                    let ((name, result), _, new_remaining) = #crate_path::prelude::futures::future::select_all(remaining).await;
-
-                    let result = #crate_path::prelude::anyhow::Context::context(#crate_path::prelude::anyhow::Context::context(result, name)?, name);
-
-                    #entry_as_global::on_service_exit(&#global_ident, name, result).await?;
+                    let ((name, (exit_policy, result)), _, new_remaining) = #crate_path::prelude::futures::future::select_all(remaining).await;
+
+                    // A panic is treated like an error returned by the service.
+                    let result = match result {
+                        ::core::result::Result::Ok(result) => result,
+                        ::core::result::Result::Err(err) => ::core::result::Result::Err(#crate_path::prelude::anyhow::Error::from(err)),
+                    };
+                    let result = #crate_path::prelude::anyhow::Context::context(result, name);
+
+                    match (#entry_as_global::on_service_exit(&#global_ident, name, result).await, exit_policy) {
+                        (::core::result::Result::Err(_), #crate_path::service::ExitPolicy::Ignore) => {}
+                        (result, _) => result?,
+                    }
 
                     remaining = new_remaining;
                 }
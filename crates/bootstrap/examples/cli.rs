@@ -9,6 +9,7 @@ scuffle_bootstrap::main! {
     Global {
         SignalSvc,
         MySvc,
+        MigrateSvc,
         svc_fn,
     }
 }
@@ -42,6 +43,30 @@ impl Service<Global> for MySvc {
     }
 }
 
+/// Only runs when the binary is started with the `migrate` subcommand.
+struct MigrateSvc;
+
+impl Service<Global> for MigrateSvc {
+    fn cli(command: clap::Command) -> clap::Command {
+        command.subcommand(
+            clap::Command::new("migrate")
+                .about("Run the migrations")
+                .arg(clap::Arg::new("dry-run").long("dry-run").action(clap::ArgAction::SetTrue)),
+        )
+    }
+
+    async fn enabled(&self, _: &Arc<Global>) -> anyhow::Result<bool> {
+        Ok(scuffle_bootstrap::cli::subcommand_name() == Some("migrate"))
+    }
+
+    async fn run(self, _: Arc<Global>, _: scuffle_context::Context) -> anyhow::Result<()> {
+        let matches = scuffle_bootstrap::cli::matches().and_then(|matches| matches.subcommand_matches("migrate"));
+        let dry_run = matches.is_some_and(|matches| matches.get_flag("dry-run"));
+        println!("migrating (dry run: {dry_run})");
+        Ok(())
+    }
+}
+
 struct Global {
     pub config: Config,
 }
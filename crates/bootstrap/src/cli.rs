@@ -0,0 +1,125 @@
+//! Command line argument parsing.
+//!
+//! When the `cli` feature is enabled, the [`main!`](crate::main) macro builds
+//! a single [`clap::Command`] for the binary and parses the command line
+//! before the config is parsed.
+//!
+//! The config and every service can contribute arguments and subcommands to
+//! this command with [`ConfigParser::cli`] and [`Service::cli`]. The parsed
+//! arguments are available from [`matches()`], for example from
+//! [`ConfigParser::parse`] or [`Service::enabled`].
+//!
+//! ```rust
+//! # use std::sync::Arc;
+//! # struct Global;
+//! /// Runs the database migrations when the `migrate` subcommand is given.
+//! struct MigrateSvc;
+//!
+//! impl scuffle_bootstrap::Service<Global> for MigrateSvc {
+//!     fn cli(command: clap::Command) -> clap::Command {
+//!         command.subcommand(clap::Command::new("migrate").about("Run the database migrations"))
+//!     }
+//!
+//!     async fn enabled(&self, _: &Arc<Global>) -> anyhow::Result<bool> {
+//!         Ok(scuffle_bootstrap::cli::subcommand_name() == Some("migrate"))
+//!     }
+//!
+//!     async fn run(self, _: Arc<Global>, _: scuffle_context::Context) -> anyhow::Result<()> {
+//!         // Run the migrations
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! [`ConfigParser::cli`]: crate::ConfigParser::cli
+//! [`ConfigParser::parse`]: crate::ConfigParser::parse
+//! [`Service::cli`]: crate::Service::cli
+//! [`Service::enabled`]: crate::Service::enabled
+
+use std::sync::OnceLock;
+
+use crate::service::Service;
+
+static MATCHES: OnceLock<clap::ArgMatches> = OnceLock::new();
+
+/// Returns the parsed command line arguments of the binary.
+///
+/// Returns `None` if the command line has not been parsed by the
+/// [`main!`](crate::main) macro.
+pub fn matches() -> Option<&'static clap::ArgMatches> {
+    MATCHES.get()
+}
+
+/// Returns the name of the subcommand given on the command line, if any.
+pub fn subcommand_name() -> Option<&'static str> {
+    matches()?.subcommand_name()
+}
+
+/// Creates the command of the binary, used by the [`main!`](crate::main) macro.
+#[doc(hidden)]
+pub fn command(name: &'static str, version: &'static str, about: &'static str, author: &'static str) -> clap::Command {
+    clap::Command::new(name)
+        .version(version)
+        .about(about)
+        .author(author)
+        .bin_name(name)
+}
+
+/// Returns the [`Service::cli`] function of the service returned by the
+/// closure, without calling the closure.
+#[doc(hidden)]
+pub fn service<G, S: Service<G>>(_: impl FnOnce() -> S) -> fn(clap::Command) -> clap::Command {
+    S::cli
+}
+
+/// Parses the command line with the given command, exiting the process on
+/// errors and for `--help` and `--version`.
+#[doc(hidden)]
+pub fn parse(command: clap::Command) {
+    parse_from(command, std::env::args_os());
+}
+
+fn parse_from<I, T>(command: clap::Command, args: I) -> &'static clap::ArgMatches
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    MATCHES.get_or_init(|| command.get_matches_from(args))
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{matches, parse_from, service, subcommand_name};
+    use crate::Service;
+
+    struct MigrateSvc;
+
+    impl Service<()> for MigrateSvc {
+        fn cli(command: clap::Command) -> clap::Command {
+            command.subcommand(clap::Command::new("migrate"))
+        }
+
+        async fn run(self, _: Arc<()>, _: scuffle_context::Context) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn merged_command() {
+        let command = super::command("app", "1.0.0", "", "");
+        let command = service::<(), _>(|| MigrateSvc)(command);
+        let command = service::<(), _>(|| |_: Arc<()>, _: scuffle_context::Context| async { Ok(()) })(command);
+        let command = command.arg(clap::Arg::new("config").long("config"));
+
+        assert!(matches().is_none());
+        assert_eq!(subcommand_name(), None);
+
+        let parsed = parse_from(command, ["app", "--config", "app.toml", "migrate"]);
+        assert_eq!(parsed.get_one::<String>("config").map(String::as_str), Some("app.toml"));
+        assert_eq!(subcommand_name(), Some("migrate"));
+        assert!(std::ptr::eq(matches().unwrap(), parsed));
+    }
+}
@@ -15,6 +15,17 @@
 pub trait ConfigParser: Sized {
     /// Parse the configuration for the application.
     fn parse() -> impl std::future::Future<Output = anyhow::Result<Self>>;
+
+    /// Add the arguments of the configuration to the command line of the
+    /// application.
+    ///
+    /// The parsed arguments are available from
+    /// [`cli::matches`](crate::cli::matches) when [`parse`](Self::parse) is
+    /// called.
+    #[cfg(feature = "cli")]
+    fn cli(command: clap::Command) -> clap::Command {
+        command
+    }
 }
 
 impl ConfigParser for () {
@@ -34,6 +45,19 @@ impl ConfigParser for EmptyConfig {
     }
 }
 
+/// The parsed command line arguments can be used as the configuration
+/// directly.
+#[cfg(feature = "cli")]
+impl ConfigParser for clap::ArgMatches {
+    fn parse() -> impl std::future::Future<Output = anyhow::Result<Self>> {
+        std::future::ready(
+            crate::cli::matches()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("command line arguments have not been parsed")),
+        )
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
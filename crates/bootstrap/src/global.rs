@@ -12,71 +12,194 @@ use std::sync::Arc;
 
 use crate::config::{ConfigParser, EmptyConfig};
 
-fn default_runtime_builder() -> tokio::runtime::Builder {
-    let worker_threads = std::env::var("TOKIO_WORKER_THREADS")
-        .unwrap_or_default()
-        .parse::<usize>()
-        .ok()
-        .or_else(|| std::thread::available_parallelism().ok().map(|p| p.get()));
-
-    let mut builder = if let Some(1) = worker_threads {
-        tokio::runtime::Builder::new_current_thread()
-    } else {
-        tokio::runtime::Builder::new_multi_thread()
-    };
-
-    if let Some(worker_threads) = worker_threads {
-        builder.worker_threads(worker_threads);
-    }
+/// Configuration for the tokio runtime of the process.
+///
+/// Options which are not set fall back to the environment variables described
+/// in [`Global::tokio_runtime`] and then to tokio's defaults. The
+/// [`main!`](crate::main) macro passes a config with the runtime options of its
+/// `#[bootstrap(...)]` attribute to [`Global::tokio_runtime`].
+///
+/// ```rust
+/// # use scuffle_bootstrap::global::RuntimeConfig;
+/// let runtime = RuntimeConfig::new()
+///     .worker_threads(4)
+///     .thread_name("my-app-worker")
+///     .build()
+///     .unwrap();
+/// # drop(runtime);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[must_use = "runtime configs must be used to build a runtime"]
+pub struct RuntimeConfig {
+    current_thread: bool,
+    worker_threads: Option<usize>,
+    max_blocking_threads: Option<usize>,
+    thread_name: Option<String>,
+    thread_stack_size: Option<usize>,
+}
 
-    if let Ok(max_blocking_threads) = std::env::var("TOKIO_MAX_BLOCKING_THREADS")
-        .unwrap_or_default()
-        .parse::<usize>()
-    {
-        builder.max_blocking_threads(max_blocking_threads);
+impl RuntimeConfig {
+    /// Create a new config without any options set.
+    pub const fn new() -> Self {
+        Self {
+            current_thread: false,
+            worker_threads: None,
+            max_blocking_threads: None,
+            thread_name: None,
+            thread_stack_size: None,
+        }
     }
 
-    if !std::env::var("TOKIO_DISABLE_TIME")
-        .unwrap_or_default()
-        .parse::<bool>()
-        .ok()
-        .unwrap_or(false)
-    {
-        builder.enable_time();
+    /// Use a current thread runtime instead of a multi thread runtime.
+    ///
+    /// This is useful for tests. See [`tokio::runtime::Builder::new_current_thread`] for details.
+    pub const fn current_thread(mut self, current_thread: bool) -> Self {
+        self.current_thread = current_thread;
+        self
     }
 
-    if !std::env::var("TOKIO_DISABLE_IO")
-        .unwrap_or_default()
-        .parse::<bool>()
-        .ok()
-        .unwrap_or(false)
-    {
-        builder.enable_io();
+    /// Set the number of worker threads. If 1, a current thread runtime is used.
+    /// Must not be 0.
+    ///
+    /// Takes precedence over `TOKIO_WORKER_THREADS`.
+    /// See [`tokio::runtime::Builder::worker_threads`] for details.
+    pub const fn worker_threads(mut self, worker_threads: usize) -> Self {
+        self.worker_threads = Some(worker_threads);
+        self
     }
 
-    if let Ok(thread_stack_size) = std::env::var("TOKIO_THREAD_STACK_SIZE").unwrap_or_default().parse::<usize>() {
-        builder.thread_stack_size(thread_stack_size);
+    /// Set the maximum number of blocking threads. Must not be 0.
+    ///
+    /// Takes precedence over `TOKIO_MAX_BLOCKING_THREADS`.
+    /// See [`tokio::runtime::Builder::max_blocking_threads`] for details.
+    pub const fn max_blocking_threads(mut self, max_blocking_threads: usize) -> Self {
+        self.max_blocking_threads = Some(max_blocking_threads);
+        self
     }
 
-    if let Ok(global_queue_interval) = std::env::var("TOKIO_GLOBAL_QUEUE_INTERVAL")
-        .unwrap_or_default()
-        .parse::<u32>()
-    {
-        builder.global_queue_interval(global_queue_interval);
+    /// Set the name of the threads spawned by the runtime.
+    ///
+    /// See [`tokio::runtime::Builder::thread_name`] for details.
+    pub fn thread_name(mut self, thread_name: impl Into<String>) -> Self {
+        self.thread_name = Some(thread_name.into());
+        self
     }
 
-    if let Ok(event_interval) = std::env::var("TOKIO_EVENT_INTERVAL").unwrap_or_default().parse::<u32>() {
-        builder.event_interval(event_interval);
+    /// Set the stack size of the threads spawned by the runtime.
+    ///
+    /// Takes precedence over `TOKIO_THREAD_STACK_SIZE`.
+    /// See [`tokio::runtime::Builder::thread_stack_size`] for details.
+    pub const fn thread_stack_size(mut self, thread_stack_size: usize) -> Self {
+        self.thread_stack_size = Some(thread_stack_size);
+        self
     }
 
-    if let Ok(max_io_events_per_tick) = std::env::var("TOKIO_MAX_IO_EVENTS_PER_TICK")
-        .unwrap_or_default()
-        .parse::<usize>()
-    {
-        builder.max_io_events_per_tick(max_io_events_per_tick);
+    /// Create a [`tokio::runtime::Builder`] from the config.
+    ///
+    /// Returns an [`InvalidInput`](std::io::ErrorKind::InvalidInput) error if
+    /// the number of worker threads or maximum number of blocking threads is 0.
+    pub fn builder(&self) -> std::io::Result<tokio::runtime::Builder> {
+        let worker_threads = self
+            .worker_threads
+            .or_else(|| {
+                std::env::var("TOKIO_WORKER_THREADS")
+                    .unwrap_or_default()
+                    .parse::<usize>()
+                    .ok()
+            })
+            .or_else(|| std::thread::available_parallelism().ok().map(|p| p.get()));
+
+        if worker_threads == Some(0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "worker_threads must be greater than 0",
+            ));
+        }
+
+        let mut builder = if self.current_thread || worker_threads == Some(1) {
+            tokio::runtime::Builder::new_current_thread()
+        } else {
+            tokio::runtime::Builder::new_multi_thread()
+        };
+
+        if let Some(worker_threads) = worker_threads {
+            builder.worker_threads(worker_threads);
+        }
+
+        if let Some(max_blocking_threads) = self.max_blocking_threads.or_else(|| {
+            std::env::var("TOKIO_MAX_BLOCKING_THREADS")
+                .unwrap_or_default()
+                .parse::<usize>()
+                .ok()
+        }) {
+            if max_blocking_threads == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "max_blocking_threads must be greater than 0",
+                ));
+            }
+
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+
+        if !std::env::var("TOKIO_DISABLE_TIME")
+            .unwrap_or_default()
+            .parse::<bool>()
+            .ok()
+            .unwrap_or(false)
+        {
+            builder.enable_time();
+        }
+
+        if !std::env::var("TOKIO_DISABLE_IO")
+            .unwrap_or_default()
+            .parse::<bool>()
+            .ok()
+            .unwrap_or(false)
+        {
+            builder.enable_io();
+        }
+
+        if let Some(thread_name) = &self.thread_name {
+            builder.thread_name(thread_name);
+        }
+
+        if let Some(thread_stack_size) = self.thread_stack_size.or_else(|| {
+            std::env::var("TOKIO_THREAD_STACK_SIZE")
+                .unwrap_or_default()
+                .parse::<usize>()
+                .ok()
+        }) {
+            builder.thread_stack_size(thread_stack_size);
+        }
+
+        if let Ok(global_queue_interval) = std::env::var("TOKIO_GLOBAL_QUEUE_INTERVAL")
+            .unwrap_or_default()
+            .parse::<u32>()
+        {
+            builder.global_queue_interval(global_queue_interval);
+        }
+
+        if let Ok(event_interval) = std::env::var("TOKIO_EVENT_INTERVAL").unwrap_or_default().parse::<u32>() {
+            builder.event_interval(event_interval);
+        }
+
+        if let Ok(max_io_events_per_tick) = std::env::var("TOKIO_MAX_IO_EVENTS_PER_TICK")
+            .unwrap_or_default()
+            .parse::<usize>()
+        {
+            builder.max_io_events_per_tick(max_io_events_per_tick);
+        }
+
+        Ok(builder)
     }
 
-    builder
+    /// Build the runtime.
+    ///
+    /// See [`RuntimeConfig::builder`] for the errors returned for invalid options.
+    pub fn build(&self) -> std::io::Result<tokio::runtime::Runtime> {
+        self.builder()?.build()
+    }
 }
 
 /// This trait is implemented for the global type of your application.
@@ -138,9 +261,14 @@ pub trait Global: Send + Sync + 'static {
     /// - `TOKIO_MAX_IO_EVENTS_PER_TICK`: Maximum IO events per tick.
     ///
     ///   See [`tokio::runtime::Builder::max_io_events_per_tick`] for details.
+    ///
+    /// Options set on `config` take precedence over the environment variables.
+    /// The [`main!`](crate::main) macro passes the runtime options of its
+    /// `#[bootstrap(...)]` attribute, or an empty [`RuntimeConfig`] if there
+    /// are none.
     #[inline(always)]
-    fn tokio_runtime() -> tokio::runtime::Runtime {
-        default_runtime_builder().build().expect("runtime build")
+    fn tokio_runtime(config: RuntimeConfig) -> tokio::runtime::Runtime {
+        config.build().expect("runtime build")
     }
 
     /// Initialize the global.
@@ -163,9 +291,11 @@ pub trait Global: Send + Sync + 'static {
     /// Called after a service exits.
     ///
     /// `name` is the name of the service that exited and `result` is the result
-    /// the service exited with. Returning an error from this function will
-    /// stop all currently running services and [`on_exit`](Global::on_exit)
-    /// will be called with the result of this function.
+    /// the service exited with, a panic is reported as an error. Returning an
+    /// error from this function will stop all currently running services and
+    /// [`on_exit`](Global::on_exit) will be called with the result of this
+    /// function, unless the [`ExitPolicy`](crate::service::ExitPolicy) of the
+    /// service is [`Ignore`](crate::service::ExitPolicy::Ignore).
     #[inline(always)]
     fn on_service_exit(
         self: &Arc<Self>,
@@ -227,9 +357,14 @@ pub trait GlobalWithoutConfig: Send + Sync + 'static {
     /// - `TOKIO_MAX_IO_EVENTS_PER_TICK`: Maximum IO events per tick.
     ///
     ///   See [`tokio::runtime::Builder::max_io_events_per_tick`] for details.
+    ///
+    /// Options set on `config` take precedence over the environment variables.
+    /// The [`main!`](crate::main) macro passes the runtime options of its
+    /// `#[bootstrap(...)]` attribute, or an empty [`RuntimeConfig`] if there
+    /// are none.
     #[inline(always)]
-    fn tokio_runtime() -> tokio::runtime::Runtime {
-        default_runtime_builder().build().expect("runtime build")
+    fn tokio_runtime(config: RuntimeConfig) -> tokio::runtime::Runtime {
+        config.build().expect("runtime build")
     }
 
     /// Initialize the global.
@@ -252,9 +387,11 @@ pub trait GlobalWithoutConfig: Send + Sync + 'static {
     /// Called after a service exits.
     ///
     /// `name` is the name of the service that exited and `result` is the result
-    /// the service exited with. Returning an error from this function will
-    /// stop all currently running services and [`on_exit`](Global::on_exit)
-    /// will be called with the result of this function.
+    /// the service exited with, a panic is reported as an error. Returning an
+    /// error from this function will stop all currently running services and
+    /// [`on_exit`](Global::on_exit) will be called with the result of this
+    /// function, unless the [`ExitPolicy`](crate::service::ExitPolicy) of the
+    /// service is [`Ignore`](crate::service::ExitPolicy::Ignore).
     #[inline(always)]
     fn on_service_exit(
         self: &Arc<Self>,
@@ -283,8 +420,8 @@ impl<T: GlobalWithoutConfig> Global for T {
     type Config = EmptyConfig;
 
     #[inline(always)]
-    fn tokio_runtime() -> tokio::runtime::Runtime {
-        <T as GlobalWithoutConfig>::tokio_runtime()
+    fn tokio_runtime(config: RuntimeConfig) -> tokio::runtime::Runtime {
+        <T as GlobalWithoutConfig>::tokio_runtime(config)
     }
 
     #[inline(always)]
@@ -321,7 +458,7 @@ mod tests {
     use std::sync::Arc;
     use std::thread;
 
-    use super::{Global, GlobalWithoutConfig};
+    use super::{Global, GlobalWithoutConfig, RuntimeConfig};
     use crate::EmptyConfig;
 
     struct TestGlobal;
@@ -338,7 +475,7 @@ mod tests {
     async fn default_global() {
         thread::spawn(|| {
             // To get the coverage
-            TestGlobal::tokio_runtime();
+            TestGlobal::tokio_runtime(RuntimeConfig::new());
         });
 
         assert!(matches!(TestGlobal::pre_init(), Ok(())));
@@ -364,7 +501,7 @@ mod tests {
     async fn default_global_no_config() {
         thread::spawn(|| {
             // To get the coverage
-            <TestGlobalWithoutConfig as Global>::tokio_runtime();
+            <TestGlobalWithoutConfig as Global>::tokio_runtime(RuntimeConfig::new());
         });
 
         assert!(matches!(TestGlobalWithoutConfig::pre_init(), Ok(())));
@@ -382,4 +519,51 @@ mod tests {
                 .is_err()
         );
     }
+
+    #[test]
+    fn runtime_config() {
+        let runtime = RuntimeConfig::new()
+            .worker_threads(2)
+            .max_blocking_threads(4)
+            .thread_name("test-worker")
+            .thread_stack_size(4 * 1024 * 1024)
+            .build()
+            .unwrap();
+
+        assert_eq!(runtime.handle().runtime_flavor(), tokio::runtime::RuntimeFlavor::MultiThread);
+        assert_eq!(runtime.metrics().num_workers(), 2);
+
+        let name = runtime.block_on(async {
+            tokio::spawn(async { std::thread::current().name().map(str::to_owned) })
+                .await
+                .unwrap()
+        });
+        assert_eq!(name.as_deref(), Some("test-worker"));
+    }
+
+    #[test]
+    fn runtime_config_current_thread() {
+        let runtime = RuntimeConfig::new().current_thread(true).worker_threads(4).build().unwrap();
+        assert_eq!(
+            runtime.handle().runtime_flavor(),
+            tokio::runtime::RuntimeFlavor::CurrentThread
+        );
+
+        let runtime = RuntimeConfig::new().worker_threads(1).build().unwrap();
+        assert_eq!(
+            runtime.handle().runtime_flavor(),
+            tokio::runtime::RuntimeFlavor::CurrentThread
+        );
+    }
+
+    #[test]
+    fn runtime_config_invalid() {
+        let err = RuntimeConfig::new().worker_threads(0).build().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "worker_threads must be greater than 0");
+
+        let err = RuntimeConfig::new().max_blocking_threads(0).build().unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "max_blocking_threads must be greater than 0");
+    }
 }
@@ -61,6 +61,8 @@
 #![deny(unsafe_code)]
 #![deny(unreachable_pub)]
 
+#[cfg(feature = "cli")]
+pub mod cli;
 pub mod config;
 pub mod global;
 pub mod service;
@@ -111,6 +113,56 @@ pub mod prelude {
 /// # }
 /// ```
 ///
+/// # Options
+///
+/// The runtime of the generated main function can be configured with a
+/// `#[bootstrap(...)]` attribute before the global type:
+///
+/// - `worker_threads = 4`: Number of worker threads.
+/// - `max_blocking_threads = 512`: Maximum number of blocking threads.
+/// - `thread_name = "my-app-worker"`: Name of the runtime threads.
+/// - `thread_stack_size = 2097152`: Stack size of the runtime threads.
+/// - `current_thread`: Use a current thread runtime, useful for tests.
+///
+/// The options are passed to [`Global::tokio_runtime`] as a
+/// [`RuntimeConfig`](global::RuntimeConfig) and take precedence over the
+/// environment variables read by its default implementation. A zero
+/// `worker_threads` or `max_blocking_threads` is rejected at compile time.
+///
+/// ```rust
+/// # #[cfg(not(windows))]
+/// # {
+/// # use std::sync::Arc;
+/// # struct MyGlobal;
+/// # struct MyService;
+/// # impl scuffle_bootstrap::global::GlobalWithoutConfig for MyGlobal {
+/// #     async fn init() -> anyhow::Result<Arc<Self>> {
+/// #         Ok(Arc::new(Self))
+/// #     }
+/// # }
+/// # impl scuffle_bootstrap::service::Service<MyGlobal> for MyService {
+/// #     async fn run(self, global: Arc<MyGlobal>, ctx: scuffle_context::Context) -> anyhow::Result<()> {
+/// #         println!("running");
+/// #         ctx.done().await;
+/// #         Ok(())
+/// #     }
+/// # }
+/// scuffle_bootstrap::main! {
+///     #[bootstrap(worker_threads = 4, thread_name = "my-app-worker")]
+///     MyGlobal {
+///         MyService,
+///     }
+/// }
+/// # }
+/// ```
+///
+/// # Command line
+///
+/// With the `cli` feature, the generated main function parses the command
+/// line before parsing the config. The command is named after the binary and
+/// merges the arguments and subcommands contributed by the config and all
+/// services, see the `cli` module for details.
+///
 /// # See Also
 ///
 /// - [`Service`]
@@ -123,6 +175,40 @@ macro_rules! main {
     };
 }
 
+/// Parses the command line in the main function generated by [`main!`].
+#[doc(hidden)]
+#[cfg(feature = "cli")]
+#[macro_export]
+macro_rules! __cli {
+    ($global:ty { $($(#[$attr:meta])* $service:expr,)* }) => {{
+        let mut command = $crate::cli::command(
+            ::core::option_env!("CARGO_BIN_NAME").unwrap_or(::core::env!("CARGO_PKG_NAME")),
+            ::core::env!("CARGO_PKG_VERSION"),
+            ::core::env!("CARGO_PKG_DESCRIPTION"),
+            ::core::env!("CARGO_PKG_AUTHORS"),
+        );
+
+        command = <<$global as $crate::global::Global>::Config as $crate::config::ConfigParser>::cli(command);
+
+        $(
+            $(#[$attr])*
+            {
+                command = $crate::cli::service::<$global, _>(|| $service)(command);
+            }
+        )*
+
+        $crate::cli::parse(command);
+    }};
+}
+
+/// Parses the command line in the main function generated by [`main!`].
+#[doc(hidden)]
+#[cfg(not(feature = "cli"))]
+#[macro_export]
+macro_rules! __cli {
+    ($($tt:tt)*) => {};
+}
+
 /// Changelogs generated by [scuffle_changelog]
 #[cfg(feature = "docs")]
 #[scuffle_changelog::changelog]
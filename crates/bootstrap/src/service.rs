@@ -3,6 +3,22 @@
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+/// What happens when a service exits with an error or panics.
+///
+/// The policy is applied after [`Global::on_service_exit`](crate::Global::on_service_exit)
+/// has been called, to the result returned by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExitPolicy {
+    /// Stop all other services and exit the process.
+    #[default]
+    Shutdown,
+    /// Keep the other services running.
+    ///
+    /// Useful for optional background jobs which should not take down the process.
+    Ignore,
+}
 
 /// A service that can be run.
 ///
@@ -27,6 +43,14 @@ pub trait Service<Global>: Send + Sync + 'static + Sized {
         std::future::ready(Ok(true))
     }
 
+    /// Returns what should happen when the service exits with an error or
+    /// panics.
+    ///
+    /// To restart the service instead, wrap it in [`Restart`].
+    fn exit_policy(&self) -> ExitPolicy {
+        ExitPolicy::Shutdown
+    }
+
     /// Run the service.
     /// This function should return a future that is pending as long as the
     /// service is running. When the service finishes without any errors,
@@ -46,6 +70,17 @@ pub trait Service<Global>: Send + Sync + 'static + Sized {
             Ok(())
         }
     }
+
+    /// Add the arguments and subcommands of the service to the command line
+    /// of the application.
+    ///
+    /// The parsed arguments are available from
+    /// [`cli::matches`](crate::cli::matches), for example in
+    /// [`enabled`](Self::enabled).
+    #[cfg(feature = "cli")]
+    fn cli(command: clap::Command) -> clap::Command {
+        command
+    }
 }
 
 impl<G, F, Fut> Service<G> for F
@@ -62,6 +97,133 @@ where
     }
 }
 
+/// A service which is restarted with an exponential backoff when it exits
+/// with an error or panics.
+///
+/// Every restart runs a fresh clone of the inner service. The backoff starts
+/// at [`initial_backoff`](Self::initial_backoff) and doubles after every
+/// restart up to [`max_backoff`](Self::max_backoff). A run lasting at least
+/// the maximum backoff is considered healthy and resets the backoff and the
+/// restart count.
+///
+/// Once [`max_restarts`](Self::max_restarts) consecutive restarts have failed,
+/// the last error is returned and the [`ExitPolicy`] of the inner service
+/// applies. The service is not restarted when it exits successfully or the
+/// context is done.
+///
+/// ```rust
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # use scuffle_bootstrap::service::Restart;
+/// # struct Global;
+/// let svc = Restart::new(|_: Arc<Global>, _: scuffle_context::Context| async { anyhow::Ok(()) })
+///     .initial_backoff(Duration::from_millis(100))
+///     .max_restarts(5);
+/// # drop(svc);
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "services must be run"]
+pub struct Restart<S> {
+    service: S,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: Option<usize>,
+}
+
+impl<S> Restart<S> {
+    /// Wrap the given service.
+    ///
+    /// By default the backoff starts at 1 second, is capped at 1 minute and
+    /// the service is restarted indefinitely.
+    pub const fn new(service: S) -> Self {
+        Self {
+            service,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            max_restarts: None,
+        }
+    }
+
+    /// Set the time to wait before the first restart.
+    pub const fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Set the maximum time to wait between restarts.
+    pub const fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Set the maximum number of consecutive restarts.
+    pub const fn max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+}
+
+impl<G, S> Service<G> for Restart<S>
+where
+    G: Send + Sync + 'static,
+    S: Service<G> + Clone,
+{
+    fn name(&self) -> Option<&'static str> {
+        self.service.name()
+    }
+
+    fn enabled(&self, global: &Arc<G>) -> impl std::future::Future<Output = anyhow::Result<bool>> + Send {
+        self.service.enabled(global)
+    }
+
+    fn exit_policy(&self) -> ExitPolicy {
+        self.service.exit_policy()
+    }
+
+    #[cfg(feature = "cli")]
+    fn cli(command: clap::Command) -> clap::Command {
+        S::cli(command)
+    }
+
+    async fn run(self, global: Arc<G>, ctx: scuffle_context::Context) -> anyhow::Result<()> {
+        let mut restarts = 0;
+        let mut backoff = self.initial_backoff;
+
+        loop {
+            let started = tokio::time::Instant::now();
+
+            // Run every attempt in its own task so that panics can be caught.
+            let err = match tokio::spawn(self.service.clone().run(global.clone(), ctx.clone())).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(err)) => err,
+                Err(err) => err.into(),
+            };
+
+            if ctx.is_done() {
+                return Err(err);
+            }
+
+            if started.elapsed() >= self.max_backoff {
+                restarts = 0;
+                backoff = self.initial_backoff;
+            }
+
+            if self.max_restarts.is_some_and(|max_restarts| restarts >= max_restarts) {
+                return Err(err.context(format!("service failed after {restarts} restarts")));
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                // Shutting down while waiting, the service is not running anymore.
+                _ = ctx.done() => return Ok(()),
+            }
+
+            restarts += 1;
+            backoff = backoff.saturating_mul(2).min(self.max_backoff);
+        }
+    }
+}
+
 pin_project_lite::pin_project! {
     /// A future that can be named yielding the name and the result of the inner future.
     #[must_use = "futures do nothing unless polled"]
@@ -96,10 +258,12 @@ where
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
     use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
 
     use scuffle_future_ext::FutureExt;
 
-    use super::{NamedFuture, Service};
+    use super::{ExitPolicy, NamedFuture, Restart, Service};
 
     struct DefaultService;
 
@@ -112,6 +276,7 @@ mod tests {
         let (ctx, handler) = scuffle_context::Context::new();
 
         assert_eq!(svc.name(), None);
+        assert_eq!(svc.exit_policy(), ExitPolicy::Shutdown);
         assert!(svc.enabled(&global).await.unwrap());
 
         handler.cancel();
@@ -150,4 +315,112 @@ mod tests {
         let named_fut = NamedFuture::new("test", async { 42 });
         assert_eq!(named_fut.await, ("test", 42));
     }
+
+    #[derive(Clone)]
+    struct FlakyService {
+        runs: Arc<AtomicUsize>,
+        fail_until: usize,
+        panic: bool,
+    }
+
+    impl Service<()> for FlakyService {
+        fn name(&self) -> Option<&'static str> {
+            Some("flaky")
+        }
+
+        fn exit_policy(&self) -> ExitPolicy {
+            ExitPolicy::Ignore
+        }
+
+        async fn run(self, _: Arc<()>, _: scuffle_context::Context) -> anyhow::Result<()> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst) + 1;
+            if run > self.fail_until {
+                Ok(())
+            } else if self.panic {
+                panic!("run {run} panicked");
+            } else {
+                anyhow::bail!("run {run} failed")
+            }
+        }
+    }
+
+    fn flaky(fail_until: usize, panic: bool) -> (Arc<AtomicUsize>, Restart<FlakyService>) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let svc = Restart::new(FlakyService {
+            runs: runs.clone(),
+            fail_until,
+            panic,
+        })
+        .initial_backoff(Duration::from_millis(1))
+        .max_backoff(Duration::from_millis(4));
+
+        (runs, svc)
+    }
+
+    #[tokio::test]
+    async fn restart_delegates() {
+        let (_, svc) = flaky(0, false);
+
+        assert_eq!(svc.name(), Some("flaky"));
+        assert_eq!(svc.exit_policy(), ExitPolicy::Ignore);
+        assert!(svc.enabled(&Arc::new(())).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn restart_until_success() {
+        let (ctx, _handler) = scuffle_context::Context::new();
+
+        let (runs, svc) = flaky(3, false);
+        assert!(svc.run(Arc::new(()), ctx.clone()).await.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+
+        let (runs, svc) = flaky(3, true);
+        assert!(svc.run(Arc::new(()), ctx).await.is_ok());
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn restart_max_restarts() {
+        let (ctx, _handler) = scuffle_context::Context::new();
+
+        let (runs, svc) = flaky(usize::MAX, false);
+        let err = svc.max_restarts(2).run(Arc::new(()), ctx.clone()).await.unwrap_err();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(format!("{err:#}"), "service failed after 2 restarts: run 3 failed");
+
+        let (runs, svc) = flaky(usize::MAX, true);
+        let err = svc.max_restarts(0).run(Arc::new(()), ctx).await.unwrap_err();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(format!("{err:#}").contains("panicked"));
+    }
+
+    #[tokio::test]
+    async fn restart_stops_on_shutdown() {
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let (runs, svc) = flaky(usize::MAX, false);
+        let svc = svc
+            .initial_backoff(Duration::from_secs(60))
+            .max_backoff(Duration::from_secs(60));
+        let run = tokio::spawn(svc.run(Arc::new(()), ctx));
+
+        while runs.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        handler.cancel();
+        assert!(
+            run.with_timeout(Duration::from_millis(200))
+                .await
+                .expect("restart should stop waiting on shutdown")
+                .unwrap()
+                .is_ok()
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // A service failing after the context is done is not restarted.
+        let (runs, svc) = flaky(usize::MAX, false);
+        assert!(svc.run(Arc::new(()), handler.context()).await.is_err());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
 }
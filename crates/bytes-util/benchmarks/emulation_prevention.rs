@@ -0,0 +1,64 @@
+use std::hint::black_box;
+use std::io::{Read, Write};
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use scuffle_bytes_util::EmulationPreventionIo;
+
+/// The size of the NAL unit payloads, roughly a high bitrate 1080p key frame.
+const NALU_SIZE: usize = 1024 * 1024;
+
+/// A pseudo random RBSP where roughly one in 64 bytes needs an emulation prevention byte.
+fn rbsp() -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    (0..NALU_SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            match state % 128 {
+                0..=31 => 0x00,
+                value => value as u8,
+            }
+        })
+        .collect()
+}
+
+fn insert(c: &mut Criterion) {
+    let rbsp = rbsp();
+
+    let mut group = c.benchmark_group("insert");
+    group.throughput(Throughput::Bytes(rbsp.len() as u64));
+
+    group.bench_with_input(BenchmarkId::new("write_all", NALU_SIZE), &rbsp, |b, rbsp| {
+        b.iter(|| {
+            let mut ebsp = Vec::with_capacity(rbsp.len());
+            EmulationPreventionIo::new(&mut ebsp).write_all(black_box(rbsp)).unwrap();
+            black_box(ebsp);
+        });
+    });
+
+    group.finish();
+}
+
+fn remove(c: &mut Criterion) {
+    let mut ebsp = Vec::new();
+    EmulationPreventionIo::new(&mut ebsp).write_all(&rbsp()).unwrap();
+
+    let mut group = c.benchmark_group("remove");
+    group.throughput(Throughput::Bytes(ebsp.len() as u64));
+
+    group.bench_with_input(BenchmarkId::new("read_to_end", NALU_SIZE), &ebsp, |b, ebsp| {
+        b.iter(|| {
+            let mut rbsp = Vec::with_capacity(ebsp.len());
+            EmulationPreventionIo::new(black_box(&ebsp[..]))
+                .read_to_end(&mut rbsp)
+                .unwrap();
+            black_box(rbsp);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, insert, remove);
+criterion_main!(benches);
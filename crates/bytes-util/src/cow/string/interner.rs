@@ -0,0 +1,143 @@
+use super::StringCow;
+
+/// A set of well-known strings that [`StringCow`] values can be resolved to.
+///
+/// Decoders often see the same small set of strings (e.g. object keys) over and over again.
+/// Looking up the raw bytes in the interner before creating a [`StringCow`] allows them
+/// to return a [`StringCow::StaticRef`] to the shared static string instead of allocating
+/// (and validating) a new string every time.
+///
+/// ```rust
+/// # use scuffle_bytes_util::{StringCow, StringInterner};
+/// let interner = StringInterner::from_iter(["app", "tcUrl"]);
+///
+/// assert_eq!(interner.get(b"app"), Some("app"));
+/// assert_eq!(interner.get(b"flashVer"), None);
+///
+/// let cow = interner.intern(StringCow::from_string("tcUrl".to_string()));
+/// assert!(matches!(cow, StringCow::StaticRef("tcUrl")));
+/// ```
+///
+/// Strings are bucketed by their length, so a lookup is an index followed by a comparison
+/// against the few strings of the same length. This is intended for small sets of short
+/// strings and is considerably cheaper than hashing the input.
+#[derive(Debug, Clone, Default)]
+pub struct StringInterner {
+    buckets: Vec<Vec<&'static str>>,
+    len: usize,
+}
+
+impl StringInterner {
+    /// Creates an empty [`StringInterner`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a string to the interner.
+    ///
+    /// Returns `false` if the string was already present.
+    pub fn insert(&mut self, string: &'static str) -> bool {
+        if self.contains(string) {
+            return false;
+        }
+
+        if self.buckets.len() <= string.len() {
+            self.buckets.resize_with(string.len() + 1, Vec::new);
+        }
+
+        self.buckets[string.len()].push(string);
+        self.len += 1;
+        true
+    }
+
+    /// Returns the number of strings in the interner.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the interner contains no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the interner contains the given string.
+    pub fn contains(&self, string: &str) -> bool {
+        self.get(string.as_bytes()).is_some()
+    }
+
+    /// Looks up the static string matching the given bytes.
+    ///
+    /// No UTF-8 validation is needed because only valid strings can be inserted.
+    pub fn get(&self, bytes: &[u8]) -> Option<&'static str> {
+        self.buckets
+            .get(bytes.len())?
+            .iter()
+            .find(|string| string.as_bytes() == bytes)
+            .copied()
+    }
+
+    /// Resolves the given [`StringCow`] to the shared static string if it is known,
+    /// otherwise it is returned unchanged.
+    pub fn intern<'a>(&self, cow: StringCow<'a>) -> StringCow<'a> {
+        match self.get(cow.as_str().as_bytes()) {
+            Some(string) => StringCow::from_static(string),
+            None => cow,
+        }
+    }
+}
+
+impl FromIterator<&'static str> for StringInterner {
+    fn from_iter<T: IntoIterator<Item = &'static str>>(iter: T) -> Self {
+        let mut interner = Self::new();
+        interner.extend(iter);
+        interner
+    }
+}
+
+impl Extend<&'static str> for StringInterner {
+    fn extend<T: IntoIterator<Item = &'static str>>(&mut self, iter: T) {
+        for string in iter {
+            self.insert(string);
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use bytestring::ByteString;
+
+    use super::StringInterner;
+    use crate::StringCow;
+
+    #[test]
+    fn insert_and_get() {
+        let mut interner = StringInterner::new();
+        assert!(interner.is_empty());
+
+        assert!(interner.insert("app"));
+        assert!(!interner.insert("app"));
+        assert!(interner.insert("tcUrl"));
+
+        assert_eq!(interner.len(), 2);
+        assert!(interner.contains("app"));
+        assert!(!interner.contains("flashVer"));
+        assert_eq!(interner.get(b"tcUrl"), Some("tcUrl"));
+        assert_eq!(interner.get(b"tcurl"), None);
+        assert_eq!(interner.get(&[0xff]), None);
+    }
+
+    #[test]
+    fn intern() {
+        let interner: StringInterner = ["app", "tcUrl"].into_iter().collect();
+
+        let cow = interner.intern(StringCow::from_bytes(ByteString::from_static("app")));
+        assert!(matches!(cow, StringCow::StaticRef("app")));
+
+        let cow = interner.intern(StringCow::from_ref("tcUrl"));
+        assert!(matches!(cow, StringCow::StaticRef("tcUrl")));
+
+        let cow = interner.intern(StringCow::from_string("flashVer".to_string()));
+        assert!(matches!(cow, StringCow::String(ref s) if s == "flashVer"));
+    }
+}
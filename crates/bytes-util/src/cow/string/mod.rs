@@ -4,9 +4,12 @@ use std::hash::Hash;
 
 use bytestring::ByteString;
 
+mod interner;
 #[cfg(feature = "serde")]
 pub(crate) mod serde;
 
+pub use interner::StringInterner;
+
 /// A [`Cow`] type for strings.
 #[derive(Debug, Clone, Eq)]
 pub enum StringCow<'a> {
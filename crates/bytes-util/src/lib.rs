@@ -20,16 +20,18 @@ mod bytes_cursor;
 mod cow;
 mod nal_emulation_prevention;
 pub mod range_check;
+mod string_reader;
 pub mod zero_copy;
 
 pub use bit_read::BitReader;
 pub use bit_write::BitWriter;
 pub use bytes_cursor::{BytesCursor, BytesCursorExt};
 pub use cow::bytes::BytesCow;
-pub use cow::string::StringCow;
 #[cfg(feature = "serde")]
 pub use cow::string::serde::StringCowDeserializer;
+pub use cow::string::{StringCow, StringInterner};
 pub use nal_emulation_prevention::EmulationPreventionIo;
+pub use string_reader::{StringEncoding, StringReader};
 
 /// Changelogs generated by [scuffle_changelog]
 #[cfg(feature = "docs")]
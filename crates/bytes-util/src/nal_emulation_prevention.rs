@@ -11,29 +11,32 @@ pub struct EmulationPreventionIo<I> {
 
 impl<I> EmulationPreventionIo<I> {
     /// Creates a new `EmulationPrevention` wrapper around the given [`std::io::Read`] or [`std::io::Write`].
-    /// This should be a buffered reader or writer because we will only read or write one byte at a time.
-    /// If the underlying io is not buffered this will result in poor performance.
+    /// Data is passed to the inner io in chunks between the emulation prevention bytes, so small reads
+    /// or writes on the wrapper result in small reads or writes on the inner io.
     pub fn new(inner: I) -> Self {
         Self { inner, zero_count: 0 }
     }
+
+    fn next_zero_count(&self, byte: u8) -> u8 {
+        if byte == 0x00 { self.zero_count.saturating_add(1) } else { 0 }
+    }
 }
 
 impl<I: std::io::Write> std::io::Write for EmulationPreventionIo<I> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        for &byte in buf {
-            if self.zero_count >= 2 && (byte <= 0x03) {
+        let mut start = 0;
+        for (idx, &byte) in buf.iter().enumerate() {
+            if self.zero_count >= 2 && byte <= 0x03 {
+                self.inner.write_all(&buf[start..idx])?;
                 self.inner.write_all(&[0x3])?;
                 self.zero_count = 0;
+                start = idx;
             }
 
-            self.inner.write_all(&[byte])?;
-            if byte == 0x00 {
-                self.zero_count += 1;
-            } else {
-                self.zero_count = 0;
-            }
+            self.zero_count = self.next_zero_count(byte);
         }
 
+        self.inner.write_all(&buf[start..])?;
         Ok(buf.len())
     }
 
@@ -44,33 +47,36 @@ impl<I: std::io::Write> std::io::Write for EmulationPreventionIo<I> {
 
 impl<I: std::io::Read> std::io::Read for EmulationPreventionIo<I> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let mut read_size = 0;
-        let mut one_byte = [0; 1];
-        while buf.len() > read_size {
-            let size = self.inner.read(&mut one_byte)?;
+        loop {
+            let size = self.inner.read(buf)?;
             if size == 0 {
-                break;
+                return Ok(0);
             }
 
-            let byte = one_byte[0];
-            match byte {
-                0x03 if self.zero_count >= 2 => {
+            // Remove the emulation prevention bytes in place by moving the data between them to the front.
+            let mut read_size = 0;
+            let mut start = 0;
+            for idx in 0..size {
+                let byte = buf[idx];
+                if self.zero_count >= 2 && byte == 0x03 {
+                    buf.copy_within(start..idx, read_size);
+                    read_size += idx - start;
+                    start = idx + 1;
                     self.zero_count = 0;
                     continue;
                 }
-                0x00 => {
-                    self.zero_count += 1;
-                }
-                _ => {
-                    self.zero_count = 0;
-                }
+
+                self.zero_count = self.next_zero_count(byte);
             }
 
-            buf[read_size] = byte;
-            read_size += 1;
-        }
+            buf.copy_within(start..size, read_size);
+            read_size += size - start;
 
-        Ok(read_size)
+            // Returning zero would signal the end of the stream, so try again if we only read an emulation prevention byte.
+            if read_size > 0 {
+                return Ok(read_size);
+            }
+        }
     }
 }
 
@@ -142,4 +148,53 @@ mod tests {
         // Should match original after roundtrip
         assert_eq!(original, decoded);
     }
+
+    const CASES: &[(&[u8], &[u8])] = &[
+        (&[], &[]),
+        (&[0x01, 0x02, 0x03], &[0x01, 0x02, 0x03]),
+        (&[0x00, 0x00, 0x00, 0x00], &[0x00, 0x00, 0x03, 0x00, 0x00]),
+        (&[0x00, 0x00, 0x03, 0x03], &[0x00, 0x00, 0x03, 0x03, 0x03]),
+        (&[0x00, 0x00, 0x04, 0x00], &[0x00, 0x00, 0x04, 0x00]),
+        (&[0x80, 0x00, 0x00, 0x01, 0x02], &[0x80, 0x00, 0x00, 0x03, 0x01, 0x02]),
+    ];
+
+    #[test]
+    fn test_cases_byte_by_byte() {
+        for (rbsp, ebsp) in CASES {
+            let mut encoded = Vec::new();
+            let mut writer = EmulationPreventionIo::new(&mut encoded);
+            for byte in rbsp.iter() {
+                writer.write_all(std::slice::from_ref(byte)).unwrap();
+            }
+            assert_eq!(encoded, *ebsp, "rbsp: {rbsp:02x?}");
+
+            let mut reader = EmulationPreventionIo::new(*ebsp);
+            let mut decoded = Vec::new();
+            let mut byte = [0; 1];
+            while reader.read(&mut byte).unwrap() == 1 {
+                decoded.push(byte[0]);
+            }
+            assert_eq!(decoded, *rbsp, "ebsp: {ebsp:02x?}");
+        }
+    }
+
+    #[test]
+    fn test_long_zero_runs() {
+        // Long runs of zeros interleaved with every byte value that can follow them.
+        let original = (0..4096u32)
+            .map(|i| if i % 300 < 290 { 0x00 } else { (i % 5) as u8 })
+            .collect::<Vec<_>>();
+
+        let mut encoded = Vec::new();
+        EmulationPreventionIo::new(&mut encoded).write_all(&original).unwrap();
+        assert!(
+            !encoded
+                .windows(3)
+                .any(|window| window[..2] == [0x00, 0x00] && window[2] <= 0x02)
+        );
+
+        let mut decoded = Vec::new();
+        EmulationPreventionIo::new(&encoded[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, original);
+    }
 }
@@ -0,0 +1,282 @@
+use std::io;
+use std::str::Utf8Error;
+
+use bytestring::ByteString;
+
+use crate::zero_copy::ZeroCopyReader;
+use crate::{BytesCow, StringCow};
+
+/// The text encoding of a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringEncoding {
+    /// UTF-8
+    #[default]
+    Utf8,
+    /// ISO/IEC 8859-1 (Latin-1)
+    ///
+    /// Every byte maps to the unicode code point of the same value, so decoding never fails.
+    Latin1,
+}
+
+/// Reads strings from binary data.
+///
+/// Configures the encoding, how invalid data is handled and the maximum length of a string, so that parsers
+/// do not have to repeat these checks and a malicious length prefix can not make a parser allocate large buffers.
+///
+/// ```rust
+/// # use scuffle_bytes_util::StringReader;
+/// let reader = StringReader::utf8().max_len(16);
+///
+/// let mut data = std::io::Cursor::new(b"hello\0world");
+/// assert_eq!(reader.read_null_terminated(&mut data).unwrap(), "hello");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[must_use]
+pub struct StringReader {
+    encoding: StringEncoding,
+    lossy: bool,
+    max_len: Option<usize>,
+    allow_unterminated: bool,
+}
+
+impl StringReader {
+    /// Creates a new reader for the given encoding.
+    ///
+    /// By default invalid data is an error and the length is not limited.
+    pub const fn new(encoding: StringEncoding) -> Self {
+        Self {
+            encoding,
+            lossy: false,
+            max_len: None,
+            allow_unterminated: false,
+        }
+    }
+
+    /// Creates a new reader for UTF-8 strings.
+    pub const fn utf8() -> Self {
+        Self::new(StringEncoding::Utf8)
+    }
+
+    /// Creates a new reader for Latin-1 strings.
+    pub const fn latin1() -> Self {
+        Self::new(StringEncoding::Latin1)
+    }
+
+    /// Replaces invalid sequences with `U+FFFD REPLACEMENT CHARACTER` instead of returning an error.
+    pub const fn lossy(mut self) -> Self {
+        self.lossy = true;
+        self
+    }
+
+    /// Sets the maximum length of a string in bytes, excluding the null terminator.
+    ///
+    /// Longer strings are rejected before any data is read.
+    pub const fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Accepts null-terminated strings that are terminated by the end of the data instead.
+    pub const fn allow_unterminated(mut self) -> Self {
+        self.allow_unterminated = true;
+        self
+    }
+
+    /// Decodes a string from the given bytes.
+    ///
+    /// Valid UTF-8 (and ASCII Latin-1) input is not copied.
+    pub fn decode<'a>(&self, bytes: BytesCow<'a>) -> io::Result<StringCow<'a>> {
+        self.check_len(bytes.as_bytes().len())?;
+
+        match self.encoding {
+            StringEncoding::Latin1 if !bytes.as_bytes().is_ascii() => {
+                Ok(StringCow::from_string(bytes.as_bytes().iter().map(|&b| b as char).collect()))
+            }
+            _ => match decode_utf8(bytes) {
+                Ok(string) => Ok(string),
+                Err((bytes, _)) if self.lossy => {
+                    Ok(StringCow::from_string(String::from_utf8_lossy(bytes.as_bytes()).into_owned()))
+                }
+                Err((_, err)) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            },
+        }
+    }
+
+    /// Reads a string of `len` bytes.
+    ///
+    /// This is used for length-prefixed strings, after the length has been read.
+    pub fn read<'a>(&self, reader: &mut impl ZeroCopyReader<'a>, len: usize) -> io::Result<StringCow<'a>> {
+        self.decode(self.read_bytes(reader, len)?)
+    }
+
+    /// Reads the `len` bytes of a string without decoding them.
+    ///
+    /// The length is checked before anything is read. This is useful when the bytes have to be inspected
+    /// before they are passed to [`StringReader::decode`], for example to look them up in a
+    /// [`StringInterner`](crate::StringInterner).
+    pub fn read_bytes<'a>(&self, reader: &mut impl ZeroCopyReader<'a>, len: usize) -> io::Result<BytesCow<'a>> {
+        self.check_len(len)?;
+        reader.try_read(len)
+    }
+
+    /// Reads a null-terminated string.
+    ///
+    /// The null terminator is consumed but not part of the returned string.
+    pub fn read_null_terminated(&self, reader: &mut impl io::Read) -> io::Result<StringCow<'static>> {
+        let mut bytes = Vec::new();
+        let mut byte = [0];
+
+        loop {
+            match reader.read_exact(&mut byte) {
+                Ok(()) if byte[0] == 0 => break,
+                Ok(()) => {
+                    bytes.push(byte[0]);
+                    self.check_len(bytes.len())?;
+                }
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof && self.allow_unterminated => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.decode(BytesCow::from_vec(bytes))
+    }
+
+    fn check_len(&self, len: usize) -> io::Result<()> {
+        if self.max_len.is_some_and(|max_len| len > max_len) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "string exceeds the maximum length",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes UTF-8 without copying, returns the input on failure so that it can be decoded lossily.
+fn decode_utf8(bytes: BytesCow<'_>) -> Result<StringCow<'_>, (BytesCow<'_>, Utf8Error)> {
+    match bytes {
+        BytesCow::Slice(slice) => std::str::from_utf8(slice)
+            .map(StringCow::from_ref)
+            .map_err(|err| (bytes, err)),
+        BytesCow::StaticSlice(slice) => std::str::from_utf8(slice)
+            .map(StringCow::from_static)
+            .map_err(|err| (bytes, err)),
+        BytesCow::Vec(vec) => String::from_utf8(vec).map(StringCow::from_string).map_err(|err| {
+            let utf8_error = err.utf8_error();
+            (BytesCow::from_vec(err.into_bytes()), utf8_error)
+        }),
+        // Cloning `Bytes` only increments a reference count.
+        BytesCow::Bytes(bytes) => ByteString::try_from(bytes.clone())
+            .map(StringCow::from_bytes)
+            .map_err(|err| (BytesCow::from_bytes(bytes), err)),
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::zero_copy::{BytesBuf, Slice};
+
+    #[test]
+    fn decode_utf8_zero_copy() {
+        let reader = StringReader::utf8();
+
+        assert!(matches!(
+            reader.decode(BytesCow::from_slice(b"abc")).unwrap(),
+            StringCow::Ref("abc")
+        ));
+        assert!(matches!(
+            reader.decode(BytesCow::from_static(b"abc")).unwrap(),
+            StringCow::StaticRef("abc")
+        ));
+        assert!(matches!(
+            reader.decode(BytesCow::from_bytes(Bytes::from_static(b"abc"))).unwrap(),
+            StringCow::Bytes(_)
+        ));
+        assert!(matches!(
+            reader.decode(BytesCow::from_vec(b"abc".to_vec())).unwrap(),
+            StringCow::String(_)
+        ));
+    }
+
+    #[test]
+    fn decode_invalid_utf8() {
+        let invalid: &[u8] = b"a\xffb";
+
+        for bytes in [
+            BytesCow::from_slice(invalid),
+            BytesCow::from_static(b"a\xffb"),
+            BytesCow::from_vec(invalid.to_vec()),
+            BytesCow::from_bytes(Bytes::from_static(b"a\xffb")),
+        ] {
+            let err = StringReader::utf8().decode(bytes.clone()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.get_ref().unwrap().downcast_ref::<Utf8Error>().is_some());
+
+            assert_eq!(StringReader::utf8().lossy().decode(bytes).unwrap(), "a\u{FFFD}b");
+        }
+    }
+
+    #[test]
+    fn decode_latin1() {
+        let reader = StringReader::latin1();
+
+        assert!(matches!(
+            reader.decode(BytesCow::from_slice(b"abc")).unwrap(),
+            StringCow::Ref("abc")
+        ));
+        assert_eq!(reader.decode(BytesCow::from_slice(b"caf\xe9")).unwrap(), "café");
+        assert_eq!(reader.decode(BytesCow::from_slice(b"\xff\x80")).unwrap(), "\u{ff}\u{80}");
+    }
+
+    #[test]
+    fn read_length_prefixed() {
+        let reader = StringReader::utf8().max_len(5);
+
+        let mut slice = Slice::from(&b"hello world"[..]);
+        assert!(matches!(reader.read(&mut slice, 5).unwrap(), StringCow::Ref("hello")));
+
+        // The length is checked before anything is read.
+        let err = reader.read(&mut slice, 6).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "string exceeds the maximum length");
+        assert_eq!(reader.read(&mut slice, 5).unwrap(), " worl");
+        assert!(reader.read_bytes(&mut slice, 6).is_err());
+        assert_eq!(reader.read_bytes(&mut slice, 1).unwrap(), b"d");
+
+        let mut buf = BytesBuf::from(Bytes::from_static(b"abc"));
+        let err = reader.read(&mut buf, 4).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_null_terminated() {
+        let reader = StringReader::utf8();
+
+        let mut cursor = io::Cursor::new(b"abc\0\0def");
+        assert_eq!(reader.read_null_terminated(&mut cursor).unwrap(), "abc");
+        assert_eq!(reader.read_null_terminated(&mut cursor).unwrap(), "");
+
+        let err = reader.read_null_terminated(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut cursor = io::Cursor::new(b"def");
+        assert_eq!(reader.allow_unterminated().read_null_terminated(&mut cursor).unwrap(), "def");
+
+        let mut cursor = io::Cursor::new(b"abcdef\0");
+        let err = reader.max_len(5).read_null_terminated(&mut cursor).unwrap_err();
+        assert_eq!(err.to_string(), "string exceeds the maximum length");
+        // Stops reading as soon as the limit is exceeded.
+        assert_eq!(cursor.position(), 6);
+
+        let mut cursor = io::Cursor::new(b"abcde\0");
+        assert_eq!(reader.max_len(5).read_null_terminated(&mut cursor).unwrap(), "abcde");
+
+        let mut cursor = io::Cursor::new(b"caf\xe9\0");
+        assert_eq!(StringReader::latin1().read_null_terminated(&mut cursor).unwrap(), "café");
+    }
+}
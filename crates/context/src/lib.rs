@@ -41,9 +41,13 @@
 #![deny(unsafe_code)]
 #![deny(unreachable_pub)]
 
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::time::Duration;
 
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
 /// For extending types.
@@ -109,6 +113,35 @@ impl ContextTrackerInner {
     }
 }
 
+/// Values attached to a context, see [`Context::with_value`].
+///
+/// The map is never mutated once shared, adding a value copies it.
+#[derive(Clone, Default)]
+struct Values(Option<Arc<ValueMap>>);
+
+/// Maps the type id of a value to its type name, for debugging, and the value.
+type ValueMap = HashMap<TypeId, (&'static str, Arc<dyn Any + Send + Sync>)>;
+
+impl Values {
+    fn get<T: Any>(&self) -> Option<&T> {
+        self.0.as_ref()?.get(&TypeId::of::<T>())?.1.downcast_ref()
+    }
+
+    fn insert<T: Any + Send + Sync>(&self, value: T) -> Self {
+        let mut map = self.0.as_deref().cloned().unwrap_or_default();
+        map.insert(TypeId::of::<T>(), (std::any::type_name::<T>(), Arc::new(value)));
+        Self(Some(Arc::new(map)))
+    }
+}
+
+impl std::fmt::Debug for Values {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(self.0.iter().flat_map(|map| map.values().map(|(name, _)| name)))
+            .finish()
+    }
+}
+
 /// A context for cancelling futures and waiting for shutdown.
 ///
 /// A context can be created from a handler by calling [`Handler::context`] or
@@ -120,10 +153,19 @@ impl ContextTrackerInner {
 /// [`Handler::cancel`].
 /// The [`Handler::shutdown`] method will block until all contexts have been
 /// dropped allowing for a graceful shutdown.
+///
+/// A context can also have a deadline, see [`Context::with_deadline`], after
+/// which it is cancelled automatically. Deadlines are inherited by child
+/// contexts.
+///
+/// Values such as request ids can be attached to a context with
+/// [`Context::with_value`], they are inherited by child contexts as well.
 #[derive(Debug)]
 pub struct Context {
     token: CancellationToken,
     tracker: ContextTracker,
+    deadline: Option<Instant>,
+    values: Values,
 }
 
 impl Clone for Context {
@@ -131,6 +173,8 @@ impl Clone for Context {
         Self {
             token: self.token.clone(),
             tracker: self.tracker.0.child(),
+            deadline: self.deadline,
+            values: self.values.clone(),
         }
     }
 }
@@ -163,14 +207,148 @@ impl Context {
             Self {
                 tracker: tracker.child(),
                 token: token.clone(),
+                deadline: self.deadline,
+                values: self.values.clone(),
             },
             Handler {
                 token: Arc::new(TokenDropGuard(token)),
                 tracker,
+                deadline: self.deadline,
+                values: self.values.clone(),
             },
         )
     }
 
+    #[must_use]
+    /// Create a new child context which is cancelled automatically once the
+    /// deadline is reached.
+    /// Returns a new child context and child handler of this context.
+    ///
+    /// If this context already has an earlier deadline, the child keeps the
+    /// earlier one, so a child can never outlive the deadline of its parent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime, unless the deadline has
+    /// already passed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use scuffle_context::Context;
+    /// # tokio_test::block_on(async {
+    /// let (parent, parent_handler) = Context::new();
+    /// let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+    /// let (child, child_handler) = parent.with_deadline(deadline);
+    ///
+    /// assert_eq!(child.deadline(), Some(deadline));
+    /// # });
+    /// ```
+    pub fn with_deadline(&self, deadline: Instant) -> (Self, Handler) {
+        let (mut ctx, mut handler) = self.new_child();
+
+        if self.deadline.is_some_and(|parent| parent <= deadline) {
+            // The parent is cancelled first and the cancellation is propagated to the child.
+            return (ctx, handler);
+        }
+
+        ctx.deadline = Some(deadline);
+        handler.deadline = Some(deadline);
+
+        if deadline <= Instant::now() {
+            handler.cancel();
+        } else {
+            let token = ctx.token.clone();
+            let tracker = Arc::clone(&handler.tracker);
+            tokio::spawn(futures_lite::future::or(token.clone().cancelled_owned(), async move {
+                tokio::time::sleep_until(deadline).await;
+                tracker.stop();
+                token.cancel();
+            }));
+        }
+
+        (ctx, handler)
+    }
+
+    #[must_use]
+    /// Create a new child context which is cancelled automatically once the
+    /// timeout has elapsed.
+    /// Returns a new child context and child handler of this context.
+    ///
+    /// This is the same as calling [`Context::with_deadline`] with
+    /// `Instant::now() + timeout`. A timeout too large to be represented as a
+    /// deadline is treated as no timeout at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime, unless the timeout is zero.
+    pub fn with_timeout(&self, timeout: Duration) -> (Self, Handler) {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.with_deadline(deadline),
+            None => self.new_child(),
+        }
+    }
+
+    #[must_use]
+    /// Create a new child context with a value attached.
+    /// Returns a new child context and child handler of this context.
+    ///
+    /// Values are looked up by type with [`Context::value`] and are inherited
+    /// by all child contexts, so they can be used to pass request scoped data
+    /// like request ids down to spawned tasks.
+    /// Attaching a value of a type which is already present replaces it for the
+    /// child, the parent is not affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use scuffle_context::Context;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct RequestId(u64);
+    ///
+    /// let (parent, parent_handler) = Context::new();
+    /// let (ctx, handler) = parent.with_value(RequestId(1));
+    /// let (child, child_handler) = ctx.new_child();
+    ///
+    /// assert_eq!(child.value::<RequestId>(), Some(&RequestId(1)));
+    /// assert_eq!(parent.value::<RequestId>(), None);
+    /// ```
+    pub fn with_value<T: Any + Send + Sync>(&self, value: T) -> (Self, Handler) {
+        let (mut ctx, mut handler) = self.new_child();
+
+        let values = self.values.insert(value);
+        ctx.values = values.clone();
+        handler.values = values;
+
+        (ctx, handler)
+    }
+
+    /// Returns the value of type `T` attached to this context or one of its
+    /// parents, see [`Context::with_value`].
+    #[must_use]
+    pub fn value<T: Any>(&self) -> Option<&T> {
+        self.values.get()
+    }
+
+    /// Returns the deadline after which this context is cancelled, if any.
+    ///
+    /// This is the earliest deadline of this context and all of its parents.
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Returns the time left until the deadline of this context is reached, if
+    /// it has one.
+    ///
+    /// Returns [`Duration::ZERO`] once the deadline has passed.
+    #[must_use]
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
     #[must_use]
     /// Returns the global context
     pub fn global() -> Self {
@@ -221,6 +399,8 @@ impl Drop for TokenDropGuard {
 pub struct Handler {
     token: Arc<TokenDropGuard>,
     tracker: Arc<ContextTrackerInner>,
+    deadline: Option<Instant>,
+    values: Values,
 }
 
 impl Default for Handler {
@@ -239,6 +419,8 @@ impl Handler {
         Handler {
             token: Arc::new(TokenDropGuard(token)),
             tracker,
+            deadline: None,
+            values: Values::default(),
         }
     }
 
@@ -275,6 +457,8 @@ impl Handler {
         Context {
             token: self.token.child(),
             tracker: self.tracker.child(),
+            deadline: self.deadline,
+            values: self.values.clone(),
         }
     }
 
@@ -299,7 +483,10 @@ impl Handler {
 #[cfg_attr(all(coverage_nightly, test), coverage(off))]
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use scuffle_future_ext::FutureExt;
+    use tokio::time::Instant;
 
     use crate::{Context, Handler};
 
@@ -416,6 +603,118 @@ mod tests {
         assert!(child_handler.is_done());
         assert!(child_ctx.is_done());
     }
+
+    #[tokio::test]
+    async fn with_timeout() {
+        let handler = Handler::new();
+        let ctx = handler.context();
+        assert_eq!(ctx.deadline(), None);
+        assert_eq!(ctx.remaining(), None);
+
+        let (child_ctx, child_handler) = ctx.with_timeout(Duration::from_millis(100));
+        let deadline = child_ctx.deadline().expect("deadline");
+        assert!(child_ctx.remaining().unwrap() <= Duration::from_millis(100));
+        assert_eq!(child_ctx.clone().deadline(), Some(deadline));
+        assert_eq!(child_handler.context().deadline(), Some(deadline));
+        assert!(!child_ctx.is_done());
+
+        assert!(child_ctx.done().with_timeout(Duration::from_millis(500)).await.is_ok());
+        assert!(Instant::now() >= deadline);
+        assert_eq!(child_ctx.remaining(), Some(Duration::ZERO));
+        assert!(child_handler.is_done());
+        assert!(!ctx.is_done());
+        assert!(!handler.is_done());
+
+        drop(child_ctx);
+        assert!(child_handler.wait().with_timeout(Duration::from_millis(200)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn with_deadline_inherited() {
+        let handler = Handler::new();
+        let ctx = handler.context();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let (parent, _parent_handler) = ctx.with_deadline(deadline);
+
+        // A later deadline does not extend the deadline of the parent.
+        let (child, _child_handler) = parent.with_deadline(deadline + Duration::from_secs(60));
+        assert_eq!(child.deadline(), Some(deadline));
+
+        // A plain child inherits the deadline.
+        let (child, _child_handler) = parent.new_child();
+        assert_eq!(child.deadline(), Some(deadline));
+
+        // An earlier deadline replaces it.
+        let earlier = deadline - Duration::from_secs(30);
+        let (child, _child_handler) = parent.with_deadline(earlier);
+        assert_eq!(child.deadline(), Some(earlier));
+    }
+
+    #[tokio::test]
+    async fn with_deadline_cancelled_by_parent() {
+        let handler = Handler::new();
+        let ctx = handler.context();
+        let (child_ctx, child_handler) = ctx.with_timeout(Duration::from_secs(60));
+
+        handler.cancel();
+        assert!(child_ctx.is_done());
+        assert!(child_handler.is_done());
+    }
+
+    #[test]
+    fn with_deadline_elapsed() {
+        // No runtime is needed if the deadline has already passed.
+        let handler = Handler::new();
+        let ctx = handler.context();
+        let (child_ctx, child_handler) = ctx.with_timeout(Duration::ZERO);
+
+        assert!(child_ctx.is_done());
+        assert!(child_handler.is_done());
+        assert!(!ctx.is_done());
+        assert!(!handler.is_done());
+
+        let (child_ctx, _child_handler) = ctx.with_timeout(Duration::MAX);
+        assert_eq!(child_ctx.deadline(), None);
+    }
+
+    #[test]
+    fn with_value() {
+        #[derive(Debug, PartialEq)]
+        struct RequestId(u64);
+
+        #[derive(Debug, PartialEq)]
+        struct Tenant(&'static str);
+
+        let handler = Handler::new();
+        let ctx = handler.context();
+        assert_eq!(ctx.value::<RequestId>(), None);
+
+        let (parent, parent_handler) = ctx.with_value(RequestId(1));
+        assert_eq!(parent.value::<RequestId>(), Some(&RequestId(1)));
+        assert_eq!(parent.clone().value::<RequestId>(), Some(&RequestId(1)));
+        assert_eq!(parent_handler.context().value::<RequestId>(), Some(&RequestId(1)));
+        assert_eq!(ctx.value::<RequestId>(), None);
+
+        // Values are inherited and can be combined.
+        let (child, _child_handler) = parent.with_value(Tenant("scuffle"));
+        assert_eq!(child.value::<RequestId>(), Some(&RequestId(1)));
+        assert_eq!(child.value::<Tenant>(), Some(&Tenant("scuffle")));
+        assert_eq!(parent.value::<Tenant>(), None);
+
+        // A value of the same type replaces the inherited one.
+        let (child, _child_handler) = child.with_value(RequestId(2));
+        assert_eq!(child.value::<RequestId>(), Some(&RequestId(2)));
+        assert_eq!(parent.value::<RequestId>(), Some(&RequestId(1)));
+
+        let (child, _child_handler) = child.new_child();
+        assert_eq!(child.value::<RequestId>(), Some(&RequestId(2)));
+        assert!(format!("{child:?}").contains("Tenant"));
+
+        // Values do not affect cancellation.
+        handler.cancel();
+        assert!(child.is_done());
+        assert_eq!(child.value::<Tenant>(), Some(&Tenant("scuffle")));
+    }
 }
 
 /// Changelogs generated by [scuffle_changelog]
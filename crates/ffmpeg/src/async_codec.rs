@@ -0,0 +1,389 @@
+//! Decoding and encoding blocks the calling thread, so it should not run on the worker threads of a
+//! tokio runtime.
+//!
+//! [`AsyncDecoder`] and [`AsyncEncoder`] move a decoder or an encoder onto a blocking thread
+//! (see [`tokio::task::spawn_blocking`]) and run the send/receive loop there, including the
+//! `EAGAIN` handling. Packets and frames are passed through bounded channels: sending waits while
+//! the input queue is full and the blocking thread pauses while the output queue is full, so a slow
+//! consumer slows down the producer instead of buffering an unbounded amount of frames.
+//!
+//! The input and output sides can be [split](AsyncDecoder::split) and driven from different tasks.
+//! Feeding and draining from the same task only works as long as the output queue does not fill up.
+
+use tokio::sync::mpsc;
+
+use crate::decoder::{AudioDecoder, GenericDecoder, ReceiveFrame, VideoDecoder};
+use crate::encoder::{Encoder, ReceivePacket};
+use crate::error::{FfmpegError, FfmpegErrorCode};
+use crate::frame::{AudioFrame, GenericFrame, VideoFrame};
+use crate::packet::Packet;
+
+/// The input side of an [`AsyncDecoder`] or an [`AsyncEncoder`].
+#[derive(Debug)]
+pub struct AsyncSender<T> {
+    tx: mpsc::Sender<Option<T>>,
+}
+
+impl<T> Clone for AsyncSender<T> {
+    fn clone(&self) -> Self {
+        Self { tx: self.tx.clone() }
+    }
+}
+
+impl<T> AsyncSender<T> {
+    /// Sends an input, waiting while the input queue is full.
+    ///
+    /// Returns `false` if the blocking thread has stopped, either after an error or after the end of file.
+    pub async fn send(&self, input: T) -> bool {
+        self.tx.send(Some(input)).await.is_ok()
+    }
+
+    /// Signals the end of the input, after which the remaining outputs are flushed.
+    ///
+    /// Returns `false` if the blocking thread has stopped.
+    pub async fn send_eof(&self) -> bool {
+        self.tx.send(None).await.is_ok()
+    }
+}
+
+/// The output side of an [`AsyncDecoder`] or an [`AsyncEncoder`].
+#[derive(Debug)]
+pub struct AsyncReceiver<T> {
+    rx: mpsc::Receiver<Result<T, FfmpegError>>,
+}
+
+impl<T> AsyncReceiver<T> {
+    /// Receives the next output.
+    ///
+    /// Returns `None` once the blocking thread has stopped and all outputs have been received.
+    /// An error is always the last output.
+    pub async fn recv(&mut self) -> Option<Result<T, FfmpegError>> {
+        self.rx.recv().await
+    }
+
+    /// Returns the underlying channel, for example to wrap it in a `tokio_stream::wrappers::ReceiverStream`.
+    pub fn into_inner(self) -> mpsc::Receiver<Result<T, FfmpegError>> {
+        self.rx
+    }
+}
+
+/// A decoder running on a blocking thread.
+#[derive(Debug)]
+pub struct AsyncDecoder<F> {
+    packets: AsyncSender<Packet>,
+    frames: AsyncReceiver<F>,
+}
+
+impl AsyncDecoder<GenericFrame> {
+    /// Moves the decoder onto a blocking thread.
+    ///
+    /// `capacity` is the size of the packet and the frame queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn new(decoder: GenericDecoder, capacity: usize) -> Self {
+        Self::spawn(decoder, capacity)
+    }
+}
+
+impl AsyncDecoder<VideoFrame> {
+    /// Moves the video decoder onto a blocking thread.
+    ///
+    /// `capacity` is the size of the packet and the frame queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn video(decoder: VideoDecoder, capacity: usize) -> Self {
+        Self::spawn(decoder, capacity)
+    }
+}
+
+impl AsyncDecoder<AudioFrame> {
+    /// Moves the audio decoder onto a blocking thread.
+    ///
+    /// `capacity` is the size of the packet and the frame queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn audio(decoder: AudioDecoder, capacity: usize) -> Self {
+        Self::spawn(decoder, capacity)
+    }
+}
+
+impl<F: Send + 'static> AsyncDecoder<F> {
+    fn spawn<C: Codec<Input = Packet, Output = F>>(decoder: C, capacity: usize) -> Self {
+        let (packets, frames) = spawn(decoder, capacity);
+        Self { packets, frames }
+    }
+
+    /// Sends a packet to the decoder, waiting while the packet queue is full.
+    ///
+    /// Returns `false` if the decoder has stopped.
+    pub async fn send_packet(&self, packet: Packet) -> bool {
+        self.packets.send(packet).await
+    }
+
+    /// Sends an end-of-file to the decoder, after which the remaining frames are flushed.
+    ///
+    /// Returns `false` if the decoder has stopped.
+    pub async fn send_eof(&self) -> bool {
+        self.packets.send_eof().await
+    }
+
+    /// Receives the next decoded frame.
+    ///
+    /// Returns `None` once the decoder has stopped and all frames have been received.
+    pub async fn receive_frame(&mut self) -> Option<Result<F, FfmpegError>> {
+        self.frames.recv().await
+    }
+
+    /// Splits the decoder into its packet and frame side.
+    pub fn split(self) -> (AsyncSender<Packet>, AsyncReceiver<F>) {
+        (self.packets, self.frames)
+    }
+}
+
+/// An encoder running on a blocking thread.
+#[derive(Debug)]
+pub struct AsyncEncoder {
+    frames: AsyncSender<GenericFrame>,
+    packets: AsyncReceiver<Packet>,
+}
+
+impl AsyncEncoder {
+    /// Moves the encoder onto a blocking thread.
+    ///
+    /// `capacity` is the size of the frame and the packet queue.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn new(encoder: Encoder, capacity: usize) -> Self {
+        let (frames, packets) = spawn(encoder, capacity);
+        Self { frames, packets }
+    }
+
+    /// Sends a frame to the encoder, waiting while the frame queue is full.
+    ///
+    /// Returns `false` if the encoder has stopped.
+    pub async fn send_frame(&self, frame: GenericFrame) -> bool {
+        self.frames.send(frame).await
+    }
+
+    /// Sends an end-of-file to the encoder, after which the remaining packets are flushed.
+    ///
+    /// Returns `false` if the encoder has stopped.
+    pub async fn send_eof(&self) -> bool {
+        self.frames.send_eof().await
+    }
+
+    /// Receives the next encoded packet.
+    ///
+    /// Returns `None` once the encoder has stopped and all packets have been received.
+    pub async fn receive_packet(&mut self) -> Option<Result<Packet, FfmpegError>> {
+        self.packets.recv().await
+    }
+
+    /// Splits the encoder into its frame and packet side.
+    pub fn split(self) -> (AsyncSender<GenericFrame>, AsyncReceiver<Packet>) {
+        (self.frames, self.packets)
+    }
+}
+
+/// The result of trying to receive an output.
+enum Receive<T> {
+    Output(T),
+    NeedInput,
+    Eof,
+}
+
+/// The send/receive interface shared by decoders and encoders.
+trait Codec: Send + 'static {
+    type Input: Send + 'static;
+    type Output: Send + 'static;
+
+    /// Sends an input, or the end of file if `input` is `None`.
+    fn send(&mut self, input: Option<&Self::Input>) -> Result<(), FfmpegError>;
+
+    fn receive(&mut self) -> Result<Receive<Self::Output>, FfmpegError>;
+}
+
+impl<F> From<ReceiveFrame<F>> for Receive<F> {
+    fn from(value: ReceiveFrame<F>) -> Self {
+        match value {
+            ReceiveFrame::Frame(frame) => Self::Output(frame),
+            ReceiveFrame::NeedPacket => Self::NeedInput,
+            ReceiveFrame::Eof => Self::Eof,
+        }
+    }
+}
+
+macro_rules! impl_decoder_codec {
+    ($decoder:ty, $frame:ty) => {
+        impl Codec for $decoder {
+            type Input = Packet;
+            type Output = $frame;
+
+            fn send(&mut self, input: Option<&Packet>) -> Result<(), FfmpegError> {
+                match input {
+                    Some(packet) => self.send_packet(packet),
+                    None => self.send_eof(),
+                }
+            }
+
+            fn receive(&mut self) -> Result<Receive<$frame>, FfmpegError> {
+                self.try_receive_frame().map(Into::into)
+            }
+        }
+    };
+}
+
+impl_decoder_codec!(GenericDecoder, GenericFrame);
+impl_decoder_codec!(VideoDecoder, VideoFrame);
+impl_decoder_codec!(AudioDecoder, AudioFrame);
+
+impl Codec for Encoder {
+    type Input = GenericFrame;
+    type Output = Packet;
+
+    fn send(&mut self, input: Option<&GenericFrame>) -> Result<(), FfmpegError> {
+        match input {
+            Some(frame) => self.send_frame(frame),
+            None => self.send_eof(),
+        }
+    }
+
+    fn receive(&mut self) -> Result<Receive<Packet>, FfmpegError> {
+        Ok(match self.try_receive_packet()? {
+            ReceivePacket::Packet(packet) => Receive::Output(packet),
+            ReceivePacket::NeedFrame => Receive::NeedInput,
+            ReceivePacket::Eof => Receive::Eof,
+        })
+    }
+}
+
+fn spawn<C: Codec>(codec: C, capacity: usize) -> (AsyncSender<C::Input>, AsyncReceiver<C::Output>) {
+    let capacity = capacity.max(1);
+    let (input_tx, input_rx) = mpsc::channel(capacity);
+    let (output_tx, output_rx) = mpsc::channel(capacity);
+
+    tokio::task::spawn_blocking(move || {
+        if let Err(err) = run(codec, input_rx, &output_tx) {
+            // The receiver might be gone already, in which case nobody is interested in the error.
+            let _ = output_tx.blocking_send(Err(err));
+        }
+    });
+
+    (AsyncSender { tx: input_tx }, AsyncReceiver { rx: output_rx })
+}
+
+/// Runs the send/receive loop until the end of file, an error or until either side is dropped.
+fn run<C: Codec>(
+    mut codec: C,
+    mut inputs: mpsc::Receiver<Option<C::Input>>,
+    outputs: &mpsc::Sender<Result<C::Output, FfmpegError>>,
+) -> Result<(), FfmpegError> {
+    while let Some(input) = inputs.blocking_recv() {
+        loop {
+            match codec.send(input.as_ref()) {
+                // The outputs have to be received before the codec accepts more input.
+                Err(FfmpegError::Code(FfmpegErrorCode::Eagain)) => {
+                    if !drain(&mut codec, outputs)? {
+                        return Ok(());
+                    }
+                }
+                result => break result?,
+            }
+        }
+
+        if !drain(&mut codec, outputs)? {
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
+
+/// Forwards outputs until the codec needs more input.
+///
+/// Returns `false` if the codec has been flushed or the receiver is gone.
+fn drain<C: Codec>(codec: &mut C, outputs: &mpsc::Sender<Result<C::Output, FfmpegError>>) -> Result<bool, FfmpegError> {
+    loop {
+        match codec.receive()? {
+            // Blocks this thread while the output queue is full.
+            Receive::Output(output) => {
+                if outputs.blocking_send(Ok(output)).is_err() {
+                    return Ok(false);
+                }
+            }
+            Receive::NeedInput => return Ok(true),
+            Receive::Eof => return Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::AsyncDecoder;
+    use crate::AVMediaType;
+    use crate::decoder::Decoder;
+    use crate::io::Input;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_decoder() {
+        let mut input = Input::open("../../assets/avc_aac_large.mp4").expect("Failed to open valid file");
+        let streams = input.streams();
+        let video_stream = streams.best(AVMediaType::Video).expect("No video stream found");
+        let video_stream_index = video_stream.index();
+        let new_decoder = || {
+            Decoder::new(&video_stream)
+                .expect("Failed to create decoder")
+                .video()
+                .expect("Failed to get video decoder")
+        };
+
+        let mut decoder = new_decoder();
+        let async_decoder = AsyncDecoder::video(new_decoder(), 1);
+
+        let mut packets = Vec::new();
+        while let Some(packet) = input.receive_packet().expect("Failed to receive packet") {
+            if packet.stream_index() == video_stream_index {
+                packets.push(packet);
+            }
+        }
+
+        let mut expected = Vec::new();
+        for packet in &packets {
+            decoder.send_packet(packet).expect("Failed to send packet");
+            while let Some(frame) = decoder.receive_frame().expect("Failed to receive frame") {
+                expected.push(frame.pts());
+            }
+        }
+        decoder.send_eof().expect("Failed to send eof");
+        while let Some(frame) = decoder.receive_frame().expect("Failed to receive frame") {
+            expected.push(frame.pts());
+        }
+
+        // A queue size of 1 makes the sender wait for the receiver.
+        let (sender, mut receiver) = async_decoder.split();
+        let feeder = tokio::spawn(async move {
+            for packet in packets {
+                assert!(sender.send(packet).await);
+            }
+            assert!(sender.send_eof().await);
+        });
+
+        let mut frames = Vec::new();
+        while let Some(frame) = receiver.recv().await {
+            frames.push(frame.expect("Failed to decode frame").pts());
+        }
+
+        feeder.await.expect("feeder panicked");
+        assert!(!expected.is_empty());
+        assert_eq!(frames, expected);
+    }
+}
@@ -0,0 +1,302 @@
+use std::ffi::CStr;
+
+use crate::dict::CStringLike;
+use crate::error::{FfmpegError, FfmpegErrorCode};
+use crate::ffi::*;
+use crate::packet::Packet;
+use crate::rational::Rational;
+use crate::smart_object::SmartPtr;
+use crate::stream::Stream;
+
+/// A bitstream filter which transforms packets without decoding them.
+///
+/// Commonly used to convert between bitstream formats, e.g. `h264_mp4toannexb` converts
+/// H.264 from the length prefixed (AVCC) format used by MP4 and FLV to the Annex-B format
+/// expected by raw decoders, and `aac_adtstoasc` strips the ADTS headers from AAC.
+///
+/// ```rust,no_run
+/// # use scuffle_ffmpeg::bitstream_filter::BitstreamFilter;
+/// # use scuffle_ffmpeg::AVMediaType;
+/// # fn test_fn() -> Result<(), scuffle_ffmpeg::error::FfmpegError> {
+/// let mut input = scuffle_ffmpeg::io::Input::open("input.mp4")?;
+/// let (stream_index, mut bsf) = {
+///     let streams = input.streams();
+///     let stream = streams.best(AVMediaType::Video).expect("no video stream");
+///     let bsf = BitstreamFilter::builder("h264_mp4toannexb")?.stream(&stream)?.build()?;
+///     (stream.index(), bsf)
+/// };
+///
+/// for packet in input.packets() {
+///     let mut packet = packet?;
+///     if packet.stream_index() != stream_index {
+///         continue;
+///     }
+///
+///     bsf.send_packet(&mut packet)?;
+///     while let Some(packet) = bsf.receive_packet()? {
+///         // Annex-B packet
+///         # drop(packet);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BitstreamFilter(SmartPtr<AVBSFContext>);
+
+/// Safety: `BitstreamFilter` is safe to send between threads.
+unsafe impl Send for BitstreamFilter {}
+
+impl std::fmt::Debug for BitstreamFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitstreamFilter")
+            .field("name", &self.name())
+            .field("time_base_in", &self.time_base_in())
+            .field("time_base_out", &self.time_base_out())
+            .finish()
+    }
+}
+
+/// A builder for a [`BitstreamFilter`].
+///
+/// Created with [`BitstreamFilter::builder`].
+pub struct BitstreamFilterBuilder(SmartPtr<AVBSFContext>);
+
+/// Safety: `BitstreamFilterBuilder` is safe to send between threads.
+unsafe impl Send for BitstreamFilterBuilder {}
+
+impl BitstreamFilter {
+    /// Creates a builder for the bitstream filter with the given name, e.g. `h264_mp4toannexb`.
+    ///
+    /// Returns [`FfmpegError::NoFilter`] if there is no bitstream filter with this name.
+    pub fn builder(name: &str) -> Result<BitstreamFilterBuilder, FfmpegError> {
+        let name = std::ffi::CString::new(name).or(Err(FfmpegError::Arguments("name must not contain null bytes")))?;
+
+        // Safety: `av_bsf_get_by_name` is safe to call and `name` is a valid c-string.
+        let filter = unsafe { av_bsf_get_by_name(name.as_ptr()) };
+        if filter.is_null() {
+            return Err(FfmpegError::NoFilter);
+        }
+
+        let destructor = |ptr: &mut *mut AVBSFContext| {
+            // Safety: The pointer here is valid.
+            unsafe { av_bsf_free(ptr) };
+        };
+
+        let mut ptr = std::ptr::null_mut();
+
+        // Safety: `av_bsf_alloc` is safe to call and `filter` is a valid pointer.
+        FfmpegErrorCode(unsafe { av_bsf_alloc(filter, &mut ptr) }).result()?;
+
+        // Safety: `ptr` was allocated by `av_bsf_alloc`, and `destructor` has been setup to free the context.
+        let ctx = unsafe { SmartPtr::wrap_non_null(ptr, destructor) }.ok_or(FfmpegError::Alloc)?;
+
+        Ok(BitstreamFilterBuilder(ctx))
+    }
+
+    /// Returns the pointer to the bitstream filter context.
+    pub const fn as_ptr(&self) -> *const AVBSFContext {
+        self.0.as_ptr()
+    }
+
+    /// Returns the mutable pointer to the bitstream filter context.
+    pub const fn as_mut_ptr(&mut self) -> *mut AVBSFContext {
+        self.0.as_mut_ptr()
+    }
+
+    /// Returns the name of the bitstream filter.
+    pub fn name(&self) -> &str {
+        // Safety: `filter` is set by `av_bsf_alloc` and always valid.
+        let filter = unsafe { &*self.0.as_deref_except().filter };
+        // Safety: The name of a filter is a valid static c-string.
+        unsafe { CStr::from_ptr(filter.name) }.to_str().unwrap_or_default()
+    }
+
+    /// Returns the time base of the input packets.
+    pub fn time_base_in(&self) -> Rational {
+        self.0.as_deref_except().time_base_in.into()
+    }
+
+    /// Returns the time base of the output packets.
+    ///
+    /// Packets should be converted from this time base when they are muxed.
+    pub fn time_base_out(&self) -> Rational {
+        self.0.as_deref_except().time_base_out.into()
+    }
+
+    /// Returns the codec parameters of the output packets.
+    ///
+    /// These can differ from the input parameters, e.g. `h264_mp4toannexb` removes the extradata.
+    pub const fn codec_parameters_out(&self) -> Option<&AVCodecParameters> {
+        // Safety: `par_out` is allocated by `av_bsf_alloc` and valid for the lifetime of the context.
+        unsafe { self.0.as_deref_except().par_out.as_ref() }
+    }
+
+    /// Sends a packet to the bitstream filter.
+    ///
+    /// On success the filter takes the data of the packet and leaves it empty.
+    ///
+    /// Returns [`FfmpegErrorCode::Eagain`] if the filtered packets have to be received with
+    /// [`BitstreamFilter::receive_packet`] before the filter accepts more packets. The packet is
+    /// left untouched on errors, so it can be sent again.
+    pub fn send_packet(&mut self, packet: &mut Packet) -> Result<(), FfmpegError> {
+        // Safety: `packet` is a valid pointer, on success the filter takes ownership of its data and resets it.
+        FfmpegErrorCode(unsafe { av_bsf_send_packet(self.0.as_mut_ptr(), packet.as_mut_ptr()) }).result()?;
+        Ok(())
+    }
+
+    /// Signals the end of the stream so that any buffered packets are output.
+    pub fn send_eof(&mut self) -> Result<(), FfmpegError> {
+        // Safety: `av_bsf_send_packet` accepts a null packet to signal the end of the stream.
+        FfmpegErrorCode(unsafe { av_bsf_send_packet(self.0.as_mut_ptr(), std::ptr::null_mut()) }).result()?;
+        Ok(())
+    }
+
+    /// Receives a filtered packet.
+    ///
+    /// Returns `None` when the filter needs more packets or has been fully flushed after
+    /// [`BitstreamFilter::send_eof`].
+    pub fn receive_packet(&mut self) -> Result<Option<Packet>, FfmpegError> {
+        let mut packet = Packet::new()?;
+
+        // Safety: `packet` is a valid pointer, and `self.0` is a valid pointer.
+        match FfmpegErrorCode(unsafe { av_bsf_receive_packet(self.0.as_mut_ptr(), packet.as_mut_ptr()) }) {
+            code if code.is_success() => Ok(Some(packet)),
+            FfmpegErrorCode::Eagain | FfmpegErrorCode::Eof => Ok(None),
+            code => Err(FfmpegError::Code(code)),
+        }
+    }
+
+    /// Resets the internal state of the filter, dropping any buffered packets.
+    ///
+    /// Should be called when seeking, the filter accepts packets again after
+    /// [`BitstreamFilter::send_eof`].
+    pub fn flush(&mut self) {
+        // Safety: `self.0` is a valid pointer.
+        unsafe { av_bsf_flush(self.0.as_mut_ptr()) };
+    }
+}
+
+impl BitstreamFilterBuilder {
+    /// Sets an option of the bitstream filter, e.g. `aud` = `insert` for `h264_metadata`.
+    pub fn option<'a>(mut self, key: impl CStringLike<'a>, value: impl CStringLike<'a>) -> Result<Self, FfmpegError> {
+        let key = key.into_c_str().ok_or(FfmpegError::Arguments("key cannot be empty"))?;
+        let value = value.into_c_str().ok_or(FfmpegError::Arguments("value cannot be empty"))?;
+
+        // Safety: `self.0` is a valid pointer to an object starting with an `AVClass` and the strings are valid.
+        FfmpegErrorCode(unsafe {
+            av_opt_set(
+                self.0.as_mut_ptr().cast(),
+                key.as_ptr(),
+                value.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN as _,
+            )
+        })
+        .result()?;
+
+        Ok(self)
+    }
+
+    /// Sets the codec parameters of the input packets.
+    pub fn codec_parameters(mut self, codec_parameters: &AVCodecParameters) -> Result<Self, FfmpegError> {
+        // Safety: `par_in` is allocated by `av_bsf_alloc`, and `codec_parameters` is a valid pointer.
+        FfmpegErrorCode(unsafe { avcodec_parameters_copy(self.0.as_deref_mut_except().par_in, codec_parameters) })
+            .result()?;
+        Ok(self)
+    }
+
+    /// Sets the time base of the input packets.
+    pub fn time_base(mut self, time_base: impl Into<Rational>) -> Self {
+        self.0.as_deref_mut_except().time_base_in = time_base.into().into();
+        self
+    }
+
+    /// Sets the codec parameters and the time base of the input packets from a stream.
+    pub fn stream(self, stream: &Stream) -> Result<Self, FfmpegError> {
+        let codec_parameters = stream.codec_parameters().ok_or(FfmpegError::NoStream)?;
+        Ok(self.codec_parameters(codec_parameters)?.time_base(stream.time_base()))
+    }
+
+    /// Initializes the bitstream filter.
+    ///
+    /// Fails if the filter does not support the codec of the input packets.
+    pub fn build(mut self) -> Result<BitstreamFilter, FfmpegError> {
+        // Safety: `self.0` is a valid pointer.
+        FfmpegErrorCode(unsafe { av_bsf_init(self.0.as_mut_ptr()) }).result()?;
+        Ok(BitstreamFilter(self.0))
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use crate::AVMediaType;
+    use crate::bitstream_filter::BitstreamFilter;
+    use crate::error::FfmpegError;
+    use crate::io::Input;
+
+    #[test]
+    fn test_bitstream_filter_unknown() {
+        assert!(matches!(BitstreamFilter::builder("not_a_filter"), Err(FfmpegError::NoFilter)));
+        assert!(matches!(
+            BitstreamFilter::builder("null\0"),
+            Err(FfmpegError::Arguments("name must not contain null bytes"))
+        ));
+    }
+
+    #[test]
+    fn test_bitstream_filter_unknown_option() {
+        let result = BitstreamFilter::builder("h264_mp4toannexb")
+            .unwrap()
+            .option("not_an_option", "1");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bitstream_filter_mp4toannexb() {
+        let mut input = Input::open("../../assets/avc_aac.mp4").expect("Failed to open file");
+        let (stream_index, mut bsf) = {
+            let streams = input.streams();
+            let stream = streams.best(AVMediaType::Video).expect("No video stream found");
+            let bsf = BitstreamFilter::builder("h264_mp4toannexb")
+                .unwrap()
+                .stream(&stream)
+                .unwrap()
+                .build()
+                .unwrap();
+            (stream.index(), bsf)
+        };
+
+        assert_eq!(bsf.name(), "h264_mp4toannexb");
+        assert_eq!(bsf.time_base_in(), bsf.time_base_out());
+        assert!(bsf.codec_parameters_out().is_some());
+
+        let mut filtered = Vec::new();
+        for packet in input.packets().take(20) {
+            let mut packet = packet.expect("Failed to read packet");
+            if packet.stream_index() != stream_index {
+                continue;
+            }
+
+            // AVCC packets start with the length of the first NAL unit.
+            assert_ne!(&packet.data()[..4], &[0, 0, 0, 1]);
+
+            bsf.send_packet(&mut packet).expect("Failed to send packet");
+            assert!(packet.data().is_empty());
+            while let Some(packet) = bsf.receive_packet().expect("Failed to receive packet") {
+                filtered.push(packet);
+            }
+        }
+
+        bsf.send_eof().expect("Failed to send eof");
+        while let Some(packet) = bsf.receive_packet().expect("Failed to receive packet") {
+            filtered.push(packet);
+        }
+
+        assert!(!filtered.is_empty());
+        for packet in &filtered {
+            assert!(packet.data().starts_with(&[0, 0, 0, 1]) || packet.data().starts_with(&[0, 0, 1]));
+        }
+
+        bsf.flush();
+    }
+}
@@ -8,7 +8,7 @@ use crate::packet::Packet;
 use crate::rational::Rational;
 use crate::smart_object::SmartPtr;
 use crate::stream::Stream;
-use crate::{AVCodecID, AVMediaType, AVPixelFormat, AVSampleFormat};
+use crate::{AVCodecFlags, AVCodecFlags2, AVCodecID, AVDiscard, AVMediaType, AVPixelFormat, AVSampleFormat, AVThreadType};
 
 /// Either a [`VideoDecoder`] or an [`AudioDecoder`].
 ///
@@ -69,19 +69,59 @@ impl std::fmt::Debug for AudioDecoder {
 }
 
 /// Options for creating a [`Decoder`].
+#[derive(bon::Builder)]
 pub struct DecoderOptions {
     /// The codec to use for decoding.
     pub codec: Option<DecoderCodec>,
     /// The number of threads to use for decoding.
+    #[builder(default = 1)]
     pub thread_count: i32,
+    /// The threading methods the decoder is allowed to use.
+    ///
+    /// [`AVThreadType::Frame`] adds one frame of delay per thread,
+    /// use [`AVThreadType::Slice`] when latency matters.
+    pub thread_type: Option<AVThreadType>,
+    /// The codec flags, e.g. [`AVCodecFlags::LowDelay`].
+    pub flags: Option<AVCodecFlags>,
+    /// The additional codec flags.
+    pub flags2: Option<AVCodecFlags2>,
+    /// Which frames the decoder should skip decoding, e.g. [`AVDiscard::NonKey`] to only decode keyframes.
+    pub skip_frame: Option<AVDiscard>,
 }
 
 /// The default options for a [`Decoder`].
 impl Default for DecoderOptions {
     fn default() -> Self {
-        Self {
-            codec: None,
-            thread_count: 1,
+        Self::builder().build()
+    }
+}
+
+/// The result of trying to receive a frame from a decoder.
+#[derive(Debug)]
+pub enum ReceiveFrame<F> {
+    /// A decoded frame.
+    Frame(F),
+    /// The decoder needs more packets before it can output another frame.
+    NeedPacket,
+    /// The decoder has been fully flushed and will not output any more frames.
+    Eof,
+}
+
+impl<F> ReceiveFrame<F> {
+    /// Returns the frame if one was received.
+    pub fn into_frame(self) -> Option<F> {
+        match self {
+            Self::Frame(frame) => Some(frame),
+            Self::NeedPacket | Self::Eof => None,
+        }
+    }
+
+    /// Maps the received frame with the given function.
+    pub fn map<T>(self, f: impl FnOnce(F) -> T) -> ReceiveFrame<T> {
+        match self {
+            Self::Frame(frame) => ReceiveFrame::Frame(f(frame)),
+            Self::NeedPacket => ReceiveFrame::NeedPacket,
+            Self::Eof => ReceiveFrame::Eof,
         }
     }
 }
@@ -126,6 +166,10 @@ impl Decoder {
         decoder_mut.pkt_timebase = ist.time_base().into();
         decoder_mut.time_base = ist.time_base().into();
         decoder_mut.thread_count = options.thread_count;
+        decoder_mut.thread_type = options.thread_type.map_or(decoder_mut.thread_type, Into::into);
+        decoder_mut.flags = options.flags.map_or(decoder_mut.flags, Into::into);
+        decoder_mut.flags2 = options.flags2.map_or(decoder_mut.flags2, Into::into);
+        decoder_mut.skip_frame = options.skip_frame.map_or(decoder_mut.skip_frame, Into::into);
 
         if AVMediaType(decoder_mut.codec_type) == AVMediaType::Video {
             // Safety: Even though we are upcasting `AVFormatContext` from a const pointer to a
@@ -199,17 +243,29 @@ impl GenericDecoder {
     }
 
     /// Receives a frame from the decoder.
+    ///
+    /// Returns `None` both when the decoder needs more packets and when it has been fully flushed,
+    /// use [`GenericDecoder::try_receive_frame`] to tell these apart.
     pub fn receive_frame(&mut self) -> Result<Option<GenericFrame>, FfmpegError> {
+        Ok(self.try_receive_frame()?.into_frame())
+    }
+
+    /// Tries to receive a frame from the decoder without blocking.
+    ///
+    /// Returns [`ReceiveFrame::NeedPacket`] if the decoder needs more packets before it can output a frame
+    /// and [`ReceiveFrame::Eof`] once the decoder has been flushed with [`GenericDecoder::send_eof`].
+    pub fn try_receive_frame(&mut self) -> Result<ReceiveFrame<GenericFrame>, FfmpegError> {
         let mut frame = GenericFrame::new()?;
 
         // Safety: `frame` is a valid pointer, and `self.decoder` is a valid pointer.
         let ret = FfmpegErrorCode(unsafe { avcodec_receive_frame(self.decoder.as_mut_ptr(), frame.as_mut_ptr()) });
 
         match ret {
-            FfmpegErrorCode::Eagain | FfmpegErrorCode::Eof => Ok(None),
+            FfmpegErrorCode::Eagain => Ok(ReceiveFrame::NeedPacket),
+            FfmpegErrorCode::Eof => Ok(ReceiveFrame::Eof),
             code if code.is_success() => {
                 frame.set_time_base(self.decoder.as_deref_except().time_base);
-                Ok(Some(frame))
+                Ok(ReceiveFrame::Frame(frame))
             }
             code => Err(FfmpegError::Code(code)),
         }
@@ -246,6 +302,13 @@ impl VideoDecoder {
     pub fn receive_frame(&mut self) -> Result<Option<VideoFrame>, FfmpegError> {
         Ok(self.0.receive_frame()?.map(|frame| frame.video()))
     }
+
+    /// Tries to receive a frame from the decoder without blocking.
+    ///
+    /// See [`GenericDecoder::try_receive_frame`].
+    pub fn try_receive_frame(&mut self) -> Result<ReceiveFrame<VideoFrame>, FfmpegError> {
+        Ok(self.0.try_receive_frame()?.map(|frame| frame.video()))
+    }
 }
 
 impl std::ops::Deref for VideoDecoder {
@@ -282,6 +345,13 @@ impl AudioDecoder {
     pub fn receive_frame(&mut self) -> Result<Option<AudioFrame>, FfmpegError> {
         Ok(self.0.receive_frame()?.map(|frame| frame.audio()))
     }
+
+    /// Tries to receive a frame from the decoder without blocking.
+    ///
+    /// See [`GenericDecoder::try_receive_frame`].
+    pub fn try_receive_frame(&mut self) -> Result<ReceiveFrame<AudioFrame>, FfmpegError> {
+        Ok(self.0.try_receive_frame()?.map(|frame| frame.audio()))
+    }
 }
 
 impl std::ops::Deref for AudioDecoder {
@@ -304,9 +374,9 @@ mod tests {
     use std::num::NonZero;
 
     use crate::codec::DecoderCodec;
-    use crate::decoder::{Decoder, DecoderOptions};
+    use crate::decoder::{Decoder, DecoderOptions, ReceiveFrame};
     use crate::io::Input;
-    use crate::{AVCodecID, AVMediaType};
+    use crate::{AVCodecFlags, AVCodecFlags2, AVCodecID, AVDiscard, AVMediaType, AVThreadType};
 
     #[test]
     fn test_generic_decoder_debug() {
@@ -330,6 +400,7 @@ mod tests {
         let decoder_options = DecoderOptions {
             codec: Some(DecoderCodec::new(AVCodecID::H264).expect("Failed to find H264 codec")),
             thread_count: 2,
+            ..Default::default()
         };
         let decoder = Decoder::with_options(&stream, decoder_options).expect("Failed to create Decoder");
         let generic_decoder = match decoder {
@@ -373,6 +444,7 @@ mod tests {
         let decoder_options = DecoderOptions {
             codec: Some(DecoderCodec::new(AVCodecID::H264).expect("Failed to find H264 codec")),
             thread_count: 2,
+            ..Default::default()
         };
         let decoder = Decoder::with_options(&stream, decoder_options).expect("Failed to create Decoder");
 
@@ -426,6 +498,7 @@ mod tests {
         let decoder_options = DecoderOptions {
             codec: Some(DecoderCodec::new(AVCodecID::Aac).expect("Failed to find AAC codec")),
             thread_count: 2,
+            ..Default::default()
         };
         let decoder = Decoder::with_options(&stream, decoder_options).expect("Failed to create Decoder");
         let audio_decoder = match decoder {
@@ -454,6 +527,79 @@ mod tests {
 
         assert!(default_options.codec.is_none(), "Expected default codec to be None");
         assert_eq!(default_options.thread_count, 1, "Expected default thread_count to be 1");
+        assert!(
+            default_options.thread_type.is_none(),
+            "Expected default thread_type to be None"
+        );
+        assert!(default_options.flags.is_none(), "Expected default flags to be None");
+        assert!(default_options.flags2.is_none(), "Expected default flags2 to be None");
+        assert!(default_options.skip_frame.is_none(), "Expected default skip_frame to be None");
+    }
+
+    #[test]
+    fn test_decoder_options_low_delay() {
+        let valid_file_path = "../../assets/avc_aac_large.mp4";
+        let input = Input::open(valid_file_path).expect("Failed to open valid file");
+        let streams = input.streams();
+        let stream = streams.best(AVMediaType::Video).expect("No video stream found");
+
+        let options = DecoderOptions::builder()
+            .thread_count(4)
+            .thread_type(AVThreadType::Slice)
+            .flags(AVCodecFlags::LowDelay)
+            .flags2(AVCodecFlags2::Fast)
+            .skip_frame(AVDiscard::NonKey)
+            .build();
+        let decoder = Decoder::with_options(&stream, options)
+            .expect("Failed to create Decoder")
+            .video()
+            .expect("Failed to get video decoder");
+
+        let context = decoder.decoder.as_deref_except();
+        assert_eq!(context.thread_count, 4);
+        assert_eq!(AVThreadType(context.thread_type), AVThreadType::Slice);
+        assert_eq!(AVCodecFlags(context.flags) & AVCodecFlags::LowDelay, AVCodecFlags::LowDelay);
+        assert_eq!(AVCodecFlags2(context.flags2) & AVCodecFlags2::Fast, AVCodecFlags2::Fast);
+        assert_eq!(AVDiscard(context.skip_frame), AVDiscard::NonKey);
+    }
+
+    #[test]
+    fn test_decoder_try_receive_frame() {
+        let valid_file_path = "../../assets/avc_aac_large.mp4";
+        let mut input = Input::open(valid_file_path).expect("Failed to open valid file");
+        let streams = input.streams();
+        let video_stream = streams.best(AVMediaType::Video).expect("No video stream found");
+        let video_stream_index = video_stream.index();
+        let mut decoder = Decoder::new(&video_stream)
+            .expect("Failed to create decoder")
+            .video()
+            .expect("Failed to get video decoder");
+
+        assert!(matches!(decoder.try_receive_frame(), Ok(ReceiveFrame::NeedPacket)));
+
+        let mut frames = 0;
+        while let Some(packet) = input.receive_packet().expect("Failed to receive packet") {
+            if packet.stream_index() != video_stream_index {
+                continue;
+            }
+
+            decoder.send_packet(&packet).expect("Failed to send packet");
+            while let ReceiveFrame::Frame(_) = decoder.try_receive_frame().expect("Failed to receive frame") {
+                frames += 1;
+            }
+        }
+
+        decoder.send_eof().expect("Failed to send eof");
+        loop {
+            match decoder.try_receive_frame().expect("Failed to receive frame") {
+                ReceiveFrame::Frame(_) => frames += 1,
+                ReceiveFrame::NeedPacket => panic!("Expected the decoder to be flushed"),
+                ReceiveFrame::Eof => break,
+            }
+        }
+
+        assert!(frames > 0, "Expected frames to be decoded");
+        assert!(matches!(decoder.try_receive_frame(), Ok(ReceiveFrame::Eof)));
     }
 
     #[test]
@@ -551,6 +697,7 @@ mod tests {
         let decoder_options = DecoderOptions {
             codec: None,
             thread_count: 2,
+            ..Default::default()
         };
         let decoder = Decoder::with_options(&stream, decoder_options).expect("Failed to create Decoder");
         let mut video_decoder = match decoder {
@@ -587,6 +734,7 @@ mod tests {
         let decoder_options = DecoderOptions {
             codec: None,
             thread_count: 2,
+            ..Default::default()
         };
         let decoder = Decoder::with_options(&stream, decoder_options).expect("Failed to create Decoder");
         let mut audio_decoder = match decoder {
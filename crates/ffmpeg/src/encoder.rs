@@ -1,7 +1,9 @@
+use std::ffi::CStr;
 use std::ptr::NonNull;
 
 use crate::codec::EncoderCodec;
 use crate::dict::Dictionary;
+use crate::encoder_options::{EncoderLevel, EncoderOptions, EncoderPreset, EncoderProfile, EncoderTune};
 use crate::error::{FfmpegError, FfmpegErrorCode};
 use crate::ffi::*;
 use crate::frame::{AudioChannelLayout, GenericFrame};
@@ -23,7 +25,32 @@ pub struct Encoder {
 /// Safety: `Encoder` can be sent between threads.
 unsafe impl Send for Encoder {}
 
+/// The result of trying to receive a packet from an encoder.
+#[derive(Debug)]
+pub enum ReceivePacket {
+    /// An encoded packet.
+    Packet(Packet),
+    /// The encoder needs more frames before it can output another packet.
+    NeedFrame,
+    /// The encoder has been fully flushed and will not output any more packets.
+    Eof,
+}
+
+impl ReceivePacket {
+    /// Returns the packet if one was received.
+    pub fn into_packet(self) -> Option<Packet> {
+        match self {
+            Self::Packet(packet) => Some(packet),
+            Self::NeedFrame | Self::Eof => None,
+        }
+    }
+}
+
 /// Represents the settings for a video encoder.
+///
+/// `crf`, `preset`, `tune`, `profile` and `level` are validated and translated into the codec specific
+/// options of libx264, libx265 and libaom-av1 when the encoder is created. Entries in
+/// `codec_specific_options` take precedence over them.
 #[derive(bon::Builder)]
 pub struct VideoEncoderSettings {
     width: i32,
@@ -41,6 +68,12 @@ pub struct VideoEncoderSettings {
     rc_max_rate: Option<i64>,
     rc_buffer_size: Option<i32>,
     max_b_frames: Option<i32>,
+    /// The constant rate factor, lower values mean better quality.
+    crf: Option<f32>,
+    preset: Option<EncoderPreset>,
+    tune: Option<EncoderTune>,
+    profile: Option<EncoderProfile>,
+    level: Option<EncoderLevel>,
     codec_specific_options: Option<Dictionary>,
     flags: Option<i32>,
     flags2: Option<i32>,
@@ -55,6 +88,16 @@ impl VideoEncoderSettings {
             ));
         }
 
+        if let Some(rc_max_rate) = self.rc_max_rate.filter(|rate| *rate > 0) {
+            if self.rc_buffer_size.is_none_or(|size| size <= 0) {
+                return Err(FfmpegError::Arguments("rc_max_rate requires rc_buffer_size to be set"));
+            }
+
+            if self.rc_min_rate.is_some_and(|rate| rate > rc_max_rate) {
+                return Err(FfmpegError::Arguments("rc_min_rate must not be greater than rc_max_rate"));
+            }
+        }
+
         encoder.width = self.width;
         encoder.height = self.height;
         encoder.pix_fmt = self.pixel_format.into();
@@ -78,6 +121,16 @@ impl VideoEncoderSettings {
 
         Ok(())
     }
+
+    const fn encoder_options(&self) -> EncoderOptions {
+        EncoderOptions {
+            crf: self.crf,
+            preset: self.preset,
+            tune: self.tune,
+            profile: self.profile,
+            level: self.level,
+        }
+    }
 }
 
 /// Represents the settings for an audio encoder.
@@ -143,6 +196,25 @@ impl EncoderSettings {
             EncoderSettings::Audio(audio_settings) => audio_settings.codec_specific_options.as_mut(),
         }
     }
+
+    /// Returns the codec specific options including the typed video options for the given encoder.
+    fn codec_options(&mut self, codec_name: &CStr) -> Result<Option<Dictionary>, FfmpegError> {
+        let options = match self {
+            EncoderSettings::Video(video_settings) => video_settings.encoder_options(),
+            EncoderSettings::Audio(_) => EncoderOptions::default(),
+        };
+
+        if options.is_empty() {
+            return Ok(self.codec_specific_options().cloned());
+        }
+
+        let mut dict = options.to_dictionary(codec_name)?;
+        if let Some(codec_specific_options) = self.codec_specific_options() {
+            dict.extend(&*codec_specific_options)?;
+        }
+
+        Ok(Some(dict))
+    }
 }
 
 impl From<VideoEncoderSettings> for EncoderSettings {
@@ -196,7 +268,11 @@ impl Encoder {
 
         encoder_mut.time_base = incoming_time_base.into();
 
-        let mut codec_options = settings.codec_specific_options().cloned();
+        // Safety: `codec` is not null, so it points to a valid `AVCodec`.
+        let codec_name = unsafe { (*codec.as_ptr()).name };
+        // Safety: The name of a codec is a valid static c-string.
+        let codec_name = unsafe { CStr::from_ptr(codec_name) };
+        let mut codec_options = settings.codec_options(codec_name)?;
 
         let codec_options_ptr = codec_options
             .as_mut()
@@ -246,14 +322,26 @@ impl Encoder {
     }
 
     /// Receives a packet from the encoder.
+    ///
+    /// Returns `None` both when the encoder needs more frames and when it has been fully flushed,
+    /// use [`Encoder::try_receive_packet`] to tell these apart.
     pub fn receive_packet(&mut self) -> Result<Option<Packet>, FfmpegError> {
+        Ok(self.try_receive_packet()?.into_packet())
+    }
+
+    /// Tries to receive a packet from the encoder without blocking.
+    ///
+    /// Returns [`ReceivePacket::NeedFrame`] if the encoder needs more frames before it can output a packet
+    /// and [`ReceivePacket::Eof`] once the encoder has been flushed with [`Encoder::send_eof`].
+    pub fn try_receive_packet(&mut self) -> Result<ReceivePacket, FfmpegError> {
         let mut packet = Packet::new()?;
 
         // Safety: `self.encoder` and `packet` are valid pointers.
         let ret = FfmpegErrorCode(unsafe { avcodec_receive_packet(self.encoder.as_mut_ptr(), packet.as_mut_ptr()) });
 
         match ret {
-            FfmpegErrorCode::Eagain | FfmpegErrorCode::Eof => Ok(None),
+            FfmpegErrorCode::Eagain => Ok(ReceivePacket::NeedFrame),
+            FfmpegErrorCode::Eof => Ok(ReceivePacket::Eof),
             code if code.is_success() => {
                 if cfg!(debug_assertions) {
                     debug_assert!(
@@ -272,7 +360,7 @@ impl Encoder {
 
                 packet.convert_timebase(self.incoming_time_base, self.outgoing_time_base);
                 packet.set_stream_index(self.stream_index);
-                Ok(Some(packet))
+                Ok(ReceivePacket::Packet(packet))
             }
             code => Err(FfmpegError::Code(code)),
         }
@@ -307,6 +395,7 @@ mod tests {
     use crate::decoder::Decoder;
     use crate::dict::Dictionary;
     use crate::encoder::{AudioChannelLayout, AudioEncoderSettings, Encoder, EncoderSettings, VideoEncoderSettings};
+    use crate::encoder_options::{EncoderLevel, EncoderPreset, EncoderProfile, EncoderTune};
     use crate::error::FfmpegError;
     use crate::ffi::AVCodecContext;
     use crate::io::{Input, Output, OutputOptions};
@@ -422,6 +511,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_video_encoder_settings_rate_control_error() {
+        let builder = || {
+            VideoEncoderSettings::builder()
+                .width(1920)
+                .height(1080)
+                .frame_rate(30.into())
+                .pixel_format(AVPixelFormat::Yuv420p)
+                .rc_max_rate(2_000_000)
+        };
+
+        // Safety: We are zeroing the memory for the encoder context.
+        let mut encoder = unsafe { std::mem::zeroed::<AVCodecContext>() };
+        assert_eq!(
+            builder().build().apply(&mut encoder).unwrap_err(),
+            FfmpegError::Arguments("rc_max_rate requires rc_buffer_size to be set")
+        );
+        assert_eq!(
+            builder()
+                .rc_buffer_size(4_000_000)
+                .rc_min_rate(3_000_000)
+                .build()
+                .apply(&mut encoder)
+                .unwrap_err(),
+            FfmpegError::Arguments("rc_min_rate must not be greater than rc_max_rate")
+        );
+    }
+
+    #[test]
+    fn test_encoder_settings_typed_options() {
+        let mut codec_specific_options = Dictionary::new();
+        codec_specific_options.set(c"preset", c"slow").expect("Failed to set preset");
+
+        let video_settings = VideoEncoderSettings::builder()
+            .width(8)
+            .height(8)
+            .frame_rate(30.into())
+            .pixel_format(AVPixelFormat::Yuv420p)
+            .crf(23.0)
+            .preset(EncoderPreset::Fast)
+            .tune(EncoderTune::ZeroLatency)
+            .profile(EncoderProfile::Main)
+            .level(EncoderLevel::new(3, 1))
+            .codec_specific_options(codec_specific_options)
+            .build();
+        let mut encoder_settings = EncoderSettings::Video(video_settings);
+
+        let options = encoder_settings
+            .codec_options(c"libx264")
+            .expect("Failed to get codec options")
+            .expect("Codec options should be set");
+        assert_eq!(options.get(c"crf"), Some(c"23"));
+        // Codec specific options take precedence over the typed options.
+        assert_eq!(options.get(c"preset"), Some(c"slow"));
+        assert_eq!(options.get(c"tune"), Some(c"zerolatency"));
+        assert_eq!(options.get(c"profile"), Some(c"main"));
+        assert_eq!(options.get(c"level"), Some(c"3.1"));
+
+        assert!(matches!(
+            encoder_settings.codec_options(c"mpeg4"),
+            Err(FfmpegError::Arguments(_))
+        ));
+    }
+
+    #[test]
+    fn test_encoder_new_typed_options_unsupported() {
+        let codec = EncoderCodec::new(AVCodecID::Mpeg4).expect("Failed to find MPEG-4 encoder");
+        let data = std::io::Cursor::new(Vec::new());
+        let options = OutputOptions::builder().format_name("mp4").unwrap().build();
+        let mut output = Output::new(data, options).expect("Failed to create Output");
+        let settings = VideoEncoderSettings::builder()
+            .width(640)
+            .height(480)
+            .frame_rate(30.into())
+            .pixel_format(AVPixelFormat::Yuv420p)
+            .crf(23.0)
+            .build();
+        let result = Encoder::new(
+            codec,
+            &mut output,
+            AVRational { num: 1, den: 1000 },
+            AVRational { num: 1, den: 1000 },
+            settings,
+        );
+
+        assert!(matches!(result, Err(FfmpegError::Arguments(_))));
+    }
+
     #[test]
     fn test_audio_encoder_apply() {
         let sample_rate = 44100;
@@ -820,7 +997,9 @@ mod tests {
                         if let Some(stsz) = trak.mdia.minf.stbl.stsz.as_mut() {
                             stsz.samples.clear();
                         }
-                        trak.mdia.minf.stbl.stco.entries.clear();
+                        if let Some(stco) = trak.mdia.minf.stbl.stco.as_mut() {
+                            stco.entries.clear();
+                        }
                     });
                 }
                 _ => {}
@@ -0,0 +1,442 @@
+use std::ffi::CStr;
+
+use crate::dict::Dictionary;
+use crate::error::FfmpegError;
+
+/// The speed to compression efficiency trade-off of an encoder.
+///
+/// Maps to the `preset` option of libx264 and libx265 and to the `cpu-used` option of libaom-av1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncoderPreset {
+    /// The fastest preset with the worst compression.
+    Ultrafast,
+    /// `superfast`
+    Superfast,
+    /// `veryfast`
+    Veryfast,
+    /// `faster`
+    Faster,
+    /// `fast`
+    Fast,
+    /// `medium`, the default of libx264 and libx265.
+    Medium,
+    /// `slow`
+    Slow,
+    /// `slower`
+    Slower,
+    /// `veryslow`
+    Veryslow,
+    /// The slowest preset with the best compression.
+    Placebo,
+}
+
+impl EncoderPreset {
+    /// Returns the name of the preset as understood by libx264 and libx265.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ultrafast => "ultrafast",
+            Self::Superfast => "superfast",
+            Self::Veryfast => "veryfast",
+            Self::Faster => "faster",
+            Self::Fast => "fast",
+            Self::Medium => "medium",
+            Self::Slow => "slow",
+            Self::Slower => "slower",
+            Self::Veryslow => "veryslow",
+            Self::Placebo => "placebo",
+        }
+    }
+
+    /// The `cpu-used` value of libaom-av1 closest to this preset.
+    const fn aom_cpu_used(&self) -> &'static str {
+        match self {
+            Self::Ultrafast | Self::Superfast => "8",
+            Self::Veryfast => "7",
+            Self::Faster => "6",
+            Self::Fast => "5",
+            Self::Medium => "4",
+            Self::Slow => "3",
+            Self::Slower => "2",
+            Self::Veryslow => "1",
+            Self::Placebo => "0",
+        }
+    }
+}
+
+/// Tunes the encoder for a type of content or a use case.
+///
+/// Not every encoder supports every tune, libaom-av1 only supports [`EncoderTune::Psnr`] and [`EncoderTune::Ssim`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncoderTune {
+    /// High quality movie content, libx264 only.
+    Film,
+    /// Cartoons and other animated content.
+    Animation,
+    /// Preserves the grain structure of old film content.
+    Grain,
+    /// Slideshow like content, libx264 only.
+    StillImage,
+    /// Allows faster decoding by disabling some filters.
+    FastDecode,
+    /// Fast encoding and low latency streaming.
+    ZeroLatency,
+    /// Optimizes for the PSNR metric.
+    Psnr,
+    /// Optimizes for the SSIM metric.
+    Ssim,
+}
+
+impl EncoderTune {
+    /// Returns the name of the tune as understood by the encoders.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Film => "film",
+            Self::Animation => "animation",
+            Self::Grain => "grain",
+            Self::StillImage => "stillimage",
+            Self::FastDecode => "fastdecode",
+            Self::ZeroLatency => "zerolatency",
+            Self::Psnr => "psnr",
+            Self::Ssim => "ssim",
+        }
+    }
+}
+
+/// The profile of the encoded bitstream.
+///
+/// - libx264 supports `Baseline`, `Main`, `High`, `High10`, `High422` and `High444`.
+/// - libx265 supports `Main` and `Main10`.
+/// - libaom-av1 supports `Main`, `High` and `Professional`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncoderProfile {
+    /// H.264 constrained baseline profile.
+    Baseline,
+    /// H.264 main, HEVC main or AV1 main profile.
+    Main,
+    /// H.264 high or AV1 high profile.
+    High,
+    /// H.264 high 10 profile.
+    High10,
+    /// H.264 high 4:2:2 profile.
+    High422,
+    /// H.264 high 4:4:4 predictive profile.
+    High444,
+    /// HEVC main 10 profile.
+    Main10,
+    /// AV1 professional profile.
+    Professional,
+}
+
+/// The level of the encoded bitstream, e.g. `4.1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncoderLevel {
+    major: u8,
+    minor: u8,
+}
+
+impl EncoderLevel {
+    /// Creates a new level from its major and minor version, e.g. `EncoderLevel::new(4, 1)` for level `4.1`.
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    /// Returns the major version of the level.
+    pub const fn major(&self) -> u8 {
+        self.major
+    }
+
+    /// Returns the minor version of the level.
+    pub const fn minor(&self) -> u8 {
+        self.minor
+    }
+
+    const fn is_h264(&self) -> bool {
+        matches!(
+            (self.major, self.minor),
+            (1, 0..=3) | (2, 0..=2) | (3, 0..=2) | (4, 0..=2) | (5, 0..=2) | (6, 0..=2)
+        )
+    }
+
+    const fn is_hevc(&self) -> bool {
+        matches!(
+            (self.major, self.minor),
+            (1, 0) | (2, 0..=1) | (3, 0..=1) | (4, 0..=1) | (5, 0..=2) | (6, 0..=2)
+        )
+    }
+}
+
+impl std::fmt::Display for EncoderLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OptionsCodec {
+    X264,
+    X265,
+    Aom,
+}
+
+impl OptionsCodec {
+    fn from_name(name: &CStr) -> Option<Self> {
+        match name.to_bytes() {
+            b"libx264" | b"libx264rgb" => Some(Self::X264),
+            b"libx265" => Some(Self::X265),
+            b"libaom-av1" => Some(Self::Aom),
+            _ => None,
+        }
+    }
+}
+
+/// The typed options of [`VideoEncoderSettings`](crate::encoder::VideoEncoderSettings) which are passed to the
+/// encoder as codec specific options.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct EncoderOptions {
+    pub(crate) crf: Option<f32>,
+    pub(crate) preset: Option<EncoderPreset>,
+    pub(crate) tune: Option<EncoderTune>,
+    pub(crate) profile: Option<EncoderProfile>,
+    pub(crate) level: Option<EncoderLevel>,
+}
+
+impl EncoderOptions {
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.crf.is_none() && self.preset.is_none() && self.tune.is_none() && self.profile.is_none() && self.level.is_none()
+    }
+
+    /// Validates the options for the encoder with the given name and converts them into codec specific options.
+    pub(crate) fn to_dictionary(self, codec_name: &CStr) -> Result<Dictionary, FfmpegError> {
+        let mut dict = Dictionary::new();
+        if self.is_empty() {
+            return Ok(dict);
+        }
+
+        let codec = OptionsCodec::from_name(codec_name).ok_or(FfmpegError::Arguments(
+            "crf, preset, tune, profile and level are only supported by libx264, libx265 and libaom-av1",
+        ))?;
+
+        if let Some(crf) = self.crf {
+            let crf = match codec {
+                OptionsCodec::X264 | OptionsCodec::X265 => {
+                    if !(0.0..=51.0).contains(&crf) {
+                        return Err(FfmpegError::Arguments("crf must be between 0 and 51"));
+                    }
+
+                    crf.to_string()
+                }
+                OptionsCodec::Aom => {
+                    if !(0.0..=63.0).contains(&crf) || crf.fract() != 0.0 {
+                        return Err(FfmpegError::Arguments("crf must be a whole number between 0 and 63"));
+                    }
+
+                    (crf as u8).to_string()
+                }
+            };
+
+            dict.set(c"crf", crf)?;
+        }
+
+        if let Some(preset) = self.preset {
+            match codec {
+                OptionsCodec::X264 | OptionsCodec::X265 => dict.set(c"preset", preset.as_str())?,
+                OptionsCodec::Aom => dict.set(c"cpu-used", preset.aom_cpu_used())?,
+            }
+        }
+
+        if let Some(tune) = self.tune {
+            let supported = match codec {
+                OptionsCodec::X264 => true,
+                OptionsCodec::X265 => !matches!(tune, EncoderTune::Film | EncoderTune::StillImage),
+                OptionsCodec::Aom => matches!(tune, EncoderTune::Psnr | EncoderTune::Ssim),
+            };
+
+            if !supported {
+                return Err(FfmpegError::Arguments("tune is not supported by this encoder"));
+            }
+
+            dict.set(c"tune", tune.as_str())?;
+        }
+
+        if let Some(profile) = self.profile {
+            let profile = match (codec, profile) {
+                (OptionsCodec::X264, EncoderProfile::Baseline) => "baseline",
+                (OptionsCodec::X264 | OptionsCodec::X265, EncoderProfile::Main) => "main",
+                (OptionsCodec::X264, EncoderProfile::High) => "high",
+                (OptionsCodec::X264, EncoderProfile::High10) => "high10",
+                (OptionsCodec::X264, EncoderProfile::High422) => "high422",
+                (OptionsCodec::X264, EncoderProfile::High444) => "high444p",
+                (OptionsCodec::X265, EncoderProfile::Main10) => "main10",
+                // libaom-av1 has no private profile option, this sets `AVCodecContext.profile`.
+                (OptionsCodec::Aom, EncoderProfile::Main) => "0",
+                (OptionsCodec::Aom, EncoderProfile::High) => "1",
+                (OptionsCodec::Aom, EncoderProfile::Professional) => "2",
+                _ => return Err(FfmpegError::Arguments("profile is not supported by this encoder")),
+            };
+
+            dict.set(c"profile", profile)?;
+        }
+
+        if let Some(level) = self.level {
+            match codec {
+                OptionsCodec::X264 if level.is_h264() => dict.set(c"level", level.to_string())?,
+                // libx265 does not have a level option, it has to be passed as a x265 parameter.
+                OptionsCodec::X265 if level.is_hevc() => dict.set(c"x265-params", format!("level-idc={level}"))?,
+                OptionsCodec::X264 | OptionsCodec::X265 => {
+                    return Err(FfmpegError::Arguments("level is not valid for this encoder"));
+                }
+                OptionsCodec::Aom => return Err(FfmpegError::Arguments("level is not supported by libaom-av1")),
+            }
+        }
+
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use crate::encoder_options::{EncoderLevel, EncoderOptions, EncoderPreset, EncoderProfile, EncoderTune};
+    use crate::error::FfmpegError;
+
+    #[test]
+    fn test_encoder_options_empty() {
+        let dict = EncoderOptions::default().to_dictionary(c"mpeg4").unwrap();
+        assert!(dict.is_empty());
+    }
+
+    #[test]
+    fn test_encoder_options_unsupported_codec() {
+        let options = EncoderOptions {
+            crf: Some(23.0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.to_dictionary(c"mpeg4").unwrap_err(),
+            FfmpegError::Arguments(
+                "crf, preset, tune, profile and level are only supported by libx264, libx265 and libaom-av1"
+            )
+        );
+    }
+
+    #[test]
+    fn test_encoder_options_x264() {
+        let options = EncoderOptions {
+            crf: Some(23.5),
+            preset: Some(EncoderPreset::Veryfast),
+            tune: Some(EncoderTune::ZeroLatency),
+            profile: Some(EncoderProfile::High),
+            level: Some(EncoderLevel::new(4, 1)),
+        };
+
+        let dict = options.to_dictionary(c"libx264").unwrap();
+        assert_eq!(dict.get(c"crf"), Some(c"23.5"));
+        assert_eq!(dict.get(c"preset"), Some(c"veryfast"));
+        assert_eq!(dict.get(c"tune"), Some(c"zerolatency"));
+        assert_eq!(dict.get(c"profile"), Some(c"high"));
+        assert_eq!(dict.get(c"level"), Some(c"4.1"));
+    }
+
+    #[test]
+    fn test_encoder_options_x265() {
+        let options = EncoderOptions {
+            crf: Some(28.0),
+            preset: Some(EncoderPreset::Slow),
+            tune: Some(EncoderTune::Grain),
+            profile: Some(EncoderProfile::Main10),
+            level: Some(EncoderLevel::new(5, 1)),
+        };
+
+        let dict = options.to_dictionary(c"libx265").unwrap();
+        assert_eq!(dict.get(c"crf"), Some(c"28"));
+        assert_eq!(dict.get(c"preset"), Some(c"slow"));
+        assert_eq!(dict.get(c"tune"), Some(c"grain"));
+        assert_eq!(dict.get(c"profile"), Some(c"main10"));
+        assert_eq!(dict.get(c"x265-params"), Some(c"level-idc=5.1"));
+
+        let options = EncoderOptions {
+            tune: Some(EncoderTune::Film),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_dictionary(c"libx265").unwrap_err(),
+            FfmpegError::Arguments("tune is not supported by this encoder")
+        );
+
+        let options = EncoderOptions {
+            profile: Some(EncoderProfile::High),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_dictionary(c"libx265").unwrap_err(),
+            FfmpegError::Arguments("profile is not supported by this encoder")
+        );
+    }
+
+    #[test]
+    fn test_encoder_options_aom() {
+        let options = EncoderOptions {
+            crf: Some(30.0),
+            preset: Some(EncoderPreset::Medium),
+            tune: Some(EncoderTune::Ssim),
+            profile: Some(EncoderProfile::High),
+            level: None,
+        };
+
+        let dict = options.to_dictionary(c"libaom-av1").unwrap();
+        assert_eq!(dict.get(c"crf"), Some(c"30"));
+        assert_eq!(dict.get(c"cpu-used"), Some(c"4"));
+        assert_eq!(dict.get(c"tune"), Some(c"ssim"));
+        assert_eq!(dict.get(c"profile"), Some(c"1"));
+
+        let options = EncoderOptions {
+            crf: Some(30.5),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_dictionary(c"libaom-av1").unwrap_err(),
+            FfmpegError::Arguments("crf must be a whole number between 0 and 63")
+        );
+
+        let options = EncoderOptions {
+            level: Some(EncoderLevel::new(4, 0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_dictionary(c"libaom-av1").unwrap_err(),
+            FfmpegError::Arguments("level is not supported by libaom-av1")
+        );
+    }
+
+    #[test]
+    fn test_encoder_options_validation() {
+        for crf in [-1.0, 51.5, f32::NAN] {
+            let options = EncoderOptions {
+                crf: Some(crf),
+                ..Default::default()
+            };
+            assert_eq!(
+                options.to_dictionary(c"libx264").unwrap_err(),
+                FfmpegError::Arguments("crf must be between 0 and 51")
+            );
+        }
+
+        let options = EncoderOptions {
+            level: Some(EncoderLevel::new(4, 3)),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_dictionary(c"libx264").unwrap_err(),
+            FfmpegError::Arguments("level is not valid for this encoder")
+        );
+    }
+
+    #[test]
+    fn test_encoder_level_display() {
+        let level = EncoderLevel::new(5, 2);
+        assert_eq!(level.major(), 5);
+        assert_eq!(level.minor(), 2);
+        assert_eq!(level.to_string(), "5.2");
+    }
+}
@@ -0,0 +1,164 @@
+use nutype_enum::{bitwise_enum, nutype_enum};
+
+use crate::ffi::*;
+
+const _: () = {
+    assert!(std::mem::size_of::<AVCodecFlags>() == std::mem::size_of::<std::ffi::c_int>());
+    assert!(std::mem::size_of::<AVCodecFlags2>() == std::mem::size_of::<std::ffi::c_int>());
+};
+
+nutype_enum! {
+    /// Codec flags used in FFmpeg's `AVCodecContext.flags`.
+    ///
+    /// These flags configure the behavior of encoders and decoders.
+    ///
+    /// See the official FFmpeg documentation:
+    /// <https://ffmpeg.org/doxygen/trunk/group__lavc__core.html>
+    pub enum AVCodecFlags(i32) {
+        /// Allow decoders to produce frames with data planes that are not aligned.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_UNALIGNED`
+        Unaligned = AV_CODEC_FLAG_UNALIGNED as _,
+
+        /// Use a fixed quality scale.
+        /// - **Used for**: Encoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_QSCALE`
+        QScale = AV_CODEC_FLAG_QSCALE as _,
+
+        /// Output frames even if they are corrupted.
+        /// - **Used for**: Decoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_OUTPUT_CORRUPT`
+        OutputCorrupt = AV_CODEC_FLAG_OUTPUT_CORRUPT as _,
+
+        /// Request the encoder to output reconstructed frames.
+        /// - **Used for**: Encoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_RECON_FRAME`
+        ReconFrame = AV_CODEC_FLAG_RECON_FRAME as _,
+
+        /// Copy the opaque field from packets to frames and vice versa.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_COPY_OPAQUE`
+        CopyOpaque = AV_CODEC_FLAG_COPY_OPAQUE as _,
+
+        /// Signal that the frame duration should be used when encoding.
+        /// - **Used for**: Encoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_FRAME_DURATION`
+        FrameDuration = AV_CODEC_FLAG_FRAME_DURATION as _,
+
+        /// Only decode or encode the grayscale (luma) plane.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_GRAY`
+        Gray = AV_CODEC_FLAG_GRAY as _,
+
+        /// Force low delay, frames are output as soon as they are decoded.
+        /// - **Used for**: Live decoding where latency matters more than reordering.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_LOW_DELAY`
+        LowDelay = AV_CODEC_FLAG_LOW_DELAY as _,
+
+        /// Place global headers in extradata instead of every keyframe.
+        /// - **Used for**: Encoding into containers that require global headers (e.g. mp4).
+        /// - **Equivalent to**: `AV_CODEC_FLAG_GLOBAL_HEADER`
+        GlobalHeader = AV_CODEC_FLAG_GLOBAL_HEADER as _,
+
+        /// Use only bitexact algorithms.
+        /// - **Used for**: Reproducible output, e.g. in tests.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_BITEXACT`
+        BitExact = AV_CODEC_FLAG_BITEXACT as _,
+
+        /// Only produce closed GOPs.
+        /// - **Used for**: Encoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG_CLOSED_GOP`
+        ClosedGop = AV_CODEC_FLAG_CLOSED_GOP as _,
+    }
+}
+
+bitwise_enum!(AVCodecFlags);
+
+impl PartialEq<i32> for AVCodecFlags {
+    fn eq(&self, other: &i32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl From<u32> for AVCodecFlags {
+    fn from(value: u32) -> Self {
+        AVCodecFlags(value as _)
+    }
+}
+
+impl From<AVCodecFlags> for u32 {
+    fn from(value: AVCodecFlags) -> Self {
+        value.0 as u32
+    }
+}
+
+nutype_enum! {
+    /// Additional codec flags used in FFmpeg's `AVCodecContext.flags2`.
+    ///
+    /// See the official FFmpeg documentation:
+    /// <https://ffmpeg.org/doxygen/trunk/group__lavc__core.html>
+    pub enum AVCodecFlags2(i32) {
+        /// Allow non spec compliant speedup tricks.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_FAST`
+        Fast = AV_CODEC_FLAG2_FAST as _,
+
+        /// Skip bitstream encoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_NO_OUTPUT`
+        NoOutput = AV_CODEC_FLAG2_NO_OUTPUT as _,
+
+        /// Place global headers at every keyframe instead of in extradata.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_LOCAL_HEADER`
+        LocalHeader = AV_CODEC_FLAG2_LOCAL_HEADER as _,
+
+        /// Input packets may contain partial frames instead of being aligned to frame boundaries.
+        /// - **Used for**: Decoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_CHUNKS`
+        Chunks = AV_CODEC_FLAG2_CHUNKS as _,
+
+        /// Discard the cropping information from the SPS.
+        /// - **Used for**: Decoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_IGNORE_CROP`
+        IgnoreCrop = AV_CODEC_FLAG2_IGNORE_CROP as _,
+
+        /// Show all frames before the first keyframe.
+        /// - **Used for**: Decoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_SHOW_ALL`
+        ShowAll = AV_CODEC_FLAG2_SHOW_ALL as _,
+
+        /// Export motion vectors through frame side data.
+        /// - **Used for**: Decoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_EXPORT_MVS`
+        ExportMvs = AV_CODEC_FLAG2_EXPORT_MVS as _,
+
+        /// Do not skip samples and export skip information as frame side data.
+        /// - **Used for**: Decoding.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_SKIP_MANUAL`
+        SkipManual = AV_CODEC_FLAG2_SKIP_MANUAL as _,
+
+        /// Do not reset the ASS ReadOrder field on flush.
+        /// - **Used for**: Decoding subtitles.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_RO_FLUSH_NOOP`
+        RoFlushNoop = AV_CODEC_FLAG2_RO_FLUSH_NOOP as _,
+
+        /// Generate or parse ICC profiles on encode / decode.
+        /// - **Equivalent to**: `AV_CODEC_FLAG2_ICC_PROFILES`
+        IccProfiles = AV_CODEC_FLAG2_ICC_PROFILES as _,
+    }
+}
+
+bitwise_enum!(AVCodecFlags2);
+
+impl PartialEq<i32> for AVCodecFlags2 {
+    fn eq(&self, other: &i32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl From<u32> for AVCodecFlags2 {
+    fn from(value: u32) -> Self {
+        AVCodecFlags2(value as _)
+    }
+}
+
+impl From<AVCodecFlags2> for u32 {
+    fn from(value: AVCodecFlags2) -> Self {
+        value.0 as u32
+    }
+}
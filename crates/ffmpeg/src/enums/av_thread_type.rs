@@ -0,0 +1,45 @@
+use nutype_enum::{bitwise_enum, nutype_enum};
+
+use crate::ffi::*;
+
+const _: () = {
+    assert!(std::mem::size_of::<AVThreadType>() == std::mem::size_of::<std::ffi::c_int>());
+};
+
+nutype_enum! {
+    /// Threading methods used in FFmpeg's `AVCodecContext.thread_type`.
+    ///
+    /// See the official FFmpeg documentation:
+    /// <https://ffmpeg.org/doxygen/trunk/structAVCodecContext.html>
+    pub enum AVThreadType(i32) {
+        /// Decode more than one frame at once.
+        /// - **Used for**: Throughput, adds one frame of delay per thread.
+        /// - **Equivalent to**: `FF_THREAD_FRAME`
+        Frame = FF_THREAD_FRAME as _,
+
+        /// Decode more than one part of a single frame at once.
+        /// - **Used for**: Low latency decoding, does not add any delay.
+        /// - **Equivalent to**: `FF_THREAD_SLICE`
+        Slice = FF_THREAD_SLICE as _,
+    }
+}
+
+bitwise_enum!(AVThreadType);
+
+impl PartialEq<i32> for AVThreadType {
+    fn eq(&self, other: &i32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl From<u32> for AVThreadType {
+    fn from(value: u32) -> Self {
+        AVThreadType(value as _)
+    }
+}
+
+impl From<AVThreadType> for u32 {
+    fn from(value: AVThreadType) -> Self {
+        value.0 as u32
+    }
+}
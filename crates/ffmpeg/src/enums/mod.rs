@@ -1,3 +1,6 @@
+mod av_codec_flags;
+pub use av_codec_flags::*;
+
 mod av_codec_id;
 pub use av_codec_id::*;
 
@@ -36,3 +39,6 @@ pub use av_pkt_flags::*;
 
 mod av_discard;
 pub use av_discard::*;
+
+mod av_thread_type;
+pub use av_thread_type::*;
@@ -1,5 +1,6 @@
 mod input;
 mod internal;
+mod muxer;
 mod output;
 
 /// A module that contains the channel implementation for io operations.
@@ -7,4 +8,5 @@ mod output;
 pub mod channel;
 
 pub use input::*;
+pub use muxer::*;
 pub use output::*;
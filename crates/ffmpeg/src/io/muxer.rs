@@ -0,0 +1,423 @@
+use std::ffi::CStr;
+use std::time::Duration;
+
+use nutype_enum::{bitwise_enum, nutype_enum};
+
+use crate::dict::Dictionary;
+use crate::error::FfmpegError;
+use crate::ffi::*;
+
+nutype_enum! {
+    /// Flags for the `mov` / `mp4` muxer.
+    ///
+    /// These are passed to the muxer as the `movflags` option.
+    ///
+    /// See the official FFmpeg documentation:
+    /// <https://ffmpeg.org/ffmpeg-formats.html#Options-11>
+    pub enum MovFlags(u32) {
+        /// Write an initial `moov` atom directly at the start of the file, without any samples in it.
+        /// - **Used for**: Fragmented output that is written to a non-seekable output.
+        /// - **Equivalent to**: `empty_moov`
+        EmptyMoov = 1 << 0,
+
+        /// Start a new fragment at each video keyframe.
+        /// - **Equivalent to**: `frag_keyframe`
+        FragKeyframe = 1 << 1,
+
+        /// Start a new fragment at each frame.
+        /// - **Equivalent to**: `frag_every_frame`
+        FragEveryFrame = 1 << 2,
+
+        /// Write a separate `moof` / `mdat` pair for each track.
+        /// - **Equivalent to**: `separate_moof`
+        SeparateMoof = 1 << 3,
+
+        /// Only start a new fragment when the caller flushes the muxer by writing a null packet.
+        /// - **Equivalent to**: `frag_custom`
+        FragCustom = 1 << 4,
+
+        /// Don't write any absolute base data offset in `tfhd` atoms.
+        /// - **Equivalent to**: `omit_tfhd_offset`
+        OmitTfhdOffset = 1 << 5,
+
+        /// Use the `default-base-is-moof` flag in `tfhd` atoms.
+        /// - **Used for**: Fragmented output targeting MSE compatible players.
+        /// - **Equivalent to**: `default_base_moof`
+        DefaultBaseMoof = 1 << 6,
+
+        /// Move the `moov` atom to the start of the file after writing the trailer.
+        /// - **Used for**: Progressive download, requires a seekable output.
+        /// - **Equivalent to**: `faststart`
+        FastStart = 1 << 7,
+
+        /// Write segments compatible with DASH.
+        /// - **Equivalent to**: `dash`
+        Dash = 1 << 8,
+
+        /// Write segments compatible with CMAF.
+        /// - **Equivalent to**: `cmaf`
+        Cmaf = 1 << 9,
+
+        /// Signal that the next fragment is discontinuous from the previous one.
+        /// - **Equivalent to**: `frag_discont`
+        FragDiscont = 1 << 10,
+
+        /// Delay writing the initial `moov` until the first fragment is cut or the first fragment flush.
+        /// - **Equivalent to**: `delay_moov`
+        DelayMoov = 1 << 11,
+
+        /// Write a global `sidx` index at the start of the file.
+        /// - **Equivalent to**: `global_sidx`
+        GlobalSidx = 1 << 12,
+
+        /// Don't write any `sidx` atoms.
+        /// - **Equivalent to**: `skip_sidx`
+        SkipSidx = 1 << 13,
+
+        /// Don't write the `mfra` trailer for fragmented output.
+        /// - **Equivalent to**: `skip_trailer`
+        SkipTrailer = 1 << 14,
+
+        /// Use negative composition time offsets in `trun` atoms.
+        /// - **Equivalent to**: `negative_cts_offsets`
+        NegativeCtsOffsets = 1 << 15,
+    }
+}
+
+bitwise_enum!(MovFlags);
+
+impl MovFlags {
+    const NAMES: &[(Self, &'static str)] = &[
+        (Self::EmptyMoov, "empty_moov"),
+        (Self::FragKeyframe, "frag_keyframe"),
+        (Self::FragEveryFrame, "frag_every_frame"),
+        (Self::SeparateMoof, "separate_moof"),
+        (Self::FragCustom, "frag_custom"),
+        (Self::OmitTfhdOffset, "omit_tfhd_offset"),
+        (Self::DefaultBaseMoof, "default_base_moof"),
+        (Self::FastStart, "faststart"),
+        (Self::Dash, "dash"),
+        (Self::Cmaf, "cmaf"),
+        (Self::FragDiscont, "frag_discont"),
+        (Self::DelayMoov, "delay_moov"),
+        (Self::GlobalSidx, "global_sidx"),
+        (Self::SkipSidx, "skip_sidx"),
+        (Self::SkipTrailer, "skip_trailer"),
+        (Self::NegativeCtsOffsets, "negative_cts_offsets"),
+    ];
+
+    /// Returns the flags in the format expected by the `movflags` option, e.g. `frag_keyframe+empty_moov`.
+    ///
+    /// Returns `None` if no flags are set.
+    pub fn option_value(self) -> Option<String> {
+        flags_option_value(Self::NAMES, |flag| self & flag == flag)
+    }
+}
+
+nutype_enum! {
+    /// Flags for the `mpegts` muxer.
+    ///
+    /// These are passed to the muxer as the `mpegts_flags` option.
+    ///
+    /// See the official FFmpeg documentation:
+    /// <https://ffmpeg.org/ffmpeg-formats.html#Options-14>
+    pub enum MpegTsFlags(u32) {
+        /// Re-send the PAT and PMT at each video keyframe.
+        /// - **Equivalent to**: `resend_headers`
+        ResendHeaders = 1 << 0,
+
+        /// Use LATM packetization for AAC.
+        /// - **Equivalent to**: `latm`
+        Latm = 1 << 1,
+
+        /// Re-send the PAT and PMT at each video frame.
+        /// - **Equivalent to**: `pat_pmt_at_frames`
+        PatPmtAtFrames = 1 << 2,
+
+        /// Conform to System B (DVB) instead of System A (ATSC).
+        /// - **Equivalent to**: `system_b`
+        SystemB = 1 << 3,
+
+        /// Mark the initial packet of each stream as a discontinuity.
+        /// - **Used for**: Segments that are not played back in a continuous stream (e.g. HLS).
+        /// - **Equivalent to**: `initial_discontinuity`
+        InitialDiscontinuity = 1 << 4,
+
+        /// Emit a network information table.
+        /// - **Equivalent to**: `nit`
+        Nit = 1 << 5,
+    }
+}
+
+bitwise_enum!(MpegTsFlags);
+
+impl MpegTsFlags {
+    const NAMES: &[(Self, &'static str)] = &[
+        (Self::ResendHeaders, "resend_headers"),
+        (Self::Latm, "latm"),
+        (Self::PatPmtAtFrames, "pat_pmt_at_frames"),
+        (Self::SystemB, "system_b"),
+        (Self::InitialDiscontinuity, "initial_discontinuity"),
+        (Self::Nit, "nit"),
+    ];
+
+    /// Returns the flags in the format expected by the `mpegts_flags` option, e.g. `resend_headers+initial_discontinuity`.
+    ///
+    /// Returns `None` if no flags are set.
+    pub fn option_value(self) -> Option<String> {
+        flags_option_value(Self::NAMES, |flag| self & flag == flag)
+    }
+}
+
+fn flags_option_value<F: Copy>(names: &[(F, &'static str)], contains: impl Fn(F) -> bool) -> Option<String> {
+    let value = names
+        .iter()
+        .filter(|(flag, _)| contains(*flag))
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join("+");
+
+    (!value.is_empty()).then_some(value)
+}
+
+/// Formats a duration for a `AV_OPT_TYPE_DURATION` option.
+fn duration_option_value(duration: Duration) -> String {
+    format!("{}us", duration.as_micros())
+}
+
+/// Formats a duration for an integer option that is expressed in microseconds.
+fn micros_option_value(duration: Duration) -> String {
+    duration.as_micros().min(i32::MAX as u128).to_string()
+}
+
+/// Options for the `mov` / `mp4` muxer.
+#[derive(Debug, Clone, Default, bon::Builder)]
+pub struct MovOptions {
+    /// The flags for the muxer.
+    flags: Option<MovFlags>,
+    /// Start a new fragment after the given duration has passed.
+    ///
+    /// Durations longer than `i32::MAX` microseconds are clamped.
+    frag_duration: Option<Duration>,
+    /// Start a new fragment once it has reached the given size in bytes.
+    frag_size: Option<u32>,
+    /// The minimum duration of a fragment.
+    min_frag_duration: Option<Duration>,
+}
+
+impl MovOptions {
+    pub(crate) fn apply(&self, options: &mut Dictionary) -> Result<(), FfmpegError> {
+        if let Some(flags) = self.flags.and_then(MovFlags::option_value) {
+            options.set(c"movflags", flags)?;
+        }
+
+        if let Some(frag_duration) = self.frag_duration {
+            options.set(c"frag_duration", micros_option_value(frag_duration))?;
+        }
+
+        if let Some(frag_size) = self.frag_size {
+            options.set(c"frag_size", frag_size.to_string())?;
+        }
+
+        if let Some(min_frag_duration) = self.min_frag_duration {
+            options.set(c"min_frag_duration", micros_option_value(min_frag_duration))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for the `mpegts` muxer.
+#[derive(Debug, Clone, Default, bon::Builder)]
+pub struct MpegTsOptions {
+    /// The flags for the muxer.
+    flags: Option<MpegTsFlags>,
+    /// The `transport_stream_id` written to the PAT.
+    transport_stream_id: Option<u16>,
+    /// The `original_network_id` written to the SDT.
+    original_network_id: Option<u16>,
+    /// The `service_id` (program number) written to the PAT and PMT.
+    service_id: Option<u16>,
+    /// The PID of the first PMT. Must be in the range `0x0020..=0x1ffa`.
+    pmt_start_pid: Option<u16>,
+    /// The PID of the first elementary stream. Must be in the range `0x0020..=0x1ffa`.
+    start_pid: Option<u16>,
+    /// Write BDAV (`m2ts`) packets instead of plain 188 byte transport stream packets.
+    m2ts_mode: Option<bool>,
+    /// The minimum size of a PES packet payload in bytes.
+    pes_payload_size: Option<u32>,
+    /// Keep the original timestamps instead of shifting them to start at zero.
+    copyts: Option<bool>,
+    /// The maximum time between PAT / PMT tables.
+    pat_period: Option<Duration>,
+    /// The maximum time between SDT tables.
+    sdt_period: Option<Duration>,
+}
+
+impl MpegTsOptions {
+    pub(crate) fn apply(&self, options: &mut Dictionary) -> Result<(), FfmpegError> {
+        if let Some(flags) = self.flags.and_then(MpegTsFlags::option_value) {
+            options.set(c"mpegts_flags", flags)?;
+        }
+
+        let ids = [
+            (c"mpegts_transport_stream_id", self.transport_stream_id),
+            (c"mpegts_original_network_id", self.original_network_id),
+            (c"mpegts_service_id", self.service_id),
+            (c"mpegts_pmt_start_pid", self.pmt_start_pid),
+            (c"mpegts_start_pid", self.start_pid),
+        ];
+
+        for (key, value) in ids {
+            if let Some(value) = value {
+                options.set(key, value.to_string())?;
+            }
+        }
+
+        let bools = [(c"mpegts_m2ts_mode", self.m2ts_mode), (c"mpegts_copyts", self.copyts)];
+
+        for (key, value) in bools {
+            if let Some(value) = value {
+                options.set(key, if value { c"1" } else { c"0" })?;
+            }
+        }
+
+        if let Some(pes_payload_size) = self.pes_payload_size {
+            options.set(c"pes_payload_size", pes_payload_size.to_string())?;
+        }
+
+        if let Some(pat_period) = self.pat_period {
+            options.set(c"pat_period", duration_option_value(pat_period))?;
+        }
+
+        if let Some(sdt_period) = self.sdt_period {
+            options.set(c"sdt_period", duration_option_value(sdt_period))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that every option in `options` is a private option of the given output format.
+pub(crate) fn check_format_options(format: *const AVOutputFormat, options: &Dictionary) -> Result<(), FfmpegError> {
+    // Safety: The format is either null or a valid pointer.
+    let priv_class = unsafe { format.as_ref() }
+        .map(|format| format.priv_class)
+        .unwrap_or(std::ptr::null());
+
+    for (key, _) in options {
+        if priv_class.is_null() || !has_option(&priv_class, key) {
+            return Err(FfmpegError::Arguments("muxer option is not supported by the output format"));
+        }
+    }
+
+    Ok(())
+}
+
+fn has_option(priv_class: &*const AVClass, name: &CStr) -> bool {
+    // Safety: `av_opt_find` is safe to call with a pointer to a class pointer when searching with `AV_OPT_SEARCH_FAKE_OBJ`.
+    let option = unsafe {
+        av_opt_find(
+            priv_class as *const *const AVClass as *mut _,
+            name.as_ptr(),
+            std::ptr::null(),
+            0,
+            AV_OPT_SEARCH_FAKE_OBJ as _,
+        )
+    };
+
+    !option.is_null()
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::time::Duration;
+
+    use super::{MovFlags, MovOptions, MpegTsFlags, MpegTsOptions};
+    use crate::dict::Dictionary;
+
+    fn collect(options: &Dictionary) -> Vec<(String, String)> {
+        options
+            .iter()
+            .map(|(key, value)| (key.to_string_lossy().into_owned(), value.to_string_lossy().into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_mov_flags_option_value() {
+        assert_eq!(MovFlags(0).option_value(), None);
+        assert_eq!(
+            (MovFlags::EmptyMoov | MovFlags::FragKeyframe | MovFlags::DefaultBaseMoof).option_value(),
+            Some("empty_moov+frag_keyframe+default_base_moof".to_string())
+        );
+        assert_eq!(MovFlags::FastStart.option_value(), Some("faststart".to_string()));
+    }
+
+    #[test]
+    fn test_mpegts_flags_option_value() {
+        assert_eq!(MpegTsFlags(0).option_value(), None);
+        assert_eq!(
+            (MpegTsFlags::ResendHeaders | MpegTsFlags::InitialDiscontinuity).option_value(),
+            Some("resend_headers+initial_discontinuity".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mov_options_apply() {
+        let mut options = Dictionary::new();
+        MovOptions::builder()
+            .flags(MovFlags::FragKeyframe | MovFlags::EmptyMoov)
+            .frag_duration(Duration::from_secs(2))
+            .build()
+            .apply(&mut options)
+            .expect("Failed to apply options");
+
+        insta::assert_debug_snapshot!(collect(&options), @r#"
+        [
+            (
+                "movflags",
+                "empty_moov+frag_keyframe",
+            ),
+            (
+                "frag_duration",
+                "2000000",
+            ),
+        ]
+        "#);
+    }
+
+    #[test]
+    fn test_mpegts_options_apply() {
+        let mut options = Dictionary::new();
+        MpegTsOptions::builder()
+            .flags(MpegTsFlags::ResendHeaders)
+            .service_id(1)
+            .m2ts_mode(false)
+            .pat_period(Duration::from_millis(100))
+            .build()
+            .apply(&mut options)
+            .expect("Failed to apply options");
+
+        insta::assert_debug_snapshot!(collect(&options), @r#"
+        [
+            (
+                "mpegts_flags",
+                "resend_headers",
+            ),
+            (
+                "mpegts_service_id",
+                "1",
+            ),
+            (
+                "mpegts_m2ts_mode",
+                "0",
+            ),
+            (
+                "pat_period",
+                "100000us",
+            ),
+        ]
+        "#);
+    }
+}
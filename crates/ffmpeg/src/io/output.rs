@@ -1,7 +1,9 @@
 use std::ffi::CString;
 use std::ptr::NonNull;
+use std::time::Duration;
 
 use super::internal::{Inner, InnerOptions, seek, write_packet};
+use super::muxer::{MovOptions, MpegTsOptions, check_format_options};
 use crate::consts::DEFAULT_BUFFER_SIZE;
 use crate::dict::Dictionary;
 use crate::error::{FfmpegError, FfmpegErrorCode};
@@ -18,6 +20,35 @@ pub struct OutputOptions {
     buffer_size: usize,
     #[builder(setters(vis = "", name = format_ffi_internal))]
     format_ffi: *const AVOutputFormat,
+    /// Options for the `mov` / `mp4` muxer, e.g. to write fragmented mp4.
+    mov: Option<MovOptions>,
+    /// Options for the `mpegts` muxer.
+    mpegts: Option<MpegTsOptions>,
+    /// The maximum buffering duration for interleaving packets.
+    ///
+    /// A duration of zero makes the muxer wait until it has a packet for every stream before writing.
+    max_interleave_delta: Option<Duration>,
+    /// Whether the output should be flushed after every packet.
+    flush_packets: Option<bool>,
+}
+
+impl OutputOptions {
+    /// The muxer options that are passed to the output format when writing the header.
+    fn muxer_options(&self) -> Result<Dictionary, FfmpegError> {
+        let mut options = Dictionary::new();
+
+        if let Some(mov) = &self.mov {
+            mov.apply(&mut options)?;
+        }
+
+        if let Some(mpegts) = &self.mpegts {
+            mpegts.apply(&mut options)?;
+        }
+
+        check_format_options(self.format_ffi, &options)?;
+
+        Ok(options)
+    }
 }
 
 impl<S: output_options_builder::State> OutputOptionsBuilder<S> {
@@ -91,6 +122,7 @@ impl<S: output_options_builder::State> OutputOptionsBuilder<S> {
 pub struct Output<T: Send + Sync> {
     inner: Inner<T>,
     state: OutputState,
+    muxer_options: Dictionary,
 }
 
 /// Safety: `T` must be `Send` and `Sync`.
@@ -104,6 +136,25 @@ enum OutputState {
 }
 
 impl<T: Send + Sync> Output<T> {
+    fn from_inner(mut inner: Inner<T>, options: &OutputOptions) -> Result<Self, FfmpegError> {
+        let muxer_options = options.muxer_options()?;
+        let context = inner.context.as_deref_mut_except();
+
+        if let Some(max_interleave_delta) = options.max_interleave_delta {
+            context.max_interleave_delta = max_interleave_delta.as_micros().try_into().unwrap_or(i64::MAX);
+        }
+
+        if let Some(flush_packets) = options.flush_packets {
+            context.flush_packets = flush_packets as _;
+        }
+
+        Ok(Self {
+            inner,
+            state: OutputState::Uninitialized,
+            muxer_options,
+        })
+    }
+
     /// Consumes the `Output` and returns the inner data.
     pub fn into_inner(mut self) -> T {
         *(self.inner.data.take().unwrap())
@@ -113,18 +164,17 @@ impl<T: Send + Sync> Output<T> {
 impl<T: std::io::Write + Send + Sync> Output<T> {
     /// Creates a new `Output` with the given output and options.
     pub fn new(output: T, options: OutputOptions) -> Result<Self, FfmpegError> {
-        Ok(Self {
-            inner: Inner::new(
-                output,
-                InnerOptions {
-                    buffer_size: options.buffer_size,
-                    write_fn: Some(write_packet::<T>),
-                    output_format: options.format_ffi,
-                    ..Default::default()
-                },
-            )?,
-            state: OutputState::Uninitialized,
-        })
+        let inner = Inner::new(
+            output,
+            InnerOptions {
+                buffer_size: options.buffer_size,
+                write_fn: Some(write_packet::<T>),
+                output_format: options.format_ffi,
+                ..Default::default()
+            },
+        )?;
+
+        Self::from_inner(inner, &options)
     }
 
     /// Creates a new `Output` with the given output and options. The output must be seekable.
@@ -132,19 +182,18 @@ impl<T: std::io::Write + Send + Sync> Output<T> {
     where
         T: std::io::Seek,
     {
-        Ok(Self {
-            inner: Inner::new(
-                output,
-                InnerOptions {
-                    buffer_size: options.buffer_size,
-                    write_fn: Some(write_packet::<T>),
-                    seek_fn: Some(seek::<T>),
-                    output_format: options.format_ffi,
-                    ..Default::default()
-                },
-            )?,
-            state: OutputState::Uninitialized,
-        })
+        let inner = Inner::new(
+            output,
+            InnerOptions {
+                buffer_size: options.buffer_size,
+                write_fn: Some(write_packet::<T>),
+                seek_fn: Some(seek::<T>),
+                output_format: options.format_ffi,
+                ..Default::default()
+            },
+        )?;
+
+        Self::from_inner(inner, &options)
     }
 }
 
@@ -210,25 +259,28 @@ impl<T: Send + Sync> Output<T> {
     }
 
     /// Writes the header to the output.
+    ///
+    /// The muxer options from the [`OutputOptions`] are passed to the output format.
     pub fn write_header(&mut self) -> Result<(), FfmpegError> {
-        if self.state != OutputState::Uninitialized {
-            return Err(FfmpegError::Arguments("header already written"));
-        }
-
-        // Safety: `avformat_write_header` is safe to call, if the header has not been
-        // written yet.
-        FfmpegErrorCode(unsafe { avformat_write_header(self.as_mut_ptr(), std::ptr::null_mut()) }).result()?;
-        self.state = OutputState::HeaderWritten;
-
-        Ok(())
+        self.write_header_with_options(&mut Dictionary::new())
     }
 
     /// Writes the header to the output with the given options.
+    ///
+    /// The muxer options from the [`OutputOptions`] are added to the given options,
+    /// unless an option with the same key is already present.
+    /// After this call `options` contains the options that were not consumed by the muxer.
     pub fn write_header_with_options(&mut self, options: &mut Dictionary) -> Result<(), FfmpegError> {
         if self.state != OutputState::Uninitialized {
             return Err(FfmpegError::Arguments("header already written"));
         }
 
+        for (key, value) in &self.muxer_options {
+            if !options.iter().any(|(existing, _)| existing == key) {
+                options.set(key, value)?;
+            }
+        }
+
         // Safety: `avformat_write_header` is safe to call, if the header has not been
         // written yet.
         FfmpegErrorCode(unsafe { avformat_write_header(self.as_mut_ptr(), options.as_mut_ptr_ref()) }).result()?;
@@ -304,6 +356,7 @@ impl Output<()> {
         Ok(Self {
             inner: Inner::open_output(path)?,
             state: OutputState::Uninitialized,
+            muxer_options: Dictionary::new(),
         })
     }
 }
@@ -315,6 +368,7 @@ mod tests {
     use std::io::{Cursor, Write};
     use std::path::PathBuf;
     use std::ptr;
+    use std::time::Duration;
 
     use bytes::{Buf, Bytes};
     use sha2::Digest;
@@ -323,7 +377,7 @@ mod tests {
     use crate::dict::Dictionary;
     use crate::error::FfmpegError;
     use crate::io::output::{AVCodec, AVRational, OutputState};
-    use crate::io::{Input, Output, OutputOptions};
+    use crate::io::{Input, MovFlags, MovOptions, MpegTsFlags, MpegTsOptions, Output, OutputOptions};
     use crate::{AVFmtFlags, AVMediaType};
 
     #[test]
@@ -589,4 +643,117 @@ mod tests {
 
         insta::assert_debug_snapshot!("test_output_write_mp4_fragmented_trailer", get_boxes!(output));
     }
+
+    fn write_fragmented_mp4(options: OutputOptions, header_options: &mut Dictionary) -> Vec<u8> {
+        let mut output = Output::new(Vec::new(), options).expect("Failed to create Output");
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+
+        let mut input = Input::seekable(std::fs::File::open(dir.join("avc_aac.mp4")).expect("Failed to open file"))
+            .expect("Failed to create Input");
+        let streams = input.streams();
+        let best_video_stream = streams.best(AVMediaType::Video).expect("no video stream found");
+        let best_video_stream_index = best_video_stream.index();
+
+        output.copy_stream(&best_video_stream).expect("Failed to copy stream");
+        output
+            .write_header_with_options(header_options)
+            .expect("Failed to write header");
+
+        while let Some(packet) = input.receive_packet().expect("Failed to receive packet") {
+            if packet.stream_index() == best_video_stream_index {
+                output.write_interleaved_packet(packet).expect("Failed to write packet");
+            }
+        }
+
+        output.write_trailer().expect("Failed to write trailer");
+        output.into_inner()
+    }
+
+    #[test]
+    fn test_output_mov_options() {
+        let options = OutputOptions::builder()
+            .format_name("mp4")
+            .unwrap()
+            .mov(
+                MovOptions::builder()
+                    .flags(MovFlags::FragKeyframe | MovFlags::EmptyMoov)
+                    .build(),
+            )
+            .max_interleave_delta(Duration::ZERO)
+            .flush_packets(true)
+            .build();
+
+        let typed = write_fragmented_mp4(options, &mut Dictionary::new());
+
+        let options = OutputOptions::builder().format_name("mp4").unwrap().build();
+        let raw = write_fragmented_mp4(
+            options,
+            &mut Dictionary::try_from_iter([("movflags", "frag_keyframe+empty_moov")])
+                .expect("Failed to create dictionary from hashmap"),
+        );
+
+        assert_eq!(typed, raw, "Expected typed options to match the raw movflags option");
+    }
+
+    #[test]
+    fn test_output_muxer_options_override() {
+        let options = OutputOptions::builder()
+            .format_name("mp4")
+            .unwrap()
+            .mov(MovOptions::builder().flags(MovFlags::FastStart).build())
+            .build();
+        let mut output = Output::new(Vec::new(), options).expect("Failed to create Output");
+
+        let mut header_options = Dictionary::try_from_iter([("movflags", "frag_keyframe+empty_moov")])
+            .expect("Failed to create dictionary from hashmap");
+        output
+            .write_header_with_options(&mut header_options)
+            .expect("Failed to write header");
+
+        assert!(header_options.is_empty(), "Expected all options to be consumed");
+    }
+
+    #[test]
+    fn test_output_mpegts_options() {
+        let options = OutputOptions::builder()
+            .format_name("mpegts")
+            .unwrap()
+            .mpegts(
+                MpegTsOptions::builder()
+                    .flags(MpegTsFlags::ResendHeaders | MpegTsFlags::InitialDiscontinuity)
+                    .service_id(1)
+                    .pat_period(Duration::from_millis(100))
+                    .build(),
+            )
+            .build();
+        let mut output = Output::new(Vec::new(), options).expect("Failed to create Output");
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+
+        let input = Input::seekable(std::fs::File::open(dir.join("avc_aac.mp4")).expect("Failed to open file"))
+            .expect("Failed to create Input");
+        let streams = input.streams();
+        let best_video_stream = streams.best(AVMediaType::Video).expect("no video stream found");
+        output.copy_stream(&best_video_stream).expect("Failed to copy stream");
+
+        let mut header_options = Dictionary::new();
+        output
+            .write_header_with_options(&mut header_options)
+            .expect("Failed to write header");
+
+        assert!(header_options.is_empty(), "Expected all muxer options to be consumed");
+    }
+
+    #[test]
+    fn test_output_muxer_options_unsupported_format() {
+        let options = OutputOptions::builder()
+            .format_name("mpegts")
+            .unwrap()
+            .mov(MovOptions::builder().flags(MovFlags::FragKeyframe).build())
+            .build();
+
+        assert!(matches!(
+            Output::new(Vec::new(), options),
+            Err(FfmpegError::Arguments("muxer option is not supported by the output format"))
+        ));
+    }
 }
@@ -240,6 +240,11 @@
 #![deny(clippy::undocumented_unsafe_blocks)]
 #![deny(clippy::multiple_unsafe_ops_per_block)]
 
+/// Async wrappers which run decoders and encoders on blocking threads.
+#[cfg(feature = "tokio")]
+pub mod async_codec;
+/// Bitstream filter specific functionality.
+pub mod bitstream_filter;
 /// Codec specific functionality.
 pub mod codec;
 /// Constants.
@@ -250,6 +255,8 @@ pub mod decoder;
 pub mod dict;
 /// Encoder specific functionality.
 pub mod encoder;
+/// Typed options for common video encoders.
+pub mod encoder_options;
 /// Error handling.
 pub mod error;
 /// Filter graph specific functionality.
@@ -0,0 +1,67 @@
+use std::hint::black_box;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use scuffle_flv::file::FlvFile;
+use scuffle_flv::options::DemuxOptions;
+
+/// The size of the synthetic VOD file, large enough to not fit into the CPU caches.
+const VOD_SIZE: usize = 64 * 1024 * 1024;
+
+fn asset(name: &str) -> Bytes {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets").join(name);
+    std::fs::read(path).expect("failed to read asset").into()
+}
+
+/// Builds a large file by repeating the tags of the given file until it is at least `size` bytes.
+fn repeat_tags(file: &Bytes, size: usize) -> Bytes {
+    // The header is 9 bytes followed by the first PreviousTagSize.
+    let (header, tags) = file.split_at(13);
+
+    let mut buf = BytesMut::with_capacity(size + file.len());
+    buf.put_slice(header);
+    while buf.len() < size {
+        buf.put_slice(tags);
+    }
+
+    buf.freeze()
+}
+
+fn demux(c: &mut Criterion) {
+    let mut group = c.benchmark_group("demux");
+
+    for name in ["avc_aac.flv", "avc_aac_long.flv", "av1_aac.flv", "hevc_aac.flv"] {
+        let file = asset(name);
+        group.throughput(Throughput::Bytes(file.len() as u64));
+        group.bench_with_input(BenchmarkId::new("file", name), &file, |b, file| {
+            b.iter(|| FlvFile::demux(&mut Cursor::new(black_box(file.clone()))).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+fn scan(c: &mut Criterion) {
+    let file = repeat_tags(&asset("avc_aac_long.flv"), VOD_SIZE);
+    // Skipping every tag only parses the tag headers, like a scan for the duration or keyframes of a VOD.
+    let headers_only = DemuxOptions::default().with_max_tag_size(0).with_skip_oversized_tags(true);
+
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(file.len() as u64));
+
+    group.bench_with_input(BenchmarkId::new("vod", "tags"), &file, |b, file| {
+        b.iter(|| FlvFile::demux(&mut Cursor::new(black_box(file.clone()))).unwrap());
+    });
+
+    group.bench_with_input(BenchmarkId::new("vod", "headers"), &file, |b, file| {
+        b.iter(|| FlvFile::demux_with_options(&mut Cursor::new(black_box(file.clone())), &headers_only).unwrap());
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, demux, scan);
+criterion_main!(benches);
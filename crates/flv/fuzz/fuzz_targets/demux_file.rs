@@ -0,0 +1,9 @@
+#![no_main]
+
+use bytes::Bytes;
+use libfuzzer_sys::fuzz_target;
+use scuffle_flv::file::FlvFile;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = FlvFile::demux(&mut std::io::Cursor::new(Bytes::copy_from_slice(data)));
+});
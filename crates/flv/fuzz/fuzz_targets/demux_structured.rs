@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scuffle_flv::file::FlvFile;
+use scuffle_flv::fuzz::FuzzFlvFile;
+
+fuzz_target!(|file: FuzzFlvFile| {
+    let _ = FlvFile::demux(&mut std::io::Cursor::new(file.to_bytes()));
+});
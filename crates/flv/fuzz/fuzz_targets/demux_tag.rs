@@ -0,0 +1,10 @@
+#![no_main]
+
+use bytes::Bytes;
+use libfuzzer_sys::fuzz_target;
+use scuffle_flv::tag::{FlvTagData, FlvTagType};
+
+fuzz_target!(|input: (FlvTagType, Vec<u8>)| {
+    let (tag_type, data) = input;
+    let _ = FlvTagData::demux(tag_type, &mut std::io::Cursor::new(Bytes::from(data)));
+});
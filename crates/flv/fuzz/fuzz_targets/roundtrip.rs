@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scuffle_flv::file::FlvFile;
+
+fuzz_target!(|file: FlvFile<'static>| {
+    let bytes = scuffle_flv::fuzz::write_file(&file);
+    let demuxed = FlvFile::demux(&mut std::io::Cursor::new(bytes)).expect("failed to demux a written file");
+    assert_eq!(demuxed, file);
+});
@@ -42,3 +42,171 @@ impl AudioData {
         Ok(AudioData { header, body })
     }
 }
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AudioData {
+    /// Generates audio data that demuxes back into the same value.
+    ///
+    /// For that the header and the body always match and enhanced timestamp offsets are
+    /// exactly 3 bytes long.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use body::enhanced::{
+            AudioChannel, AudioChannelMask, AudioChannelOrder, AudioPacket, AudioTrack, ExAudioTagBody,
+            MultichannelConfigOrder,
+        };
+        use body::legacy::LegacyAudioTagBody;
+        use body::legacy::aac::{AacAudioData, AacPacketType};
+        use header::enhanced::{
+            AudioFourCc, AudioPacketModEx, AudioPacketModExType, AudioPacketType, ExAudioTagHeader, ExAudioTagHeaderContent,
+        };
+        use header::legacy::{LegacyAudioTagHeader, SoundFormat, SoundRate, SoundSize, SoundType};
+
+        use crate::common::AvMultitrackType;
+        use crate::fuzz::arbitrary_bytes;
+
+        if u.arbitrary()? {
+            let header = LegacyAudioTagHeader {
+                // Everything except the enhanced header marker.
+                sound_format: SoundFormat(*u.choose(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 12, 13, 14, 15])?),
+                sound_rate: SoundRate(u.int_in_range(0..=3)?),
+                sound_size: SoundSize(u.int_in_range(0..=1)?),
+                sound_type: SoundType(u.int_in_range(0..=1)?),
+            };
+
+            let body = if header.sound_format == SoundFormat::Aac {
+                let aac_packet_type = AacPacketType(u.arbitrary()?);
+                LegacyAudioTagBody::Aac(AacAudioData::new(aac_packet_type, arbitrary_bytes(u)?))
+            } else {
+                LegacyAudioTagBody::Other {
+                    sound_data: arbitrary_bytes(u)?,
+                }
+            };
+
+            return Ok(Self {
+                header: AudioTagHeader::Legacy(header),
+                body: AudioTagBody::Legacy(body),
+            });
+        }
+
+        let mut audio_packet_mod_exs = Vec::new();
+        while u.arbitrary()? {
+            let audio_packet_mod_ex_type = AudioPacketModExType(u.int_in_range(0..=15)?);
+
+            audio_packet_mod_exs.push(if audio_packet_mod_ex_type == AudioPacketModExType::TimestampOffsetNano {
+                AudioPacketModEx::TimestampOffsetNano {
+                    audio_timestamp_nano_offset: u.int_in_range(0..=0xFFFFFF)?,
+                }
+            } else {
+                // The data is between 1 and 65536 bytes long.
+                let mut mod_ex_data = u.arbitrary::<Vec<u8>>()?;
+                mod_ex_data.resize(mod_ex_data.len().clamp(1, 0x10000), 0);
+
+                AudioPacketModEx::Other {
+                    audio_packet_mod_ex_type,
+                    mod_ex_data: mod_ex_data.into(),
+                }
+            });
+        }
+
+        // Everything except the multitrack and modifier extension markers.
+        let audio_packet_type = AudioPacketType(*u.choose(&[0, 1, 2, 3, 4, 6, 8, 9, 10, 11, 12, 13, 14, 15])?);
+
+        let content = match u.int_in_range(0..=4)? {
+            0 => ExAudioTagHeaderContent::NoMultiTrack(AudioFourCc(u.arbitrary()?)),
+            1 => ExAudioTagHeaderContent::OneTrack(AudioFourCc(u.arbitrary()?)),
+            2 => ExAudioTagHeaderContent::ManyTracks(AudioFourCc(u.arbitrary()?)),
+            3 => ExAudioTagHeaderContent::ManyTracksManyCodecs,
+            _ => ExAudioTagHeaderContent::Unknown {
+                audio_multitrack_type: AvMultitrackType(u.int_in_range(3..=15)?),
+                audio_four_cc: AudioFourCc(u.arbitrary()?),
+            },
+        };
+
+        fn packet(
+            u: &mut arbitrary::Unstructured<'_>,
+            audio_packet_type: AudioPacketType,
+        ) -> arbitrary::Result<AudioPacket> {
+            Ok(match audio_packet_type {
+                AudioPacketType::MultichannelConfig => {
+                    let (channel_count, multichannel_config) = match u.int_in_range(0..=3)? {
+                        0 => {
+                            let mut channels = u.arbitrary::<Vec<u8>>()?;
+                            channels.truncate(u8::MAX as usize);
+
+                            (
+                                channels.len() as u8,
+                                MultichannelConfigOrder::Custom(channels.into_iter().map(AudioChannel).collect()),
+                            )
+                        }
+                        1 => (
+                            u.arbitrary()?,
+                            MultichannelConfigOrder::Native(AudioChannelMask::from(u.arbitrary::<u32>()?)),
+                        ),
+                        2 => (u.arbitrary()?, MultichannelConfigOrder::Unspecified),
+                        _ => (
+                            u.arbitrary()?,
+                            MultichannelConfigOrder::Unknown(AudioChannelOrder(u.int_in_range(3..=u8::MAX)?)),
+                        ),
+                    };
+
+                    AudioPacket::MultichannelConfig {
+                        channel_count,
+                        multichannel_config,
+                    }
+                }
+                AudioPacketType::SequenceEnd => AudioPacket::SequenceEnd,
+                AudioPacketType::SequenceStart => AudioPacket::SequenceStart {
+                    header_data: arbitrary_bytes(u)?,
+                },
+                AudioPacketType::CodedFrames => AudioPacket::CodedFrames {
+                    data: arbitrary_bytes(u)?,
+                },
+                _ => AudioPacket::Unknown {
+                    audio_packet_type,
+                    data: arbitrary_bytes(u)?,
+                },
+            })
+        }
+
+        let body = match &content {
+            ExAudioTagHeaderContent::NoMultiTrack(audio_four_cc) => ExAudioTagBody::NoMultitrack {
+                audio_four_cc: *audio_four_cc,
+                packet: packet(u, audio_packet_type)?,
+            },
+            content => {
+                let mut tracks = Vec::new();
+
+                // There is always at least one track.
+                loop {
+                    let audio_four_cc = match content {
+                        ExAudioTagHeaderContent::OneTrack(audio_four_cc)
+                        | ExAudioTagHeaderContent::ManyTracks(audio_four_cc)
+                        | ExAudioTagHeaderContent::Unknown { audio_four_cc, .. } => *audio_four_cc,
+                        _ => AudioFourCc(u.arbitrary()?),
+                    };
+
+                    tracks.push(AudioTrack {
+                        audio_four_cc,
+                        audio_track_id: u.arbitrary()?,
+                        packet: packet(u, audio_packet_type)?,
+                    });
+
+                    if matches!(content, ExAudioTagHeaderContent::OneTrack(_)) || !u.arbitrary()? {
+                        break;
+                    }
+                }
+
+                ExAudioTagBody::ManyTracks(tracks)
+            }
+        };
+
+        Ok(Self {
+            header: AudioTagHeader::Enhanced(ExAudioTagHeader {
+                audio_packet_mod_exs,
+                audio_packet_type,
+                content,
+            }),
+            body: AudioTagBody::Enhanced(body),
+        })
+    }
+}
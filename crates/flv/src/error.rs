@@ -21,6 +21,16 @@ pub enum FlvError {
         /// The expected number of bytes.
         expected_bytes: usize,
     },
+    /// A tag exceeded one of the limits of the [`DemuxOptions`](crate::options::DemuxOptions).
+    #[error("{tag_type:?} tag of {size} bytes exceeds the limit of {max} bytes")]
+    TagTooLarge {
+        /// The type of the tag.
+        tag_type: crate::tag::FlvTagType,
+        /// The declared size of the tag data.
+        size: u32,
+        /// The limit that was exceeded.
+        max: u32,
+    },
     /// AMF0 error.
     #[error("amf0: {0}")]
     Amf0(#[from] scuffle_amf0::Amf0Error),
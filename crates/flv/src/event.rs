@@ -0,0 +1,397 @@
+//! Normalized media events.
+//!
+//! Legacy and enhanced audio and video tags describe the same things in different ways.
+//! [`VideoData::events`] and [`AudioData::events`] turn both into the same small set of events,
+//! so consumers do not have to match on every legacy and enhanced body themselves.
+//!
+//! The events borrow from the demuxed tag, the detailed structs are still accessible through it.
+
+use bytes::Bytes;
+use scuffle_av1::AV1CodecConfigurationRecord;
+use scuffle_h264::AVCDecoderConfigurationRecord;
+use scuffle_h265::HEVCDecoderConfigurationRecord;
+
+use crate::audio::AudioData;
+use crate::audio::body::AudioTagBody;
+use crate::audio::body::enhanced::{AudioPacket, ExAudioTagBody};
+use crate::audio::body::legacy::LegacyAudioTagBody;
+use crate::audio::body::legacy::aac::AacAudioData;
+use crate::video::VideoData;
+use crate::video::body::VideoTagBody;
+use crate::video::body::enhanced::metadata::VideoPacketMetadataEntry;
+use crate::video::body::enhanced::{ExVideoTagBody, VideoPacket, VideoPacketCodedFrames, VideoPacketSequenceStart};
+use crate::video::body::legacy::LegacyVideoTagBody;
+use crate::video::header::legacy::{LegacyVideoTagHeader, LegacyVideoTagHeaderAvcPacket};
+use crate::video::header::{VideoFrameType, VideoTagHeaderData};
+
+/// The decoder configuration of a video track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VideoSequenceHeader<'b> {
+    /// H.264/AVC codec configuration record
+    Avc(&'b AVCDecoderConfigurationRecord),
+    /// H.265/HEVC codec configuration record
+    Hevc(&'b HEVCDecoderConfigurationRecord),
+    /// Av1 codec configuration record
+    Av1(&'b AV1CodecConfigurationRecord),
+    /// Other codecs like VP8 and VP9
+    Other(&'b Bytes),
+}
+
+/// A normalized video event.
+///
+/// Legacy video tags only ever contain track `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VideoEvent<'b, 'a> {
+    /// The decoder configuration of the track, sent before the first frame.
+    SequenceHeader {
+        /// The track ID.
+        track_id: u8,
+        /// The decoder configuration.
+        header: VideoSequenceHeader<'b>,
+    },
+    /// Coded video frames.
+    Frame {
+        /// The track ID.
+        track_id: u8,
+        /// Whether the frame is a keyframe.
+        keyframe: bool,
+        /// The composition time offset in milliseconds, `0` if the codec does not have one.
+        composition_time_offset: i32,
+        /// The video data.
+        data: &'b Bytes,
+    },
+    /// The end of the track.
+    SequenceEnd {
+        /// The track ID.
+        track_id: u8,
+    },
+    /// Updated metadata of the track, e.g. HDR color information.
+    MetadataUpdate {
+        /// The track ID.
+        track_id: u8,
+        /// The metadata entries.
+        metadata: &'b [VideoPacketMetadataEntry<'a>],
+    },
+}
+
+/// A normalized audio event.
+///
+/// Legacy audio tags only ever contain track `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioEvent<'b> {
+    /// The decoder configuration of the track, sent before the first frame.
+    ///
+    /// For AAC this is the `AudioSpecificConfig`.
+    SequenceHeader {
+        /// The track ID.
+        track_id: u8,
+        /// The header data.
+        data: &'b Bytes,
+    },
+    /// Coded audio frames.
+    Frame {
+        /// The track ID.
+        track_id: u8,
+        /// The audio data.
+        data: &'b Bytes,
+    },
+    /// The end of the track.
+    SequenceEnd {
+        /// The track ID.
+        track_id: u8,
+    },
+}
+
+/// Sign extends the 24 bit composition time offset of legacy AVC packets.
+const fn legacy_composition_time_offset(composition_time_offset: u32) -> i32 {
+    ((composition_time_offset << 8) as i32) >> 8
+}
+
+impl<'a> VideoData<'a> {
+    /// Returns the normalized events contained in this video data.
+    ///
+    /// Video commands, MPEG2-TS sequence starts and unknown packets do not produce any events.
+    pub fn events(&self) -> Vec<VideoEvent<'_, 'a>> {
+        let keyframe = matches!(
+            self.header.frame_type,
+            VideoFrameType::KeyFrame | VideoFrameType::GeneratedKeyFrame
+        );
+
+        match (&self.header.data, &self.body) {
+            (VideoTagHeaderData::Legacy(header), VideoTagBody::Legacy(body)) => {
+                legacy_video_event(header, body, keyframe).into_iter().collect()
+            }
+            // The legacy body is always demuxed with a legacy header
+            (_, VideoTagBody::Legacy(_)) => Vec::new(),
+            (_, VideoTagBody::Enhanced(ExVideoTagBody::Command)) => Vec::new(),
+            (_, VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack { packet, .. })) => {
+                enhanced_video_event(0, packet, keyframe).into_iter().collect()
+            }
+            (_, VideoTagBody::Enhanced(ExVideoTagBody::ManyTracks(tracks))) => tracks
+                .iter()
+                .filter_map(|track| enhanced_video_event(track.video_track_id, &track.packet, keyframe))
+                .collect(),
+        }
+    }
+}
+
+fn legacy_video_event<'b, 'a>(
+    header: &LegacyVideoTagHeader,
+    body: &'b LegacyVideoTagBody,
+    keyframe: bool,
+) -> Option<VideoEvent<'b, 'a>> {
+    match (header, body) {
+        (_, LegacyVideoTagBody::AvcVideoPacketSeqHdr(record)) => Some(VideoEvent::SequenceHeader {
+            track_id: 0,
+            header: VideoSequenceHeader::Avc(record),
+        }),
+        (LegacyVideoTagHeader::AvcPacket(LegacyVideoTagHeaderAvcPacket::EndOfSequence), _) => {
+            Some(VideoEvent::SequenceEnd { track_id: 0 })
+        }
+        (
+            LegacyVideoTagHeader::AvcPacket(LegacyVideoTagHeaderAvcPacket::Nalu { composition_time_offset }),
+            LegacyVideoTagBody::Other { data },
+        ) => Some(VideoEvent::Frame {
+            track_id: 0,
+            keyframe,
+            composition_time_offset: legacy_composition_time_offset(*composition_time_offset),
+            data,
+        }),
+        (LegacyVideoTagHeader::Other { .. }, LegacyVideoTagBody::Other { data }) => Some(VideoEvent::Frame {
+            track_id: 0,
+            keyframe,
+            composition_time_offset: 0,
+            data,
+        }),
+        _ => None,
+    }
+}
+
+fn enhanced_video_event<'b, 'a>(track_id: u8, packet: &'b VideoPacket<'a>, keyframe: bool) -> Option<VideoEvent<'b, 'a>> {
+    let frame = |composition_time_offset, data| VideoEvent::Frame {
+        track_id,
+        keyframe,
+        composition_time_offset,
+        data,
+    };
+
+    match packet {
+        VideoPacket::SequenceStart(seq_start) => Some(VideoEvent::SequenceHeader {
+            track_id,
+            header: match seq_start {
+                VideoPacketSequenceStart::Avc(record) => VideoSequenceHeader::Avc(record),
+                VideoPacketSequenceStart::Hevc(record) => VideoSequenceHeader::Hevc(record),
+                VideoPacketSequenceStart::Av1(record) => VideoSequenceHeader::Av1(record),
+                VideoPacketSequenceStart::Other(data) => VideoSequenceHeader::Other(data),
+            },
+        }),
+        VideoPacket::CodedFrames(
+            VideoPacketCodedFrames::Avc {
+                composition_time_offset,
+                data,
+            }
+            | VideoPacketCodedFrames::Hevc {
+                composition_time_offset,
+                data,
+            },
+        ) => Some(frame(*composition_time_offset, data)),
+        VideoPacket::CodedFrames(VideoPacketCodedFrames::Other(data)) | VideoPacket::CodedFramesX { data } => {
+            Some(frame(0, data))
+        }
+        VideoPacket::SequenceEnd => Some(VideoEvent::SequenceEnd { track_id }),
+        VideoPacket::Metadata(metadata) => Some(VideoEvent::MetadataUpdate { track_id, metadata }),
+        VideoPacket::Mpeg2TsSequenceStart(_) | VideoPacket::Unknown { .. } => None,
+    }
+}
+
+impl AudioData {
+    /// Returns the normalized events contained in this audio data.
+    ///
+    /// Multichannel configurations and unknown packets do not produce any events.
+    pub fn events(&self) -> Vec<AudioEvent<'_>> {
+        match &self.body {
+            AudioTagBody::Legacy(LegacyAudioTagBody::Aac(AacAudioData::SequenceHeader(data))) => {
+                vec![AudioEvent::SequenceHeader { track_id: 0, data }]
+            }
+            AudioTagBody::Legacy(LegacyAudioTagBody::Aac(AacAudioData::Raw(data)))
+            | AudioTagBody::Legacy(LegacyAudioTagBody::Other { sound_data: data }) => {
+                vec![AudioEvent::Frame { track_id: 0, data }]
+            }
+            AudioTagBody::Legacy(LegacyAudioTagBody::Aac(AacAudioData::Unknown { .. })) => Vec::new(),
+            AudioTagBody::Enhanced(ExAudioTagBody::NoMultitrack { packet, .. }) => {
+                enhanced_audio_event(0, packet).into_iter().collect()
+            }
+            AudioTagBody::Enhanced(ExAudioTagBody::ManyTracks(tracks)) => tracks
+                .iter()
+                .filter_map(|track| enhanced_audio_event(track.audio_track_id, &track.packet))
+                .collect(),
+        }
+    }
+}
+
+fn enhanced_audio_event(track_id: u8, packet: &AudioPacket) -> Option<AudioEvent<'_>> {
+    match packet {
+        AudioPacket::SequenceStart { header_data } => Some(AudioEvent::SequenceHeader {
+            track_id,
+            data: header_data,
+        }),
+        AudioPacket::CodedFrames { data } => Some(AudioEvent::Frame { track_id, data }),
+        AudioPacket::SequenceEnd => Some(AudioEvent::SequenceEnd { track_id }),
+        AudioPacket::MultichannelConfig { .. } | AudioPacket::Unknown { .. } => None,
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use bytes::Bytes;
+
+    use crate::audio::AudioData;
+    use crate::event::{AudioEvent, VideoEvent, VideoSequenceHeader};
+    use crate::video::VideoData;
+
+    fn video(data: &'static [u8]) -> VideoData<'static> {
+        VideoData::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap()
+    }
+
+    fn audio(data: &'static [u8]) -> AudioData {
+        AudioData::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_video_events() {
+        // keyframe, avc, nalu, composition time offset -1
+        let data = video(&[0x17, 0x01, 0xFF, 0xFF, 0xFF, 0x42]);
+        assert_eq!(
+            data.events(),
+            [VideoEvent::Frame {
+                track_id: 0,
+                keyframe: true,
+                composition_time_offset: -1,
+                data: &Bytes::from_static(&[0x42]),
+            }]
+        );
+
+        // interframe, avc, end of sequence
+        let data = video(&[0x27, 0x02, 0x00, 0x00, 0x00]);
+        assert_eq!(data.events(), [VideoEvent::SequenceEnd { track_id: 0 }]);
+
+        // interframe, vp6
+        let data = video(&[0x24, 0x01, 0x02]);
+        assert_eq!(
+            data.events(),
+            [VideoEvent::Frame {
+                track_id: 0,
+                keyframe: false,
+                composition_time_offset: 0,
+                data: &Bytes::from_static(&[0x01, 0x02]),
+            }]
+        );
+
+        // command frame, start seek
+        let data = video(&[0x52, 0x00]);
+        assert!(data.events().is_empty());
+    }
+
+    #[test]
+    fn test_enhanced_video_events() {
+        // enhanced, keyframe, coded frames x, hvc1
+        let data = video(&[0b1001_0011, b'h', b'v', b'c', b'1', 0x42]);
+        assert_eq!(
+            data.events(),
+            [VideoEvent::Frame {
+                track_id: 0,
+                keyframe: true,
+                composition_time_offset: 0,
+                data: &Bytes::from_static(&[0x42]),
+            }]
+        );
+
+        // enhanced, keyframe, sequence start, vp09
+        let data = video(&[0b1001_0000, b'v', b'p', b'0', b'9', 0x01]);
+        assert_eq!(
+            data.events(),
+            [VideoEvent::SequenceHeader {
+                track_id: 0,
+                header: VideoSequenceHeader::Other(&Bytes::from_static(&[0x01])),
+            }]
+        );
+
+        // enhanced, interframe, multitrack, many tracks with one codec, coded frames x, hvc1
+        // track 1 and track 2
+        let data = video(&[0b1010_0110, 0b0001_0011, b'h', b'v', b'c', b'1', 1, 0, 0, 1, 0x42, 2, 0, 0, 0]);
+        assert_eq!(
+            data.events(),
+            [
+                VideoEvent::Frame {
+                    track_id: 1,
+                    keyframe: false,
+                    composition_time_offset: 0,
+                    data: &Bytes::from_static(&[0x42]),
+                },
+                VideoEvent::Frame {
+                    track_id: 2,
+                    keyframe: false,
+                    composition_time_offset: 0,
+                    data: &Bytes::new(),
+                },
+            ]
+        );
+
+        // enhanced, sequence end, av01
+        let data = video(&[0b1001_0010, b'a', b'v', b'0', b'1']);
+        assert_eq!(data.events(), [VideoEvent::SequenceEnd { track_id: 0 }]);
+    }
+
+    #[test]
+    fn test_legacy_audio_events() {
+        // aac, sequence header
+        let data = audio(&[0xAF, 0x00, 0x12, 0x10]);
+        assert_eq!(
+            data.events(),
+            [AudioEvent::SequenceHeader {
+                track_id: 0,
+                data: &Bytes::from_static(&[0x12, 0x10]),
+            }]
+        );
+
+        // aac, raw
+        let data = audio(&[0xAF, 0x01, 0x42]);
+        assert_eq!(
+            data.events(),
+            [AudioEvent::Frame {
+                track_id: 0,
+                data: &Bytes::from_static(&[0x42]),
+            }]
+        );
+
+        // mp3
+        let data = audio(&[0x2F, 0x42]);
+        assert_eq!(
+            data.events(),
+            [AudioEvent::Frame {
+                track_id: 0,
+                data: &Bytes::from_static(&[0x42]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_enhanced_audio_events() {
+        // enhanced, sequence start, opus
+        let data = audio(&[0x90, b'O', b'p', b'u', b's', 0x01]);
+        assert_eq!(
+            data.events(),
+            [AudioEvent::SequenceHeader {
+                track_id: 0,
+                data: &Bytes::from_static(&[0x01]),
+            }]
+        );
+
+        // enhanced, sequence end, opus
+        let data = audio(&[0x92, b'O', b'p', b'u', b's']);
+        assert_eq!(data.events(), [AudioEvent::SequenceEnd { track_id: 0 }]);
+    }
+}
@@ -1,11 +1,13 @@
 //! FLV file processing
 
-use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
 
 use super::header::FlvHeader;
-use super::tag::FlvTag;
+use super::tag::{FlvTag, read_array};
+#[cfg(feature = "tokio")]
+use super::tag::{TagHeader, read_array_async_or_eof};
 use crate::error::FlvError;
+use crate::options::{DemuxOptions, DemuxWarning};
 
 /// An FLV file is a combination of a [`FlvHeader`] followed by the
 /// FLV File Body (which is a series of [`FlvTag`]s)
@@ -26,13 +28,35 @@ impl FlvFile<'_> {
     /// The reader needs to be a [`std::io::Cursor`] with a [`Bytes`] buffer because we
     /// take advantage of zero-copy reading.
     pub fn demux(reader: &mut std::io::Cursor<Bytes>) -> Result<Self, FlvError> {
+        Self::demux_with_options(reader, &DemuxOptions::default()).map(|(file, _)| file)
+    }
+
+    /// Demux an FLV file from a reader, enforcing the limits and checks of the given [`DemuxOptions`].
+    ///
+    /// Returns the file together with all non-fatal problems found while demuxing.
+    pub fn demux_with_options(
+        reader: &mut std::io::Cursor<Bytes>,
+        options: &DemuxOptions,
+    ) -> Result<(Self, Vec<DemuxWarning>), FlvError> {
         let header = FlvHeader::demux(reader)?;
 
         let mut tags = Vec::new();
+        let mut warnings = Vec::new();
+        // The first PreviousTagSize is always 0.
+        let mut previous_tag_size = 0;
+
         while reader.has_remaining() {
-            // We don't care about the previous tag size, its only really used for seeking
-            // backwards.
-            reader.read_u32::<BigEndian>()?;
+            // The previous tag size is only really used for seeking backwards, so it is
+            // only checked when requested.
+            let offset = reader.position();
+            let actual = u32::from_be_bytes(read_array(reader)?);
+            if options.check_previous_tag_size && actual != previous_tag_size {
+                warnings.push(DemuxWarning::PreviousTagSizeMismatch {
+                    offset,
+                    expected: previous_tag_size,
+                    actual,
+                });
+            }
 
             // If there is no more data, we can stop reading.
             if !reader.has_remaining() {
@@ -40,10 +64,77 @@ impl FlvFile<'_> {
             }
 
             // Demux the tag from the reader.
-            let tag = FlvTag::demux(reader)?;
-            tags.push(tag);
+            let start = reader.position();
+            let tag = FlvTag::demux_with_options(reader, options, &mut warnings)?;
+            previous_tag_size = (reader.position() - start) as u32;
+            tags.extend(tag);
+        }
+
+        Ok((FlvFile { header, tags }, warnings))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FlvFile<'_> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            header: u.arbitrary()?,
+            tags: u.arbitrary_iter()?.collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl FlvFile<'_> {
+    /// Demux an FLV file from an async reader, like a network socket.
+    ///
+    /// The file ends when the reader ends after a `PreviousTagSize` field or a tag.
+    pub async fn demux_async<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> Result<Self, FlvError> {
+        Self::demux_async_with_options(reader, &DemuxOptions::default())
+            .await
+            .map(|(file, _)| file)
+    }
+
+    /// Demux an FLV file from an async reader, enforcing the limits and checks of the given [`DemuxOptions`].
+    ///
+    /// Returns the file together with all non-fatal problems found while demuxing.
+    pub async fn demux_async_with_options<R: tokio::io::AsyncRead + Unpin>(
+        reader: &mut R,
+        options: &DemuxOptions,
+    ) -> Result<(Self, Vec<DemuxWarning>), FlvError> {
+        let header = FlvHeader::demux_async(reader).await?;
+        // The header is followed by the extra data up to the data offset.
+        let mut offset = (9 + header.extra.len()) as u64;
+
+        let mut tags = Vec::new();
+        let mut warnings = Vec::new();
+        // The first PreviousTagSize is always 0.
+        let mut previous_tag_size = 0;
+
+        while let Some(actual) = read_array_async_or_eof(reader).await? {
+            let actual = u32::from_be_bytes(actual);
+            if options.check_previous_tag_size && actual != previous_tag_size {
+                warnings.push(DemuxWarning::PreviousTagSizeMismatch {
+                    offset,
+                    expected: previous_tag_size,
+                    actual,
+                });
+            }
+            offset += 4;
+
+            // If there is no more data, we can stop reading.
+            let Some(tag_header) = read_array_async_or_eof(reader).await? else {
+                break;
+            };
+
+            // Demux the tag from the reader.
+            let tag_header = TagHeader::parse(tag_header);
+            previous_tag_size = tag_header.tag_size();
+            let tag = FlvTag::demux_async_after_header(tag_header, offset, reader, options, &mut warnings).await?;
+            offset += u64::from(previous_tag_size);
+            tags.extend(tag);
         }
 
-        Ok(FlvFile { header, tags })
+        Ok((FlvFile { header, tags }, warnings))
     }
 }
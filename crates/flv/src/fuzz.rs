@@ -0,0 +1,916 @@
+//! Structured inputs for fuzzing the demuxer.
+//!
+//! Feeding raw bytes into the demuxer means most inputs are rejected by the FLV header or
+//! the first tag header. The types in this module implement [`arbitrary::Arbitrary`] and
+//! are written back into bytes, so a fuzzer spends its time on the tag bodies and on
+//! malformed framing, such as a wrong `PreviousTagSize` or a `DataSize` that is larger
+//! than the actual tag.
+//!
+//! ```rust
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use scuffle_flv::file::FlvFile;
+//! use scuffle_flv::fuzz::FuzzFlvFile;
+//!
+//! let mut u = Unstructured::new(&[0x42; 128]);
+//! let file = FuzzFlvFile::arbitrary(&mut u).unwrap();
+//!
+//! // The demuxer must never panic, no matter how malformed the input is.
+//! let _ = FlvFile::demux(&mut std::io::Cursor::new(file.to_bytes()));
+//! ```
+//!
+//! [`FlvFile`] and the tag, audio, video and script data types it contains implement
+//! [`arbitrary::Arbitrary`] as well. [`write_file`] turns them back into bytes, so a fuzzer
+//! can check that demuxing reproduces the generated file.
+
+use arbitrary::Unstructured;
+use bytes::{BufMut, Bytes, BytesMut};
+use scuffle_amf0::encoder::Amf0Encoder;
+use scuffle_amf0::{Amf0Object, Amf0Value};
+
+use crate::audio::AudioData;
+use crate::audio::body::AudioTagBody;
+use crate::audio::body::enhanced::{AudioChannelOrder, AudioPacket, ExAudioTagBody, MultichannelConfigOrder};
+use crate::audio::body::legacy::LegacyAudioTagBody;
+use crate::audio::body::legacy::aac::{AacAudioData, AacPacketType};
+use crate::audio::header::AudioTagHeader;
+use crate::audio::header::enhanced::{AudioPacketModEx, AudioPacketModExType, AudioPacketType, ExAudioTagHeaderContent};
+use crate::audio::header::legacy::SoundFormat;
+use crate::common::AvMultitrackType;
+use crate::file::FlvFile;
+use crate::header::FlvHeader;
+use crate::script::{OnMetaData, OnMetaDataAudioCodecId, OnMetaDataVideoCodecId, ScriptData};
+use crate::tag::{FlvTag, FlvTagData, FlvTagType};
+use crate::video::VideoData;
+use crate::video::body::VideoTagBody;
+use crate::video::body::enhanced::metadata::{MetadataColorInfo, VideoPacketMetadataEntry};
+use crate::video::body::enhanced::{
+    ExVideoTagBody, VideoPacket, VideoPacketCodedFrames, VideoPacketMpeg2TsSequenceStart, VideoPacketSequenceStart,
+};
+use crate::video::body::legacy::LegacyVideoTagBody;
+use crate::video::header::VideoTagHeaderData;
+use crate::video::header::enhanced::{ExVideoTagHeaderContent, VideoPacketModEx, VideoPacketModExType, VideoPacketType};
+use crate::video::header::legacy::{AvcPacketType, LegacyVideoTagHeader, LegacyVideoTagHeaderAvcPacket, VideoCodecId};
+
+/// The size of the FLV header without any extra data.
+const HEADER_SIZE: u32 = 9;
+
+/// The size of a tag header.
+const TAG_HEADER_SIZE: u32 = 11;
+
+/// An FLV file that can be generated from arbitrary input.
+///
+/// See the [module level documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, arbitrary::Arbitrary)]
+pub struct FuzzFlvFile {
+    /// The header of the file.
+    pub header: FlvHeader,
+    /// Overrides the `DataOffset` field of the header.
+    ///
+    /// If `None`, the correct offset is written.
+    pub data_offset: Option<u32>,
+    /// The tags in the file.
+    pub tags: Vec<FuzzFlvTag>,
+}
+
+impl FuzzFlvFile {
+    /// Writes the file into bytes.
+    pub fn to_bytes(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+
+        buf.put_slice(b"FLV");
+        buf.put_u8(self.header.version);
+        buf.put_u8(((self.header.is_audio_present as u8) << 2) | self.header.is_video_present as u8);
+        buf.put_u32(
+            self.data_offset
+                .unwrap_or(HEADER_SIZE.saturating_add(self.header.extra.len() as u32)),
+        );
+        buf.put_slice(&self.header.extra);
+
+        // The first PreviousTagSize is always 0.
+        let mut previous_tag_size = 0;
+        for tag in &self.tags {
+            buf.put_u32(tag.previous_tag_size.unwrap_or(previous_tag_size));
+            previous_tag_size = tag.write(&mut buf);
+        }
+
+        buf.put_u32(previous_tag_size);
+
+        buf.freeze()
+    }
+}
+
+/// An FLV tag that can be generated from arbitrary input.
+///
+/// See the [module level documentation](self) for more information.
+#[derive(Debug, Clone, PartialEq, arbitrary::Arbitrary)]
+pub struct FuzzFlvTag {
+    /// Overrides the `PreviousTagSize` field written before this tag.
+    ///
+    /// If `None`, the size of the previous tag is written.
+    pub previous_tag_size: Option<u32>,
+    /// Whether the `Filter` bit is set, which marks the tag as encrypted.
+    pub encrypted: bool,
+    /// The type of the tag.
+    pub tag_type: FlvTagType,
+    /// Overrides the `DataSize` field of the tag, only the lower 24 bits are written.
+    ///
+    /// If `None`, the length of `data` is written.
+    pub data_size: Option<u32>,
+    /// The timestamp of the tag in milliseconds.
+    pub timestamp_ms: u32,
+    /// The stream id of the tag, only the lower 24 bits are written.
+    pub stream_id: u32,
+    /// The tag body.
+    pub data: Vec<u8>,
+}
+
+impl FuzzFlvTag {
+    /// Writes the tag into the given buffer.
+    ///
+    /// Returns the size of the tag, which is the value of the following `PreviousTagSize` field.
+    pub fn write(&self, buf: &mut BytesMut) -> u32 {
+        buf.put_u8(((self.encrypted as u8) << 5) | (self.tag_type.0 & 0b00011111));
+        buf.put_uint(self.data_size.unwrap_or(self.data.len() as u32) as u64 & 0xFFFFFF, 3);
+        buf.put_uint(self.timestamp_ms as u64 & 0xFFFFFF, 3);
+        buf.put_u8((self.timestamp_ms >> 24) as u8);
+        buf.put_uint(self.stream_id as u64 & 0xFFFFFF, 3);
+        buf.put_slice(&self.data);
+
+        TAG_HEADER_SIZE.saturating_add(self.data.len() as u32)
+    }
+}
+
+/// Writes a demuxed FLV file back into bytes.
+///
+/// This is the inverse of [`FlvFile::demux`] for the values generated by the
+/// [`Arbitrary`](arbitrary::Arbitrary) implementations of [`FlvFile`] and the types it
+/// contains, which lets a fuzzer check that demuxing a file reproduces it exactly:
+///
+/// ```rust
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use scuffle_flv::file::FlvFile;
+///
+/// let mut u = Unstructured::new(&[0x42; 256]);
+/// let file = FlvFile::arbitrary(&mut u).unwrap();
+///
+/// let bytes = scuffle_flv::fuzz::write_file(&file);
+/// assert_eq!(FlvFile::demux(&mut std::io::Cursor::new(bytes)).unwrap(), file);
+/// ```
+///
+/// Other values are written on a best effort basis and may demux into something else,
+/// for example a body that does not match its header.
+pub fn write_file(file: &FlvFile<'_>) -> Bytes {
+    let mut buf = BytesMut::new();
+
+    buf.put_slice(b"FLV");
+    buf.put_u8(file.header.version);
+    buf.put_u8(((file.header.is_audio_present as u8) << 2) | file.header.is_video_present as u8);
+    buf.put_u32(HEADER_SIZE.saturating_add(file.header.extra.len() as u32));
+    buf.put_slice(&file.header.extra);
+
+    // The first PreviousTagSize is always 0.
+    let mut previous_tag_size = 0;
+    for tag in &file.tags {
+        buf.put_u32(previous_tag_size);
+        previous_tag_size = write_tag(tag, &mut buf);
+    }
+
+    buf.put_u32(previous_tag_size);
+
+    buf.freeze()
+}
+
+/// Writes a demuxed FLV tag into the given buffer.
+///
+/// Returns the size of the tag, which is the value of the following `PreviousTagSize` field.
+/// See [`write_file`] for which values are written exactly.
+pub fn write_tag(tag: &FlvTag<'_>, buf: &mut BytesMut) -> u32 {
+    let mut data = BytesMut::new();
+
+    let (filter, tag_type) = match &tag.data {
+        FlvTagData::Audio(audio) => {
+            write_audio(audio, &mut data);
+            (false, FlvTagType::Audio)
+        }
+        FlvTagData::Video(video) => {
+            write_video(video, &mut data);
+            (false, FlvTagType::Video)
+        }
+        FlvTagData::ScriptData(script_data) => {
+            write_script_data(script_data, &mut data);
+            (false, FlvTagType::ScriptData)
+        }
+        // The tag type of an encrypted tag is not kept by the demuxer.
+        FlvTagData::Encrypted { data: body } => {
+            data.put_slice(body);
+            (true, FlvTagType::Audio)
+        }
+        FlvTagData::Unknown { tag_type, data: body } => {
+            data.put_slice(body);
+            (false, *tag_type)
+        }
+    };
+
+    FuzzFlvTag {
+        previous_tag_size: None,
+        encrypted: filter,
+        tag_type,
+        data_size: None,
+        timestamp_ms: tag.timestamp_ms,
+        stream_id: tag.stream_id,
+        data: data.to_vec(),
+    }
+    .write(buf)
+}
+
+fn write_audio(audio: &AudioData, buf: &mut BytesMut) {
+    match &audio.header {
+        AudioTagHeader::Legacy(header) => buf.put_u8(
+            (header.sound_format.0 << 4) | (header.sound_rate.0 << 2) | (header.sound_size.0 << 1) | header.sound_type.0,
+        ),
+        AudioTagHeader::Enhanced(header) => {
+            let multitrack = multitrack_type_audio(&header.content);
+            let packet_type = if multitrack.is_some() {
+                AudioPacketType::Multitrack
+            } else {
+                header.audio_packet_type
+            };
+
+            let first_packet_type = if header.audio_packet_mod_exs.is_empty() {
+                packet_type
+            } else {
+                AudioPacketType::ModEx
+            };
+            buf.put_u8((SoundFormat::ExHeader.0 << 4) | first_packet_type.0);
+
+            for (i, mod_ex) in header.audio_packet_mod_exs.iter().enumerate() {
+                let next_packet_type = if i + 1 < header.audio_packet_mod_exs.len() {
+                    AudioPacketType::ModEx
+                } else {
+                    packet_type
+                };
+
+                match mod_ex {
+                    AudioPacketModEx::TimestampOffsetNano {
+                        audio_timestamp_nano_offset,
+                    } => write_mod_ex(
+                        AudioPacketModExType::TimestampOffsetNano.0,
+                        &audio_timestamp_nano_offset.to_be_bytes()[1..],
+                        next_packet_type.0,
+                        buf,
+                    ),
+                    AudioPacketModEx::Other {
+                        audio_packet_mod_ex_type,
+                        mod_ex_data,
+                    } => write_mod_ex(audio_packet_mod_ex_type.0, mod_ex_data, next_packet_type.0, buf),
+                }
+            }
+
+            match &header.content {
+                ExAudioTagHeaderContent::NoMultiTrack(audio_four_cc) => buf.put_slice(&audio_four_cc.0),
+                ExAudioTagHeaderContent::OneTrack(audio_four_cc)
+                | ExAudioTagHeaderContent::ManyTracks(audio_four_cc)
+                | ExAudioTagHeaderContent::Unknown { audio_four_cc, .. } => {
+                    buf.put_u8(
+                        (multitrack.map_or(0, |multitrack_type| multitrack_type.0) << 4) | header.audio_packet_type.0,
+                    );
+                    buf.put_slice(&audio_four_cc.0);
+                }
+                ExAudioTagHeaderContent::ManyTracksManyCodecs => {
+                    buf.put_u8((AvMultitrackType::ManyTracksManyCodecs.0 << 4) | header.audio_packet_type.0)
+                }
+            }
+        }
+    }
+
+    match &audio.body {
+        AudioTagBody::Legacy(LegacyAudioTagBody::Aac(aac)) => match aac {
+            AacAudioData::SequenceHeader(data) => {
+                buf.put_u8(AacPacketType::SequenceHeader.0);
+                buf.put_slice(data);
+            }
+            AacAudioData::Raw(data) => {
+                buf.put_u8(AacPacketType::Raw.0);
+                buf.put_slice(data);
+            }
+            AacAudioData::Unknown { aac_packet_type, data } => {
+                buf.put_u8(aac_packet_type.0);
+                buf.put_slice(data);
+            }
+        },
+        AudioTagBody::Legacy(LegacyAudioTagBody::Other { sound_data }) => buf.put_slice(sound_data),
+        AudioTagBody::Enhanced(ExAudioTagBody::NoMultitrack { packet, .. }) => write_audio_packet(packet, false, buf),
+        AudioTagBody::Enhanced(ExAudioTagBody::ManyTracks(tracks)) => {
+            let (many_codecs, with_size) = match &audio.header {
+                AudioTagHeader::Enhanced(header) => (
+                    header.content == ExAudioTagHeaderContent::ManyTracksManyCodecs,
+                    !matches!(header.content, ExAudioTagHeaderContent::OneTrack(_)),
+                ),
+                AudioTagHeader::Legacy(_) => (false, false),
+            };
+
+            for track in tracks {
+                if many_codecs {
+                    buf.put_slice(&track.audio_four_cc.0);
+                }
+
+                buf.put_u8(track.audio_track_id);
+                write_audio_packet(&track.packet, with_size, buf);
+            }
+        }
+    }
+}
+
+fn multitrack_type_audio(content: &ExAudioTagHeaderContent) -> Option<AvMultitrackType> {
+    match content {
+        ExAudioTagHeaderContent::NoMultiTrack(_) => None,
+        ExAudioTagHeaderContent::OneTrack(_) => Some(AvMultitrackType::OneTrack),
+        ExAudioTagHeaderContent::ManyTracks(_) => Some(AvMultitrackType::ManyTracks),
+        ExAudioTagHeaderContent::ManyTracksManyCodecs => Some(AvMultitrackType::ManyTracksManyCodecs),
+        ExAudioTagHeaderContent::Unknown {
+            audio_multitrack_type, ..
+        } => Some(*audio_multitrack_type),
+    }
+}
+
+fn write_audio_packet(packet: &AudioPacket, with_size: bool, buf: &mut BytesMut) {
+    let mut data = BytesMut::new();
+
+    match packet {
+        AudioPacket::MultichannelConfig {
+            channel_count,
+            multichannel_config,
+        } => match multichannel_config {
+            MultichannelConfigOrder::Custom(channels) => {
+                data.put_u8(AudioChannelOrder::Custom.0);
+                data.put_u8(*channel_count);
+                data.extend(channels.iter().map(|channel| channel.0));
+            }
+            MultichannelConfigOrder::Native(mask) => {
+                data.put_u8(AudioChannelOrder::Native.0);
+                data.put_u8(*channel_count);
+                data.put_u32(mask.bits());
+            }
+            MultichannelConfigOrder::Unspecified => {
+                data.put_u8(AudioChannelOrder::Unspecified.0);
+                data.put_u8(*channel_count);
+            }
+            MultichannelConfigOrder::Unknown(order) => {
+                data.put_u8(order.0);
+                data.put_u8(*channel_count);
+            }
+        },
+        // The demuxer reads the size of a sequence end but no data.
+        AudioPacket::SequenceEnd => {}
+        AudioPacket::SequenceStart { header_data: body }
+        | AudioPacket::CodedFrames { data: body }
+        | AudioPacket::Unknown { data: body, .. } => data.put_slice(body),
+    }
+
+    if with_size {
+        buf.put_uint(data.len() as u64, 3);
+    }
+
+    buf.put_slice(&data);
+}
+
+fn write_video(video: &VideoData<'_>, buf: &mut BytesMut) {
+    match &video.header.data {
+        VideoTagHeaderData::Legacy(header) => {
+            let video_codec_id = match header {
+                // The demuxer does not keep the codec id of a command frame.
+                LegacyVideoTagHeader::VideoCommand(_) => VideoCodecId(0),
+                LegacyVideoTagHeader::AvcPacket(_) => VideoCodecId::Avc,
+                LegacyVideoTagHeader::Other { video_codec_id } => *video_codec_id,
+            };
+            buf.put_u8(((video.header.frame_type.0 & 0b111) << 4) | video_codec_id.0);
+
+            match header {
+                LegacyVideoTagHeader::VideoCommand(command) => buf.put_u8(command.0),
+                LegacyVideoTagHeader::AvcPacket(packet) => {
+                    let (avc_packet_type, composition_time_offset) = match packet {
+                        LegacyVideoTagHeaderAvcPacket::SequenceHeader => (AvcPacketType::SeqHdr, 0),
+                        LegacyVideoTagHeaderAvcPacket::Nalu { composition_time_offset } => {
+                            (AvcPacketType::Nalu, *composition_time_offset)
+                        }
+                        LegacyVideoTagHeaderAvcPacket::EndOfSequence => (AvcPacketType::EndOfSequence, 0),
+                        LegacyVideoTagHeaderAvcPacket::Unknown {
+                            avc_packet_type,
+                            composition_time_offset,
+                        } => (*avc_packet_type, *composition_time_offset),
+                    };
+                    buf.put_u8(avc_packet_type.0);
+                    buf.put_uint(composition_time_offset as u64 & 0xFFFFFF, 3);
+                }
+                LegacyVideoTagHeader::Other { .. } => {}
+            }
+        }
+        VideoTagHeaderData::Enhanced(header) => {
+            let multitrack = multitrack_type_video(&header.content);
+            let packet_type = if multitrack.is_some() {
+                VideoPacketType::Multitrack
+            } else {
+                header.video_packet_type
+            };
+
+            let first_packet_type = if header.video_packet_mod_exs.is_empty() {
+                packet_type
+            } else {
+                VideoPacketType::ModEx
+            };
+            buf.put_u8(0b1000_0000 | ((video.header.frame_type.0 & 0b111) << 4) | first_packet_type.0);
+
+            for (i, mod_ex) in header.video_packet_mod_exs.iter().enumerate() {
+                let next_packet_type = if i + 1 < header.video_packet_mod_exs.len() {
+                    VideoPacketType::ModEx
+                } else {
+                    packet_type
+                };
+
+                match mod_ex {
+                    VideoPacketModEx::TimestampOffsetNano {
+                        video_timestamp_nano_offset,
+                    } => write_mod_ex(
+                        VideoPacketModExType::TimestampOffsetNano.0,
+                        &video_timestamp_nano_offset.to_be_bytes()[1..],
+                        next_packet_type.0,
+                        buf,
+                    ),
+                    VideoPacketModEx::Other {
+                        video_packet_mod_ex_type,
+                        mod_ex_data,
+                    } => write_mod_ex(video_packet_mod_ex_type.0, mod_ex_data, next_packet_type.0, buf),
+                }
+            }
+
+            match &header.content {
+                ExVideoTagHeaderContent::VideoCommand(command) => buf.put_u8(command.0),
+                ExVideoTagHeaderContent::NoMultiTrack(video_four_cc) => buf.put_slice(&video_four_cc.0),
+                ExVideoTagHeaderContent::OneTrack(video_four_cc)
+                | ExVideoTagHeaderContent::ManyTracks(video_four_cc)
+                | ExVideoTagHeaderContent::Unknown { video_four_cc, .. } => {
+                    buf.put_u8(
+                        (multitrack.map_or(0, |multitrack_type| multitrack_type.0) << 4) | header.video_packet_type.0,
+                    );
+                    buf.put_slice(&video_four_cc.0);
+                }
+                ExVideoTagHeaderContent::ManyTracksManyCodecs => {
+                    buf.put_u8((AvMultitrackType::ManyTracksManyCodecs.0 << 4) | header.video_packet_type.0)
+                }
+            }
+        }
+    }
+
+    match &video.body {
+        VideoTagBody::Legacy(LegacyVideoTagBody::Command) | VideoTagBody::Enhanced(ExVideoTagBody::Command) => {}
+        VideoTagBody::Legacy(LegacyVideoTagBody::AvcVideoPacketSeqHdr(record)) => record
+            .build(&mut (&mut *buf).writer())
+            .expect("writing to a buffer never fails"),
+        VideoTagBody::Legacy(LegacyVideoTagBody::Other { data }) => buf.put_slice(data),
+        VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack { packet, .. }) => write_video_packet(packet, false, buf),
+        VideoTagBody::Enhanced(ExVideoTagBody::ManyTracks(tracks)) => {
+            let (many_codecs, with_size) = match &video.header.data {
+                VideoTagHeaderData::Enhanced(header) => (
+                    header.content == ExVideoTagHeaderContent::ManyTracksManyCodecs,
+                    !matches!(header.content, ExVideoTagHeaderContent::OneTrack(_)),
+                ),
+                VideoTagHeaderData::Legacy(_) => (false, false),
+            };
+
+            for track in tracks {
+                if many_codecs {
+                    buf.put_slice(&track.video_four_cc.0);
+                }
+
+                buf.put_u8(track.video_track_id);
+                write_video_packet(&track.packet, with_size, buf);
+            }
+        }
+    }
+}
+
+fn multitrack_type_video(content: &ExVideoTagHeaderContent) -> Option<AvMultitrackType> {
+    match content {
+        ExVideoTagHeaderContent::VideoCommand(_) | ExVideoTagHeaderContent::NoMultiTrack(_) => None,
+        ExVideoTagHeaderContent::OneTrack(_) => Some(AvMultitrackType::OneTrack),
+        ExVideoTagHeaderContent::ManyTracks(_) => Some(AvMultitrackType::ManyTracks),
+        ExVideoTagHeaderContent::ManyTracksManyCodecs => Some(AvMultitrackType::ManyTracksManyCodecs),
+        ExVideoTagHeaderContent::Unknown {
+            video_multitrack_type, ..
+        } => Some(*video_multitrack_type),
+    }
+}
+
+fn write_video_packet(packet: &VideoPacket<'_>, with_size: bool, buf: &mut BytesMut) {
+    let mut data = BytesMut::new();
+
+    match packet {
+        VideoPacket::Metadata(entries) => {
+            let mut encoder = Amf0Encoder::new((&mut data).writer());
+
+            for entry in entries {
+                let (key, object) = match entry {
+                    VideoPacketMetadataEntry::ColorInfo(color_info) => ("colorInfo", color_info_object(color_info)),
+                    VideoPacketMetadataEntry::Other { key, object } => (key.as_ref(), object.clone()),
+                };
+
+                encoder.encode_string(key).expect("writing to a buffer never fails");
+                encoder.encode_object(&object).expect("writing to a buffer never fails");
+            }
+        }
+        // The demuxer reads the size of a sequence end but no data.
+        VideoPacket::SequenceEnd => {}
+        VideoPacket::SequenceStart(sequence_start) => match sequence_start {
+            VideoPacketSequenceStart::Av1(record) => record
+                .mux(&mut (&mut data).writer())
+                .expect("writing to a buffer never fails"),
+            VideoPacketSequenceStart::Avc(record) => record
+                .build(&mut (&mut data).writer())
+                .expect("writing to a buffer never fails"),
+            VideoPacketSequenceStart::Hevc(record) => record
+                .mux(&mut (&mut data).writer())
+                .expect("writing to a buffer never fails"),
+            VideoPacketSequenceStart::Other(body) => data.put_slice(body),
+        },
+        VideoPacket::Mpeg2TsSequenceStart(sequence_start) => match sequence_start {
+            VideoPacketMpeg2TsSequenceStart::Av1(descriptor) => {
+                data.put_u8(descriptor.tag);
+                data.put_u8(descriptor.length);
+                descriptor
+                    .codec_configuration_record
+                    .mux(&mut (&mut data).writer())
+                    .expect("writing to a buffer never fails");
+            }
+            VideoPacketMpeg2TsSequenceStart::Other(body) => data.put_slice(body),
+        },
+        VideoPacket::CodedFrames(coded_frames) => match coded_frames {
+            VideoPacketCodedFrames::Avc {
+                composition_time_offset,
+                data: body,
+            }
+            | VideoPacketCodedFrames::Hevc {
+                composition_time_offset,
+                data: body,
+            } => {
+                data.put_int(*composition_time_offset as i64, 3);
+                data.put_slice(body);
+            }
+            VideoPacketCodedFrames::Other(body) => data.put_slice(body),
+        },
+        VideoPacket::CodedFramesX { data: body } | VideoPacket::Unknown { data: body, .. } => data.put_slice(body),
+    }
+
+    if with_size {
+        buf.put_uint(data.len() as u64, 3);
+    }
+
+    buf.put_slice(&data);
+}
+
+fn color_info_object(color_info: &MetadataColorInfo) -> Amf0Object<'static> {
+    fn object<const N: usize>(fields: [(&'static str, Option<f64>); N]) -> Amf0Value<'static> {
+        Amf0Value::Object(
+            fields
+                .into_iter()
+                .filter_map(|(key, value)| Some((key.into(), Amf0Value::Number(value?))))
+                .collect(),
+        )
+    }
+
+    let mut color_info_object = Amf0Object::new();
+
+    if let Some(color_config) = &color_info.color_config {
+        color_info_object.insert(
+            "colorConfig".into(),
+            object([
+                ("bitDepth", color_config.bit_depth),
+                ("colorPrimaries", color_config.color_primaries),
+                ("transferCharacteristics", color_config.transfer_characteristics),
+                ("matrixCoefficients", color_config.matrix_coefficients),
+            ]),
+        );
+    }
+
+    if let Some(hdr_cll) = &color_info.hdr_cll {
+        color_info_object.insert(
+            "hdrCll".into(),
+            object([("maxFall", hdr_cll.max_fall), ("maxCll", hdr_cll.max_cll)]),
+        );
+    }
+
+    if let Some(hdr_mdcv) = &color_info.hdr_mdcv {
+        color_info_object.insert(
+            "hdrMdcv".into(),
+            object([
+                ("redX", hdr_mdcv.red_x),
+                ("redY", hdr_mdcv.red_y),
+                ("greenX", hdr_mdcv.green_x),
+                ("greenY", hdr_mdcv.green_y),
+                ("blueX", hdr_mdcv.blue_x),
+                ("blueY", hdr_mdcv.blue_y),
+                ("whitePointX", hdr_mdcv.white_point_x),
+                ("whitePointY", hdr_mdcv.white_point_y),
+                ("maxLuminance", hdr_mdcv.max_luminance),
+                ("minLuminance", hdr_mdcv.min_luminance),
+            ]),
+        );
+    }
+
+    color_info_object
+}
+
+fn write_script_data(script_data: &ScriptData<'_>, buf: &mut BytesMut) {
+    let mut encoder = Amf0Encoder::new((&mut *buf).writer());
+
+    match script_data {
+        ScriptData::OnMetaData(on_meta_data) => {
+            encoder.encode_string("onMetaData").expect("writing to a buffer never fails");
+            encoder
+                .encode_object(&on_meta_data_object(on_meta_data))
+                .expect("writing to a buffer never fails");
+        }
+        ScriptData::OnXmpData(on_xmp_data) => {
+            let mut object = on_xmp_data.other.clone();
+            if let Some(live_xml) = &on_xmp_data.live_xml {
+                object.insert("liveXML".into(), Amf0Value::String(live_xml.clone()));
+            }
+
+            encoder.encode_string("onXMPData").expect("writing to a buffer never fails");
+            encoder.encode_object(&object).expect("writing to a buffer never fails");
+        }
+        ScriptData::Other { name, data } => {
+            encoder.encode_string(name.as_ref()).expect("writing to a buffer never fails");
+            for value in data {
+                value.encode(&mut encoder).expect("writing to a buffer never fails");
+            }
+        }
+    }
+}
+
+fn on_meta_data_object<'a>(on_meta_data: &OnMetaData<'a>) -> Amf0Object<'a> {
+    let mut object = on_meta_data.other.clone();
+
+    let mut insert = |key: &'static str, value: Option<Amf0Value<'a>>| {
+        if let Some(value) = value {
+            object.insert(key.into(), value);
+        }
+    };
+
+    insert(
+        "audiocodecid",
+        on_meta_data.audiocodecid.as_ref().map(|id| match id {
+            OnMetaDataAudioCodecId::Legacy(sound_format) => Amf0Value::Number(sound_format.0 as f64),
+            OnMetaDataAudioCodecId::Enhanced(audio_four_cc) => Amf0Value::Number(u32::from_be_bytes(audio_four_cc.0) as f64),
+        }),
+    );
+    insert("audiodatarate", on_meta_data.audiodatarate.map(Amf0Value::Number));
+    insert("audiodelay", on_meta_data.audiodelay.map(Amf0Value::Number));
+    insert("audiosamplerate", on_meta_data.audiosamplerate.map(Amf0Value::Number));
+    insert("audiosamplesize", on_meta_data.audiosamplesize.map(Amf0Value::Number));
+    insert("canSeekToEnd", on_meta_data.can_seek_to_end.map(Amf0Value::Boolean));
+    insert(
+        "creationdate",
+        on_meta_data
+            .creationdate
+            .as_ref()
+            .map(|creationdate| Amf0Value::String(creationdate.clone().into())),
+    );
+    insert("duration", on_meta_data.duration.map(Amf0Value::Number));
+    insert("filesize", on_meta_data.filesize.map(Amf0Value::Number));
+    insert("framerate", on_meta_data.framerate.map(Amf0Value::Number));
+    insert("height", on_meta_data.height.map(Amf0Value::Number));
+    insert("stereo", on_meta_data.stereo.map(Amf0Value::Boolean));
+    insert(
+        "videocodecid",
+        on_meta_data.videocodecid.as_ref().map(|id| match id {
+            OnMetaDataVideoCodecId::Legacy(video_codec_id) => Amf0Value::Number(video_codec_id.0 as f64),
+            OnMetaDataVideoCodecId::Enhanced(video_four_cc) => Amf0Value::Number(u32::from_be_bytes(video_four_cc.0) as f64),
+        }),
+    );
+    insert("videodatarate", on_meta_data.videodatarate.map(Amf0Value::Number));
+    insert("width", on_meta_data.width.map(Amf0Value::Number));
+    insert(
+        "keyframes",
+        on_meta_data.keyframes.as_ref().map(|keyframes| {
+            let (times, filepositions): (Vec<_>, Vec<_>) = keyframes
+                .iter()
+                .map(|(time, position)| (Amf0Value::Number(*time), Amf0Value::Number(*position as f64)))
+                .unzip();
+
+            Amf0Value::Object(
+                [
+                    ("times".into(), Amf0Value::from(times)),
+                    ("filepositions".into(), Amf0Value::from(filepositions)),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        }),
+    );
+    insert(
+        "audioTrackIdInfoMap",
+        on_meta_data.audio_track_id_info_map.clone().map(Amf0Value::Object),
+    );
+    insert(
+        "videoTrackIdInfoMap",
+        on_meta_data.video_track_id_info_map.clone().map(Amf0Value::Object),
+    );
+
+    object
+}
+
+/// Writes a modifier extension of an enhanced audio or video header.
+fn write_mod_ex(mod_ex_type: u8, mod_ex_data: &[u8], next_packet_type: u8, buf: &mut BytesMut) {
+    // The size is stored minus one, sizes above 256 are escaped with 0xFF and a u16.
+    let size = mod_ex_data.len().saturating_sub(1);
+    if size < 0xFF {
+        buf.put_u8(size as u8);
+    } else {
+        buf.put_u8(0xFF);
+        buf.put_u16(size as u16);
+    }
+
+    buf.put_slice(mod_ex_data);
+    buf.put_u8((mod_ex_type << 4) | (next_packet_type & 0b0000_1111));
+}
+
+/// Generates a number which is not NaN, so that it compares equal to itself after demuxing.
+pub(crate) fn arbitrary_number(u: &mut Unstructured<'_>) -> arbitrary::Result<f64> {
+    let number: f64 = u.arbitrary()?;
+    Ok(if number.is_nan() { 0.0 } else { number })
+}
+
+pub(crate) fn arbitrary_bytes(u: &mut Unstructured<'_>) -> arbitrary::Result<Bytes> {
+    Ok(Bytes::from(u.arbitrary::<Vec<u8>>()?))
+}
+
+/// Generates an AMF0 value, nesting objects and arrays at most `depth` levels deep.
+///
+/// Dates and XML documents are not generated, since they are demuxed as numbers and strings.
+pub(crate) fn arbitrary_amf0_value<'a>(u: &mut Unstructured<'_>, depth: usize) -> arbitrary::Result<Amf0Value<'a>> {
+    let max = if depth == 0 { 3 } else { 5 };
+
+    Ok(match u.int_in_range(0..=max)? {
+        0 => Amf0Value::Number(arbitrary_number(u)?),
+        1 => Amf0Value::Boolean(u.arbitrary()?),
+        2 => Amf0Value::String(u.arbitrary::<String>()?.into()),
+        3 => Amf0Value::Null,
+        4 => Amf0Value::Object(arbitrary_amf0_object(u, depth - 1, &[])?),
+        _ => {
+            let mut values = Vec::new();
+            while u.arbitrary()? {
+                values.push(arbitrary_amf0_value(u, depth - 1)?);
+            }
+
+            Amf0Value::Array(values.into())
+        }
+    })
+}
+
+/// Generates an AMF0 object without any of the `reserved` keys.
+pub(crate) fn arbitrary_amf0_object<'a>(
+    u: &mut Unstructured<'_>,
+    depth: usize,
+    reserved: &[&str],
+) -> arbitrary::Result<Amf0Object<'a>> {
+    let mut object = Amf0Object::new();
+
+    while u.arbitrary()? {
+        let key: String = u.arbitrary()?;
+        // An empty key marks the end of an object.
+        if key.is_empty() || reserved.contains(&key.as_str()) {
+            continue;
+        }
+
+        object.insert(key.into(), arbitrary_amf0_value(u, depth)?);
+    }
+
+    Ok(object)
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use bytes::Bytes;
+
+    use super::{FuzzFlvFile, FuzzFlvTag, write_file};
+    use crate::file::FlvFile;
+    use crate::header::FlvHeader;
+    use crate::tag::{FlvTagData, FlvTagType};
+
+    fn tag(tag_type: FlvTagType, data: &[u8]) -> FuzzFlvTag {
+        FuzzFlvTag {
+            previous_tag_size: None,
+            encrypted: false,
+            tag_type,
+            data_size: None,
+            timestamp_ms: 0x01020304,
+            stream_id: 0,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let file = FuzzFlvFile {
+            header: FlvHeader {
+                version: 1,
+                is_audio_present: true,
+                is_video_present: false,
+                extra: Bytes::from_static(&[1, 2, 3]),
+            },
+            data_offset: None,
+            tags: vec![
+                tag(FlvTagType(1), &[4, 5, 6]),
+                FuzzFlvTag {
+                    encrypted: true,
+                    ..tag(FlvTagType::Audio, &[7, 8])
+                },
+            ],
+        };
+
+        let demuxed = FlvFile::demux(&mut std::io::Cursor::new(file.to_bytes())).expect("failed to demux");
+
+        assert_eq!(demuxed.header, file.header);
+        assert_eq!(demuxed.tags.len(), 2);
+        assert_eq!(demuxed.tags[0].timestamp_ms, 0x01020304);
+        assert_eq!(
+            demuxed.tags[0].data,
+            FlvTagData::Unknown {
+                tag_type: FlvTagType(1),
+                data: Bytes::from_static(&[4, 5, 6]),
+            }
+        );
+        assert_eq!(
+            demuxed.tags[1].data,
+            FlvTagData::Encrypted {
+                data: Bytes::from_static(&[7, 8]),
+            }
+        );
+    }
+
+    #[test]
+    fn oversized_tag() {
+        let file = FuzzFlvFile {
+            header: FlvHeader {
+                version: 1,
+                is_audio_present: false,
+                is_video_present: false,
+                extra: Bytes::new(),
+            },
+            data_offset: None,
+            tags: vec![FuzzFlvTag {
+                data_size: Some(0xFFFFFF),
+                ..tag(FlvTagType(1), &[1, 2, 3])
+            }],
+        };
+
+        assert!(FlvFile::demux(&mut std::io::Cursor::new(file.to_bytes())).is_err());
+    }
+
+    #[test]
+    fn arbitrary_inputs_do_not_panic() {
+        let mut state = 0x2545F4914F6CDD1Du64;
+        let mut data = vec![0; 4096];
+
+        for _ in 0..256 {
+            for byte in &mut data {
+                // xorshift64
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *byte = state as u8;
+            }
+
+            let Ok(file) = FuzzFlvFile::arbitrary(&mut Unstructured::new(&data)) else {
+                continue;
+            };
+
+            let _ = FlvFile::demux(&mut std::io::Cursor::new(file.to_bytes()));
+        }
+    }
+
+    #[test]
+    fn arbitrary_files_roundtrip() {
+        let mut state = 0x9E3779B97F4A7C15u64;
+        let mut data = vec![0; 4096];
+
+        for _ in 0..1024 {
+            for byte in &mut data {
+                // xorshift64
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *byte = state as u8;
+            }
+
+            let Ok(file) = FlvFile::arbitrary(&mut Unstructured::new(&data)) else {
+                continue;
+            };
+
+            let demuxed = FlvFile::demux(&mut std::io::Cursor::new(write_file(&file))).expect("failed to demux");
+            assert_eq!(demuxed, file);
+        }
+    }
+}
@@ -65,4 +65,50 @@ impl FlvHeader {
             extra,
         })
     }
+
+    /// Demux the FLV header from the given async reader.
+    /// The reader will be returned in the position of the start of the data
+    /// offset.
+    #[cfg(feature = "tokio")]
+    pub async fn demux_async<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> Result<Self, FlvError> {
+        use tokio::io::AsyncReadExt;
+
+        let fixed: [u8; FIXED_HEADER_SIZE] = crate::tag::read_array_async(reader).await?;
+
+        // The signature is checked before reading any further, in case this is not an FLV stream at all.
+        let signature = u32::from_be_bytes([0, fixed[0], fixed[1], fixed[2]]);
+        if signature != u32::from_be_bytes([0, b'F', b'L', b'V']) {
+            return Err(FlvError::InvalidSignature(signature));
+        }
+
+        let data_offset = u32::from_be_bytes([fixed[5], fixed[6], fixed[7], fixed[8]]);
+        let remaining = (data_offset as usize)
+            .checked_sub(FIXED_HEADER_SIZE)
+            .ok_or(FlvError::InvalidDataOffset(data_offset))?;
+
+        // The data offset is not trusted, so the extra data is not allocated up front.
+        let mut header = fixed.to_vec();
+        let read = reader.take(remaining as u64).read_to_end(&mut header).await?;
+        if read != remaining {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "not enough bytes").into());
+        }
+
+        Self::demux(&mut io::Cursor::new(Bytes::from(header)))
+    }
+}
+
+/// The size of the header up to and including the DataOffset field.
+#[cfg(feature = "tokio")]
+const FIXED_HEADER_SIZE: usize = 9;
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FlvHeader {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            version: u.arbitrary()?,
+            is_audio_present: u.arbitrary()?,
+            is_video_present: u.arbitrary()?,
+            extra: Bytes::from(u.arbitrary::<Vec<u8>>()?),
+        })
+    }
 }
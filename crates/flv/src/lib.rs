@@ -27,10 +27,15 @@
 pub mod audio;
 pub mod common;
 pub mod error;
+pub mod event;
 pub mod file;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
 pub mod header;
+pub mod options;
 pub mod script;
 pub mod tag;
+pub mod timestamp;
 pub mod video;
 
 #[cfg(test)]
@@ -217,6 +222,22 @@ mod tests {
                     TimingInfo {
                         num_units_in_tick: 1,
                         time_scale: 120,
+                        fixed_frame_rate_flag: true,
+                    },
+                ),
+                nal_hrd_parameters: None,
+                vcl_hrd_parameters: None,
+                low_delay_hrd_flag: None,
+                pic_struct_present_flag: false,
+                bitstream_restriction: Some(
+                    BitstreamRestriction {
+                        motion_vectors_over_pic_boundaries_flag: true,
+                        max_bytes_per_pic_denom: 0,
+                        max_bits_per_mb_denom: 0,
+                        log2_max_mv_length_horizontal: 11,
+                        log2_max_mv_length_vertical: 11,
+                        max_num_reorder_frames: 2,
+                        max_dec_frame_buffering: 4,
                     },
                 ),
             }
@@ -722,6 +743,32 @@ mod tests {
             };
         }
     }
+
+    #[tokio::test]
+    #[cfg(feature = "tokio")]
+    async fn test_demux_flv_async() {
+        use tokio::io::AsyncWriteExt;
+
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+
+        for name in ["avc_aac.flv", "av1_aac.flv", "hevc_aac.flv"] {
+            let data = Bytes::from(std::fs::read(dir.join(name)).expect("failed to read file"));
+            let expected = FlvFile::demux(&mut io::Cursor::new(data.clone())).expect("failed to demux flv");
+
+            let mut file = tokio::fs::File::open(dir.join(name)).await.expect("failed to open file");
+            let flv = FlvFile::demux_async(&mut file).await.expect("failed to demux flv");
+            assert_eq!(flv, expected, "{name}");
+
+            // A stream which only delivers a few bytes at a time.
+            let (mut writer, mut reader) = tokio::io::duplex(7);
+            let write = async move {
+                writer.write_all(&data).await.expect("failed to write");
+                writer.shutdown().await.expect("failed to shutdown");
+            };
+            let (flv, ()) = tokio::join!(FlvFile::demux_async(&mut reader), write);
+            assert_eq!(flv.expect("failed to demux flv"), expected, "{name}");
+        }
+    }
 }
 
 /// Changelogs generated by [scuffle_changelog]
@@ -0,0 +1,260 @@
+//! Demuxing limits and integrity checks.
+//!
+//! The size of an FLV tag is declared by the input itself, so hostile or corrupt input can
+//! declare tags of up to 16 MiB each. [`DemuxOptions`] allows bounding those sizes and checking
+//! every tag against its `PreviousTagSize` trailer.
+
+use crate::tag::FlvTagType;
+
+/// Options for [`FlvFile::demux_with_options`](crate::file::FlvFile::demux_with_options) and
+/// [`FlvTag::demux_with_options`](crate::tag::FlvTag::demux_with_options).
+///
+/// The default options do not limit anything, which is the same as the plain `demux` functions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DemuxOptions {
+    /// The maximum size of the data of a single tag in bytes.
+    pub max_tag_size: Option<u32>,
+    /// The maximum size of the data of a single script data tag in bytes.
+    pub max_script_data_size: Option<u32>,
+    /// The maximum length of a single string in script data in bytes.
+    pub max_script_string_len: Option<usize>,
+    /// Skip tags which exceed one of the limits instead of failing with an error.
+    ///
+    /// A [`DemuxWarning::OversizedTag`] is reported for every skipped tag.
+    pub skip_oversized_tags: bool,
+    /// Check that the `PreviousTagSize` trailer matches the size of the previous tag.
+    ///
+    /// A [`DemuxWarning::PreviousTagSizeMismatch`] is reported for every mismatch.
+    pub check_previous_tag_size: bool,
+}
+
+impl DemuxOptions {
+    /// Sets the maximum size of the data of a single tag in bytes.
+    pub fn with_max_tag_size(mut self, max_tag_size: u32) -> Self {
+        self.max_tag_size = Some(max_tag_size);
+        self
+    }
+
+    /// Sets the maximum size of the data of a single script data tag in bytes.
+    pub fn with_max_script_data_size(mut self, max_script_data_size: u32) -> Self {
+        self.max_script_data_size = Some(max_script_data_size);
+        self
+    }
+
+    /// Sets the maximum length of a single string in script data in bytes.
+    pub fn with_max_script_string_len(mut self, max_script_string_len: usize) -> Self {
+        self.max_script_string_len = Some(max_script_string_len);
+        self
+    }
+
+    /// Sets whether tags which exceed one of the limits are skipped instead of failing with an error.
+    pub fn with_skip_oversized_tags(mut self, skip_oversized_tags: bool) -> Self {
+        self.skip_oversized_tags = skip_oversized_tags;
+        self
+    }
+
+    /// Sets whether the `PreviousTagSize` trailer is checked.
+    pub fn with_check_previous_tag_size(mut self, check_previous_tag_size: bool) -> Self {
+        self.check_previous_tag_size = check_previous_tag_size;
+        self
+    }
+
+    /// Returns the limit the given tag exceeds, if any.
+    pub(crate) fn exceeded_limit(&self, tag_type: FlvTagType, size: u32) -> Option<u32> {
+        let script_limit = self.max_script_data_size.filter(|_| tag_type == FlvTagType::ScriptData);
+
+        [self.max_tag_size, script_limit]
+            .into_iter()
+            .flatten()
+            .filter(|max| size > *max)
+            .min()
+    }
+}
+
+/// A non-fatal problem found while demuxing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DemuxWarning {
+    /// A tag exceeded one of the configured limits and was skipped.
+    OversizedTag {
+        /// The byte offset of the tag in the input.
+        offset: u64,
+        /// The type of the tag.
+        tag_type: FlvTagType,
+        /// The declared size of the tag data.
+        size: u32,
+        /// The limit that was exceeded.
+        max: u32,
+    },
+    /// The `PreviousTagSize` trailer does not match the size of the previous tag.
+    PreviousTagSizeMismatch {
+        /// The byte offset of the `PreviousTagSize` field in the input.
+        offset: u64,
+        /// The size of the previous tag, including its header.
+        expected: u32,
+        /// The value of the `PreviousTagSize` field.
+        actual: u32,
+    },
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use bytes::Bytes;
+
+    use super::{DemuxOptions, DemuxWarning};
+    use crate::error::FlvError;
+    use crate::file::FlvFile;
+    use crate::tag::FlvTagType;
+
+    fn file(previous_tag_size: u8) -> Bytes {
+        let mut data = vec![b'F', b'L', b'V', 0x01, 0x05, 0x00, 0x00, 0x00, 0x09];
+        // PreviousTagSize0
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        // audio tag, 3 bytes, aac raw
+        data.extend_from_slice(&[0x08, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        data.extend_from_slice(&[0xAF, 0x01, 0x42]);
+        data.extend_from_slice(&[0x00, 0x00, 0x00, previous_tag_size]);
+        // script data tag, 5 bytes, name "a" with a null value
+        data.extend_from_slice(&[0x12, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        data.extend_from_slice(&[0x02, 0x00, 0x01, b'a', 0x05]);
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 16]);
+        Bytes::from(data)
+    }
+
+    #[test]
+    fn limits() {
+        let options = DemuxOptions::default().with_max_script_data_size(4);
+        let err = FlvFile::demux_with_options(&mut io::Cursor::new(file(14)), &options).unwrap_err();
+        assert!(matches!(
+            err,
+            FlvError::TagTooLarge {
+                tag_type: FlvTagType::ScriptData,
+                size: 5,
+                max: 4,
+            }
+        ));
+
+        let options = DemuxOptions::default().with_max_tag_size(2).with_skip_oversized_tags(true);
+        let (flv, warnings) = FlvFile::demux_with_options(&mut io::Cursor::new(file(14)), &options).unwrap();
+        assert!(flv.tags.is_empty());
+        assert_eq!(
+            warnings,
+            [
+                DemuxWarning::OversizedTag {
+                    offset: 13,
+                    tag_type: FlvTagType::Audio,
+                    size: 3,
+                    max: 2,
+                },
+                DemuxWarning::OversizedTag {
+                    offset: 31,
+                    tag_type: FlvTagType::ScriptData,
+                    size: 5,
+                    max: 2,
+                },
+            ]
+        );
+
+        // The smallest limit is reported
+        let options = DemuxOptions::default()
+            .with_max_tag_size(3)
+            .with_max_script_data_size(1)
+            .with_skip_oversized_tags(true);
+        let (flv, warnings) = FlvFile::demux_with_options(&mut io::Cursor::new(file(14)), &options).unwrap();
+        assert_eq!(flv.tags.len(), 1);
+        assert_eq!(
+            warnings,
+            [DemuxWarning::OversizedTag {
+                offset: 31,
+                tag_type: FlvTagType::ScriptData,
+                size: 5,
+                max: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn script_string_len() {
+        let options = DemuxOptions::default().with_max_script_string_len(1);
+        let (flv, _) = FlvFile::demux_with_options(&mut io::Cursor::new(file(14)), &options).unwrap();
+        assert_eq!(flv.tags.len(), 2);
+
+        let options = DemuxOptions::default().with_max_script_string_len(0);
+        let err = FlvFile::demux_with_options(&mut io::Cursor::new(file(14)), &options).unwrap_err();
+        assert!(matches!(err, FlvError::Amf0(scuffle_amf0::Amf0Error::Io(err)) if err.kind() == io::ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn previous_tag_size() {
+        let options = DemuxOptions::default().with_check_previous_tag_size(true);
+        let (flv, warnings) = FlvFile::demux_with_options(&mut io::Cursor::new(file(14)), &options).unwrap();
+        assert_eq!(flv.tags.len(), 2);
+        assert!(warnings.is_empty());
+
+        let (_, warnings) = FlvFile::demux_with_options(&mut io::Cursor::new(file(15)), &options).unwrap();
+        assert_eq!(
+            warnings,
+            [DemuxWarning::PreviousTagSizeMismatch {
+                offset: 27,
+                expected: 14,
+                actual: 15,
+            }]
+        );
+
+        // Not checked by default
+        let (_, warnings) = FlvFile::demux_with_options(&mut io::Cursor::new(file(15)), &DemuxOptions::default()).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tokio")]
+    async fn demux_async() {
+        let options = DemuxOptions::default()
+            .with_max_script_data_size(4)
+            .with_skip_oversized_tags(true)
+            .with_check_previous_tag_size(true);
+        let (flv, warnings) = FlvFile::demux_async_with_options(&mut &file(15)[..], &options).await.unwrap();
+        assert_eq!(flv.tags.len(), 1);
+        assert_eq!(
+            warnings,
+            [
+                DemuxWarning::PreviousTagSizeMismatch {
+                    offset: 27,
+                    expected: 14,
+                    actual: 15,
+                },
+                DemuxWarning::OversizedTag {
+                    offset: 31,
+                    tag_type: FlvTagType::ScriptData,
+                    size: 5,
+                    max: 4,
+                },
+            ]
+        );
+
+        let err = FlvFile::demux_async_with_options(&mut &file(14)[..], &options.clone().with_skip_oversized_tags(false))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            FlvError::TagTooLarge {
+                tag_type: FlvTagType::ScriptData,
+                size: 5,
+                max: 4,
+            }
+        ));
+
+        // The stream ends in the middle of a tag
+        let data = file(14);
+        let err = FlvFile::demux_async(&mut &data[..data.len() - 6]).await.unwrap_err();
+        assert!(matches!(err, FlvError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof));
+
+        // The stream ends in the middle of a skipped tag
+        let err = FlvFile::demux_async_with_options(&mut &data[..data.len() - 6], &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FlvError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof));
+    }
+}
@@ -2,18 +2,20 @@
 
 use core::fmt;
 use std::io;
+use std::sync::LazyLock;
 
 use bytes::Bytes;
 use scuffle_amf0::de::MultiValue;
 use scuffle_amf0::decoder::Amf0Decoder;
 use scuffle_amf0::{Amf0Object, Amf0Value};
-use scuffle_bytes_util::{BytesCursorExt, StringCow};
+use scuffle_bytes_util::{BytesCursorExt, StringCow, StringInterner};
 use serde::de::VariantAccess;
 use serde_derive::Deserialize;
 
 use crate::audio::header::enhanced::AudioFourCc;
 use crate::audio::header::legacy::SoundFormat;
 use crate::error::FlvError;
+use crate::options::DemuxOptions;
 use crate::video::header::enhanced::VideoFourCc;
 use crate::video::header::legacy::VideoCodecId;
 
@@ -79,6 +81,56 @@ impl<'de> serde::Deserialize<'de> for OnMetaDataVideoCodecId {
     }
 }
 
+/// Deserializes the `keyframes` object of the [`OnMetaData`] script data.
+///
+/// The object contains two strict arrays of the same length, `times` in seconds
+/// and `filepositions` in bytes, which are zipped into `(time, file position)` pairs.
+///
+/// The index is only a hint for seeking, so a malformed `keyframes` object results in `None`
+/// instead of failing to demux the script data.
+fn deserialize_keyframes<'de, D>(deserializer: D) -> Result<Option<Vec<(f64, u64)>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<Amf0Value<'de>> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(value.as_ref().and_then(parse_keyframes))
+}
+
+fn parse_keyframes(value: &Amf0Value<'_>) -> Option<Vec<(f64, u64)>> {
+    let Amf0Value::Object(object) = value else {
+        return None;
+    };
+
+    let numbers = |key: &str| {
+        let (_, Amf0Value::Array(values)) = object.iter().find(|(k, _)| k.as_str() == key)? else {
+            return None;
+        };
+
+        values
+            .iter()
+            .map(|value| match value {
+                Amf0Value::Number(n) => Some(*n),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+
+    let filepositions = numbers("filepositions")?;
+    let times = numbers("times")?;
+    if filepositions.len() != times.len() {
+        return None;
+    }
+
+    times
+        .into_iter()
+        .zip(filepositions)
+        .map(|(time, position)| {
+            let valid = position >= 0.0 && position.fract() == 0.0 && position <= u64::MAX as f64;
+            valid.then_some((time, position as u64))
+        })
+        .collect()
+}
+
 /// FLV `onMetaData` script data
 ///
 /// Defined by:
@@ -132,6 +184,13 @@ pub struct OnMetaData<'a> {
     /// Width of the video, in pixels.
     #[serde(default)]
     pub width: Option<f64>,
+    /// Keyframe index as `(time, file position)` pairs, with the time in seconds
+    /// and the file position in bytes.
+    ///
+    /// Not part of the spec, but commonly written by muxers to allow seeking.
+    /// Parsed from the `keyframes` object's `times` and `filepositions` arrays.
+    #[serde(default, deserialize_with = "deserialize_keyframes")]
+    pub keyframes: Option<Vec<(f64, u64)>>,
     /// The audioTrackIdInfoMap and videoTrackIdInfoMap objects are designed to store
     /// metadata for audio and video tracks respectively. Each object uses a TrackId as
     /// a key to map to properties that detail the unique characteristics of each
@@ -184,10 +243,10 @@ pub struct OnXmpData<'a> {
     ///
     /// For further details, see [www.adobe.com/devnet/xmp/pdfs/XMPSpecificationPart3.pdf](https://web.archive.org/web/20090306165322/https://www.adobe.com/devnet/xmp/pdfs/XMPSpecificationPart3.pdf).
     #[serde(default, rename = "liveXML")]
-    live_xml: Option<StringCow<'a>>,
+    pub(crate) live_xml: Option<StringCow<'a>>,
     /// Any other metadata contained in the script data.
     #[serde(flatten, borrow)]
-    other: Amf0Object<'a>,
+    pub(crate) other: Amf0Object<'a>,
 }
 
 /// FLV `SCRIPTDATA` tag
@@ -255,16 +314,180 @@ impl<'de> serde::Deserialize<'de> for ScriptData<'de> {
     }
 }
 
+/// Script data names and metadata keys which are written by most muxers.
+///
+/// They are resolved to static strings while decoding instead of being allocated for every tag.
+static INTERNER: LazyLock<StringInterner> = LazyLock::new(|| {
+    [
+        "onMetaData",
+        "onXMPData",
+        "liveXML",
+        "audiocodecid",
+        "audiodatarate",
+        "audiodelay",
+        "audiosamplerate",
+        "audiosamplesize",
+        "canSeekToEnd",
+        "creationdate",
+        "duration",
+        "filesize",
+        "framerate",
+        "height",
+        "stereo",
+        "videocodecid",
+        "videodatarate",
+        "width",
+        "keyframes",
+        "times",
+        "filepositions",
+        "audioTrackIdInfoMap",
+        "videoTrackIdInfoMap",
+        "encoder",
+        "fileSize",
+        "major_brand",
+        "minor_version",
+        "compatible_brands",
+    ]
+    .into_iter()
+    .collect()
+});
+
 impl ScriptData<'_> {
     /// Demux the [`ScriptData`] from the given reader.
     pub fn demux(reader: &mut io::Cursor<Bytes>) -> Result<Self, FlvError> {
+        Self::demux_with_options(reader, &DemuxOptions::default())
+    }
+
+    /// Demux the [`ScriptData`] from the given reader, limiting the length of strings to
+    /// [`DemuxOptions::max_script_string_len`].
+    pub fn demux_with_options(reader: &mut io::Cursor<Bytes>, options: &DemuxOptions) -> Result<Self, FlvError> {
         let buf = reader.extract_remaining();
-        let mut decoder = Amf0Decoder::from_buf(buf);
+        let mut decoder = Amf0Decoder::from_buf(buf).with_interner(&INTERNER);
+        if let Some(max_len) = options.max_script_string_len {
+            decoder = decoder.with_max_string_len(max_len);
+        }
 
         serde::de::Deserialize::deserialize(&mut decoder).map_err(FlvError::Amf0)
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ScriptData<'_> {
+    /// Generates script data that demuxes back into the same value.
+    ///
+    /// For that numbers are never NaN, keyframe file positions fit into an `f64` without
+    /// losing precision and known `onMetaData` properties are never part of
+    /// [`OnMetaData::other`].
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::fuzz::{arbitrary_amf0_object, arbitrary_amf0_value, arbitrary_number};
+
+        fn number(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Option<f64>> {
+            if u.arbitrary()? {
+                arbitrary_number(u).map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+
+        fn object<'b>(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Option<Amf0Object<'b>>> {
+            if u.arbitrary()? {
+                arbitrary_amf0_object(u, 2, &[]).map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+
+        // Codec ids up to u8::MAX are legacy ids, everything above is a FOURCC.
+        fn codec_id(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Option<u32>> {
+            if u.arbitrary()? { u.arbitrary().map(Some) } else { Ok(None) }
+        }
+
+        match u.int_in_range(0..=2)? {
+            0 => {
+                let keyframes = if u.arbitrary()? {
+                    let mut keyframes = Vec::new();
+                    while u.arbitrary()? {
+                        // The file positions are stored as f64.
+                        keyframes.push((arbitrary_number(u)?, u.int_in_range(0..=(1 << f64::MANTISSA_DIGITS))?));
+                    }
+
+                    Some(keyframes)
+                } else {
+                    None
+                };
+
+                Ok(Self::OnMetaData(Box::new(OnMetaData {
+                    audiocodecid: codec_id(u)?.map(|id| match u8::try_from(id) {
+                        Ok(id) => OnMetaDataAudioCodecId::Legacy(SoundFormat::from(id)),
+                        Err(_) => OnMetaDataAudioCodecId::Enhanced(AudioFourCc::from(id.to_be_bytes())),
+                    }),
+                    audiodatarate: number(u)?,
+                    audiodelay: number(u)?,
+                    audiosamplerate: number(u)?,
+                    audiosamplesize: number(u)?,
+                    can_seek_to_end: u.arbitrary()?,
+                    creationdate: u.arbitrary()?,
+                    duration: number(u)?,
+                    filesize: number(u)?,
+                    framerate: number(u)?,
+                    height: number(u)?,
+                    stereo: u.arbitrary()?,
+                    videocodecid: codec_id(u)?.map(|id| match u8::try_from(id) {
+                        Ok(id) => OnMetaDataVideoCodecId::Legacy(VideoCodecId::from(id)),
+                        Err(_) => OnMetaDataVideoCodecId::Enhanced(VideoFourCc::from(id.to_be_bytes())),
+                    }),
+                    videodatarate: number(u)?,
+                    width: number(u)?,
+                    keyframes,
+                    audio_track_id_info_map: object(u)?,
+                    video_track_id_info_map: object(u)?,
+                    other: arbitrary_amf0_object(u, 2, ON_META_DATA_PROPERTIES)?,
+                })))
+            }
+            1 => Ok(Self::OnXmpData(OnXmpData {
+                live_xml: u.arbitrary::<Option<String>>()?.map(StringCow::from),
+                other: arbitrary_amf0_object(u, 2, &["liveXML"])?,
+            })),
+            _ => {
+                let name = u.arbitrary::<String>()?;
+                if name == "onMetaData" || name == "onXMPData" {
+                    return Err(arbitrary::Error::IncorrectFormat);
+                }
+
+                let mut data = Vec::new();
+                while u.arbitrary()? {
+                    data.push(arbitrary_amf0_value(u, 2)?);
+                }
+
+                Ok(Self::Other { name: name.into(), data })
+            }
+        }
+    }
+}
+
+/// The properties of [`OnMetaData`] which are not part of [`OnMetaData::other`].
+#[cfg(feature = "arbitrary")]
+const ON_META_DATA_PROPERTIES: &[&str] = &[
+    "audiocodecid",
+    "audiodatarate",
+    "audiodelay",
+    "audiosamplerate",
+    "audiosamplesize",
+    "canSeekToEnd",
+    "creationdate",
+    "duration",
+    "filesize",
+    "framerate",
+    "height",
+    "stereo",
+    "videocodecid",
+    "videodatarate",
+    "width",
+    "keyframes",
+    "audioTrackIdInfoMap",
+    "videoTrackIdInfoMap",
+];
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
@@ -322,6 +545,7 @@ mod tests {
                 videocodecid: None,
                 videodatarate: None,
                 width: Some(1280.0),
+                keyframes: None,
                 audio_track_id_info_map: None,
                 video_track_id_info_map: None,
                 other: Amf0Object::new(),
@@ -392,6 +616,7 @@ mod tests {
                 videocodecid: Some(OnMetaDataVideoCodecId::Enhanced(VideoFourCc::Avc)),
                 videodatarate: Some(1024.0),
                 width: Some(1280.0),
+                keyframes: None,
                 audio_track_id_info_map: Some([("test".into(), Amf0Value::Number(1.0))].into_iter().collect()),
                 video_track_id_info_map: Some([("test2".into(), Amf0Value::Number(2.0))].into_iter().collect()),
                 other: Amf0Object::new(),
@@ -399,6 +624,109 @@ mod tests {
         );
     }
 
+    fn encode_keyframes(filepositions: &[f64], times: &[f64]) -> Bytes {
+        let mut data = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut data);
+
+        let keyframes: Amf0Object = [
+            (
+                "filepositions".into(),
+                Amf0Value::Array(filepositions.iter().copied().map(Amf0Value::Number).collect()),
+            ),
+            (
+                "times".into(),
+                Amf0Value::Array(times.iter().copied().map(Amf0Value::Number).collect()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        encoder.encode_string("onMetaData").unwrap();
+        let object: Amf0Object = [
+            ("duration".into(), Amf0Value::Number(4.0)),
+            ("keyframes".into(), Amf0Value::Object(keyframes)),
+        ]
+        .into_iter()
+        .collect();
+        encoder.encode_object(&object).unwrap();
+
+        Bytes::from_owner(data)
+    }
+
+    #[test]
+    fn script_on_meta_data_keyframes() {
+        let data = encode_keyframes(&[13.0, 4096.0, 65536.0], &[0.0, 2.0, 4.0]);
+        let script_data = ScriptData::demux(&mut io::Cursor::new(data)).unwrap();
+
+        let ScriptData::OnMetaData(metadata) = script_data else {
+            panic!("expected onMetaData");
+        };
+
+        assert_eq!(metadata.duration, Some(4.0));
+        assert_eq!(metadata.keyframes, Some(vec![(0.0, 13), (2.0, 4096), (4.0, 65536)]));
+        assert!(metadata.other.is_empty());
+    }
+
+    #[test]
+    fn script_on_meta_data_interned_keys() {
+        let mut data = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut data);
+
+        encoder.encode_string("onMetaData").unwrap();
+        let object: Amf0Object = [("encoder".into(), Amf0Value::String("Lavf61.7.100".into()))]
+            .into_iter()
+            .collect();
+        encoder.encode_object(&object).unwrap();
+
+        let script_data = ScriptData::demux(&mut io::Cursor::new(Bytes::from_owner(data))).unwrap();
+
+        let ScriptData::OnMetaData(metadata) = script_data else {
+            panic!("expected onMetaData");
+        };
+
+        let (key, value) = metadata.other.iter().next().unwrap();
+        // Deserializing borrows the interned static string instead of allocating a new one.
+        assert!(matches!(key, StringCow::Ref("encoder")));
+        assert_eq!(value, &Amf0Value::String("Lavf61.7.100".into()));
+    }
+
+    #[test]
+    fn script_on_meta_data_keyframes_invalid() {
+        let on_meta_data = |data| {
+            let ScriptData::OnMetaData(metadata) = ScriptData::demux(&mut io::Cursor::new(data)).unwrap() else {
+                panic!("expected onMetaData");
+            };
+
+            metadata
+        };
+
+        // a malformed index is dropped, the rest of the metadata is still demuxed
+        let metadata = on_meta_data(encode_keyframes(&[13.0, 4096.0], &[0.0, 2.0, 4.0]));
+        assert_eq!(metadata.duration, Some(4.0));
+        assert_eq!(metadata.keyframes, None);
+
+        let metadata = on_meta_data(encode_keyframes(&[-1.0], &[0.0]));
+        assert_eq!(metadata.keyframes, None);
+
+        let metadata = on_meta_data(encode_keyframes(&[1.5], &[0.0]));
+        assert_eq!(metadata.keyframes, None);
+
+        let mut data = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut data);
+        encoder.encode_string("onMetaData").unwrap();
+        let object: Amf0Object = [
+            ("duration".into(), Amf0Value::Number(4.0)),
+            ("keyframes".into(), Amf0Value::String("broken".into())),
+        ]
+        .into_iter()
+        .collect();
+        encoder.encode_object(&object).unwrap();
+
+        let metadata = on_meta_data(Bytes::from_owner(data));
+        assert_eq!(metadata.duration, Some(4.0));
+        assert_eq!(metadata.keyframes, None);
+    }
+
     #[test]
     fn script_on_xmp_data() {
         #[rustfmt::skip]
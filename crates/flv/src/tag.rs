@@ -1,6 +1,5 @@
 //! FLV Tag processing
 
-use byteorder::{BigEndian, ReadBytesExt};
 use bytes::Bytes;
 use nutype_enum::nutype_enum;
 use scuffle_bytes_util::BytesCursorExt;
@@ -9,6 +8,7 @@ use super::audio::AudioData;
 use super::script::ScriptData;
 use super::video::VideoData;
 use crate::error::FlvError;
+use crate::options::{DemuxOptions, DemuxWarning};
 
 /// An FLV Tag
 ///
@@ -31,6 +31,18 @@ pub struct FlvTag<'a> {
     pub data: FlvTagData<'a>,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FlvTag<'_> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            timestamp_ms: u.arbitrary()?,
+            // Only 24 bits are used for the stream id.
+            stream_id: u.int_in_range(0..=0xFFFFFF)?,
+            data: u.arbitrary()?,
+        })
+    }
+}
+
 impl FlvTag<'_> {
     /// Demux a FLV tag from the given reader.
     ///
@@ -39,42 +51,230 @@ impl FlvTag<'_> {
     /// The reader needs to be a [`std::io::Cursor`] with a [`Bytes`] buffer because we
     /// take advantage of zero-copy reading.
     pub fn demux(reader: &mut std::io::Cursor<Bytes>) -> Result<Self, FlvError> {
-        let first_byte = reader.read_u8()?;
-
-        // encrypted
-        let filter = (first_byte & 0b0010_0000) != 0;
-
-        // Only the last 5 bits are the tag type.
-        let tag_type = FlvTagType::from(first_byte & 0b00011111);
+        let tag = Self::demux_with_options(reader, &DemuxOptions::default(), &mut Vec::new())?;
+        // Without any limits no tag is ever skipped.
+        Ok(tag.expect("tag skipped without limits"))
+    }
 
-        let data_size = reader.read_u24::<BigEndian>()?;
-        // The timestamp bit is weird. Its 24bits but then there is an extended 8 bit
-        // number to create a 32bit number.
-        let timestamp_ms = reader.read_u24::<BigEndian>()? | ((reader.read_u8()? as u32) << 24);
+    /// Demux a FLV tag from the given reader, enforcing the limits of the given [`DemuxOptions`].
+    ///
+    /// The limits are checked before the tag data is read.
+    /// Returns `None` if the tag exceeded a limit and [`DemuxOptions::skip_oversized_tags`] is set,
+    /// in which case a [`DemuxWarning::OversizedTag`] is added to `warnings` and the reader is advanced
+    /// past the tag.
+    pub fn demux_with_options(
+        reader: &mut std::io::Cursor<Bytes>,
+        options: &DemuxOptions,
+        warnings: &mut Vec<DemuxWarning>,
+    ) -> Result<Option<Self>, FlvError> {
+        let offset = reader.position();
+        // The whole tag header is read at once, instead of field by field.
+        let header = TagHeader::parse(read_array(reader)?);
 
-        // The stream id according to the spec is ALWAYS 0. (likely not true)
-        let stream_id = reader.read_u24::<BigEndian>()?;
+        if let Some(max) = header.exceeded_limit(options)? {
+            // Skipping still requires the whole tag to be present.
+            skip(reader, header.data_size as usize)?;
+            warnings.push(header.oversized_warning(offset, max));
+            return Ok(None);
+        }
 
         // We then extract the data from the reader. (advancing the cursor to the end of
         // the tag)
-        let data = reader.extract_bytes(data_size as usize)?;
+        let data = reader.extract_bytes(header.data_size as usize)?;
+
+        header.into_tag(data, options).map(Some)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl FlvTag<'_> {
+    /// Demux a FLV tag from the given async reader.
+    ///
+    /// The reader will be advanced to the end of the tag. Unlike [`FlvTag::demux`] the tag data
+    /// has to be copied out of the reader.
+    pub async fn demux_async<R: tokio::io::AsyncRead + Unpin>(reader: &mut R) -> Result<Self, FlvError> {
+        let tag = Self::demux_async_with_options(reader, &DemuxOptions::default(), &mut Vec::new()).await?;
+        // Without any limits no tag is ever skipped.
+        Ok(tag.expect("tag skipped without limits"))
+    }
+
+    /// Demux a FLV tag from the given async reader, enforcing the limits of the given [`DemuxOptions`].
+    ///
+    /// This behaves like [`FlvTag::demux_with_options`], the limits are checked before the tag data
+    /// is read, so oversized tags are never buffered. Since the position of the reader is not known,
+    /// the offset of a [`DemuxWarning::OversizedTag`] is always 0.
+    pub async fn demux_async_with_options<R: tokio::io::AsyncRead + Unpin>(
+        reader: &mut R,
+        options: &DemuxOptions,
+        warnings: &mut Vec<DemuxWarning>,
+    ) -> Result<Option<Self>, FlvError> {
+        let header = TagHeader::parse(read_array_async(reader).await?);
+        Self::demux_async_after_header(header, 0, reader, options, warnings).await
+    }
+
+    /// Demux the rest of a FLV tag after its header was read from the async reader.
+    pub(crate) async fn demux_async_after_header<R: tokio::io::AsyncRead + Unpin>(
+        header: TagHeader,
+        offset: u64,
+        reader: &mut R,
+        options: &DemuxOptions,
+        warnings: &mut Vec<DemuxWarning>,
+    ) -> Result<Option<Self>, FlvError> {
+        use tokio::io::AsyncReadExt;
+
+        if let Some(max) = header.exceeded_limit(options)? {
+            // Skipping still requires the whole tag to be present.
+            let size = u64::from(header.data_size);
+            if tokio::io::copy(&mut reader.take(size), &mut tokio::io::sink()).await? != size {
+                return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "not enough bytes").into());
+            }
+
+            warnings.push(header.oversized_warning(offset, max));
+            return Ok(None);
+        }
+
+        // The buffer grows with the data actually read, so a truncated stream declaring a large tag
+        // does not allocate the whole declared size up front.
+        let size = u64::from(header.data_size);
+        let mut data = Vec::new();
+        if reader.take(size).read_to_end(&mut data).await? as u64 != size {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "not enough bytes").into());
+        }
+
+        header.into_tag(Bytes::from(data), options).map(Some)
+    }
+}
+
+/// The fields of the tag header, from the TagType up to and including the StreamID field.
+pub(crate) struct TagHeader {
+    /// Whether the tag is encrypted.
+    filter: bool,
+    tag_type: FlvTagType,
+    data_size: u32,
+    timestamp_ms: u32,
+    stream_id: u32,
+}
+
+impl TagHeader {
+    pub(crate) fn parse(header: [u8; TAG_HEADER_SIZE]) -> Self {
+        Self {
+            // encrypted
+            filter: (header[0] & 0b0010_0000) != 0,
+            // Only the last 5 bits are the tag type.
+            tag_type: FlvTagType::from(header[0] & 0b00011111),
+            data_size: u32::from_be_bytes([0, header[1], header[2], header[3]]),
+            // The timestamp bit is weird. Its 24bits but then there is an extended 8 bit
+            // number to create a 32bit number.
+            timestamp_ms: u32::from_be_bytes([header[7], header[4], header[5], header[6]]),
+            // The stream id according to the spec is ALWAYS 0. (likely not true)
+            stream_id: u32::from_be_bytes([0, header[8], header[9], header[10]]),
+        }
+    }
+
+    /// Returns the limit the tag exceeds if it has to be skipped, or an error if oversized tags are not skipped.
+    fn exceeded_limit(&self, options: &DemuxOptions) -> Result<Option<u32>, FlvError> {
+        match options.exceeded_limit(self.tag_type, self.data_size) {
+            Some(max) if !options.skip_oversized_tags => Err(FlvError::TagTooLarge {
+                tag_type: self.tag_type,
+                size: self.data_size,
+                max,
+            }),
+            max => Ok(max),
+        }
+    }
+
+    /// The size of the whole tag, including the header.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn tag_size(&self) -> u32 {
+        TAG_HEADER_SIZE as u32 + self.data_size
+    }
+
+    fn oversized_warning(&self, offset: u64, max: u32) -> DemuxWarning {
+        DemuxWarning::OversizedTag {
+            offset,
+            tag_type: self.tag_type,
+            size: self.data_size,
+            max,
+        }
+    }
 
-        let data = if !filter {
+    fn into_tag<'a>(self, data: Bytes, options: &DemuxOptions) -> Result<FlvTag<'a>, FlvError> {
+        let data = if !self.filter {
             // Finally we demux the data.
-            FlvTagData::demux(tag_type, &mut std::io::Cursor::new(data))?
+            FlvTagData::demux_with_options(self.tag_type, &mut std::io::Cursor::new(data), options)?
         } else {
             // If the tag is encrypted we just return the data as is.
             FlvTagData::Encrypted { data }
         };
 
         Ok(FlvTag {
-            timestamp_ms,
-            stream_id,
+            timestamp_ms: self.timestamp_ms,
+            stream_id: self.stream_id,
             data,
         })
     }
 }
 
+/// The size of the tag header, from the TagType up to and including the StreamID field.
+const TAG_HEADER_SIZE: usize = 11;
+
+/// Reads `N` bytes from the reader at once.
+///
+/// This is a lot cheaper than reading every field separately, since the bounds are only checked once.
+pub(crate) fn read_array<const N: usize>(reader: &mut std::io::Cursor<Bytes>) -> std::io::Result<[u8; N]> {
+    let position = reader.position() as usize;
+    let array = reader
+        .get_ref()
+        .get(position..)
+        .and_then(|remaining| remaining.first_chunk::<N>())
+        .copied()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "not enough bytes"))?;
+    reader.set_position((position + N) as u64);
+    Ok(array)
+}
+
+/// Reads `N` bytes from the async reader.
+#[cfg(feature = "tokio")]
+pub(crate) async fn read_array_async<const N: usize, R: tokio::io::AsyncRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<[u8; N]> {
+    use tokio::io::AsyncReadExt;
+
+    let mut array = [0; N];
+    reader.read_exact(&mut array).await?;
+    Ok(array)
+}
+
+/// Reads `N` bytes from the async reader, or returns `None` if the reader is at its end.
+///
+/// The end of the reader in the middle of the array is still an error.
+#[cfg(feature = "tokio")]
+pub(crate) async fn read_array_async_or_eof<const N: usize, R: tokio::io::AsyncRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<[u8; N]>> {
+    use tokio::io::AsyncReadExt;
+
+    let mut array = [0; N];
+    let read = reader.read(&mut array).await?;
+    if read == 0 {
+        return Ok(None);
+    }
+
+    reader.read_exact(&mut array[read..]).await?;
+    Ok(Some(array))
+}
+
+/// Advances the reader by `size` bytes, without slicing the skipped bytes.
+fn skip(reader: &mut std::io::Cursor<Bytes>, size: usize) -> std::io::Result<()> {
+    let position = reader.position() as usize;
+    if reader.get_ref().len().saturating_sub(position) < size {
+        return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "not enough bytes"));
+    }
+
+    reader.set_position((position + size) as u64);
+    Ok(())
+}
+
 nutype_enum! {
     /// FLV Tag Type
     ///
@@ -98,6 +298,19 @@ nutype_enum! {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FlvTagType {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Mostly pick a known tag type, otherwise the demuxer would rarely get past the tag header.
+        if u.ratio(3, 4)? {
+            u.choose(&[Self::Audio, Self::Video, Self::ScriptData]).copied()
+        } else {
+            // Only 5 bits are used for the tag type.
+            Ok(Self(u.arbitrary::<u8>()? & 0b00011111))
+        }
+    }
+}
+
 /// FLV Tag Data
 ///
 /// This is a container for the actual media data.
@@ -142,6 +355,33 @@ pub enum FlvTagData<'a> {
     },
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for FlvTagData<'_> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::fuzz::arbitrary_bytes;
+
+        Ok(match u.int_in_range(0..=4)? {
+            0 => Self::Audio(u.arbitrary()?),
+            1 => Self::Video(u.arbitrary()?),
+            2 => Self::ScriptData(u.arbitrary()?),
+            3 => Self::Encrypted {
+                data: arbitrary_bytes(u)?,
+            },
+            _ => {
+                let tag_type = FlvTagType(u.int_in_range(0..=0b00011111)?);
+                if matches!(tag_type, FlvTagType::Audio | FlvTagType::Video | FlvTagType::ScriptData) {
+                    return Err(arbitrary::Error::IncorrectFormat);
+                }
+
+                Self::Unknown {
+                    tag_type,
+                    data: arbitrary_bytes(u)?,
+                }
+            }
+        })
+    }
+}
+
 impl FlvTagData<'_> {
     /// Demux a FLV tag data from the given reader.
     ///
@@ -150,10 +390,21 @@ impl FlvTagData<'_> {
     /// The reader needs to be a [`std::io::Cursor`] with a [`Bytes`] buffer because we
     /// take advantage of zero-copy reading.
     pub fn demux(tag_type: FlvTagType, reader: &mut std::io::Cursor<Bytes>) -> Result<Self, FlvError> {
+        Self::demux_with_options(tag_type, reader, &DemuxOptions::default())
+    }
+
+    /// Demux a FLV tag data from the given reader, enforcing the string limits of the given [`DemuxOptions`].
+    ///
+    /// The size limits of the options apply to whole tags and are checked by [`FlvTag::demux_with_options`].
+    pub fn demux_with_options(
+        tag_type: FlvTagType,
+        reader: &mut std::io::Cursor<Bytes>,
+        options: &DemuxOptions,
+    ) -> Result<Self, FlvError> {
         match tag_type {
             FlvTagType::Audio => Ok(FlvTagData::Audio(AudioData::demux(reader)?)),
             FlvTagType::Video => Ok(FlvTagData::Video(VideoData::demux(reader)?)),
-            FlvTagType::ScriptData => Ok(FlvTagData::ScriptData(ScriptData::demux(reader)?)),
+            FlvTagType::ScriptData => Ok(FlvTagData::ScriptData(ScriptData::demux_with_options(reader, options)?)),
             _ => Ok(FlvTagData::Unknown {
                 tag_type,
                 data: reader.extract_remaining(),
@@ -161,3 +412,57 @@ impl FlvTagData<'_> {
         }
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use bytes::Bytes;
+
+    use super::{FlvTag, FlvTagData, FlvTagType};
+    use crate::error::FlvError;
+
+    #[test]
+    fn header() {
+        // unknown tag, 2 bytes, timestamp 0x12345678 with the extended byte, stream id 0x9abcde
+        let data = Bytes::from_static(&[0x0A, 0x00, 0x00, 0x02, 0x34, 0x56, 0x78, 0x12, 0x9A, 0xBC, 0xDE, 0x01, 0x02]);
+        let mut reader = io::Cursor::new(data);
+        let tag = FlvTag::demux(&mut reader).unwrap();
+        assert_eq!(tag.timestamp_ms, 0x12345678);
+        assert_eq!(tag.stream_id, 0x9ABCDE);
+        assert_eq!(
+            tag.data,
+            FlvTagData::Unknown {
+                tag_type: FlvTagType(10),
+                data: Bytes::from_static(&[0x01, 0x02]),
+            }
+        );
+        assert_eq!(reader.position(), 13);
+
+        // The header is incomplete
+        let mut reader = io::Cursor::new(Bytes::from_static(&[0x08, 0x00, 0x00, 0x02, 0x00]));
+        let err = FlvTag::demux(&mut reader).unwrap_err();
+        assert!(matches!(err, FlvError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tokio")]
+    async fn demux_async_truncated() {
+        // unknown tag declaring the maximum size of 16MB, followed by only 2 bytes
+        let data = [0x0A, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02];
+        let err = FlvTag::demux_async(&mut &data[..]).await.unwrap_err();
+        assert!(matches!(err, FlvError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof));
+
+        let mut data = data.to_vec();
+        data[1..4].copy_from_slice(&[0x00, 0x00, 0x02]);
+        let tag = FlvTag::demux_async(&mut &data[..]).await.unwrap();
+        assert_eq!(
+            tag.data,
+            FlvTagData::Unknown {
+                tag_type: FlvTagType(10),
+                data: Bytes::from_static(&[0x01, 0x02]),
+            }
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! Timestamp rewriting for spliced streams.
+//!
+//! When multiple FLV sources are concatenated into a single output, e.g. for ad splices or
+//! after an encoder reconnects, the timestamps of each source usually start over or jump.
+//! [`TimestampRewriter`] maps the timestamps of every source onto one continuous timeline.
+
+use crate::event::{AudioEvent, VideoEvent};
+use crate::tag::{FlvTag, FlvTagData};
+use crate::video::VideoData;
+use crate::video::body::VideoTagBody;
+use crate::video::body::enhanced::{ExVideoTagBody, VideoPacket, VideoPacketCodedFrames};
+use crate::video::header::VideoTagHeaderData;
+use crate::video::header::legacy::{LegacyVideoTagHeader, LegacyVideoTagHeaderAvcPacket};
+
+/// Rewrites the timestamps of tags from one or more concatenated FLV sources.
+///
+/// - Every source is shifted by a single offset, so the deltas between audio and video tags of the
+///   same source stay intact.
+/// - After a [`splice`](Self::splice) the next source continues right after the last emitted tag,
+///   separated by the configured [gap](Self::with_gap_ms).
+/// - Timestamps never go below `0` and never go backwards for the same media type.
+///   When a video timestamp has to be clamped, the composition time offset is reduced by the same amount
+///   to keep the presentation time.
+/// - When a source starts sending frames without sending its own sequence header first, the last
+///   seen sequence header is emitted again so decoders can pick up the new source.
+#[derive(Debug, Clone, Default)]
+pub struct TimestampRewriter<'a> {
+    gap_ms: u32,
+    normalize: bool,
+    offset: Option<i64>,
+    last_timestamp: Option<u32>,
+    audio: MediaState<'a>,
+    video: MediaState<'a>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MediaState<'a> {
+    last_timestamp: Option<u32>,
+    sequence_header: Option<FlvTag<'a>>,
+    sequence_header_sent: bool,
+}
+
+impl<'a> MediaState<'a> {
+    /// Clamps the timestamp so it does not go backwards.
+    fn clamp(&mut self, timestamp: u32) -> u32 {
+        let timestamp = self.last_timestamp.map_or(timestamp, |last| timestamp.max(last));
+        self.last_timestamp = Some(timestamp);
+        timestamp
+    }
+
+    /// Returns the cached sequence header if a frame is sent before the source sent its own.
+    fn required_sequence_header(&mut self, is_frame: bool, timestamp_ms: u32) -> Option<FlvTag<'a>> {
+        if !is_frame || self.sequence_header_sent {
+            return None;
+        }
+
+        let mut tag = self.sequence_header.clone()?;
+        tag.timestamp_ms = timestamp_ms;
+        self.sequence_header_sent = true;
+        Some(tag)
+    }
+}
+
+impl<'a> TimestampRewriter<'a> {
+    /// Creates a new [`TimestampRewriter`].
+    ///
+    /// By default the timestamps of the first source are kept as is and no gap is inserted between sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the gap in milliseconds between the last tag of a source and the first tag of the next source.
+    pub fn with_gap_ms(mut self, gap_ms: u32) -> Self {
+        self.gap_ms = gap_ms;
+        self
+    }
+
+    /// Re-normalizes the timestamps of the first source so the output starts at `0`.
+    pub fn with_normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Marks the start of a new source.
+    ///
+    /// The next tag passed to [`rewrite`](Self::rewrite) is placed right after the last emitted tag.
+    pub fn splice(&mut self) {
+        self.offset = None;
+        self.audio.sequence_header_sent = false;
+        self.video.sequence_header_sent = false;
+    }
+
+    /// Returns the timestamp of the last emitted tag, if any.
+    pub fn last_timestamp_ms(&self) -> Option<u32> {
+        self.last_timestamp
+    }
+
+    /// Rewrites the timestamp of the given tag.
+    ///
+    /// Returns the tags that should be emitted in order, which is the given tag, possibly preceded by
+    /// a sequence header that the current source did not send.
+    pub fn rewrite(&mut self, mut tag: FlvTag<'a>) -> Vec<FlvTag<'a>> {
+        let offset = *self.offset.get_or_insert_with(|| {
+            let target = match self.last_timestamp {
+                Some(last) => i64::from(last) + i64::from(self.gap_ms),
+                None if self.normalize => 0,
+                None => i64::from(tag.timestamp_ms),
+            };
+
+            target - i64::from(tag.timestamp_ms)
+        });
+
+        let unclamped = i64::from(tag.timestamp_ms) + offset;
+        let timestamp = unclamped.clamp(0, i64::from(u32::MAX)) as u32;
+        let mut tags = Vec::with_capacity(1);
+
+        match &mut tag.data {
+            FlvTagData::Video(data) => {
+                let events = data.events();
+                let is_sequence_header = events.iter().any(|e| matches!(e, VideoEvent::SequenceHeader { .. }));
+                let is_frame = events.iter().any(|e| matches!(e, VideoEvent::Frame { .. }));
+
+                tag.timestamp_ms = self.video.clamp(timestamp);
+                // Both the clamp to 0 and the clamp to the last video timestamp move the tag later
+                let shift = (i64::from(tag.timestamp_ms) - unclamped).clamp(0, i64::from(u32::MAX)) as u32;
+                shift_composition_time_offset(data, shift);
+
+                if is_sequence_header {
+                    self.video.sequence_header = Some(tag.clone());
+                    self.video.sequence_header_sent = true;
+                }
+
+                tags.extend(self.video.required_sequence_header(is_frame, tag.timestamp_ms));
+            }
+            FlvTagData::Audio(data) => {
+                let events = data.events();
+                let is_sequence_header = events.iter().any(|e| matches!(e, AudioEvent::SequenceHeader { .. }));
+                let is_frame = events.iter().any(|e| matches!(e, AudioEvent::Frame { .. }));
+
+                tag.timestamp_ms = self.audio.clamp(timestamp);
+
+                if is_sequence_header {
+                    self.audio.sequence_header = Some(tag.clone());
+                    self.audio.sequence_header_sent = true;
+                }
+
+                tags.extend(self.audio.required_sequence_header(is_frame, tag.timestamp_ms));
+            }
+            _ => tag.timestamp_ms = timestamp,
+        }
+
+        self.last_timestamp = Some(
+            self.last_timestamp
+                .map_or(tag.timestamp_ms, |last| last.max(tag.timestamp_ms)),
+        );
+        tags.push(tag);
+        tags
+    }
+}
+
+/// Reduces the composition time offsets of all coded frames by the given amount, without going below `0`.
+fn shift_composition_time_offset(data: &mut VideoData<'_>, shift: u32) {
+    if shift == 0 {
+        return;
+    }
+
+    let shift = i32::try_from(shift).unwrap_or(i32::MAX);
+    let shift_enhanced = |packet: &mut VideoPacket<'_>| {
+        if let VideoPacket::CodedFrames(
+            VideoPacketCodedFrames::Avc {
+                composition_time_offset, ..
+            }
+            | VideoPacketCodedFrames::Hevc {
+                composition_time_offset, ..
+            },
+        ) = packet
+        {
+            *composition_time_offset = composition_time_offset.saturating_sub(shift).max(0);
+        }
+    };
+
+    match (&mut data.header.data, &mut data.body) {
+        (
+            VideoTagHeaderData::Legacy(LegacyVideoTagHeader::AvcPacket(LegacyVideoTagHeaderAvcPacket::Nalu {
+                composition_time_offset,
+            })),
+            _,
+        ) => {
+            // Sign extend the 24 bit value before shifting it
+            let offset = ((*composition_time_offset << 8) as i32) >> 8;
+            *composition_time_offset = (offset.saturating_sub(shift).max(0) as u32) & 0x00FF_FFFF;
+        }
+        (_, VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack { packet, .. })) => shift_enhanced(packet),
+        (_, VideoTagBody::Enhanced(ExVideoTagBody::ManyTracks(tracks))) => {
+            tracks.iter_mut().for_each(|track| shift_enhanced(&mut track.packet));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use bytes::Bytes;
+
+    use super::TimestampRewriter;
+    use crate::audio::AudioData;
+    use crate::event::VideoEvent;
+    use crate::tag::{FlvTag, FlvTagData};
+    use crate::video::VideoData;
+
+    fn video(timestamp_ms: u32, data: &'static [u8]) -> FlvTag<'static> {
+        FlvTag {
+            timestamp_ms,
+            stream_id: 0,
+            data: FlvTagData::Video(VideoData::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap()),
+        }
+    }
+
+    fn audio(timestamp_ms: u32, data: &'static [u8]) -> FlvTag<'static> {
+        FlvTag {
+            timestamp_ms,
+            stream_id: 0,
+            data: FlvTagData::Audio(AudioData::demux(&mut io::Cursor::new(Bytes::from_static(data))).unwrap()),
+        }
+    }
+
+    fn timestamps(tags: &[FlvTag<'_>]) -> Vec<u32> {
+        tags.iter().map(|tag| tag.timestamp_ms).collect()
+    }
+
+    // enhanced, keyframe, sequence start, vp09
+    const VIDEO_SEQUENCE_HEADER: &[u8] = &[0b1001_0000, b'v', b'p', b'0', b'9', 0x01];
+    // enhanced, keyframe, coded frames x, vp09
+    const VIDEO_FRAME: &[u8] = &[0b1001_0011, b'v', b'p', b'0', b'9', 0x42];
+    // aac, sequence header
+    const AUDIO_SEQUENCE_HEADER: &[u8] = &[0xAF, 0x00, 0x12, 0x10];
+    // aac, raw
+    const AUDIO_FRAME: &[u8] = &[0xAF, 0x01, 0x42];
+
+    #[test]
+    fn offset() {
+        let mut rewriter = TimestampRewriter::new().with_gap_ms(10);
+
+        assert_eq!(timestamps(&rewriter.rewrite(video(100, VIDEO_SEQUENCE_HEADER))), [100]);
+        assert_eq!(timestamps(&rewriter.rewrite(video(100, VIDEO_FRAME))), [100]);
+        assert_eq!(timestamps(&rewriter.rewrite(audio(120, AUDIO_SEQUENCE_HEADER))), [120]);
+        assert_eq!(timestamps(&rewriter.rewrite(video(133, VIDEO_FRAME))), [133]);
+        assert_eq!(rewriter.last_timestamp_ms(), Some(133));
+
+        rewriter.splice();
+
+        // The new source starts over at 5, the audio/video delta is kept
+        assert_eq!(timestamps(&rewriter.rewrite(video(5, VIDEO_SEQUENCE_HEADER))), [143]);
+        assert_eq!(timestamps(&rewriter.rewrite(audio(25, AUDIO_SEQUENCE_HEADER))), [163]);
+        assert_eq!(timestamps(&rewriter.rewrite(video(38, VIDEO_FRAME))), [176]);
+    }
+
+    #[test]
+    fn normalize() {
+        let mut rewriter = TimestampRewriter::new().with_normalize(true);
+
+        assert_eq!(timestamps(&rewriter.rewrite(video(1000, VIDEO_SEQUENCE_HEADER))), [0]);
+        assert_eq!(timestamps(&rewriter.rewrite(video(1033, VIDEO_FRAME))), [33]);
+    }
+
+    #[test]
+    fn clamp() {
+        let mut rewriter = TimestampRewriter::new();
+
+        assert_eq!(timestamps(&rewriter.rewrite(video(100, VIDEO_FRAME))), [100]);
+        assert_eq!(timestamps(&rewriter.rewrite(video(90, VIDEO_FRAME))), [100]);
+        // audio is clamped independently of video
+        assert_eq!(timestamps(&rewriter.rewrite(audio(90, AUDIO_FRAME))), [90]);
+
+        rewriter.splice();
+
+        // A splice into a source that starts above the previous one moves it back
+        assert_eq!(timestamps(&rewriter.rewrite(video(5000, VIDEO_FRAME))), [100]);
+        assert_eq!(timestamps(&rewriter.rewrite(audio(4980, AUDIO_FRAME))), [90]);
+        assert_eq!(timestamps(&rewriter.rewrite(audio(4950, AUDIO_FRAME))), [90]);
+    }
+
+    #[test]
+    fn clamp_composition_time_offset() {
+        let mut rewriter = TimestampRewriter::new();
+
+        // keyframe, avc, nalu, composition time offset 40
+        rewriter.rewrite(video(100, &[0x17, 0x01, 0x00, 0x00, 0x28, 0x42]));
+        let tags = rewriter.rewrite(video(90, &[0x17, 0x01, 0x00, 0x00, 0x28, 0x42]));
+
+        let FlvTagData::Video(data) = &tags[0].data else {
+            panic!("expected video data");
+        };
+        assert_eq!(tags[0].timestamp_ms, 100);
+        assert!(matches!(
+            data.events()[0],
+            VideoEvent::Frame {
+                composition_time_offset: 30,
+                ..
+            }
+        ));
+
+        // enhanced, keyframe, coded frames, hvc1, composition time offset 5
+        let tags = rewriter.rewrite(video(80, &[0b1001_0001, b'h', b'v', b'c', b'1', 0x00, 0x00, 0x05, 0x42]));
+        let FlvTagData::Video(data) = &tags[0].data else {
+            panic!("expected video data");
+        };
+        assert!(matches!(
+            data.events()[0],
+            VideoEvent::Frame {
+                composition_time_offset: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn clamp_composition_time_offset_normalize() {
+        let mut rewriter = TimestampRewriter::new().with_normalize(true);
+
+        // The first tag of the source is later than the following video tag
+        assert_eq!(timestamps(&rewriter.rewrite(audio(1000, AUDIO_FRAME))), [0]);
+
+        // keyframe, avc, nalu, composition time offset 40
+        let tags = rewriter.rewrite(video(990, &[0x17, 0x01, 0x00, 0x00, 0x28, 0x42]));
+        let FlvTagData::Video(data) = &tags[0].data else {
+            panic!("expected video data");
+        };
+        // The presentation time 1030 is shifted to 30
+        assert_eq!(tags[0].timestamp_ms, 0);
+        assert!(matches!(
+            data.events()[0],
+            VideoEvent::Frame {
+                composition_time_offset: 30,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn sequence_headers() {
+        let mut rewriter = TimestampRewriter::new();
+
+        rewriter.rewrite(video(0, VIDEO_SEQUENCE_HEADER));
+        rewriter.rewrite(audio(0, AUDIO_SEQUENCE_HEADER));
+        assert_eq!(rewriter.rewrite(video(0, VIDEO_FRAME)).len(), 1);
+        assert_eq!(rewriter.rewrite(audio(20, AUDIO_FRAME)).len(), 1);
+
+        rewriter.splice();
+
+        // The new source does not send a video sequence header
+        let tags = rewriter.rewrite(video(0, VIDEO_FRAME));
+        assert_eq!(tags, [video(20, VIDEO_SEQUENCE_HEADER), video(20, VIDEO_FRAME)]);
+        assert_eq!(rewriter.rewrite(video(33, VIDEO_FRAME)).len(), 1);
+
+        // The new source sends its own audio sequence header
+        assert_eq!(
+            rewriter.rewrite(audio(10, AUDIO_SEQUENCE_HEADER)),
+            [audio(30, AUDIO_SEQUENCE_HEADER)]
+        );
+        assert_eq!(rewriter.rewrite(audio(30, AUDIO_FRAME)), [audio(50, AUDIO_FRAME)]);
+    }
+}
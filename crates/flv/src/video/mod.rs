@@ -44,6 +44,305 @@ impl VideoData<'_> {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for VideoData<'_> {
+    /// Generates video data that demuxes back into the same value.
+    ///
+    /// For that the header and the body always match, enhanced timestamp offsets are exactly
+    /// 3 bytes long and commands carry no data. Codec configuration records are not generated,
+    /// sequence starts use a FOURCC which is kept as raw bytes instead.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use body::enhanced::metadata::{
+            MetadataColorInfo, MetadataColorInfoColorConfig, MetadataColorInfoHdrCll, MetadataColorInfoHdrMdcv,
+            VideoPacketMetadataEntry,
+        };
+        use body::enhanced::{
+            ExVideoTagBody, VideoPacket, VideoPacketCodedFrames, VideoPacketMpeg2TsSequenceStart, VideoPacketSequenceStart,
+            VideoTrack,
+        };
+        use body::legacy::LegacyVideoTagBody;
+        use header::enhanced::{
+            ExVideoTagHeader, ExVideoTagHeaderContent, VideoFourCc, VideoPacketModEx, VideoPacketModExType, VideoPacketType,
+        };
+        use header::legacy::{AvcPacketType, LegacyVideoTagHeader, LegacyVideoTagHeaderAvcPacket, VideoCodecId};
+        use header::{VideoCommand, VideoFrameType, VideoTagHeaderData};
+
+        use crate::common::AvMultitrackType;
+        use crate::fuzz::{arbitrary_amf0_object, arbitrary_bytes, arbitrary_number};
+
+        fn number(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Option<f64>> {
+            if u.arbitrary()? {
+                arbitrary_number(u).map(Some)
+            } else {
+                Ok(None)
+            }
+        }
+
+        fn four_cc(
+            u: &mut arbitrary::Unstructured<'_>,
+            video_packet_type: VideoPacketType,
+        ) -> arbitrary::Result<VideoFourCc> {
+            let video_four_cc = VideoFourCc(u.arbitrary()?);
+
+            // These are parsed into codec configuration records, which are not generated.
+            let parsed: &[VideoFourCc] = match video_packet_type {
+                VideoPacketType::SequenceStart => &[VideoFourCc::Av1, VideoFourCc::Avc, VideoFourCc::Hevc],
+                VideoPacketType::Mpeg2TsSequenceStart => &[VideoFourCc::Av1],
+                _ => &[],
+            };
+
+            if parsed.contains(&video_four_cc) {
+                Ok(VideoFourCc::Vp9)
+            } else {
+                Ok(video_four_cc)
+            }
+        }
+
+        fn packet<'b>(
+            u: &mut arbitrary::Unstructured<'_>,
+            video_packet_type: VideoPacketType,
+            video_four_cc: VideoFourCc,
+        ) -> arbitrary::Result<VideoPacket<'b>> {
+            Ok(match video_packet_type {
+                VideoPacketType::Metadata => {
+                    let mut entries = Vec::new();
+                    while u.arbitrary()? {
+                        entries.push(if u.arbitrary()? {
+                            VideoPacketMetadataEntry::ColorInfo(MetadataColorInfo {
+                                color_config: if u.arbitrary()? {
+                                    Some(MetadataColorInfoColorConfig {
+                                        bit_depth: number(u)?,
+                                        color_primaries: number(u)?,
+                                        transfer_characteristics: number(u)?,
+                                        matrix_coefficients: number(u)?,
+                                    })
+                                } else {
+                                    None
+                                },
+                                hdr_cll: if u.arbitrary()? {
+                                    Some(MetadataColorInfoHdrCll {
+                                        max_fall: number(u)?,
+                                        max_cll: number(u)?,
+                                    })
+                                } else {
+                                    None
+                                },
+                                hdr_mdcv: if u.arbitrary()? {
+                                    Some(MetadataColorInfoHdrMdcv {
+                                        red_x: number(u)?,
+                                        red_y: number(u)?,
+                                        green_x: number(u)?,
+                                        green_y: number(u)?,
+                                        blue_x: number(u)?,
+                                        blue_y: number(u)?,
+                                        white_point_x: number(u)?,
+                                        white_point_y: number(u)?,
+                                        max_luminance: number(u)?,
+                                        min_luminance: number(u)?,
+                                    })
+                                } else {
+                                    None
+                                },
+                            })
+                        } else {
+                            let key = u.arbitrary::<String>()?;
+                            if key == "colorInfo" {
+                                continue;
+                            }
+
+                            VideoPacketMetadataEntry::Other {
+                                key: key.into(),
+                                object: arbitrary_amf0_object(u, 2, &[])?,
+                            }
+                        });
+                    }
+
+                    VideoPacket::Metadata(entries)
+                }
+                VideoPacketType::SequenceEnd => VideoPacket::SequenceEnd,
+                VideoPacketType::SequenceStart => {
+                    VideoPacket::SequenceStart(VideoPacketSequenceStart::Other(arbitrary_bytes(u)?))
+                }
+                VideoPacketType::Mpeg2TsSequenceStart => {
+                    VideoPacket::Mpeg2TsSequenceStart(VideoPacketMpeg2TsSequenceStart::Other(arbitrary_bytes(u)?))
+                }
+                VideoPacketType::CodedFrames => VideoPacket::CodedFrames(match video_four_cc {
+                    VideoFourCc::Avc => VideoPacketCodedFrames::Avc {
+                        composition_time_offset: u.int_in_range(-0x800000..=0x7FFFFF)?,
+                        data: arbitrary_bytes(u)?,
+                    },
+                    VideoFourCc::Hevc => VideoPacketCodedFrames::Hevc {
+                        composition_time_offset: u.int_in_range(-0x800000..=0x7FFFFF)?,
+                        data: arbitrary_bytes(u)?,
+                    },
+                    _ => VideoPacketCodedFrames::Other(arbitrary_bytes(u)?),
+                }),
+                VideoPacketType::CodedFramesX => VideoPacket::CodedFramesX {
+                    data: arbitrary_bytes(u)?,
+                },
+                _ => VideoPacket::Unknown {
+                    video_packet_type,
+                    data: arbitrary_bytes(u)?,
+                },
+            })
+        }
+
+        // Only 3 bits are used for the frame type.
+        let frame_type = VideoFrameType(u.int_in_range(0..=7)?);
+
+        if u.arbitrary()? {
+            let video_codec_id = VideoCodecId(u.int_in_range(0..=15)?);
+
+            let (frame_type, header, body) = if video_codec_id == VideoCodecId::Avc {
+                // The sequence header is parsed into a codec configuration record, which is not generated.
+                let avc_packet = match u.int_in_range(0..=2)? {
+                    0 => LegacyVideoTagHeaderAvcPacket::Nalu {
+                        composition_time_offset: u.int_in_range(0..=0xFFFFFF)?,
+                    },
+                    1 => LegacyVideoTagHeaderAvcPacket::EndOfSequence,
+                    _ => LegacyVideoTagHeaderAvcPacket::Unknown {
+                        avc_packet_type: AvcPacketType(u.int_in_range(3..=u8::MAX)?),
+                        composition_time_offset: u.int_in_range(0..=0xFFFFFF)?,
+                    },
+                };
+
+                (
+                    frame_type,
+                    LegacyVideoTagHeader::AvcPacket(avc_packet),
+                    LegacyVideoTagBody::Other {
+                        data: arbitrary_bytes(u)?,
+                    },
+                )
+            } else if frame_type == VideoFrameType::Command {
+                (
+                    frame_type,
+                    LegacyVideoTagHeader::VideoCommand(VideoCommand(u.arbitrary()?)),
+                    LegacyVideoTagBody::Command,
+                )
+            } else {
+                (
+                    frame_type,
+                    LegacyVideoTagHeader::Other { video_codec_id },
+                    LegacyVideoTagBody::Other {
+                        data: arbitrary_bytes(u)?,
+                    },
+                )
+            };
+
+            return Ok(Self {
+                header: VideoTagHeader {
+                    frame_type,
+                    data: VideoTagHeaderData::Legacy(header),
+                },
+                body: VideoTagBody::Legacy(body),
+            });
+        }
+
+        let mut video_packet_mod_exs = Vec::new();
+        while u.arbitrary()? {
+            let video_packet_mod_ex_type = VideoPacketModExType(u.int_in_range(0..=15)?);
+
+            video_packet_mod_exs.push(if video_packet_mod_ex_type == VideoPacketModExType::TimestampOffsetNano {
+                VideoPacketModEx::TimestampOffsetNano {
+                    video_timestamp_nano_offset: u.int_in_range(0..=0xFFFFFF)?,
+                }
+            } else {
+                // The data is between 1 and 65536 bytes long.
+                let mut mod_ex_data = u.arbitrary::<Vec<u8>>()?;
+                mod_ex_data.resize(mod_ex_data.len().clamp(1, 0x10000), 0);
+
+                VideoPacketModEx::Other {
+                    video_packet_mod_ex_type,
+                    mod_ex_data: mod_ex_data.into(),
+                }
+            });
+        }
+
+        if frame_type == VideoFrameType::Command && u.arbitrary()? {
+            return Ok(Self {
+                header: VideoTagHeader {
+                    frame_type,
+                    data: VideoTagHeaderData::Enhanced(ExVideoTagHeader {
+                        video_packet_mod_exs,
+                        // Everything except the metadata and modifier extension markers.
+                        video_packet_type: VideoPacketType(*u.choose(&[0, 1, 2, 3, 5, 6, 8, 9, 10, 11, 12, 13, 14, 15])?),
+                        content: ExVideoTagHeaderContent::VideoCommand(VideoCommand(u.arbitrary()?)),
+                    }),
+                },
+                body: VideoTagBody::Enhanced(ExVideoTagBody::Command),
+            });
+        }
+
+        // Everything except the multitrack and modifier extension markers.
+        let video_packet_type = VideoPacketType(*u.choose(&[0, 1, 2, 3, 4, 5, 8, 9, 10, 11, 12, 13, 14, 15])?);
+
+        let content = match u.int_in_range(0..=4)? {
+            0 => ExVideoTagHeaderContent::NoMultiTrack(four_cc(u, video_packet_type)?),
+            1 => ExVideoTagHeaderContent::OneTrack(four_cc(u, video_packet_type)?),
+            2 => ExVideoTagHeaderContent::ManyTracks(four_cc(u, video_packet_type)?),
+            3 => ExVideoTagHeaderContent::ManyTracksManyCodecs,
+            _ => ExVideoTagHeaderContent::Unknown {
+                video_multitrack_type: AvMultitrackType(u.int_in_range(3..=15)?),
+                video_four_cc: four_cc(u, video_packet_type)?,
+            },
+        };
+
+        // Command frames are always demuxed as commands, unless they carry metadata without multiple tracks.
+        let frame_type = if frame_type == VideoFrameType::Command
+            && (video_packet_type != VideoPacketType::Metadata
+                || !matches!(content, ExVideoTagHeaderContent::NoMultiTrack(_)))
+        {
+            VideoFrameType::KeyFrame
+        } else {
+            frame_type
+        };
+
+        let body = match &content {
+            ExVideoTagHeaderContent::NoMultiTrack(video_four_cc) => ExVideoTagBody::NoMultitrack {
+                video_four_cc: *video_four_cc,
+                packet: packet(u, video_packet_type, *video_four_cc)?,
+            },
+            content => {
+                let mut tracks = Vec::new();
+
+                // There is always at least one track.
+                loop {
+                    let video_four_cc = match content {
+                        ExVideoTagHeaderContent::OneTrack(video_four_cc)
+                        | ExVideoTagHeaderContent::ManyTracks(video_four_cc)
+                        | ExVideoTagHeaderContent::Unknown { video_four_cc, .. } => *video_four_cc,
+                        _ => four_cc(u, video_packet_type)?,
+                    };
+
+                    tracks.push(VideoTrack {
+                        video_four_cc,
+                        video_track_id: u.arbitrary()?,
+                        packet: packet(u, video_packet_type, video_four_cc)?,
+                    });
+
+                    if matches!(content, ExVideoTagHeaderContent::OneTrack(_)) || !u.arbitrary()? {
+                        break;
+                    }
+                }
+
+                ExVideoTagBody::ManyTracks(tracks)
+            }
+        };
+
+        Ok(Self {
+            header: VideoTagHeader {
+                frame_type,
+                data: VideoTagHeaderData::Enhanced(ExVideoTagHeader {
+                    video_packet_mod_exs,
+                    video_packet_type,
+                    content,
+                }),
+            },
+            body: VideoTagBody::Enhanced(body),
+        })
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
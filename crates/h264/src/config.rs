@@ -1,12 +1,15 @@
+use std::collections::BTreeMap;
 use std::io::{
     Write, {self},
 };
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use bytes::{Buf, Bytes};
-use scuffle_bytes_util::{BitReader, BitWriter, BytesCursorExt};
+use scuffle_bytes_util::{BitReader, BitWriter, BytesCursorExt, EmulationPreventionIo, range_check};
+use scuffle_expgolomb::BitReaderExpGolombExt;
 
-use crate::sps::SpsExtended;
+use crate::NALUnitType;
+use crate::sps::{Sps, SpsExtended};
 
 /// The AVC (H.264) Decoder Configuration Record.
 /// ISO/IEC 14496-15:2022(E) - 5.3.2.1.2
@@ -92,6 +95,11 @@ pub struct AvccExtendedConfig {
 }
 
 impl AVCDecoderConfigurationRecord {
+    /// Returns a builder which constructs an AVCDecoderConfigurationRecord from SPS and PPS NAL units.
+    pub fn builder() -> AVCDecoderConfigurationRecordBuilder {
+        AVCDecoderConfigurationRecordBuilder::new()
+    }
+
     /// Parses an AVCDecoderConfigurationRecord from a byte stream.
     /// Returns a parsed AVCDecoderConfigurationRecord.
     pub fn parse(reader: &mut io::Cursor<Bytes>) -> io::Result<Self> {
@@ -165,6 +173,19 @@ impl AVCDecoderConfigurationRecord {
         })
     }
 
+    /// Returns the codec string of the stream as used in the `codecs` parameter of HLS and DASH playlists,
+    /// for example `avc1.64001F`.
+    ///
+    /// The profile, constraint flags and level are each encoded as two hexadecimal digits.
+    ///
+    /// ISO/IEC 14496-15:2022(E) - E.3
+    pub fn codec_string(&self) -> String {
+        format!(
+            "avc1.{:02X}{:02X}{:02X}",
+            self.profile_indication, self.profile_compatibility, self.level_indication
+        )
+    }
+
     /// Returns the total byte size of the AVCDecoderConfigurationRecord.
     pub fn size(&self) -> u64 {
         1 // configuration_version
@@ -252,6 +273,179 @@ impl AVCDecoderConfigurationRecord {
     }
 }
 
+/// A builder for the [`AVCDecoderConfigurationRecord`] from SPS and PPS NAL units.
+///
+/// Parameter sets are keyed by their id, so adding a parameter set with an id that is already
+/// known replaces the previous one and adding the exact same parameter set twice is a no-op.
+/// This makes it possible to feed every parameter set found in an Annex-B stream into the builder
+/// and only rebuild the record when [`add_nal_unit`](Self::add_nal_unit) reports a change.
+///
+/// The profile, profile compatibility and level of the record are derived from the SPS sets,
+/// as described in ISO/IEC 14496-15:2022(E) - 5.3.2.1.2.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AVCDecoderConfigurationRecordBuilder {
+    length_size_minus_one: u8,
+    sps: BTreeMap<u16, (Sps, Bytes)>,
+    pps: BTreeMap<u16, Bytes>,
+}
+
+impl Default for AVCDecoderConfigurationRecordBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AVCDecoderConfigurationRecordBuilder {
+    /// Creates a new builder without any parameter sets, using 4 byte NAL unit lengths.
+    pub fn new() -> Self {
+        Self {
+            length_size_minus_one: 3,
+            sps: BTreeMap::new(),
+            pps: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the length in bytes of the NAL unit length prefix minus one.
+    ///
+    /// Valid values are 0, 1 and 3, the default is 3.
+    pub fn length_size_minus_one(mut self, length_size_minus_one: u8) -> Self {
+        self.length_size_minus_one = length_size_minus_one;
+        self
+    }
+
+    /// Adds a NAL unit (without start code or length prefix) to the builder.
+    ///
+    /// SPS and PPS NAL units are added with [`add_sps`](Self::add_sps) and
+    /// [`add_pps`](Self::add_pps), all other NAL units are ignored.
+    ///
+    /// Returns `true` if the parameter sets of the builder changed.
+    pub fn add_nal_unit(&mut self, nal_unit: Bytes) -> io::Result<bool> {
+        let Some(&header) = nal_unit.first() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "NAL unit is empty"));
+        };
+
+        match NALUnitType(header & 0b00011111) {
+            NALUnitType::SPS => self.add_sps(nal_unit),
+            NALUnitType::PPS => self.add_pps(nal_unit),
+            _ => Ok(false),
+        }
+    }
+
+    /// Adds an SPS NAL unit to the builder, replacing any SPS with the same `seq_parameter_set_id`.
+    ///
+    /// Returns `true` if the SPS sets of the builder changed.
+    pub fn add_sps(&mut self, nal_unit: Bytes) -> io::Result<bool> {
+        let sps = Sps::parse_with_emulation_prevention(io::Cursor::new(&nal_unit))?;
+
+        if self
+            .sps
+            .get(&sps.seq_parameter_set_id)
+            .is_some_and(|(_, data)| *data == nal_unit)
+        {
+            return Ok(false);
+        }
+
+        self.sps.insert(sps.seq_parameter_set_id, (sps, nal_unit));
+        Ok(true)
+    }
+
+    /// Adds a PPS NAL unit to the builder, replacing any PPS with the same `pic_parameter_set_id`.
+    ///
+    /// Returns `true` if the PPS sets of the builder changed.
+    pub fn add_pps(&mut self, nal_unit: Bytes) -> io::Result<bool> {
+        let mut bit_reader = BitReader::new(EmulationPreventionIo::new(io::Cursor::new(&nal_unit)));
+
+        let forbidden_zero_bit = bit_reader.read_bit()?;
+        if forbidden_zero_bit {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Forbidden zero bit is set"));
+        }
+
+        bit_reader.read_bits(2)?; // nal_ref_idc
+        let nal_unit_type = bit_reader.read_bits(5)? as u8;
+        if NALUnitType(nal_unit_type) != NALUnitType::PPS {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "NAL unit type is not PPS"));
+        }
+
+        // ISO/IEC-14496-10-2022 - 7.4.2.2
+        let pic_parameter_set_id = bit_reader.read_exp_golomb()?;
+        range_check!(pic_parameter_set_id, 0, 255)?;
+        let pic_parameter_set_id = pic_parameter_set_id as u16;
+
+        if self.pps.get(&pic_parameter_set_id).is_some_and(|data| *data == nal_unit) {
+            return Ok(false);
+        }
+
+        self.pps.insert(pic_parameter_set_id, nal_unit);
+        Ok(true)
+    }
+
+    /// Removes all parameter sets from the builder.
+    pub fn clear(&mut self) {
+        self.sps.clear();
+        self.pps.clear();
+    }
+
+    /// Builds the AVCDecoderConfigurationRecord from the parameter sets added so far.
+    ///
+    /// Fails if there is no SPS or PPS, or if the SPS sets signal different profiles.
+    pub fn build(&self) -> io::Result<AVCDecoderConfigurationRecord> {
+        if !matches!(self.length_size_minus_one, 0 | 1 | 3) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "length_size_minus_one must be 0, 1 or 3",
+            ));
+        }
+
+        let Some((first, _)) = self.sps.values().next() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no SPS was added"));
+        };
+
+        if self.pps.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no PPS was added"));
+        }
+
+        // num_of_sequence_parameter_sets is only 5 bits
+        if self.sps.len() > 31 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "too many SPS sets"));
+        }
+
+        if self.sps.values().any(|(sps, _)| sps.profile_idc != first.profile_idc) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "SPS sets have different profiles"));
+        }
+
+        // The profile compatibility is the byte between profile_idc and level_idc in the SPS.
+        // A flag may only be set if it is set in all SPS sets.
+        let profile_compatibility = self
+            .sps
+            .values()
+            .map(|(_, data)| data.get(2).copied().unwrap_or_default())
+            .fold(0xFF, |acc, flags| acc & flags);
+
+        let level_indication = self.sps.values().map(|(sps, _)| sps.level_idc).max().unwrap_or_default();
+
+        let extended_config = match first.profile_idc {
+            66 | 77 | 88 => None,
+            _ => Some(AvccExtendedConfig {
+                chroma_format_idc: first.ext.as_ref().map(|ext| ext.chroma_format_idc).unwrap_or(1),
+                bit_depth_luma_minus8: first.ext.as_ref().map(|ext| ext.bit_depth_luma_minus8).unwrap_or(0),
+                bit_depth_chroma_minus8: first.ext.as_ref().map(|ext| ext.bit_depth_chroma_minus8).unwrap_or(0),
+                sequence_parameter_set_ext: Vec::new(),
+            }),
+        };
+
+        Ok(AVCDecoderConfigurationRecord {
+            configuration_version: 1,
+            profile_indication: first.profile_idc,
+            profile_compatibility,
+            level_indication,
+            length_size_minus_one: self.length_size_minus_one,
+            sps: self.sps.values().map(|(_, data)| data.clone()).collect(),
+            pps: self.pps.values().cloned().collect(),
+            extended_config,
+        })
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
@@ -262,7 +456,7 @@ mod tests {
     use scuffle_bytes_util::BitWriter;
 
     use crate::config::{AVCDecoderConfigurationRecord, AvccExtendedConfig};
-    use crate::sps::SpsExtended;
+    use crate::sps::{Sps, SpsExtended};
 
     #[test]
     fn test_config_parse() {
@@ -310,6 +504,7 @@ mod tests {
         let sps = &result.sps[0];
 
         assert_eq!(**sps, *sample_sps);
+        assert_eq!(result.codec_string(), "avc1.64001F");
     }
 
     #[test]
@@ -466,4 +661,105 @@ mod tests {
         }
         "#);
     }
+
+    const SAMPLE_SPS: &[u8] = b"gd\0\x1f\xac\xd9A\xe0m\xf9\xe6\xa0  (\0\0\x03\0\x08\0\0\x03\x01\xe0x\xc1\x8c\xb0";
+    const SAMPLE_PPS: &[u8] = b"h\xeb\xe3\xcb\"\xc0";
+
+    fn modified_sps(f: impl FnOnce(&mut Sps)) -> Bytes {
+        let mut sps = Sps::parse_with_emulation_prevention(io::Cursor::new(SAMPLE_SPS)).unwrap();
+        f(&mut sps);
+
+        let mut buf = Vec::new();
+        sps.build_with_emulation_prevention(&mut buf).unwrap();
+        buf.into()
+    }
+
+    #[test]
+    fn test_config_builder() {
+        let mut data = b"\x01d\0\x1f\xff\xe1\0\x1d".to_vec();
+        data.extend_from_slice(SAMPLE_SPS);
+        data.extend_from_slice(b"\x01\0\x06");
+        data.extend_from_slice(SAMPLE_PPS);
+        data.extend_from_slice(b"\xfd\xf8\xf8\0");
+        let data = Bytes::from(data);
+
+        let mut builder = AVCDecoderConfigurationRecord::builder();
+        assert!(builder.add_nal_unit(Bytes::from_static(SAMPLE_SPS)).unwrap());
+        assert!(builder.add_nal_unit(Bytes::from_static(SAMPLE_PPS)).unwrap());
+        // slices are ignored
+        assert!(!builder.add_nal_unit(Bytes::from_static(b"\x65\x88\x84")).unwrap());
+
+        let config = builder.build().unwrap();
+        assert_eq!(
+            config,
+            AVCDecoderConfigurationRecord::parse(&mut io::Cursor::new(data.clone())).unwrap()
+        );
+        assert_eq!(config.codec_string(), "avc1.64001F");
+
+        let mut buf = Vec::new();
+        config.build(&mut buf).unwrap();
+        assert_eq!(buf, data.to_vec());
+    }
+
+    #[test]
+    fn test_config_builder_parameter_sets() {
+        let mut builder = AVCDecoderConfigurationRecord::builder().length_size_minus_one(1);
+        assert!(builder.add_sps(Bytes::from_static(SAMPLE_SPS)).unwrap());
+        assert!(!builder.add_sps(Bytes::from_static(SAMPLE_SPS)).unwrap());
+        assert!(builder.add_pps(Bytes::from_static(SAMPLE_PPS)).unwrap());
+        assert!(!builder.add_pps(Bytes::from_static(SAMPLE_PPS)).unwrap());
+
+        // a second SPS with a higher level
+        let sps1 = modified_sps(|sps| {
+            sps.seq_parameter_set_id = 1;
+            sps.level_idc = 40;
+        });
+        assert!(builder.add_sps(sps1.clone()).unwrap());
+
+        // replaces the SPS with id 0
+        let sps0 = modified_sps(|sps| sps.max_num_ref_frames = 2);
+        assert!(builder.add_sps(sps0.clone()).unwrap());
+
+        // pic_parameter_set_id = 1
+        let pps1 = Bytes::from_static(b"\x68\x5b\xe3\xcb\x22\xc0");
+        assert!(builder.add_pps(pps1.clone()).unwrap());
+
+        let config = builder.build().unwrap();
+        assert_eq!(config.length_size_minus_one, 1);
+        assert_eq!(config.profile_indication, 100);
+        assert_eq!(config.profile_compatibility, 0);
+        assert_eq!(config.level_indication, 40);
+        assert_eq!(config.sps, vec![sps0, sps1]);
+        assert_eq!(config.pps, vec![Bytes::from_static(SAMPLE_PPS), pps1]);
+
+        builder.clear();
+        assert_eq!(builder.build().unwrap_err().to_string(), "no SPS was added");
+    }
+
+    #[test]
+    fn test_config_builder_errors() {
+        let mut builder = AVCDecoderConfigurationRecord::builder();
+        assert!(builder.add_nal_unit(Bytes::new()).is_err());
+        assert!(builder.add_sps(Bytes::from_static(SAMPLE_PPS)).is_err());
+        assert!(builder.add_pps(Bytes::from_static(SAMPLE_SPS)).is_err());
+
+        builder.add_sps(Bytes::from_static(SAMPLE_SPS)).unwrap();
+        assert_eq!(builder.build().unwrap_err().to_string(), "no PPS was added");
+
+        builder.add_pps(Bytes::from_static(SAMPLE_PPS)).unwrap();
+        builder
+            .add_sps(modified_sps(|sps| {
+                sps.seq_parameter_set_id = 1;
+                sps.profile_idc = 77;
+                sps.ext = None;
+            }))
+            .unwrap();
+        assert_eq!(builder.build().unwrap_err().to_string(), "SPS sets have different profiles");
+
+        let builder = builder.length_size_minus_one(2);
+        assert_eq!(
+            builder.build().unwrap_err().to_string(),
+            "length_size_minus_one must be 0, 1 or 3"
+        );
+    }
 }
@@ -73,6 +73,26 @@
 //!
 //! For more examples, check out the tests in the source code for the build function.
 //!
+//! ### Building from parameter sets
+//!
+//! ```rust
+//! use bytes::Bytes;
+//!
+//! use scuffle_h264::AVCDecoderConfigurationRecord;
+//!
+//! # let sps = Bytes::from_static(b"gd\0\x1f\xac\xd9A\xe0m\xf9\xe6\xa0  (\0\0\x03\0\x08\0\0\x03\x01\xe0x\xc1\x8c\xb0");
+//! # let pps = Bytes::from_static(b"h\xeb\xe3\xcb\"\xc0");
+//! let mut builder = AVCDecoderConfigurationRecord::builder();
+//!
+//! // Feed the NAL units of the stream, for example after splitting an Annex-B stream.
+//! // Only SPS and PPS NAL units are kept, duplicates are ignored.
+//! builder.add_nal_unit(sps).unwrap();
+//! builder.add_nal_unit(pps).unwrap();
+//!
+//! let config = builder.build().unwrap();
+//! assert_eq!(config.codec_string(), "avc1.64001F");
+//! ```
+//!
 //! ## License
 //!
 //! This project is licensed under the MIT or Apache-2.0 license.
@@ -92,7 +112,7 @@ mod sps;
 pub use enums::*;
 pub use sps::*;
 
-pub use self::config::{AVCDecoderConfigurationRecord, AvccExtendedConfig};
+pub use self::config::{AVCDecoderConfigurationRecord, AVCDecoderConfigurationRecordBuilder, AvccExtendedConfig};
 
 /// Changelogs generated by [scuffle_changelog]
 #[cfg(feature = "docs")]
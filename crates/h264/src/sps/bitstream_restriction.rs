@@ -0,0 +1,241 @@
+use std::io;
+
+use scuffle_bytes_util::{BitReader, BitWriter, range_check};
+use scuffle_expgolomb::{BitReaderExpGolombExt, BitWriterExpGolombExt, size_of_exp_golomb};
+
+/// `BitstreamRestriction` contains the fields that are set when `bitstream_restriction_flag == 1`.
+///
+/// This contains the following fields: `motion_vectors_over_pic_boundaries_flag`, `max_bytes_per_pic_denom`,
+/// `max_bits_per_mb_denom`, `log2_max_mv_length_horizontal`, `log2_max_mv_length_vertical`,
+/// `max_num_reorder_frames` and `max_dec_frame_buffering`.
+///
+/// ISO/IEC-14496-10-2022 - E.2.1
+///
+/// Refer to the direct fields for more information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitstreamRestriction {
+    /// The `motion_vectors_over_pic_boundaries_flag` is a single bit.
+    ///
+    /// 0 means no sample outside the picture boundaries is used for inter prediction.
+    ///
+    /// 1 means one or more samples outside the picture boundaries may be used for inter prediction.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub motion_vectors_over_pic_boundaries_flag: bool,
+
+    /// The `max_bytes_per_pic_denom` indicates the maximum number of bytes of the VCL NAL units
+    /// of any coded picture. 0 means no limit is indicated.
+    ///
+    /// The value of this ranges from \[0, 16\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub max_bytes_per_pic_denom: u8,
+
+    /// The `max_bits_per_mb_denom` indicates the maximum number of coded bits of any macroblock.
+    /// 0 means no limit is indicated.
+    ///
+    /// The value of this ranges from \[0, 16\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub max_bits_per_mb_denom: u8,
+
+    /// The `log2_max_mv_length_horizontal` is the maximum absolute value of a decoded horizontal
+    /// motion vector component, in units of 1/4 luma samples, as a log2.
+    ///
+    /// The value of this ranges from \[0, 16\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub log2_max_mv_length_horizontal: u8,
+
+    /// The `log2_max_mv_length_vertical` is the maximum absolute value of a decoded vertical
+    /// motion vector component, in units of 1/4 luma samples, as a log2.
+    ///
+    /// The value of this ranges from \[0, 16\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub log2_max_mv_length_vertical: u8,
+
+    /// The `max_num_reorder_frames` is the maximum number of frames which can precede any frame
+    /// in decoding order and follow it in output order.
+    ///
+    /// The value of this ranges from \[0, `max_dec_frame_buffering`\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub max_num_reorder_frames: u8,
+
+    /// The `max_dec_frame_buffering` is the required size of the decoded picture buffer (DPB)
+    /// in units of frame buffers.
+    ///
+    /// The value of this ranges from \[`max_num_ref_frames`, `MaxDpbFrames`\], where `MaxDpbFrames`
+    /// is at most 16.
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub max_dec_frame_buffering: u8,
+}
+
+impl BitstreamRestriction {
+    /// Parses the fields defined when the `bitstream_restriction_flag == 1` from a bitstream.
+    /// Returns a `BitstreamRestriction` struct.
+    pub fn parse<T: io::Read>(reader: &mut BitReader<T>) -> io::Result<Self> {
+        let motion_vectors_over_pic_boundaries_flag = reader.read_bit()?;
+
+        let max_bytes_per_pic_denom = reader.read_exp_golomb()?;
+        range_check!(max_bytes_per_pic_denom, 0, 16)?;
+        let max_bytes_per_pic_denom = max_bytes_per_pic_denom as u8;
+
+        let max_bits_per_mb_denom = reader.read_exp_golomb()?;
+        range_check!(max_bits_per_mb_denom, 0, 16)?;
+        let max_bits_per_mb_denom = max_bits_per_mb_denom as u8;
+
+        let log2_max_mv_length_horizontal = reader.read_exp_golomb()?;
+        range_check!(log2_max_mv_length_horizontal, 0, 16)?;
+        let log2_max_mv_length_horizontal = log2_max_mv_length_horizontal as u8;
+
+        let log2_max_mv_length_vertical = reader.read_exp_golomb()?;
+        range_check!(log2_max_mv_length_vertical, 0, 16)?;
+        let log2_max_mv_length_vertical = log2_max_mv_length_vertical as u8;
+
+        let max_num_reorder_frames = reader.read_exp_golomb()?;
+        let max_dec_frame_buffering = reader.read_exp_golomb()?;
+        range_check!(max_dec_frame_buffering, 0, 16)?;
+        range_check!(max_num_reorder_frames, 0, max_dec_frame_buffering)?;
+
+        Ok(BitstreamRestriction {
+            motion_vectors_over_pic_boundaries_flag,
+            max_bytes_per_pic_denom,
+            max_bits_per_mb_denom,
+            log2_max_mv_length_horizontal,
+            log2_max_mv_length_vertical,
+            max_num_reorder_frames: max_num_reorder_frames as u8,
+            max_dec_frame_buffering: max_dec_frame_buffering as u8,
+        })
+    }
+
+    /// Builds the BitstreamRestriction struct into a byte stream.
+    /// Returns a built byte stream.
+    pub fn build<T: io::Write>(&self, writer: &mut BitWriter<T>) -> io::Result<()> {
+        writer.write_bit(self.motion_vectors_over_pic_boundaries_flag)?;
+        writer.write_exp_golomb(self.max_bytes_per_pic_denom as u64)?;
+        writer.write_exp_golomb(self.max_bits_per_mb_denom as u64)?;
+        writer.write_exp_golomb(self.log2_max_mv_length_horizontal as u64)?;
+        writer.write_exp_golomb(self.log2_max_mv_length_vertical as u64)?;
+        writer.write_exp_golomb(self.max_num_reorder_frames as u64)?;
+        writer.write_exp_golomb(self.max_dec_frame_buffering as u64)?;
+        Ok(())
+    }
+
+    /// Returns the total bits of the BitstreamRestriction struct.
+    ///
+    /// Note that this isn't the bytesize since aligning it may cause some values to be different.
+    pub fn bitsize(&self) -> u64 {
+        1 + // motion_vectors_over_pic_boundaries_flag
+        size_of_exp_golomb(self.max_bytes_per_pic_denom as u64) +
+        size_of_exp_golomb(self.max_bits_per_mb_denom as u64) +
+        size_of_exp_golomb(self.log2_max_mv_length_horizontal as u64) +
+        size_of_exp_golomb(self.log2_max_mv_length_vertical as u64) +
+        size_of_exp_golomb(self.max_num_reorder_frames as u64) +
+        size_of_exp_golomb(self.max_dec_frame_buffering as u64)
+    }
+
+    /// Returns the total bytes of the BitstreamRestriction struct.
+    ///
+    /// Note that this calls [`BitstreamRestriction::bitsize()`] and calculates the number of bytes
+    /// including any necessary padding such that the bitstream is byte aligned.
+    pub fn bytesize(&self) -> u64 {
+        self.bitsize().div_ceil(8)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use scuffle_bytes_util::{BitReader, BitWriter};
+    use scuffle_expgolomb::BitWriterExpGolombExt;
+
+    use crate::sps::BitstreamRestriction;
+
+    #[test]
+    fn test_build_size_bitstream_restriction() {
+        // create bitstream for bitstream_restriction
+        let mut data = Vec::new();
+        let mut writer = BitWriter::new(&mut data);
+
+        writer.write_bit(true).unwrap();
+        writer.write_exp_golomb(2).unwrap();
+        writer.write_exp_golomb(1).unwrap();
+        writer.write_exp_golomb(16).unwrap();
+        writer.write_exp_golomb(16).unwrap();
+        writer.write_exp_golomb(2).unwrap();
+        writer.write_exp_golomb(4).unwrap();
+        writer.finish().unwrap();
+
+        // parse bitstream
+        let mut reader = BitReader::new_from_slice(&mut data);
+        let bitstream_restriction = BitstreamRestriction::parse(&mut reader).unwrap();
+
+        insta::assert_debug_snapshot!(bitstream_restriction, @r"
+        BitstreamRestriction {
+            motion_vectors_over_pic_boundaries_flag: true,
+            max_bytes_per_pic_denom: 2,
+            max_bits_per_mb_denom: 1,
+            log2_max_mv_length_horizontal: 16,
+            log2_max_mv_length_vertical: 16,
+            max_num_reorder_frames: 2,
+            max_dec_frame_buffering: 4,
+        }
+        ");
+
+        // create a writer for the builder
+        let mut buf = Vec::new();
+        let mut writer2 = BitWriter::new(&mut buf);
+
+        // build from the example result
+        bitstream_restriction.build(&mut writer2).unwrap();
+        writer2.finish().unwrap();
+
+        assert_eq!(buf, data);
+
+        // now we re-parse so we can compare the bit sizes.
+        // create a reader for the parser
+        let mut reader2 = BitReader::new_from_slice(buf);
+        let rebuilt_bitstream_restriction = BitstreamRestriction::parse(&mut reader2).unwrap();
+
+        // now we can check the size:
+        assert_eq!(rebuilt_bitstream_restriction.bitsize(), bitstream_restriction.bitsize());
+        assert_eq!(rebuilt_bitstream_restriction.bytesize(), bitstream_restriction.bytesize());
+    }
+
+    #[test]
+    fn test_parse_bitstream_restriction_reorder_exceeds_buffering() {
+        let mut data = Vec::new();
+        let mut writer = BitWriter::new(&mut data);
+
+        writer.write_bit(false).unwrap();
+        writer.write_exp_golomb(0).unwrap();
+        writer.write_exp_golomb(0).unwrap();
+        writer.write_exp_golomb(16).unwrap();
+        writer.write_exp_golomb(16).unwrap();
+        // max_num_reorder_frames is larger than max_dec_frame_buffering
+        writer.write_exp_golomb(3).unwrap();
+        writer.write_exp_golomb(2).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = BitReader::new_from_slice(&mut data);
+        let result = BitstreamRestriction::parse(&mut reader);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+}
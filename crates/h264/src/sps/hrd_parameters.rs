@@ -0,0 +1,345 @@
+use std::io;
+
+use scuffle_bytes_util::{BitReader, BitWriter, range_check};
+use scuffle_expgolomb::{BitReaderExpGolombExt, BitWriterExpGolombExt, size_of_exp_golomb};
+
+/// `HrdParameters` contains the fields that are set when `nal_hrd_parameters_present_flag == 1`
+/// or `vcl_hrd_parameters_present_flag == 1`.
+///
+/// This describes the hypothetical reference decoder (HRD) which the bitstream conforms to,
+/// i.e. the bit rates and coded picture buffer (CPB) sizes of each delivery schedule.
+///
+/// ISO/IEC-14496-10-2022 - E.1.2
+///
+/// Refer to the direct fields for more information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HrdParameters {
+    /// The `bit_rate_scale` (together with `bit_rate_value_minus1`) specifies the maximum
+    /// input bit rate of the CPB.
+    ///
+    /// This is 4 bits.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub bit_rate_scale: u8,
+
+    /// The `cpb_size_scale` (together with `cpb_size_value_minus1`) specifies the CPB size.
+    ///
+    /// This is 4 bits.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub cpb_size_scale: u8,
+
+    /// The delivery schedules described by this HRD, indexed by `SchedSelIdx`.
+    ///
+    /// The number of entries is `cpb_cnt_minus1 + 1`, which ranges from \[1, 32\].
+    /// `cpb_cnt_minus1` is encoded as an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub cpb_specs: Vec<CpbSpec>,
+
+    /// The `initial_cpb_removal_delay_length_minus1` plus 1 is the length in bits of the
+    /// `initial_cpb_removal_delay` and `initial_cpb_removal_delay_offset` syntax elements
+    /// of the buffering period SEI message.
+    ///
+    /// This is 5 bits.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub initial_cpb_removal_delay_length_minus1: u8,
+
+    /// The `cpb_removal_delay_length_minus1` plus 1 is the length in bits of the
+    /// `cpb_removal_delay` syntax element of the picture timing SEI message.
+    ///
+    /// This is 5 bits.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub cpb_removal_delay_length_minus1: u8,
+
+    /// The `dpb_output_delay_length_minus1` plus 1 is the length in bits of the
+    /// `dpb_output_delay` syntax element of the picture timing SEI message.
+    ///
+    /// This is 5 bits.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub dpb_output_delay_length_minus1: u8,
+
+    /// The `time_offset_length` is the length in bits of the `time_offset` syntax element
+    /// of the picture timing SEI message. 0 means the `time_offset` is not present.
+    ///
+    /// This is 5 bits.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub time_offset_length: u8,
+}
+
+/// `CpbSpec` contains the fields of a single delivery schedule (`SchedSelIdx`) of the HRD.
+///
+/// ISO/IEC-14496-10-2022 - E.1.2
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpbSpec {
+    /// The `bit_rate_value_minus1` (together with `bit_rate_scale`) specifies the maximum
+    /// input bit rate for this delivery schedule.
+    ///
+    /// The value of this ranges from \[0, 2^32 - 2\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub bit_rate_value_minus1: u32,
+
+    /// The `cpb_size_value_minus1` (together with `cpb_size_scale`) specifies the CPB size
+    /// for this delivery schedule.
+    ///
+    /// The value of this ranges from \[0, 2^32 - 2\].
+    ///
+    /// This is a variable number of bits as it is encoded by an exp golomb (unsigned).
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub cpb_size_value_minus1: u32,
+
+    /// The `cbr_flag` is a single bit.
+    ///
+    /// 0 means the stream is decoded in variable bit rate (VBR) mode.
+    ///
+    /// 1 means the stream is decoded in constant bit rate (CBR) mode.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub cbr_flag: bool,
+}
+
+impl HrdParameters {
+    /// Parses the fields defined by `hrd_parameters()` from a bitstream.
+    /// Returns a `HrdParameters` struct.
+    pub fn parse<T: io::Read>(reader: &mut BitReader<T>) -> io::Result<Self> {
+        let cpb_cnt_minus1 = reader.read_exp_golomb()?;
+        range_check!(cpb_cnt_minus1, 0, 31)?;
+
+        let bit_rate_scale = reader.read_bits(4)? as u8;
+        let cpb_size_scale = reader.read_bits(4)? as u8;
+
+        let mut cpb_specs = Vec::with_capacity(cpb_cnt_minus1 as usize + 1);
+        for _ in 0..=cpb_cnt_minus1 {
+            let bit_rate_value_minus1 = reader.read_exp_golomb()?;
+            range_check!(bit_rate_value_minus1, 0, u32::MAX as u64 - 1)?;
+
+            let cpb_size_value_minus1 = reader.read_exp_golomb()?;
+            range_check!(cpb_size_value_minus1, 0, u32::MAX as u64 - 1)?;
+
+            let cbr_flag = reader.read_bit()?;
+
+            cpb_specs.push(CpbSpec {
+                bit_rate_value_minus1: bit_rate_value_minus1 as u32,
+                cpb_size_value_minus1: cpb_size_value_minus1 as u32,
+                cbr_flag,
+            });
+        }
+
+        let initial_cpb_removal_delay_length_minus1 = reader.read_bits(5)? as u8;
+        let cpb_removal_delay_length_minus1 = reader.read_bits(5)? as u8;
+        let dpb_output_delay_length_minus1 = reader.read_bits(5)? as u8;
+        let time_offset_length = reader.read_bits(5)? as u8;
+
+        Ok(HrdParameters {
+            bit_rate_scale,
+            cpb_size_scale,
+            cpb_specs,
+            initial_cpb_removal_delay_length_minus1,
+            cpb_removal_delay_length_minus1,
+            dpb_output_delay_length_minus1,
+            time_offset_length,
+        })
+    }
+
+    /// Builds the HrdParameters struct into a byte stream.
+    /// Returns a built byte stream.
+    pub fn build<T: io::Write>(&self, writer: &mut BitWriter<T>) -> io::Result<()> {
+        if self.cpb_specs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "hrd_parameters must contain at least one cpb specification",
+            ));
+        }
+
+        writer.write_exp_golomb(self.cpb_specs.len() as u64 - 1)?;
+        writer.write_bits(self.bit_rate_scale as u64, 4)?;
+        writer.write_bits(self.cpb_size_scale as u64, 4)?;
+
+        for spec in &self.cpb_specs {
+            writer.write_exp_golomb(spec.bit_rate_value_minus1 as u64)?;
+            writer.write_exp_golomb(spec.cpb_size_value_minus1 as u64)?;
+            writer.write_bit(spec.cbr_flag)?;
+        }
+
+        writer.write_bits(self.initial_cpb_removal_delay_length_minus1 as u64, 5)?;
+        writer.write_bits(self.cpb_removal_delay_length_minus1 as u64, 5)?;
+        writer.write_bits(self.dpb_output_delay_length_minus1 as u64, 5)?;
+        writer.write_bits(self.time_offset_length as u64, 5)?;
+        Ok(())
+    }
+
+    /// Returns the total bits of the HrdParameters struct.
+    ///
+    /// Note that this isn't the bytesize since aligning it may cause some values to be different.
+    pub fn bitsize(&self) -> u64 {
+        size_of_exp_golomb(self.cpb_specs.len().saturating_sub(1) as u64) +
+        4 + // bit_rate_scale
+        4 + // cpb_size_scale
+        self.cpb_specs
+            .iter()
+            .map(|spec| {
+                size_of_exp_golomb(spec.bit_rate_value_minus1 as u64) +
+                size_of_exp_golomb(spec.cpb_size_value_minus1 as u64) +
+                1 // cbr_flag
+            })
+            .sum::<u64>() +
+        5 + // initial_cpb_removal_delay_length_minus1
+        5 + // cpb_removal_delay_length_minus1
+        5 + // dpb_output_delay_length_minus1
+        5 // time_offset_length
+    }
+
+    /// Returns the total bytes of the HrdParameters struct.
+    ///
+    /// Note that this calls [`HrdParameters::bitsize()`] and calculates the number of bytes
+    /// including any necessary padding such that the bitstream is byte aligned.
+    pub fn bytesize(&self) -> u64 {
+        self.bitsize().div_ceil(8)
+    }
+
+    /// Returns the maximum input bit rate of the CPB in bits per second for the given `SchedSelIdx`.
+    ///
+    /// `bit_rate = (bit_rate_value_minus1 + 1) * 2^(6 + bit_rate_scale)`
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub fn bit_rate(&self, sched_sel_idx: usize) -> Option<u64> {
+        self.cpb_specs
+            .get(sched_sel_idx)
+            .map(|spec| (spec.bit_rate_value_minus1 as u64 + 1) << (6 + self.bit_rate_scale as u64))
+    }
+
+    /// Returns the CPB size in bits for the given `SchedSelIdx`.
+    ///
+    /// `cpb_size = (cpb_size_value_minus1 + 1) * 2^(4 + cpb_size_scale)`
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.2
+    pub fn cpb_size(&self, sched_sel_idx: usize) -> Option<u64> {
+        self.cpb_specs
+            .get(sched_sel_idx)
+            .map(|spec| (spec.cpb_size_value_minus1 as u64 + 1) << (4 + self.cpb_size_scale as u64))
+    }
+
+    /// Returns true if every delivery schedule of this HRD operates in constant bit rate mode.
+    pub fn is_cbr(&self) -> bool {
+        !self.cpb_specs.is_empty() && self.cpb_specs.iter().all(|spec| spec.cbr_flag)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use scuffle_bytes_util::{BitReader, BitWriter};
+    use scuffle_expgolomb::BitWriterExpGolombExt;
+
+    use crate::sps::HrdParameters;
+
+    #[test]
+    fn test_build_size_hrd_parameters() {
+        // create bitstream for hrd_parameters
+        let mut data = Vec::new();
+        let mut writer = BitWriter::new(&mut data);
+
+        // cpb_cnt_minus1
+        writer.write_exp_golomb(1).unwrap();
+        // bit_rate_scale
+        writer.write_bits(2, 4).unwrap();
+        // cpb_size_scale
+        writer.write_bits(3, 4).unwrap();
+
+        // SchedSelIdx 0
+        writer.write_exp_golomb(15624).unwrap();
+        writer.write_exp_golomb(31249).unwrap();
+        writer.write_bit(true).unwrap();
+
+        // SchedSelIdx 1
+        writer.write_exp_golomb(7812).unwrap();
+        writer.write_exp_golomb(15624).unwrap();
+        writer.write_bit(false).unwrap();
+
+        // initial_cpb_removal_delay_length_minus1
+        writer.write_bits(23, 5).unwrap();
+        // cpb_removal_delay_length_minus1
+        writer.write_bits(23, 5).unwrap();
+        // dpb_output_delay_length_minus1
+        writer.write_bits(23, 5).unwrap();
+        // time_offset_length
+        writer.write_bits(24, 5).unwrap();
+        writer.finish().unwrap();
+
+        // parse bitstream
+        let mut reader = BitReader::new_from_slice(&mut data);
+        let hrd_parameters = HrdParameters::parse(&mut reader).unwrap();
+
+        insta::assert_debug_snapshot!(hrd_parameters, @r"
+        HrdParameters {
+            bit_rate_scale: 2,
+            cpb_size_scale: 3,
+            cpb_specs: [
+                CpbSpec {
+                    bit_rate_value_minus1: 15624,
+                    cpb_size_value_minus1: 31249,
+                    cbr_flag: true,
+                },
+                CpbSpec {
+                    bit_rate_value_minus1: 7812,
+                    cpb_size_value_minus1: 15624,
+                    cbr_flag: false,
+                },
+            ],
+            initial_cpb_removal_delay_length_minus1: 23,
+            cpb_removal_delay_length_minus1: 23,
+            dpb_output_delay_length_minus1: 23,
+            time_offset_length: 24,
+        }
+        ");
+
+        assert_eq!(hrd_parameters.bit_rate(0), Some(4_000_000));
+        assert_eq!(hrd_parameters.cpb_size(0), Some(4_000_000));
+        assert_eq!(hrd_parameters.bit_rate(1), Some(2_000_128));
+        assert_eq!(hrd_parameters.bit_rate(2), None);
+        assert!(!hrd_parameters.is_cbr());
+
+        // create a writer for the builder
+        let mut buf = Vec::new();
+        let mut writer2 = BitWriter::new(&mut buf);
+
+        // build from the example result
+        hrd_parameters.build(&mut writer2).unwrap();
+        writer2.finish().unwrap();
+
+        assert_eq!(buf, data);
+
+        // now we re-parse so we can compare the bit sizes.
+        // create a reader for the parser
+        let mut reader2 = BitReader::new_from_slice(buf);
+        let rebuilt_hrd_parameters = HrdParameters::parse(&mut reader2).unwrap();
+
+        // now we can check the size:
+        assert_eq!(rebuilt_hrd_parameters.bitsize(), hrd_parameters.bitsize());
+        assert_eq!(rebuilt_hrd_parameters.bytesize(), hrd_parameters.bytesize());
+    }
+
+    #[test]
+    fn test_parse_hrd_parameters_invalid_cpb_cnt() {
+        let mut data = Vec::new();
+        let mut writer = BitWriter::new(&mut data);
+
+        // cpb_cnt_minus1 is out of range
+        writer.write_exp_golomb(32).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = BitReader::new_from_slice(&mut data);
+        let result = HrdParameters::parse(&mut reader);
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+}
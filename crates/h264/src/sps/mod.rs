@@ -1,3 +1,6 @@
+mod bitstream_restriction;
+pub use self::bitstream_restriction::BitstreamRestriction;
+
 mod chroma_sample_loc;
 use self::chroma_sample_loc::ChromaSampleLoc;
 
@@ -7,6 +10,9 @@ use self::color_config::ColorConfig;
 mod frame_crop_info;
 use self::frame_crop_info::FrameCropInfo;
 
+mod hrd_parameters;
+pub use self::hrd_parameters::{CpbSpec, HrdParameters};
+
 mod pic_order_count_type1;
 use self::pic_order_count_type1::PicOrderCountType1;
 
@@ -348,10 +354,52 @@ pub struct Sps {
     /// An optional `TimingInfo`. This is computed from other fields, and isn't directly set.
     ///
     /// If `timing_info_present_flag` is set, then the `TimingInfo` will be computed, and
-    /// is comprised of `num_units_in_tick` and `time_scale`.
+    /// is comprised of `num_units_in_tick`, `time_scale` and `fixed_frame_rate_flag`.
     ///
     /// Refer to the TimingInfo struct for more info.
     pub timing_info: Option<TimingInfo>,
+
+    /// An optional `HrdParameters` for the NAL HRD. This is computed from other fields, and isn't directly set.
+    ///
+    /// If `nal_hrd_parameters_present_flag` is set, then the `HrdParameters` will be computed.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    ///
+    /// Refer to the HrdParameters struct for more info.
+    pub nal_hrd_parameters: Option<HrdParameters>,
+
+    /// An optional `HrdParameters` for the VCL HRD. This is computed from other fields, and isn't directly set.
+    ///
+    /// If `vcl_hrd_parameters_present_flag` is set, then the `HrdParameters` will be computed.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    ///
+    /// Refer to the HrdParameters struct for more info.
+    pub vcl_hrd_parameters: Option<HrdParameters>,
+
+    /// An optional `low_delay_hrd_flag` is a single bit.
+    ///
+    /// If either `nal_hrd_parameters` or `vcl_hrd_parameters` is present, then this field will be read and stored.
+    ///
+    /// 0 means the HRD operates in non-low-delay mode, 1 means it operates in low-delay mode.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub low_delay_hrd_flag: Option<bool>,
+
+    /// The `pic_struct_present_flag` is a single bit.
+    ///
+    /// 1 means picture timing SEI messages contain the `pic_struct` syntax element.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub pic_struct_present_flag: bool,
+
+    /// An optional `BitstreamRestriction`. This is computed from other fields, and isn't directly set.
+    ///
+    /// If `bitstream_restriction_flag` is set, then the `BitstreamRestriction` will be computed, and
+    /// contains amongst others the `max_num_reorder_frames` and `max_dec_frame_buffering`.
+    ///
+    /// Refer to the BitstreamRestriction struct for more info.
+    pub bitstream_restriction: Option<BitstreamRestriction>,
 }
 
 impl Sps {
@@ -478,10 +526,15 @@ impl Sps {
         let mut color_config = None;
         let mut chroma_sample_loc = None;
         let mut timing_info = None;
+        let mut nal_hrd_parameters = None;
+        let mut vcl_hrd_parameters = None;
+        let mut low_delay_hrd_flag = None;
+        let mut pic_struct_present_flag = false;
+        let mut bitstream_restriction = None;
 
         let vui_parameters_present_flag = bit_reader.read_bit()?;
         if vui_parameters_present_flag {
-            // We read the VUI parameters to get the frame rate.
+            // ISO/IEC-14496-10-2022 - E.1.1
 
             let aspect_ratio_info_present_flag = bit_reader.read_bit()?;
             if aspect_ratio_info_present_flag {
@@ -514,6 +567,27 @@ impl Sps {
             if timing_info_present_flag {
                 timing_info = Some(TimingInfo::parse(&mut bit_reader)?)
             }
+
+            let nal_hrd_parameters_present_flag = bit_reader.read_bit()?;
+            if nal_hrd_parameters_present_flag {
+                nal_hrd_parameters = Some(HrdParameters::parse(&mut bit_reader)?)
+            }
+
+            let vcl_hrd_parameters_present_flag = bit_reader.read_bit()?;
+            if vcl_hrd_parameters_present_flag {
+                vcl_hrd_parameters = Some(HrdParameters::parse(&mut bit_reader)?)
+            }
+
+            if nal_hrd_parameters_present_flag || vcl_hrd_parameters_present_flag {
+                low_delay_hrd_flag = Some(bit_reader.read_bit()?);
+            }
+
+            pic_struct_present_flag = bit_reader.read_bit()?;
+
+            let bitstream_restriction_flag = bit_reader.read_bit()?;
+            if bitstream_restriction_flag {
+                bitstream_restriction = Some(BitstreamRestriction::parse(&mut bit_reader)?)
+            }
         }
 
         Ok(Sps {
@@ -545,6 +619,11 @@ impl Sps {
             color_config,
             chroma_sample_loc,
             timing_info,
+            nal_hrd_parameters,
+            vcl_hrd_parameters,
+            low_delay_hrd_flag,
+            pic_struct_present_flag,
+            bitstream_restriction,
         })
     }
 
@@ -601,51 +680,69 @@ impl Sps {
             frame_crop_info.build(&mut bit_writer)?;
         }
 
-        match (
-            &self.sample_aspect_ratio,
-            &self.overscan_appropriate_flag,
-            &self.color_config,
-            &self.chroma_sample_loc,
-            &self.timing_info,
-        ) {
-            (None, None, None, None, None) => {
-                bit_writer.write_bit(false)?;
+        if self.vui_parameters_present() {
+            // vui_parameters_present_flag
+            bit_writer.write_bit(true)?;
+
+            // aspect_ratio_info_present_flag
+            bit_writer.write_bit(self.sample_aspect_ratio.is_some())?;
+            if let Some(sar) = &self.sample_aspect_ratio {
+                sar.build(&mut bit_writer)?;
             }
-            _ => {
-                // vui_parameters_present_flag
-                bit_writer.write_bit(true)?;
-
-                // aspect_ratio_info_present_flag
-                bit_writer.write_bit(self.sample_aspect_ratio.is_some())?;
-                if let Some(sar) = &self.sample_aspect_ratio {
-                    sar.build(&mut bit_writer)?;
-                }
-
-                // overscan_info_present_flag
-                bit_writer.write_bit(self.overscan_appropriate_flag.is_some())?;
-                if let Some(overscan) = &self.overscan_appropriate_flag {
-                    bit_writer.write_bit(*overscan)?;
-                }
-
-                // video_signal_type_prsent_flag
-                bit_writer.write_bit(self.color_config.is_some())?;
-                if let Some(color) = &self.color_config {
-                    color.build(&mut bit_writer)?;
-                }
-
-                // chroma_log_info_present_flag
-                bit_writer.write_bit(self.chroma_sample_loc.is_some())?;
-                if let Some(chroma) = &self.chroma_sample_loc {
-                    chroma.build(&mut bit_writer)?;
-                }
-
-                // timing_info_present_flag
-                bit_writer.write_bit(self.timing_info.is_some())?;
-                if let Some(timing) = &self.timing_info {
-                    timing.build(&mut bit_writer)?;
-                }
+
+            // overscan_info_present_flag
+            bit_writer.write_bit(self.overscan_appropriate_flag.is_some())?;
+            if let Some(overscan) = &self.overscan_appropriate_flag {
+                bit_writer.write_bit(*overscan)?;
+            }
+
+            // video_signal_type_prsent_flag
+            bit_writer.write_bit(self.color_config.is_some())?;
+            if let Some(color) = &self.color_config {
+                color.build(&mut bit_writer)?;
+            }
+
+            // chroma_log_info_present_flag
+            bit_writer.write_bit(self.chroma_sample_loc.is_some())?;
+            if let Some(chroma) = &self.chroma_sample_loc {
+                chroma.build(&mut bit_writer)?;
+            }
+
+            // timing_info_present_flag
+            bit_writer.write_bit(self.timing_info.is_some())?;
+            if let Some(timing) = &self.timing_info {
+                timing.build(&mut bit_writer)?;
+            }
+
+            // nal_hrd_parameters_present_flag
+            bit_writer.write_bit(self.nal_hrd_parameters.is_some())?;
+            if let Some(hrd) = &self.nal_hrd_parameters {
+                hrd.build(&mut bit_writer)?;
+            }
+
+            // vcl_hrd_parameters_present_flag
+            bit_writer.write_bit(self.vcl_hrd_parameters.is_some())?;
+            if let Some(hrd) = &self.vcl_hrd_parameters {
+                hrd.build(&mut bit_writer)?;
             }
+
+            // low_delay_hrd_flag
+            if self.nal_hrd_parameters.is_some() || self.vcl_hrd_parameters.is_some() {
+                bit_writer.write_bit(self.low_delay_hrd_flag.unwrap_or(false))?;
+            }
+
+            // pic_struct_present_flag
+            bit_writer.write_bit(self.pic_struct_present_flag)?;
+
+            // bitstream_restriction_flag
+            bit_writer.write_bit(self.bitstream_restriction.is_some())?;
+            if let Some(restriction) = &self.bitstream_restriction {
+                restriction.build(&mut bit_writer)?;
+            }
+        } else {
+            bit_writer.write_bit(false)?;
         }
+
         bit_writer.finish()?;
 
         Ok(())
@@ -690,18 +787,35 @@ impl Sps {
         1 + // frame_cropping_flag
         self.frame_crop_info.as_ref().map_or(0, |frame| frame.bitsize()) +
         1 + // vui_parameters_present_flag
-        if matches!(
-            (&self.sample_aspect_ratio, &self.overscan_appropriate_flag, &self.color_config, &self.chroma_sample_loc, &self.timing_info),
-            (None, None, None, None, None)
-        ) {
+        if !self.vui_parameters_present() {
             0
         } else {
             self.sample_aspect_ratio.as_ref().map_or(1, |sar| 1 + sar.bitsize()) +
             self.overscan_appropriate_flag.map_or(1, |_| 2) +
             self.color_config.as_ref().map_or(1, |color| 1 + color.bitsize()) +
             self.chroma_sample_loc.as_ref().map_or(1, |chroma| 1 + chroma.bitsize()) +
-            self.timing_info.as_ref().map_or(1, |timing| 1 + timing.bitsize())
-        }).div_ceil(8)
+            self.timing_info.as_ref().map_or(1, |timing| 1 + timing.bitsize()) +
+            self.nal_hrd_parameters.as_ref().map_or(1, |hrd| 1 + hrd.bitsize()) +
+            self.vcl_hrd_parameters.as_ref().map_or(1, |hrd| 1 + hrd.bitsize()) +
+            (self.nal_hrd_parameters.is_some() || self.vcl_hrd_parameters.is_some()) as u64 + // low_delay_hrd_flag
+            1 + // pic_struct_present_flag
+            self.bitstream_restriction.as_ref().map_or(1, |restriction| 1 + restriction.bitsize())
+        })
+        .div_ceil(8)
+    }
+
+    /// Returns true if any of the VUI parameters are set, in which case the
+    /// `vui_parameters_present_flag` is written.
+    fn vui_parameters_present(&self) -> bool {
+        self.sample_aspect_ratio.is_some()
+            || self.overscan_appropriate_flag.is_some()
+            || self.color_config.is_some()
+            || self.chroma_sample_loc.is_some()
+            || self.timing_info.is_some()
+            || self.nal_hrd_parameters.is_some()
+            || self.vcl_hrd_parameters.is_some()
+            || self.pic_struct_present_flag
+            || self.bitstream_restriction.is_some()
     }
 
     /// The height as a u64. This is computed from other fields, and isn't directly set.
@@ -740,6 +854,69 @@ impl Sps {
     pub fn frame_rate(&self) -> Option<f64> {
         self.timing_info.as_ref().map(|timing| timing.frame_rate())
     }
+
+    /// Returns the required size of the decoded picture buffer (DPB) in frames.
+    ///
+    /// If `bitstream_restriction_flag` is set, then this is the signaled `max_dec_frame_buffering`.
+    /// Otherwise it is inferred from the level limits as
+    /// `min(MaxDpbMbs / (PicWidthInMbs * FrameHeightInMbs), 16)`.
+    ///
+    /// Returns `None` if the `level_idc` is unknown and no bitstream restriction is present.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1, A.3.1 Table A-1
+    pub fn max_dec_frame_buffering(&self) -> Option<u64> {
+        if let Some(restriction) = &self.bitstream_restriction {
+            return Some(restriction.max_dec_frame_buffering as u64);
+        }
+
+        // level 1b is signaled as level_idc 11 with constraint_set3_flag for these profiles
+        let level_1b = self.level_idc == 9
+            || (self.level_idc == 11 && self.constraint_set3_flag && matches!(self.profile_idc, 66 | 77 | 88));
+
+        let max_dpb_mbs: u64 = match self.level_idc {
+            _ if level_1b => 396,
+            10 => 396,
+            11 => 900,
+            12 | 13 | 20 => 2376,
+            21 => 4752,
+            22 | 30 => 8100,
+            31 => 18000,
+            32 => 20480,
+            40 | 41 => 32768,
+            42 => 34816,
+            50 => 110400,
+            51 | 52 => 184320,
+            60..=62 => 696320,
+            _ => return None,
+        };
+
+        let pic_width_in_mbs = self.pic_width_in_mbs_minus1 + 1;
+        let frame_height_in_mbs =
+            (2 - self.mb_adaptive_frame_field_flag.is_none() as u64) * (self.pic_height_in_map_units_minus1 + 1);
+
+        Some((max_dpb_mbs / (pic_width_in_mbs * frame_height_in_mbs)).min(16))
+    }
+
+    /// Returns the maximum number of frames that precede any frame in decoding order and follow it in output order.
+    ///
+    /// This is only known if `bitstream_restriction_flag` is set.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub fn max_num_reorder_frames(&self) -> Option<u64> {
+        self.bitstream_restriction
+            .as_ref()
+            .map(|restriction| restriction.max_num_reorder_frames as u64)
+    }
+
+    /// Returns true if the HRD parameters signal constant bit rate (CBR) operation.
+    ///
+    /// The NAL HRD is preferred over the VCL HRD. Returns `None` if neither is present.
+    pub fn is_cbr(&self) -> Option<bool> {
+        self.nal_hrd_parameters
+            .as_ref()
+            .or(self.vcl_hrd_parameters.as_ref())
+            .map(|hrd| hrd.is_cbr())
+    }
 }
 
 #[cfg(test)]
@@ -889,6 +1066,54 @@ mod tests {
         // 28800 = time_scale
         // time_scale is a u32
         writer.write_bits(28800, 32).unwrap();
+        // fixed_frame_rate_flag
+        writer.write_bit(true).unwrap();
+
+        // nal_hrd_parameters_present_flag
+        writer.write_bit(true).unwrap();
+        // cpb_cnt_minus1 is expg
+        writer.write_exp_golomb(0).unwrap();
+        // bit_rate_scale
+        writer.write_bits(4, 4).unwrap();
+        // cpb_size_scale
+        writer.write_bits(4, 4).unwrap();
+        // ~50 Mbit/s: bit_rate = (bit_rate_value_minus1 + 1) * 2^(6 + 4)
+        writer.write_exp_golomb(48827).unwrap();
+        // cpb_size = (cpb_size_value_minus1 + 1) * 2^(4 + 4)
+        writer.write_exp_golomb(195311).unwrap();
+        // cbr_flag
+        writer.write_bit(true).unwrap();
+        // initial_cpb_removal_delay_length_minus1
+        writer.write_bits(23, 5).unwrap();
+        // cpb_removal_delay_length_minus1
+        writer.write_bits(23, 5).unwrap();
+        // dpb_output_delay_length_minus1
+        writer.write_bits(23, 5).unwrap();
+        // time_offset_length
+        writer.write_bits(24, 5).unwrap();
+        // vcl_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // low_delay_hrd_flag
+        writer.write_bit(false).unwrap();
+        // pic_struct_present_flag
+        writer.write_bit(true).unwrap();
+
+        // bitstream_restriction_flag
+        writer.write_bit(true).unwrap();
+        // motion_vectors_over_pic_boundaries_flag
+        writer.write_bit(true).unwrap();
+        // max_bytes_per_pic_denom is expg
+        writer.write_exp_golomb(2).unwrap();
+        // max_bits_per_mb_denom is expg
+        writer.write_exp_golomb(1).unwrap();
+        // log2_max_mv_length_horizontal is expg
+        writer.write_exp_golomb(16).unwrap();
+        // log2_max_mv_length_vertical is expg
+        writer.write_exp_golomb(16).unwrap();
+        // max_num_reorder_frames is expg
+        writer.write_exp_golomb(2).unwrap();
+        // max_dec_frame_buffering is expg
+        writer.write_exp_golomb(4).unwrap();
         writer.finish().unwrap();
 
         let result = Sps::parse(std::io::Cursor::new(sps)).unwrap();
@@ -945,6 +1170,40 @@ mod tests {
                 TimingInfo {
                     num_units_in_tick: 100,
                     time_scale: 28800,
+                    fixed_frame_rate_flag: true,
+                },
+            ),
+            nal_hrd_parameters: Some(
+                HrdParameters {
+                    bit_rate_scale: 4,
+                    cpb_size_scale: 4,
+                    cpb_specs: [
+                        CpbSpec {
+                            bit_rate_value_minus1: 48827,
+                            cpb_size_value_minus1: 195311,
+                            cbr_flag: true,
+                        },
+                    ],
+                    initial_cpb_removal_delay_length_minus1: 23,
+                    cpb_removal_delay_length_minus1: 23,
+                    dpb_output_delay_length_minus1: 23,
+                    time_offset_length: 24,
+                },
+            ),
+            vcl_hrd_parameters: None,
+            low_delay_hrd_flag: Some(
+                false,
+            ),
+            pic_struct_present_flag: true,
+            bitstream_restriction: Some(
+                BitstreamRestriction {
+                    motion_vectors_over_pic_boundaries_flag: true,
+                    max_bytes_per_pic_denom: 2,
+                    max_bits_per_mb_denom: 1,
+                    log2_max_mv_length_horizontal: 16,
+                    log2_max_mv_length_vertical: 16,
+                    max_num_reorder_frames: 2,
+                    max_dec_frame_buffering: 4,
                 },
             ),
         }
@@ -953,6 +1212,21 @@ mod tests {
         assert_eq!(Some(144.0), result.frame_rate());
         assert_eq!(3840, result.width());
         assert_eq!(2160, result.height());
+        assert_eq!(Some(4), result.max_dec_frame_buffering());
+        assert_eq!(Some(2), result.max_num_reorder_frames());
+
+        // without a bitstream restriction the dpb size is derived from the level limits
+        // level 5.1: MaxDpbMbs = 184320, 184320 / (240 * 135) = 5
+        let mut level_limited = result.clone();
+        level_limited.bitstream_restriction = None;
+        level_limited.level_idc = 51;
+        assert_eq!(Some(5), level_limited.max_dec_frame_buffering());
+        assert_eq!(None, level_limited.max_num_reorder_frames());
+        assert_eq!(Some(true), result.is_cbr());
+        assert_eq!(
+            Some(49_999_872),
+            result.nal_hrd_parameters.as_ref().and_then(|hrd| hrd.bit_rate(0))
+        );
 
         // create a writer for the builder
         let mut buf = Vec::new();
@@ -1144,6 +1418,17 @@ mod tests {
         // 960 000 = time_scale
         // time_scale is a u32
         writer.write_bits(960000, 32).unwrap();
+        // fixed_frame_rate_flag
+        writer.write_bit(false).unwrap();
+
+        // nal_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // vcl_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // pic_struct_present_flag
+        writer.write_bit(false).unwrap();
+        // bitstream_restriction_flag
+        writer.write_bit(false).unwrap();
         writer.finish().unwrap();
 
         let result = Sps::parse(std::io::Cursor::new(&sps)).unwrap();
@@ -1239,8 +1524,14 @@ mod tests {
                 TimingInfo {
                     num_units_in_tick: 1000,
                     time_scale: 960000,
+                    fixed_frame_rate_flag: false,
                 },
             ),
+            nal_hrd_parameters: None,
+            vcl_hrd_parameters: None,
+            low_delay_hrd_flag: None,
+            pic_struct_present_flag: false,
+            bitstream_restriction: None,
         }
         ");
 
@@ -1340,6 +1631,14 @@ mod tests {
 
         // timing_info_present_flag
         writer.write_bit(false).unwrap();
+        // nal_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // vcl_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // pic_struct_present_flag
+        writer.write_bit(false).unwrap();
+        // bitstream_restriction_flag
+        writer.write_bit(false).unwrap();
         writer.finish().unwrap();
 
         let result = Sps::parse(std::io::Cursor::new(&sps)).unwrap();
@@ -1389,6 +1688,11 @@ mod tests {
                 },
             ),
             timing_info: None,
+            nal_hrd_parameters: None,
+            vcl_hrd_parameters: None,
+            low_delay_hrd_flag: None,
+            pic_struct_present_flag: false,
+            bitstream_restriction: None,
         }
         ");
 
@@ -1485,6 +1789,11 @@ mod tests {
             color_config: None,
             chroma_sample_loc: None,
             timing_info: None,
+            nal_hrd_parameters: None,
+            vcl_hrd_parameters: None,
+            low_delay_hrd_flag: None,
+            pic_struct_present_flag: false,
+            bitstream_restriction: None,
         }
         ");
 
@@ -1955,6 +2264,11 @@ mod tests {
             color_config: None,
             chroma_sample_loc: None,
             timing_info: None,
+            nal_hrd_parameters: None,
+            vcl_hrd_parameters: None,
+            low_delay_hrd_flag: None,
+            pic_struct_present_flag: false,
+            bitstream_restriction: None,
         }
         ");
 
@@ -2182,6 +2496,22 @@ mod tests {
         // time_scale is a u32
         writer.write_bits(960000, 32).unwrap();
         bit_count += 32;
+        // fixed_frame_rate_flag
+        writer.write_bit(false).unwrap();
+        bit_count += 1;
+
+        // nal_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        bit_count += 1;
+        // vcl_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        bit_count += 1;
+        // pic_struct_present_flag
+        writer.write_bit(false).unwrap();
+        bit_count += 1;
+        // bitstream_restriction_flag
+        writer.write_bit(false).unwrap();
+        bit_count += 1;
         writer.finish().unwrap();
 
         let result = Sps::parse(std::io::Cursor::new(&sps)).unwrap();
@@ -2279,6 +2609,14 @@ mod tests {
 
         // timing_info_present_flag
         writer.write_bit(false).unwrap();
+        // nal_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // vcl_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // pic_struct_present_flag
+        writer.write_bit(false).unwrap();
+        // bitstream_restriction_flag
+        writer.write_bit(false).unwrap();
         writer.finish().unwrap();
 
         let reduced_sps = Sps::parse(std::io::Cursor::new(&sps)).unwrap();
@@ -2366,6 +2704,14 @@ mod tests {
 
         // timing_info_present_flag
         writer.write_bit(false).unwrap();
+        // nal_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // vcl_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap();
+        // pic_struct_present_flag
+        writer.write_bit(false).unwrap();
+        // bitstream_restriction_flag
+        writer.write_bit(false).unwrap();
         writer.finish().unwrap();
 
         let result = Sps::parse(std::io::Cursor::new(&sps)).unwrap();
@@ -2417,6 +2763,11 @@ mod tests {
             color_config: None,
             chroma_sample_loc: None,
             timing_info: None,
+            nal_hrd_parameters: None,
+            vcl_hrd_parameters: None,
+            low_delay_hrd_flag: None,
+            pic_struct_present_flag: false,
+            bitstream_restriction: None,
         }
         ");
     }
@@ -6,7 +6,7 @@ use scuffle_bytes_util::{BitReader, BitWriter};
 
 /// `TimingInfo` contains the fields that are set when `timing_info_present_flag == 1`.
 ///
-/// This contains the following fields: `num_units_in_tick` and `time_scale`.
+/// This contains the following fields: `num_units_in_tick`, `time_scale` and `fixed_frame_rate_flag`.
 ///
 /// ISO/IEC-14496-10-2022 - E.2.1
 ///
@@ -36,6 +36,16 @@ pub struct TimingInfo {
     ///
     /// ISO/IEC-14496-10-2022 - E.2.1
     pub time_scale: NonZeroU32,
+
+    /// The `fixed_frame_rate_flag` is a single bit.
+    ///
+    /// 0 means the temporal distance between consecutive pictures may vary.
+    ///
+    /// 1 means the temporal distance between the HRD output times of any two consecutive
+    /// pictures in output order is constrained.
+    ///
+    /// ISO/IEC-14496-10-2022 - E.2.1
+    pub fixed_frame_rate_flag: bool,
 }
 
 impl TimingInfo {
@@ -48,9 +58,12 @@ impl TimingInfo {
         let time_scale = NonZeroU32::new(reader.read_u32::<BigEndian>()?)
             .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "time_scale cannot be 0"))?;
 
+        let fixed_frame_rate_flag = reader.read_bit()?;
+
         Ok(TimingInfo {
             num_units_in_tick,
             time_scale,
+            fixed_frame_rate_flag,
         })
     }
 
@@ -59,17 +72,18 @@ impl TimingInfo {
     pub fn build<T: io::Write>(&self, writer: &mut BitWriter<T>) -> io::Result<()> {
         writer.write_bits(self.num_units_in_tick.get() as u64, 32)?;
         writer.write_bits(self.time_scale.get() as u64, 32)?;
+        writer.write_bit(self.fixed_frame_rate_flag)?;
         Ok(())
     }
 
-    /// Returns the total bits of the TimingInfo struct. It is always 64 bits (8 bytes).
+    /// Returns the total bits of the TimingInfo struct. It is always 65 bits.
     pub fn bitsize(&self) -> u64 {
-        64
+        65
     }
 
-    /// Returns the total bytes of the TimingInfo struct. It is always 8 bytes (64 bits).
+    /// Returns the total bytes of the TimingInfo struct. It is always 9 bytes (65 bits padded).
     pub fn bytesize(&self) -> u64 {
-        8
+        9
     }
 
     /// Returns the frame rate of the TimingInfo struct.
@@ -93,6 +107,7 @@ mod tests {
 
         writer.write_bits(1234, 32).unwrap();
         writer.write_bits(321, 32).unwrap();
+        writer.write_bit(true).unwrap();
         writer.finish().unwrap();
 
         // parse bitstream
@@ -80,12 +80,127 @@ pub struct NaluArray {
     pub nal_unit_type: NALUnitType,
     /// The raw byte stream of NAL units.
     ///
-    /// You might want to use [`SpsNALUnit::parse`](crate::SpsNALUnit::parse)
-    /// to parse an SPS NAL unit.
+    /// You might want to use [`VpsNALUnit::parse`](crate::VpsNALUnit::parse),
+    /// [`SpsNALUnit::parse`](crate::SpsNALUnit::parse) or [`PpsNALUnit::parse`](crate::PpsNALUnit::parse)
+    /// to parse the parameter set NAL units.
     pub nalus: Vec<Bytes>,
 }
 
+/// The sample entry type of an HEVC track, which determines where the parameter sets are stored.
+///
+/// ISO/IEC 14496-15 - 8.4.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HevcSampleEntry {
+    /// `hvc1`, all parameter sets are stored in the decoder configuration record.
+    Hvc1,
+    /// `hev1`, parameter sets may also be stored in the samples.
+    Hev1,
+}
+
+impl HevcSampleEntry {
+    /// Returns the four character code of the sample entry.
+    pub fn fourcc(self) -> [u8; 4] {
+        match self {
+            Self::Hvc1 => *b"hvc1",
+            Self::Hev1 => *b"hev1",
+        }
+    }
+
+    /// Returns the four character code of the sample entry as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hvc1 => "hvc1",
+            Self::Hev1 => "hev1",
+        }
+    }
+}
+
+/// Formats a codec string from the general profile, tier and level.
+///
+/// ISO/IEC 14496-15 - E.3
+pub(crate) fn codec_string(
+    sample_entry: HevcSampleEntry,
+    profile_space: u8,
+    tier_flag: bool,
+    profile_idc: u8,
+    profile_compatibility_flags: ProfileCompatibilityFlags,
+    constraint_indicator_flags: u64,
+    level_idc: u8,
+) -> String {
+    let profile_space = match profile_space {
+        1 => "A",
+        2 => "B",
+        3 => "C",
+        _ => "",
+    };
+
+    // The compatibility flags are written in reverse bit order, with the flag of profile 31 as the most
+    // significant bit. The flags are stored with the flag of profile 0 as the most significant bit.
+    let profile_compatibility = profile_compatibility_flags.bits().reverse_bits();
+
+    let mut codec = format!(
+        "{}.{profile_space}{profile_idc}.{profile_compatibility:X}.{}{level_idc}",
+        sample_entry.as_str(),
+        if tier_flag { 'H' } else { 'L' },
+    );
+
+    // The 6 bytes of the constraint flags, trailing zero bytes are omitted.
+    let constraint_bytes = &constraint_indicator_flags.to_be_bytes()[2..];
+    let len = constraint_bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    for byte in &constraint_bytes[..len] {
+        codec.push_str(&format!(".{byte:X}"));
+    }
+
+    codec
+}
+
 impl HEVCDecoderConfigurationRecord {
+    /// Returns the sample entry type to use for this stream.
+    ///
+    /// This is [`HevcSampleEntry::Hvc1`] if the record contains complete VPS, SPS and PPS arrays,
+    /// otherwise the parameter sets may also be sent in-band and [`HevcSampleEntry::Hev1`] has to be used.
+    ///
+    /// ISO/IEC 14496-15 - 8.4.1
+    pub fn sample_entry(&self) -> HevcSampleEntry {
+        let complete = |nal_unit_type: NALUnitType| {
+            self.arrays
+                .iter()
+                .any(|array| array.nal_unit_type == nal_unit_type && array.array_completeness && !array.nalus.is_empty())
+        };
+
+        if complete(NALUnitType::VpsNut) && complete(NALUnitType::SpsNut) && complete(NALUnitType::PpsNut) {
+            HevcSampleEntry::Hvc1
+        } else {
+            HevcSampleEntry::Hev1
+        }
+    }
+
+    /// Returns the codec string of the stream as used in the `codecs` parameter of HLS and DASH playlists,
+    /// for example `hvc1.1.6.L93.B0`.
+    ///
+    /// This always uses the `hvc1` sample entry type, use [`codec_string_for`](Self::codec_string_for)
+    /// together with [`sample_entry`](Self::sample_entry) to pick the sample entry type from the record.
+    ///
+    /// ISO/IEC 14496-15 - E.3
+    pub fn codec_string(&self) -> String {
+        self.codec_string_for(HevcSampleEntry::Hvc1)
+    }
+
+    /// Returns the codec string of the stream for the given sample entry type, for example `hev1.1.6.L93.B0`.
+    ///
+    /// ISO/IEC 14496-15 - E.3
+    pub fn codec_string_for(&self, sample_entry: HevcSampleEntry) -> String {
+        codec_string(
+            sample_entry,
+            self.general_profile_space,
+            self.general_tier_flag,
+            self.general_profile_idc,
+            self.general_profile_compatibility_flags,
+            self.general_constraint_indicator_flags,
+            self.general_level_idc,
+        )
+    }
+
     /// Demuxes an [`HEVCDecoderConfigurationRecord`] from a byte stream.
     ///
     /// Returns a demuxed [`HEVCDecoderConfigurationRecord`].
@@ -280,8 +395,8 @@ mod tests {
     use bytes::Bytes;
 
     use crate::{
-        ConstantFrameRate, HEVCDecoderConfigurationRecord, NALUnitType, NumTemporalLayers, ParallelismType,
-        ProfileCompatibilityFlags, SpsNALUnit,
+        ConstantFrameRate, HEVCDecoderConfigurationRecord, HevcSampleEntry, NALUnitType, NumTemporalLayers, ParallelismType,
+        PpsNALUnit, ProfileCompatibilityFlags, SpsNALUnit, VpsNALUnit,
     };
 
     #[test]
@@ -300,6 +415,22 @@ mod tests {
         );
         assert_eq!(config.general_constraint_indicator_flags, (1 << 47) | (1 << 44)); // 1. bit and 4. bit
         assert_eq!(config.general_level_idc, 153);
+        assert_eq!(config.codec_string(), "hvc1.1.2.L153.90");
+        // The parameter set arrays are not complete, so they may also be sent in-band.
+        assert_eq!(config.sample_entry(), HevcSampleEntry::Hev1);
+        assert_eq!(config.codec_string_for(config.sample_entry()), "hev1.1.2.L153.90");
+        assert_eq!(
+            HEVCDecoderConfigurationRecord {
+                general_profile_space: 1,
+                general_tier_flag: true,
+                general_profile_compatibility_flags: ProfileCompatibilityFlags::MainProfile
+                    | ProfileCompatibilityFlags::Main10Profile,
+                general_constraint_indicator_flags: 0xB0_00_00_00_00_01,
+                ..config.clone()
+            }
+            .codec_string(),
+            "hvc1.A1.6.H153.B0.0.0.0.0.1"
+        );
         assert_eq!(config.min_spatial_segmentation_idc, 0);
         assert_eq!(config.parallelism_type, ParallelismType::MixedOrUnknown);
         assert_eq!(config.chroma_format_idc, 1);
@@ -312,10 +443,21 @@ mod tests {
         assert_eq!(config.length_size_minus_one, 3);
         assert_eq!(config.arrays.len(), 3);
 
+        let mut complete = config.clone();
+        complete.arrays.iter_mut().for_each(|array| array.array_completeness = true);
+        assert_eq!(complete.sample_entry(), HevcSampleEntry::Hvc1);
+        complete.arrays.pop();
+        assert_eq!(complete.sample_entry(), HevcSampleEntry::Hev1);
+
         let vps = &config.arrays[0];
         assert!(!vps.array_completeness);
         assert_eq!(vps.nal_unit_type, NALUnitType::VpsNut);
         assert_eq!(vps.nalus.len(), 1);
+        let vps = VpsNALUnit::parse(io::Cursor::new(vps.nalus[0].clone())).unwrap();
+        assert_eq!(
+            vps.rbsp.profile_tier_level.general_profile.level_idc,
+            Some(config.general_level_idc)
+        );
 
         let sps = &config.arrays[1];
         assert!(!sps.array_completeness);
@@ -323,11 +465,21 @@ mod tests {
         assert_eq!(sps.nalus.len(), 1);
         let sps = SpsNALUnit::parse(io::Cursor::new(sps.nalus[0].clone())).unwrap();
         insta::assert_debug_snapshot!(sps);
+        assert_eq!(
+            sps.rbsp.profile_tier_level.codec_string(HevcSampleEntry::Hvc1),
+            config.codec_string()
+        );
 
         let pps = &config.arrays[2];
         assert!(!pps.array_completeness);
         assert_eq!(pps.nal_unit_type, NALUnitType::PpsNut);
         assert_eq!(pps.nalus.len(), 1);
+        let pps = PpsNALUnit::parse(io::Cursor::new(pps.nalus[0].clone())).unwrap();
+        assert_eq!(pps.rbsp.parallelism_type(), config.parallelism_type);
+
+        // The parameter sets reference each other.
+        assert_eq!(sps.rbsp.sps_video_parameter_set_id, vps.rbsp.vps_video_parameter_set_id);
+        assert_eq!(pps.rbsp.pps_seq_parameter_set_id, sps.rbsp.sps_seq_parameter_set_id);
     }
 
     #[test]
@@ -18,3 +18,6 @@ pub use num_temporal_layers::*;
 
 mod profile_compatibility_flags;
 pub use profile_compatibility_flags::*;
+
+mod slice_type;
+pub use slice_type::*;
@@ -205,4 +205,76 @@ impl NALUnitType {
     pub fn is_vcl(&self) -> bool {
         (0..=31).contains(&self.0)
     }
+
+    /// Returns `true` if this is an IRAP (intra random access point) NAL unit type.
+    ///
+    /// This includes BLA, IDR, CRA and the reserved IRAP NAL unit types.
+    /// A picture made of IRAP NAL units can be decoded without referencing any other picture.
+    ///
+    /// ISO/IEC 23008-2 - Table 7-1
+    pub fn is_irap(&self) -> bool {
+        (16..=23).contains(&self.0)
+    }
+
+    /// Returns `true` if this is an IDR (instantaneous decoding refresh) NAL unit type.
+    ///
+    /// ISO/IEC 23008-2 - Table 7-1
+    pub fn is_idr(&self) -> bool {
+        *self == Self::IdrWRadl || *self == Self::IdrNLp
+    }
+
+    /// Returns `true` if this is a BLA (broken link access) NAL unit type.
+    ///
+    /// ISO/IEC 23008-2 - Table 7-1
+    pub fn is_bla(&self) -> bool {
+        (16..=18).contains(&self.0)
+    }
+
+    /// Returns `true` if this is a CRA (clean random access) NAL unit type.
+    ///
+    /// ISO/IEC 23008-2 - Table 7-1
+    pub fn is_cra(&self) -> bool {
+        *self == Self::CraNut
+    }
+
+    /// Returns `true` if this is a RASL (random access skipped leading) NAL unit type.
+    ///
+    /// RASL pictures are not decodable when decoding starts at the associated IRAP picture.
+    ///
+    /// ISO/IEC 23008-2 - Table 7-1
+    pub fn is_rasl(&self) -> bool {
+        *self == Self::RaslN || *self == Self::RaslR
+    }
+
+    /// Returns `true` if this is a RADL (random access decodable leading) NAL unit type.
+    ///
+    /// ISO/IEC 23008-2 - Table 7-1
+    pub fn is_radl(&self) -> bool {
+        *self == Self::RadlN || *self == Self::RadlR
+    }
+
+    /// Returns `true` if this is a sub-layer non-reference NAL unit type.
+    ///
+    /// Pictures of this type are not used for inter prediction of pictures in the same sub-layer.
+    ///
+    /// ISO/IEC 23008-2 - 7.4.2.2
+    pub fn is_sub_layer_non_reference(&self) -> bool {
+        self.0 <= 14 && self.0.is_multiple_of(2)
+    }
+
+    /// Returns `true` if a NAL unit of this type starts a new access unit when it
+    /// follows the last VCL NAL unit of a picture.
+    ///
+    /// The first VCL NAL unit of a picture, indicated by
+    /// [`first_slice_segment_in_pic_flag`](crate::SliceSegmentHeader::first_slice_segment_in_pic_flag),
+    /// starts a new access unit as well.
+    ///
+    /// ISO/IEC 23008-2 - 7.4.2.4.4
+    pub fn starts_access_unit(&self) -> bool {
+        matches!(
+            *self,
+            Self::VpsNut | Self::SpsNut | Self::PpsNut | Self::AudNut | Self::PrefixSeiNut
+        ) || (41..=44).contains(&self.0)
+            || (48..=55).contains(&self.0)
+    }
 }
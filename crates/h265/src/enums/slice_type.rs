@@ -0,0 +1,16 @@
+use nutype_enum::nutype_enum;
+
+nutype_enum! {
+    /// Represents all possible values of the `slice_type` field in the
+    /// [`SliceSegmentHeader`](crate::SliceSegmentHeader).
+    ///
+    /// ISO/IEC 23008-2 - Table 7-7
+    pub enum SliceType(u8) {
+        /// B (bi-predictive) slice.
+        B = 0,
+        /// P (predictive) slice.
+        P = 1,
+        /// I (intra) slice.
+        I = 2,
+    }
+}
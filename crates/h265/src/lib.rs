@@ -1,6 +1,7 @@
 //! A pure Rust implementation of the HEVC/H.265 decoder.
 //!
-//! This crate is designed to provide a simple and safe interface to decode HEVC/H.265 SPS NALUs.
+//! This crate is designed to provide a simple and safe interface to decode HEVC/H.265 VPS, SPS and PPS NALUs
+//! and slice segment headers.
 #![cfg_attr(feature = "docs", doc = "\n\nSee the [changelog][changelog] for a full release history.")]
 #![cfg_attr(feature = "docs", doc = "## Feature flags")]
 #![cfg_attr(feature = "docs", doc = document_features::document_features!())]
@@ -33,12 +34,18 @@
 mod config;
 mod enums;
 mod nal_unit_header;
+mod pps;
 mod rbsp_trailing_bits;
+mod slice_segment_header;
 mod sps;
+mod vps;
 
-pub use config::{HEVCDecoderConfigurationRecord, NaluArray};
+pub use config::{HEVCDecoderConfigurationRecord, HevcSampleEntry, NaluArray};
 pub use enums::*;
+pub use pps::*;
+pub use slice_segment_header::{SliceSegmentHeader, SliceSegmentHeaderParams, SliceSegmentNALUnit};
 pub use sps::*;
+pub use vps::*;
 
 /// Changelogs generated by [scuffle_changelog]
 #[cfg(feature = "docs")]
@@ -0,0 +1,630 @@
+use std::io;
+
+use scuffle_bytes_util::{BitReader, EmulationPreventionIo, range_check};
+use scuffle_expgolomb::BitReaderExpGolombExt;
+
+use crate::nal_unit_header::NALUnitHeader;
+use crate::rbsp_trailing_bits::rbsp_trailing_bits;
+use crate::{NALUnitType, ParallelismType, ScalingListData};
+
+/// Picture parameter set contained in a NAL unit.
+///
+/// This only represents picture parameter sets that are part of NAL units.
+/// Therefore the NAL unit header is included in this struct as [`PpsNALUnit::nal_unit_header`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PpsNALUnit {
+    /// The NAL unit header.
+    pub nal_unit_header: NALUnitHeader,
+    /// The PPS RBSP.
+    pub rbsp: PpsRbsp,
+}
+
+impl PpsNALUnit {
+    /// Parses a PPS NAL unit from the given reader.
+    pub fn parse(mut reader: impl io::Read) -> io::Result<Self> {
+        let nal_unit_header = NALUnitHeader::parse(&mut reader)?;
+        if nal_unit_header.nal_unit_type != NALUnitType::PpsNut {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "nal_unit_type is not PPS_NUT"));
+        }
+
+        let rbsp = PpsRbsp::parse(reader)?;
+
+        Ok(PpsNALUnit { nal_unit_header, rbsp })
+    }
+}
+
+/// Picture parameter set RBSP.
+///
+/// For parsing PPS RBSPs that are part of NAL units, please use [`PpsNALUnit::parse`].
+///
+/// `pic_parameter_set_rbsp()`
+///
+/// - ISO/IEC 23008-2 - 7.3.2.3
+/// - ISO/IEC 23008-2 - 7.4.3.3
+#[derive(Debug, Clone, PartialEq)]
+pub struct PpsRbsp {
+    /// Identifies the PPS for reference by other syntax elements.
+    ///
+    /// The value is in range \[0, 63\].
+    pub pps_pic_parameter_set_id: u64,
+    /// Specifies the value of `sps_seq_parameter_set_id` for the active SPS.
+    ///
+    /// The value is in range \[0, 15\].
+    pub pps_seq_parameter_set_id: u64,
+    /// Equal to `true` specifies the presence of the syntax element `dependent_slice_segment_flag`
+    /// in the slice segment headers for coded pictures referring to the PPS.
+    pub dependent_slice_segments_enabled_flag: bool,
+    /// Equal to `true` indicates that the `pic_output_flag` syntax element is present in the associated slice headers.
+    pub output_flag_present_flag: bool,
+    /// Specifies the number of extra slice header bits that are present in the slice header RBSP
+    /// for coded pictures referring to the PPS.
+    ///
+    /// The value is in range \[0, 7\].
+    pub num_extra_slice_header_bits: u8,
+    /// Equal to `true` specifies that sign bit hiding is enabled.
+    pub sign_data_hiding_enabled_flag: bool,
+    /// Equal to `true` specifies that `cabac_init_flag` is present in slice headers referring to the PPS.
+    pub cabac_init_present_flag: bool,
+    /// Specifies the inferred value of `num_ref_idx_l0_active_minus1` for P and B slices
+    /// with `num_ref_idx_active_override_flag` equal to `false`.
+    ///
+    /// The value is in range \[0, 14\].
+    pub num_ref_idx_l0_default_active_minus1: u8,
+    /// Specifies the inferred value of `num_ref_idx_l1_active_minus1`
+    /// with `num_ref_idx_active_override_flag` equal to `false`.
+    ///
+    /// The value is in range \[0, 14\].
+    pub num_ref_idx_l1_default_active_minus1: u8,
+    /// This value plus 26 specifies the initial value of `SliceQpY` for each slice referring to the PPS.
+    ///
+    /// The value is in range \[−(26 + [`QpBdOffset_Y`](crate::SpsRbsp::qp_bd_offset_y)), 25\].
+    pub init_qp_minus26: i8,
+    /// Equal to `true` specifies that constrained intra prediction is used.
+    pub constrained_intra_pred_flag: bool,
+    /// Equal to `true` specifies that `transform_skip_flag` may be present in the residual coding syntax.
+    pub transform_skip_enabled_flag: bool,
+    /// Specifies the difference between the luma coding tree block size and the minimum luma coding block size
+    /// of coding units that convey `cu_qp_delta_abs` and `cu_qp_delta_sign_flag`,
+    /// if `cu_qp_delta_enabled_flag` is `true`.
+    pub diff_cu_qp_delta_depth: Option<u64>,
+    /// Specifies the offset to the luma quantization parameter used for deriving `Qp′Cb`.
+    ///
+    /// The value is in range \[−12, 12\].
+    pub pps_cb_qp_offset: i8,
+    /// Specifies the offset to the luma quantization parameter used for deriving `Qp′Cr`.
+    ///
+    /// The value is in range \[−12, 12\].
+    pub pps_cr_qp_offset: i8,
+    /// Equal to `true` indicates that the `slice_cb_qp_offset` and `slice_cr_qp_offset` syntax elements
+    /// are present in the associated slice headers.
+    pub pps_slice_chroma_qp_offsets_present_flag: bool,
+    /// Equal to `true` specifies that weighted prediction is applied to P slices.
+    pub weighted_pred_flag: bool,
+    /// Equal to `true` specifies that weighted prediction is applied to B slices.
+    pub weighted_bipred_flag: bool,
+    /// Equal to `true` specifies that `cu_transquant_bypass_flag` is present.
+    pub transquant_bypass_enabled_flag: bool,
+    /// The tile layout, if `tiles_enabled_flag` is `true`.
+    ///
+    /// See [`PpsTiles`] for details.
+    pub tiles: Option<PpsTiles>,
+    /// Equal to `true` specifies that a specific synchronization process for context variables is invoked
+    /// before decoding the coding tree unit which includes the first coding tree block of a row of
+    /// coding tree blocks in each tile in each picture referring to the PPS. (wavefront parallel processing)
+    pub entropy_coding_sync_enabled_flag: bool,
+    /// Equal to `true` specifies that in-loop filtering operations may be performed across
+    /// left and upper boundaries of slices referring to the PPS.
+    pub pps_loop_filter_across_slices_enabled_flag: bool,
+    /// The deblocking filter control, if `deblocking_filter_control_present_flag` is `true`.
+    ///
+    /// See [`PpsDeblockingFilterControl`] for details.
+    pub deblocking_filter_control: Option<PpsDeblockingFilterControl>,
+    /// The [`ScalingListData`] structure contained in this PPS, if present.
+    pub scaling_list_data: Option<ScalingListData>,
+    /// Equal to `true` specifies that the syntax structure `ref_pic_lists_modification()` is present in the slice segment header.
+    pub lists_modification_present_flag: bool,
+    /// This value plus 2 specifies the value of the variable `Log2ParMrgLevel`.
+    pub log2_parallel_merge_level_minus2: u64,
+    /// Equal to `true` specifies that slice segment header extension syntax elements are present
+    /// in the slice segment headers for coded pictures referring to the PPS.
+    pub slice_segment_header_extension_present_flag: bool,
+    /// The [`PpsRangeExtension`] structure contained in this PPS, if present.
+    pub range_extension: Option<PpsRangeExtension>,
+    /// Equal to `true` specifies that the `pps_multilayer_extension()` syntax structure is present.
+    ///
+    /// The extension is not parsed.
+    pub pps_multilayer_extension_flag: bool,
+    /// Equal to `true` specifies that the `pps_3d_extension()` syntax structure is present.
+    ///
+    /// The extension is not parsed.
+    pub pps_3d_extension_flag: bool,
+    /// Equal to `true` specifies that the `pps_scc_extension()` syntax structure is present.
+    ///
+    /// The extension is not parsed.
+    pub pps_scc_extension_flag: bool,
+}
+
+impl PpsRbsp {
+    /// Parses a PPS RBSP from the given reader.
+    ///
+    /// Uses [`EmulationPreventionIo`] to handle emulation prevention bytes.
+    ///
+    /// Returns a [`PpsRbsp`] struct.
+    pub fn parse(reader: impl io::Read) -> io::Result<Self> {
+        let mut bit_reader = BitReader::new(EmulationPreventionIo::new(reader));
+
+        let pps_pic_parameter_set_id = bit_reader.read_exp_golomb()?;
+        range_check!(pps_pic_parameter_set_id, 0, 63)?;
+
+        let pps_seq_parameter_set_id = bit_reader.read_exp_golomb()?;
+        range_check!(pps_seq_parameter_set_id, 0, 15)?;
+
+        let dependent_slice_segments_enabled_flag = bit_reader.read_bit()?;
+        let output_flag_present_flag = bit_reader.read_bit()?;
+        let num_extra_slice_header_bits = bit_reader.read_bits(3)? as u8;
+        let sign_data_hiding_enabled_flag = bit_reader.read_bit()?;
+        let cabac_init_present_flag = bit_reader.read_bit()?;
+
+        let num_ref_idx_l0_default_active_minus1 = bit_reader.read_exp_golomb()?;
+        range_check!(num_ref_idx_l0_default_active_minus1, 0, 14)?;
+        let num_ref_idx_l0_default_active_minus1 = num_ref_idx_l0_default_active_minus1 as u8;
+
+        let num_ref_idx_l1_default_active_minus1 = bit_reader.read_exp_golomb()?;
+        range_check!(num_ref_idx_l1_default_active_minus1, 0, 14)?;
+        let num_ref_idx_l1_default_active_minus1 = num_ref_idx_l1_default_active_minus1 as u8;
+
+        // The lower bound depends on the bit depth of the SPS, QpBdOffset_Y is at most 48.
+        let init_qp_minus26 = bit_reader.read_signed_exp_golomb()?;
+        range_check!(init_qp_minus26, -(26 + 48), 25)?;
+        let init_qp_minus26 = init_qp_minus26 as i8;
+
+        let constrained_intra_pred_flag = bit_reader.read_bit()?;
+        let transform_skip_enabled_flag = bit_reader.read_bit()?;
+
+        let mut diff_cu_qp_delta_depth = None;
+        let cu_qp_delta_enabled_flag = bit_reader.read_bit()?;
+        if cu_qp_delta_enabled_flag {
+            diff_cu_qp_delta_depth = Some(bit_reader.read_exp_golomb()?);
+        }
+
+        let pps_cb_qp_offset = bit_reader.read_signed_exp_golomb()?;
+        range_check!(pps_cb_qp_offset, -12, 12)?;
+        let pps_cb_qp_offset = pps_cb_qp_offset as i8;
+
+        let pps_cr_qp_offset = bit_reader.read_signed_exp_golomb()?;
+        range_check!(pps_cr_qp_offset, -12, 12)?;
+        let pps_cr_qp_offset = pps_cr_qp_offset as i8;
+
+        let pps_slice_chroma_qp_offsets_present_flag = bit_reader.read_bit()?;
+        let weighted_pred_flag = bit_reader.read_bit()?;
+        let weighted_bipred_flag = bit_reader.read_bit()?;
+        let transquant_bypass_enabled_flag = bit_reader.read_bit()?;
+        let tiles_enabled_flag = bit_reader.read_bit()?;
+        let entropy_coding_sync_enabled_flag = bit_reader.read_bit()?;
+
+        let mut tiles = None;
+        if tiles_enabled_flag {
+            tiles = Some(PpsTiles::parse(&mut bit_reader)?);
+        }
+
+        let pps_loop_filter_across_slices_enabled_flag = bit_reader.read_bit()?;
+
+        let mut deblocking_filter_control = None;
+        let deblocking_filter_control_present_flag = bit_reader.read_bit()?;
+        if deblocking_filter_control_present_flag {
+            deblocking_filter_control = Some(PpsDeblockingFilterControl::parse(&mut bit_reader)?);
+        }
+
+        let mut scaling_list_data = None;
+        let pps_scaling_list_data_present_flag = bit_reader.read_bit()?;
+        if pps_scaling_list_data_present_flag {
+            scaling_list_data = Some(ScalingListData::parse(&mut bit_reader)?);
+        }
+
+        let lists_modification_present_flag = bit_reader.read_bit()?;
+        let log2_parallel_merge_level_minus2 = bit_reader.read_exp_golomb()?;
+        let slice_segment_header_extension_present_flag = bit_reader.read_bit()?;
+
+        // Extensions
+        let mut range_extension = None;
+        let mut pps_multilayer_extension_flag = false;
+        let mut pps_3d_extension_flag = false;
+        let mut pps_scc_extension_flag = false;
+
+        let pps_extension_present_flag = bit_reader.read_bit()?;
+        if pps_extension_present_flag {
+            let pps_range_extension_flag = bit_reader.read_bit()?;
+            pps_multilayer_extension_flag = bit_reader.read_bit()?;
+            pps_3d_extension_flag = bit_reader.read_bit()?;
+            pps_scc_extension_flag = bit_reader.read_bit()?;
+            let pps_extension_4bits = bit_reader.read_bits(4)? as u8;
+
+            if pps_extension_4bits != 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "pps_extension_4bits must be 0"));
+            }
+
+            if pps_range_extension_flag {
+                range_extension = Some(PpsRangeExtension::parse(&mut bit_reader, transform_skip_enabled_flag)?);
+            }
+        }
+
+        // The other extensions are not parsed, so the trailing bits can only be checked without them.
+        if !pps_multilayer_extension_flag && !pps_3d_extension_flag && !pps_scc_extension_flag {
+            rbsp_trailing_bits(&mut bit_reader)?;
+        }
+
+        Ok(PpsRbsp {
+            pps_pic_parameter_set_id,
+            pps_seq_parameter_set_id,
+            dependent_slice_segments_enabled_flag,
+            output_flag_present_flag,
+            num_extra_slice_header_bits,
+            sign_data_hiding_enabled_flag,
+            cabac_init_present_flag,
+            num_ref_idx_l0_default_active_minus1,
+            num_ref_idx_l1_default_active_minus1,
+            init_qp_minus26,
+            constrained_intra_pred_flag,
+            transform_skip_enabled_flag,
+            diff_cu_qp_delta_depth,
+            pps_cb_qp_offset,
+            pps_cr_qp_offset,
+            pps_slice_chroma_qp_offsets_present_flag,
+            weighted_pred_flag,
+            weighted_bipred_flag,
+            transquant_bypass_enabled_flag,
+            tiles,
+            entropy_coding_sync_enabled_flag,
+            pps_loop_filter_across_slices_enabled_flag,
+            deblocking_filter_control,
+            scaling_list_data,
+            lists_modification_present_flag,
+            log2_parallel_merge_level_minus2,
+            slice_segment_header_extension_present_flag,
+            range_extension,
+            pps_multilayer_extension_flag,
+            pps_3d_extension_flag,
+            pps_scc_extension_flag,
+        })
+    }
+
+    /// Equal to `true` specifies that there is more than one tile in each picture referring to the PPS.
+    ///
+    /// ISO/IEC 23008-2 - 7.4.3.3.1
+    pub fn tiles_enabled_flag(&self) -> bool {
+        self.tiles.is_some()
+    }
+
+    /// The type of parallel decoding supported by pictures referring to this PPS,
+    /// as signalled by [`HEVCDecoderConfigurationRecord::parallelism_type`](crate::HEVCDecoderConfigurationRecord::parallelism_type).
+    ///
+    /// ISO/IEC 14496-15 - 8.3.2.1.3
+    pub fn parallelism_type(&self) -> ParallelismType {
+        match (self.tiles_enabled_flag(), self.entropy_coding_sync_enabled_flag) {
+            (true, false) => ParallelismType::Tile,
+            (false, true) => ParallelismType::EntropyCodingSync,
+            _ => ParallelismType::MixedOrUnknown,
+        }
+    }
+}
+
+/// The tile layout of the PPS.
+///
+/// Directly part of [PPS RBSP](PpsRbsp).
+///
+/// ISO/IEC 23008-2 - 7.4.3.3.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct PpsTiles {
+    /// This value plus 1 specifies the number of tile columns partitioning the picture.
+    pub num_tile_columns_minus1: u64,
+    /// This value plus 1 specifies the number of tile rows partitioning the picture.
+    pub num_tile_rows_minus1: u64,
+    /// Equal to `true` specifies that tile column boundaries and likewise tile row boundaries
+    /// are distributed uniformly across the picture.
+    pub uniform_spacing_flag: bool,
+    /// `column_width_minus1[i]` plus 1 specifies the width of the `i`-th tile column in units of coding tree blocks.
+    ///
+    /// Only present for the first `num_tile_columns_minus1` columns, if `uniform_spacing_flag` is `false`.
+    pub column_width_minus1: Vec<u64>,
+    /// `row_height_minus1[i]` plus 1 specifies the height of the `i`-th tile row in units of coding tree blocks.
+    ///
+    /// Only present for the first `num_tile_rows_minus1` rows, if `uniform_spacing_flag` is `false`.
+    pub row_height_minus1: Vec<u64>,
+    /// Equal to `true` specifies that in-loop filtering operations may be performed across
+    /// tile boundaries in pictures referring to the PPS.
+    pub loop_filter_across_tiles_enabled_flag: bool,
+}
+
+impl PpsTiles {
+    fn parse<R: io::Read>(bit_reader: &mut BitReader<R>) -> io::Result<Self> {
+        let num_tile_columns_minus1 = bit_reader.read_exp_golomb()?;
+        let num_tile_rows_minus1 = bit_reader.read_exp_golomb()?;
+
+        if num_tile_columns_minus1 == 0 && num_tile_rows_minus1 == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "num_tile_columns_minus1 and num_tile_rows_minus1 must not both be 0 when tiles_enabled_flag is 1",
+            ));
+        }
+
+        let uniform_spacing_flag = bit_reader.read_bit()?;
+
+        let mut column_width_minus1 = Vec::new();
+        let mut row_height_minus1 = Vec::new();
+        if !uniform_spacing_flag {
+            for _ in 0..num_tile_columns_minus1 {
+                column_width_minus1.push(bit_reader.read_exp_golomb()?);
+            }
+
+            for _ in 0..num_tile_rows_minus1 {
+                row_height_minus1.push(bit_reader.read_exp_golomb()?);
+            }
+        }
+
+        let loop_filter_across_tiles_enabled_flag = bit_reader.read_bit()?;
+
+        Ok(Self {
+            num_tile_columns_minus1,
+            num_tile_rows_minus1,
+            uniform_spacing_flag,
+            column_width_minus1,
+            row_height_minus1,
+            loop_filter_across_tiles_enabled_flag,
+        })
+    }
+}
+
+/// The deblocking filter control of the PPS.
+///
+/// Directly part of [PPS RBSP](PpsRbsp).
+///
+/// ISO/IEC 23008-2 - 7.4.3.3.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct PpsDeblockingFilterControl {
+    /// Equal to `true` specifies the presence of `deblocking_filter_override_flag`
+    /// in the slice headers for pictures referring to the PPS.
+    pub deblocking_filter_override_enabled_flag: bool,
+    /// Equal to `true` specifies that the operation of deblocking filter is not applied for slices referring
+    /// to the PPS in which `slice_deblocking_filter_disabled_flag` is not present.
+    pub pps_deblocking_filter_disabled_flag: bool,
+    /// Specifies the default deblocking parameter offset for β (divided by 2).
+    ///
+    /// The value is in range \[−6, 6\].
+    pub pps_beta_offset_div2: i8,
+    /// Specifies the default deblocking parameter offset for tC (divided by 2).
+    ///
+    /// The value is in range \[−6, 6\].
+    pub pps_tc_offset_div2: i8,
+}
+
+impl PpsDeblockingFilterControl {
+    fn parse<R: io::Read>(bit_reader: &mut BitReader<R>) -> io::Result<Self> {
+        let deblocking_filter_override_enabled_flag = bit_reader.read_bit()?;
+        let pps_deblocking_filter_disabled_flag = bit_reader.read_bit()?;
+
+        let mut pps_beta_offset_div2 = 0;
+        let mut pps_tc_offset_div2 = 0;
+        if !pps_deblocking_filter_disabled_flag {
+            let beta_offset_div2 = bit_reader.read_signed_exp_golomb()?;
+            range_check!(beta_offset_div2, -6, 6)?;
+            pps_beta_offset_div2 = beta_offset_div2 as i8;
+
+            let tc_offset_div2 = bit_reader.read_signed_exp_golomb()?;
+            range_check!(tc_offset_div2, -6, 6)?;
+            pps_tc_offset_div2 = tc_offset_div2 as i8;
+        }
+
+        Ok(Self {
+            deblocking_filter_override_enabled_flag,
+            pps_deblocking_filter_disabled_flag,
+            pps_beta_offset_div2,
+            pps_tc_offset_div2,
+        })
+    }
+}
+
+/// Picture parameter set range extension.
+///
+/// `pps_range_extension()`
+///
+/// - ISO/IEC 23008-2 - 7.3.2.3.2
+/// - ISO/IEC 23008-2 - 7.4.3.3.2
+#[derive(Debug, Clone, PartialEq)]
+pub struct PpsRangeExtension {
+    /// This value plus 2 specifies the maximum transform block size for which `transform_skip_flag` may be present,
+    /// if `transform_skip_enabled_flag` is `true`.
+    pub log2_max_transform_skip_block_size_minus2: Option<u64>,
+    /// Equal to `true` specifies that `log2_res_scale_abs_plus1` and `res_scale_sign_flag`
+    /// may be present in the transform unit syntax for pictures referring to the PPS.
+    pub cross_component_prediction_enabled_flag: bool,
+    /// `diff_cu_chroma_qp_offset_depth`, `cb_qp_offset_list` and `cr_qp_offset_list`,
+    /// if `chroma_qp_offset_list_enabled_flag` is `true`.
+    ///
+    /// See [`ChromaQpOffsetList`] for details.
+    pub chroma_qp_offset_list: Option<ChromaQpOffsetList>,
+    /// The base 2 logarithm of the scaling parameter that is used to scale sample adaptive offset values for luma samples.
+    pub log2_sao_offset_scale_luma: u64,
+    /// The base 2 logarithm of the scaling parameter that is used to scale sample adaptive offset values for chroma samples.
+    pub log2_sao_offset_scale_chroma: u64,
+}
+
+impl PpsRangeExtension {
+    fn parse<R: io::Read>(bit_reader: &mut BitReader<R>, transform_skip_enabled_flag: bool) -> io::Result<Self> {
+        let mut log2_max_transform_skip_block_size_minus2 = None;
+        if transform_skip_enabled_flag {
+            log2_max_transform_skip_block_size_minus2 = Some(bit_reader.read_exp_golomb()?);
+        }
+
+        let cross_component_prediction_enabled_flag = bit_reader.read_bit()?;
+
+        let mut chroma_qp_offset_list = None;
+        let chroma_qp_offset_list_enabled_flag = bit_reader.read_bit()?;
+        if chroma_qp_offset_list_enabled_flag {
+            chroma_qp_offset_list = Some(ChromaQpOffsetList::parse(bit_reader)?);
+        }
+
+        let log2_sao_offset_scale_luma = bit_reader.read_exp_golomb()?;
+        let log2_sao_offset_scale_chroma = bit_reader.read_exp_golomb()?;
+
+        Ok(Self {
+            log2_max_transform_skip_block_size_minus2,
+            cross_component_prediction_enabled_flag,
+            chroma_qp_offset_list,
+            log2_sao_offset_scale_luma,
+            log2_sao_offset_scale_chroma,
+        })
+    }
+}
+
+/// The chroma QP offset list of the [`PpsRangeExtension`].
+///
+/// ISO/IEC 23008-2 - 7.4.3.3.2
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChromaQpOffsetList {
+    /// Specifies the difference between the luma coding tree block size and the minimum luma coding block size
+    /// of coding units that convey `cu_chroma_qp_offset_flag`.
+    pub diff_cu_chroma_qp_offset_depth: u64,
+    /// `cb_qp_offset_list[i]` specifies the offsets used in the derivation of `Qp′Cb`.
+    ///
+    /// Contains 1 to 6 values in range \[−12, 12\].
+    pub cb_qp_offset_list: Vec<i8>,
+    /// `cr_qp_offset_list[i]` specifies the offsets used in the derivation of `Qp′Cr`.
+    ///
+    /// Contains 1 to 6 values in range \[−12, 12\].
+    pub cr_qp_offset_list: Vec<i8>,
+}
+
+impl ChromaQpOffsetList {
+    fn parse<R: io::Read>(bit_reader: &mut BitReader<R>) -> io::Result<Self> {
+        let diff_cu_chroma_qp_offset_depth = bit_reader.read_exp_golomb()?;
+
+        let chroma_qp_offset_list_len_minus1 = bit_reader.read_exp_golomb()?;
+        range_check!(chroma_qp_offset_list_len_minus1, 0, 5)?;
+
+        let mut cb_qp_offset_list = Vec::with_capacity(chroma_qp_offset_list_len_minus1 as usize + 1);
+        let mut cr_qp_offset_list = Vec::with_capacity(chroma_qp_offset_list_len_minus1 as usize + 1);
+        for _ in 0..=chroma_qp_offset_list_len_minus1 {
+            let cb_qp_offset = bit_reader.read_signed_exp_golomb()?;
+            range_check!(cb_qp_offset, -12, 12)?;
+            cb_qp_offset_list.push(cb_qp_offset as i8);
+
+            let cr_qp_offset = bit_reader.read_signed_exp_golomb()?;
+            range_check!(cr_qp_offset, -12, 12)?;
+            cr_qp_offset_list.push(cr_qp_offset as i8);
+        }
+
+        Ok(Self {
+            diff_cu_chroma_qp_offset_depth,
+            cb_qp_offset_list,
+            cr_qp_offset_list,
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use scuffle_bytes_util::BitWriter;
+    use scuffle_expgolomb::BitWriterExpGolombExt;
+
+    use crate::{ParallelismType, PpsNALUnit};
+
+    #[test]
+    fn test_pps_parse() {
+        // x265
+        let data = b"\x44\x01\xc1\x72\xb4\x22\x40";
+
+        let nalu = PpsNALUnit::parse(io::Cursor::new(data)).unwrap();
+        let pps = &nalu.rbsp;
+
+        assert_eq!(pps.pps_pic_parameter_set_id, 0);
+        assert_eq!(pps.pps_seq_parameter_set_id, 0);
+        assert!(!pps.tiles_enabled_flag());
+        assert!(!pps.entropy_coding_sync_enabled_flag);
+        assert_eq!(pps.parallelism_type(), ParallelismType::MixedOrUnknown);
+
+        insta::assert_debug_snapshot!(nalu);
+    }
+
+    #[test]
+    fn test_pps_parse2() {
+        let data = b"D\x01\xc0\x93|\x0c\xc9";
+
+        let nalu = PpsNALUnit::parse(io::Cursor::new(data)).unwrap();
+
+        insta::assert_debug_snapshot!(nalu);
+    }
+
+    #[test]
+    fn test_pps_parse_tiles() {
+        let mut data = b"\x44\x01".to_vec();
+        let mut writer = BitWriter::new(&mut data);
+
+        writer.write_exp_golomb(1).unwrap(); // pps_pic_parameter_set_id
+        writer.write_exp_golomb(2).unwrap(); // pps_seq_parameter_set_id
+        writer.write_bits(0, 5).unwrap(); // dependent_slice_segments_enabled_flag .. num_extra_slice_header_bits
+        writer.write_bits(0, 2).unwrap(); // sign_data_hiding_enabled_flag, cabac_init_present_flag
+        writer.write_exp_golomb(0).unwrap(); // num_ref_idx_l0_default_active_minus1
+        writer.write_exp_golomb(0).unwrap(); // num_ref_idx_l1_default_active_minus1
+        writer.write_signed_exp_golomb(-4).unwrap(); // init_qp_minus26
+        writer.write_bits(0, 2).unwrap(); // constrained_intra_pred_flag, transform_skip_enabled_flag
+        writer.write_bit(false).unwrap(); // cu_qp_delta_enabled_flag
+        writer.write_signed_exp_golomb(0).unwrap(); // pps_cb_qp_offset
+        writer.write_signed_exp_golomb(0).unwrap(); // pps_cr_qp_offset
+        writer.write_bits(0, 4).unwrap(); // pps_slice_chroma_qp_offsets_present_flag .. transquant_bypass_enabled_flag
+        writer.write_bit(true).unwrap(); // tiles_enabled_flag
+        writer.write_bit(true).unwrap(); // entropy_coding_sync_enabled_flag
+        writer.write_exp_golomb(2).unwrap(); // num_tile_columns_minus1
+        writer.write_exp_golomb(1).unwrap(); // num_tile_rows_minus1
+        writer.write_bit(false).unwrap(); // uniform_spacing_flag
+        writer.write_exp_golomb(9).unwrap(); // column_width_minus1[0]
+        writer.write_exp_golomb(19).unwrap(); // column_width_minus1[1]
+        writer.write_exp_golomb(16).unwrap(); // row_height_minus1[0]
+        writer.write_bit(true).unwrap(); // loop_filter_across_tiles_enabled_flag
+        writer.write_bit(true).unwrap(); // pps_loop_filter_across_slices_enabled_flag
+        writer.write_bit(true).unwrap(); // deblocking_filter_control_present_flag
+        writer.write_bit(true).unwrap(); // deblocking_filter_override_enabled_flag
+        writer.write_bit(false).unwrap(); // pps_deblocking_filter_disabled_flag
+        writer.write_signed_exp_golomb(-2).unwrap(); // pps_beta_offset_div2
+        writer.write_signed_exp_golomb(3).unwrap(); // pps_tc_offset_div2
+        writer.write_bit(false).unwrap(); // pps_scaling_list_data_present_flag
+        writer.write_bit(false).unwrap(); // lists_modification_present_flag
+        writer.write_exp_golomb(0).unwrap(); // log2_parallel_merge_level_minus2
+        writer.write_bit(false).unwrap(); // slice_segment_header_extension_present_flag
+        writer.write_bit(false).unwrap(); // pps_extension_present_flag
+        writer.write_bit(true).unwrap(); // rbsp_stop_one_bit
+        writer.finish().unwrap();
+
+        let nalu = PpsNALUnit::parse(io::Cursor::new(data)).unwrap();
+        let pps = &nalu.rbsp;
+
+        assert_eq!(pps.pps_pic_parameter_set_id, 1);
+        assert_eq!(pps.pps_seq_parameter_set_id, 2);
+        assert_eq!(pps.init_qp_minus26, -4);
+        assert!(pps.entropy_coding_sync_enabled_flag);
+        assert_eq!(pps.parallelism_type(), ParallelismType::MixedOrUnknown);
+
+        let tiles = pps.tiles.as_ref().unwrap();
+        assert_eq!(tiles.num_tile_columns_minus1, 2);
+        assert_eq!(tiles.num_tile_rows_minus1, 1);
+        assert!(!tiles.uniform_spacing_flag);
+        assert_eq!(tiles.column_width_minus1, vec![9, 19]);
+        assert_eq!(tiles.row_height_minus1, vec![16]);
+        assert!(tiles.loop_filter_across_tiles_enabled_flag);
+
+        let deblocking_filter_control = pps.deblocking_filter_control.as_ref().unwrap();
+        assert!(deblocking_filter_control.deblocking_filter_override_enabled_flag);
+        assert_eq!(deblocking_filter_control.pps_beta_offset_div2, -2);
+        assert_eq!(deblocking_filter_control.pps_tc_offset_div2, 3);
+    }
+
+    #[test]
+    fn test_pps_parse_not_pps() {
+        let data = b"@\x01\x0c\x01\xff\xff\x01@\0\0\x03\0\x90\0\0\x03\0\0\x03\0\x99\x95@\x90";
+
+        let err = PpsNALUnit::parse(io::Cursor::new(data)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,326 @@
+use std::io;
+
+use scuffle_bytes_util::{BitReader, EmulationPreventionIo, range_check};
+use scuffle_expgolomb::BitReaderExpGolombExt;
+
+use crate::nal_unit_header::NALUnitHeader;
+use crate::{NALUnitType, SliceType, SpsRbsp};
+
+/// The values from the active SPS and PPS that are required to parse a [`SliceSegmentHeader`]
+/// up to and including the `slice_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceSegmentHeaderParams {
+    /// Matches the `dependent_slice_segments_enabled_flag` field of the PPS.
+    ///
+    /// ISO/IEC 23008-2 - 7.4.3.3.1
+    pub dependent_slice_segments_enabled_flag: bool,
+    /// Matches the `num_extra_slice_header_bits` field of the PPS.
+    ///
+    /// The value is in range \[0, 7\].
+    ///
+    /// ISO/IEC 23008-2 - 7.4.3.3.1
+    pub num_extra_slice_header_bits: u8,
+    /// Matches [`SpsRbsp::pic_size_in_ctbs_y`].
+    pub pic_size_in_ctbs_y: u64,
+}
+
+impl SliceSegmentHeaderParams {
+    /// Creates the parameters from the active SPS and a PPS NAL unit.
+    ///
+    /// Only the leading fields of the PPS are parsed.
+    ///
+    /// ISO/IEC 23008-2 - 7.3.2.3.1
+    pub fn new(sps: &SpsRbsp, mut pps: impl io::Read) -> io::Result<Self> {
+        let nal_unit_header = NALUnitHeader::parse(&mut pps)?;
+        if nal_unit_header.nal_unit_type != NALUnitType::PpsNut {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "nal_unit_type is not PPS_NUT"));
+        }
+
+        let mut bit_reader = BitReader::new(EmulationPreventionIo::new(pps));
+
+        let pps_pic_parameter_set_id = bit_reader.read_exp_golomb()?;
+        range_check!(pps_pic_parameter_set_id, 0, 63)?;
+
+        let pps_seq_parameter_set_id = bit_reader.read_exp_golomb()?;
+        range_check!(pps_seq_parameter_set_id, 0, 15)?;
+
+        let dependent_slice_segments_enabled_flag = bit_reader.read_bit()?;
+        let _output_flag_present_flag = bit_reader.read_bit()?;
+        let num_extra_slice_header_bits = bit_reader.read_bits(3)? as u8;
+
+        Ok(Self {
+            dependent_slice_segments_enabled_flag,
+            num_extra_slice_header_bits,
+            pic_size_in_ctbs_y: sps.pic_size_in_ctbs_y(),
+        })
+    }
+}
+
+/// Slice segment layer contained in a NAL unit.
+///
+/// Only the slice segment header is parsed, the slice segment data is ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceSegmentNALUnit {
+    /// The NAL unit header.
+    pub nal_unit_header: NALUnitHeader,
+    /// The slice segment header.
+    pub header: SliceSegmentHeader,
+}
+
+impl SliceSegmentNALUnit {
+    /// Parses the header of a slice segment NAL unit from the given reader.
+    pub fn parse(mut reader: impl io::Read, params: &SliceSegmentHeaderParams) -> io::Result<Self> {
+        let nal_unit_header = NALUnitHeader::parse(&mut reader)?;
+        if !nal_unit_header.nal_unit_type.is_vcl() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "nal_unit_type is not a VCL NAL unit type",
+            ));
+        }
+
+        let header = SliceSegmentHeader::parse(reader, nal_unit_header.nal_unit_type, params)?;
+
+        Ok(Self { nal_unit_header, header })
+    }
+
+    /// Reads only the `first_slice_segment_in_pic_flag` of a slice segment NAL unit.
+    ///
+    /// This does not require any parameter sets, which makes it enough to find access unit boundaries.
+    /// Returns `None` if the NAL unit is not a VCL NAL unit.
+    pub fn parse_first_slice_segment_in_pic_flag(mut reader: impl io::Read) -> io::Result<Option<bool>> {
+        let nal_unit_header = NALUnitHeader::parse(&mut reader)?;
+        if !nal_unit_header.nal_unit_type.is_vcl() {
+            return Ok(None);
+        }
+
+        BitReader::new(EmulationPreventionIo::new(reader)).read_bit().map(Some)
+    }
+
+    /// Returns `true` if this slice segment belongs to an IRAP picture, which can be decoded
+    /// without referencing any other picture.
+    pub fn is_keyframe(&self) -> bool {
+        self.nal_unit_header.nal_unit_type.is_irap()
+    }
+}
+
+/// Slice segment header.
+///
+/// Only the fields up to and including the `slice_type` are parsed.
+///
+/// `slice_segment_header()`
+///
+/// - ISO/IEC 23008-2 - 7.3.6.1
+/// - ISO/IEC 23008-2 - 7.4.7.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceSegmentHeader {
+    /// Equal to `true` specifies that the slice segment is the first slice segment of the picture in decoding order.
+    pub first_slice_segment_in_pic_flag: bool,
+    /// Affects the output of previously-decoded pictures in the decoded picture buffer after the
+    /// decoding of an IDR or a BLA picture.
+    ///
+    /// Only present for IRAP pictures, otherwise `false`.
+    pub no_output_of_prior_pics_flag: bool,
+    /// Specifies the value of `pps_pic_parameter_set_id` for the PPS in use.
+    ///
+    /// The value is in range \[0, 63\].
+    pub slice_pic_parameter_set_id: u64,
+    /// Equal to `true` specifies that the values of the slice segment header syntax elements are
+    /// inferred from the preceding independent slice segment.
+    pub dependent_slice_segment_flag: bool,
+    /// Specifies the address of the first CTB in the slice segment, in the coding tree block raster scan of a picture.
+    ///
+    /// The value is in range \[0, `PicSizeInCtbsY` - 1\].
+    pub slice_segment_address: u64,
+    /// Specifies the coding type of the slice.
+    ///
+    /// `None` for dependent slice segments, which share the slice type of the preceding independent slice segment.
+    pub slice_type: Option<SliceType>,
+}
+
+impl SliceSegmentHeader {
+    /// Parses a slice segment header from the given reader.
+    ///
+    /// The reader should be positioned right after the NAL unit header.
+    pub fn parse(reader: impl io::Read, nal_unit_type: NALUnitType, params: &SliceSegmentHeaderParams) -> io::Result<Self> {
+        let mut bit_reader = BitReader::new(EmulationPreventionIo::new(reader));
+
+        let first_slice_segment_in_pic_flag = bit_reader.read_bit()?;
+
+        let mut no_output_of_prior_pics_flag = false;
+        if nal_unit_type.is_irap() {
+            no_output_of_prior_pics_flag = bit_reader.read_bit()?;
+        }
+
+        let slice_pic_parameter_set_id = bit_reader.read_exp_golomb()?;
+        range_check!(slice_pic_parameter_set_id, 0, 63)?;
+
+        let mut dependent_slice_segment_flag = false;
+        let mut slice_segment_address = 0;
+        if !first_slice_segment_in_pic_flag {
+            if params.dependent_slice_segments_enabled_flag {
+                dependent_slice_segment_flag = bit_reader.read_bit()?;
+            }
+
+            // Ceil(Log2(PicSizeInCtbsY))
+            let bits = u64::BITS - params.pic_size_in_ctbs_y.saturating_sub(1).leading_zeros();
+            slice_segment_address = bit_reader.read_bits(bits as u8)?;
+            range_check!(slice_segment_address, 0, params.pic_size_in_ctbs_y.saturating_sub(1))?;
+        }
+
+        let mut slice_type = None;
+        if !dependent_slice_segment_flag {
+            // slice_reserved_flag
+            bit_reader.read_bits(params.num_extra_slice_header_bits)?;
+
+            let value = bit_reader.read_exp_golomb()?;
+            range_check!(value, 0, 2)?;
+            slice_type = Some(SliceType::from(value as u8));
+        }
+
+        Ok(Self {
+            first_slice_segment_in_pic_flag,
+            no_output_of_prior_pics_flag,
+            slice_pic_parameter_set_id,
+            dependent_slice_segment_flag,
+            slice_segment_address,
+            slice_type,
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use crate::{NALUnitType, SliceSegmentHeader, SliceSegmentHeaderParams, SliceSegmentNALUnit, SliceType, SpsNALUnit};
+
+    // Taken from assets/hevc_aac.flv, encoded with x265.
+    const SPS: &[u8] = b"\x42\x01\x01\x01\x60\x00\x00\x03\x00\x90\x00\x00\x03\x00\x00\x03\x00\x99\xa0\x01\xe0\x20\x02\x1c\x59\x65\x66\x92\x4c\xaf\x01\x68\x08\x00\x00\x03\x00\x08\x00\x00\x03\x01\xe0\x40";
+    const PPS: &[u8] = b"\x44\x01\xc1\x72\xb4\x22\x40";
+    const IDR: &[u8] = b"\x28\x01\xaf\x0a\x60\xf9\x72\x39\xff\xf7\xb2\x6e";
+    const P: &[u8] = b"\x02\x01\xd0\x19\x5f\x84\x31\x85\x10\xfa\xc4\x08";
+    const B: &[u8] = b"\x02\x01\xe0\x44\x97\xe1\x86\x10\x84\x00\x00\x03";
+
+    fn params() -> SliceSegmentHeaderParams {
+        let sps = SpsNALUnit::parse(io::Cursor::new(SPS)).unwrap();
+        SliceSegmentHeaderParams::new(&sps.rbsp, io::Cursor::new(PPS)).unwrap()
+    }
+
+    #[test]
+    fn test_params() {
+        assert_eq!(
+            params(),
+            SliceSegmentHeaderParams {
+                dependent_slice_segments_enabled_flag: false,
+                num_extra_slice_header_bits: 0,
+                pic_size_in_ctbs_y: 2074,
+            }
+        );
+
+        let err =
+            SliceSegmentHeaderParams::new(&SpsNALUnit::parse(io::Cursor::new(SPS)).unwrap().rbsp, io::Cursor::new(SPS))
+                .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_slice_segment_parse() {
+        let params = params();
+
+        let idr = SliceSegmentNALUnit::parse(io::Cursor::new(IDR), &params).unwrap();
+        assert_eq!(idr.nal_unit_header.nal_unit_type, NALUnitType::IdrNLp);
+        assert!(idr.is_keyframe());
+        assert_eq!(
+            idr.header,
+            SliceSegmentHeader {
+                first_slice_segment_in_pic_flag: true,
+                no_output_of_prior_pics_flag: false,
+                slice_pic_parameter_set_id: 0,
+                dependent_slice_segment_flag: false,
+                slice_segment_address: 0,
+                slice_type: Some(SliceType::I),
+            }
+        );
+
+        let p = SliceSegmentNALUnit::parse(io::Cursor::new(P), &params).unwrap();
+        assert_eq!(p.nal_unit_header.nal_unit_type, NALUnitType::TrailR);
+        assert!(!p.is_keyframe());
+        assert!(p.header.first_slice_segment_in_pic_flag);
+        assert_eq!(p.header.slice_type, Some(SliceType::P));
+
+        let b = SliceSegmentNALUnit::parse(io::Cursor::new(B), &params).unwrap();
+        assert_eq!(b.header.slice_type, Some(SliceType::B));
+
+        let err = SliceSegmentNALUnit::parse(io::Cursor::new(PPS), &params).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_slice_segment_address() {
+        let params = SliceSegmentHeaderParams {
+            dependent_slice_segments_enabled_flag: true,
+            num_extra_slice_header_bits: 2,
+            pic_size_in_ctbs_y: 8,
+        };
+
+        // first_slice_segment_in_pic_flag = 0, slice_pic_parameter_set_id = 0 (1),
+        // dependent_slice_segment_flag = 1, slice_segment_address = 5 (101)
+        let header = SliceSegmentHeader::parse(io::Cursor::new(b"\x74\x80\x00\x00"), NALUnitType::TrailR, &params).unwrap();
+        assert!(header.dependent_slice_segment_flag);
+        assert_eq!(header.slice_segment_address, 5);
+        assert_eq!(header.slice_type, None);
+
+        // first_slice_segment_in_pic_flag = 0, slice_pic_parameter_set_id = 0 (1),
+        // dependent_slice_segment_flag = 0, slice_segment_address = 3 (011),
+        // slice_reserved_flag (11), slice_type = 1 (010)
+        let header = SliceSegmentHeader::parse(io::Cursor::new(b"\x4f\x40\x00\x00"), NALUnitType::TrailR, &params).unwrap();
+        assert!(!header.dependent_slice_segment_flag);
+        assert_eq!(header.slice_segment_address, 3);
+        assert_eq!(header.slice_type, Some(SliceType::P));
+    }
+
+    #[test]
+    fn test_first_slice_segment_in_pic_flag() {
+        assert_eq!(
+            SliceSegmentNALUnit::parse_first_slice_segment_in_pic_flag(io::Cursor::new(IDR)).unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            SliceSegmentNALUnit::parse_first_slice_segment_in_pic_flag(io::Cursor::new(b"\x02\x01\x7f")).unwrap(),
+            Some(false)
+        );
+        assert_eq!(
+            SliceSegmentNALUnit::parse_first_slice_segment_in_pic_flag(io::Cursor::new(PPS)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nal_unit_type_classification() {
+        assert!(NALUnitType::IdrWRadl.is_irap());
+        assert!(NALUnitType::CraNut.is_irap());
+        assert!(NALUnitType::BlaNLp.is_irap());
+        assert!(!NALUnitType::TrailR.is_irap());
+
+        assert!(NALUnitType::IdrNLp.is_idr());
+        assert!(!NALUnitType::CraNut.is_idr());
+        assert!(NALUnitType::BlaWRadl.is_bla());
+        assert!(NALUnitType::CraNut.is_cra());
+        assert!(NALUnitType::RaslN.is_rasl());
+        assert!(NALUnitType::RadlR.is_radl());
+
+        assert!(NALUnitType::TrailN.is_sub_layer_non_reference());
+        assert!(NALUnitType::RaslN.is_sub_layer_non_reference());
+        assert!(!NALUnitType::TrailR.is_sub_layer_non_reference());
+        assert!(!NALUnitType::CraNut.is_sub_layer_non_reference());
+
+        assert!(NALUnitType::AudNut.starts_access_unit());
+        assert!(NALUnitType::SpsNut.starts_access_unit());
+        assert!(NALUnitType::PrefixSeiNut.starts_access_unit());
+        assert!(NALUnitType::from(50).starts_access_unit());
+        assert!(!NALUnitType::SuffixSeiNut.starts_access_unit());
+        assert!(!NALUnitType::EosNut.starts_access_unit());
+        assert!(!NALUnitType::TrailR.starts_access_unit());
+    }
+}
@@ -3,7 +3,8 @@ use std::io;
 use byteorder::{BigEndian, ReadBytesExt};
 use scuffle_bytes_util::{BitReader, range_check};
 
-use crate::ProfileCompatibilityFlags;
+use crate::config::codec_string;
+use crate::{HevcSampleEntry, ProfileCompatibilityFlags};
 
 /// Profile, tier and level.
 ///
@@ -45,6 +46,23 @@ pub struct ProfileTierLevel {
 }
 
 impl ProfileTierLevel {
+    /// Returns the codec string of the general profile, tier and level as used in the `codecs`
+    /// parameter of HLS and DASH playlists, for example `hvc1.1.6.L93.B0`.
+    ///
+    /// ISO/IEC 14496-15 - E.3
+    pub fn codec_string(&self, sample_entry: HevcSampleEntry) -> String {
+        let profile = &self.general_profile;
+        codec_string(
+            sample_entry,
+            profile.profile_space,
+            profile.tier_flag,
+            profile.profile_idc,
+            profile.profile_compatibility_flag,
+            profile.constraint_indicator_flags(),
+            profile.level_idc.unwrap_or_default(),
+        )
+    }
+
     pub(crate) fn parse<R: io::Read>(bit_reader: &mut BitReader<R>, max_num_sub_layers_minus_1: u8) -> io::Result<Self> {
         // When parsing SPSs, the profile_present_flag is always true. (See 7.3.2.2.1)
         // Since this decoder only supports SPS decoding, it is assumed to be true here.
@@ -158,6 +176,49 @@ pub struct Profile {
 }
 
 impl Profile {
+    /// Returns the 48 constraint flags following the profile compatibility flags, as stored in
+    /// [`general_constraint_indicator_flags`](crate::HEVCDecoderConfigurationRecord::general_constraint_indicator_flags).
+    pub fn constraint_indicator_flags(&self) -> u64 {
+        let mut flags = (self.progressive_source_flag as u64) << 47
+            | (self.interlaced_source_flag as u64) << 46
+            | (self.non_packed_constraint_flag as u64) << 45
+            | (self.frame_only_constraint_flag as u64) << 44;
+
+        match self.additional_flags {
+            ProfileAdditionalFlags::Full {
+                max_12bit_constraint_flag,
+                max_10bit_constraint_flag,
+                max_8bit_constraint_flag,
+                max_422chroma_constraint_flag,
+                max_420chroma_constraint_flag,
+                max_monochrome_constraint_flag,
+                intra_constraint_flag,
+                one_picture_only_constraint_flag,
+                lower_bit_rate_constraint_flag,
+                max_14bit_constraint_flag,
+            } => {
+                flags |= (max_12bit_constraint_flag as u64) << 43
+                    | (max_10bit_constraint_flag as u64) << 42
+                    | (max_8bit_constraint_flag as u64) << 41
+                    | (max_422chroma_constraint_flag as u64) << 40
+                    | (max_420chroma_constraint_flag as u64) << 39
+                    | (max_monochrome_constraint_flag as u64) << 38
+                    | (intra_constraint_flag as u64) << 37
+                    | (one_picture_only_constraint_flag as u64) << 36
+                    | (lower_bit_rate_constraint_flag as u64) << 35
+                    | (max_14bit_constraint_flag.unwrap_or(false) as u64) << 34;
+            }
+            ProfileAdditionalFlags::Main10Profile {
+                one_picture_only_constraint_flag,
+            } => {
+                flags |= (one_picture_only_constraint_flag as u64) << 36;
+            }
+            ProfileAdditionalFlags::None => {}
+        }
+
+        flags | self.inbld_flag.unwrap_or(false) as u64
+    }
+
     fn parse<R: io::Read>(bit_reader: &mut BitReader<R>, level_present: bool) -> io::Result<Self> {
         let profile_space = bit_reader.read_bits(2)? as u8;
         let tier_flag = bit_reader.read_bit()?;
@@ -3,9 +3,9 @@ use std::io;
 use scuffle_bytes_util::{BitReader, range_check};
 use scuffle_expgolomb::BitReaderExpGolombExt;
 
-/// Info for each sub-layer in the SPS.
+/// Info for each sub-layer in the SPS or VPS.
 ///
-/// Directly part of [SPS RBSP](crate::SpsRbsp).
+/// Directly part of [SPS RBSP](crate::SpsRbsp) and [VPS RBSP](crate::VpsRbsp).
 #[derive(Debug, Clone, PartialEq)]
 pub struct SubLayerOrderingInfo {
     /// `sps_max_dec_pic_buffering_minus1[i]` plus 1 specifies the maximum required size of the decoded
@@ -0,0 +1,409 @@
+use std::io;
+use std::num::NonZero;
+
+use byteorder::{BigEndian, ReadBytesExt};
+use scuffle_bytes_util::{BitReader, EmulationPreventionIo, range_check};
+use scuffle_expgolomb::BitReaderExpGolombExt;
+
+use crate::nal_unit_header::NALUnitHeader;
+use crate::rbsp_trailing_bits::rbsp_trailing_bits;
+use crate::{HrdParameters, NALUnitType, ProfileTierLevel, SubLayerOrderingInfo};
+
+/// Video parameter set contained in a NAL unit.
+///
+/// This only represents video parameter sets that are part of NAL units.
+/// Therefore the NAL unit header is included in this struct as [`VpsNALUnit::nal_unit_header`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VpsNALUnit {
+    /// The NAL unit header.
+    pub nal_unit_header: NALUnitHeader,
+    /// The VPS RBSP.
+    pub rbsp: VpsRbsp,
+}
+
+impl VpsNALUnit {
+    /// Parses a VPS NAL unit from the given reader.
+    pub fn parse(mut reader: impl io::Read) -> io::Result<Self> {
+        let nal_unit_header = NALUnitHeader::parse(&mut reader)?;
+        if nal_unit_header.nal_unit_type != NALUnitType::VpsNut {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "nal_unit_type is not VPS_NUT"));
+        }
+
+        let rbsp = VpsRbsp::parse(reader)?;
+
+        Ok(VpsNALUnit { nal_unit_header, rbsp })
+    }
+}
+
+/// Video parameter set RBSP.
+///
+/// For parsing VPS RBSPs that are part of NAL units, please use [`VpsNALUnit::parse`].
+///
+/// `video_parameter_set_rbsp()`
+///
+/// - ISO/IEC 23008-2 - 7.3.2.1
+/// - ISO/IEC 23008-2 - 7.4.3.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct VpsRbsp {
+    /// Provides an identifier for the VPS for reference by other syntax elements.
+    ///
+    /// The value is in range \[0, 15\].
+    pub vps_video_parameter_set_id: u8,
+    /// Equal to `true` specifies that the base layer is provided in the bitstream.
+    ///
+    /// Equal to `false` specifies that the base layer is provided by an external means not specified in this specification.
+    pub vps_base_layer_internal_flag: bool,
+    /// Equal to `true` specifies that the base layer is available for use in the decoding process.
+    pub vps_base_layer_available_flag: bool,
+    /// This value plus 1 specifies the maximum allowed number of layers in each CVS referring to the VPS.
+    ///
+    /// The value is in range \[0, 62\].
+    pub vps_max_layers_minus1: u8,
+    /// This value plus 1 specifies the maximum number of temporal sub-layers that may be present
+    /// in each CVS referring to the VPS.
+    ///
+    /// The value is in range \[0, 6\].
+    pub vps_max_sub_layers_minus1: u8,
+    /// Specifies whether inter prediction is additionally restricted for CVSs referring to the VPS.
+    ///
+    /// When `vps_max_sub_layers_minus1 == 0`, this flag is `true`.
+    pub vps_temporal_id_nesting_flag: bool,
+    /// The [`ProfileTierLevel`] structure contained in this VPS.
+    pub profile_tier_level: ProfileTierLevel,
+    /// `vps_max_dec_pic_buffering_minus1`, `vps_max_num_reorder_pics`, and `vps_max_latency_increase_plus1` for each sub-layer.
+    ///
+    /// See [`SubLayerOrderingInfo`] for details.
+    pub sub_layer_ordering_info: SubLayerOrderingInfo,
+    /// Specifies the maximum allowed value of `nuh_layer_id` of all NAL units in each CVS referring to the VPS.
+    ///
+    /// The value is in range \[0, 62\].
+    pub vps_max_layer_id: u8,
+    /// This value plus 1 specifies the number of layer sets that are specified by the VPS.
+    ///
+    /// The value is in range \[0, 1023\].
+    pub vps_num_layer_sets_minus1: u16,
+    /// `layer_id_included_flag[i][j]` specifies whether the `nuh_layer_id` `j` is included in the layer set `i`.
+    ///
+    /// The first entry is the layer set 1, because the layer set 0 only contains the `nuh_layer_id` 0.
+    /// Each entry has `vps_max_layer_id + 1` flags.
+    pub layer_id_included_flag: Vec<Vec<bool>>,
+    /// `vps_num_units_in_tick`, `vps_time_scale`, `vps_poc_proportional_to_timing_flag`,
+    /// `vps_num_ticks_poc_diff_one_minus1` and the HRD parameters, if `vps_timing_info_present_flag` is `true`.
+    ///
+    /// See [`VpsTimingInfo`] for details.
+    pub vps_timing_info: Option<VpsTimingInfo>,
+    /// Equal to `true` specifies that the `vps_extension()` syntax structure is present.
+    ///
+    /// The extension is not parsed.
+    pub vps_extension_flag: bool,
+}
+
+impl VpsRbsp {
+    /// Parses a VPS RBSP from the given reader.
+    ///
+    /// Uses [`EmulationPreventionIo`] to handle emulation prevention bytes.
+    ///
+    /// Returns a [`VpsRbsp`] struct.
+    pub fn parse(reader: impl io::Read) -> io::Result<Self> {
+        let mut bit_reader = BitReader::new(EmulationPreventionIo::new(reader));
+
+        let vps_video_parameter_set_id = bit_reader.read_bits(4)? as u8;
+        let vps_base_layer_internal_flag = bit_reader.read_bit()?;
+        let vps_base_layer_available_flag = bit_reader.read_bit()?;
+
+        let vps_max_layers_minus1 = bit_reader.read_bits(6)? as u8;
+        range_check!(vps_max_layers_minus1, 0, 62)?;
+
+        let vps_max_sub_layers_minus1 = bit_reader.read_bits(3)? as u8;
+        range_check!(vps_max_sub_layers_minus1, 0, 6)?;
+
+        let vps_temporal_id_nesting_flag = bit_reader.read_bit()?;
+
+        if vps_max_sub_layers_minus1 == 0 && !vps_temporal_id_nesting_flag {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "vps_temporal_id_nesting_flag must be 1 when vps_max_sub_layers_minus1 is 0",
+            ));
+        }
+
+        // vps_reserved_0xffff_16bits, decoders shall ignore the value
+        bit_reader.read_u16::<BigEndian>()?;
+
+        let profile_tier_level = ProfileTierLevel::parse(&mut bit_reader, vps_max_sub_layers_minus1)?;
+
+        let vps_sub_layer_ordering_info_present_flag = bit_reader.read_bit()?;
+        let sub_layer_ordering_info = SubLayerOrderingInfo::parse(
+            &mut bit_reader,
+            vps_sub_layer_ordering_info_present_flag,
+            vps_max_sub_layers_minus1,
+        )?;
+
+        let vps_max_layer_id = bit_reader.read_bits(6)? as u8;
+        range_check!(vps_max_layer_id, 0, 62)?;
+
+        let vps_num_layer_sets_minus1 = bit_reader.read_exp_golomb()?;
+        range_check!(vps_num_layer_sets_minus1, 0, 1023)?;
+        let vps_num_layer_sets_minus1 = vps_num_layer_sets_minus1 as u16;
+
+        let mut layer_id_included_flag = Vec::with_capacity(vps_num_layer_sets_minus1 as usize);
+        for _ in 1..=vps_num_layer_sets_minus1 {
+            let flags = (0..=vps_max_layer_id)
+                .map(|_| bit_reader.read_bit())
+                .collect::<io::Result<Vec<_>>>()?;
+            layer_id_included_flag.push(flags);
+        }
+
+        let mut vps_timing_info = None;
+        let vps_timing_info_present_flag = bit_reader.read_bit()?;
+        if vps_timing_info_present_flag {
+            vps_timing_info = Some(VpsTimingInfo::parse(
+                &mut bit_reader,
+                vps_base_layer_internal_flag,
+                vps_max_sub_layers_minus1,
+                vps_num_layer_sets_minus1,
+            )?);
+        }
+
+        let vps_extension_flag = bit_reader.read_bit()?;
+
+        // The extension is not parsed, so the trailing bits can only be checked without it.
+        if !vps_extension_flag {
+            rbsp_trailing_bits(&mut bit_reader)?;
+        }
+
+        Ok(VpsRbsp {
+            vps_video_parameter_set_id,
+            vps_base_layer_internal_flag,
+            vps_base_layer_available_flag,
+            vps_max_layers_minus1,
+            vps_max_sub_layers_minus1,
+            vps_temporal_id_nesting_flag,
+            profile_tier_level,
+            sub_layer_ordering_info,
+            vps_max_layer_id,
+            vps_num_layer_sets_minus1,
+            layer_id_included_flag,
+            vps_timing_info,
+            vps_extension_flag,
+        })
+    }
+
+    /// The `nuh_layer_id` values included in the layer set `i`.
+    ///
+    /// Returns [`None`] if `i` is greater than [`vps_num_layer_sets_minus1`](Self::vps_num_layer_sets_minus1).
+    ///
+    /// ISO/IEC 23008-2 - 7.4.3.1
+    pub fn layer_set_layer_ids(&self, i: usize) -> Option<Vec<u8>> {
+        if i == 0 {
+            return Some(vec![0]);
+        }
+
+        let flags = self.layer_id_included_flag.get(i - 1)?;
+        Some(
+            flags
+                .iter()
+                .enumerate()
+                .filter(|(_, included)| **included)
+                .map(|(layer_id, _)| layer_id as u8)
+                .collect(),
+        )
+    }
+}
+
+/// Timing and HRD information of the VPS.
+///
+/// Directly part of [VPS RBSP](VpsRbsp).
+///
+/// ISO/IEC 23008-2 - 7.4.3.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct VpsTimingInfo {
+    /// The number of time units of a clock operating at the frequency `vps_time_scale` Hz
+    /// that corresponds to one increment (called a clock tick) of a clock tick counter.
+    pub num_units_in_tick: NonZero<u32>,
+    /// The number of time units that pass in one second.
+    pub time_scale: NonZero<u32>,
+    /// Equal to `true` indicates that the picture order count value for each picture in the CVS that is not
+    /// the first picture in the CVS, in decoding order, is proportional to the output time of the picture
+    /// relative to the output time of the first picture in the CVS.
+    pub poc_proportional_to_timing_flag: bool,
+    /// This value plus 1 specifies the number of clock ticks corresponding to a
+    /// difference of picture order count values equal to 1.
+    ///
+    /// The value is in range \[0, 2^32 − 2\].
+    pub num_ticks_poc_diff_one_minus1: Option<u32>,
+    /// The HRD parameters of the layer sets, see [`VpsHrdParameters`].
+    pub hrd_parameters: Vec<VpsHrdParameters>,
+}
+
+impl VpsTimingInfo {
+    fn parse<R: io::Read>(
+        bit_reader: &mut BitReader<R>,
+        vps_base_layer_internal_flag: bool,
+        vps_max_sub_layers_minus1: u8,
+        vps_num_layer_sets_minus1: u16,
+    ) -> io::Result<Self> {
+        let num_units_in_tick = NonZero::new(bit_reader.read_u32::<BigEndian>()?).ok_or(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "vps_num_units_in_tick must not be zero",
+        ))?;
+        let time_scale = NonZero::new(bit_reader.read_u32::<BigEndian>()?)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "vps_time_scale must not be zero"))?;
+
+        let mut num_ticks_poc_diff_one_minus1 = None;
+        let poc_proportional_to_timing_flag = bit_reader.read_bit()?;
+        if poc_proportional_to_timing_flag {
+            let vps_num_ticks_poc_diff_one_minus1 = bit_reader.read_exp_golomb()?;
+            range_check!(vps_num_ticks_poc_diff_one_minus1, 0, 2u64.pow(32) - 2)?;
+            num_ticks_poc_diff_one_minus1 = Some(vps_num_ticks_poc_diff_one_minus1 as u32);
+        }
+
+        let vps_num_hrd_parameters = bit_reader.read_exp_golomb()?;
+        range_check!(vps_num_hrd_parameters, 0, vps_num_layer_sets_minus1 as u64 + 1)?;
+
+        let mut hrd_parameters = Vec::with_capacity(vps_num_hrd_parameters as usize);
+        for i in 0..vps_num_hrd_parameters {
+            let hrd_layer_set_idx = bit_reader.read_exp_golomb()?;
+            range_check!(
+                hrd_layer_set_idx,
+                if vps_base_layer_internal_flag { 0 } else { 1 },
+                vps_num_layer_sets_minus1 as u64
+            )?;
+
+            // cprms_present_flag[0] is inferred to be 1
+            let cprms_present_flag = i == 0 || bit_reader.read_bit()?;
+
+            hrd_parameters.push(VpsHrdParameters {
+                hrd_layer_set_idx: hrd_layer_set_idx as u16,
+                cprms_present_flag,
+                hrd_parameters: HrdParameters::parse(bit_reader, cprms_present_flag, vps_max_sub_layers_minus1)?,
+            });
+        }
+
+        Ok(Self {
+            num_units_in_tick,
+            time_scale,
+            poc_proportional_to_timing_flag,
+            num_ticks_poc_diff_one_minus1,
+            hrd_parameters,
+        })
+    }
+}
+
+/// The HRD parameters of a layer set in the VPS.
+///
+/// Directly part of [`VpsTimingInfo`].
+///
+/// ISO/IEC 23008-2 - 7.4.3.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct VpsHrdParameters {
+    /// Specifies the index, into the list of layer sets specified by the VPS,
+    /// of the layer set to which the HRD parameters apply.
+    pub hrd_layer_set_idx: u16,
+    /// Equal to `true` specifies that the HRD parameters that are common for all sub-layers are present.
+    ///
+    /// Always `true` for the first HRD parameters.
+    pub cprms_present_flag: bool,
+    /// The [`HrdParameters`] structure.
+    pub hrd_parameters: HrdParameters,
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::io;
+
+    use scuffle_bytes_util::BitWriter;
+    use scuffle_expgolomb::BitWriterExpGolombExt;
+
+    use crate::VpsNALUnit;
+
+    #[test]
+    fn test_vps_parse() {
+        let data = b"@\x01\x0c\x01\xff\xff\x01@\0\0\x03\0\x90\0\0\x03\0\0\x03\0\x99\x95@\x90";
+
+        let nalu = VpsNALUnit::parse(io::Cursor::new(data)).unwrap();
+        let vps = &nalu.rbsp;
+
+        assert_eq!(vps.vps_video_parameter_set_id, 0);
+        assert!(vps.vps_base_layer_internal_flag);
+        assert!(vps.vps_base_layer_available_flag);
+        assert_eq!(vps.vps_max_layers_minus1, 0);
+        assert_eq!(vps.vps_max_sub_layers_minus1, 0);
+        assert!(vps.vps_temporal_id_nesting_flag);
+        assert_eq!(vps.profile_tier_level.general_profile.level_idc, Some(153));
+        assert_eq!(vps.vps_max_layer_id, 0);
+        assert_eq!(vps.vps_num_layer_sets_minus1, 0);
+        assert_eq!(vps.layer_set_layer_ids(0), Some(vec![0]));
+        assert_eq!(vps.layer_set_layer_ids(1), None);
+        assert!(vps.vps_timing_info.is_none());
+        assert!(!vps.vps_extension_flag);
+
+        insta::assert_debug_snapshot!(nalu);
+    }
+
+    #[test]
+    fn test_vps_parse_layer_sets_and_timing_info() {
+        let mut data = b"\x40\x01".to_vec();
+        let mut writer = BitWriter::new(&mut data);
+
+        writer.write_bits(0, 4).unwrap(); // vps_video_parameter_set_id
+        writer.write_bit(true).unwrap(); // vps_base_layer_internal_flag
+        writer.write_bit(true).unwrap(); // vps_base_layer_available_flag
+        writer.write_bits(0, 6).unwrap(); // vps_max_layers_minus1
+        writer.write_bits(0, 3).unwrap(); // vps_max_sub_layers_minus1
+        writer.write_bit(true).unwrap(); // vps_temporal_id_nesting_flag
+        writer.write_bits(0xffff, 16).unwrap(); // vps_reserved_0xffff_16bits
+        for byte in b"\x01\x60\0\0\0\x90\0\0\0\0\0\x99" {
+            writer.write_bits(*byte as u64, 8).unwrap(); // profile_tier_level
+        }
+        writer.write_bit(true).unwrap(); // vps_sub_layer_ordering_info_present_flag
+        writer.write_exp_golomb(4).unwrap(); // vps_max_dec_pic_buffering_minus1[0]
+        writer.write_exp_golomb(2).unwrap(); // vps_max_num_reorder_pics[0]
+        writer.write_exp_golomb(0).unwrap(); // vps_max_latency_increase_plus1[0]
+        writer.write_bits(1, 6).unwrap(); // vps_max_layer_id
+        writer.write_exp_golomb(1).unwrap(); // vps_num_layer_sets_minus1
+        writer.write_bit(false).unwrap(); // layer_id_included_flag[1][0]
+        writer.write_bit(true).unwrap(); // layer_id_included_flag[1][1]
+        writer.write_bit(true).unwrap(); // vps_timing_info_present_flag
+        writer.write_bits(1001, 32).unwrap(); // vps_num_units_in_tick
+        writer.write_bits(60000, 32).unwrap(); // vps_time_scale
+        writer.write_bit(true).unwrap(); // vps_poc_proportional_to_timing_flag
+        writer.write_exp_golomb(0).unwrap(); // vps_num_ticks_poc_diff_one_minus1
+        writer.write_exp_golomb(1).unwrap(); // vps_num_hrd_parameters
+        writer.write_exp_golomb(1).unwrap(); // hrd_layer_set_idx[0]
+        writer.write_bit(false).unwrap(); // nal_hrd_parameters_present_flag
+        writer.write_bit(false).unwrap(); // vcl_hrd_parameters_present_flag
+        writer.write_bit(true).unwrap(); // fixed_pic_rate_general_flag[0]
+        writer.write_exp_golomb(0).unwrap(); // elemental_duration_in_tc_minus1[0]
+        writer.write_exp_golomb(0).unwrap(); // cpb_cnt_minus1[0]
+        writer.write_bit(false).unwrap(); // vps_extension_flag
+        writer.write_bit(true).unwrap(); // rbsp_stop_one_bit
+        writer.finish().unwrap();
+
+        let nalu = VpsNALUnit::parse(io::Cursor::new(data)).unwrap();
+        let vps = &nalu.rbsp;
+
+        assert_eq!(vps.sub_layer_ordering_info.sps_max_dec_pic_buffering_minus1, vec![4]);
+        assert_eq!(vps.sub_layer_ordering_info.sps_max_num_reorder_pics, vec![2]);
+        assert_eq!(vps.vps_max_layer_id, 1);
+        assert_eq!(vps.vps_num_layer_sets_minus1, 1);
+        assert_eq!(vps.layer_set_layer_ids(0), Some(vec![0]));
+        assert_eq!(vps.layer_set_layer_ids(1), Some(vec![1]));
+
+        let timing_info = vps.vps_timing_info.as_ref().unwrap();
+        assert_eq!(timing_info.num_units_in_tick.get(), 1001);
+        assert_eq!(timing_info.time_scale.get(), 60000);
+        assert_eq!(timing_info.num_ticks_poc_diff_one_minus1, Some(0));
+        assert_eq!(timing_info.hrd_parameters.len(), 1);
+        assert_eq!(timing_info.hrd_parameters[0].hrd_layer_set_idx, 1);
+        assert!(timing_info.hrd_parameters[0].cprms_present_flag);
+    }
+
+    #[test]
+    fn test_vps_parse_not_vps() {
+        let data = b"\x44\x01\xc1\x72\xb4\x22\x40";
+
+        let err = VpsNALUnit::parse(io::Cursor::new(data)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
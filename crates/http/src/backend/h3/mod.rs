@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use body::QuicIncomingBody;
 use scuffle_context::ContextFutExt;
@@ -10,6 +11,7 @@ use scuffle_context::ContextFutExt;
 use tracing::Instrument;
 use utils::copy_response_body;
 
+use crate::connection::{ConnectionInfo, TlsVersion};
 use crate::error::HttpError;
 use crate::service::{HttpService, HttpServiceFactory};
 
@@ -36,11 +38,29 @@ pub struct Http3Backend<F> {
     /// Use `[::]` for a dual-stack listener.
     /// For example, use `[::]:80` to bind to port 80 on both IPv4 and IPv6.
     bind: SocketAddr,
+    /// Bind the listening sockets with `SO_REUSEPORT`.
+    ///
+    /// This allows multiple processes to listen on the same address at the same time,
+    /// for example the old and the new process during a rolling restart.
+    /// Only supported on unix platforms.
+    #[builder(default = false)]
+    reuse_port: bool,
+    /// Use listening sockets passed in by the service manager instead of binding new ones.
+    ///
+    /// Sockets are passed via the `LISTEN_FDS` protocol used by systemd socket activation.
+    /// An inherited socket is used if it is bound to the same address as `bind`,
+    /// otherwise a new socket is bound as usual.
+    #[builder(default = false)]
+    inherit_listeners: bool,
     /// rustls config.
     ///
     /// Use this field to set the server into TLS mode.
     /// It will only accept TLS connections when this is set.
     rustls_config: rustls::ServerConfig,
+    /// Close QUIC connections which have not received any packets for this long.
+    ///
+    /// Defaults to quinn's idle timeout.
+    idle_timeout: Option<Duration>,
 }
 
 impl<F> Http3Backend<F>
@@ -64,10 +84,25 @@ where
         // not quite sure why this is necessary but it is
         self.rustls_config.max_early_data_size = u32::MAX;
         let crypto = h3_quinn::quinn::crypto::rustls::QuicServerConfig::try_from(self.rustls_config)?;
-        let server_config = h3_quinn::quinn::ServerConfig::with_crypto(Arc::new(crypto));
+        let mut server_config = h3_quinn::quinn::ServerConfig::with_crypto(Arc::new(crypto));
+
+        if let Some(idle_timeout) = self.idle_timeout {
+            // Timeouts above the maximum of ~146 million years are clamped
+            let idle_timeout =
+                h3_quinn::quinn::IdleTimeout::try_from(idle_timeout).unwrap_or_else(|_| h3_quinn::quinn::VarInt::MAX.into());
+            let mut transport_config = h3_quinn::quinn::TransportConfig::default();
+            transport_config.max_idle_timeout(Some(idle_timeout));
+            server_config.transport_config(Arc::new(transport_config));
+        }
 
         // Bind the UDP socket
-        let socket = std::net::UdpSocket::bind(self.bind)?;
+        let socket = crate::listener::udp_socket(
+            self.bind,
+            crate::listener::ListenerOptions {
+                reuse_port: self.reuse_port,
+                inherit_listeners: self.inherit_listeners,
+            },
+        )?;
 
         // Runtime for the quinn endpoint
         let runtime = h3_quinn::quinn::default_runtime().ok_or_else(|| io::Error::other("no async runtime found"))?;
@@ -90,6 +125,8 @@ where
                     runtime,
                 )?;
 
+                let endpoint_addr = endpoint.local_addr().unwrap_or(self.bind);
+
                 #[cfg(feature = "tracing")]
                 tracing::trace!("waiting for connections");
 
@@ -105,6 +142,23 @@ where
                                 return Ok(());
                             };
                             let addr = conn.remote_address();
+                            let connection_info = {
+                                let local_addr =
+                                    SocketAddr::new(conn.local_ip().unwrap_or(endpoint_addr.ip()), endpoint_addr.port());
+                                let alpn_protocol = conn
+                                    .handshake_data()
+                                    .and_then(|data| data.downcast::<h3_quinn::quinn::crypto::rustls::HandshakeData>().ok())
+                                    .and_then(|data| data.protocol);
+                                // QUIC always uses TLS 1.3
+                                ConnectionInfo::new(addr, local_addr, http::Version::HTTP_3)
+                                    .with_tls(alpn_protocol.as_deref(), Some(TlsVersion::Tls13))
+                            };
+                            let peer_certificates = conn
+                                .peer_identity()
+                                .and_then(|identity| {
+                                    identity.downcast::<Vec<rustls::pki_types::CertificateDer<'static>>>().ok()
+                                })
+                                .and_then(|chain| crate::tls::PeerCertificates::new(*chain));
 
                             #[cfg(feature = "tracing")]
                             tracing::debug!(addr = %addr, "accepted quic connection");
@@ -148,16 +202,21 @@ where
                                                 .get(http::header::CONTENT_LENGTH)
                                                 .and_then(|len| len.to_str().ok().and_then(|x| x.parse().ok()));
                                             let body = QuicIncomingBody::new(recv, size_hint);
-                                            let req = req.map(|_| crate::body::IncomingBody::from(body));
+                                            let mut req = req.map(|_| crate::body::IncomingBody::from(body));
+                                            req.extensions_mut().insert(connection_info.clone());
+                                            if let Some(peer_certificates) = &peer_certificates {
+                                                req.extensions_mut().insert(peer_certificates.clone());
+                                            }
 
                                             let ctx = ctx.clone();
                                             let mut http_service = http_service.clone();
                                             tokio::spawn(async move {
                                                 let _res: Result<_, HttpError<F>> = async move {
-                                                    let resp = http_service
-                                                        .call(req)
-                                                        .await
-                                                        .map_err(|e| HttpError::ServiceError(e))?;
+                                                    #[cfg(feature = "tracing")]
+                                                    let resp = crate::trace::call(&mut http_service, req).await;
+                                                    #[cfg(not(feature = "tracing"))]
+                                                    let resp = http_service.call(req).await;
+                                                    let resp = resp.map_err(|e| HttpError::ServiceError(e))?;
                                                     let (parts, body) = resp.into_parts();
 
                                                     send.send_response(http::Response::from_parts(parts, ())).await?;
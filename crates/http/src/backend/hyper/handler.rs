@@ -1,18 +1,39 @@
+use std::time::Duration;
+
 use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
 use hyper_util::server::conn::auto;
 use scuffle_context::ContextFutExt;
 use tokio::io::{AsyncRead, AsyncWrite};
 
+use super::idle::{IdleIo, IdleTracker};
 use crate::error::HttpError;
 use crate::service::{HttpService, HttpServiceFactory};
 
+/// Options for serving a connection.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ConnectionOptions {
+    pub(crate) http1: bool,
+    pub(crate) http2: bool,
+    #[cfg(feature = "http1")]
+    pub(crate) http1_keep_alive: bool,
+    #[cfg(feature = "http1")]
+    pub(crate) http1_keep_alive_timeout: Option<Duration>,
+    #[cfg(feature = "http2")]
+    pub(crate) http2_keep_alive_interval: Option<Duration>,
+    #[cfg(feature = "http2")]
+    pub(crate) http2_keep_alive_timeout: Option<Duration>,
+    pub(crate) idle_timeout: Option<Duration>,
+}
+
 /// Helper function used by hyper server to handle incoming connections.
+///
+/// The given `extensions` are added to every request on this connection.
 pub(crate) async fn handle_connection<F, S, I>(
     ctx: scuffle_context::Context,
     service: S,
     io: I,
-    http1: bool,
-    http2: bool,
+    extensions: http::Extensions,
+    options: ConnectionOptions,
 ) -> Result<(), HttpError<F>>
 where
     F: HttpServiceFactory<Service = S>,
@@ -24,61 +45,102 @@ where
     <S::ResBody as http_body::Body>::Error: std::error::Error + Send + Sync,
     I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    let io = TokioIo::new(io);
+    let idle_tracker = options.idle_timeout.map(IdleTracker::new);
+    let io = TokioIo::new(IdleIo::new(io, idle_tracker.clone()));
 
+    let request_tracker = idle_tracker.clone();
     let hyper_proxy_service = hyper::service::service_fn(move |req: http::Request<hyper::body::Incoming>| {
         let mut service = service.clone();
+        let extensions = extensions.clone();
+        let request_guard = request_tracker.as_ref().map(IdleTracker::request);
         async move {
-            let (parts, body) = req.into_parts();
+            let (mut parts, body) = req.into_parts();
+            parts.extensions.extend(extensions);
+            // The auto builder only knows the HTTP version once the request was parsed.
+            if let Some(info) = parts.extensions.get_mut::<crate::connection::ConnectionInfo>() {
+                info.set_http_version(parts.version);
+            }
             let body = crate::body::IncomingBody::from(body);
             let req = http::Request::from_parts(parts, body);
-            service.call(req).await
+            #[cfg(feature = "tracing")]
+            let res = crate::trace::call(&mut service, req).await;
+            #[cfg(not(feature = "tracing"))]
+            let res = service.call(req).await;
+            drop(request_guard);
+            res
         }
     });
 
     let mut builder = auto::Builder::new(TokioExecutor::new());
 
-    if http1 && http2 {
-        #[cfg(feature = "http1")]
-        builder.http1().timer(TokioTimer::new());
+    #[cfg(feature = "http1")]
+    {
+        let mut http1 = builder.http1();
+        http1.timer(TokioTimer::new()).keep_alive(options.http1_keep_alive);
 
-        #[cfg(feature = "http2")]
-        builder.http2().timer(TokioTimer::new());
+        if let Some(keep_alive_timeout) = options.http1_keep_alive_timeout {
+            http1.header_read_timeout(keep_alive_timeout);
+        }
+    }
+
+    #[cfg(feature = "http2")]
+    {
+        let mut http2 = builder.http2();
+        http2
+            .timer(TokioTimer::new())
+            .keep_alive_interval(options.http2_keep_alive_interval);
+
+        if let Some(keep_alive_timeout) = options.http2_keep_alive_timeout {
+            http2.keep_alive_timeout(keep_alive_timeout);
+        }
+    }
 
+    let builder = if options.http1 && options.http2 {
         builder
-            .serve_connection_with_upgrades(io, hyper_proxy_service)
-            .with_context(ctx)
-            .await
-            .transpose()
-            .map_err(HttpError::HyperConnection)?;
-    } else if http1 {
+    } else if options.http1 {
         #[cfg(not(feature = "http1"))]
         unreachable!("http1 enabled but http1 feature disabled");
 
         #[cfg(feature = "http1")]
-        builder
-            .http1_only()
-            .serve_connection_with_upgrades(io, hyper_proxy_service)
-            .with_context(ctx)
-            .await
-            .transpose()
-            .map_err(HttpError::HyperConnection)?;
-    } else if http2 {
+        builder.http1_only()
+    } else if options.http2 {
         #[cfg(not(feature = "http2"))]
         unreachable!("http2 enabled but http2 feature disabled");
 
         #[cfg(feature = "http2")]
-        builder
-            .http2_only()
-            .serve_connection_with_upgrades(io, hyper_proxy_service)
-            .with_context(ctx)
-            .await
-            .transpose()
-            .map_err(HttpError::HyperConnection)?;
+        builder.http2_only()
     } else {
         #[cfg(feature = "tracing")]
         tracing::warn!("both http1 and http2 are disabled, closing connection");
+
+        return Ok(());
+    };
+
+    let conn = builder.serve_connection_with_upgrades(io, hyper_proxy_service);
+    let mut conn = std::pin::pin!(conn);
+
+    let idle = std::pin::pin!(async {
+        match &idle_tracker {
+            Some(idle_tracker) => idle_tracker.idle().await,
+            None => std::future::pending().await,
+        }
+    });
+
+    async {
+        if let futures::future::Either::Left((res, _)) = futures::future::select(conn.as_mut(), idle).await {
+            return res;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("closing idle connection");
+
+        conn.as_mut().graceful_shutdown();
+        conn.await
     }
+    .with_context(ctx)
+    .await
+    .transpose()
+    .map_err(HttpError::HyperConnection)?;
 
     Ok(())
 }
@@ -0,0 +1,173 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::Instant;
+
+/// Tracks the activity of a connection to close it once it has been idle for too long.
+///
+/// A connection is idle when no data was read or written and no request was in flight
+/// for the configured timeout.
+#[derive(Debug, Clone)]
+pub(crate) struct IdleTracker {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    timeout: Duration,
+    start: Instant,
+    /// Milliseconds since `start` of the last activity.
+    last_activity: AtomicU64,
+    active_requests: AtomicUsize,
+}
+
+impl IdleTracker {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                timeout,
+                start: Instant::now(),
+                last_activity: AtomicU64::new(0),
+                active_requests: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Record activity on the connection.
+    pub(crate) fn touch(&self) {
+        let elapsed = self.inner.start.elapsed().as_millis() as u64;
+        self.inner.last_activity.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    /// Mark a request as in flight until the returned guard is dropped.
+    pub(crate) fn request(&self) -> RequestGuard {
+        self.inner.active_requests.fetch_add(1, Ordering::Relaxed);
+        self.touch();
+        RequestGuard(self.clone())
+    }
+
+    /// Resolves once the connection has been idle for the timeout.
+    pub(crate) async fn idle(&self) {
+        loop {
+            let last_activity = Duration::from_millis(self.inner.last_activity.load(Ordering::Relaxed));
+            let deadline = self.inner.start + last_activity + self.inner.timeout;
+
+            if deadline > Instant::now() {
+                tokio::time::sleep_until(deadline).await;
+            } else if self.inner.active_requests.load(Ordering::Relaxed) == 0 {
+                return;
+            } else {
+                // A request is in flight, check again after another timeout.
+                tokio::time::sleep(self.inner.timeout).await;
+            }
+        }
+    }
+}
+
+/// Keeps a request in flight, see [`IdleTracker::request`].
+pub(crate) struct RequestGuard(IdleTracker);
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.0.touch();
+        self.0.inner.active_requests.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// An IO wrapper recording every successful read and write on an [`IdleTracker`].
+    pub(crate) struct IdleIo<I> {
+        #[pin]
+        io: I,
+        tracker: Option<IdleTracker>,
+    }
+}
+
+impl<I> IdleIo<I> {
+    pub(crate) fn new(io: I, tracker: Option<IdleTracker>) -> Self {
+        Self { io, tracker }
+    }
+}
+
+impl<I: AsyncRead> AsyncRead for IdleIo<I> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.project();
+        let filled = buf.filled().len();
+        let res = this.io.poll_read(cx, buf);
+
+        if let (Some(tracker), true) = (this.tracker, buf.filled().len() > filled) {
+            tracker.touch();
+        }
+
+        res
+    }
+}
+
+impl<I: AsyncWrite> AsyncWrite for IdleIo<I> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let this = self.project();
+        let res = this.io.poll_write(cx, buf);
+
+        if let (Some(tracker), Poll::Ready(Ok(1..))) = (this.tracker, &res) {
+            tracker.touch();
+        }
+
+        res
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.project();
+        let res = this.io.poll_write_vectored(cx, bufs);
+
+        if let (Some(tracker), Poll::Ready(Ok(1..))) = (this.tracker, &res) {
+            tracker.touch();
+        }
+
+        res
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.project().io.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.project().io.poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::time::Duration;
+
+    use scuffle_future_ext::FutureExt;
+
+    use super::IdleTracker;
+
+    #[tokio::test(start_paused = true)]
+    async fn idle() {
+        let tracker = IdleTracker::new(Duration::from_secs(10));
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        tracker.touch();
+        assert!(tracker.idle().with_timeout(Duration::from_secs(9)).await.is_err());
+        assert!(tracker.idle().with_timeout(Duration::from_secs(2)).await.is_ok());
+
+        let request = tracker.request();
+        assert!(tracker.idle().with_timeout(Duration::from_secs(60)).await.is_err());
+        drop(request);
+        assert!(tracker.idle().with_timeout(Duration::from_secs(11)).await.is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 //! Hyper backend.
 use std::fmt::Debug;
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use scuffle_context::ContextFutExt;
 #[cfg(feature = "tracing")]
@@ -10,6 +11,7 @@ use crate::error::HttpError;
 use crate::service::{HttpService, HttpServiceFactory};
 
 mod handler;
+mod idle;
 mod stream;
 mod utils;
 
@@ -33,6 +35,20 @@ pub struct HyperBackend<F> {
     /// Use `[::]` for a dual-stack listener.
     /// For example, use `[::]:80` to bind to port 80 on both IPv4 and IPv6.
     bind: SocketAddr,
+    /// Bind the listening sockets with `SO_REUSEPORT`.
+    ///
+    /// This allows multiple processes to listen on the same address at the same time,
+    /// for example the old and the new process during a rolling restart.
+    /// Only supported on unix platforms.
+    #[builder(default = false)]
+    reuse_port: bool,
+    /// Use listening sockets passed in by the service manager instead of binding new ones.
+    ///
+    /// Sockets are passed via the `LISTEN_FDS` protocol used by systemd socket activation.
+    /// An inherited socket is used if it is bound to the same address as `bind`,
+    /// otherwise a new socket is bound as usual.
+    #[builder(default = false)]
+    inherit_listeners: bool,
     /// rustls config.
     ///
     /// Use this field to set the server into TLS mode.
@@ -47,6 +63,29 @@ pub struct HyperBackend<F> {
     #[cfg(feature = "http2")]
     #[builder(default = true)]
     http2_enabled: bool,
+    /// Keep HTTP/1.1 connections open for further requests.
+    #[cfg(feature = "http1")]
+    #[builder(default = true)]
+    http1_keep_alive: bool,
+    /// How long to wait for the headers of the next request on a HTTP/1.1 connection.
+    ///
+    /// Idle keep-alive connections are closed after this timeout. Defaults to hyper's header read timeout.
+    #[cfg(feature = "http1")]
+    http1_keep_alive_timeout: Option<Duration>,
+    /// Interval of the HTTP/2 ping frames sent to check that the connection is still alive.
+    ///
+    /// Pings are disabled by default.
+    #[cfg(feature = "http2")]
+    http2_keep_alive_interval: Option<Duration>,
+    /// How long to wait for the acknowledgement of a HTTP/2 ping before closing the connection.
+    ///
+    /// Defaults to hyper's keep-alive timeout.
+    #[cfg(feature = "http2")]
+    http2_keep_alive_timeout: Option<Duration>,
+    /// Close connections which have not read or written any data and have no request in flight for this long.
+    ///
+    /// Disabled by default.
+    idle_timeout: Option<Duration>,
 }
 
 impl<F> HyperBackend<F>
@@ -76,7 +115,13 @@ where
         }
 
         // We have to create an std listener first because the tokio listener isn't clonable
-        let listener = tokio::net::TcpListener::bind(self.bind).await?.into_std()?;
+        let listener = crate::listener::tcp_listener(
+            self.bind,
+            crate::listener::ListenerOptions {
+                reuse_port: self.reuse_port,
+                inherit_listeners: self.inherit_listeners,
+            },
+        )?;
 
         #[cfg(feature = "tls-rustls")]
         let tls_acceptor = self
@@ -100,8 +145,11 @@ where
                         #[cfg(feature = "tracing")]
                         tracing::trace!("waiting for connections");
 
-                        let (mut stream, addr) = match listener.accept().with_context(ctx.clone()).await {
-                            Some(Ok((tcp_stream, addr))) => (stream::Stream::Tcp(tcp_stream), addr),
+                        let (mut stream, addr, local_addr) = match listener.accept().with_context(ctx.clone()).await {
+                            Some(Ok((tcp_stream, addr))) => {
+                                let local_addr = tcp_stream.local_addr().unwrap_or(self.bind);
+                                (stream::Stream::Tcp(tcp_stream), addr, local_addr)
+                            }
                             Some(Err(e)) if utils::is_fatal_tcp_error(&e) => {
                                 #[cfg(feature = "tracing")]
                                 tracing::error!(err = %e, "failed to accept tcp connection");
@@ -161,17 +209,35 @@ where
                             #[cfg(feature = "tracing")]
                             tracing::trace!("handling connection");
 
-                            #[cfg(feature = "http1")]
-                            let http1 = self.http1_enabled;
-                            #[cfg(not(feature = "http1"))]
-                            let http1 = false;
+                            let options = handler::ConnectionOptions {
+                                #[cfg(feature = "http1")]
+                                http1: self.http1_enabled,
+                                #[cfg(not(feature = "http1"))]
+                                http1: false,
+                                #[cfg(feature = "http2")]
+                                http2: self.http2_enabled,
+                                #[cfg(not(feature = "http2"))]
+                                http2: false,
+                                #[cfg(feature = "http1")]
+                                http1_keep_alive: self.http1_keep_alive,
+                                #[cfg(feature = "http1")]
+                                http1_keep_alive_timeout: self.http1_keep_alive_timeout,
+                                #[cfg(feature = "http2")]
+                                http2_keep_alive_interval: self.http2_keep_alive_interval,
+                                #[cfg(feature = "http2")]
+                                http2_keep_alive_timeout: self.http2_keep_alive_timeout,
+                                idle_timeout: self.idle_timeout,
+                            };
 
-                            #[cfg(feature = "http2")]
-                            let http2 = self.http2_enabled;
-                            #[cfg(not(feature = "http2"))]
-                            let http2 = false;
+                            let mut extensions = http::Extensions::new();
+                            extensions.insert(stream.connection_info(addr, local_addr));
+                            #[cfg(feature = "tls-rustls")]
+                            if let Some(peer_certificates) = stream.peer_certificates() {
+                                extensions.insert(peer_certificates);
+                            }
 
-                            let _res = handler::handle_connection::<F, _, _>(ctx, http_service, stream, http1, http2).await;
+                            let _res =
+                                handler::handle_connection::<F, _, _>(ctx, http_service, stream, extensions, options).await;
 
                             #[cfg(feature = "tracing")]
                             if let Err(e) = _res {
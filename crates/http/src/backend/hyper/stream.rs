@@ -1,5 +1,9 @@
+use std::net::SocketAddr;
+
 use tokio::io::{AsyncRead, AsyncWrite};
 
+use crate::connection::ConnectionInfo;
+
 /// A stream that can be either a TCP stream or a TLS stream.
 ///
 /// Implements [`AsyncRead`] and [`AsyncWrite`] by delegating to the inner stream.
@@ -23,6 +27,32 @@ impl Stream {
             Stream::Tls(_) => Ok(self),
         }
     }
+
+    /// Information about this connection, the HTTP version is set per request.
+    pub(crate) fn connection_info(&self, peer_addr: SocketAddr, local_addr: SocketAddr) -> ConnectionInfo {
+        let info = ConnectionInfo::new(peer_addr, local_addr, http::Version::HTTP_11);
+
+        match self {
+            Stream::Tcp(_) => info,
+            #[cfg(feature = "tls-rustls")]
+            Stream::Tls(stream) => {
+                let conn = stream.get_ref().1;
+                info.with_tls(
+                    conn.alpn_protocol(),
+                    conn.protocol_version().and_then(crate::connection::TlsVersion::from_rustls),
+                )
+            }
+        }
+    }
+
+    /// The certificate chain the client presented during the TLS handshake, if any.
+    #[cfg(feature = "tls-rustls")]
+    pub(crate) fn peer_certificates(&self) -> Option<crate::tls::PeerCertificates> {
+        match self {
+            Stream::Tcp(_) => None,
+            Stream::Tls(stream) => crate::tls::PeerCertificates::new(stream.get_ref().1.peer_certificates()?.to_vec()),
+        }
+    }
 }
 
 impl AsyncRead for Stream {
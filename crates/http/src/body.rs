@@ -1,8 +1,12 @@
 //! Types for working with HTTP bodies.
 
 use std::fmt::Debug;
+use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU64, AtomicUsize, Ordering};
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use bytes::{Buf, Bytes};
 use http_body::Frame;
@@ -18,6 +22,12 @@ pub enum IncomingBodyError {
     #[error("h3 body error: {0}")]
     #[cfg(feature = "http3")]
     H3(#[from] crate::backend::h3::body::H3BodyError),
+    /// The body exceeded its size limit.
+    #[error("{0}")]
+    LengthLimitExceeded(#[from] LengthLimitError),
+    /// The body was not received within its time limit.
+    #[error("{0}")]
+    Timeout(#[from] BodyTimeoutError),
 }
 
 /// The body of an incoming request.
@@ -31,6 +41,89 @@ pub enum IncomingBody {
     /// The body of an incoming h3 request.
     #[cfg(feature = "http3")]
     Quic(crate::backend::h3::body::QuicIncomingBody<h3_quinn::RecvStream>),
+    /// An incoming body with a size limit.
+    ///
+    /// Create by calling [`IncomingBody::with_limit`].
+    Limited(Box<TrackedBody<IncomingBody, BodyLimit>>),
+    /// An incoming body with a time limit.
+    ///
+    /// Create by calling [`IncomingBody::with_timeout`].
+    Timeout(Box<TimeoutBody>),
+    /// An incoming body whose beginning was already read into memory by a service.
+    Buffered(Box<BufferedBody>),
+}
+
+impl IncomingBody {
+    /// Limit the number of bytes that can be read from this body.
+    ///
+    /// Reading more than `limit` bytes results in an [`IncomingBodyError::LengthLimitExceeded`] error.
+    /// If the body is already limited, the new limit replaces the old one.
+    pub fn with_limit(self, limit: usize) -> Self {
+        #[cfg_attr(
+            not(any(feature = "http1", feature = "http2", feature = "http3")),
+            allow(unreachable_patterns)
+        )]
+        match self {
+            IncomingBody::Limited(body) => {
+                let TrackedBody { body, tracker } = *body;
+                IncomingBody::Limited(Box::new(TrackedBody::new(body, tracker.with_limit(limit))))
+            }
+            IncomingBody::Timeout(mut body) => {
+                body.body = body.body.with_limit(limit);
+                IncomingBody::Timeout(body)
+            }
+            body => IncomingBody::Limited(Box::new(TrackedBody::new(body, BodyLimit::new(limit)))),
+        }
+    }
+
+    /// Limit the time it may take to receive this body, starting now.
+    ///
+    /// Once the timeout elapsed, reading the body results in an [`IncomingBodyError::Timeout`] error.
+    /// If the body already has a timeout, the new timeout replaces the old one.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        #[cfg_attr(
+            not(any(feature = "http1", feature = "http2", feature = "http3")),
+            allow(unreachable_patterns)
+        )]
+        match self {
+            IncomingBody::Timeout(mut body) => {
+                body.timeout = timeout;
+                body.sleep.as_mut().reset(tokio::time::Instant::now() + timeout);
+                IncomingBody::Timeout(body)
+            }
+            IncomingBody::Limited(body) => {
+                let TrackedBody { body, tracker } = *body;
+                IncomingBody::Limited(Box::new(TrackedBody::new(body.with_timeout(timeout), tracker)))
+            }
+            body => IncomingBody::Timeout(Box::new(TimeoutBody {
+                body,
+                timeout,
+                sleep: Box::pin(tokio::time::sleep(timeout)),
+                report: None,
+            })),
+        }
+    }
+
+    /// Report all limits of this body that are exceeded to `report`.
+    pub(crate) fn report_limits(self, report: &LimitReport) -> Self {
+        #[cfg_attr(
+            not(any(feature = "http1", feature = "http2", feature = "http3")),
+            allow(unreachable_patterns)
+        )]
+        match self {
+            IncomingBody::Limited(body) => {
+                let TrackedBody { body, mut tracker } = *body;
+                tracker.report = Some(report.clone());
+                IncomingBody::Limited(Box::new(TrackedBody::new(body.report_limits(report), tracker)))
+            }
+            IncomingBody::Timeout(mut body) => {
+                body.report = Some(report.clone());
+                body.body = body.body.report_limits(report);
+                IncomingBody::Timeout(body)
+            }
+            body => body,
+        }
+    }
 }
 
 #[cfg(any(feature = "http1", feature = "http2"))]
@@ -57,8 +150,9 @@ impl http_body::Body for IncomingBody {
             IncomingBody::Hyper(body) => body.is_end_stream(),
             #[cfg(feature = "http3")]
             IncomingBody::Quic(body) => body.is_end_stream(),
-            #[cfg(not(any(feature = "http1", feature = "http2", feature = "http3")))]
-            _ => false,
+            IncomingBody::Limited(body) => body.is_end_stream(),
+            IncomingBody::Timeout(body) => body.body.is_end_stream(),
+            IncomingBody::Buffered(body) => body.is_end_stream(),
         }
     }
 
@@ -71,8 +165,12 @@ impl http_body::Body for IncomingBody {
             IncomingBody::Hyper(body) => std::pin::Pin::new(body).poll_frame(_cx).map_err(Into::into),
             #[cfg(feature = "http3")]
             IncomingBody::Quic(body) => std::pin::Pin::new(body).poll_frame(_cx).map_err(Into::into),
-            #[cfg(not(any(feature = "http1", feature = "http2", feature = "http3")))]
-            _ => std::task::Poll::Ready(None),
+            IncomingBody::Limited(body) => std::pin::Pin::new(body.as_mut()).poll_frame(_cx).map_err(|err| match err {
+                TrackedBodyError::Body(err) => err,
+                TrackedBodyError::Tracker(err) => err.into(),
+            }),
+            IncomingBody::Timeout(body) => body.poll_frame(_cx),
+            IncomingBody::Buffered(body) => body.poll_frame(_cx),
         }
     }
 
@@ -82,8 +180,157 @@ impl http_body::Body for IncomingBody {
             IncomingBody::Hyper(body) => body.size_hint(),
             #[cfg(feature = "http3")]
             IncomingBody::Quic(body) => body.size_hint(),
-            #[cfg(not(any(feature = "http1", feature = "http2", feature = "http3")))]
-            _ => http_body::SizeHint::default(),
+            IncomingBody::Limited(body) => body.size_hint(),
+            IncomingBody::Timeout(body) => body.body.size_hint(),
+            IncomingBody::Buffered(body) => body.size_hint(),
+        }
+    }
+}
+
+/// The error returned by a [`TimeoutBody`] when the body was not received in time.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("body timed out after {timeout:?}")]
+pub struct BodyTimeoutError {
+    /// The timeout that elapsed.
+    pub timeout: Duration,
+}
+
+/// An incoming body that fails once its timeout elapsed.
+///
+/// Create by calling [`IncomingBody::with_timeout`].
+pub struct TimeoutBody {
+    body: IncomingBody,
+    timeout: Duration,
+    sleep: Pin<Box<tokio::time::Sleep>>,
+    report: Option<LimitReport>,
+}
+
+impl TimeoutBody {
+    fn poll_frame(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, IncomingBodyError>>> {
+        // Data that is already available is still returned after the timeout elapsed.
+        if let Poll::Ready(frame) = http_body::Body::poll_frame(Pin::new(&mut self.body), cx) {
+            return Poll::Ready(frame);
+        }
+
+        match self.sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                if let Some(report) = &self.report {
+                    report.exceeded(LimitExceeded::Timeout);
+                }
+
+                Poll::Ready(Some(Err(BodyTimeoutError { timeout: self.timeout }.into())))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// An incoming body whose beginning was already read into memory.
+///
+/// Returns the data that was read, followed by the rest of the original body, so services can look
+/// at the request body and still pass it on unchanged.
+pub struct BufferedBody {
+    data: Option<Bytes>,
+    error: Option<IncomingBodyError>,
+    rest: Option<Pin<Box<IncomingBody>>>,
+    trailers: Option<http::HeaderMap>,
+}
+
+impl BufferedBody {
+    /// A body that was read completely.
+    pub(crate) fn complete(data: Bytes, trailers: Option<http::HeaderMap>) -> Self {
+        Self {
+            data: Some(data).filter(|data| !data.is_empty()),
+            error: None,
+            rest: None,
+            trailers,
+        }
+    }
+
+    /// A body of which only `data` was read, followed by `rest`.
+    pub(crate) fn partial(data: Bytes, rest: Pin<Box<IncomingBody>>) -> Self {
+        Self {
+            data: Some(data).filter(|data| !data.is_empty()),
+            error: None,
+            rest: Some(rest),
+            trailers: None,
+        }
+    }
+
+    /// A body that failed with `error` after `data` was read.
+    pub(crate) fn failed(data: Bytes, error: IncomingBodyError) -> Self {
+        Self {
+            data: Some(data).filter(|data| !data.is_empty()),
+            error: Some(error),
+            rest: None,
+            trailers: None,
+        }
+    }
+
+    fn poll_frame(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, IncomingBodyError>>> {
+        if let Some(data) = self.data.take() {
+            return Poll::Ready(Some(Ok(Frame::data(data))));
+        }
+
+        if let Some(error) = self.error.take() {
+            return Poll::Ready(Some(Err(error)));
+        }
+
+        if let Some(rest) = &mut self.rest {
+            return http_body::Body::poll_frame(rest.as_mut(), cx);
+        }
+
+        Poll::Ready(self.trailers.take().map(|trailers| Ok(Frame::trailers(trailers))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none()
+            && self.error.is_none()
+            && self.trailers.is_none()
+            && self
+                .rest
+                .as_ref()
+                .is_none_or(|rest| http_body::Body::is_end_stream(rest.as_ref().get_ref()))
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let len = self.data.as_ref().map_or(0, |data| data.len() as u64);
+        let Some(rest) = &self.rest else {
+            return http_body::SizeHint::with_exact(len);
+        };
+
+        let rest = http_body::Body::size_hint(rest.as_ref().get_ref());
+        let mut hint = http_body::SizeHint::new();
+        hint.set_lower(rest.lower() + len);
+        if let Some(upper) = rest.upper() {
+            hint.set_upper(upper + len);
+        }
+
+        hint
+    }
+}
+
+/// Which limit of a request body was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LimitExceeded {
+    Length = 1,
+    Timeout = 2,
+}
+
+/// Records the first limit a request body exceeded, shared between clones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LimitReport(Arc<AtomicU8>);
+
+impl LimitReport {
+    fn exceeded(&self, limit: LimitExceeded) {
+        let _ = self.0.compare_exchange(0, limit as u8, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    pub(crate) fn get(&self) -> Option<LimitExceeded> {
+        match self.0.load(Ordering::Relaxed) {
+            1 => Some(LimitExceeded::Length),
+            2 => Some(LimitExceeded::Timeout),
+            _ => None,
         }
     }
 }
@@ -141,13 +388,101 @@ pub trait Tracker: Send + Sync + 'static {
 
     /// Called when data is read from the body.
     ///
-    /// The `size` parameter is the size of the data that is remaining to be read from the body.
+    /// The `size` parameter is the size of the data frame that was read from the body.
+    /// Returning an error from this function fails the body with [`TrackedBodyError::Tracker`].
     fn on_data(&self, size: usize) -> Result<(), Self::Error> {
         let _ = size;
         Ok(())
     }
 }
 
+/// The error returned by [`BodyLimit`] when a body exceeds its size limit.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("body length limit exceeded: {limit} bytes")]
+pub struct LengthLimitError {
+    /// The limit that was exceeded.
+    pub limit: usize,
+}
+
+/// A [`Tracker`] that fails the body once more than `limit` bytes have been read.
+#[derive(Debug)]
+pub struct BodyLimit {
+    limit: usize,
+    read: AtomicUsize,
+    report: Option<LimitReport>,
+}
+
+impl BodyLimit {
+    /// Create a new [`BodyLimit`] with the given limit in bytes.
+    pub const fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            read: AtomicUsize::new(0),
+            report: None,
+        }
+    }
+
+    /// Replace the limit, keeping the number of bytes read so far.
+    pub fn with_limit(self, limit: usize) -> Self {
+        Self { limit, ..self }
+    }
+
+    /// The limit in bytes.
+    pub const fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The number of bytes read so far.
+    pub fn read(&self) -> usize {
+        self.read.load(Ordering::Relaxed)
+    }
+}
+
+impl Tracker for BodyLimit {
+    type Error = LengthLimitError;
+
+    fn on_data(&self, size: usize) -> Result<(), Self::Error> {
+        let read = self.read.fetch_add(size, Ordering::Relaxed).saturating_add(size);
+        if read > self.limit {
+            if let Some(report) = &self.report {
+                report.exceeded(LimitExceeded::Length);
+            }
+
+            return Err(LengthLimitError { limit: self.limit });
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`Tracker`] that counts the number of bytes read from a body.
+///
+/// The counter is shared between clones, so a clone can be kept around
+/// (e.g. for access logs or metrics) while the body is being sent.
+#[derive(Debug, Clone, Default)]
+pub struct ByteCounter(Arc<AtomicU64>);
+
+impl ByteCounter {
+    /// Create a new [`ByteCounter`] starting at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of bytes counted so far.
+    pub fn bytes(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Tracker for ByteCounter {
+    type Error = std::convert::Infallible;
+
+    fn on_data(&self, size: usize) -> Result<(), Self::Error> {
+        self.0.fetch_add(size as u64, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
 impl<B, T> http_body::Body for TrackedBody<B, T>
 where
     B: http_body::Body,
@@ -162,12 +497,9 @@ where
         match this.body.poll_frame(cx) {
             Poll::Pending => Poll::Pending,
             Poll::Ready(frame) => {
-                if let Some(Ok(frame)) = &frame {
-                    if let Some(data) = frame.data_ref() {
-                        if let Err(err) = this.tracker.on_data(data.remaining()) {
-                            return Poll::Ready(Some(Err(TrackedBodyError::Tracker(err))));
-                        }
-                    }
+                let data = frame.as_ref().and_then(|frame| frame.as_ref().ok()?.data_ref());
+                if let Some(Err(err)) = data.map(|data| this.tracker.on_data(data.remaining())) {
+                    return Poll::Ready(Some(Err(TrackedBodyError::Tracker(err))));
                 }
 
                 Poll::Ready(frame.transpose().map_err(TrackedBodyError::Body).transpose())
@@ -216,4 +548,132 @@ mod tests {
         let err = TrackedBodyError::<TestBody, TestTracker>::Body(());
         assert_eq!(format!("{err:?}"), "TrackedBodyError::Body(())",);
     }
+
+    #[test]
+    fn body_limit() {
+        use super::{BodyLimit, LengthLimitError, Tracker};
+
+        let limit = BodyLimit::new(10);
+        assert_eq!(limit.limit(), 10);
+        assert!(limit.on_data(6).is_ok());
+        assert!(limit.on_data(4).is_ok());
+        assert_eq!(limit.read(), 10);
+        assert_eq!(limit.on_data(1), Err(LengthLimitError { limit: 10 }));
+
+        let limit = limit.with_limit(20);
+        assert_eq!(limit.read(), 11);
+        assert!(limit.on_data(9).is_ok());
+        assert_eq!(
+            limit.on_data(1).unwrap_err().to_string(),
+            "body length limit exceeded: 20 bytes"
+        );
+    }
+
+    #[test]
+    fn limit_report() {
+        use super::{BodyLimit, LimitExceeded, LimitReport, Tracker};
+
+        let report = LimitReport::default();
+        assert_eq!(report.get(), None);
+
+        let mut limit = BodyLimit::new(1);
+        limit.report = Some(report.clone());
+        assert!(limit.on_data(1).is_ok());
+        assert_eq!(report.get(), None);
+        assert!(limit.on_data(1).is_err());
+        assert_eq!(report.get(), Some(LimitExceeded::Length));
+
+        // The first exceeded limit is kept
+        report.exceeded(LimitExceeded::Timeout);
+        assert_eq!(report.get(), Some(LimitExceeded::Length));
+    }
+
+    #[test]
+    fn byte_counter() {
+        use super::{ByteCounter, Tracker};
+
+        let counter = ByteCounter::new();
+        let clone = counter.clone();
+        counter.on_data(5).unwrap();
+        clone.on_data(3).unwrap();
+        assert_eq!(counter.bytes(), 8);
+        assert_eq!(clone.bytes(), 8);
+    }
+
+    #[tokio::test]
+    async fn tracked_body_limit() {
+        use http_body::Body;
+
+        use super::{BodyLimit, TrackedBody};
+
+        struct TestBody(Vec<&'static [u8]>);
+
+        impl http_body::Body for TestBody {
+            type Data = bytes::Bytes;
+            type Error = Infallible;
+
+            fn poll_frame(
+                mut self: std::pin::Pin<&mut Self>,
+                _cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+                if self.0.is_empty() {
+                    return std::task::Poll::Ready(None);
+                }
+
+                let data = self.0.remove(0);
+                std::task::Poll::Ready(Some(Ok(http_body::Frame::data(bytes::Bytes::from_static(data)))))
+            }
+        }
+
+        let body = TrackedBody::new(TestBody(vec![b"hello", b"world"]), BodyLimit::new(8));
+        let mut body = std::pin::pin!(body);
+
+        let frame = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await.unwrap();
+        assert_eq!(frame.unwrap().into_data().unwrap(), "hello");
+
+        let frame = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await.unwrap();
+        assert!(matches!(frame, Err(TrackedBodyError::Tracker(err)) if err.limit == 8));
+    }
+
+    #[tokio::test]
+    async fn buffered_body() {
+        use http_body::Body;
+
+        use super::{BufferedBody, IncomingBody, LengthLimitError};
+
+        async fn frames(body: IncomingBody) -> Vec<String> {
+            let mut body = std::pin::pin!(body);
+            let mut frames = Vec::new();
+            while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+                frames.push(match frame {
+                    Ok(frame) if frame.is_data() => format!("{:?}", frame.into_data().unwrap()),
+                    Ok(frame) => format!("{:?}", frame.into_trailers().unwrap()),
+                    Err(err) => err.to_string(),
+                });
+            }
+
+            assert!(body.is_end_stream());
+            frames
+        }
+
+        let mut trailers = http::HeaderMap::new();
+        trailers.insert("test", http::HeaderValue::from_static("test"));
+
+        let complete = IncomingBody::Buffered(Box::new(BufferedBody::complete(
+            bytes::Bytes::from_static(b"world"),
+            Some(trailers),
+        )));
+        let body = IncomingBody::Buffered(Box::new(BufferedBody::partial(
+            bytes::Bytes::from_static(b"hello "),
+            Box::pin(complete),
+        )));
+        assert_eq!(body.size_hint().exact(), Some(11));
+        assert_eq!(frames(body).await, ["b\"hello \"", "b\"world\"", "{\"test\": \"test\"}"]);
+
+        let body = IncomingBody::Buffered(Box::new(BufferedBody::failed(
+            bytes::Bytes::from_static(b"hello"),
+            LengthLimitError { limit: 5 }.into(),
+        )));
+        assert_eq!(frames(body).await, ["b\"hello\"", "body length limit exceeded: 5 bytes"]);
+    }
 }
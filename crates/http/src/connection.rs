@@ -0,0 +1,114 @@
+//! Information about the connection a request was received on.
+//!
+//! Both the hyper and the HTTP/3 backend add [`ConnectionInfo`] to the extensions of every request,
+//! so services can make protocol dependent decisions, e.g. advertising HTTP/3 with an `Alt-Svc` header.
+//!
+//! ```rust
+//! use scuffle_http::connection::ConnectionInfo;
+//!
+//! let service = scuffle_http::service::fn_http_service(|req| async move {
+//!     let mut builder = scuffle_http::Response::builder();
+//!
+//!     if let Some(info) = req.extensions().get::<ConnectionInfo>() {
+//!         if info.http_version() != scuffle_http::http::Version::HTTP_3 {
+//!             builder = builder.header("alt-svc", format!("h3=\":{}\"", info.local_addr().port()));
+//!         }
+//!     }
+//!
+//!     builder.body("hello".to_string())
+//! });
+//! # let _ = service;
+//! ```
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+
+/// The TLS protocol version negotiated for a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TlsVersion {
+    /// TLS 1.2
+    Tls12,
+    /// TLS 1.3
+    Tls13,
+}
+
+#[cfg(feature = "tls-rustls")]
+impl TlsVersion {
+    pub(crate) fn from_rustls(version: rustls::ProtocolVersion) -> Option<Self> {
+        match version {
+            rustls::ProtocolVersion::TLSv1_2 => Some(Self::Tls12),
+            rustls::ProtocolVersion::TLSv1_3 => Some(Self::Tls13),
+            _ => None,
+        }
+    }
+}
+
+/// Information about the connection a request was received on.
+///
+/// This is added to the request extensions by the server backends.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    peer_addr: SocketAddr,
+    local_addr: SocketAddr,
+    alpn_protocol: Option<Bytes>,
+    tls_version: Option<TlsVersion>,
+    http_version: http::Version,
+}
+
+impl ConnectionInfo {
+    #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+    pub(crate) fn new(peer_addr: SocketAddr, local_addr: SocketAddr, http_version: http::Version) -> Self {
+        Self {
+            peer_addr,
+            local_addr,
+            alpn_protocol: None,
+            tls_version: None,
+            http_version,
+        }
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    pub(crate) fn with_tls(mut self, alpn_protocol: Option<&[u8]>, tls_version: Option<TlsVersion>) -> Self {
+        self.alpn_protocol = alpn_protocol.map(Bytes::copy_from_slice);
+        self.tls_version = tls_version;
+        self
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    pub(crate) fn set_http_version(&mut self, http_version: http::Version) {
+        self.http_version = http_version;
+    }
+
+    /// The address of the client.
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer_addr
+    }
+
+    /// The local address the connection was accepted on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The protocol negotiated via ALPN during the TLS handshake, e.g. `h2` or `h3`.
+    ///
+    /// `None` for plain text connections or if the client did not offer ALPN.
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn_protocol.as_deref()
+    }
+
+    /// The negotiated TLS version, `None` for plain text connections.
+    pub fn tls_version(&self) -> Option<TlsVersion> {
+        self.tls_version
+    }
+
+    /// The HTTP version spoken on the connection.
+    pub fn http_version(&self) -> http::Version {
+        self.http_version
+    }
+
+    /// Whether the connection is encrypted with TLS.
+    pub fn is_tls(&self) -> bool {
+        self.tls_version.is_some()
+    }
+}
@@ -64,9 +64,16 @@ compile_error!("feature \"tls-rustls\" must be enabled when \"http3\" is enabled
 #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
 pub mod backend;
 pub mod body;
+pub mod connection;
 pub mod error;
+#[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+mod listener;
 mod server;
 pub mod service;
+#[cfg(feature = "tls-rustls")]
+pub mod tls;
+#[cfg(feature = "tracing")]
+pub mod trace;
 
 pub use http;
 pub use http::Response;
@@ -254,23 +261,58 @@ mod tests {
     }
 
     #[cfg(feature = "tls-rustls")]
-    fn rustls_config() -> rustls::ServerConfig {
-        rustls::crypto::aws_lc_rs::default_provider()
-            .install_default()
-            .expect("failed to install aws lc provider");
+    fn install_crypto_provider() {
+        // Tests run concurrently in the same process, so the provider might already be installed.
+        if rustls::crypto::CryptoProvider::get_default().is_none() {
+            let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        }
+    }
 
-        let certfile = std::fs::File::open("../../assets/cert.pem").expect("cert not found");
-        let certs = rustls_pemfile::certs(&mut std::io::BufReader::new(certfile))
+    #[cfg(feature = "tls-rustls")]
+    fn load_certs(path: &str) -> Vec<rustls::pki_types::CertificateDer<'static>> {
+        let certfile = std::fs::File::open(path).expect("cert not found");
+        rustls_pemfile::certs(&mut std::io::BufReader::new(certfile))
             .collect::<Result<Vec<_>, _>>()
-            .expect("failed to load certs");
-        let keyfile = std::fs::File::open("../../assets/key.pem").expect("key not found");
-        let key = rustls_pemfile::private_key(&mut std::io::BufReader::new(keyfile))
+            .expect("failed to load certs")
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    fn load_key(path: &str) -> rustls::pki_types::PrivateKeyDer<'static> {
+        let keyfile = std::fs::File::open(path).expect("key not found");
+        rustls_pemfile::private_key(&mut std::io::BufReader::new(keyfile))
             .expect("failed to load key")
-            .expect("no key found");
+            .expect("no key found")
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    fn rustls_config() -> rustls::ServerConfig {
+        install_crypto_provider();
 
         rustls::ServerConfig::builder()
             .with_no_client_auth()
-            .with_single_cert(certs, key)
+            .with_single_cert(load_certs("../../assets/cert.pem"), load_key("../../assets/key.pem"))
+            .expect("failed to build config")
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    fn rustls_client_auth_config(optional: bool) -> rustls::ServerConfig {
+        install_crypto_provider();
+
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in load_certs("../../assets/client_ca.pem") {
+            roots.add(cert).expect("failed to add root");
+        }
+
+        let client_auth = if optional {
+            crate::tls::ClientAuth::Optional(roots.into())
+        } else {
+            crate::tls::ClientAuth::Required(roots.into())
+        };
+
+        client_auth
+            .server_config_builder()
+            .expect("failed to build verifier")
+            .with_single_cert(load_certs("../../assets/cert.pem"), load_key("../../assets/key.pem"))
             .expect("failed to build config")
     }
 
@@ -555,6 +597,508 @@ mod tests {
         test_server(builder, &[reqwest::Version::HTTP_11, reqwest::Version::HTTP_2]).await;
     }
 
+    #[tokio::test]
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    async fn limits_service() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::body::ByteCounter;
+        use crate::service::limits_service;
+
+        let counters = Arc::new(Mutex::new(Vec::<ByteCounter>::new()));
+
+        let builder = HttpServer::builder()
+            .service_factory(service_clone_factory(limits_service(
+                fn_http_service({
+                    let counters = counters.clone();
+                    move |req| {
+                        counters
+                            .lock()
+                            .unwrap()
+                            .push(req.extensions().get::<ByteCounter>().unwrap().clone());
+
+                        async move {
+                            let body = axum::body::to_bytes(axum::body::Body::new(req.into_body()), usize::MAX)
+                                .await
+                                .unwrap();
+                            Ok::<_, Infallible>(http::Response::new(String::from_utf8(body.to_vec()).unwrap()))
+                        }
+                    }
+                }),
+                |_| Some(RESPONSE_TEXT.len()),
+            )))
+            .enable_http1(true)
+            .enable_http2(true);
+
+        test_server(builder, &[reqwest::Version::HTTP_11, reqwest::Version::HTTP_2]).await;
+
+        let counters = counters.lock().unwrap();
+        assert_eq!(counters.len(), 2);
+        for counter in counters.iter() {
+            assert_eq!(counter.bytes(), RESPONSE_TEXT.len() as u64);
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    async fn limits_service_payload_too_large() {
+        use crate::service::limits_service;
+
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(limits_service(
+                fn_http_service(|_| async { Ok::<_, Infallible>(http::Response::new(RESPONSE_TEXT.to_string())) }),
+                |req| (req.uri().path() == "/small").then_some(4),
+            )))
+            .enable_http1(true)
+            .enable_http2(true)
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let client = reqwest::Client::new();
+
+        let resp = client
+            .post(format!("http://{addr}/small"))
+            .body(RESPONSE_TEXT)
+            .send()
+            .await
+            .expect("failed to get response");
+        assert_eq!(resp.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(resp.text().await.unwrap(), "");
+
+        let resp = client
+            .post(format!("http://{addr}/large"))
+            .body(RESPONSE_TEXT)
+            .send()
+            .await
+            .expect("failed to get response");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        assert_eq!(resp.text().await.unwrap(), RESPONSE_TEXT);
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "http1")]
+    async fn limits_service_streaming() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        use crate::service::{HttpService, RequestBodyLimits, limits_service};
+
+        async fn request(addr: std::net::SocketAddr, req: &[u8]) -> String {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.expect("failed to connect");
+            stream.write_all(req).await.expect("failed to write request");
+
+            let mut resp = Vec::new();
+            while !resp.windows(4).any(|w| w == b"\r\n\r\n") {
+                let mut buf = [0; 1024];
+                let n = stream.read(&mut buf).await.expect("failed to read response");
+                assert_ne!(n, 0, "connection closed without a response");
+                resp.extend_from_slice(&buf[..n]);
+            }
+
+            let resp = String::from_utf8(resp).unwrap();
+            resp.lines().next().unwrap().to_string()
+        }
+
+        let limits = limits_service(
+            fn_http_service(|req: crate::IncomingRequest| async move {
+                let body = axum::body::to_bytes(axum::body::Body::new(req.into_body()), usize::MAX).await?;
+                Ok::<_, axum::Error>(http::Response::new(String::from_utf8(body.to_vec()).unwrap()))
+            }),
+            |_| Some(4),
+        )
+        .with_request_body_timeout(Duration::from_millis(100));
+
+        let service = fn_http_service(move |mut req| {
+            if req.uri().path() == "/override" {
+                req.extensions_mut().insert(RequestBodyLimits::default());
+            }
+
+            let mut limits = limits.clone();
+            async move { limits.call(req).await }
+        });
+
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(service))
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        // The limit is only exceeded while streaming the body, there is no content-length
+        let chunked = b"POST / HTTP/1.1\r\nhost: test\r\ntransfer-encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        assert_eq!(request(addr, chunked).await, "HTTP/1.1 413 Payload Too Large");
+
+        // The body is never completed
+        let slow = b"POST / HTTP/1.1\r\nhost: test\r\ncontent-length: 4\r\n\r\nab";
+        assert_eq!(request(addr, slow).await, "HTTP/1.1 408 Request Timeout");
+
+        let chunked = b"POST /override HTTP/1.1\r\nhost: test\r\ntransfer-encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        assert_eq!(request(addr, chunked).await, "HTTP/1.1 200 OK");
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "http1")]
+    async fn idle_connections() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        /// Sends a request and returns whether the server closed the connection within the given time.
+        async fn closed_after(addr: std::net::SocketAddr, wait: Duration) -> bool {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.expect("failed to connect");
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nhost: test\r\n\r\n")
+                .await
+                .expect("failed to write request");
+
+            let mut resp = Vec::new();
+            while !resp.ends_with(RESPONSE_TEXT.as_bytes()) {
+                let mut buf = [0; 1024];
+                let n = stream.read(&mut buf).await.expect("failed to read response");
+                assert_ne!(n, 0, "connection closed without a response");
+                resp.extend_from_slice(&buf[..n]);
+            }
+
+            let mut buf = [0; 1];
+            matches!(stream.read(&mut buf).with_timeout(wait).await, Ok(Ok(0)))
+        }
+
+        let service = fn_http_service(|_| async { Ok::<_, Infallible>(http::Response::new(RESPONSE_TEXT.to_string())) });
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let default_addr = get_available_addr().expect("failed to get available address");
+        let default = HttpServer::builder()
+            .service_factory(service_clone_factory(service.clone()))
+            .bind(default_addr)
+            .ctx(ctx.clone())
+            .build();
+
+        let no_keep_alive_addr = get_available_addr().expect("failed to get available address");
+        let no_keep_alive = HttpServer::builder()
+            .service_factory(service_clone_factory(service.clone()))
+            .bind(no_keep_alive_addr)
+            .ctx(ctx.clone())
+            .http1_keep_alive(false)
+            .build();
+
+        let idle_timeout_addr = get_available_addr().expect("failed to get available address");
+        let idle_timeout = HttpServer::builder()
+            .service_factory(service_clone_factory(service))
+            .bind(idle_timeout_addr)
+            .ctx(ctx)
+            .idle_timeout(Duration::from_millis(100))
+            .build();
+
+        let handles = [
+            tokio::spawn(async move { default.run().await.expect("server run failed") }),
+            tokio::spawn(async move { no_keep_alive.run().await.expect("server run failed") }),
+            tokio::spawn(async move { idle_timeout.run().await.expect("server run failed") }),
+        ];
+
+        // Wait for the servers to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        assert!(!closed_after(default_addr, Duration::from_millis(500)).await);
+        assert!(closed_after(no_keep_alive_addr, Duration::from_millis(100)).await);
+        assert!(closed_after(idle_timeout_addr, Duration::from_millis(500)).await);
+
+        handler.shutdown().await;
+        for handle in handles {
+            handle.await.expect("task failed");
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    async fn idempotency_service() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use crate::service::{IdempotencyConfig, idempotency_service};
+
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(idempotency_service(
+                fn_http_service({
+                    let calls = calls.clone();
+                    move |req| {
+                        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                        async move {
+                            if req.uri().path() == "/slow" {
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                            }
+
+                            let mut resp = http::Response::new(call.to_string());
+                            match req.uri().path() {
+                                "/error" => *resp.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR,
+                                "/large" => *resp.body_mut() = format!("{call:0>32}"),
+                                _ => {}
+                            }
+
+                            Ok::<_, Infallible>(resp)
+                        }
+                    }
+                }),
+                IdempotencyConfig::builder()
+                    .ttl(Duration::from_secs(60))
+                    .max_body_size(16)
+                    .build(),
+            )))
+            .enable_http1(true)
+            .enable_http2(true)
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let client = reqwest::Client::new();
+        let send = |method: reqwest::Method, path: &str, key: Option<&str>, body: &'static str, auth: Option<&str>| {
+            let mut request = client.request(method, format!("http://{addr}{path}")).body(body);
+            if let Some(key) = key {
+                request = request.header("idempotency-key", key);
+            }
+
+            if let Some(auth) = auth {
+                request = request.header("authorization", auth);
+            }
+
+            async move {
+                let resp = request.send().await.expect("failed to get response");
+                let replayed = resp.headers().contains_key("idempotent-replayed");
+                (resp.status(), replayed, resp.text().await.unwrap())
+            }
+        };
+
+        let ok = reqwest::StatusCode::OK;
+        let unprocessable = reqwest::StatusCode::UNPROCESSABLE_ENTITY;
+        let post = reqwest::Method::POST;
+
+        assert_eq!(send(post.clone(), "/", Some("a"), "", None).await, (ok, false, "1".into()));
+        assert_eq!(send(post.clone(), "/", Some("a"), "", None).await, (ok, true, "1".into()));
+
+        // a different key or caller
+        assert_eq!(send(post.clone(), "/", Some("b"), "", None).await, (ok, false, "2".into()));
+        assert_eq!(
+            send(post.clone(), "/", Some("a"), "", Some("Bearer other")).await,
+            (ok, false, "3".into())
+        );
+        assert_eq!(
+            send(post.clone(), "/", Some("a"), "", Some("Bearer other")).await,
+            (ok, true, "3".into())
+        );
+
+        // the key is reused with a different path, method or body
+        assert_eq!(
+            send(post.clone(), "/other", Some("a"), "", None).await,
+            (unprocessable, false, "".into())
+        );
+        assert_eq!(
+            send(reqwest::Method::PATCH, "/", Some("a"), "", None).await,
+            (unprocessable, false, "".into())
+        );
+        assert_eq!(
+            send(post.clone(), "/", Some("a"), "body", None).await,
+            (unprocessable, false, "".into())
+        );
+
+        // no key or an idempotent method
+        assert_eq!(send(post.clone(), "/", None, "", None).await, (ok, false, "4".into()));
+        assert_eq!(
+            send(reqwest::Method::PUT, "/", Some("a"), "", None).await,
+            (ok, false, "5".into())
+        );
+
+        // server errors are not cached
+        let error = reqwest::StatusCode::INTERNAL_SERVER_ERROR;
+        assert_eq!(
+            send(post.clone(), "/error", Some("e"), "", None).await,
+            (error, false, "6".into())
+        );
+        assert_eq!(
+            send(post.clone(), "/error", Some("e"), "", None).await,
+            (error, false, "7".into())
+        );
+
+        // requests and responses larger than the max body size are not cached
+        let large = "x".repeat(32).leak();
+        assert_eq!(send(post.clone(), "/", Some("l"), large, None).await, (ok, false, "8".into()));
+        assert_eq!(send(post.clone(), "/", Some("l"), large, None).await, (ok, false, "9".into()));
+        assert_eq!(
+            send(post.clone(), "/large", Some("l"), "", None).await,
+            (ok, false, format!("{:0>32}", 10))
+        );
+        assert_eq!(
+            send(post.clone(), "/large", Some("l"), "", None).await,
+            (ok, false, format!("{:0>32}", 11))
+        );
+
+        // a retry while the first request is in flight waits for its response
+        let (first, second) = tokio::join!(
+            send(post.clone(), "/slow", Some("s"), "", None),
+            send(post.clone(), "/slow", Some("s"), "", None),
+        );
+        let mut results = [first, second];
+        results.sort();
+        assert_eq!(results, [(ok, false, "12".into()), (ok, true, "12".into())]);
+
+        assert_eq!(calls.load(Ordering::SeqCst), 12);
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    async fn cache_service() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use crate::service::{CacheConfig, cache_service};
+
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(cache_service(
+                fn_http_service({
+                    let calls = calls.clone();
+                    move |req| {
+                        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                        async move {
+                            let cache_control = match req.uri().path() {
+                                "/no-store" => "no-store",
+                                "/short" => "max-age=0",
+                                _ => "max-age=60",
+                            };
+
+                            let resp = http::Response::builder()
+                                .header(http::header::CACHE_CONTROL, cache_control)
+                                .header(http::header::VARY, "accept-language")
+                                .body(call.to_string())
+                                .unwrap();
+
+                            Ok::<_, Infallible>(resp)
+                        }
+                    }
+                }),
+                CacheConfig::builder().min_ttl(Duration::from_millis(200)).build(),
+            )))
+            .enable_http1(true)
+            .enable_http2(true)
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let client = reqwest::Client::new();
+        let send = |method: reqwest::Method, path: &str, headers: &[(&'static str, &'static str)]| {
+            let mut request = client.request(method, format!("http://{addr}{path}"));
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+
+            async move {
+                let resp = request.send().await.expect("failed to get response");
+                let cached = resp.headers().contains_key("age");
+                (cached, resp.text().await.unwrap())
+            }
+        };
+
+        let get = reqwest::Method::GET;
+
+        assert_eq!(send(get.clone(), "/", &[]).await, (false, "1".into()));
+        assert_eq!(send(get.clone(), "/", &[]).await, (true, "1".into()));
+
+        // different path, query, method or vary header
+        assert_eq!(send(get.clone(), "/other", &[]).await, (false, "2".into()));
+        assert_eq!(send(get.clone(), "/?a=b", &[]).await, (false, "3".into()));
+        assert_eq!(send(reqwest::Method::POST, "/", &[]).await, (false, "4".into()));
+        assert_eq!(
+            send(get.clone(), "/", &[("accept-language", "en")]).await,
+            (false, "5".into())
+        );
+        assert_eq!(send(get.clone(), "/", &[("accept-language", "en")]).await, (true, "5".into()));
+        assert_eq!(send(get.clone(), "/", &[]).await, (true, "1".into()));
+
+        // bypassing the cache stores the new response
+        assert_eq!(
+            send(get.clone(), "/", &[("cache-control", "no-cache")]).await,
+            (false, "6".into())
+        );
+        assert_eq!(send(get.clone(), "/", &[]).await, (true, "6".into()));
+
+        // authorized requests and uncacheable responses
+        assert_eq!(
+            send(get.clone(), "/", &[("authorization", "secret")]).await,
+            (false, "7".into())
+        );
+        assert_eq!(send(get.clone(), "/no-store", &[]).await, (false, "8".into()));
+        assert_eq!(send(get.clone(), "/no-store", &[]).await, (false, "9".into()));
+
+        // max-age=0 is clamped to the min ttl
+        assert_eq!(send(get.clone(), "/short", &[]).await, (false, "10".into()));
+        assert_eq!(send(get.clone(), "/short", &[]).await, (true, "10".into()));
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(send(get.clone(), "/short", &[]).await, (false, "11".into()));
+
+        // different host
+        assert_eq!(
+            send(get.clone(), "/", &[("host", "example.com")]).await,
+            (false, "12".into())
+        );
+        assert_eq!(
+            send(get.clone(), "/", &[("host", "example.com")]).await,
+            (true, "12".into())
+        );
+        assert_eq!(send(get.clone(), "/", &[]).await, (true, "6".into()));
+
+        assert_eq!(calls.load(Ordering::SeqCst), 12);
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
     #[tokio::test]
     #[cfg(all(feature = "http2", feature = "http3", feature = "tls-rustls"))]
     async fn response_trailers() {
@@ -598,4 +1142,296 @@ mod tests {
 
         test_tls_server(builder, &[reqwest::Version::HTTP_2, reqwest::Version::HTTP_3]).await;
     }
+
+    #[tokio::test]
+    #[cfg(all(feature = "tls-rustls", feature = "http1", feature = "http2", feature = "http3"))]
+    async fn rustls_client_auth() {
+        let service = fn_http_service(|req| async move {
+            let identity = req
+                .extensions()
+                .get::<crate::tls::PeerCertificates>()
+                .map(|certs| certs.dns_names().join(","))
+                .unwrap_or_else(|| "anonymous".to_string());
+            Ok::<_, Infallible>(http::Response::new(identity))
+        });
+
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(service))
+            .rustls_config(rustls_client_auth_config(false))
+            .enable_http3(true)
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let mut identity = std::fs::read("../../assets/client_cert.pem").expect("cert not found");
+        identity.extend(std::fs::read("../../assets/client_key.pem").expect("key not found"));
+        let identity = reqwest::Identity::from_pem(&identity).expect("failed to load identity");
+
+        let url = format!("https://{addr}/");
+
+        for version in [reqwest::Version::HTTP_11, reqwest::Version::HTTP_2, reqwest::Version::HTTP_3] {
+            let builder = reqwest::Client::builder()
+                .danger_accept_invalid_certs(true)
+                .https_only(true)
+                .identity(identity.clone());
+
+            let builder = if version == reqwest::Version::HTTP_3 {
+                builder.http3_prior_knowledge()
+            } else if version == reqwest::Version::HTTP_2 {
+                builder.http2_prior_knowledge()
+            } else {
+                builder.http1_only()
+            };
+
+            let client = builder.build().expect("failed to build client");
+            let resp = client
+                .get(&url)
+                .version(version)
+                .send()
+                .await
+                .unwrap_or_else(|_| panic!("failed to get response version {version:?}"))
+                .text()
+                .await
+                .expect("failed to get text");
+
+            assert_eq!(resp, "client.scuffle.test");
+        }
+
+        // Clients without a certificate are rejected during the handshake
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .https_only(true)
+            .http1_only()
+            .build()
+            .expect("failed to build client");
+        assert!(client.get(&url).send().await.is_err());
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "tls-rustls", feature = "http1"))]
+    async fn rustls_optional_client_auth() {
+        let builder = HttpServer::builder()
+            .service_factory(service_clone_factory(fn_http_service(|req| async move {
+                assert!(req.extensions().get::<crate::tls::PeerCertificates>().is_none());
+                Ok::<_, Infallible>(http::Response::new(RESPONSE_TEXT.to_string()))
+            })))
+            .rustls_config(rustls_client_auth_config(true));
+
+        #[cfg(feature = "http2")]
+        let builder = builder.enable_http2(false);
+
+        test_tls_server(builder, &[reqwest::Version::HTTP_11]).await;
+    }
+
+    #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
+    fn connection_info_service()
+    -> impl crate::service::HttpService<Error = Infallible, ResBody = String> + Clone + std::fmt::Debug {
+        fn_http_service(|req| async move {
+            let info = req
+                .extensions()
+                .get::<crate::connection::ConnectionInfo>()
+                .expect("missing connection info");
+            assert_eq!(info.peer_addr().ip(), std::net::Ipv4Addr::LOCALHOST);
+            assert_eq!(info.http_version(), req.version());
+            let alpn = info.alpn_protocol().map(|p| String::from_utf8_lossy(p).into_owned());
+            Ok::<_, Infallible>(http::Response::new(format!(
+                "{} {:?} {:?} {:?}",
+                info.local_addr().port(),
+                info.http_version(),
+                alpn,
+                info.tls_version(),
+            )))
+        })
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "http1", feature = "http2"))]
+    async fn connection_info() {
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(connection_info_service()))
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let url = format!("http://{addr}/");
+
+        for (version, expected) in [
+            (reqwest::Version::HTTP_11, format!("{} HTTP/1.1 None None", addr.port())),
+            (reqwest::Version::HTTP_2, format!("{} HTTP/2.0 None None", addr.port())),
+        ] {
+            let builder = reqwest::Client::builder();
+            let builder = if version == reqwest::Version::HTTP_2 {
+                builder.http2_prior_knowledge()
+            } else {
+                builder.http1_only()
+            };
+
+            let client = builder.build().expect("failed to build client");
+            let resp = client
+                .get(&url)
+                .version(version)
+                .send()
+                .await
+                .unwrap_or_else(|_| panic!("failed to get response version {version:?}"))
+                .text()
+                .await
+                .expect("failed to get text");
+
+            assert_eq!(resp, expected);
+        }
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "tracing", feature = "http1", feature = "http2"))]
+    async fn trace_context() {
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let service = fn_http_service(|req| async move {
+            let context = req
+                .extensions()
+                .get::<crate::trace::TraceContext>()
+                .expect("missing trace context");
+            Ok::<_, Infallible>(http::Response::new(format!(
+                "{:032x} {:?}",
+                context.trace_id(),
+                context.parent_id().map(|id| format!("{id:016x}")),
+            )))
+        });
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(service))
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let url = format!("http://{addr}/");
+
+        for version in [reqwest::Version::HTTP_11, reqwest::Version::HTTP_2] {
+            let builder = reqwest::Client::builder();
+            let builder = if version == reqwest::Version::HTTP_2 {
+                builder.http2_prior_knowledge()
+            } else {
+                builder.http1_only()
+            };
+
+            let client = builder.build().expect("failed to build client");
+            let resp = client
+                .get(&url)
+                .version(version)
+                .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+                .send()
+                .await
+                .unwrap_or_else(|_| panic!("failed to get response version {version:?}"))
+                .text()
+                .await
+                .expect("failed to get text");
+
+            assert_eq!(resp, "4bf92f3577b34da6a3ce929d0e0e4736 Some(\"00f067aa0ba902b7\")");
+
+            // without a traceparent a new trace is started
+            let resp = client
+                .get(&url)
+                .version(version)
+                .send()
+                .await
+                .unwrap_or_else(|_| panic!("failed to get response version {version:?}"))
+                .text()
+                .await
+                .expect("failed to get text");
+
+            assert!(resp.ends_with(" None"), "{resp}");
+        }
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
+
+    #[tokio::test]
+    #[cfg(all(feature = "tls-rustls", feature = "http1", feature = "http2", feature = "http3"))]
+    async fn rustls_connection_info() {
+        let addr = get_available_addr().expect("failed to get available address");
+        let (ctx, handler) = scuffle_context::Context::new();
+
+        let server = HttpServer::builder()
+            .service_factory(service_clone_factory(connection_info_service()))
+            .rustls_config(rustls_config())
+            .enable_http3(true)
+            .bind(addr)
+            .ctx(ctx)
+            .build();
+
+        let handle = tokio::spawn(async move {
+            server.run().await.expect("server run failed");
+        });
+
+        // Wait for the server to start
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let url = format!("https://{addr}/");
+
+        for (version, expected) in [
+            (reqwest::Version::HTTP_11, "HTTP/1.1 Some(\"http/1.1\") Some(Tls13)"),
+            (reqwest::Version::HTTP_2, "HTTP/2.0 Some(\"h2\") Some(Tls13)"),
+            (reqwest::Version::HTTP_3, "HTTP/3.0 Some(\"h3\") Some(Tls13)"),
+        ] {
+            let builder = reqwest::Client::builder().danger_accept_invalid_certs(true).https_only(true);
+            let builder = if version == reqwest::Version::HTTP_3 {
+                builder.http3_prior_knowledge()
+            } else if version == reqwest::Version::HTTP_2 {
+                builder.http2_prior_knowledge()
+            } else {
+                builder.http1_only()
+            };
+
+            let client = builder.build().expect("failed to build client");
+            let resp = client
+                .get(&url)
+                .version(version)
+                .send()
+                .await
+                .unwrap_or_else(|_| panic!("failed to get response version {version:?}"))
+                .text()
+                .await
+                .expect("failed to get text");
+
+            assert_eq!(resp, format!("{} {expected}", addr.port()));
+        }
+
+        handler.shutdown().await;
+        handle.await.expect("task failed");
+    }
 }
@@ -0,0 +1,206 @@
+//! Creating the listening sockets used by the backends.
+use std::io;
+use std::net::SocketAddr;
+
+use socket2::{Domain, Protocol, Socket, Type};
+
+/// The first file descriptor passed by the service manager.
+///
+/// See <https://www.freedesktop.org/software/systemd/man/latest/sd_listen_fds.html>.
+#[cfg(unix)]
+const LISTEN_FDS_START: i32 = 3;
+
+/// Options for creating a listening socket.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ListenerOptions {
+    pub(crate) reuse_port: bool,
+    pub(crate) inherit_listeners: bool,
+}
+
+/// Creates a TCP listener for the given address.
+///
+/// If `inherit_listeners` is set, an inherited socket bound to the same address is used instead of binding a new one.
+pub(crate) fn tcp_listener(addr: SocketAddr, options: ListenerOptions) -> io::Result<std::net::TcpListener> {
+    let listener = match inherited_socket(addr, Type::STREAM, options)? {
+        Some(socket) => socket,
+        None => {
+            let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+            // Same as tokio, which allows the port to be reused while old connections are in TIME_WAIT.
+            #[cfg(unix)]
+            socket.set_reuse_address(true)?;
+            set_reuse_port(&socket, options.reuse_port)?;
+            socket.bind(&addr.into())?;
+            socket.listen(1024)?;
+            socket
+        }
+    };
+
+    listener.set_nonblocking(true)?;
+
+    Ok(listener.into())
+}
+
+/// Creates a UDP socket for the given address.
+///
+/// If `inherit_listeners` is set, an inherited socket bound to the same address is used instead of binding a new one.
+#[cfg(feature = "http3")]
+pub(crate) fn udp_socket(addr: SocketAddr, options: ListenerOptions) -> io::Result<std::net::UdpSocket> {
+    let socket = match inherited_socket(addr, Type::DGRAM, options)? {
+        Some(socket) => socket,
+        None => {
+            let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+            set_reuse_port(&socket, options.reuse_port)?;
+            socket.bind(&addr.into())?;
+            socket
+        }
+    };
+
+    Ok(socket.into())
+}
+
+#[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))))]
+fn set_reuse_port(socket: &Socket, reuse_port: bool) -> io::Result<()> {
+    if reuse_port {
+        socket.set_reuse_port(true)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(all(unix, not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin")))))]
+fn set_reuse_port(_socket: &Socket, reuse_port: bool) -> io::Result<()> {
+    if reuse_port {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "SO_REUSEPORT is not supported on this platform",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the number of file descriptors passed to this process by the service manager.
+///
+/// Follows the `LISTEN_FDS` protocol used by systemd socket activation. `LISTEN_PID` is checked if it is set, so
+/// that file descriptors meant for a parent process are not picked up by its children.
+#[cfg(unix)]
+fn listen_fds() -> usize {
+    if std::env::var("LISTEN_PID").is_ok_and(|pid| pid.parse::<u32>().ok() != Some(std::process::id())) {
+        return 0;
+    }
+
+    std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|fds| fds.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Finds an inherited socket of the given type that is bound to `addr`.
+///
+/// The returned socket is a duplicate of the inherited file descriptor, so the same socket can be picked up by
+/// multiple servers (and by a server that is restarted within the same process).
+#[cfg(unix)]
+fn inherited_socket(addr: SocketAddr, ty: Type, options: ListenerOptions) -> io::Result<Option<Socket>> {
+    if !options.inherit_listeners {
+        return Ok(None);
+    }
+
+    find_socket(addr, ty, (LISTEN_FDS_START..).take(listen_fds()))
+}
+
+#[cfg(unix)]
+fn find_socket(addr: SocketAddr, ty: Type, fds: impl IntoIterator<Item = std::os::fd::RawFd>) -> io::Result<Option<Socket>> {
+    use std::os::fd::BorrowedFd;
+
+    for fd in fds {
+        // SAFETY: The service manager keeps the passed file descriptors open for the lifetime of the process and
+        // we never close them ourselves, we only ever duplicate them.
+        #[allow(unsafe_code)]
+        let fd = unsafe { BorrowedFd::borrow_raw(fd) };
+        let socket = socket2::SockRef::from(&fd);
+
+        // Not every passed file descriptor has to be a socket.
+        let Ok(socket_ty) = socket.r#type() else {
+            continue;
+        };
+
+        let local_addr = socket.local_addr().ok().and_then(|a| a.as_socket());
+        if socket_ty == ty && local_addr == Some(addr) {
+            return Ok(Some(fd.try_clone_to_owned()?.into()));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(not(unix))]
+fn inherited_socket(_addr: SocketAddr, _ty: Type, _options: ListenerOptions) -> io::Result<Option<Socket>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))))]
+    fn reuse_port() {
+        let options = ListenerOptions {
+            reuse_port: true,
+            inherit_listeners: false,
+        };
+
+        let first = tcp_listener("127.0.0.1:0".parse().unwrap(), options).expect("failed to bind");
+        let addr = first.local_addr().unwrap();
+
+        // A second listener can bind to the same address while the first one is still open.
+        let second = tcp_listener(addr, options).expect("failed to bind with SO_REUSEPORT");
+        assert_eq!(second.local_addr().unwrap(), addr);
+
+        // But not without SO_REUSEPORT.
+        let err = tcp_listener(
+            addr,
+            ListenerOptions {
+                reuse_port: false,
+                inherit_listeners: false,
+            },
+        )
+        .expect_err("bind should fail");
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn find_inherited_socket() {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::File::open("Cargo.toml").unwrap();
+        let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let fds = [file.as_raw_fd(), udp.as_raw_fd(), tcp.as_raw_fd()];
+
+        let addr = tcp.local_addr().unwrap();
+        let socket = find_socket(addr, Type::STREAM, fds).unwrap().expect("tcp listener not found");
+        assert_eq!(socket.local_addr().unwrap().as_socket(), Some(addr));
+        assert_ne!(socket.as_raw_fd(), tcp.as_raw_fd());
+
+        let addr = udp.local_addr().unwrap();
+        let socket = find_socket(addr, Type::DGRAM, fds).unwrap().expect("udp socket not found");
+        assert_eq!(socket.local_addr().unwrap().as_socket(), Some(addr));
+
+        // Wrong type.
+        assert!(find_socket(addr, Type::STREAM, fds).unwrap().is_none());
+
+        // Listeners are only picked up when enabled.
+        let options = ListenerOptions {
+            reuse_port: false,
+            inherit_listeners: false,
+        };
+        assert!(
+            inherited_socket(tcp.local_addr().unwrap(), Type::STREAM, options)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use crate::error::HttpError;
 use crate::service::{HttpService, HttpServiceFactory};
@@ -26,6 +27,20 @@ pub struct HttpServer<F> {
     /// Use `[::]` for a dual-stack listener.
     /// For example, use `[::]:80` to bind to port 80 on both IPv4 and IPv6.
     bind: SocketAddr,
+    /// Bind the listening sockets with `SO_REUSEPORT`.
+    ///
+    /// This allows multiple processes to listen on the same address at the same time,
+    /// for example the old and the new process during a rolling restart.
+    /// Only supported on unix platforms.
+    #[builder(default = false)]
+    reuse_port: bool,
+    /// Use listening sockets passed in by the service manager instead of binding new ones.
+    ///
+    /// Sockets are passed via the `LISTEN_FDS` protocol used by systemd socket activation.
+    /// An inherited socket is used if it is bound to the same address as `bind`,
+    /// otherwise a new socket is bound as usual.
+    #[builder(default = false)]
+    inherit_listeners: bool,
     /// Enable HTTP/1.1.
     #[builder(default = true)]
     #[cfg(feature = "http1")]
@@ -37,6 +52,36 @@ pub struct HttpServer<F> {
     #[builder(default = false, setters(vis = "", name = enable_http3_internal))]
     #[cfg(feature = "http3")]
     enable_http3: bool,
+    /// Keep HTTP/1.1 connections open for further requests.
+    #[builder(default = true)]
+    #[cfg(feature = "http1")]
+    http1_keep_alive: bool,
+    /// How long to wait for the headers of the next request on a HTTP/1.1 connection.
+    ///
+    /// Idle keep-alive connections are closed after this timeout. Defaults to hyper's header read timeout.
+    #[cfg(feature = "http1")]
+    http1_keep_alive_timeout: Option<Duration>,
+    /// Interval of the HTTP/2 ping frames sent to check that the connection is still alive.
+    ///
+    /// Pings are disabled by default.
+    #[cfg(feature = "http2")]
+    http2_keep_alive_interval: Option<Duration>,
+    /// How long to wait for the acknowledgement of a HTTP/2 ping before closing the connection.
+    ///
+    /// Defaults to hyper's keep-alive timeout.
+    #[cfg(feature = "http2")]
+    http2_keep_alive_timeout: Option<Duration>,
+    /// Close QUIC connections which have not received any packets for this long.
+    ///
+    /// Defaults to quinn's idle timeout.
+    #[cfg(feature = "http3")]
+    http3_idle_timeout: Option<Duration>,
+    /// Close HTTP/1.1 and HTTP/2 connections which have not read or written any data
+    /// and have no request in flight for this long.
+    ///
+    /// This reaps dead connections of clients which disappeared without closing them.
+    /// Disabled by default, use [`http3_idle_timeout`](HttpServerBuilder::http3_idle_timeout) for HTTP/3.
+    idle_timeout: Option<Duration>,
     /// rustls config.
     ///
     /// Use this field to set the server into TLS mode.
@@ -204,7 +249,10 @@ where
                         .worker_tasks(self.worker_tasks)
                         .service_factory(self.service_factory)
                         .bind(self.bind)
+                        .reuse_port(self.reuse_port)
+                        .inherit_listeners(self.inherit_listeners)
                         .rustls_config(_rustls_config)
+                        .maybe_idle_timeout(self.http3_idle_timeout)
                         .build();
 
                     return backend.run().await;
@@ -216,13 +264,22 @@ where
                         .worker_tasks(self.worker_tasks)
                         .service_factory(self.service_factory)
                         .bind(self.bind)
-                        .rustls_config(_rustls_config);
+                        .reuse_port(self.reuse_port)
+                        .inherit_listeners(self.inherit_listeners)
+                        .rustls_config(_rustls_config)
+                        .maybe_idle_timeout(self.idle_timeout);
 
                     #[cfg(feature = "http1")]
-                    let builder = builder.http1_enabled(self.enable_http1);
+                    let builder = builder
+                        .http1_enabled(self.enable_http1)
+                        .http1_keep_alive(self.http1_keep_alive)
+                        .maybe_http1_keep_alive_timeout(self.http1_keep_alive_timeout);
 
                     #[cfg(feature = "http2")]
-                    let builder = builder.http2_enabled(self.enable_http2);
+                    let builder = builder
+                        .http2_enabled(self.enable_http2)
+                        .maybe_http2_keep_alive_interval(self.http2_keep_alive_interval)
+                        .maybe_http2_keep_alive_timeout(self.http2_keep_alive_timeout);
 
                     return builder.build().run().await;
                 }
@@ -233,13 +290,22 @@ where
                         .worker_tasks(self.worker_tasks)
                         .service_factory(self.service_factory.clone())
                         .bind(self.bind)
-                        .rustls_config(_rustls_config.clone());
+                        .reuse_port(self.reuse_port)
+                        .inherit_listeners(self.inherit_listeners)
+                        .rustls_config(_rustls_config.clone())
+                        .maybe_idle_timeout(self.idle_timeout);
 
                     #[cfg(feature = "http1")]
-                    let builder = builder.http1_enabled(self.enable_http1);
+                    let builder = builder
+                        .http1_enabled(self.enable_http1)
+                        .http1_keep_alive(self.http1_keep_alive)
+                        .maybe_http1_keep_alive_timeout(self.http1_keep_alive_timeout);
 
                     #[cfg(feature = "http2")]
-                    let builder = builder.http2_enabled(self.enable_http2);
+                    let builder = builder
+                        .http2_enabled(self.enable_http2)
+                        .maybe_http2_keep_alive_interval(self.http2_keep_alive_interval)
+                        .maybe_http2_keep_alive_timeout(self.http2_keep_alive_timeout);
 
                     let hyper = std::pin::pin!(builder.build().run());
 
@@ -248,7 +314,10 @@ where
                         .worker_tasks(self.worker_tasks)
                         .service_factory(self.service_factory)
                         .bind(self.bind)
+                        .reuse_port(self.reuse_port)
+                        .inherit_listeners(self.inherit_listeners)
                         .rustls_config(_rustls_config)
+                        .maybe_idle_timeout(self.http3_idle_timeout)
                         .build()
                         .run();
                     let http3 = std::pin::pin!(http3);
@@ -275,13 +344,22 @@ where
                 .ctx(self.ctx)
                 .worker_tasks(self.worker_tasks)
                 .service_factory(self.service_factory)
-                .bind(self.bind);
+                .bind(self.bind)
+                .reuse_port(self.reuse_port)
+                .inherit_listeners(self.inherit_listeners)
+                .maybe_idle_timeout(self.idle_timeout);
 
             #[cfg(feature = "http1")]
-            let builder = builder.http1_enabled(self.enable_http1);
+            let builder = builder
+                .http1_enabled(self.enable_http1)
+                .http1_keep_alive(self.http1_keep_alive)
+                .maybe_http1_keep_alive_timeout(self.http1_keep_alive_timeout);
 
             #[cfg(feature = "http2")]
-            let builder = builder.http2_enabled(self.enable_http2);
+            let builder = builder
+                .http2_enabled(self.enable_http2)
+                .maybe_http2_keep_alive_interval(self.http2_keep_alive_interval)
+                .maybe_http2_keep_alive_timeout(self.http2_keep_alive_timeout);
 
             return builder.build().run().await;
         }
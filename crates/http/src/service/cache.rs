@@ -0,0 +1,756 @@
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use bytes::{Buf, Bytes};
+use hashlink::LinkedHashMap;
+use hashlink::linked_hash_map::Entry;
+use http_body::Body;
+
+use super::{Collected, HttpService};
+use crate::IncomingRequest;
+
+/// The configuration of a [`CacheService`].
+///
+/// Create by calling [`CacheConfig::builder`] or use the [`Default`] configuration.
+#[derive(Debug, Clone, bon::Builder)]
+pub struct CacheConfig {
+    /// The maximum total size of all cached responses in bytes.
+    ///
+    /// When the cache is full the least recently used responses are evicted.
+    /// Responses larger than this are never cached, their body is streamed once this many bytes
+    /// were read without buffering the rest.
+    #[builder(default = 16 * 1024 * 1024)]
+    max_size: usize,
+    /// The minimum time a response is cached for.
+    ///
+    /// A response asking for a shorter lifetime via `max-age` is still cached for this long.
+    #[builder(default = Duration::ZERO)]
+    min_ttl: Duration,
+    /// The maximum time a response is cached for.
+    ///
+    /// A response asking for a longer lifetime via `max-age` is only cached for this long.
+    #[builder(default = Duration::from_secs(60 * 60))]
+    max_ttl: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// A [`HttpService`] that caches responses in memory.
+///
+/// Only `GET` and `HEAD` requests without an `Authorization` header are cached. Responses are
+/// cached by method, host, path, query and the request headers listed in the `Vary` response header.
+///
+/// Whether and how long a response is cached is decided by its `Cache-Control` header:
+/// - responses are only cached when they have a `s-maxage` or `max-age` directive. `s-maxage`
+///   takes precedence because this is a shared cache.
+/// - responses with `no-store`, `no-cache` or `private`, a `Vary: *` header or a `Set-Cookie`
+///   header are never cached.
+/// - the lifetime is clamped to the [`min_ttl`](CacheConfigBuilder::min_ttl) and
+///   [`max_ttl`](CacheConfigBuilder::max_ttl) of the [`CacheConfig`].
+///
+/// Only responses with a status code that is cacheable by default (for example `200` or `404`)
+/// are cached.
+///
+/// Requests with `Cache-Control: no-cache` are not served from the cache, but their response is
+/// still stored. Responses to requests with `Cache-Control: no-store` are not stored.
+///
+/// Responses served from the cache have an `Age` header set.
+///
+/// All clones of the service share the same cache, so it works across connections when used
+/// with [`service_clone_factory`](super::service_clone_factory).
+///
+/// Create by calling [`cache_service`].
+#[derive(Clone)]
+pub struct CacheService<S> {
+    inner: S,
+    config: CacheConfig,
+    cache: Arc<Mutex<Cache>>,
+}
+
+impl<S: Debug> Debug for CacheService<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheService")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Create a [`CacheService`] wrapping the given service.
+///
+/// See [`CacheService`] for details.
+pub fn cache_service<S>(inner: S, config: CacheConfig) -> CacheService<S>
+where
+    S: HttpService,
+{
+    CacheService {
+        inner,
+        config,
+        cache: Arc::new(Mutex::new(Cache::default())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    method: http::Method,
+    /// The `:authority` of the request or its `Host` header, lowercased.
+    authority: Option<String>,
+    path_and_query: String,
+}
+
+impl CacheKey {
+    fn from_request(req: &IncomingRequest) -> Option<Self> {
+        if req.method() != http::Method::GET && req.method() != http::Method::HEAD {
+            return None;
+        }
+
+        if req.headers().contains_key(http::header::AUTHORIZATION) {
+            return None;
+        }
+
+        let authority = req
+            .uri()
+            .authority()
+            .map(|authority| authority.as_str())
+            .or_else(|| req.headers().get(http::header::HOST).and_then(|host| host.to_str().ok()))
+            .map(str::to_ascii_lowercase);
+
+        Some(Self {
+            method: req.method().clone(),
+            authority,
+            path_and_query: req.uri().path_and_query().map_or("/", |p| p.as_str()).to_owned(),
+        })
+    }
+}
+
+/// The parsed directives of a `Cache-Control` header that are relevant to us.
+#[derive(Debug, Default)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &http::HeaderMap) -> Self {
+        let mut cache_control = Self::default();
+
+        let directives = headers
+            .get_all(http::header::CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => cache_control.no_store = true,
+                "no-cache" => cache_control.no_cache = true,
+                "private" => cache_control.private = true,
+                "max-age" => cache_control.max_age = value.and_then(|v| v.parse().ok()),
+                "s-maxage" => cache_control.s_maxage = value.and_then(|v| v.parse().ok()),
+                _ => {}
+            }
+        }
+
+        cache_control
+    }
+}
+
+/// Status codes that are cacheable by default.
+///
+/// See [RFC 9110 section 15.1](https://www.rfc-editor.org/rfc/rfc9110#section-15.1).
+fn is_cacheable_status(status: http::StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+impl CacheConfig {
+    /// Returns how long the response should be cached for, or `None` if it should not be cached.
+    fn ttl(&self, parts: &http::response::Parts) -> Option<Duration> {
+        if !is_cacheable_status(parts.status) || parts.headers.contains_key(http::header::SET_COOKIE) {
+            return None;
+        }
+
+        let cache_control = CacheControl::from_headers(&parts.headers);
+        if cache_control.no_store || cache_control.no_cache || cache_control.private {
+            return None;
+        }
+
+        let max_age = Duration::from_secs(cache_control.s_maxage.or(cache_control.max_age)?);
+        let ttl = max_age.max(self.min_ttl).min(self.max_ttl);
+
+        Some(ttl).filter(|ttl| !ttl.is_zero())
+    }
+}
+
+/// Returns the header names listed in the `Vary` header, or `None` for `Vary: *`.
+fn vary_headers(headers: &http::HeaderMap) -> Option<Vec<http::HeaderName>> {
+    let mut names = Vec::new();
+
+    let values = headers
+        .get_all(http::header::VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+
+    for name in values {
+        if name == "*" {
+            return None;
+        }
+
+        if let Ok(name) = http::HeaderName::try_from(name) {
+            names.push(name);
+        }
+    }
+
+    Some(names)
+}
+
+fn header_values(headers: &http::HeaderMap, name: &http::HeaderName) -> Vec<http::HeaderValue> {
+    headers.get_all(name).iter().cloned().collect()
+}
+
+#[derive(Debug)]
+struct CachedResponse {
+    status: http::StatusCode,
+    version: http::Version,
+    headers: http::HeaderMap,
+    body: Bytes,
+    trailers: Option<http::HeaderMap>,
+}
+
+impl CachedResponse {
+    fn size(&self) -> usize {
+        let header_size = |headers: &http::HeaderMap| {
+            headers
+                .iter()
+                .map(|(name, value)| name.as_str().len() + value.len())
+                .sum::<usize>()
+        };
+
+        self.body.len() + header_size(&self.headers) + self.trailers.as_ref().map_or(0, header_size)
+    }
+
+    fn to_response<B: http_body::Body>(&self, age: Option<Duration>) -> http::Response<CacheBody<B>> {
+        let mut resp = http::Response::new(CacheBody::cached(self.body.clone(), self.trailers.clone()));
+        *resp.status_mut() = self.status;
+        *resp.version_mut() = self.version;
+        *resp.headers_mut() = self.headers.clone();
+
+        if let Some(age) = age {
+            resp.headers_mut().insert(http::header::AGE, age.as_secs().into());
+        }
+
+        resp
+    }
+}
+
+struct Variant {
+    /// The request headers this response varies on, with the values of the request that stored it.
+    vary: Vec<(http::HeaderName, Vec<http::HeaderValue>)>,
+    response: Arc<CachedResponse>,
+    stored_at: Instant,
+    expires_at: Instant,
+}
+
+impl Variant {
+    fn matches(&self, headers: &http::HeaderMap) -> bool {
+        self.vary.iter().all(|(name, values)| header_values(headers, name) == *values)
+    }
+}
+
+/// Removes the expired variants, returning their total size.
+fn remove_expired(variants: &mut Vec<Variant>, now: Instant) -> usize {
+    let mut size = 0;
+    variants.retain(|variant| {
+        let expired = variant.expires_at <= now;
+        if expired {
+            size += variant.response.size();
+        }
+
+        !expired
+    });
+
+    size
+}
+
+/// The cached responses, ordered from least to most recently used.
+///
+/// Expired responses are removed when their key is accessed, otherwise they are evicted like any
+/// other response once they become the least recently used.
+#[derive(Default)]
+struct Cache {
+    entries: LinkedHashMap<CacheKey, Vec<Variant>>,
+    size: usize,
+}
+
+impl Cache {
+    fn get(&mut self, key: &CacheKey, headers: &http::HeaderMap, now: Instant) -> Option<(Arc<CachedResponse>, Duration)> {
+        let variants = self.entries.to_back(key)?;
+        self.size -= remove_expired(variants, now);
+        if variants.is_empty() {
+            self.entries.remove(key);
+            return None;
+        }
+
+        let variant = variants.iter().find(|variant| variant.matches(headers))?;
+
+        Some((variant.response.clone(), now.saturating_duration_since(variant.stored_at)))
+    }
+
+    fn insert(&mut self, key: CacheKey, variant: Variant, max_size: usize, now: Instant) {
+        let variants = match self.entries.entry(key) {
+            Entry::Occupied(mut entry) => {
+                entry.to_back();
+                entry.into_mut()
+            }
+            Entry::Vacant(entry) => entry.insert(Vec::new()),
+        };
+
+        self.size -= remove_expired(variants, now);
+        // Replace the response previously stored for the same request headers.
+        variants.retain(|existing| {
+            let replaced = existing.vary == variant.vary;
+            if replaced {
+                self.size -= existing.response.size();
+            }
+
+            !replaced
+        });
+
+        self.size += variant.response.size();
+        variants.push(variant);
+
+        while self.size > max_size {
+            let Some((_, variants)) = self.entries.pop_front() else {
+                break;
+            };
+
+            self.size -= variants.iter().map(|variant| variant.response.size()).sum::<usize>();
+        }
+    }
+}
+
+impl<S> HttpService for CacheService<S>
+where
+    S: HttpService + Send,
+    S::ResBody: Send,
+    <S::ResBody as http_body::Body>::Data: Send,
+    <S::ResBody as http_body::Body>::Error: Send,
+{
+    type Error = S::Error;
+    type ResBody = CacheBody<S::ResBody>;
+
+    async fn call(&mut self, req: IncomingRequest) -> Result<http::Response<Self::ResBody>, Self::Error> {
+        let Some(key) = CacheKey::from_request(&req) else {
+            return Ok(self.inner.call(req).await?.map(CacheBody::new));
+        };
+
+        let request_cache_control = CacheControl::from_headers(req.headers());
+
+        if !request_cache_control.no_cache {
+            let cached = self
+                .cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(&key, req.headers(), Instant::now());
+
+            if let Some((response, age)) = cached {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(path = %key.path_and_query, "serving response from cache");
+
+                return Ok(response.to_response(Some(age)));
+            }
+        }
+
+        let request_headers = req.headers().clone();
+        let (parts, body) = self.inner.call(req).await?.into_parts();
+
+        let ttl = self.config.ttl(&parts).filter(|_| !request_cache_control.no_store);
+        let vary = vary_headers(&parts.headers);
+
+        let (Some(ttl), Some(vary)) = (ttl, vary) else {
+            return Ok(http::Response::from_parts(parts, CacheBody::new(body)));
+        };
+
+        let (data, trailers) = match super::collect_body_limited(body, self.config.max_size).await {
+            Collected::Complete(data, trailers) => (data, trailers),
+            Collected::Partial(data, rest) => {
+                return Ok(http::Response::from_parts(parts, CacheBody::partial(data, rest)));
+            }
+            Collected::Failed(data, err) => {
+                return Ok(http::Response::from_parts(parts, CacheBody::failed(data, err)));
+            }
+        };
+
+        let response = Arc::new(CachedResponse {
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers,
+            body: data,
+            trailers,
+        });
+
+        if response.size() <= self.config.max_size {
+            let now = Instant::now();
+            let variant = Variant {
+                vary: vary
+                    .into_iter()
+                    .map(|name| {
+                        let values = header_values(&request_headers, &name);
+                        (name, values)
+                    })
+                    .collect(),
+                response: response.clone(),
+                stored_at: now,
+                expires_at: now + ttl,
+            };
+
+            self.cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key, variant, self.config.max_size, now);
+        }
+
+        Ok(response.to_response(None))
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The response body of a [`CacheService`].
+    ///
+    /// This is either the body of the inner service or a body that was buffered for the cache.
+    pub struct CacheBody<B: http_body::Body> {
+        #[pin]
+        body: Option<B>,
+        rest: Option<Pin<Box<B>>>,
+        data: Option<Bytes>,
+        trailers: Option<http::HeaderMap>,
+        error: Option<B::Error>,
+    }
+}
+
+impl<B: http_body::Body> CacheBody<B> {
+    fn new(body: B) -> Self {
+        Self {
+            body: Some(body),
+            rest: None,
+            data: None,
+            trailers: None,
+            error: None,
+        }
+    }
+
+    fn cached(data: Bytes, trailers: Option<http::HeaderMap>) -> Self {
+        Self {
+            body: None,
+            rest: None,
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers,
+            error: None,
+        }
+    }
+
+    /// A body that was too large to cache, of which `data` was already read.
+    fn partial(data: Bytes, rest: Pin<Box<B>>) -> Self {
+        Self {
+            body: None,
+            rest: Some(rest),
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers: None,
+            error: None,
+        }
+    }
+
+    fn failed(data: Bytes, error: B::Error) -> Self {
+        Self {
+            body: None,
+            rest: None,
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl<B> Body for CacheBody<B>
+where
+    B: http_body::Body,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+
+        if let Some(body) = this.body.as_pin_mut() {
+            return body
+                .poll_frame(cx)
+                .map_ok(|frame| frame.map_data(|mut data| data.copy_to_bytes(data.remaining())));
+        }
+
+        if let Some(data) = this.data.take() {
+            return Poll::Ready(Some(Ok(http_body::Frame::data(data))));
+        }
+
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Some(Err(error)));
+        }
+
+        if let Some(rest) = this.rest {
+            return rest
+                .as_mut()
+                .poll_frame(cx)
+                .map_ok(|frame| frame.map_data(|mut data| data.copy_to_bytes(data.remaining())));
+        }
+
+        Poll::Ready(this.trailers.take().map(|trailers| Ok(http_body::Frame::trailers(trailers))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.body {
+            Some(body) => body.is_end_stream(),
+            None => {
+                self.data.is_none()
+                    && self.trailers.is_none()
+                    && self.error.is_none()
+                    && self.rest.as_ref().is_none_or(|rest| rest.is_end_stream())
+            }
+        }
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let len = self.data.as_ref().map_or(0, |data| data.len() as u64);
+        match (&self.body, &self.rest) {
+            (Some(body), _) => body.size_hint(),
+            (None, Some(rest)) => {
+                let rest = rest.size_hint();
+                let mut hint = http_body::SizeHint::new();
+                hint.set_lower(rest.lower() + len);
+                if let Some(upper) = rest.upper() {
+                    hint.set_upper(upper + len);
+                }
+
+                hint
+            }
+            (None, None) => http_body::SizeHint::with_exact(len),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::convert::Infallible;
+    use std::time::{Duration, Instant};
+
+    use bytes::Bytes;
+    use http_body::Body;
+
+    use super::{Cache, CacheBody, CacheConfig, CacheKey, CachedResponse, Variant};
+    use crate::service::fn_http_service;
+
+    #[test]
+    fn cache_service_debug() {
+        let service = super::cache_service(
+            fn_http_service(|_| async { Ok::<_, Infallible>(http::Response::new(String::new())) }),
+            CacheConfig::default(),
+        );
+        assert!(format!("{service:?}").starts_with("CacheService { inner: FnHttpService("));
+    }
+
+    fn ttl(config: &CacheConfig, status: http::StatusCode, headers: &[(&'static str, &'static str)]) -> Option<Duration> {
+        let mut resp = http::Response::builder().status(status);
+        for (name, value) in headers {
+            resp = resp.header(*name, *value);
+        }
+
+        config.ttl(&resp.body(()).unwrap().into_parts().0)
+    }
+
+    #[test]
+    fn cache_control() {
+        let config = CacheConfig::builder()
+            .min_ttl(Duration::from_secs(2))
+            .max_ttl(Duration::from_secs(60))
+            .build();
+        let ok = http::StatusCode::OK;
+
+        assert_eq!(
+            ttl(&config, ok, &[("cache-control", "max-age=10")]),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            ttl(&config, ok, &[("cache-control", "public, max-age=10, s-maxage=20")]),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            ttl(&config, http::StatusCode::NOT_FOUND, &[("cache-control", "max-age=\"30\"")]),
+            Some(Duration::from_secs(30))
+        );
+
+        // clamped
+        assert_eq!(
+            ttl(&config, ok, &[("cache-control", "max-age=0")]),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            ttl(&config, ok, &[("cache-control", "max-age=3600")]),
+            Some(Duration::from_secs(60))
+        );
+
+        // not cacheable
+        assert_eq!(ttl(&config, ok, &[]), None);
+        assert_eq!(ttl(&config, ok, &[("cache-control", "max-age=invalid")]), None);
+        assert_eq!(ttl(&config, ok, &[("cache-control", "no-store, max-age=10")]), None);
+        assert_eq!(ttl(&config, ok, &[("cache-control", "No-Cache, max-age=10")]), None);
+        assert_eq!(ttl(&config, ok, &[("cache-control", "private, max-age=10")]), None);
+        assert_eq!(
+            ttl(&config, ok, &[("cache-control", "max-age=10"), ("set-cookie", "a=b")]),
+            None
+        );
+        assert_eq!(
+            ttl(
+                &config,
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                &[("cache-control", "max-age=10")]
+            ),
+            None
+        );
+        assert_eq!(ttl(&CacheConfig::default(), ok, &[("cache-control", "max-age=0")]), None);
+    }
+
+    #[test]
+    fn vary_headers() {
+        let mut headers = http::HeaderMap::new();
+        headers.append(http::header::VARY, "Accept-Encoding, accept".parse().unwrap());
+        headers.append(http::header::VARY, "origin".parse().unwrap());
+        assert_eq!(
+            super::vary_headers(&headers),
+            Some(vec![
+                http::header::ACCEPT_ENCODING,
+                http::header::ACCEPT,
+                http::header::ORIGIN
+            ])
+        );
+
+        headers.append(http::header::VARY, "*".parse().unwrap());
+        assert_eq!(super::vary_headers(&headers), None);
+    }
+
+    #[test]
+    fn cache_key() {
+        let key = |req: http::request::Builder| {
+            let body = crate::body::BufferedBody::complete(Bytes::new(), None);
+            CacheKey::from_request(&req.body(crate::body::IncomingBody::Buffered(Box::new(body))).unwrap())
+        };
+
+        let key_a = key(http::Request::get("/a?b=c").header(http::header::HOST, "Example.com")).unwrap();
+        assert_eq!(key_a.authority.as_deref(), Some("example.com"));
+        assert_eq!(key_a.path_and_query, "/a?b=c");
+
+        // the :authority takes precedence over the host header
+        let key_b = key(http::Request::get("https://other.com/a?b=c").header(http::header::HOST, "example.com")).unwrap();
+        assert_eq!(key_b.authority.as_deref(), Some("other.com"));
+        assert_ne!(key_a, key_b);
+
+        assert_eq!(key(http::Request::get("/")).unwrap().authority, None);
+        assert!(key(http::Request::post("/")).is_none());
+        assert!(key(http::Request::get("/").header(http::header::AUTHORIZATION, "secret")).is_none());
+    }
+
+    #[test]
+    fn cache_eviction() {
+        let now = Instant::now();
+        let mut cache = Cache::default();
+
+        let key = |path: &str| CacheKey {
+            method: http::Method::GET,
+            authority: None,
+            path_and_query: path.to_owned(),
+        };
+
+        let variant = |size: usize, ttl: Duration| Variant {
+            vary: Vec::new(),
+            response: std::sync::Arc::new(CachedResponse {
+                status: http::StatusCode::OK,
+                version: http::Version::HTTP_11,
+                headers: http::HeaderMap::new(),
+                body: Bytes::from(vec![0; size]),
+                trailers: None,
+            }),
+            stored_at: now,
+            expires_at: now + ttl,
+        };
+
+        let headers = http::HeaderMap::new();
+        let ttl = Duration::from_secs(60);
+
+        cache.insert(key("/a"), variant(4, ttl), 10, now);
+        cache.insert(key("/b"), variant(4, ttl), 10, now);
+        assert_eq!(cache.size, 8);
+
+        // /a was used more recently than /b
+        assert!(cache.get(&key("/a"), &headers, now).is_some());
+        cache.insert(key("/c"), variant(4, ttl), 10, now);
+        assert_eq!(cache.size, 8);
+        assert!(cache.get(&key("/b"), &headers, now).is_none());
+        assert!(cache.get(&key("/a"), &headers, now).is_some());
+        assert!(cache.get(&key("/c"), &headers, now).is_some());
+
+        // replacing a response does not count twice
+        cache.insert(key("/c"), variant(2, ttl), 10, now);
+        assert_eq!(cache.size, 6);
+
+        // expired responses are not returned and removed when accessed
+        let later = now + ttl;
+        assert!(cache.get(&key("/a"), &headers, later).is_none());
+        assert_eq!(cache.size, 2);
+        cache.insert(key("/d"), variant(1, ttl * 2), 10, later);
+        assert_eq!(cache.size, 3);
+        assert_eq!(cache.entries.len(), 2);
+
+        // the expired /c is the least recently used response
+        cache.insert(key("/e"), variant(8, ttl * 2), 10, later);
+        assert_eq!(cache.size, 9);
+        assert!(cache.get(&key("/d"), &headers, later).is_some());
+        assert!(cache.get(&key("/e"), &headers, later).is_some());
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_body_partial() {
+        let rest = Box::pin(CacheBody::<String>::new(String::from("world")));
+        let mut body = std::pin::pin!(CacheBody::partial(Bytes::from_static(b"hello "), rest));
+        assert_eq!(body.size_hint().exact(), Some(11));
+
+        let mut data = Vec::new();
+        while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+            data.extend_from_slice(&frame.unwrap().into_data().unwrap());
+        }
+
+        assert_eq!(data, b"hello world");
+        assert!(body.is_end_stream());
+    }
+}
@@ -0,0 +1,697 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, RandomState};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use bytes::{Buf, Bytes};
+use http_body::Body;
+use tokio::sync::watch;
+
+use super::{Collected, HttpService};
+use crate::IncomingRequest;
+use crate::body::{BufferedBody, IncomingBody};
+
+/// The request header that carries the idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: http::HeaderName = http::HeaderName::from_static("idempotency-key");
+
+/// The response header that is set on responses which are replayed from the cache.
+pub const IDEMPOTENT_REPLAYED_HEADER: http::HeaderName = http::HeaderName::from_static("idempotent-replayed");
+
+type Identity = Arc<dyn Fn(&IncomingRequest) -> Option<String> + Send + Sync>;
+
+/// The configuration of an [`IdempotencyService`].
+///
+/// Create by calling [`IdempotencyConfig::builder`] or use the [`Default`] configuration.
+#[derive(Clone, bon::Builder)]
+pub struct IdempotencyConfig {
+    /// How long a completed response is kept in the cache.
+    #[builder(default = Duration::from_secs(24 * 60 * 60))]
+    ttl: Duration,
+    /// The maximum number of idempotency keys that are tracked at the same time.
+    ///
+    /// When the cache is full the oldest responses are evicted. Requests that arrive while all
+    /// entries are still in flight are passed to the inner service without deduplication.
+    #[builder(default = 10_000)]
+    max_entries: usize,
+    /// The maximum size of request and response bodies in bytes.
+    ///
+    /// Requests with a larger body are passed to the inner service without deduplication and
+    /// larger responses are streamed without being cached.
+    #[builder(default = 1024 * 1024)]
+    max_body_size: usize,
+    /// Returns the identity of the caller that sent the request, for example the id of the
+    /// authenticated user.
+    ///
+    /// Idempotency keys are scoped to this identity, so a caller never gets the response to the
+    /// request of another caller. Requests without an identity are passed to the inner service
+    /// without deduplication.
+    ///
+    /// By default the value of the `Authorization` header is used and requests without it share
+    /// one anonymous scope.
+    #[builder(with = |identity: impl Fn(&IncomingRequest) -> Option<String> + Send + Sync + 'static| {
+        Arc::new(identity) as Identity
+    })]
+    identity: Option<Identity>,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Debug for IdempotencyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyConfig")
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .field("max_body_size", &self.max_body_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdempotencyConfig {
+    fn identity(&self, req: &IncomingRequest) -> Option<String> {
+        match &self.identity {
+            Some(identity) => identity(req),
+            None => match req.headers().get(http::header::AUTHORIZATION) {
+                Some(value) => value.to_str().ok().map(str::to_owned),
+                None => Some(String::new()),
+            },
+        }
+    }
+}
+
+/// A [`HttpService`] that makes retries of non-idempotent requests safe.
+///
+/// `POST` and `PATCH` requests with an [`Idempotency-Key`](IDEMPOTENCY_KEY_HEADER) header are only
+/// passed to the inner service once per key and [identity](IdempotencyConfigBuilder::identity).
+/// The response is buffered and cached for the configured time to live, and retries with the same
+/// key get the cached response, marked with an [`Idempotent-Replayed: true`](IDEMPOTENT_REPLAYED_HEADER)
+/// header.
+///
+/// The method, URI and body of the first request are remembered. A request that reuses the key
+/// with a different method, URI or body is rejected with `422 Unprocessable Entity`.
+///
+/// When a retry arrives while the first request is still being handled, it waits for that request
+/// to finish instead of calling the inner service again.
+///
+/// Responses are not cached when the inner service returns an error, responds with a server error
+/// (`5xx`), the response body fails or is larger than the
+/// [`max_body_size`](IdempotencyConfigBuilder::max_body_size). The next retry calls the inner
+/// service again.
+///
+/// All clones of the service share the same cache, so it works across connections when used
+/// with [`service_clone_factory`](super::service_clone_factory).
+///
+/// Create by calling [`idempotency_service`].
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    config: IdempotencyConfig,
+    hasher: RandomState,
+    cache: Arc<Mutex<Cache>>,
+}
+
+impl<S: Debug> Debug for IdempotencyService<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyService")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Create an [`IdempotencyService`] wrapping the given service.
+///
+/// See [`IdempotencyService`] for details.
+pub fn idempotency_service<S>(inner: S, config: IdempotencyConfig) -> IdempotencyService<S>
+where
+    S: HttpService,
+{
+    IdempotencyService {
+        inner,
+        config,
+        hasher: RandomState::new(),
+        cache: Arc::new(Mutex::new(Cache::default())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    identity: String,
+    key: http::HeaderValue,
+}
+
+impl CacheKey {
+    fn from_request(req: &IncomingRequest, config: &IdempotencyConfig) -> Option<Self> {
+        if req.method() != http::Method::POST && req.method() != http::Method::PATCH {
+            return None;
+        }
+
+        let key = req.headers().get(IDEMPOTENCY_KEY_HEADER)?;
+
+        Some(Self {
+            identity: config.identity(req)?,
+            key: key.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CachedResponse {
+    status: http::StatusCode,
+    version: http::Version,
+    headers: http::HeaderMap,
+    body: Bytes,
+    trailers: Option<http::HeaderMap>,
+}
+
+impl CachedResponse {
+    fn to_response<B: http_body::Body>(&self, replayed: bool) -> http::Response<IdempotencyBody<B>> {
+        let mut resp = http::Response::new(IdempotencyBody::cached(self.body.clone(), self.trailers.clone()));
+        *resp.status_mut() = self.status;
+        *resp.version_mut() = self.version;
+        *resp.headers_mut() = self.headers.clone();
+
+        if replayed {
+            resp.headers_mut()
+                .insert(IDEMPOTENT_REPLAYED_HEADER, http::HeaderValue::from_static("true"));
+        }
+
+        resp
+    }
+}
+
+fn unprocessable<B: http_body::Body>() -> http::Response<IdempotencyBody<B>> {
+    let mut resp = http::Response::new(IdempotencyBody::cached(Bytes::new(), None));
+    *resp.status_mut() = http::StatusCode::UNPROCESSABLE_ENTITY;
+    resp
+}
+
+enum State {
+    InFlight(watch::Receiver<Option<Arc<CachedResponse>>>),
+    Completed {
+        response: Arc<CachedResponse>,
+        expires_at: Instant,
+    },
+}
+
+struct Entry {
+    /// The hash of the method, URI and body of the request that created the entry.
+    fingerprint: u64,
+    state: State,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<CacheKey, Entry>,
+    /// The completed entries in the order they expire in, which is the order they completed in
+    /// because all of them have the same time to live.
+    expiry: VecDeque<(Instant, CacheKey)>,
+}
+
+impl Cache {
+    fn evict_expired(&mut self, now: Instant) {
+        while self.expiry.front().is_some_and(|(expires_at, _)| *expires_at <= now) {
+            self.evict_oldest();
+        }
+    }
+
+    /// Evicts completed entries until there is room for a new one.
+    ///
+    /// Returns `false` if the cache is full of in-flight requests.
+    fn make_room(&mut self, max_entries: usize) -> bool {
+        while self.entries.len() >= max_entries {
+            if !self.evict_oldest() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn evict_oldest(&mut self) -> bool {
+        let Some((expires_at, key)) = self.expiry.pop_front() else {
+            return false;
+        };
+
+        if matches!(
+            self.entries.get(&key),
+            Some(Entry { state: State::Completed { expires_at: entry_expires_at, .. }, .. }) if *entry_expires_at == expires_at
+        ) {
+            self.entries.remove(&key);
+        }
+
+        true
+    }
+}
+
+/// Removes the in-flight entry if the request does not complete, so the next retry calls
+/// the inner service again.
+struct InFlightGuard {
+    cache: Arc<Mutex<Cache>>,
+    key: CacheKey,
+    fingerprint: u64,
+    tx: watch::Sender<Option<Arc<CachedResponse>>>,
+}
+
+impl InFlightGuard {
+    fn complete(self, response: Arc<CachedResponse>, ttl: Duration) {
+        let expires_at = Instant::now() + ttl;
+
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.entries.insert(
+            self.key.clone(),
+            Entry {
+                fingerprint: self.fingerprint,
+                state: State::Completed {
+                    response: response.clone(),
+                    expires_at,
+                },
+            },
+        );
+        cache.expiry.push_back((expires_at, self.key.clone()));
+        drop(cache);
+
+        self.tx.send_replace(Some(response));
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if matches!(
+            cache.entries.get(&self.key),
+            Some(Entry {
+                state: State::InFlight(_),
+                ..
+            })
+        ) {
+            cache.entries.remove(&self.key);
+        }
+    }
+}
+
+impl<S> HttpService for IdempotencyService<S>
+where
+    S: HttpService + Send,
+    S::ResBody: Send,
+    <S::ResBody as http_body::Body>::Data: Send,
+    <S::ResBody as http_body::Body>::Error: Send,
+{
+    type Error = S::Error;
+    type ResBody = IdempotencyBody<S::ResBody>;
+
+    async fn call(&mut self, req: IncomingRequest) -> Result<http::Response<Self::ResBody>, Self::Error> {
+        let Some(key) = CacheKey::from_request(&req, &self.config) else {
+            return Ok(self.inner.call(req).await?.map(IdempotencyBody::new));
+        };
+
+        let (parts, body) = req.into_parts();
+        let (body, fingerprint) = match super::collect_body_limited(body, self.config.max_body_size).await {
+            Collected::Complete(data, trailers) => {
+                let fingerprint = self.hasher.hash_one((&parts.method, &parts.uri, &data));
+                (BufferedBody::complete(data, trailers), Some(fingerprint))
+            }
+            Collected::Partial(data, rest) => (BufferedBody::partial(data, rest), None),
+            Collected::Failed(data, err) => (BufferedBody::failed(data, err), None),
+        };
+        let req = http::Request::from_parts(parts, IncomingBody::Buffered(Box::new(body)));
+
+        let Some(fingerprint) = fingerprint else {
+            return Ok(self.inner.call(req).await?.map(IdempotencyBody::new));
+        };
+
+        let guard = loop {
+            let mut rx = {
+                let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+                cache.evict_expired(Instant::now());
+
+                match cache.entries.get(&key) {
+                    Some(entry) if entry.fingerprint != fingerprint => return Ok(unprocessable()),
+                    Some(Entry {
+                        state: State::Completed { response, .. },
+                        ..
+                    }) => return Ok(response.to_response(true)),
+                    Some(Entry {
+                        state: State::InFlight(rx),
+                        ..
+                    }) => rx.clone(),
+                    None => {
+                        if !cache.make_room(self.config.max_entries) {
+                            break None;
+                        }
+
+                        let (tx, rx) = watch::channel(None);
+                        cache.entries.insert(
+                            key.clone(),
+                            Entry {
+                                fingerprint,
+                                state: State::InFlight(rx),
+                            },
+                        );
+                        break Some(InFlightGuard {
+                            cache: self.cache.clone(),
+                            key,
+                            fingerprint,
+                            tx,
+                        });
+                    }
+                }
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!("waiting for in-flight request with the same idempotency key");
+
+            // If the sender is dropped the first request did not complete, so we try again.
+            if let Ok(response) = rx.wait_for(Option::is_some).await {
+                let response = response.as_ref().expect("checked by wait_for").clone();
+                return Ok(response.to_response(true));
+            }
+        };
+
+        let Some(guard) = guard else {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("idempotency cache is full of in-flight requests");
+
+            return Ok(self.inner.call(req).await?.map(IdempotencyBody::new));
+        };
+
+        let (parts, body) = self.inner.call(req).await?.into_parts();
+        if parts.status.is_server_error() {
+            return Ok(http::Response::from_parts(parts, IdempotencyBody::new(body)));
+        }
+
+        let (data, trailers) = match super::collect_body_limited(body, self.config.max_body_size).await {
+            Collected::Complete(data, trailers) => (data, trailers),
+            Collected::Partial(data, rest) => {
+                return Ok(http::Response::from_parts(parts, IdempotencyBody::partial(data, rest)));
+            }
+            Collected::Failed(data, err) => {
+                return Ok(http::Response::from_parts(parts, IdempotencyBody::failed(data, err)));
+            }
+        };
+
+        let response = Arc::new(CachedResponse {
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers,
+            body: data,
+            trailers,
+        });
+
+        guard.complete(response.clone(), self.config.ttl);
+
+        Ok(response.to_response(false))
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The response body of an [`IdempotencyService`].
+    ///
+    /// This is either the body of the inner service or a body that was buffered for the cache.
+    pub struct IdempotencyBody<B: http_body::Body> {
+        #[pin]
+        body: Option<B>,
+        rest: Option<Pin<Box<B>>>,
+        data: Option<Bytes>,
+        trailers: Option<http::HeaderMap>,
+        error: Option<B::Error>,
+    }
+}
+
+impl<B: http_body::Body> IdempotencyBody<B> {
+    fn new(body: B) -> Self {
+        Self {
+            body: Some(body),
+            rest: None,
+            data: None,
+            trailers: None,
+            error: None,
+        }
+    }
+
+    fn cached(data: Bytes, trailers: Option<http::HeaderMap>) -> Self {
+        Self {
+            body: None,
+            rest: None,
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers,
+            error: None,
+        }
+    }
+
+    /// A body that was too large to cache, of which `data` was already read.
+    fn partial(data: Bytes, rest: Pin<Box<B>>) -> Self {
+        Self {
+            body: None,
+            rest: Some(rest),
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers: None,
+            error: None,
+        }
+    }
+
+    fn failed(data: Bytes, error: B::Error) -> Self {
+        Self {
+            body: None,
+            rest: None,
+            data: Some(data).filter(|data| !data.is_empty()),
+            trailers: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl<B> Body for IdempotencyBody<B>
+where
+    B: http_body::Body,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+
+        if let Some(body) = this.body.as_pin_mut() {
+            return body
+                .poll_frame(cx)
+                .map_ok(|frame| frame.map_data(|mut data| data.copy_to_bytes(data.remaining())));
+        }
+
+        if let Some(data) = this.data.take() {
+            return Poll::Ready(Some(Ok(http_body::Frame::data(data))));
+        }
+
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Some(Err(error)));
+        }
+
+        if let Some(rest) = this.rest {
+            return rest
+                .as_mut()
+                .poll_frame(cx)
+                .map_ok(|frame| frame.map_data(|mut data| data.copy_to_bytes(data.remaining())));
+        }
+
+        Poll::Ready(this.trailers.take().map(|trailers| Ok(http_body::Frame::trailers(trailers))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.body {
+            Some(body) => body.is_end_stream(),
+            None => {
+                self.data.is_none()
+                    && self.trailers.is_none()
+                    && self.error.is_none()
+                    && self.rest.as_ref().is_none_or(|rest| rest.is_end_stream())
+            }
+        }
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        let len = self.data.as_ref().map_or(0, |data| data.len() as u64);
+        match (&self.body, &self.rest) {
+            (Some(body), _) => body.size_hint(),
+            (None, Some(rest)) => {
+                let rest = rest.size_hint();
+                let mut hint = http_body::SizeHint::new();
+                hint.set_lower(rest.lower() + len);
+                if let Some(upper) = rest.upper() {
+                    hint.set_upper(upper + len);
+                }
+
+                hint
+            }
+            (None, None) => http_body::SizeHint::with_exact(len),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use bytes::Bytes;
+    use http_body::Body;
+
+    use super::{Cache, CacheKey, CachedResponse, Entry, IdempotencyBody, IdempotencyConfig, State};
+    use crate::service::fn_http_service;
+
+    #[test]
+    fn idempotency_service_debug() {
+        let service = super::idempotency_service(
+            fn_http_service(|_| async { Ok::<_, Infallible>(http::Response::new(String::new())) }),
+            IdempotencyConfig::builder().identity(|_| None).build(),
+        );
+        let debug = format!("{service:?}");
+        assert!(debug.starts_with("IdempotencyService { inner: FnHttpService("));
+        assert!(
+            debug.ends_with("config: IdempotencyConfig { ttl: 86400s, max_entries: 10000, max_body_size: 1048576, .. } }")
+        );
+    }
+
+    #[test]
+    fn identity() {
+        let request = |auth: Option<&'static str>| {
+            let mut req = http::Request::new(crate::body::IncomingBody::Buffered(Box::new(
+                crate::body::BufferedBody::complete(Bytes::new(), None),
+            )));
+            if let Some(auth) = auth {
+                req.headers_mut()
+                    .insert(http::header::AUTHORIZATION, http::HeaderValue::from_static(auth));
+            }
+            req
+        };
+
+        let config = IdempotencyConfig::default();
+        assert_eq!(config.identity(&request(None)), Some(String::new()));
+        assert_eq!(config.identity(&request(Some("Bearer a"))), Some("Bearer a".into()));
+
+        let config = IdempotencyConfig::builder()
+            .identity(|req| req.headers().get("x-user").map(|user| user.to_str().unwrap().to_owned()))
+            .build();
+        assert_eq!(config.identity(&request(Some("Bearer a"))), None);
+    }
+
+    fn completed(cache: &mut Cache, key: &str, expires_at: Instant) {
+        let key = CacheKey {
+            identity: String::new(),
+            key: http::HeaderValue::from_str(key).unwrap(),
+        };
+        let response = Arc::new(CachedResponse {
+            status: http::StatusCode::OK,
+            version: http::Version::HTTP_11,
+            headers: http::HeaderMap::new(),
+            body: Bytes::new(),
+            trailers: None,
+        });
+
+        cache.entries.insert(
+            key.clone(),
+            Entry {
+                fingerprint: 0,
+                state: State::Completed { response, expires_at },
+            },
+        );
+        cache.expiry.push_back((expires_at, key));
+    }
+
+    fn contains(cache: &Cache, key: &str) -> bool {
+        cache.entries.keys().any(|entry| entry.key == key)
+    }
+
+    #[test]
+    fn cache_eviction() {
+        let now = Instant::now();
+        let mut cache = Cache::default();
+        completed(&mut cache, "a", now);
+        completed(&mut cache, "b", now + Duration::from_secs(1));
+        completed(&mut cache, "c", now + Duration::from_secs(2));
+
+        cache.evict_expired(now);
+        assert!(!contains(&cache, "a"));
+        assert_eq!(cache.entries.len(), 2);
+
+        // the oldest entry makes room for a new one
+        assert!(cache.make_room(2));
+        assert!(!contains(&cache, "b"));
+        assert!(contains(&cache, "c"));
+
+        // in-flight entries are never evicted
+        let (_tx, rx) = tokio::sync::watch::channel(None);
+        cache.entries.insert(
+            CacheKey {
+                identity: String::new(),
+                key: http::HeaderValue::from_static("d"),
+            },
+            Entry {
+                fingerprint: 0,
+                state: State::InFlight(rx),
+            },
+        );
+        assert!(!cache.make_room(1));
+        assert_eq!(cache.entries.len(), 1);
+        assert!(contains(&cache, "d"));
+    }
+
+    #[tokio::test]
+    async fn idempotency_body_cached() {
+        let mut trailers = http::HeaderMap::new();
+        trailers.insert("test", http::HeaderValue::from_static("test"));
+
+        let mut body = std::pin::pin!(IdempotencyBody::<String>::cached(
+            Bytes::from_static(b"hello"),
+            Some(trailers)
+        ));
+        assert!(!body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(5));
+
+        let frame = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.into_data().unwrap(), Bytes::from_static(b"hello"));
+
+        let frame = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.into_trailers().unwrap().get("test").unwrap(), "test");
+
+        assert!(body.is_end_stream());
+        assert!(std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn idempotency_body_partial() {
+        let rest = Box::pin(IdempotencyBody::<String>::new(String::from("world")));
+        let mut body = std::pin::pin!(IdempotencyBody::partial(Bytes::from_static(b"hello "), rest));
+        assert_eq!(body.size_hint().exact(), Some(11));
+
+        let mut data = Vec::new();
+        while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+            data.extend_from_slice(&frame.unwrap().into_data().unwrap());
+        }
+
+        assert_eq!(data, b"hello world");
+        assert!(body.is_end_stream());
+    }
+
+    #[test]
+    fn idempotency_body_empty() {
+        let body = IdempotencyBody::<String>::cached(Bytes::new(), None);
+        assert!(body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(0));
+    }
+}
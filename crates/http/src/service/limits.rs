@@ -0,0 +1,236 @@
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use super::HttpService;
+use crate::IncomingRequest;
+use crate::body::{ByteCounter, LimitExceeded, LimitReport, TrackedBody};
+
+/// Overrides the request body limits of a [`LimitsService`] for a single request.
+///
+/// Insert this into the request extensions before the request reaches the [`LimitsService`],
+/// e.g. in a routing layer, to use different limits for some routes.
+/// It replaces both the configured size limit and the configured timeout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestBodyLimits {
+    /// The maximum request body size in bytes, `None` for no limit.
+    pub max_size: Option<usize>,
+    /// The maximum time it may take to receive the request body, `None` for no limit.
+    pub timeout: Option<Duration>,
+}
+
+/// A [`HttpService`] that enforces request body limits and counts response bytes.
+///
+/// For every request the limit function is called to determine the maximum request body size,
+/// which allows different limits per route. When it returns `Some(limit)`:
+/// - requests with a `content-length` larger than the limit are rejected early with
+///   `413 Payload Too Large`, without calling the inner service.
+/// - otherwise the request body is limited with [`IncomingBody::with_limit`](crate::body::IncomingBody::with_limit),
+///   so reading more than the limit fails with
+///   [`IncomingBodyError::LengthLimitExceeded`](crate::body::IncomingBodyError::LengthLimitExceeded).
+///
+/// A request body timeout can be set with [`LimitsService::with_request_body_timeout`],
+/// reading the body after the timeout elapsed fails with
+/// [`IncomingBodyError::Timeout`](crate::body::IncomingBodyError::Timeout).
+/// Both limits can be overridden per request with the [`RequestBodyLimits`] extension.
+///
+/// When the inner service exceeds one of the limits while reading the body, its response
+/// (or error) is replaced with an empty `413 Payload Too Large` or `408 Request Timeout` response,
+/// instead of resetting the connection.
+///
+/// The response body is wrapped in a [`TrackedBody`] with a [`ByteCounter`].
+/// The same counter is inserted into the request extensions before calling the inner
+/// service, so it can be used for access logs or metrics once the response has been sent.
+///
+/// This works the same for all backends.
+///
+/// Create by calling [`limits_service`].
+#[derive(Clone)]
+pub struct LimitsService<S, F> {
+    inner: S,
+    request_body_limit: F,
+    request_body_timeout: Option<Duration>,
+}
+
+impl<S, F> LimitsService<S, F> {
+    /// Limit the time it may take to receive a request body.
+    ///
+    /// The timeout starts when the request is passed to the inner service.
+    pub fn with_request_body_timeout(mut self, timeout: Duration) -> Self {
+        self.request_body_timeout = Some(timeout);
+        self
+    }
+}
+
+impl<S: Debug, F> Debug for LimitsService<S, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LimitsService")
+            .field("inner", &self.inner)
+            .field("request_body_limit", &std::any::type_name::<F>())
+            .field("request_body_timeout", &self.request_body_timeout)
+            .finish()
+    }
+}
+
+/// Create a [`LimitsService`] wrapping the given service.
+///
+/// `request_body_limit` returns the maximum request body size in bytes for a given request,
+/// or `None` for no limit.
+///
+/// See [`LimitsService`] for details.
+pub fn limits_service<S, F>(inner: S, request_body_limit: F) -> LimitsService<S, F>
+where
+    S: HttpService,
+    F: Fn(&IncomingRequest) -> Option<usize>,
+{
+    LimitsService {
+        inner,
+        request_body_limit,
+        request_body_timeout: None,
+    }
+}
+
+impl<S, F> HttpService for LimitsService<S, F>
+where
+    S: HttpService + Send,
+    F: Fn(&IncomingRequest) -> Option<usize> + Send,
+{
+    type Error = S::Error;
+    type ResBody = TrackedBody<LimitsBody<S::ResBody>, ByteCounter>;
+
+    async fn call(&mut self, mut req: IncomingRequest) -> Result<http::Response<Self::ResBody>, Self::Error> {
+        let counter = ByteCounter::new();
+
+        let limits = req
+            .extensions()
+            .get::<RequestBodyLimits>()
+            .copied()
+            .unwrap_or_else(|| RequestBodyLimits {
+                max_size: (self.request_body_limit)(&req),
+                timeout: self.request_body_timeout,
+            });
+
+        if let Some(limit) = limits.max_size {
+            let content_length = req
+                .headers()
+                .get(http::header::CONTENT_LENGTH)
+                .and_then(|len| len.to_str().ok()?.parse::<u64>().ok());
+
+            if content_length.is_some_and(|len| len > limit as u64) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(limit, content_length, "request body too large");
+
+                return Ok(error_response(http::StatusCode::PAYLOAD_TOO_LARGE, counter));
+            }
+
+            req = req.map(|body| body.with_limit(limit));
+        }
+
+        if let Some(timeout) = limits.timeout {
+            req = req.map(|body| body.with_timeout(timeout));
+        }
+
+        let report = LimitReport::default();
+        req = req.map(|body| body.report_limits(&report));
+        req.extensions_mut().insert(counter.clone());
+
+        let result = self.inner.call(req).await;
+
+        let status = match report.get() {
+            Some(LimitExceeded::Length) => http::StatusCode::PAYLOAD_TOO_LARGE,
+            Some(LimitExceeded::Timeout) => http::StatusCode::REQUEST_TIMEOUT,
+            None => return Ok(result?.map(|body| TrackedBody::new(LimitsBody::new(body), counter))),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(%status, "request body limit exceeded");
+
+        Ok(error_response(status, counter))
+    }
+}
+
+fn error_response<B>(
+    status: http::StatusCode,
+    counter: ByteCounter,
+) -> http::Response<TrackedBody<LimitsBody<B>, ByteCounter>> {
+    let mut resp = http::Response::new(TrackedBody::new(LimitsBody::empty(), counter));
+    *resp.status_mut() = status;
+    resp
+}
+
+pin_project_lite::pin_project! {
+    /// The response body of a [`LimitsService`].
+    ///
+    /// This is either the body of the inner service or empty if the request was rejected.
+    pub struct LimitsBody<B> {
+        #[pin]
+        body: Option<B>,
+    }
+}
+
+impl<B> LimitsBody<B> {
+    fn new(body: B) -> Self {
+        Self { body: Some(body) }
+    }
+
+    fn empty() -> Self {
+        Self { body: None }
+    }
+}
+
+impl<B> http_body::Body for LimitsBody<B>
+where
+    B: http_body::Body,
+{
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        match self.project().body.as_pin_mut() {
+            Some(body) => body.poll_frame(cx),
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.as_ref().is_none_or(|body| body.is_end_stream())
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        match &self.body {
+            Some(body) => body.size_hint(),
+            None => http_body::SizeHint::with_exact(0),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::convert::Infallible;
+
+    use http_body::Body;
+
+    use super::LimitsBody;
+    use crate::service::fn_http_service;
+
+    #[test]
+    fn limits_service_debug() {
+        let service = super::limits_service(
+            fn_http_service(|_| async { Ok::<_, Infallible>(http::Response::new(String::new())) }),
+            |_| None,
+        );
+        assert!(format!("{service:?}").starts_with("LimitsService { inner: FnHttpService("));
+    }
+
+    #[test]
+    fn limits_body_empty() {
+        let body = LimitsBody::<String>::empty();
+        assert!(body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(0));
+    }
+}
@@ -2,15 +2,23 @@
 use std::future::Future;
 use std::net::SocketAddr;
 
+use bytes::{Buf, Bytes, BytesMut};
+
 use crate::IncomingRequest;
 
+mod cache;
 mod clone_factory;
 mod function;
+mod idempotency;
+mod limits;
 #[cfg(feature = "tower")]
 mod tower_factory;
 
+pub use cache::*;
 pub use clone_factory::*;
 pub use function::*;
+pub use idempotency::*;
+pub use limits::*;
 #[cfg(feature = "tower")]
 pub use tower_factory::*;
 
@@ -72,3 +80,56 @@ pub trait HttpServiceFactory {
     /// `remote_addr` is the address of the connecting remote peer.
     fn new_service(&mut self, remote_addr: SocketAddr) -> impl Future<Output = Result<Self::Service, Self::Error>> + Send;
 }
+
+/// The result of [`collect_body_limited`].
+enum Collected<B: http_body::Body> {
+    /// The whole body was read, with its trailers.
+    Complete(Bytes, Option<http::HeaderMap>),
+    /// The body is larger than the limit. Contains the data read so far and the rest of the body.
+    Partial(Bytes, std::pin::Pin<Box<B>>),
+    /// Reading the body failed after the data was read.
+    Failed(Bytes, B::Error),
+}
+
+/// Reads the body into memory as long as it is not larger than `limit` bytes.
+///
+/// Reading stops as soon as the limit is exceeded, or before reading anything when the size hint of
+/// the body already exceeds it, so the rest can be streamed instead.
+async fn collect_body_limited<B: http_body::Body>(body: B, limit: usize) -> Collected<B> {
+    let mut body = Box::pin(body);
+    let mut data = BytesMut::new();
+    let mut trailers: Option<http::HeaderMap> = None;
+
+    if body.size_hint().lower() > limit as u64 {
+        return Collected::Partial(data.freeze(), body);
+    }
+
+    while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(err) => return Collected::Failed(data.freeze(), err),
+        };
+
+        match frame.into_data() {
+            Ok(mut chunk) => {
+                while chunk.has_remaining() {
+                    let bytes = chunk.chunk();
+                    data.extend_from_slice(bytes);
+                    let len = bytes.len();
+                    chunk.advance(len);
+                }
+
+                if data.len() > limit {
+                    return Collected::Partial(data.freeze(), body);
+                }
+            }
+            Err(frame) => {
+                if let Ok(frame_trailers) = frame.into_trailers() {
+                    trailers.get_or_insert_default().extend(frame_trailers);
+                }
+            }
+        }
+    }
+
+    Collected::Complete(data.freeze(), trailers)
+}
@@ -0,0 +1,166 @@
+//! TLS client authentication helpers.
+//!
+//! Use [`ClientAuth`] to create a [`rustls::ServerConfig`] that requests certificates from clients (mTLS).
+//! Both the hyper and the HTTP/3 backend add [`PeerCertificates`] to the request extensions
+//! whenever the client presented a certificate chain during the handshake.
+//!
+//! ```rust
+//! # fn test() -> Result<(), Box<dyn std::error::Error>> {
+//! # let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+//! use scuffle_http::tls::{ClientAuth, PeerCertificates};
+//!
+//! let cert_chain = rustls_pemfile::certs(&mut &include_bytes!("../../../assets/cert.pem")[..])
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! let key = rustls_pemfile::private_key(&mut &include_bytes!("../../../assets/key.pem")[..])?
+//!     .ok_or("no private key found")?;
+//!
+//! // The CA which signed the client certificates.
+//! let mut roots = rustls::RootCertStore::empty();
+//! for cert in rustls_pemfile::certs(&mut &include_bytes!("../../../assets/client_ca.pem")[..]) {
+//!     roots.add(cert?)?;
+//! }
+//!
+//! let rustls_config = ClientAuth::Required(roots.into())
+//!     .server_config_builder()?
+//!     .with_single_cert(cert_chain, key)?;
+//!
+//! let service = scuffle_http::service::fn_http_service(|req| async move {
+//!     let identity = req
+//!         .extensions()
+//!         .get::<PeerCertificates>()
+//!         .and_then(|certs| certs.dns_names().first().cloned());
+//!
+//!     scuffle_http::Response::builder().body(format!("hello {identity:?}"))
+//! });
+//!
+//! scuffle_http::HttpServer::builder()
+//!     .service_factory(scuffle_http::service::service_clone_factory(service))
+//!     .bind("[::]:443".parse().unwrap())
+//!     .rustls_config(rustls_config)
+//!     .build();
+//! # Ok(())
+//! # }
+//! # test().expect("failed to build server");
+//! ```
+use std::sync::Arc;
+
+use rustls::RootCertStore;
+use rustls::pki_types::CertificateDer;
+use rustls::server::danger::ClientCertVerifier;
+use rustls::server::{VerifierBuilderError, WantsServerCert, WebPkiClientVerifier};
+
+/// How the server should authenticate clients during the TLS handshake.
+#[derive(Debug, Clone)]
+pub enum ClientAuth {
+    /// Clients must present a certificate chain that is valid for one of the given roots.
+    ///
+    /// The handshake fails if the client does not present a certificate.
+    Required(Arc<RootCertStore>),
+    /// Clients are asked for a certificate chain that is valid for one of the given roots.
+    ///
+    /// Clients that do not present a certificate are still accepted,
+    /// but a certificate that is presented must be valid.
+    Optional(Arc<RootCertStore>),
+    /// Use a custom client certificate verifier.
+    Verifier(Arc<dyn ClientCertVerifier>),
+}
+
+impl ClientAuth {
+    /// Creates the client certificate verifier.
+    ///
+    /// The webpki verifiers use the process-default [`rustls::crypto::CryptoProvider`].
+    pub fn verifier(self) -> Result<Arc<dyn ClientCertVerifier>, VerifierBuilderError> {
+        match self {
+            Self::Required(roots) => WebPkiClientVerifier::builder(roots).build(),
+            Self::Optional(roots) => WebPkiClientVerifier::builder(roots).allow_unauthenticated().build(),
+            Self::Verifier(verifier) => Ok(verifier),
+        }
+    }
+
+    /// Creates a [`rustls::ServerConfig`] builder with the client certificate verifier already set.
+    ///
+    /// Finish the config by setting the server certificate,
+    /// e.g. with [`with_single_cert`](rustls::ConfigBuilder::with_single_cert).
+    pub fn server_config_builder(
+        self,
+    ) -> Result<rustls::ConfigBuilder<rustls::ServerConfig, WantsServerCert>, VerifierBuilderError> {
+        Ok(rustls::ServerConfig::builder().with_client_cert_verifier(self.verifier()?))
+    }
+}
+
+/// The certificate chain a client presented during the TLS handshake.
+///
+/// This is added to the request extensions by the server backends
+/// and can be used by services to make authentication decisions.
+///
+/// The chain has already been verified by the configured [`ClientCertVerifier`]
+/// by the time a request reaches the service.
+#[derive(Debug, Clone)]
+pub struct PeerCertificates {
+    chain: Arc<[CertificateDer<'static>]>,
+    dns_names: Arc<[String]>,
+}
+
+impl PeerCertificates {
+    /// Creates a new [`PeerCertificates`] from the given chain, end-entity certificate first.
+    ///
+    /// Returns `None` if the chain is empty.
+    pub fn new(chain: impl Into<Arc<[CertificateDer<'static>]>>) -> Option<Self> {
+        let chain = chain.into();
+        let end_entity = chain.first()?;
+
+        // A custom verifier might accept certificates webpki cannot parse, those simply have no identity.
+        let dns_names = webpki::EndEntityCert::try_from(end_entity)
+            .map(|cert| cert.valid_dns_names().map(String::from).collect())
+            .unwrap_or_default();
+
+        Some(Self { chain, dns_names })
+    }
+
+    /// The full certificate chain, end-entity certificate first.
+    pub fn chain(&self) -> &[CertificateDer<'static>] {
+        &self.chain
+    }
+
+    /// The end-entity certificate of the client.
+    pub fn end_entity(&self) -> &CertificateDer<'static> {
+        &self.chain[0]
+    }
+
+    /// The DNS names from the subject alternative name extension of the end-entity certificate.
+    pub fn dns_names(&self) -> &[String] {
+        &self.dns_names
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::PeerCertificates;
+
+    fn load_certs(path: &str) -> Vec<rustls::pki_types::CertificateDer<'static>> {
+        let file = std::fs::File::open(path).expect("cert not found");
+        rustls_pemfile::certs(&mut std::io::BufReader::new(file))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("failed to load certs")
+    }
+
+    #[test]
+    fn peer_certificates() {
+        assert!(PeerCertificates::new(Vec::new()).is_none());
+
+        let chain = load_certs("../../assets/client_cert.pem");
+        let certs = PeerCertificates::new(chain.clone()).expect("non-empty chain");
+        assert_eq!(certs.chain(), &chain[..]);
+        assert_eq!(certs.end_entity(), &chain[0]);
+        assert_eq!(certs.dns_names(), &["client.scuffle.test".to_string()]);
+
+        // no subject alternative names
+        let certs = PeerCertificates::new(load_certs("../../assets/cert.pem")).expect("non-empty chain");
+        assert!(certs.dns_names().is_empty());
+
+        let certs =
+            PeerCertificates::new(vec![rustls::pki_types::CertificateDer::from(vec![1, 2, 3])]).expect("non-empty chain");
+        assert!(certs.dns_names().is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+//! Per request spans and [W3C trace context](https://www.w3.org/TR/trace-context/) propagation.
+//!
+//! With the `tracing` feature every request is handled in a `request` span with the fields
+//! `http.request.method`, `url.path`, `network.protocol.version`, `http.response.status_code`,
+//! `trace_id`, `span_id` and `parent_id`.
+//!
+//! The trace context of the request is read from the `traceparent` and `tracestate` headers, a new
+//! trace is started if the headers are missing or invalid. The [`TraceContext`] of the request span is
+//! added to the request extensions, so services can pass it on to the services they call.
+//!
+//! ```rust
+//! use scuffle_http::trace::TraceContext;
+//!
+//! let service = scuffle_http::service::fn_http_service(|req| async move {
+//!     let mut upstream = scuffle_http::http::Request::builder().uri("http://upstream/").body(())?;
+//!
+//!     if let Some(context) = req.extensions().get::<TraceContext>() {
+//!         context.inject(upstream.headers_mut());
+//!     }
+//!
+//!     // send the upstream request...
+//!     # let _ = upstream;
+//!     scuffle_http::Response::builder().body("hello".to_string())
+//! });
+//! # let _ = service;
+//! ```
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use tracing::Instrument;
+
+use crate::IncomingRequest;
+use crate::service::HttpService;
+
+/// The `traceparent` header.
+pub const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+/// The `tracestate` header.
+pub const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+const FLAG_SAMPLED: u8 = 0x01;
+
+/// The trace context of a span, as propagated by the `traceparent` and `tracestate` headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: u128,
+    span_id: u64,
+    parent_id: Option<u64>,
+    flags: u8,
+    tracestate: Option<HeaderValue>,
+}
+
+impl TraceContext {
+    /// Starts a new sampled trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: (u128::from(random_id()) << 64) | u128::from(random_id()),
+            span_id: random_id(),
+            parent_id: None,
+            flags: FLAG_SAMPLED,
+            tracestate: None,
+        }
+    }
+
+    /// Reads the trace context of the caller from the `traceparent` and `tracestate` headers.
+    ///
+    /// Returns `None` if there is no valid `traceparent` header. The `tracestate` is passed on
+    /// as is, multiple headers are joined into one.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let mut traceparent = headers.get_all(TRACEPARENT).iter();
+        // multiple traceparent headers are invalid
+        let (Some(traceparent), None) = (traceparent.next(), traceparent.next()) else {
+            return None;
+        };
+
+        let (trace_id, span_id, flags) = parse_traceparent(traceparent.to_str().ok()?)?;
+
+        let tracestate = headers
+            .get_all(TRACESTATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Some(Self {
+            trace_id,
+            span_id,
+            parent_id: None,
+            flags,
+            tracestate: HeaderValue::from_str(&tracestate).ok().filter(|value| !value.is_empty()),
+        })
+    }
+
+    /// Creates the context of a span called by this one, with the same trace id and a new span id.
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: random_id(),
+            parent_id: Some(self.span_id),
+            flags: self.flags,
+            tracestate: self.tracestate.clone(),
+        }
+    }
+
+    /// The id of the trace this span belongs to.
+    pub fn trace_id(&self) -> u128 {
+        self.trace_id
+    }
+
+    /// The id of this span.
+    pub fn span_id(&self) -> u64 {
+        self.span_id
+    }
+
+    /// The id of the span which called this one, `None` if this span started the trace.
+    pub fn parent_id(&self) -> Option<u64> {
+        self.parent_id
+    }
+
+    /// Whether the caller recorded the trace.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & FLAG_SAMPLED != 0
+    }
+
+    /// The vendor specific `tracestate` of the trace.
+    pub fn tracestate(&self) -> Option<&HeaderValue> {
+        self.tracestate.as_ref()
+    }
+
+    /// The `traceparent` header value for requests sent by this span.
+    pub fn traceparent(&self) -> HeaderValue {
+        HeaderValue::try_from(format!("00-{:032x}-{:016x}-{:02x}", self.trace_id, self.span_id, self.flags))
+            .expect("valid header value")
+    }
+
+    /// Adds the `traceparent` and `tracestate` headers for requests sent by this span.
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        headers.insert(TRACEPARENT, self.traceparent());
+        match &self.tracestate {
+            Some(tracestate) => headers.insert(TRACESTATE, tracestate.clone()),
+            None => headers.remove(TRACESTATE),
+        };
+    }
+}
+
+/// Parses `{version}-{trace-id}-{parent-id}-{trace-flags}`.
+fn parse_traceparent(value: &str) -> Option<(u128, u64, u8)> {
+    fn hex(part: &str, len: usize) -> Option<&str> {
+        (part.len() == len && part.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))).then_some(part)
+    }
+
+    let mut parts = value.trim().split('-');
+    let version = hex(parts.next()?, 2)?;
+    let trace_id = u128::from_str_radix(hex(parts.next()?, 32)?, 16).ok()?;
+    let span_id = u64::from_str_radix(hex(parts.next()?, 16)?, 16).ok()?;
+    let flags = u8::from_str_radix(hex(parts.next()?, 2)?, 16).ok()?;
+
+    // Version 00 has exactly four parts, later versions may append more.
+    let valid_version = match version {
+        "00" => parts.next().is_none(),
+        "ff" => false,
+        _ => true,
+    };
+
+    (valid_version && trace_id != 0 && span_id != 0).then_some((trace_id, span_id, flags))
+}
+
+/// A random non-zero id, this does not need to be cryptographically secure.
+fn random_id() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    loop {
+        let mut hasher = std::hash::RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        let id = hasher.finish();
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+/// Creates the span for the request and adds its [`TraceContext`] to the request extensions.
+pub(crate) fn request_span(req: &mut IncomingRequest) -> tracing::Span {
+    let context = match TraceContext::from_headers(req.headers()) {
+        Some(caller) => caller.child(),
+        None => TraceContext::new_root(),
+    };
+
+    let span = tracing::info_span!(
+        "request",
+        http.request.method = %req.method(),
+        url.path = req.uri().path(),
+        network.protocol.version = ?req.version(),
+        http.response.status_code = tracing::field::Empty,
+        trace_id = %format_args!("{:032x}", context.trace_id()),
+        span_id = %format_args!("{:016x}", context.span_id()),
+        parent_id = context.parent_id().map(|id| tracing::field::display(format!("{id:016x}"))),
+    );
+
+    req.extensions_mut().insert(context);
+    span
+}
+
+/// Calls the service in the span of the request.
+pub(crate) async fn call<S: HttpService>(
+    service: &mut S,
+    mut req: IncomingRequest,
+) -> Result<http::Response<S::ResBody>, S::Error> {
+    let span = request_span(&mut req);
+    let res = service.call(req).instrument(span.clone()).await;
+
+    if let Ok(res) = &res {
+        span.record("http.response.status_code", res.status().as_u16());
+    }
+
+    res
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use http::{HeaderMap, HeaderValue};
+
+    use super::{TRACEPARENT, TRACESTATE, TraceContext, parse_traceparent};
+
+    const TRACEPARENT_VALUE: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_traceparent(TRACEPARENT_VALUE),
+            Some((0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7, 0x01))
+        );
+        // future versions may append fields
+        assert_eq!(
+            parse_traceparent("cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-what-the-future-will-be-like"),
+            Some((0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7, 0x00))
+        );
+
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-+bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(parse_traceparent(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn propagate() {
+        let mut headers = HeaderMap::new();
+        headers.insert(TRACEPARENT, HeaderValue::from_static(TRACEPARENT_VALUE));
+        headers.append(TRACESTATE, HeaderValue::from_static("rojo=00f067aa0ba902b7"));
+        headers.append(TRACESTATE, HeaderValue::from_static("congo=t61rcWkgMzE"));
+
+        let caller = TraceContext::from_headers(&headers).unwrap();
+        assert_eq!(caller.trace_id(), 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(caller.span_id(), 0x00f067aa0ba902b7);
+        assert!(caller.is_sampled());
+        assert_eq!(caller.tracestate().unwrap(), "rojo=00f067aa0ba902b7,congo=t61rcWkgMzE");
+
+        let context = caller.child();
+        assert_eq!(context.trace_id(), caller.trace_id());
+        assert_eq!(context.parent_id(), Some(caller.span_id()));
+        assert_ne!(context.span_id(), caller.span_id());
+
+        let mut outgoing = HeaderMap::new();
+        context.inject(&mut outgoing);
+        assert_eq!(
+            outgoing[TRACEPARENT],
+            format!("00-4bf92f3577b34da6a3ce929d0e0e4736-{:016x}-01", context.span_id())
+        );
+        assert_eq!(outgoing[TRACESTATE], "rojo=00f067aa0ba902b7,congo=t61rcWkgMzE");
+        assert_eq!(TraceContext::from_headers(&outgoing).unwrap().span_id(), context.span_id());
+
+        // multiple traceparent headers are invalid
+        headers.append(TRACEPARENT, HeaderValue::from_static(TRACEPARENT_VALUE));
+        assert_eq!(TraceContext::from_headers(&headers), None);
+    }
+
+    #[test]
+    fn new_root() {
+        let a = TraceContext::new_root();
+        let b = TraceContext::new_root();
+        assert_ne!(a.trace_id(), b.trace_id());
+        assert_eq!(a.parent_id(), None);
+        assert!(a.is_sampled());
+
+        let mut headers = HeaderMap::new();
+        a.inject(&mut headers);
+        assert_eq!(TraceContext::from_headers(&headers).unwrap().trace_id(), a.trace_id());
+        assert!(!headers.contains_key(TRACESTATE));
+    }
+}
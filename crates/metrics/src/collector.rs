@@ -299,6 +299,174 @@ macro_rules! impl_updowncounter {
 impl_updowncounter!(i64);
 impl_updowncounter!(f64);
 
+type ObservableCallback<T> = Box<dyn Fn() -> T + Send + Sync>;
+
+/// The callbacks of an observable metric, keyed by their attributes.
+type ObservableCallbacks<T> = std::sync::Arc<parking_lot::Mutex<Vec<(Vec<KeyValue>, ObservableCallback<T>)>>>;
+
+/// An observable metric, whose value is read from callbacks when the metrics
+/// are exported instead of being pushed.
+///
+/// This is useful for values which are already tracked elsewhere, like queue
+/// depths or cache sizes.
+pub struct Observable<I, T> {
+    instrument: I,
+    callbacks: ObservableCallbacks<T>,
+}
+
+impl<I, T> Observable<I, T> {
+    /// Returns the underlying opentelemetry instrument.
+    pub fn instrument(&self) -> &I {
+        &self.instrument
+    }
+}
+
+/// The builder of an [`Observable`] metric.
+pub struct ObservableBuilder<'a, I, T> {
+    builder: opentelemetry::metrics::AsyncInstrumentBuilder<'a, I, T>,
+    callbacks: ObservableCallbacks<T>,
+}
+
+impl<'a, I, T: 'static> ObservableBuilder<'a, I, T> {
+    fn new(builder: opentelemetry::metrics::AsyncInstrumentBuilder<'a, I, T>) -> Self {
+        let callbacks = ObservableCallbacks::<T>::default();
+
+        Self {
+            builder: builder.with_callback({
+                let callbacks = callbacks.clone();
+                move |observer| {
+                    for (attributes, callback) in callbacks.lock().iter() {
+                        observer.observe(callback(), attributes);
+                    }
+                }
+            }),
+            callbacks,
+        }
+    }
+
+    /// Set the description for this metric.
+    pub fn with_description(self, description: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            builder: self.builder.with_description(description),
+            ..self
+        }
+    }
+
+    /// Set the unit for this metric.
+    pub fn with_unit(self, unit: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            builder: self.builder.with_unit(unit),
+            ..self
+        }
+    }
+}
+
+macro_rules! impl_observable {
+    ($t:ty, $value:ty, $func:ident) => {
+        impl private::Sealed for Observable<$t, $value> {
+            type Value = $value;
+        }
+
+        impl IsCollector for Observable<$t, $value> {
+            type Builder<'a> = ObservableBuilder<'a, $t, $value>;
+
+            fn builder(meter: &opentelemetry::metrics::Meter, name: impl Into<Cow<'static, str>>) -> Self::Builder<'_> {
+                ObservableBuilder::new(meter.$func(name))
+            }
+        }
+
+        impl ObservableBuilder<'_, $t, $value> {
+            /// Creates the metric.
+            pub fn build(self) -> Observable<$t, $value> {
+                Observable {
+                    instrument: self.builder.build(),
+                    callbacks: self.callbacks,
+                }
+            }
+        }
+    };
+}
+
+/// An observable counter metric with a `u64` value.
+///
+/// The callbacks return the total, which can only increase.
+pub type ObservableCounterU64 = Observable<opentelemetry::metrics::ObservableCounter<u64>, u64>;
+
+/// An observable counter metric with a `f64` value.
+///
+/// The callbacks return the total, which can only increase.
+pub type ObservableCounterF64 = Observable<opentelemetry::metrics::ObservableCounter<f64>, f64>;
+
+/// An observable gauge metric with a `u64` value.
+///
+/// The callbacks return the value at the current time.
+pub type ObservableGaugeU64 = Observable<opentelemetry::metrics::ObservableGauge<u64>, u64>;
+
+/// An observable gauge metric with a `i64` value.
+///
+/// The callbacks return the value at the current time.
+pub type ObservableGaugeI64 = Observable<opentelemetry::metrics::ObservableGauge<i64>, i64>;
+
+/// An observable gauge metric with a `f64` value.
+///
+/// The callbacks return the value at the current time.
+pub type ObservableGaugeF64 = Observable<opentelemetry::metrics::ObservableGauge<f64>, f64>;
+
+/// An observable updown counter metric with a `i64` value.
+///
+/// The callbacks return the total, which can increase and decrease.
+pub type ObservableUpDownCounterI64 = Observable<opentelemetry::metrics::ObservableUpDownCounter<i64>, i64>;
+
+/// An observable updown counter metric with a `f64` value.
+///
+/// The callbacks return the total, which can increase and decrease.
+pub type ObservableUpDownCounterF64 = Observable<opentelemetry::metrics::ObservableUpDownCounter<f64>, f64>;
+
+impl_observable!(opentelemetry::metrics::ObservableCounter<u64>, u64, u64_observable_counter);
+impl_observable!(opentelemetry::metrics::ObservableCounter<f64>, f64, f64_observable_counter);
+impl_observable!(opentelemetry::metrics::ObservableGauge<u64>, u64, u64_observable_gauge);
+impl_observable!(opentelemetry::metrics::ObservableGauge<i64>, i64, i64_observable_gauge);
+impl_observable!(opentelemetry::metrics::ObservableGauge<f64>, f64, f64_observable_gauge);
+impl_observable!(
+    opentelemetry::metrics::ObservableUpDownCounter<i64>,
+    i64,
+    i64_observable_up_down_counter
+);
+impl_observable!(
+    opentelemetry::metrics::ObservableUpDownCounter<f64>,
+    f64,
+    f64_observable_up_down_counter
+);
+
+impl<I, T> Collector<'_, Observable<I, T>>
+where
+    Observable<I, T>: IsCollector,
+{
+    /// Sets the callback which is called to read the value when the metrics are exported.
+    ///
+    /// Each set of attributes has one callback, so this replaces the previous callback
+    /// registered with the same attributes.
+    ///
+    /// The callback must not create or update observable metrics itself.
+    pub fn set_callback(&self, callback: impl Fn() -> T + Send + Sync + 'static) {
+        let mut callbacks = self.collector.callbacks.lock();
+        let callback: ObservableCallback<T> = Box::new(callback);
+
+        match callbacks.iter_mut().find(|(attributes, _)| *attributes == self.attributes) {
+            Some((_, existing)) => *existing = callback,
+            None => callbacks.push((self.attributes.clone(), callback)),
+        }
+    }
+
+    /// Removes the callback registered with these attributes, so they are no longer reported.
+    pub fn remove_callback(&self) {
+        self.collector
+            .callbacks
+            .lock()
+            .retain(|(attributes, _)| *attributes != self.attributes);
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
@@ -390,6 +558,19 @@ mod tests {
             .expect("Data point not found")
     }
 
+    fn get_gauge_value<'a>(
+        mut data_points: impl Iterator<Item = &'a opentelemetry_sdk::metrics::data::GaugeDataPoint<i64>>,
+        kind: &str,
+    ) -> i64 {
+        data_points
+            .find(|dp| {
+                dp.attributes()
+                    .any(|kv| kv.key.as_str() == "kind" && kv.value.as_str() == kind)
+            })
+            .map(|dp| dp.value())
+            .expect("Gauge data point not found")
+    }
+
     fn get_histogram_sum<'a>(
         mut data_points: impl Iterator<Item = &'a opentelemetry_sdk::metrics::data::HistogramDataPoint<u64>>,
         attr_key: &str,
@@ -750,6 +931,80 @@ mod tests {
         assert_eq!(histogram_data.data_points().next().unwrap().attributes().count(), 0);
     }
 
+    #[test]
+    fn test_observable_metric() {
+        #[crate::metrics(crate_path = "crate")]
+        mod example {
+            use crate::{MetricEnum, ObservableCounterU64, ObservableGaugeI64};
+
+            #[derive(MetricEnum)]
+            #[metrics(crate_path = "crate")]
+            pub enum Kind {
+                Http,
+                Grpc,
+            }
+
+            /// The number of queued requests.
+            #[metrics(unit = "requests")]
+            pub fn queue_depth(kind: Kind) -> ObservableGaugeI64;
+
+            #[metrics(unit = "requests")]
+            pub fn observed_requests() -> ObservableCounterU64;
+        }
+
+        let reader = setup_reader();
+
+        let depth = Arc::new(std::sync::atomic::AtomicI64::new(3));
+        example::queue_depth(example::Kind::Http).set_callback({
+            let depth = depth.clone();
+            move || depth.load(std::sync::atomic::Ordering::Relaxed)
+        });
+        example::queue_depth(example::Kind::Grpc).set_callback(|| 7);
+        example::observed_requests().set_callback(|| 10);
+
+        let metrics = reader.read();
+        let metric = find_metric(&metrics, "example_queue_depth").unwrap();
+        assert_eq!(metric.unit(), "requests");
+        assert_eq!(metric.description(), "The number of queued requests");
+
+        let AggregatedMetrics::I64(MetricData::Gauge(gauge)) = metric.data() else {
+            unreachable!()
+        };
+        assert_eq!(gauge.data_points().count(), 2);
+        assert_eq!(get_gauge_value(gauge.data_points(), "Http"), 3);
+        assert_eq!(get_gauge_value(gauge.data_points(), "Grpc"), 7);
+
+        let metric = find_metric(&metrics, "example_observed_requests").unwrap();
+        let AggregatedMetrics::U64(MetricData::Sum(sum)) = metric.data() else {
+            unreachable!()
+        };
+        assert!(sum.is_monotonic());
+        assert_eq!(sum.data_points().next().unwrap().value(), 10);
+
+        // The value is read on every export and callbacks are replaced per attribute set.
+        depth.store(5, std::sync::atomic::Ordering::Relaxed);
+        example::queue_depth(example::Kind::Grpc).set_callback(|| 1);
+
+        let metrics = reader.read();
+        let metric = find_metric(&metrics, "example_queue_depth").unwrap();
+        let AggregatedMetrics::I64(MetricData::Gauge(gauge)) = metric.data() else {
+            unreachable!()
+        };
+        assert_eq!(gauge.data_points().count(), 2);
+        assert_eq!(get_gauge_value(gauge.data_points(), "Http"), 5);
+        assert_eq!(get_gauge_value(gauge.data_points(), "Grpc"), 1);
+
+        example::queue_depth(example::Kind::Grpc).remove_callback();
+
+        let metrics = reader.read();
+        let metric = find_metric(&metrics, "example_queue_depth").unwrap();
+        let AggregatedMetrics::I64(MetricData::Gauge(gauge)) = metric.data() else {
+            unreachable!()
+        };
+        assert_eq!(gauge.data_points().count(), 1);
+        assert_eq!(get_gauge_value(gauge.data_points(), "Http"), 5);
+    }
+
     #[test]
     fn test_collector_inner() {
         let meter = opentelemetry::global::meter("test_meter");
@@ -0,0 +1,442 @@
+//! Recording metrics to multiple meter providers at once.
+//!
+//! Each [`MeterProvider`] usually owns the readers of a single backend, for
+//! example a Prometheus exporter or a periodic OTLP exporter. The
+//! [`FanOutMeterProvider`] forwards every measurement to all of them, and a
+//! [`MetricFilter`] per provider decides which metrics and attributes each
+//! backend receives.
+//!
+//! ```rust
+//! use opentelemetry_sdk::metrics::SdkMeterProvider;
+//! use scuffle_metrics::fanout::{FanOutMeterProvider, MetricFilter};
+//!
+//! let prometheus = SdkMeterProvider::builder().build();
+//! let otlp = SdkMeterProvider::builder().build();
+//!
+//! let provider = FanOutMeterProvider::builder()
+//!     .with_provider(prometheus, MetricFilter::new())
+//!     .with_provider(otlp, MetricFilter::new().include("http_").deny_attribute("path"))
+//!     .build();
+//!
+//! opentelemetry::global::set_meter_provider(provider);
+//! ```
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use opentelemetry::metrics::{
+    AsyncInstrument, AsyncInstrumentBuilder, Callback, Counter, Gauge, Histogram, HistogramBuilder, InstrumentBuilder,
+    InstrumentProvider, Meter, MeterProvider, ObservableCounter, ObservableGauge, ObservableUpDownCounter, SyncInstrument,
+    UpDownCounter,
+};
+use opentelemetry::{InstrumentationScope, Key, KeyValue};
+
+/// Decides which metrics and attributes are recorded to a meter provider.
+///
+/// An empty filter lets everything through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricFilter {
+    include: Vec<Cow<'static, str>>,
+    exclude: Vec<Cow<'static, str>>,
+    allowed_attributes: Vec<Key>,
+    denied_attributes: Vec<Key>,
+}
+
+impl MetricFilter {
+    /// Creates a new filter which lets every metric and attribute through.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only records metrics whose name starts with the given prefix.
+    ///
+    /// Can be called multiple times, a metric is recorded if it matches any of the prefixes.
+    pub fn include(mut self, prefix: impl Into<Cow<'static, str>>) -> Self {
+        self.include.push(prefix.into());
+        self
+    }
+
+    /// Does not record metrics whose name starts with the given prefix.
+    ///
+    /// Takes precedence over [`MetricFilter::include`].
+    pub fn exclude(mut self, prefix: impl Into<Cow<'static, str>>) -> Self {
+        self.exclude.push(prefix.into());
+        self
+    }
+
+    /// Only records the given attribute keys, all other attributes are dropped.
+    ///
+    /// Can be called multiple times to allow more keys.
+    pub fn allow_attribute(mut self, key: impl Into<Key>) -> Self {
+        self.allowed_attributes.push(key.into());
+        self
+    }
+
+    /// Drops the given attribute key from all measurements.
+    ///
+    /// Takes precedence over [`MetricFilter::allow_attribute`].
+    pub fn deny_attribute(mut self, key: impl Into<Key>) -> Self {
+        self.denied_attributes.push(key.into());
+        self
+    }
+
+    /// Returns true if a metric with the given name is recorded.
+    pub fn matches_name(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|prefix| name.starts_with(&**prefix)))
+            && !self.exclude.iter().any(|prefix| name.starts_with(&**prefix))
+    }
+
+    /// Returns true if an attribute with the given key is recorded.
+    pub fn matches_attribute(&self, key: &Key) -> bool {
+        (self.allowed_attributes.is_empty() || self.allowed_attributes.contains(key))
+            && !self.denied_attributes.contains(key)
+    }
+
+    /// Calls `f` with the attributes this filter lets through.
+    ///
+    /// Only allocates if an attribute is actually dropped.
+    fn with_attributes<R>(&self, attributes: &[KeyValue], f: impl FnOnce(&[KeyValue]) -> R) -> R {
+        if attributes.iter().all(|kv| self.matches_attribute(&kv.key)) {
+            f(attributes)
+        } else {
+            let attributes = attributes
+                .iter()
+                .filter(|kv| self.matches_attribute(&kv.key))
+                .cloned()
+                .collect::<Vec<_>>();
+            f(&attributes)
+        }
+    }
+}
+
+/// A [`MeterProvider`] which records every measurement to multiple meter providers.
+///
+/// Created using [`FanOutMeterProvider::builder`].
+#[derive(Clone)]
+pub struct FanOutMeterProvider {
+    providers: Arc<[(Arc<dyn MeterProvider + Send + Sync>, MetricFilter)]>,
+}
+
+impl std::fmt::Debug for FanOutMeterProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FanOutMeterProvider")
+            .field(
+                "filters",
+                &self.providers.iter().map(|(_, filter)| filter).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl FanOutMeterProvider {
+    /// Creates a new [`FanOutMeterProviderBuilder`].
+    pub fn builder() -> FanOutMeterProviderBuilder {
+        FanOutMeterProviderBuilder::default()
+    }
+}
+
+/// Builder for [`FanOutMeterProvider`].
+#[derive(Default)]
+#[must_use = "call build() to create the provider"]
+pub struct FanOutMeterProviderBuilder {
+    providers: Vec<(Arc<dyn MeterProvider + Send + Sync>, MetricFilter)>,
+}
+
+impl FanOutMeterProviderBuilder {
+    /// Adds a meter provider which records the metrics matching the filter.
+    pub fn with_provider(mut self, provider: impl MeterProvider + Send + Sync + 'static, filter: MetricFilter) -> Self {
+        self.providers.push((Arc::new(provider), filter));
+        self
+    }
+
+    /// Builds the [`FanOutMeterProvider`].
+    pub fn build(self) -> FanOutMeterProvider {
+        FanOutMeterProvider {
+            providers: self.providers.into(),
+        }
+    }
+}
+
+impl MeterProvider for FanOutMeterProvider {
+    fn meter_with_scope(&self, scope: InstrumentationScope) -> Meter {
+        Meter::new(Arc::new(FanOutInstrumentProvider {
+            meters: self
+                .providers
+                .iter()
+                .map(|(provider, filter)| (provider.meter_with_scope(scope.clone()), filter.clone()))
+                .collect(),
+        }))
+    }
+}
+
+struct FanOutInstrumentProvider {
+    meters: Vec<(Meter, MetricFilter)>,
+}
+
+type Recorder<T> = Box<dyn Fn(T, &[KeyValue]) + Send + Sync>;
+
+struct FanOutSyncInstrument<T>(Vec<Recorder<T>>);
+
+impl<T: Copy> SyncInstrument<T> for FanOutSyncInstrument<T> {
+    fn measure(&self, measurement: T, attributes: &[KeyValue]) {
+        self.0.iter().for_each(|record| record(measurement, attributes));
+    }
+}
+
+struct FilteredObserver<'a, T> {
+    inner: &'a dyn AsyncInstrument<T>,
+    filter: &'a MetricFilter,
+}
+
+impl<T> AsyncInstrument<T> for FilteredObserver<'_, T> {
+    fn observe(&self, measurement: T, attributes: &[KeyValue]) {
+        self.filter
+            .with_attributes(attributes, |attributes| self.inner.observe(measurement, attributes));
+    }
+}
+
+macro_rules! sync_instrument {
+    ($fn:ident, $builder:ident, $inst:ident<$ty:ty>, $record:ident $(, $boundaries:ident)?) => {
+        fn $fn(&self, builder: $builder<'_, $inst<$ty>>) -> $inst<$ty> {
+            let recorders = self
+                .meters
+                .iter()
+                .filter(|(_, filter)| filter.matches_name(&builder.name))
+                .map(|(meter, filter)| {
+                    let mut inner = meter.$fn(builder.name.clone());
+                    if let Some(description) = &builder.description {
+                        inner = inner.with_description(description.clone());
+                    }
+                    if let Some(unit) = &builder.unit {
+                        inner = inner.with_unit(unit.clone());
+                    }
+                    $(
+                        if let Some(boundaries) = &builder.$boundaries {
+                            inner = inner.with_boundaries(boundaries.clone());
+                        }
+                    )?
+                    let inner = inner.build();
+                    let filter = filter.clone();
+                    Box::new(move |measurement: $ty, attributes: &[KeyValue]| {
+                        filter.with_attributes(attributes, |attributes| inner.$record(measurement, attributes))
+                    }) as Recorder<$ty>
+                })
+                .collect();
+
+            $inst::new(Arc::new(FanOutSyncInstrument(recorders)))
+        }
+    };
+}
+
+macro_rules! async_instrument {
+    ($fn:ident, $inst:ident<$ty:ty>) => {
+        fn $fn(&self, builder: AsyncInstrumentBuilder<'_, $inst<$ty>, $ty>) -> $inst<$ty> {
+            let callbacks: Arc<[Callback<$ty>]> = builder.callbacks.into();
+
+            for (meter, filter) in self
+                .meters
+                .iter()
+                .filter(|(_, filter)| filter.matches_name(&builder.name))
+            {
+                let callbacks = callbacks.clone();
+                let filter = filter.clone();
+                let mut inner = meter.$fn(builder.name.clone()).with_callback(move |observer| {
+                    let observer = FilteredObserver {
+                        inner: observer,
+                        filter: &filter,
+                    };
+                    callbacks.iter().for_each(|callback| callback(&observer));
+                });
+                if let Some(description) = &builder.description {
+                    inner = inner.with_description(description.clone());
+                }
+                if let Some(unit) = &builder.unit {
+                    inner = inner.with_unit(unit.clone());
+                }
+                inner.build();
+            }
+
+            $inst::new()
+        }
+    };
+}
+
+impl InstrumentProvider for FanOutInstrumentProvider {
+    sync_instrument!(u64_counter, InstrumentBuilder, Counter<u64>, add);
+
+    sync_instrument!(f64_counter, InstrumentBuilder, Counter<f64>, add);
+
+    sync_instrument!(i64_up_down_counter, InstrumentBuilder, UpDownCounter<i64>, add);
+
+    sync_instrument!(f64_up_down_counter, InstrumentBuilder, UpDownCounter<f64>, add);
+
+    sync_instrument!(u64_gauge, InstrumentBuilder, Gauge<u64>, record);
+
+    sync_instrument!(f64_gauge, InstrumentBuilder, Gauge<f64>, record);
+
+    sync_instrument!(i64_gauge, InstrumentBuilder, Gauge<i64>, record);
+
+    sync_instrument!(u64_histogram, HistogramBuilder, Histogram<u64>, record, boundaries);
+
+    sync_instrument!(f64_histogram, HistogramBuilder, Histogram<f64>, record, boundaries);
+
+    async_instrument!(u64_observable_counter, ObservableCounter<u64>);
+
+    async_instrument!(f64_observable_counter, ObservableCounter<f64>);
+
+    async_instrument!(i64_observable_up_down_counter, ObservableUpDownCounter<i64>);
+
+    async_instrument!(f64_observable_up_down_counter, ObservableUpDownCounter<f64>);
+
+    async_instrument!(u64_observable_gauge, ObservableGauge<u64>);
+
+    async_instrument!(i64_observable_gauge, ObservableGauge<i64>);
+
+    async_instrument!(f64_observable_gauge, ObservableGauge<f64>);
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::sync::Arc;
+
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry::{Key, KeyValue};
+    use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData, ResourceMetrics};
+    use opentelemetry_sdk::metrics::reader::MetricReader;
+    use opentelemetry_sdk::metrics::{ManualReader, ManualReaderBuilder, SdkMeterProvider};
+
+    use super::{FanOutMeterProvider, MetricFilter};
+
+    #[derive(Debug, Clone)]
+    struct TestReader(Arc<ManualReader>);
+
+    impl TestReader {
+        fn new() -> Self {
+            Self(Arc::new(ManualReaderBuilder::new().build()))
+        }
+
+        /// Returns the names of the collected metrics and the sorted attribute keys of their data points.
+        fn read(&self) -> Vec<(String, Vec<Vec<Key>>)> {
+            let mut metrics = ResourceMetrics::default();
+            self.0.collect(&mut metrics).expect("collect");
+
+            let mut result = metrics
+                .scope_metrics()
+                .flat_map(|scope| scope.metrics())
+                .map(|metric| {
+                    let attributes = match metric.data() {
+                        AggregatedMetrics::U64(MetricData::Sum(sum)) => {
+                            sum.data_points().map(|dp| keys(dp.attributes())).collect()
+                        }
+                        AggregatedMetrics::I64(MetricData::Gauge(gauge)) => {
+                            gauge.data_points().map(|dp| keys(dp.attributes())).collect()
+                        }
+                        _ => unreachable!(),
+                    };
+                    (metric.name().to_owned(), attributes)
+                })
+                .collect::<Vec<_>>();
+            result.sort();
+            result
+        }
+    }
+
+    fn keys<'a>(attributes: impl Iterator<Item = &'a KeyValue>) -> Vec<Key> {
+        let mut keys = attributes.map(|kv| kv.key.clone()).collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    impl MetricReader for TestReader {
+        fn register_pipeline(&self, pipeline: std::sync::Weak<opentelemetry_sdk::metrics::Pipeline>) {
+            self.0.register_pipeline(pipeline)
+        }
+
+        fn collect(&self, rm: &mut ResourceMetrics) -> opentelemetry_sdk::error::OTelSdkResult {
+            self.0.collect(rm)
+        }
+
+        fn force_flush(&self) -> opentelemetry_sdk::error::OTelSdkResult {
+            self.0.force_flush()
+        }
+
+        fn shutdown_with_timeout(&self, timeout: std::time::Duration) -> opentelemetry_sdk::error::OTelSdkResult {
+            self.0.shutdown_with_timeout(timeout)
+        }
+
+        fn temporality(&self, kind: opentelemetry_sdk::metrics::InstrumentKind) -> opentelemetry_sdk::metrics::Temporality {
+            self.0.temporality(kind)
+        }
+    }
+
+    #[test]
+    fn filter() {
+        let filter = MetricFilter::new();
+        assert!(filter.matches_name("http_requests"));
+        assert!(filter.matches_attribute(&Key::from_static_str("path")));
+
+        let filter = MetricFilter::new()
+            .include("http_")
+            .include("grpc_")
+            .exclude("http_debug_")
+            .allow_attribute("method")
+            .allow_attribute("path")
+            .deny_attribute("path");
+        assert!(filter.matches_name("http_requests"));
+        assert!(filter.matches_name("grpc_requests"));
+        assert!(!filter.matches_name("http_debug_requests"));
+        assert!(!filter.matches_name("db_queries"));
+        assert!(filter.matches_attribute(&Key::from_static_str("method")));
+        assert!(!filter.matches_attribute(&Key::from_static_str("path")));
+        assert!(!filter.matches_attribute(&Key::from_static_str("status")));
+    }
+
+    #[test]
+    fn fan_out() {
+        let all = TestReader::new();
+        let filtered = TestReader::new();
+
+        let provider = FanOutMeterProvider::builder()
+            .with_provider(
+                SdkMeterProvider::builder().with_reader(all.clone()).build(),
+                MetricFilter::new(),
+            )
+            .with_provider(
+                SdkMeterProvider::builder().with_reader(filtered.clone()).build(),
+                MetricFilter::new().include("http_").deny_attribute("path"),
+            )
+            .build();
+
+        let meter = provider.meter("test");
+        let requests = meter.u64_counter("http_requests").build();
+        let queries = meter.u64_counter("db_queries").build();
+        let _connections = meter
+            .i64_observable_gauge("http_connections")
+            .with_callback(|observer| observer.observe(1, &[KeyValue::new("path", "/"), KeyValue::new("method", "GET")]))
+            .build();
+
+        requests.add(1, &[KeyValue::new("path", "/"), KeyValue::new("method", "GET")]);
+        queries.add(1, &[KeyValue::new("table", "users")]);
+
+        let path = Key::from_static_str("path");
+        let method = Key::from_static_str("method");
+        let table = Key::from_static_str("table");
+
+        assert_eq!(
+            all.read(),
+            vec![
+                ("db_queries".to_owned(), vec![vec![table]]),
+                ("http_connections".to_owned(), vec![vec![method.clone(), path.clone()]]),
+                ("http_requests".to_owned(), vec![vec![method.clone(), path]]),
+            ]
+        );
+        assert_eq!(
+            filtered.read(),
+            vec![
+                ("http_connections".to_owned(), vec![vec![method.clone()]]),
+                ("http_requests".to_owned(), vec![vec![method]]),
+            ]
+        );
+    }
+}
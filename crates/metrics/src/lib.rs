@@ -27,6 +27,24 @@
 //! example::request(example::Kind::Http).incr();
 //! ```
 //!
+//! Observable metrics are read from a callback when the metrics are exported,
+//! which is useful for values that are already tracked elsewhere.
+//!
+//! ```rust
+//! #[scuffle_metrics::metrics]
+//! mod queue {
+//!     use scuffle_metrics::ObservableGaugeU64;
+//!
+//!     #[metrics(unit = "jobs")]
+//!     pub fn depth(name: &str) -> ObservableGaugeU64;
+//! }
+//!
+//! let jobs = std::sync::Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+//!
+//! // The callback is called on every export
+//! queue::depth("default").set_callback(move || jobs.lock().unwrap().len() as u64);
+//! ```
+//!
 //! For details see [`metrics!`](metrics).
 //!
 //! ## License
@@ -51,9 +69,12 @@ pub mod prometheus;
 pub mod value;
 
 pub mod collector;
+pub mod fanout;
 
 pub use collector::{
-    CounterF64, CounterU64, GaugeF64, GaugeI64, GaugeU64, HistogramF64, HistogramU64, UpDownCounterF64, UpDownCounterI64,
+    CounterF64, CounterU64, GaugeF64, GaugeI64, GaugeU64, HistogramF64, HistogramU64, ObservableCounterF64,
+    ObservableCounterU64, ObservableGaugeF64, ObservableGaugeI64, ObservableGaugeU64, ObservableUpDownCounterF64,
+    ObservableUpDownCounterI64, UpDownCounterF64, UpDownCounterI64,
 };
 pub use opentelemetry;
 pub use scuffle_metrics_derive::{MetricEnum, metrics};
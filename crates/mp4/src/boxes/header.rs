@@ -16,6 +16,30 @@ impl BoxHeader {
     pub fn new(box_type: [u8; 4]) -> Self {
         Self { box_type }
     }
+
+    /// Size of a box including the basic header, given the size of its payload.
+    pub const fn box_size(primitive_size: u64) -> u64 {
+        let size = primitive_size + 8;
+
+        if size > u32::MAX as u64 { size + 8 } else { size }
+    }
+
+    /// Write the basic header of a box with the given total size.
+    pub fn mux<T: io::Write>(&self, writer: &mut T, size: u64) -> io::Result<()> {
+        if size > u32::MAX as u64 {
+            writer.write_u32::<byteorder::BigEndian>(1)?;
+        } else {
+            writer.write_u32::<byteorder::BigEndian>(size as u32)?;
+        }
+
+        writer.write_all(&self.box_type)?;
+
+        if size > u32::MAX as u64 {
+            writer.write_u64::<byteorder::BigEndian>(size)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Debug for BoxHeader {
@@ -45,8 +69,12 @@ impl BoxHeader {
             // As per spec this means the box extends to the end of the file.
             reader.extract_remaining()
         } else {
-            // We already read 8 bytes, so we need to subtract that from the size.
-            reader.extract_bytes((size - offset) as usize)?
+            // We already read the header, so we need to subtract that from the size.
+            let size = size
+                .checked_sub(offset)
+                .and_then(|size| usize::try_from(size).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid box size"))?;
+            reader.extract_bytes(size)?
         };
 
         Ok((Self { box_type }, data))
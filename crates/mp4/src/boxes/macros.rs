@@ -4,14 +4,7 @@ macro_rules! match_helper {
             $(
                 Self::$name(box_) => box_.size(),
             )*
-            Self::Unknown((_, data)) => {
-                let size = data.len() as u64 + 8;
-                if size > u32::MAX as u64 {
-                    size + 8
-                } else {
-                    size
-                }
-            }
+            Self::Unknown((_, data)) => BoxHeader::box_size(data.len() as u64),
         }
     };
     ([write] $expr:expr, $writer:expr, $($name:tt,)*) => {
@@ -20,16 +13,7 @@ macro_rules! match_helper {
                 Self::$name(box_) => box_.mux($writer)?,
             )*
             Self::Unknown((header, data)) => {
-                let size = data.len() as u64 + 8;
-                if size > u32::MAX as u64 {
-                    $writer.write_u32::<byteorder::BigEndian>(1)?;
-                } else {
-                    $writer.write_u32::<byteorder::BigEndian>(size as u32)?;
-                }
-                $writer.write_all(&header.box_type)?;
-                if size > u32::MAX as u64 {
-                    $writer.write_u64::<byteorder::BigEndian>(size)?;
-                }
+                header.mux($writer, BoxHeader::box_size(data.len() as u64))?;
                 $writer.write_all(data)?;
             }
         }
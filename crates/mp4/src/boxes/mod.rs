@@ -1,7 +1,6 @@
 use std::fmt::Debug;
 use std::io;
 
-use byteorder::WriteBytesExt;
 use bytes::Bytes;
 use paste::paste;
 
@@ -12,6 +11,11 @@ pub mod types;
 #[macro_use]
 mod macros;
 
+/// The maximum length of a null-terminated string in a box, like the name of a `hdlr` box.
+///
+/// The strings are read byte by byte until the terminator, this bounds the work done on corrupt input.
+pub(crate) const MAX_STRING_LEN: usize = 4096;
+
 use header::BoxHeader;
 pub use traits::BoxType;
 
@@ -28,16 +32,19 @@ use crate::boxes::types::dinf::Dinf;
 use crate::boxes::types::dref::Dref;
 use crate::boxes::types::edts::Edts;
 use crate::boxes::types::elst::Elst;
+use crate::boxes::types::emsg::Emsg;
 use crate::boxes::types::esds::Esds;
 use crate::boxes::types::ftyp::Ftyp;
 use crate::boxes::types::hdlr::Hdlr;
 use crate::boxes::types::hev1::Hev1;
 use crate::boxes::types::hmhd::Hmhd;
 use crate::boxes::types::hvcc::HvcC;
+use crate::boxes::types::ilst::Ilst;
 use crate::boxes::types::mdat::Mdat;
 use crate::boxes::types::mdhd::Mdhd;
 use crate::boxes::types::mdia::Mdia;
 use crate::boxes::types::mehd::Mehd;
+use crate::boxes::types::meta::Meta;
 use crate::boxes::types::mfhd::Mfhd;
 use crate::boxes::types::minf::Minf;
 use crate::boxes::types::moof::Moof;
@@ -70,6 +77,7 @@ use crate::boxes::types::traf::Traf;
 use crate::boxes::types::trak::Trak;
 use crate::boxes::types::trex::Trex;
 use crate::boxes::types::trun::Trun;
+use crate::boxes::types::udta::Udta;
 use crate::boxes::types::url::Url;
 use crate::boxes::types::vmhd::Vmhd;
 
@@ -84,5 +92,6 @@ impl_box!(
     Url, Avc1, Clap, Pasp, AvcC, Btrt,
     Mp4a, Esds, Moof, Mfhd, Traf, Tfhd,
     Tfdt, Trun, Mdat, Av01, Av1C, Colr,
-    Hev1, HvcC, Opus,
+    Hev1, HvcC, Opus, Udta, Meta, Ilst,
+    Emsg,
 );
@@ -1,6 +1,5 @@
 use std::io;
 
-use byteorder::WriteBytesExt;
 use bytes::Bytes;
 
 use super::header::BoxHeader;
@@ -23,31 +22,14 @@ pub trait BoxType {
     fn mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
         self.validate()?;
 
-        let size = self.size();
-        if size > u32::MAX as u64 {
-            writer.write_u32::<byteorder::BigEndian>(1)?;
-        } else {
-            writer.write_u32::<byteorder::BigEndian>(size as u32)?;
-        }
-
-        writer.write_all(&Self::NAME)?;
-
-        if size > u32::MAX as u64 {
-            writer.write_u64::<byteorder::BigEndian>(size)?;
-        }
+        BoxHeader::new(Self::NAME).mux(writer, self.size())?;
 
         self.primitive_mux(writer)
     }
 
     /// Size of the box including the basic header.
     fn size(&self) -> u64 {
-        let primitive_size = self.primitive_size() + 8;
-
-        if primitive_size > u32::MAX as u64 {
-            primitive_size + 8
-        } else {
-            primitive_size
-        }
+        BoxHeader::box_size(self.primitive_size())
     }
 
     /// Validate the box.
@@ -49,14 +49,12 @@ impl Av01 {
         }
 
         let seq_obu = SequenceHeaderObu::parse(header, &mut io::Cursor::new(data))?;
-        let op_point = &seq_obu.operating_points[0];
-
         Ok(VideoCodec::Av1 {
             profile: seq_obu.seq_profile,
-            level: op_point.seq_level_idx,
-            tier: op_point.seq_tier,
+            level: seq_obu.seq_level_idx_0(),
+            tier: seq_obu.seq_tier_0(),
             depth: seq_obu.color_config.bit_depth as u8,
-            monochrome: seq_obu.color_config.mono_chrome,
+            monochrome: seq_obu.is_monochrome(),
             sub_sampling_x: seq_obu.color_config.subsampling_x,
             sub_sampling_y: seq_obu.color_config.subsampling_y,
             color_primaries: seq_obu.color_config.color_primaries,
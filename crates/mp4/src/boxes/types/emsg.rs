@@ -0,0 +1,163 @@
+use std::io::{
+    Read, {self},
+};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use bytes::{Buf, Bytes};
+use scuffle_bytes_util::StringReader;
+
+use crate::boxes::MAX_STRING_LEN;
+use crate::boxes::header::{BoxHeader, FullBoxHeader};
+use crate::boxes::traits::BoxType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Event Message Box
+/// ISO/IEC 23009-1:2022(E) - 5.10.3.3
+pub struct Emsg {
+    pub header: FullBoxHeader,
+    pub scheme_id_uri: String,
+    pub value: String,
+    pub timescale: u32,
+    /// For version 0 this is the presentation time delta relative to the
+    /// earliest presentation time of the segment, for version 1 this is the
+    /// absolute presentation time.
+    pub presentation_time: u64,
+    pub event_duration: u32,
+    pub id: u32,
+    pub message_data: Bytes,
+}
+
+impl Emsg {
+    /// Creates a version 1 event message with an absolute presentation time.
+    pub fn new(
+        scheme_id_uri: String,
+        value: String,
+        timescale: u32,
+        presentation_time: u64,
+        event_duration: u32,
+        id: u32,
+        message_data: Bytes,
+    ) -> Self {
+        Self {
+            header: FullBoxHeader::new(Self::NAME, 1, 0),
+            scheme_id_uri,
+            value,
+            timescale,
+            presentation_time,
+            event_duration,
+            id,
+            message_data,
+        }
+    }
+}
+
+const STRINGS: StringReader = StringReader::utf8().max_len(MAX_STRING_LEN);
+
+impl BoxType for Emsg {
+    const NAME: [u8; 4] = *b"emsg";
+
+    fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+        let mut reader = io::Cursor::new(data);
+
+        let header = FullBoxHeader::demux(header, &mut reader)?;
+
+        let (scheme_id_uri, value, timescale, presentation_time, event_duration, id) = if header.version == 1 {
+            let timescale = reader.read_u32::<BigEndian>()?;
+            let presentation_time = reader.read_u64::<BigEndian>()?;
+            let event_duration = reader.read_u32::<BigEndian>()?;
+            let id = reader.read_u32::<BigEndian>()?;
+            let scheme_id_uri = STRINGS.read_null_terminated(&mut reader)?.to_string();
+            let value = STRINGS.read_null_terminated(&mut reader)?.to_string();
+
+            (scheme_id_uri, value, timescale, presentation_time, event_duration, id)
+        } else {
+            let scheme_id_uri = STRINGS.read_null_terminated(&mut reader)?.to_string();
+            let value = STRINGS.read_null_terminated(&mut reader)?.to_string();
+            let timescale = reader.read_u32::<BigEndian>()?;
+            let presentation_time = reader.read_u32::<BigEndian>()? as u64;
+            let event_duration = reader.read_u32::<BigEndian>()?;
+            let id = reader.read_u32::<BigEndian>()?;
+
+            (scheme_id_uri, value, timescale, presentation_time, event_duration, id)
+        };
+
+        let mut message_data = vec![0; reader.remaining()];
+        reader.read_exact(&mut message_data)?;
+
+        Ok(Self {
+            header,
+            scheme_id_uri,
+            value,
+            timescale,
+            presentation_time,
+            event_duration,
+            id,
+            message_data: message_data.into(),
+        })
+    }
+
+    fn primitive_size(&self) -> u64 {
+        self.header.size()
+        + self.scheme_id_uri.len() as u64 + 1 // scheme_id_uri + null terminator
+        + self.value.len() as u64 + 1 // value + null terminator
+        + 4 // timescale
+        + if self.header.version == 1 { 8 } else { 4 } // presentation_time
+        + 4 // event_duration
+        + 4 // id
+        + self.message_data.len() as u64 // message_data
+    }
+
+    fn primitive_mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        self.header.mux(writer)?;
+
+        if self.header.version == 1 {
+            writer.write_u32::<BigEndian>(self.timescale)?;
+            writer.write_u64::<BigEndian>(self.presentation_time)?;
+            writer.write_u32::<BigEndian>(self.event_duration)?;
+            writer.write_u32::<BigEndian>(self.id)?;
+            writer.write_all(self.scheme_id_uri.as_bytes())?;
+            writer.write_u8(0)?;
+            writer.write_all(self.value.as_bytes())?;
+            writer.write_u8(0)?;
+        } else {
+            writer.write_all(self.scheme_id_uri.as_bytes())?;
+            writer.write_u8(0)?;
+            writer.write_all(self.value.as_bytes())?;
+            writer.write_u8(0)?;
+            writer.write_u32::<BigEndian>(self.timescale)?;
+            writer.write_u32::<BigEndian>(self.presentation_time as u32)?;
+            writer.write_u32::<BigEndian>(self.event_duration)?;
+            writer.write_u32::<BigEndian>(self.id)?;
+        }
+
+        writer.write_all(&self.message_data)?;
+
+        Ok(())
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        if self.header.version > 1 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "emsg version must be 0 or 1"));
+        }
+
+        if self.header.flags != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "emsg flags must be 0"));
+        }
+
+        if self.header.version == 0 && self.presentation_time > u32::MAX as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "emsg presentation_time_delta must be less than 2^32",
+            ));
+        }
+
+        if self.scheme_id_uri.contains('\0') || self.value.contains('\0') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "emsg scheme_id_uri and value must not contain null bytes",
+            ));
+        }
+
+        Ok(())
+    }
+}
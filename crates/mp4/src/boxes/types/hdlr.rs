@@ -4,7 +4,9 @@ use std::io::{
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use bytes::Bytes;
+use scuffle_bytes_util::StringReader;
 
+use crate::boxes::MAX_STRING_LEN;
 use crate::boxes::header::{BoxHeader, FullBoxHeader};
 use crate::boxes::traits::BoxType;
 
@@ -39,6 +41,8 @@ pub enum HandlerType {
     Soun,
     Hint,
     Meta,
+    /// iTunes-style metadata, used by the `hdlr` of a `meta` box containing an `ilst`.
+    Mdir,
     Unknown([u8; 4]),
 }
 
@@ -49,6 +53,7 @@ impl HandlerType {
             Self::Soun => *b"soun",
             Self::Hint => *b"hint",
             Self::Meta => *b"meta",
+            Self::Mdir => *b"mdir",
             Self::Unknown(b) => *b,
         }
     }
@@ -61,6 +66,7 @@ impl From<[u8; 4]> for HandlerType {
             b"soun" => Self::Soun,
             b"hint" => Self::Hint,
             b"meta" => Self::Meta,
+            b"mdir" => Self::Mdir,
             _ => Self::Unknown(v),
         }
     }
@@ -84,15 +90,12 @@ impl BoxType for Hdlr {
             *v = reader.read_u32::<BigEndian>()?;
         }
 
-        let mut name = String::new();
-        loop {
-            let c = reader.read_u8()?;
-            if c == 0 {
-                break;
-            }
-
-            name.push(c as char);
-        }
+        // The name is a null-terminated UTF-8 string, however some muxers omit the terminator.
+        let name = StringReader::utf8()
+            .max_len(MAX_STRING_LEN)
+            .allow_unterminated()
+            .read_null_terminated(&mut reader)?
+            .to_string();
 
         Ok(Self {
             header,
@@ -137,7 +140,8 @@ impl BoxType for Hdlr {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "hdlr flags must be 0"));
         }
 
-        if self.reserved != [0; 3] {
+        // The iTunes metadata handler stores a vendor code in the reserved fields.
+        if self.reserved != [0; 3] && self.handler_type != HandlerType::Mdir {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "hdlr reserved must be 0"));
         }
 
@@ -0,0 +1,259 @@
+use std::io;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use bytes::{Buf, Bytes};
+use scuffle_bytes_util::BytesCursorExt;
+
+use crate::boxes::DynBox;
+use crate::boxes::header::BoxHeader;
+use crate::boxes::traits::BoxType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Metadata Item List Box
+/// iTunes-style metadata, as found in `moov/udta/meta/ilst`.
+pub struct Ilst {
+    pub header: BoxHeader,
+    pub items: Vec<IlstItem>,
+}
+
+impl Ilst {
+    pub fn new(items: Vec<IlstItem>) -> Self {
+        Self {
+            header: BoxHeader::new(Self::NAME),
+            items,
+        }
+    }
+
+    /// Returns the first item with the given key.
+    pub fn get(&self, key: [u8; 4]) -> Option<&IlstItem> {
+        self.items.iter().find(|item| item.header.box_type == key)
+    }
+
+    /// Returns the text value of the first item with the given key.
+    pub fn text(&self, key: [u8; 4]) -> Option<&str> {
+        self.get(key).and_then(|item| item.text())
+    }
+
+    /// Sets a text item, replacing any existing items with the same key.
+    pub fn set_text(&mut self, key: [u8; 4], value: impl Into<String>) {
+        let mut item = Some(IlstItem::text_item(key, value));
+
+        self.items.retain_mut(|existing| {
+            if existing.header.box_type != key {
+                return true;
+            }
+
+            match item.take() {
+                Some(item) => {
+                    *existing = item;
+                    true
+                }
+                None => false,
+            }
+        });
+
+        if let Some(item) = item {
+            self.items.push(item);
+        }
+    }
+}
+
+impl BoxType for Ilst {
+    const NAME: [u8; 4] = *b"ilst";
+
+    fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+        let mut reader = io::Cursor::new(data);
+
+        let mut items = Vec::new();
+
+        while reader.has_remaining() {
+            let (header, data) = BoxHeader::demux(&mut reader)?;
+            items.push(IlstItem::demux(header, data)?);
+        }
+
+        Ok(Self { header, items })
+    }
+
+    fn primitive_size(&self) -> u64 {
+        self.items.iter().map(|item| item.size()).sum()
+    }
+
+    fn primitive_mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        for item in &self.items {
+            item.mux(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A single metadata item in an [`Ilst`] box.
+///
+/// The box type of the item is the key of the metadata, e.g. `©nam` for the title.
+pub struct IlstItem {
+    pub header: BoxHeader,
+    pub data: Vec<IlstData>,
+    /// Other child boxes, e.g. the `mean` and `name` boxes of `----` items.
+    pub unknown: Vec<DynBox>,
+}
+
+impl IlstItem {
+    /// The album of the content.
+    pub const ALBUM: [u8; 4] = *b"\xa9alb";
+    /// The artist of the content.
+    pub const ARTIST: [u8; 4] = *b"\xa9ART";
+    /// A comment about the content.
+    pub const COMMENT: [u8; 4] = *b"\xa9cmt";
+    /// The composer / writer of the content.
+    pub const COMPOSER: [u8; 4] = *b"\xa9wrt";
+    /// The release date of the content.
+    pub const DATE: [u8; 4] = *b"\xa9day";
+    /// The tool used to encode the content.
+    pub const ENCODER: [u8; 4] = *b"\xa9too";
+    /// The genre of the content.
+    pub const GENRE: [u8; 4] = *b"\xa9gen";
+    /// The title of the content.
+    pub const TITLE: [u8; 4] = *b"\xa9nam";
+
+    pub fn new(key: [u8; 4], data: Vec<IlstData>) -> Self {
+        Self {
+            header: BoxHeader::new(key),
+            data,
+            unknown: Vec::new(),
+        }
+    }
+
+    /// Creates an item with a single UTF-8 value.
+    pub fn text_item(key: [u8; 4], value: impl Into<String>) -> Self {
+        Self::new(key, vec![IlstData::text(value)])
+    }
+
+    /// Returns the first UTF-8 value of the item.
+    pub fn text(&self) -> Option<&str> {
+        self.data.iter().find_map(|data| data.as_text())
+    }
+
+    pub fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+        let mut reader = io::Cursor::new(data);
+
+        let mut data = Vec::new();
+        let mut unknown = Vec::new();
+
+        while reader.has_remaining() {
+            let (header, payload) = BoxHeader::demux(&mut reader)?;
+
+            if header.box_type == IlstData::NAME {
+                data.push(IlstData::demux(payload)?);
+            } else {
+                unknown.push(DynBox::Unknown((header, payload)));
+            }
+        }
+
+        Ok(Self { header, data, unknown })
+    }
+
+    /// Size of the item including the basic header.
+    pub fn size(&self) -> u64 {
+        let primitive_size =
+            self.data.iter().map(|d| d.size()).sum::<u64>() + self.unknown.iter().map(|b| b.size()).sum::<u64>();
+
+        BoxHeader::box_size(primitive_size)
+    }
+
+    pub fn mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        self.header.mux(writer, self.size())?;
+
+        // The `mean` and `name` boxes of `----` items must precede the data.
+        for b in &self.unknown {
+            b.mux(writer)?;
+        }
+
+        for data in &self.data {
+            data.mux(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The value of an [`IlstItem`], stored in a `data` box.
+pub struct IlstData {
+    /// The well-known type of the value, e.g. [`IlstData::UTF8`].
+    pub data_type: u32,
+    /// The locale of the value, 0 means the default locale.
+    pub locale: u32,
+    pub value: Bytes,
+}
+
+impl IlstData {
+    /// A big endian signed integer of 1, 2, 3, 4 or 8 bytes.
+    pub const BE_SIGNED_INT: u32 = 21;
+    /// A big endian unsigned integer of 1, 2, 3, 4 or 8 bytes.
+    pub const BE_UNSIGNED_INT: u32 = 22;
+    /// Binary data without a specific type.
+    pub const BINARY: u32 = 0;
+    /// A JPEG image.
+    pub const JPEG: u32 = 13;
+    pub const NAME: [u8; 4] = *b"data";
+    /// A PNG image.
+    pub const PNG: u32 = 14;
+    /// UTF-8 text without a null terminator.
+    pub const UTF8: u32 = 1;
+
+    pub fn new(data_type: u32, value: Bytes) -> Self {
+        Self {
+            data_type,
+            locale: 0,
+            value,
+        }
+    }
+
+    /// Creates a UTF-8 value.
+    pub fn text(value: impl Into<String>) -> Self {
+        Self::new(Self::UTF8, Bytes::from(value.into()))
+    }
+
+    /// Returns the value as a string if it is a valid UTF-8 value.
+    pub fn as_text(&self) -> Option<&str> {
+        if self.data_type != Self::UTF8 {
+            return None;
+        }
+
+        std::str::from_utf8(&self.value).ok()
+    }
+
+    pub fn demux(data: Bytes) -> io::Result<Self> {
+        let mut reader = io::Cursor::new(data);
+
+        let data_type = reader.read_u32::<BigEndian>()?;
+        let locale = reader.read_u32::<BigEndian>()?;
+        let value = reader.extract_remaining();
+
+        Ok(Self {
+            data_type,
+            locale,
+            value,
+        })
+    }
+
+    /// Size of the data box including the basic header.
+    pub fn size(&self) -> u64 {
+        BoxHeader::box_size(
+            4 // data_type
+            + 4 // locale
+            + self.value.len() as u64,
+        )
+    }
+
+    pub fn mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        BoxHeader::new(Self::NAME).mux(writer, self.size())?;
+
+        writer.write_u32::<BigEndian>(self.data_type)?;
+        writer.write_u32::<BigEndian>(self.locale)?;
+        writer.write_all(&self.value)?;
+
+        Ok(())
+    }
+}
@@ -38,6 +38,34 @@ impl Mdhd {
             pre_defined: 0,
         }
     }
+
+    /// Returns the ISO-639-2/T language code of the media, e.g. `*b"eng"`.
+    ///
+    /// The language is packed as three 5-bit values, each being the difference
+    /// between the character and `0x60`.
+    pub fn language_code(&self) -> [u8; 3] {
+        [
+            ((self.language >> 10) & 0x1f) as u8 + 0x60,
+            ((self.language >> 5) & 0x1f) as u8 + 0x60,
+            (self.language & 0x1f) as u8 + 0x60,
+        ]
+    }
+
+    /// Sets the ISO-639-2/T language code of the media, e.g. `*b"eng"`.
+    ///
+    /// Returns an error if the code contains characters other than lowercase ascii letters.
+    pub fn set_language_code(&mut self, code: [u8; 3]) -> io::Result<()> {
+        if !code.iter().all(|c| c.is_ascii_lowercase()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "mdhd language must be 3 lowercase ascii letters",
+            ));
+        }
+
+        self.language = code.iter().fold(0, |language, c| (language << 5) | (c - 0x60) as u16);
+
+        Ok(())
+    }
 }
 
 impl BoxType for Mdhd {
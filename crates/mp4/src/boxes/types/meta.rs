@@ -0,0 +1,139 @@
+use std::io;
+
+use bytes::{Buf, Bytes};
+
+use super::hdlr::{HandlerType, Hdlr};
+use super::ilst::Ilst;
+use crate::boxes::DynBox;
+use crate::boxes::header::{BoxHeader, FullBoxHeader};
+use crate::boxes::traits::BoxType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// Meta Box
+/// ISO/IEC 14496-12:2022(E) - 8.11.1
+pub struct Meta {
+    pub header: FullBoxHeader,
+    /// QuickTime files store `meta` as a plain box without a version and flags.
+    /// The version and flags of the header are not written when this is set.
+    pub quicktime: bool,
+    pub hdlr: Hdlr,
+    pub ilst: Option<Ilst>,
+    pub unknown: Vec<DynBox>,
+}
+
+impl Meta {
+    pub fn new(hdlr: Hdlr, ilst: Option<Ilst>) -> Self {
+        Self {
+            header: FullBoxHeader::new(Self::NAME, 0, 0),
+            quicktime: false,
+            hdlr,
+            ilst,
+            unknown: Vec::new(),
+        }
+    }
+
+    /// Creates a meta box carrying iTunes-style metadata, with a `mdir` handler
+    /// as written by most muxers.
+    pub fn new_itunes(ilst: Ilst) -> Self {
+        let mut hdlr = Hdlr::new(HandlerType::Mdir, String::new());
+        hdlr.reserved[0] = u32::from_be_bytes(*b"appl");
+
+        Self::new(hdlr, Some(ilst))
+    }
+}
+
+impl Meta {
+    /// Returns true if the payload starts with a child box instead of a version and flags,
+    /// which is how QuickTime writes the box.
+    fn is_quicktime(data: &[u8]) -> bool {
+        matches!(data.get(4..8), Some(b"hdlr" | b"keys" | b"ilst" | b"free"))
+    }
+}
+
+impl BoxType for Meta {
+    const NAME: [u8; 4] = *b"meta";
+
+    fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+        let quicktime = Self::is_quicktime(&data);
+        let mut reader = io::Cursor::new(data);
+
+        let header = if quicktime {
+            FullBoxHeader {
+                header,
+                version: 0,
+                flags: 0,
+            }
+        } else {
+            FullBoxHeader::demux(header, &mut reader)?
+        };
+
+        let mut hdlr = None;
+        let mut ilst = None;
+        let mut unknown = Vec::new();
+
+        while reader.has_remaining() {
+            let dyn_box = DynBox::demux(&mut reader)?;
+
+            match dyn_box {
+                DynBox::Hdlr(b) => {
+                    hdlr = Some(*b);
+                }
+                DynBox::Ilst(b) => {
+                    ilst = Some(*b);
+                }
+                _ => {
+                    unknown.push(dyn_box);
+                }
+            }
+        }
+
+        let hdlr = hdlr.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "meta box is missing hdlr box"))?;
+
+        Ok(Self {
+            header,
+            quicktime,
+            hdlr,
+            ilst,
+            unknown,
+        })
+    }
+
+    fn primitive_size(&self) -> u64 {
+        let header_size = if self.quicktime { 0 } else { self.header.size() };
+
+        header_size
+            + self.hdlr.size()
+            + self.ilst.as_ref().map(|b| b.size()).unwrap_or(0)
+            + self.unknown.iter().map(|b| b.size()).sum::<u64>()
+    }
+
+    fn primitive_mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        if !self.quicktime {
+            self.header.mux(writer)?;
+        }
+
+        self.hdlr.mux(writer)?;
+
+        if let Some(ilst) = &self.ilst {
+            ilst.mux(writer)?;
+        }
+
+        for b in &self.unknown {
+            b.mux(writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        if self.header.version != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta version must be 0"));
+        }
+
+        if self.header.flags != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta flags must be 0"));
+        }
+
+        Ok(())
+    }
+}
@@ -11,16 +11,19 @@ pub mod dinf;
 pub mod dref;
 pub mod edts;
 pub mod elst;
+pub mod emsg;
 pub mod esds;
 pub mod ftyp;
 pub mod hdlr;
 pub mod hev1;
 pub mod hmhd;
 pub mod hvcc;
+pub mod ilst;
 pub mod mdat;
 pub mod mdhd;
 pub mod mdia;
 pub mod mehd;
+pub mod meta;
 pub mod mfhd;
 pub mod minf;
 pub mod moof;
@@ -53,5 +56,6 @@ pub mod traf;
 pub mod trak;
 pub mod trex;
 pub mod trun;
+pub mod udta;
 pub mod url;
 pub mod vmhd;
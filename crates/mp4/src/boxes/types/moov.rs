@@ -5,6 +5,7 @@ use bytes::{Buf, Bytes};
 use super::mvex::Mvex;
 use super::mvhd::Mvhd;
 use super::trak::Trak;
+use super::udta::Udta;
 use crate::boxes::DynBox;
 use crate::boxes::header::BoxHeader;
 use crate::boxes::traits::BoxType;
@@ -17,6 +18,7 @@ pub struct Moov {
     pub mvhd: Mvhd,
     pub traks: Vec<Trak>,
     pub mvex: Option<Mvex>,
+    pub udta: Option<Udta>,
     pub unknown: Vec<DynBox>,
 }
 
@@ -27,6 +29,7 @@ impl Moov {
             mvhd,
             traks,
             mvex,
+            udta: None,
             unknown: Vec::new(),
         }
     }
@@ -41,6 +44,7 @@ impl BoxType for Moov {
         let mut traks = Vec::new();
         let mut mvex = None;
         let mut mvhd = None;
+        let mut udta = None;
         let mut unknown = Vec::new();
 
         while reader.has_remaining() {
@@ -56,6 +60,9 @@ impl BoxType for Moov {
                 DynBox::Mvex(b) => {
                     mvex = Some(*b);
                 }
+                DynBox::Udta(b) => {
+                    udta = Some(*b);
+                }
                 _ => {
                     unknown.push(dyn_box);
                 }
@@ -69,6 +76,7 @@ impl BoxType for Moov {
             mvhd,
             traks,
             mvex,
+            udta,
             unknown,
         })
     }
@@ -77,6 +85,7 @@ impl BoxType for Moov {
         self.mvhd.size()
             + self.traks.iter().map(|b| b.size()).sum::<u64>()
             + self.mvex.as_ref().map(|b| b.size()).unwrap_or(0)
+            + self.udta.as_ref().map(|b| b.size()).unwrap_or(0)
             + self.unknown.iter().map(|b| b.size()).sum::<u64>()
     }
 
@@ -91,6 +100,10 @@ impl BoxType for Moov {
             mvex.mux(writer)?;
         }
 
+        if let Some(udta) = &self.udta {
+            udta.mux(writer)?;
+        }
+
         for unknown in &self.unknown {
             unknown.mux(writer)?;
         }
@@ -32,7 +32,7 @@ pub struct Stbl {
     pub stsc: Stsc,
     pub stsz: Option<Stsz>,
     pub stz2: Option<Stz2>,
-    pub stco: Stco,
+    pub stco: Option<Stco>,
     pub co64: Option<Co64>,
     pub stss: Option<Stss>,
     pub stsh: Option<Stsh>,
@@ -54,7 +54,7 @@ impl Stbl {
             stsc,
             stsz,
             stz2: None,
-            stco,
+            stco: Some(stco),
             co64: None,
             stss: None,
             stsh: None,
@@ -66,6 +66,28 @@ impl Stbl {
             unknown: Vec::new(),
         }
     }
+
+    /// Sets the chunk offsets of this sample table.
+    ///
+    /// A `stco` box is used if all offsets fit into 32 bits, otherwise a `co64` box is used.
+    pub fn set_chunk_offsets(&mut self, offsets: Vec<u64>) {
+        if offsets.iter().all(|offset| *offset <= u32::MAX as u64) {
+            self.stco = Some(Stco::new(offsets.into_iter().map(|offset| offset as u32).collect()));
+            self.co64 = None;
+        } else {
+            self.stco = None;
+            self.co64 = Some(Co64::new(offsets));
+        }
+    }
+
+    /// Returns the chunk offsets of this sample table, from either the `stco` or the `co64` box.
+    pub fn chunk_offsets(&self) -> Vec<u64> {
+        match (&self.stco, &self.co64) {
+            (_, Some(co64)) => co64.chunk_offset.clone(),
+            (Some(stco), None) => stco.entries.iter().map(|offset| *offset as u64).collect(),
+            (None, None) => Vec::new(),
+        }
+    }
 }
 
 impl BoxType for Stbl {
@@ -148,7 +170,12 @@ impl BoxType for Stbl {
         let stsd = stsd.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "stsd box not found in stbl box"))?;
         let stts = stts.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "stts box not found in stbl box"))?;
         let stsc = stsc.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "stsc box not found in stbl box"))?;
-        let stco = stco.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "stco box not found in stbl box"))?;
+        if stco.is_none() && co64.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stco or co64 box not found in stbl box",
+            ));
+        }
 
         Ok(Self {
             header,
@@ -178,7 +205,7 @@ impl BoxType for Stbl {
         size += self.stsc.size();
         size += self.stsz.as_ref().map(|b| b.size()).unwrap_or(0);
         size += self.stz2.as_ref().map(|b| b.size()).unwrap_or(0);
-        size += self.stco.size();
+        size += self.stco.as_ref().map(|b| b.size()).unwrap_or(0);
         size += self.co64.as_ref().map(|b| b.size()).unwrap_or(0);
         size += self.stss.as_ref().map(|b| b.size()).unwrap_or(0);
         size += self.stsh.as_ref().map(|b| b.size()).unwrap_or(0);
@@ -204,7 +231,9 @@ impl BoxType for Stbl {
         if let Some(stz2) = &self.stz2 {
             stz2.mux(writer)?;
         }
-        self.stco.mux(writer)?;
+        if let Some(stco) = &self.stco {
+            stco.mux(writer)?;
+        }
         if let Some(co64) = &self.co64 {
             co64.mux(writer)?;
         }
@@ -234,4 +263,18 @@ impl BoxType for Stbl {
         }
         Ok(())
     }
+
+    fn validate(&self) -> io::Result<()> {
+        match (&self.stco, &self.co64) {
+            (None, None) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stbl box must contain either a stco or a co64 box",
+            )),
+            (Some(_), Some(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stbl box must not contain both a stco and a co64 box",
+            )),
+            _ => Ok(()),
+        }
+    }
 }
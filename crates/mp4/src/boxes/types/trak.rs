@@ -5,6 +5,7 @@ use bytes::{Buf, Bytes};
 use super::edts::Edts;
 use super::mdia::Mdia;
 use super::tkhd::Tkhd;
+use super::udta::Udta;
 use crate::boxes::DynBox;
 use crate::boxes::header::BoxHeader;
 use crate::boxes::traits::BoxType;
@@ -17,6 +18,7 @@ pub struct Trak {
     pub tkhd: Tkhd,
     pub edts: Option<Edts>,
     pub mdia: Mdia,
+    pub udta: Option<Udta>,
     pub unknown: Vec<DynBox>,
 }
 
@@ -27,6 +29,7 @@ impl Trak {
             tkhd,
             edts,
             mdia,
+            udta: None,
             unknown: Vec::new(),
         }
     }
@@ -40,6 +43,7 @@ impl BoxType for Trak {
         let mut tkhd = None;
         let mut edts = None;
         let mut mdia = None;
+        let mut udta = None;
         let mut unknown = Vec::new();
 
         while reader.has_remaining() {
@@ -55,6 +59,9 @@ impl BoxType for Trak {
                 DynBox::Mdia(b) => {
                     mdia = Some(*b);
                 }
+                DynBox::Udta(b) => {
+                    udta = Some(*b);
+                }
                 _ => {
                     unknown.push(dyn_box);
                 }
@@ -70,6 +77,7 @@ impl BoxType for Trak {
             tkhd,
             edts,
             mdia,
+            udta,
             unknown,
         })
     }
@@ -78,6 +86,7 @@ impl BoxType for Trak {
         self.tkhd.size()
             + self.edts.as_ref().map(|b| b.size()).unwrap_or(0)
             + self.mdia.size()
+            + self.udta.as_ref().map(|b| b.size()).unwrap_or(0)
             + self.unknown.iter().map(|b| b.size()).sum::<u64>()
     }
 
@@ -89,6 +98,10 @@ impl BoxType for Trak {
 
         self.mdia.mux(writer)?;
 
+        if let Some(udta) = &self.udta {
+            udta.mux(writer)?;
+        }
+
         for box_ in &self.unknown {
             box_.mux(writer)?;
         }
@@ -0,0 +1,69 @@
+use std::io;
+
+use bytes::{Buf, Bytes};
+
+use super::meta::Meta;
+use crate::boxes::DynBox;
+use crate::boxes::header::BoxHeader;
+use crate::boxes::traits::BoxType;
+
+#[derive(Debug, Clone, PartialEq)]
+/// User Data Box
+/// ISO/IEC 14496-12:2022(E) - 8.10.1
+pub struct Udta {
+    pub header: BoxHeader,
+    pub meta: Option<Meta>,
+    pub unknown: Vec<DynBox>,
+}
+
+impl Udta {
+    pub fn new(meta: Option<Meta>) -> Self {
+        Self {
+            header: BoxHeader::new(Self::NAME),
+            meta,
+            unknown: Vec::new(),
+        }
+    }
+}
+
+impl BoxType for Udta {
+    const NAME: [u8; 4] = *b"udta";
+
+    fn demux(header: BoxHeader, data: Bytes) -> io::Result<Self> {
+        let mut reader = io::Cursor::new(data);
+
+        let mut meta = None;
+        let mut unknown = Vec::new();
+
+        while reader.has_remaining() {
+            let dyn_box = DynBox::demux(&mut reader)?;
+
+            match dyn_box {
+                DynBox::Meta(b) => {
+                    meta = Some(*b);
+                }
+                _ => {
+                    unknown.push(dyn_box);
+                }
+            }
+        }
+
+        Ok(Self { header, meta, unknown })
+    }
+
+    fn primitive_size(&self) -> u64 {
+        self.meta.as_ref().map(|b| b.size()).unwrap_or(0) + self.unknown.iter().map(|b| b.size()).sum::<u64>()
+    }
+
+    fn primitive_mux<T: io::Write>(&self, writer: &mut T) -> io::Result<()> {
+        if let Some(meta) = &self.meta {
+            meta.mux(writer)?;
+        }
+
+        for b in &self.unknown {
+            b.mux(writer)?;
+        }
+
+        Ok(())
+    }
+}
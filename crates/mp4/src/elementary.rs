@@ -0,0 +1,174 @@
+//! Conversion of MP4 samples into raw elementary streams.
+//!
+//! MP4 stores H.264 and H.265 NAL units with a length prefix and keeps the parameter sets in the
+//! sample description, AAC frames are stored without any framing.
+//! Decoders and other containers usually expect Annex-B byte streams and ADTS frames instead.
+//!
+//! See [`Mp4File::elementary_stream`](crate::Mp4File::elementary_stream) to convert a whole track.
+
+use std::io;
+
+use bytes::Bytes;
+use scuffle_aac::PartialAudioSpecificConfig;
+use scuffle_h264::AVCDecoderConfigurationRecord;
+use scuffle_h265::HEVCDecoderConfigurationRecord;
+
+/// The Annex-B start code.
+///
+/// ISO/IEC 14496-10 - B.1.1
+pub const START_CODE: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+
+/// The size of an ADTS header without CRC.
+pub const ADTS_HEADER_SIZE: usize = 7;
+
+/// The sampling frequencies indexed by the `sampling_frequency_index`.
+///
+/// ISO/IEC 14496-3:2019(E) - 1.6.3.4 (Table 1.18)
+const SAMPLING_FREQUENCIES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// Converts length prefixed NAL units to Annex-B, by replacing every length prefix with a start code.
+///
+/// `length_size` is the size of the length prefix in bytes, which is `length_size_minus_one + 1`
+/// of the decoder configuration record.
+pub fn length_prefixed_to_annexb(data: &[u8], length_size: u8, out: &mut Vec<u8>) -> io::Result<()> {
+    let length_size = length_size as usize;
+    if !matches!(length_size, 1 | 2 | 4) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid nal unit length size: {length_size}"),
+        ));
+    }
+
+    let mut data = data;
+    while !data.is_empty() {
+        if data.len() < length_size {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated nal unit length"));
+        }
+
+        let (length, rest) = data.split_at(length_size);
+        let length = length.iter().fold(0usize, |acc, byte| (acc << 8) | *byte as usize);
+        if rest.len() < length {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated nal unit"));
+        }
+
+        let (nalu, rest) = rest.split_at(length);
+        out.extend_from_slice(&START_CODE);
+        out.extend_from_slice(nalu);
+        data = rest;
+    }
+
+    Ok(())
+}
+
+fn write_parameter_sets<'a>(nalus: impl IntoIterator<Item = &'a Bytes>, out: &mut Vec<u8>) {
+    for nalu in nalus {
+        out.extend_from_slice(&START_CODE);
+        out.extend_from_slice(nalu);
+    }
+}
+
+/// Converts an H.264 sample to Annex-B.
+///
+/// If `inject_parameter_sets` is set, the SPS and PPS of the decoder configuration record are written
+/// in front of the sample, which should be done for every keyframe so decoders can start at any of them.
+pub fn avc_sample_to_annexb(
+    config: &AVCDecoderConfigurationRecord,
+    sample: &[u8],
+    inject_parameter_sets: bool,
+    out: &mut Vec<u8>,
+) -> io::Result<()> {
+    if inject_parameter_sets {
+        write_parameter_sets(config.sps.iter().chain(&config.pps), out);
+    }
+
+    length_prefixed_to_annexb(sample, config.length_size_minus_one + 1, out)
+}
+
+/// Converts an H.265 sample to Annex-B.
+///
+/// If `inject_parameter_sets` is set, all NAL units of the decoder configuration record (VPS, SPS, PPS and SEI)
+/// are written in front of the sample, which should be done for every keyframe so decoders can start at any of them.
+pub fn hevc_sample_to_annexb(
+    config: &HEVCDecoderConfigurationRecord,
+    sample: &[u8],
+    inject_parameter_sets: bool,
+    out: &mut Vec<u8>,
+) -> io::Result<()> {
+    if inject_parameter_sets {
+        write_parameter_sets(config.arrays.iter().flat_map(|array| &array.nalus), out);
+    }
+
+    length_prefixed_to_annexb(sample, config.length_size_minus_one + 1, out)
+}
+
+/// Returns the ADTS header for an AAC frame with `payload_size` bytes.
+///
+/// ADTS can only describe the AAC Main, LC, SSR and LTP object types,
+/// one of the standard sampling frequencies and up to 8 channels.
+///
+/// ISO/IEC 14496-3:2019(E) - 1.A.2.2 (Table 1.A.5, Table 1.A.6)
+pub fn adts_header(config: &PartialAudioSpecificConfig, payload_size: usize) -> io::Result<[u8; ADTS_HEADER_SIZE]> {
+    let object_type = config.audio_object_type.as_u16();
+    if !(1..=4).contains(&object_type) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("audio object type {object_type} cannot be described by adts"),
+        ));
+    }
+
+    let sampling_frequency_index = SAMPLING_FREQUENCIES
+        .iter()
+        .position(|freq| *freq == config.sampling_frequency)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("sampling frequency {} cannot be described by adts", config.sampling_frequency),
+            )
+        })? as u8;
+
+    let channel_configuration = config.channel_configuration;
+    if channel_configuration > 7 {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("channel configuration {channel_configuration} cannot be described by adts"),
+        ));
+    }
+
+    // 13 bits, including the header
+    let frame_length = payload_size + ADTS_HEADER_SIZE;
+    if frame_length >= 1 << 13 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("aac frame of {payload_size} bytes is too large for adts"),
+        ));
+    }
+
+    let profile = (object_type - 1) as u8;
+    let frame_length = frame_length as u16;
+
+    Ok([
+        // syncword
+        0xFF,
+        // syncword, id = MPEG-4, layer = 0, protection_absent = 1
+        0xF1,
+        // profile, sampling_frequency_index, private_bit = 0, channel_configuration (1 bit)
+        (profile << 6) | (sampling_frequency_index << 2) | (channel_configuration >> 2),
+        // channel_configuration (2 bits), original_copy, home, copyright bits, frame_length (2 bits)
+        ((channel_configuration & 0b11) << 6) | (frame_length >> 11) as u8,
+        // frame_length (8 bits)
+        (frame_length >> 3) as u8,
+        // frame_length (3 bits), buffer_fullness = 0x7FF (5 bits)
+        ((frame_length & 0b111) << 5) as u8 | 0b1_1111,
+        // buffer_fullness (6 bits), number_of_raw_data_blocks_in_frame = 0
+        0b1111_1100,
+    ])
+}
+
+/// Converts an AAC frame to an ADTS frame.
+pub fn aac_sample_to_adts(config: &PartialAudioSpecificConfig, sample: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+    out.extend_from_slice(&adts_header(config, sample.len())?);
+    out.extend_from_slice(sample);
+    Ok(())
+}
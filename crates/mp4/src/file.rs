@@ -0,0 +1,811 @@
+use std::{fmt, io};
+
+use bytes::{Buf, Bytes};
+use scuffle_aac::PartialAudioSpecificConfig;
+
+use crate::boxes::DynBox;
+use crate::boxes::types::moof::Moof;
+use crate::boxes::types::moov::Moov;
+use crate::boxes::types::stbl::Stbl;
+use crate::boxes::types::stsc::StscEntry;
+use crate::boxes::types::trak::Trak;
+use crate::elementary;
+
+/// An MP4 file, made of its top level boxes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mp4File {
+    pub boxes: Vec<DynBox>,
+}
+
+impl Mp4File {
+    pub fn new(boxes: Vec<DynBox>) -> Self {
+        Self { boxes }
+    }
+
+    /// Demuxes all top level boxes until the reader is exhausted.
+    pub fn demux(reader: &mut io::Cursor<Bytes>) -> io::Result<Self> {
+        let mut boxes = Vec::new();
+        while reader.has_remaining() {
+            boxes.push(DynBox::demux(reader)?);
+        }
+
+        Ok(Self { boxes })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.boxes.iter().map(|box_| box_.size()).sum()
+    }
+
+    pub fn mux<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        for box_ in &self.boxes {
+            box_.mux(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `moov` box of the file, if there is one.
+    pub fn moov(&self) -> Option<&Moov> {
+        self.boxes.iter().find_map(|box_| box_.as_moov())
+    }
+
+    /// Returns the `moof` boxes of the file, in file order.
+    pub fn moofs(&self) -> impl Iterator<Item = &Moof> + '_ {
+        self.boxes.iter().filter_map(|box_| box_.as_moof())
+    }
+
+    /// Muxes the file with the `moov` box in front of the media data, see [`Mp4File::faststart`].
+    pub fn mux_faststart<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.faststart()?.mux(writer)
+    }
+
+    /// Returns a copy of the file with the `moov` box in front of the media data ("faststart"),
+    /// so players can start the playback before the whole file has been downloaded.
+    ///
+    /// The `moov` box is moved directly after the `ftyp` box and the chunk offsets of every track,
+    /// as well as absolute fragment base data offsets, are shifted to match the new layout.
+    /// A `stco` box is replaced by a `co64` box if the shifted offsets no longer fit into 32 bits.
+    ///
+    /// Files which already have the `moov` box in front of all `mdat` and `moof` boxes are returned unchanged.
+    pub fn faststart(&self) -> io::Result<Self> {
+        let moov_idx = self
+            .boxes
+            .iter()
+            .position(|box_| box_.as_moov().is_some())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing moov box"))?;
+
+        let is_media = |box_: &DynBox| box_.as_mdat().is_some() || box_.as_moof().is_some();
+        if !self.boxes[..moov_idx].iter().any(is_media) {
+            return Ok(self.clone());
+        }
+
+        let target_idx = self.boxes[..moov_idx]
+            .iter()
+            .position(|box_| box_.as_ftyp().is_some())
+            .map_or(0, |idx| idx + 1);
+
+        let offset_of = |idx: usize| self.boxes[..idx].iter().map(|box_| box_.size()).sum::<u64>();
+        // Everything in this range is moved back by the size of the moov box.
+        let moved = offset_of(target_idx)..offset_of(moov_idx);
+
+        let original = self.boxes[moov_idx].as_moov().expect("moov box");
+        let mut moov_size = self.boxes[moov_idx].size();
+        let moov = loop {
+            let shift = |offset: u64| if moved.contains(&offset) { offset + moov_size } else { offset };
+
+            let mut moov = original.clone();
+            for trak in &mut moov.traks {
+                let stbl = &mut trak.mdia.minf.stbl;
+                let offsets = stbl.chunk_offsets().into_iter().map(shift).collect::<Vec<_>>();
+                match &mut stbl.co64 {
+                    Some(co64) => co64.chunk_offset = offsets,
+                    None => stbl.set_chunk_offsets(offsets),
+                }
+            }
+
+            // Growing the moov box can only move the chunks further back, so this converges.
+            let size = DynBox::from(moov.clone()).size();
+            if size == moov_size {
+                break moov;
+            }
+
+            moov_size = size;
+        };
+
+        let mut boxes = Vec::with_capacity(self.boxes.len());
+        for (idx, box_) in self.boxes.iter().enumerate() {
+            if idx == moov_idx {
+                continue;
+            }
+
+            let mut box_ = box_.clone();
+            if let DynBox::Moof(moof) = &mut box_ {
+                for traf in &mut moof.traf {
+                    if let Some(base_data_offset) =
+                        traf.tfhd.base_data_offset.as_mut().filter(|offset| moved.contains(*offset))
+                    {
+                        *base_data_offset += moov_size;
+                    }
+                }
+            }
+
+            boxes.push(box_);
+        }
+
+        boxes.insert(target_idx, moov.into());
+
+        Ok(Self { boxes })
+    }
+
+    /// Checks the structural invariants of the file which cannot be checked by looking at a single box.
+    ///
+    /// This checks that:
+    /// - the sample tables of every track agree on the number of samples and reference valid chunks
+    ///   and sample descriptions,
+    /// - the track durations agree with the movie and media durations,
+    /// - every chunk lies within an `mdat` box and no two chunks overlap,
+    /// - the fragment sequence numbers are increasing and fragments only reference known tracks.
+    ///
+    /// All problems found are returned, rather than only the first one.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let Some(moov) = self.moov() else {
+            return Err(vec![ValidationError::MissingMoov]);
+        };
+
+        let mut chunks = Vec::new();
+        for trak in &moov.traks {
+            validate_durations(moov, trak, &mut errors);
+            if let Some(trak_chunks) = validate_sample_table(trak, &mut errors) {
+                chunks.extend(trak_chunks);
+            }
+        }
+
+        validate_chunk_layout(&self.mdat_ranges(), chunks, &mut errors);
+        validate_fragments(moov, self.moofs(), &mut errors);
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Returns the samples of a track, in decode order.
+    ///
+    /// Only samples described by the sample table of the `moov` box are returned,
+    /// samples of fragments are not.
+    pub fn samples(&self, track_id: u32) -> io::Result<Vec<Sample>> {
+        let trak = self.trak(track_id)?;
+        let stbl = &trak.mdia.minf.stbl;
+
+        let stts_count: u64 = stbl.stts.entries.iter().map(|entry| entry.sample_count as u64).sum();
+        let sizes = match (sample_sizes(stbl), &stbl.stsz) {
+            (Some(sizes), _) => sizes,
+            (None, Some(stsz)) => vec![stsz.sample_size as u64; stts_count as usize],
+            (None, None) => return Err(io::Error::new(io::ErrorKind::InvalidData, "missing sample size box")),
+        };
+
+        let offsets = stbl.chunk_offsets();
+        let chunk_map = chunk_map(&stbl.stsc.entries, offsets.len() as u32)
+            .map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, format!("invalid stsc: {reason}")))?;
+
+        let durations = stbl
+            .stts
+            .entries
+            .iter()
+            .flat_map(|entry| std::iter::repeat_n(entry.sample_delta, entry.sample_count as usize));
+        let composition_offsets = stbl
+            .ctts
+            .iter()
+            .flat_map(|ctts| &ctts.entries)
+            .flat_map(|entry| std::iter::repeat_n(entry.sample_offset, entry.sample_count as usize))
+            .chain(std::iter::repeat(0));
+        let mut sample_info = durations.zip(composition_offsets).enumerate();
+
+        let mut sizes = sizes.into_iter();
+        let mut decode_time = 0;
+        let mut samples = Vec::with_capacity(sizes.len());
+        for (chunk_idx, (mut offset, sample_count)) in offsets.into_iter().zip(chunk_map).enumerate() {
+            let sample_description_index = stbl
+                .stsc
+                .entries
+                .iter()
+                .rfind(|entry| entry.first_chunk <= chunk_idx as u32 + 1)
+                .map_or(1, |entry| entry.sample_description_index);
+
+            for size in sizes.by_ref().take(sample_count as usize) {
+                let (idx, (duration, composition_offset)) = sample_info
+                    .next()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "stts describes too few samples"))?;
+
+                let data = self.read(offset, size).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("sample {} at offset {offset} is not within an mdat box", idx + 1),
+                    )
+                })?;
+
+                let sample_number = idx as u32 + 1;
+                samples.push(Sample {
+                    data,
+                    decode_time,
+                    duration,
+                    composition_offset,
+                    keyframe: stbl.stss.as_ref().is_none_or(|stss| stss.entries.contains(&sample_number)),
+                    sample_description_index,
+                });
+
+                offset += size;
+                decode_time += duration as u64;
+            }
+        }
+
+        Ok(samples)
+    }
+
+    /// Converts a track into its raw elementary stream, see the [`elementary`](crate::elementary) module.
+    ///
+    /// - H.264 and H.265 tracks are converted to Annex-B, with the parameter sets in front of every keyframe.
+    /// - AAC tracks are converted to ADTS.
+    ///
+    /// Other codecs are not supported.
+    pub fn elementary_stream(&self, track_id: u32) -> io::Result<Vec<u8>> {
+        let entries = &self.trak(track_id)?.mdia.minf.stbl.stsd.entries;
+        let samples = self.samples(track_id)?;
+
+        let mut out = Vec::with_capacity(samples.iter().map(|sample| sample.data.len()).sum());
+        for sample in samples {
+            let entry = entries
+                .get((sample.sample_description_index as usize).wrapping_sub(1))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid sample description index"))?;
+
+            match entry {
+                DynBox::Avc1(avc1) => elementary::avc_sample_to_annexb(
+                    &avc1.avcc.avc_decoder_configuration_record,
+                    &sample.data,
+                    sample.keyframe,
+                    &mut out,
+                )?,
+                DynBox::Hev1(hev1) => {
+                    elementary::hevc_sample_to_annexb(&hev1.hvcc.hevc_config, &sample.data, sample.keyframe, &mut out)?
+                }
+                DynBox::Mp4a(mp4a) => {
+                    let info = mp4a
+                        .esds
+                        .es_descriptor
+                        .decoder_config
+                        .as_ref()
+                        .and_then(|c| c.decoder_specific_info.as_ref())
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing decoder specific info"))?;
+                    let config = PartialAudioSpecificConfig::parse(&info.data)?;
+                    elementary::aac_sample_to_adts(&config, &sample.data, &mut out)?;
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        format!("unsupported sample entry: {}", entry.name()),
+                    ));
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the track with the given id.
+    fn trak(&self, track_id: u32) -> io::Result<&Trak> {
+        self.moov()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing moov box"))?
+            .traks
+            .iter()
+            .find(|trak| trak.tkhd.track_id == track_id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("track {track_id} not found")))
+    }
+
+    /// Reads `size` bytes at the given file offset, which have to be within the payload of a single `mdat` box.
+    fn read(&self, offset: u64, size: u64) -> Option<Bytes> {
+        let mut box_end = 0;
+        for box_ in &self.boxes {
+            box_end += box_.size();
+            let Some(mdat) = box_.as_mdat() else {
+                continue;
+            };
+
+            let payload_size: u64 = mdat.data.iter().map(|data| data.len() as u64).sum();
+            let payload_start = box_end - payload_size;
+            if offset < payload_start || offset.saturating_add(size) > box_end {
+                continue;
+            }
+
+            // The payload may be split into multiple buffers, which are only copied if the range spans several of them.
+            let mut start = offset - payload_start;
+            let mut remaining = size;
+            let mut pieces = Vec::new();
+            for data in &mdat.data {
+                let len = data.len() as u64;
+                if start >= len {
+                    start -= len;
+                    continue;
+                }
+
+                if remaining == 0 {
+                    break;
+                }
+
+                let take = remaining.min(len - start);
+                pieces.push(data.slice(start as usize..(start + take) as usize));
+                remaining -= take;
+                start = 0;
+            }
+
+            return match pieces.len() {
+                0 => Some(Bytes::new()),
+                1 => pieces.pop(),
+                _ => Some(pieces.concat().into()),
+            };
+        }
+
+        None
+    }
+
+    /// Returns the byte ranges of the payloads of all `mdat` boxes in the file.
+    fn mdat_ranges(&self) -> Vec<(u64, u64)> {
+        let mut offset = 0;
+        let mut ranges = Vec::new();
+        for box_ in &self.boxes {
+            let size = box_.size();
+            if let Some(mdat) = box_.as_mdat() {
+                let payload_size: u64 = mdat.data.iter().map(|data| data.len() as u64).sum();
+                ranges.push((offset + size - payload_size, offset + size));
+            }
+            offset += size;
+        }
+
+        ranges
+    }
+}
+
+/// A sample of a track, see [`Mp4File::samples`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// The data of the sample.
+    pub data: Bytes,
+    /// The decode time of the sample in the media timescale.
+    pub decode_time: u64,
+    /// The duration of the sample in the media timescale.
+    pub duration: u32,
+    /// The composition time of the sample relative to its decode time, in the media timescale.
+    pub composition_offset: i64,
+    /// Whether the sample is a sync sample.
+    pub keyframe: bool,
+    /// The one-based index of the sample description of the sample in the `stsd` box.
+    pub sample_description_index: u32,
+}
+
+/// A violated structural invariant of an [`Mp4File`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The file has no `moov` box.
+    MissingMoov,
+    /// A sample table box describes a different number of samples than the sample size box.
+    SampleCountMismatch {
+        track_id: u32,
+        box_type: &'static str,
+        expected: u64,
+        actual: u64,
+    },
+    /// The sample to chunk box is not a valid mapping of the chunks of the track.
+    InvalidChunkMap {
+        track_id: u32,
+        reason: String,
+    },
+    /// A sample to chunk entry references a sample description which does not exist.
+    InvalidSampleDescriptionIndex {
+        track_id: u32,
+        index: u32,
+        entries: usize,
+    },
+    /// The sync sample box references a sample which does not exist.
+    InvalidSyncSample {
+        track_id: u32,
+        sample: u32,
+        sample_count: u64,
+    },
+    /// The track is longer than the movie.
+    TrackDurationExceedsMovie {
+        track_id: u32,
+        track_duration: u64,
+        movie_duration: u64,
+    },
+    /// The media duration does not match the track duration, converted to the movie timescale.
+    MediaDurationMismatch {
+        track_id: u32,
+        media_duration: u64,
+        track_duration: u64,
+    },
+    /// The sum of the sample durations does not match the media duration.
+    SampleDurationMismatch {
+        track_id: u32,
+        sample_duration: u64,
+        media_duration: u64,
+    },
+    /// A chunk does not lie within the payload of an `mdat` box.
+    ChunkOutsideMdat {
+        track_id: u32,
+        chunk: u32,
+        offset: u64,
+        size: u64,
+    },
+    /// Two chunks share the same bytes.
+    OverlappingChunks {
+        track_id: u32,
+        chunk: u32,
+        other_track_id: u32,
+        other_chunk: u32,
+    },
+    /// The file contains fragments but the `moov` box has no `mvex` box.
+    MissingMvex,
+    /// A fragment sequence number is not greater than the one of the previous fragment.
+    NonIncreasingSequenceNumber {
+        previous: u32,
+        sequence_number: u32,
+    },
+    /// A fragment references a track which is not part of the `moov` box.
+    UnknownFragmentTrack {
+        sequence_number: u32,
+        track_id: u32,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingMoov => write!(f, "the file has no moov box"),
+            Self::SampleCountMismatch {
+                track_id,
+                box_type,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "track {track_id}: {box_type} describes {actual} samples but the track has {expected} samples"
+            ),
+            Self::InvalidChunkMap { track_id, reason } => write!(f, "track {track_id}: invalid stsc: {reason}"),
+            Self::InvalidSampleDescriptionIndex {
+                track_id,
+                index,
+                entries,
+            } => write!(
+                f,
+                "track {track_id}: stsc references sample description {index} but stsd only has {entries} entries"
+            ),
+            Self::InvalidSyncSample {
+                track_id,
+                sample,
+                sample_count,
+            } => write!(
+                f,
+                "track {track_id}: stss references sample {sample} but the track only has {sample_count} samples"
+            ),
+            Self::TrackDurationExceedsMovie {
+                track_id,
+                track_duration,
+                movie_duration,
+            } => write!(
+                f,
+                "track {track_id}: tkhd duration {track_duration} is longer than the mvhd duration {movie_duration}"
+            ),
+            Self::MediaDurationMismatch {
+                track_id,
+                media_duration,
+                track_duration,
+            } => write!(
+                f,
+                "track {track_id}: mdhd duration is {media_duration} in the movie timescale but the tkhd duration is {track_duration}, add an edit list if this is intended"
+            ),
+            Self::SampleDurationMismatch {
+                track_id,
+                sample_duration,
+                media_duration,
+            } => write!(
+                f,
+                "track {track_id}: the stts sample durations add up to {sample_duration} but the mdhd duration is {media_duration}"
+            ),
+            Self::ChunkOutsideMdat {
+                track_id,
+                chunk,
+                offset,
+                size,
+            } => write!(
+                f,
+                "track {track_id}: chunk {chunk} at offset {offset} with {size} bytes is not within an mdat box"
+            ),
+            Self::OverlappingChunks {
+                track_id,
+                chunk,
+                other_track_id,
+                other_chunk,
+            } => write!(
+                f,
+                "track {track_id}: chunk {chunk} overlaps chunk {other_chunk} of track {other_track_id}"
+            ),
+            Self::MissingMvex => write!(f, "the file has moof boxes but the moov box has no mvex box"),
+            Self::NonIncreasingSequenceNumber {
+                previous,
+                sequence_number,
+            } => write!(
+                f,
+                "moof sequence number {sequence_number} does not increase over the previous sequence number {previous}"
+            ),
+            Self::UnknownFragmentTrack {
+                sequence_number,
+                track_id,
+            } => write!(
+                f,
+                "moof {sequence_number}: traf references track {track_id} which is not in the moov box"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// A chunk of a track, as `(offset, size, track_id, chunk)`, where `chunk` is one-based.
+type Chunk = (u64, u64, u32, u32);
+
+/// Returns the sizes of all samples of the track, if they are known.
+fn sample_sizes(stbl: &Stbl) -> Option<Vec<u64>> {
+    if let Some(stsz) = &stbl.stsz {
+        // The sample count is not retained for constant sample sizes.
+        if stsz.sample_size != 0 {
+            return None;
+        }
+
+        return Some(stsz.samples.iter().map(|size| *size as u64).collect());
+    }
+
+    stbl.stz2
+        .as_ref()
+        .map(|stz2| stz2.samples.iter().map(|size| *size as u64).collect())
+}
+
+/// Validates the sample table of a track and returns its chunks if the chunk map is valid.
+fn validate_sample_table(trak: &Trak, errors: &mut Vec<ValidationError>) -> Option<Vec<Chunk>> {
+    let track_id = trak.tkhd.track_id;
+    let stbl = &trak.mdia.minf.stbl;
+
+    let stts_count: u64 = stbl.stts.entries.iter().map(|entry| entry.sample_count as u64).sum();
+    let sizes = sample_sizes(stbl);
+    let sample_count = sizes.as_ref().map(|sizes| sizes.len() as u64).unwrap_or(stts_count);
+
+    if stts_count != sample_count {
+        errors.push(ValidationError::SampleCountMismatch {
+            track_id,
+            box_type: "stts",
+            expected: sample_count,
+            actual: stts_count,
+        });
+    }
+
+    if let Some(ctts) = &stbl.ctts {
+        let ctts_count: u64 = ctts.entries.iter().map(|entry| entry.sample_count as u64).sum();
+        if ctts_count != sample_count {
+            errors.push(ValidationError::SampleCountMismatch {
+                track_id,
+                box_type: "ctts",
+                expected: sample_count,
+                actual: ctts_count,
+            });
+        }
+    }
+
+    let invalid_sync_sample = stbl.stss.as_ref().and_then(|stss| {
+        stss.entries
+            .iter()
+            .find(|sample| **sample == 0 || **sample as u64 > sample_count)
+    });
+    if let Some(sample) = invalid_sync_sample {
+        errors.push(ValidationError::InvalidSyncSample {
+            track_id,
+            sample: *sample,
+            sample_count,
+        });
+    }
+
+    for entry in &stbl.stsc.entries {
+        if entry.sample_description_index == 0 || entry.sample_description_index as usize > stbl.stsd.entries.len() {
+            errors.push(ValidationError::InvalidSampleDescriptionIndex {
+                track_id,
+                index: entry.sample_description_index,
+                entries: stbl.stsd.entries.len(),
+            });
+        }
+    }
+
+    let offsets = stbl.chunk_offsets();
+    let chunk_map = match chunk_map(&stbl.stsc.entries, offsets.len() as u32) {
+        Ok(chunk_map) => chunk_map,
+        Err(reason) => {
+            errors.push(ValidationError::InvalidChunkMap { track_id, reason });
+            return None;
+        }
+    };
+
+    let stsc_count: u64 = chunk_map.iter().map(|samples| *samples as u64).sum();
+    if stsc_count != sample_count {
+        errors.push(ValidationError::SampleCountMismatch {
+            track_id,
+            box_type: "stsc",
+            expected: sample_count,
+            actual: stsc_count,
+        });
+        return None;
+    }
+
+    let sizes = sizes?;
+    let mut samples = sizes.into_iter();
+    let chunks = offsets
+        .into_iter()
+        .zip(chunk_map)
+        .enumerate()
+        .map(|(idx, (offset, sample_count))| {
+            let size = samples.by_ref().take(sample_count as usize).sum();
+            (offset, size, track_id, idx as u32 + 1)
+        })
+        .collect();
+
+    Some(chunks)
+}
+
+/// Expands the sample to chunk entries into the number of samples of every chunk.
+fn chunk_map(entries: &[StscEntry], chunk_count: u32) -> Result<Vec<u32>, String> {
+    let Some(first) = entries.first() else {
+        if chunk_count == 0 {
+            return Ok(Vec::new());
+        }
+
+        return Err(format!("there are {chunk_count} chunks but no entries"));
+    };
+
+    if first.first_chunk != 1 {
+        return Err(format!("the first entry starts at chunk {} instead of 1", first.first_chunk));
+    }
+
+    let mut chunk_map = Vec::with_capacity(chunk_count as usize);
+    for (idx, entry) in entries.iter().enumerate() {
+        if entry.samples_per_chunk == 0 {
+            return Err(format!("entry {} has no samples per chunk", idx + 1));
+        }
+
+        if entry.first_chunk > chunk_count {
+            return Err(format!(
+                "entry {} starts at chunk {} but there are only {chunk_count} chunks",
+                idx + 1,
+                entry.first_chunk
+            ));
+        }
+
+        let next_chunk = match entries.get(idx + 1) {
+            Some(next) if next.first_chunk <= entry.first_chunk => {
+                return Err(format!(
+                    "entry {} starts at chunk {} which does not increase over chunk {}",
+                    idx + 2,
+                    next.first_chunk,
+                    entry.first_chunk
+                ));
+            }
+            Some(next) => next.first_chunk,
+            None => chunk_count + 1,
+        };
+
+        chunk_map.extend(std::iter::repeat_n(
+            entry.samples_per_chunk,
+            (next_chunk - entry.first_chunk) as usize,
+        ));
+    }
+
+    Ok(chunk_map)
+}
+
+/// Validates the durations of a track against the movie, media and sample durations.
+fn validate_durations(moov: &Moov, trak: &Trak, errors: &mut Vec<ValidationError>) {
+    let track_id = trak.tkhd.track_id;
+    let mdhd = &trak.mdia.mdhd;
+
+    if trak.tkhd.duration > moov.mvhd.duration {
+        errors.push(ValidationError::TrackDurationExceedsMovie {
+            track_id,
+            track_duration: trak.tkhd.duration,
+            movie_duration: moov.mvhd.duration,
+        });
+    }
+
+    // An edit list changes the presentation duration of the track.
+    if trak.edts.is_none() && mdhd.timescale != 0 {
+        let media_duration = (mdhd.duration as u128 * moov.mvhd.timescale as u128 / mdhd.timescale as u128) as u64;
+        // allow for the rounding of the conversion
+        if media_duration.abs_diff(trak.tkhd.duration) > 1 {
+            errors.push(ValidationError::MediaDurationMismatch {
+                track_id,
+                media_duration,
+                track_duration: trak.tkhd.duration,
+            });
+        }
+    }
+
+    let stts = &trak.mdia.minf.stbl.stts;
+    if !stts.entries.is_empty() {
+        let sample_duration: u64 = stts
+            .entries
+            .iter()
+            .map(|entry| entry.sample_count as u64 * entry.sample_delta as u64)
+            .sum();
+
+        if sample_duration != mdhd.duration {
+            errors.push(ValidationError::SampleDurationMismatch {
+                track_id,
+                sample_duration,
+                media_duration: mdhd.duration,
+            });
+        }
+    }
+}
+
+/// Validates that every chunk lies within an `mdat` payload and that no chunks overlap.
+fn validate_chunk_layout(mdats: &[(u64, u64)], mut chunks: Vec<Chunk>, errors: &mut Vec<ValidationError>) {
+    chunks.retain(|&(offset, size, track_id, chunk)| {
+        let end = offset.saturating_add(size);
+        let inside = mdats.iter().any(|&(start, stop)| offset >= start && end <= stop);
+        if !inside {
+            errors.push(ValidationError::ChunkOutsideMdat {
+                track_id,
+                chunk,
+                offset,
+                size,
+            });
+        }
+
+        inside
+    });
+
+    chunks.sort_unstable();
+    for pair in chunks.windows(2) {
+        let (offset, size, other_track_id, other_chunk) = pair[0];
+        let (next_offset, _, track_id, chunk) = pair[1];
+        if offset + size > next_offset {
+            errors.push(ValidationError::OverlappingChunks {
+                track_id,
+                chunk,
+                other_track_id,
+                other_chunk,
+            });
+        }
+    }
+}
+
+/// Validates the sequence numbers and track references of the fragments.
+fn validate_fragments<'a>(moov: &Moov, moofs: impl Iterator<Item = &'a Moof>, errors: &mut Vec<ValidationError>) {
+    let mut previous = None;
+    for moof in moofs {
+        if previous.is_none() && moov.mvex.is_none() {
+            errors.push(ValidationError::MissingMvex);
+        }
+
+        let sequence_number = moof.mfhd.sequence_number;
+        if let Some(previous) = previous.filter(|previous| sequence_number <= *previous) {
+            errors.push(ValidationError::NonIncreasingSequenceNumber {
+                previous,
+                sequence_number,
+            });
+        }
+        previous = Some(sequence_number);
+
+        for traf in &moof.traf {
+            let track_id = traf.tfhd.track_id;
+            if !moov.traks.iter().any(|trak| trak.tkhd.track_id == track_id) {
+                errors.push(ValidationError::UnknownFragmentTrack {
+                    sequence_number,
+                    track_id,
+                });
+            }
+        }
+    }
+}
@@ -15,10 +15,13 @@
 #![deny(unreachable_pub)]
 
 mod boxes;
+mod file;
 
 pub mod codec;
+pub mod elementary;
 
 pub use boxes::{BoxType, DynBox, header, types};
+pub use file::{Mp4File, Sample, ValidationError};
 
 #[cfg(test)]
 mod tests;
@@ -30,6 +30,7 @@ use crate::boxes::types::esds::descriptor::types::es::EsDescriptor;
 use crate::boxes::types::esds::descriptor::types::sl_config::SLConfigDescriptor;
 use crate::boxes::types::ftyp::{FourCC, Ftyp};
 use crate::boxes::types::hdlr::{HandlerType, Hdlr};
+use crate::boxes::types::ilst::IlstItem;
 use crate::boxes::types::mdhd::Mdhd;
 use crate::boxes::types::mfhd::Mfhd;
 use crate::boxes::types::minf::Minf;
@@ -187,6 +188,7 @@ fn test_demux_avc_aac() {
                     pre_defined: 0,
                 }
             );
+            assert_eq!(&video_trak.mdia.mdhd.language_code(), b"und");
 
             assert_eq!(
                 video_trak.mdia.hdlr,
@@ -340,14 +342,14 @@ fn test_demux_avc_aac() {
 
             assert_eq!(
                 video_trak.mdia.minf.stbl.stco,
-                Stco {
+                Some(Stco {
                     header: FullBoxHeader {
                         header: BoxHeader { box_type: *b"stco" },
                         version: 0,
                         flags: 0,
                     },
                     entries: vec![],
-                }
+                })
             );
 
             assert_eq!(video_trak.mdia.minf.stbl.co64, None);
@@ -557,14 +559,14 @@ fn test_demux_avc_aac() {
                         sbgp: None,
                         sdtp: None,
                         stdp: None,
-                        stco: Stco {
+                        stco: Some(Stco {
                             header: FullBoxHeader {
                                 header: BoxHeader { box_type: *b"stco" },
                                 version: 0,
                                 flags: 0,
                             },
                             entries: vec![],
-                        },
+                        }),
                         stsc: Stsc {
                             header: FullBoxHeader {
                                 header: BoxHeader { box_type: *b"stsc" },
@@ -638,7 +640,28 @@ fn test_demux_avc_aac() {
                 ],
                 unknown: vec![],
             })
-        )
+        );
+
+        // udta
+        let ilst = moov
+            .udta
+            .as_ref()
+            .and_then(|udta| udta.meta.as_ref())
+            .and_then(|meta| meta.ilst.as_ref())
+            .expect("ilst");
+        assert_eq!(ilst.text(IlstItem::TITLE), Some("Big Buck Bunny, Sunflower version"));
+        assert_eq!(
+            ilst.text(IlstItem::ARTIST),
+            Some("Blender Foundation 2008, Janus Bager Kristensen 2013")
+        );
+        assert_eq!(ilst.text(IlstItem::COMPOSER), Some("Sacha Goedegebure"));
+        assert_eq!(ilst.text(IlstItem::ENCODER), Some("Lavf58.76.100"));
+        assert_eq!(
+            ilst.text(IlstItem::COMMENT),
+            Some("Creative Commons Attribution 3.0 - http://bbb3d.renderfarming.net")
+        );
+        assert_eq!(ilst.text(IlstItem::GENRE), Some("Animation"));
+        assert!(moov.unknown.is_empty());
     }
 
     // moof
@@ -1264,14 +1287,14 @@ fn test_demux_av1_aac() {
 
             assert_eq!(
                 video_trak.mdia.minf.stbl.stco,
-                Stco {
+                Some(Stco {
                     header: FullBoxHeader {
                         header: BoxHeader { box_type: *b"stco" },
                         version: 0,
                         flags: 0,
                     },
                     entries: vec![],
-                }
+                })
             );
 
             assert_eq!(video_trak.mdia.minf.stbl.co64, None);
@@ -1481,14 +1504,14 @@ fn test_demux_av1_aac() {
                         sbgp: None,
                         sdtp: None,
                         stdp: None,
-                        stco: Stco {
+                        stco: Some(Stco {
                             header: FullBoxHeader {
                                 header: BoxHeader { box_type: *b"stco" },
                                 version: 0,
                                 flags: 0,
                             },
                             entries: vec![],
-                        },
+                        }),
                         stsc: Stsc {
                             header: FullBoxHeader {
                                 header: BoxHeader { box_type: *b"stsc" },
@@ -1983,14 +2006,14 @@ fn test_demux_hevc_aac() {
 
             assert_eq!(
                 video_trak.mdia.minf.stbl.stco,
-                Stco {
+                Some(Stco {
                     header: FullBoxHeader {
                         header: BoxHeader { box_type: *b"stco" },
                         version: 0,
                         flags: 0,
                     },
                     entries: vec![],
-                }
+                })
             );
 
             assert_eq!(video_trak.mdia.minf.stbl.co64, None);
@@ -2200,14 +2223,14 @@ fn test_demux_hevc_aac() {
                         sbgp: None,
                         sdtp: None,
                         stdp: None,
-                        stco: Stco {
+                        stco: Some(Stco {
                             header: FullBoxHeader {
                                 header: BoxHeader { box_type: *b"stco" },
                                 version: 0,
                                 flags: 0,
                             },
                             entries: vec![],
-                        },
+                        }),
                         stsc: Stsc {
                             header: FullBoxHeader {
                                 header: BoxHeader { box_type: *b"stsc" },
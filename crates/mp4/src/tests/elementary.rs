@@ -0,0 +1,183 @@
+use std::io;
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use scuffle_aac::{AudioObjectType, PartialAudioSpecificConfig};
+
+use crate::boxes::DynBox;
+use crate::boxes::types::mdat::Mdat;
+use crate::elementary::{START_CODE, adts_header, length_prefixed_to_annexb};
+use crate::{Mp4File, Sample};
+
+#[test]
+fn test_samples() {
+    let mut file = super::validate::file();
+    file.boxes[2] = Mdat::new(vec![Bytes::from((0..60).collect::<Vec<u8>>())]).into();
+
+    let samples = file.samples(1).unwrap();
+    assert_eq!(
+        samples,
+        [
+            Sample {
+                data: Bytes::from((0..10).collect::<Vec<u8>>()),
+                decode_time: 0,
+                duration: 1000,
+                composition_offset: 0,
+                keyframe: true,
+                sample_description_index: 1,
+            },
+            Sample {
+                data: Bytes::from((10..30).collect::<Vec<u8>>()),
+                decode_time: 1000,
+                duration: 1000,
+                composition_offset: 0,
+                keyframe: false,
+                sample_description_index: 1,
+            },
+            Sample {
+                data: Bytes::from((30..60).collect::<Vec<u8>>()),
+                decode_time: 2000,
+                duration: 1000,
+                composition_offset: 0,
+                keyframe: false,
+                sample_description_index: 1,
+            },
+        ]
+    );
+
+    // The mdat payload split into multiple buffers
+    file.boxes[2] = Mdat::new(vec![
+        Bytes::from((0..15).collect::<Vec<u8>>()),
+        Bytes::from((15..60).collect::<Vec<u8>>()),
+    ])
+    .into();
+    let split = file.samples(1).unwrap();
+    assert_eq!(split, samples);
+
+    assert_eq!(file.samples(2).unwrap_err().kind(), io::ErrorKind::NotFound);
+    assert_eq!(file.elementary_stream(1).unwrap_err().kind(), io::ErrorKind::Unsupported);
+
+    // A sample outside of the mdat box
+    file.boxes[2] = Mdat::new(vec![Bytes::from(vec![0; 50])]).into();
+    assert_eq!(file.samples(1).unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_length_prefixed_to_annexb() {
+    let mut out = Vec::new();
+    length_prefixed_to_annexb(&[0, 0, 0, 2, 0x65, 0x01, 0, 0, 0, 1, 0x41], 4, &mut out).unwrap();
+    assert_eq!(out, [0, 0, 0, 1, 0x65, 0x01, 0, 0, 0, 1, 0x41]);
+
+    let mut out = Vec::new();
+    length_prefixed_to_annexb(&[0, 1, 0x41], 2, &mut out).unwrap();
+    assert_eq!(out, [0, 0, 0, 1, 0x41]);
+
+    assert!(length_prefixed_to_annexb(&[0, 0, 0, 2, 0x65], 4, &mut Vec::new()).is_err());
+    assert!(length_prefixed_to_annexb(&[0, 0], 4, &mut Vec::new()).is_err());
+    assert!(length_prefixed_to_annexb(&[0, 0, 0, 0], 3, &mut Vec::new()).is_err());
+}
+
+#[test]
+fn test_adts_header() {
+    let config = PartialAudioSpecificConfig {
+        audio_object_type: AudioObjectType::AacLowComplexity,
+        sampling_frequency: 44100,
+        channel_configuration: 2,
+        program_config_element: None,
+    };
+
+    assert_eq!(adts_header(&config, 100).unwrap(), [0xFF, 0xF1, 0x50, 0x80, 0x0D, 0x7F, 0xFC]);
+    assert!(adts_header(&config, 8185).is_err());
+
+    let config = PartialAudioSpecificConfig {
+        sampling_frequency: 44000,
+        ..config
+    };
+    assert!(adts_header(&config, 100).is_err());
+}
+
+/// The sample descriptions of the video and audio track of the fragmented test asset.
+fn sample_entries() -> (DynBox, DynBox) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    let data = std::fs::read(dir.join("avc_aac_fragmented.mp4")).unwrap();
+    let file = Mp4File::demux(&mut io::Cursor::new(data.into())).unwrap();
+
+    let moov = file.moov().expect("moov");
+    let entry = |idx: usize| moov.traks[idx].mdia.minf.stbl.stsd.entries[0].clone();
+
+    (entry(0), entry(1))
+}
+
+/// The file from [`super::validate::file`] with the given sample description and samples.
+fn file_with(entry: DynBox, samples: [&[u8]; 3]) -> Mp4File {
+    let mut file = super::validate::file();
+    let DynBox::Moov(moov) = &mut file.boxes[1] else {
+        panic!("expected moov");
+    };
+    moov.traks[0].mdia.minf.stbl.stsd.entries = vec![entry];
+
+    // The moov box changed its size
+    let base = file.boxes[0].size() + file.boxes[1].size() + 8;
+    let DynBox::Moov(moov) = &mut file.boxes[1] else {
+        panic!("expected moov");
+    };
+    moov.traks[0].mdia.minf.stbl.set_chunk_offsets(vec![base, base + 30]);
+
+    file.boxes[2] = Mdat::new(samples.iter().map(|sample| Bytes::copy_from_slice(sample)).collect()).into();
+    file
+}
+
+#[test]
+fn test_elementary_stream_avc() {
+    let (DynBox::Avc1(avc1), _) = sample_entries() else {
+        panic!("expected avc1");
+    };
+    let config = avc1.avcc.avc_decoder_configuration_record.clone();
+
+    let file = file_with(
+        DynBox::Avc1(avc1),
+        [
+            &[0, 0, 0, 6, 0x65, 1, 2, 3, 4, 5],
+            &[0, 0, 0, 16, 0x41, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            &[
+                0, 0, 0, 3, 0x41, 1, 2, 0, 0, 0, 19, 0x41, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+            ],
+        ],
+    );
+
+    let mut expected = Vec::new();
+    // The parameter sets are injected in front of the keyframe
+    for nalu in config.sps.iter().chain(&config.pps) {
+        expected.extend_from_slice(&START_CODE);
+        expected.extend_from_slice(nalu);
+    }
+    expected.extend_from_slice(&START_CODE);
+    expected.extend_from_slice(&[0x65, 1, 2, 3, 4, 5]);
+    expected.extend_from_slice(&START_CODE);
+    expected.extend_from_slice(&[0x41, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    expected.extend_from_slice(&START_CODE);
+    expected.extend_from_slice(&[0x41, 1, 2]);
+    expected.extend_from_slice(&START_CODE);
+    expected.extend_from_slice(&[0x41, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18]);
+
+    assert_eq!(file.elementary_stream(1).unwrap(), expected);
+}
+
+#[test]
+fn test_elementary_stream_aac() {
+    let (_, mp4a) = sample_entries();
+    let file = file_with(mp4a, [&[1; 10], &[2; 20], &[3; 30]]);
+
+    let stream = file.elementary_stream(1).unwrap();
+    assert_eq!(stream.len(), 60 + 3 * 7);
+
+    // Walk the adts frames
+    let mut rest = &stream[..];
+    for (idx, payload_size) in [10, 20, 30].into_iter().enumerate() {
+        assert_eq!(rest[..2], [0xFF, 0xF1]);
+        let frame_length = ((rest[3] as usize & 0b11) << 11) | ((rest[4] as usize) << 3) | (rest[5] as usize >> 5);
+        assert_eq!(frame_length, payload_size + 7);
+        assert!(rest[7..frame_length].iter().all(|byte| *byte as usize == idx + 1));
+        rest = &rest[frame_length..];
+    }
+}
@@ -0,0 +1,110 @@
+use bytes::Bytes;
+
+use crate::Mp4File;
+use crate::boxes::DynBox;
+use crate::boxes::types::co64::Co64;
+use crate::boxes::types::mdat::Mdat;
+
+/// The file from [`super::validate::file`] with the `moov` box after the `mdat` box.
+fn slowstart_file() -> Mp4File {
+    let mut file = super::validate::file();
+    let moov = file.boxes.remove(1);
+    let moov_size = moov.size();
+    file.boxes.push(moov);
+
+    let DynBox::Moov(moov) = &mut file.boxes[2] else {
+        panic!("expected moov");
+    };
+    let stbl = &mut moov.traks[0].mdia.minf.stbl;
+    let offsets = stbl.chunk_offsets().into_iter().map(|offset| offset - moov_size).collect();
+    stbl.set_chunk_offsets(offsets);
+
+    file
+}
+
+#[test]
+fn test_faststart() {
+    let file = slowstart_file();
+    assert_eq!(file.validate(), Ok(()));
+
+    let faststart = file.faststart().unwrap();
+    assert_eq!(faststart, super::validate::file());
+    assert_eq!(faststart.validate(), Ok(()));
+
+    let mut muxed = Vec::new();
+    file.mux_faststart(&mut muxed).unwrap();
+
+    let mut expected = Vec::new();
+    faststart.mux(&mut expected).unwrap();
+    assert_eq!(muxed, expected);
+}
+
+#[test]
+fn test_faststart_keeps_co64() {
+    let mut file = slowstart_file();
+    let DynBox::Moov(moov) = &mut file.boxes[2] else {
+        panic!("expected moov");
+    };
+    let stbl = &mut moov.traks[0].mdia.minf.stbl;
+    let offsets = stbl.chunk_offsets();
+    stbl.stco = None;
+    stbl.co64 = Some(Co64::new(offsets));
+    assert_eq!(file.validate(), Ok(()));
+
+    let faststart = file.faststart().unwrap();
+    assert_eq!(faststart.validate(), Ok(()));
+
+    let stbl = &faststart.moov().unwrap().traks[0].mdia.minf.stbl;
+    assert!(stbl.stco.is_none());
+    assert!(stbl.co64.is_some());
+}
+
+#[test]
+fn test_faststart_promotes_co64() {
+    let mut file = slowstart_file();
+    let DynBox::Moov(moov) = &mut file.boxes[2] else {
+        panic!("expected moov");
+    };
+    let stbl = &mut moov.traks[0].mdia.minf.stbl;
+    let offsets = stbl.chunk_offsets();
+
+    // Pad the file in front of the media data, so the last chunk ends up at exactly u32::MAX.
+    let padding_size = u32::MAX as u64 - offsets.iter().max().unwrap() - 8;
+    let block = Bytes::from(vec![0; 1 << 20]);
+    let mut padding = vec![block.clone(); (padding_size / block.len() as u64) as usize];
+    padding.push(block.slice(..(padding_size % block.len() as u64) as usize));
+    let padding = DynBox::from(Mdat::new(padding));
+    assert_eq!(padding.size(), padding_size + 8);
+
+    let offsets = offsets.into_iter().map(|offset| offset + padding.size()).collect::<Vec<_>>();
+    stbl.set_chunk_offsets(offsets.clone());
+    assert!(stbl.stco.is_some());
+    file.boxes.insert(1, padding);
+    assert_eq!(file.validate(), Ok(()));
+
+    let faststart = file.faststart().unwrap();
+    assert_eq!(faststart.validate(), Ok(()));
+
+    // The co64 box needs 4 more bytes per chunk than the stco box, which moves the chunks back further.
+    let moov_size = faststart.boxes[1].size();
+    assert_eq!(moov_size, file.boxes[3].size() + 4 * offsets.len() as u64);
+
+    let stbl = &faststart.moov().unwrap().traks[0].mdia.minf.stbl;
+    assert!(stbl.stco.is_none());
+    assert_eq!(
+        stbl.chunk_offsets(),
+        offsets.iter().map(|offset| offset + moov_size).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_faststart_unchanged() {
+    let file = super::validate::file();
+    assert_eq!(file.faststart().unwrap(), file);
+
+    let mut file = Mp4File::new(vec![file.boxes[0].clone(), file.boxes[2].clone()]);
+    assert_eq!(file.faststart().unwrap_err().kind(), std::io::ErrorKind::InvalidData,);
+
+    file.boxes.clear();
+    assert!(file.faststart().is_err());
+}
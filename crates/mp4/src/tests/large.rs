@@ -0,0 +1,154 @@
+use std::io;
+
+use bytes::{Buf, Bytes};
+
+use crate::boxes::header::BoxHeader;
+use crate::boxes::types::co64::Co64;
+use crate::boxes::types::mdat::Mdat;
+use crate::boxes::types::stbl::Stbl;
+use crate::boxes::types::stco::Stco;
+use crate::boxes::types::stsc::Stsc;
+use crate::boxes::types::stsd::Stsd;
+use crate::boxes::types::stts::Stts;
+use crate::boxes::{BoxType, DynBox};
+
+/// A writer which only keeps the first bytes written to it, so boxes larger than 4GB
+/// can be muxed without allocating them.
+#[derive(Default)]
+struct HeadWriter {
+    head: Vec<u8>,
+    len: u64,
+}
+
+impl io::Write for HeadWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let remaining = 32usize.saturating_sub(self.head.len());
+        self.head.extend_from_slice(&buf[..remaining.min(buf.len())]);
+        self.len += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn roundtrip(box_: DynBox) -> DynBox {
+    let mut writer = Vec::new();
+    box_.mux(&mut writer).unwrap();
+    assert_eq!(writer.len() as u64, box_.size());
+
+    let mut reader = io::Cursor::new(Bytes::from(writer));
+    let demuxed = DynBox::demux(&mut reader).unwrap();
+    assert!(!reader.has_remaining());
+
+    demuxed
+}
+
+fn stbl() -> Stbl {
+    Stbl::new(
+        Stsd::new(vec![]),
+        Stts::new(vec![]),
+        Stsc::new(vec![]),
+        Stco::new(vec![]),
+        None,
+    )
+}
+
+#[test]
+fn test_mux_mdat_larger_than_4gb() {
+    // 4097 references to the same 1MiB buffer, just over 4GiB in total.
+    let chunk = Bytes::from(vec![0; 1024 * 1024]);
+    let mdat = Mdat::new(vec![chunk; 4097]);
+
+    let payload_size = 4097 * 1024 * 1024;
+    assert!(payload_size + 8 > u32::MAX as u64);
+    assert_eq!(mdat.size(), payload_size + 16);
+
+    let mut writer = HeadWriter::default();
+    mdat.mux(&mut writer).unwrap();
+
+    assert_eq!(writer.len, mdat.size());
+    // size = 1 means the real size follows the box type as a 64-bit integer
+    assert_eq!(&writer.head[0..4], &1u32.to_be_bytes());
+    assert_eq!(&writer.head[4..8], b"mdat");
+    assert_eq!(&writer.head[8..16], &(payload_size + 16).to_be_bytes());
+}
+
+#[test]
+fn test_demux_large_size_header() {
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(b"free");
+    data.extend_from_slice(&20u64.to_be_bytes());
+    data.extend_from_slice(&[1, 2, 3, 4]);
+
+    let mut reader = io::Cursor::new(Bytes::from(data));
+    let (header, data) = BoxHeader::demux(&mut reader).unwrap();
+    assert_eq!(header.box_type, *b"free");
+    assert_eq!(data, Bytes::from_static(&[1, 2, 3, 4]));
+    assert!(!reader.has_remaining());
+}
+
+#[test]
+fn test_demux_invalid_size_header() {
+    // a 64-bit size smaller than the header itself
+    let mut data = Vec::new();
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(b"free");
+    data.extend_from_slice(&8u64.to_be_bytes());
+
+    let err = BoxHeader::demux(&mut io::Cursor::new(Bytes::from(data))).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    // a 32-bit size smaller than the header itself
+    let mut data = Vec::new();
+    data.extend_from_slice(&4u32.to_be_bytes());
+    data.extend_from_slice(b"free");
+
+    let err = BoxHeader::demux(&mut io::Cursor::new(Bytes::from(data))).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_mux_co64() {
+    let co64 = Co64::new(vec![48, u32::MAX as u64 + 1, 6 * 1024 * 1024 * 1024]);
+
+    let demuxed = roundtrip(co64.clone().into());
+    assert_eq!(demuxed.as_co64().expect("co64"), &co64);
+}
+
+#[test]
+fn test_stbl_chunk_offsets() {
+    let mut stbl = stbl();
+
+    stbl.set_chunk_offsets(vec![48, u32::MAX as u64]);
+    assert_eq!(stbl.stco, Some(Stco::new(vec![48, u32::MAX])));
+    assert_eq!(stbl.co64, None);
+    assert_eq!(stbl.chunk_offsets(), vec![48, u32::MAX as u64]);
+
+    let offsets = vec![48, u32::MAX as u64, u32::MAX as u64 + 48];
+    stbl.set_chunk_offsets(offsets.clone());
+    assert_eq!(stbl.stco, None);
+    assert_eq!(stbl.co64, Some(Co64::new(offsets.clone())));
+    assert_eq!(stbl.chunk_offsets(), offsets);
+
+    let demuxed = roundtrip(stbl.clone().into());
+    let demuxed = demuxed.as_stbl().expect("stbl");
+    assert_eq!(demuxed, &stbl);
+    assert_eq!(demuxed.chunk_offsets(), offsets);
+}
+
+#[test]
+fn test_stbl_requires_one_chunk_offset_box() {
+    let mut stbl = stbl();
+
+    stbl.co64 = Some(Co64::new(vec![]));
+    let err = stbl.mux(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    stbl.stco = None;
+    stbl.co64 = None;
+    let err = stbl.mux(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
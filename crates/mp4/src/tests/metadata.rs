@@ -0,0 +1,215 @@
+use std::io;
+
+use bytes::{Buf, Bytes};
+
+use crate::boxes::header::BoxHeader;
+use crate::boxes::types::emsg::Emsg;
+use crate::boxes::types::hdlr::{HandlerType, Hdlr};
+use crate::boxes::types::ilst::{Ilst, IlstData, IlstItem};
+use crate::boxes::types::mdhd::Mdhd;
+use crate::boxes::types::meta::Meta;
+use crate::boxes::types::udta::Udta;
+use crate::boxes::{BoxType, DynBox};
+
+fn roundtrip(box_: DynBox) -> DynBox {
+    let mut writer = Vec::new();
+    box_.mux(&mut writer).unwrap();
+    assert_eq!(writer.len() as u64, box_.size());
+
+    let mut reader = io::Cursor::new(Bytes::from(writer));
+    let demuxed = DynBox::demux(&mut reader).unwrap();
+    assert!(!reader.has_remaining());
+
+    demuxed
+}
+
+#[test]
+fn test_mux_udta_ilst() {
+    let mut ilst = Ilst::new(vec![IlstItem::text_item(IlstItem::TITLE, "old title")]);
+    ilst.set_text(IlstItem::TITLE, "Big Buck Bunny");
+    ilst.set_text(IlstItem::ARTIST, "Blender Foundation");
+    ilst.set_text(IlstItem::COMMENT, "Ünïcödé comment");
+    ilst.items.push(IlstItem::new(
+        *b"tmpo",
+        vec![IlstData::new(IlstData::BE_SIGNED_INT, Bytes::from_static(&[0, 120]))],
+    ));
+
+    let udta = Udta::new(Some(Meta::new_itunes(ilst)));
+
+    let demuxed = roundtrip(udta.clone().into());
+    let demuxed = demuxed.as_udta().expect("udta");
+    assert_eq!(demuxed, &udta);
+
+    let meta = demuxed.meta.as_ref().expect("meta");
+    assert_eq!(meta.hdlr.handler_type, HandlerType::Mdir);
+
+    let ilst = meta.ilst.as_ref().expect("ilst");
+    assert_eq!(ilst.items.len(), 4);
+    assert_eq!(ilst.text(IlstItem::TITLE), Some("Big Buck Bunny"));
+    assert_eq!(ilst.text(IlstItem::ARTIST), Some("Blender Foundation"));
+    assert_eq!(ilst.text(IlstItem::COMMENT), Some("Ünïcödé comment"));
+    assert_eq!(ilst.text(*b"tmpo"), None);
+    assert_eq!(ilst.get(*b"tmpo").unwrap().data[0].value.as_ref(), &[0, 120]);
+}
+
+#[test]
+fn test_demux_ilst_freeform_item() {
+    // A `----` item carries `mean` and `name` boxes alongside its data.
+    let mut data = Vec::new();
+    data.extend_from_slice(&[0, 0, 0, 65]);
+    data.extend_from_slice(b"----");
+    data.extend_from_slice(&[0, 0, 0, 28]);
+    data.extend_from_slice(b"mean");
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data.extend_from_slice(b"com.apple.iTunes");
+    data.extend_from_slice(&[0, 0, 0, 12]);
+    data.extend_from_slice(b"name");
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data.extend_from_slice(&[0, 0, 0, 17]);
+    data.extend_from_slice(b"data");
+    data.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0]);
+    data.extend_from_slice(b"x");
+
+    let ilst = Ilst::demux(BoxHeader::new(Ilst::NAME), Bytes::from(data.clone())).unwrap();
+
+    assert_eq!(ilst.items.len(), 1);
+    assert_eq!(ilst.items[0].header.box_type, *b"----");
+    assert_eq!(ilst.items[0].text(), Some("x"));
+    assert_eq!(ilst.items[0].unknown.len(), 2);
+    assert_eq!(ilst.items[0].unknown[0].name(), "mean");
+    assert_eq!(ilst.items[0].unknown[1].name(), "name");
+
+    let mut writer = Vec::new();
+    ilst.primitive_mux(&mut writer).unwrap();
+    assert_eq!(writer, data);
+}
+
+#[test]
+fn test_mdhd_language_code() {
+    let mut mdhd = Mdhd::new(0, 0, 1000, 0);
+    assert_eq!(&mdhd.language_code(), b"und");
+
+    mdhd.set_language_code(*b"eng").unwrap();
+    assert_eq!(&mdhd.language_code(), b"eng");
+    assert_eq!(mdhd.language, 0x15c7);
+
+    let err = mdhd.set_language_code(*b"EN1").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(&mdhd.language_code(), b"eng");
+
+    let demuxed = roundtrip(mdhd.clone().into());
+    assert_eq!(demuxed.as_mdhd(), Some(&mdhd));
+}
+
+#[test]
+fn test_hdlr_name() {
+    let hdlr = Hdlr::new(HandlerType::Vide, "Vidéo Handler".to_string());
+
+    let demuxed = roundtrip(hdlr.clone().into());
+    assert_eq!(demuxed.as_hdlr(), Some(&hdlr));
+
+    // Some muxers do not null terminate the handler name.
+    let mut data = Vec::new();
+    data.extend_from_slice(&[0, 0, 0, 0]); // version + flags
+    data.extend_from_slice(&[0, 0, 0, 0]); // pre_defined
+    data.extend_from_slice(b"soun");
+    data.extend_from_slice(&[0; 12]); // reserved
+    data.extend_from_slice(b"SoundHandler");
+
+    let hdlr = Hdlr::demux(BoxHeader::new(Hdlr::NAME), Bytes::from(data.clone())).unwrap();
+    assert_eq!(hdlr.handler_type, HandlerType::Soun);
+    assert_eq!(hdlr.name, "SoundHandler");
+
+    // Overly long names are rejected.
+    let mut data = data[..24].to_vec();
+    data.resize(24 + crate::boxes::MAX_STRING_LEN + 1, b'a');
+    let err = Hdlr::demux(BoxHeader::new(Hdlr::NAME), Bytes::from(data)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_mux_emsg() {
+    let emsg = Emsg::new(
+        "https://aomedia.org/emsg/ID3".into(),
+        "".into(),
+        1000,
+        u32::MAX as u64 + 1,
+        0,
+        7,
+        Bytes::from_static(b"ID3\x04\x00"),
+    );
+
+    let demuxed = roundtrip(emsg.clone().into());
+    assert_eq!(demuxed.as_emsg().expect("emsg"), &emsg);
+
+    // Version 0 stores the strings first and a 32-bit presentation time delta.
+    let mut data = Vec::new();
+    data.extend_from_slice(&[0, 0, 0, 38]);
+    data.extend_from_slice(b"emsg");
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data.extend_from_slice(b"urn:a\0");
+    data.extend_from_slice(b"1\0");
+    data.extend_from_slice(&90000u32.to_be_bytes());
+    data.extend_from_slice(&3000u32.to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes());
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(b"ab");
+
+    let mut reader = io::Cursor::new(Bytes::from(data.clone()));
+    let demuxed = DynBox::demux(&mut reader).unwrap();
+    let emsg = demuxed.as_emsg().expect("emsg");
+    assert_eq!(emsg.header.version, 0);
+    assert_eq!(emsg.scheme_id_uri, "urn:a");
+    assert_eq!(emsg.value, "1");
+    assert_eq!(emsg.timescale, 90000);
+    assert_eq!(emsg.presentation_time, 3000);
+    assert_eq!(emsg.id, 1);
+    assert_eq!(emsg.message_data.as_ref(), b"ab");
+
+    let mut writer = Vec::new();
+    demuxed.mux(&mut writer).unwrap();
+    assert_eq!(writer, data);
+
+    let mut invalid = emsg.clone();
+    invalid.presentation_time = u32::MAX as u64 + 1;
+    assert!(invalid.validate().is_err());
+
+    // Overly long strings are rejected.
+    let mut data = vec![0, 0, 0, 0];
+    data.resize(4 + crate::boxes::MAX_STRING_LEN + 1, b'a');
+    data.push(0);
+    let err = Emsg::demux(BoxHeader::new(Emsg::NAME), Bytes::from(data)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_demux_quicktime_meta() {
+    // QuickTime writes `meta` as a plain box, so the hdlr box follows the box header directly.
+    let hdlr = Hdlr::new(HandlerType::Mdir, String::new());
+    let ilst = Ilst::new(vec![IlstItem::text_item(IlstItem::TITLE, "QuickTime")]);
+
+    let mut data = Vec::new();
+    hdlr.mux(&mut data).unwrap();
+    ilst.mux(&mut data).unwrap();
+
+    let meta = Meta::demux(BoxHeader::new(Meta::NAME), Bytes::from(data.clone())).unwrap();
+    assert!(meta.quicktime);
+    assert_eq!(meta.hdlr, hdlr);
+    assert_eq!(
+        meta.ilst.as_ref().and_then(|ilst| ilst.text(IlstItem::TITLE)),
+        Some("QuickTime")
+    );
+
+    let mut writer = Vec::new();
+    meta.primitive_mux(&mut writer).unwrap();
+    assert_eq!(writer, data);
+
+    let demuxed = roundtrip(meta.clone().into());
+    assert_eq!(demuxed.as_meta(), Some(&meta));
+
+    // The ISO variant still carries a version and flags.
+    let meta = Meta::new_itunes(ilst);
+    let demuxed = roundtrip(meta.clone().into());
+    assert!(!demuxed.as_meta().expect("meta").quicktime);
+    assert_eq!(demuxed.as_meta(), Some(&meta));
+}
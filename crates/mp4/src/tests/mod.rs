@@ -1 +1,6 @@
 mod demux;
+mod elementary;
+mod faststart;
+mod large;
+mod metadata;
+mod validate;
@@ -0,0 +1,294 @@
+use std::io;
+
+use bytes::Bytes;
+
+use crate::boxes::DynBox;
+use crate::boxes::header::{BoxHeader, FullBoxHeader};
+use crate::boxes::types::ftyp::{FourCC, Ftyp};
+use crate::boxes::types::hdlr::{HandlerType, Hdlr};
+use crate::boxes::types::mdat::Mdat;
+use crate::boxes::types::mdhd::Mdhd;
+use crate::boxes::types::mdia::Mdia;
+use crate::boxes::types::mfhd::Mfhd;
+use crate::boxes::types::minf::Minf;
+use crate::boxes::types::moof::Moof;
+use crate::boxes::types::moov::Moov;
+use crate::boxes::types::mvhd::Mvhd;
+use crate::boxes::types::stbl::Stbl;
+use crate::boxes::types::stco::Stco;
+use crate::boxes::types::stsc::{Stsc, StscEntry};
+use crate::boxes::types::stsd::Stsd;
+use crate::boxes::types::stss::Stss;
+use crate::boxes::types::stsz::Stsz;
+use crate::boxes::types::stts::{Stts, SttsEntry};
+use crate::boxes::types::tfhd::Tfhd;
+use crate::boxes::types::tkhd::Tkhd;
+use crate::boxes::types::traf::Traf;
+use crate::boxes::types::trak::Trak;
+use crate::{Mp4File, ValidationError};
+
+/// A file with one track of three samples of 10, 20 and 30 bytes in two chunks.
+pub(super) fn file() -> Mp4File {
+    let mut stbl = Stbl::new(
+        Stsd::new(vec![DynBox::Unknown((BoxHeader::new(*b"test"), Bytes::new()))]),
+        Stts::new(vec![SttsEntry {
+            sample_count: 3,
+            sample_delta: 1000,
+        }]),
+        Stsc::new(vec![
+            StscEntry {
+                first_chunk: 1,
+                samples_per_chunk: 2,
+                sample_description_index: 1,
+            },
+            StscEntry {
+                first_chunk: 2,
+                samples_per_chunk: 1,
+                sample_description_index: 1,
+            },
+        ]),
+        Stco::new(vec![0, 0]),
+        Some(Stsz::new(0, vec![10, 20, 30])),
+    );
+
+    stbl.stss = Some(Stss {
+        header: FullBoxHeader::new(*b"stss", 0, 0),
+        entries: vec![1],
+    });
+
+    let trak = Trak::new(
+        Tkhd::new(0, 0, 1, 3000, None),
+        None,
+        Mdia::new(
+            Mdhd::new(0, 0, 1000, 3000),
+            Hdlr::new(HandlerType::Soun, "SoundHandler".into()),
+            Minf::new(stbl, None, None),
+        ),
+    );
+
+    let ftyp = Ftyp::new(FourCC::Iso5, 512, vec![FourCC::Iso5]);
+    let mut moov = Moov::new(Mvhd::new(0, 0, 1000, 3000, 2), vec![trak], None);
+
+    let base = DynBox::from(ftyp.clone()).size() + DynBox::from(moov.clone()).size() + 8;
+    moov.traks[0].mdia.minf.stbl.set_chunk_offsets(vec![base, base + 30]);
+
+    Mp4File::new(vec![
+        ftyp.into(),
+        moov.into(),
+        Mdat::new(vec![Bytes::from(vec![0; 60])]).into(),
+    ])
+}
+
+fn moov_mut(file: &mut Mp4File) -> &mut Moov {
+    file.boxes
+        .iter_mut()
+        .find_map(|box_| match box_ {
+            DynBox::Moov(moov) => Some(moov.as_mut()),
+            _ => None,
+        })
+        .expect("moov")
+}
+
+fn stbl_mut(file: &mut Mp4File) -> &mut Stbl {
+    &mut moov_mut(file).traks[0].mdia.minf.stbl
+}
+
+#[test]
+fn test_validate_valid_file() {
+    let file = file();
+    assert_eq!(file.validate(), Ok(()));
+
+    let mut buf = Vec::new();
+    file.mux(&mut buf).unwrap();
+    assert_eq!(buf.len() as u64, file.size());
+
+    let demuxed = Mp4File::demux(&mut io::Cursor::new(Bytes::from(buf))).unwrap();
+    assert_eq!(demuxed.boxes.len(), 3);
+    assert_eq!(demuxed.validate(), Ok(()));
+}
+
+#[test]
+fn test_validate_missing_moov() {
+    let file = Mp4File::new(vec![Mdat::new(vec![]).into()]);
+    assert_eq!(file.validate(), Err(vec![ValidationError::MissingMoov]));
+}
+
+#[test]
+fn test_validate_sample_counts() {
+    let mut file = file();
+    let stbl = stbl_mut(&mut file);
+    stbl.stts.entries[0].sample_count = 4;
+    stbl.stss.as_mut().unwrap().entries[0] = 5;
+    stbl.stsc.entries[1].sample_description_index = 2;
+
+    let errors = file.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::SampleDurationMismatch {
+                track_id: 1,
+                sample_duration: 4000,
+                media_duration: 3000,
+            },
+            ValidationError::SampleCountMismatch {
+                track_id: 1,
+                box_type: "stts",
+                expected: 3,
+                actual: 4,
+            },
+            ValidationError::InvalidSyncSample {
+                track_id: 1,
+                sample: 5,
+                sample_count: 3,
+            },
+            ValidationError::InvalidSampleDescriptionIndex {
+                track_id: 1,
+                index: 2,
+                entries: 1,
+            },
+        ]
+    );
+    assert_eq!(
+        errors[1].to_string(),
+        "track 1: stts describes 4 samples but the track has 3 samples"
+    );
+}
+
+#[test]
+fn test_validate_chunk_map() {
+    let mut file = file();
+    stbl_mut(&mut file).stsc.entries[1].samples_per_chunk = 2;
+    assert_eq!(
+        file.validate(),
+        Err(vec![ValidationError::SampleCountMismatch {
+            track_id: 1,
+            box_type: "stsc",
+            expected: 3,
+            actual: 4,
+        }])
+    );
+
+    let mut file = self::file();
+    stbl_mut(&mut file).stsc.entries[1].first_chunk = 3;
+    let errors = file.validate().unwrap_err();
+    assert_eq!(
+        errors[0].to_string(),
+        "track 1: invalid stsc: entry 2 starts at chunk 3 but there are only 2 chunks"
+    );
+
+    let mut file = self::file();
+    stbl_mut(&mut file).stsc.entries[0].first_chunk = 2;
+    let errors = file.validate().unwrap_err();
+    assert_eq!(
+        errors[0].to_string(),
+        "track 1: invalid stsc: the first entry starts at chunk 2 instead of 1"
+    );
+}
+
+#[test]
+fn test_validate_durations() {
+    let mut file = file();
+    let moov = moov_mut(&mut file);
+    moov.mvhd.duration = 2000;
+    moov.traks[0].tkhd.duration = 2500;
+
+    assert_eq!(
+        file.validate(),
+        Err(vec![
+            ValidationError::TrackDurationExceedsMovie {
+                track_id: 1,
+                track_duration: 2500,
+                movie_duration: 2000,
+            },
+            ValidationError::MediaDurationMismatch {
+                track_id: 1,
+                media_duration: 3000,
+                track_duration: 2500,
+            },
+        ])
+    );
+
+    // the media duration is converted to the movie timescale
+    let mut file = self::file();
+    let moov = moov_mut(&mut file);
+    moov.traks[0].mdia.mdhd.timescale = 48000;
+    moov.traks[0].mdia.mdhd.duration = 144000;
+    moov.traks[0].mdia.minf.stbl.stts.entries[0].sample_delta = 48000;
+    assert_eq!(file.validate(), Ok(()));
+}
+
+#[test]
+fn test_validate_chunk_layout() {
+    let mut file = file();
+    let stbl = stbl_mut(&mut file);
+    let offsets = stbl.chunk_offsets();
+    stbl.set_chunk_offsets(vec![offsets[0], offsets[0] + 20]);
+
+    assert_eq!(
+        file.validate(),
+        Err(vec![ValidationError::OverlappingChunks {
+            track_id: 1,
+            chunk: 2,
+            other_track_id: 1,
+            other_chunk: 1,
+        }])
+    );
+
+    let mut file = self::file();
+    let stbl = stbl_mut(&mut file);
+    let offsets = stbl.chunk_offsets();
+    stbl.set_chunk_offsets(vec![offsets[0], offsets[1] + 1]);
+
+    let errors = file.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![ValidationError::ChunkOutsideMdat {
+            track_id: 1,
+            chunk: 2,
+            offset: offsets[1] + 1,
+            size: 30,
+        }]
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        format!(
+            "track 1: chunk 2 at offset {} with 30 bytes is not within an mdat box",
+            offsets[1] + 1
+        )
+    );
+}
+
+#[test]
+fn test_validate_fragments() {
+    let mut file = file();
+    let moof = |sequence_number, track_id| -> DynBox {
+        Moof::new(
+            Mfhd::new(sequence_number),
+            vec![Traf::new(Tfhd::new(track_id, None, None, None, None, None), None, None)],
+        )
+        .into()
+    };
+    file.boxes.push(moof(1, 1));
+    file.boxes.push(moof(3, 1));
+    file.boxes.push(moof(3, 2));
+
+    let errors = file.validate().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            ValidationError::MissingMvex,
+            ValidationError::NonIncreasingSequenceNumber {
+                previous: 3,
+                sequence_number: 3,
+            },
+            ValidationError::UnknownFragmentTrack {
+                sequence_number: 3,
+                track_id: 2,
+            },
+        ]
+    );
+    assert_eq!(
+        errors[2].to_string(),
+        "moof 3: traf references track 2 which is not in the moov box"
+    );
+}
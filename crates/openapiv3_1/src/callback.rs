@@ -0,0 +1,266 @@
+//! Implements [OpenAPI Callback Object][callback] types.
+//!
+//! [callback]: https://spec.openapis.org/oas/latest.html#callback-object
+use indexmap::IndexMap;
+use serde_derive::Serialize;
+
+use super::extensions::Extensions;
+use super::path::PathItem;
+use super::{Ref, RefOr};
+
+/// Implements [OpenAPI Callback Object][callback].
+///
+/// A map of possible out-of band callbacks related to the parent [`Operation`][operation]. Each
+/// key is a runtime [expression][expression] e.g. `{$request.body#/callbackUrl}` which is
+/// evaluated at runtime to identify the URL used for the callback request, and the value is the
+/// [`PathItem`] describing the requests that may be initiated by the API provider.
+///
+/// [callback]: https://spec.openapis.org/oas/latest.html#callback-object
+/// [expression]: https://spec.openapis.org/oas/latest.html#runtime-expressions
+/// [operation]: ../path/struct.Operation.html
+#[non_exhaustive]
+#[derive(Serialize, Default, Clone, PartialEq, bon::Builder)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[builder(on(_, into))]
+pub struct Callback {
+    /// Map of runtime expressions to [`PathItem`]s describing the callback requests.
+    #[serde(flatten)]
+    #[builder(field)]
+    pub paths: IndexMap<String, PathItem>,
+
+    /// Optional extensions "x-something".
+    #[serde(skip_serializing_if = "Option::is_none", flatten)]
+    pub extensions: Option<Extensions>,
+}
+
+impl Callback {
+    /// Construct a new empty [`Callback`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Append [`PathItem`] for the given runtime expression. If the expression already exists the
+    /// [`Operation`][operation]s of the [`PathItem`] will be merged with the existing path item.
+    ///
+    /// [operation]: ../path/struct.Operation.html
+    pub fn path(&mut self, expression: impl Into<String>, item: impl Into<PathItem>) -> &mut Self {
+        let expression = expression.into();
+        let item = item.into();
+        if let Some(existing_item) = self.paths.get_mut(&expression) {
+            existing_item.merge_operations(item);
+        } else {
+            self.paths.insert(expression, item);
+        }
+
+        self
+    }
+
+    /// Append [`PathItem`]s for the given runtime expressions.
+    pub fn paths<E: Into<String>, P: Into<PathItem>>(&mut self, items: impl IntoIterator<Item = (E, P)>) -> &mut Self {
+        items.into_iter().fold(self, |this, (e, p)| this.path(e, p))
+    }
+
+    /// Merge _`other`_ [`Callback`] into `self`. On conflicting expression the path item operations
+    /// will be merged into existing [`PathItem`]. Otherwise the expression with [`PathItem`] will be
+    /// appended to `self`. All [`Extensions`] will be merged from _`other`_ into `self`.
+    pub fn merge(&mut self, other: Callback) {
+        self.paths(other.paths);
+
+        if let Some(other_extensions) = other.extensions {
+            let extensions = self.extensions.get_or_insert(Extensions::default());
+            extensions.merge(other_extensions);
+        }
+    }
+}
+
+impl<'de> serde::de::Deserialize<'de> for Callback {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let items: IndexMap<String, serde_json::Value> = IndexMap::deserialize(deserializer)?;
+
+        let mut callback = Callback::new();
+        let mut extensions = Extensions::default();
+        for (key, value) in items {
+            if key.starts_with("x-") {
+                extensions.insert(key, value);
+            } else {
+                let item = serde::Deserialize::deserialize(value).map_err(serde::de::Error::custom)?;
+                callback.paths.insert(key, item);
+            }
+        }
+
+        if !extensions.is_empty() {
+            callback.extensions = Some(extensions);
+        }
+
+        Ok(callback)
+    }
+}
+
+impl<S: callback_builder::State> CallbackBuilder<S> {
+    /// Append [`PathItem`] for the given runtime expression. If the expression already exists the
+    /// [`Operation`][operation]s of the [`PathItem`] will be merged with the existing path item.
+    ///
+    /// [operation]: ../path/struct.Operation.html
+    pub fn path(mut self, expression: impl Into<String>, item: impl Into<PathItem>) -> Self {
+        let expression = expression.into();
+        let item = item.into();
+        if let Some(existing_item) = self.paths.get_mut(&expression) {
+            existing_item.merge_operations(item);
+        } else {
+            self.paths.insert(expression, item);
+        }
+
+        self
+    }
+
+    /// Append [`PathItem`]s for the given runtime expressions.
+    pub fn paths<E: Into<String>, P: Into<PathItem>>(self, items: impl IntoIterator<Item = (E, P)>) -> Self {
+        items.into_iter().fold(self, |this, (e, p)| this.path(e, p))
+    }
+}
+
+impl<S: callback_builder::IsComplete> From<CallbackBuilder<S>> for Callback {
+    fn from(builder: CallbackBuilder<S>) -> Self {
+        builder.build()
+    }
+}
+
+impl<S: callback_builder::IsComplete> From<CallbackBuilder<S>> for RefOr<Callback> {
+    fn from(builder: CallbackBuilder<S>) -> Self {
+        Self::T(builder.build())
+    }
+}
+
+impl From<Ref> for RefOr<Callback> {
+    fn from(r: Ref) -> Self {
+        Self::Ref(r)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use insta::assert_json_snapshot;
+
+    use super::Callback;
+    use crate::extensions::Extensions;
+    use crate::path::{HttpMethod, Operation, PathItem};
+    use crate::{Ref, RefOr, Response};
+
+    fn webhook(operation_id: &str) -> Operation {
+        Operation::builder()
+            .operation_id(operation_id)
+            .response("200", Response::new("webhook received"))
+            .build()
+    }
+
+    #[test]
+    fn callback_builder() {
+        let callback = Callback::builder()
+            .path(
+                "{$request.body#/callbackUrl}",
+                PathItem::new(HttpMethod::Post, webhook("onEvent")),
+            )
+            .path(
+                "{$request.body#/callbackUrl}",
+                PathItem::new(HttpMethod::Put, webhook("onReplay")),
+            )
+            .extensions(Extensions::new([("x-retries", 3)]))
+            .build();
+
+        assert_json_snapshot!(callback, @r#"
+        {
+          "{$request.body#/callbackUrl}": {
+            "put": {
+              "responses": {
+                "200": {
+                  "description": "webhook received"
+                }
+              },
+              "operationId": "onReplay"
+            },
+            "post": {
+              "responses": {
+                "200": {
+                  "description": "webhook received"
+                }
+              },
+              "operationId": "onEvent"
+            }
+          },
+          "x-retries": 3
+        }
+        "#);
+
+        let value = serde_json::to_value(&callback).unwrap();
+        let deserialized: Callback = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), value);
+    }
+
+    #[test]
+    fn operation_callbacks() {
+        let operation = Operation::builder()
+            .callback(
+                "onEvent",
+                Callback::builder().path("{$request.query.url}", PathItem::new(HttpMethod::Post, webhook("onEvent"))),
+            )
+            .callback("onShared", Ref::from_callback_name("shared"))
+            .build();
+
+        let value = serde_json::to_value(&operation).unwrap();
+        assert_json_snapshot!(value["callbacks"], @r##"
+        {
+          "onEvent": {
+            "{$request.query.url}": {
+              "post": {
+                "operationId": "onEvent",
+                "responses": {
+                  "200": {
+                    "description": "webhook received"
+                  }
+                }
+              }
+            }
+          },
+          "onShared": {
+            "$ref": "#/components/callbacks/shared"
+          }
+        }
+        "##);
+
+        let deserialized: Operation = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), value);
+        assert!(matches!(
+            deserialized.callbacks.as_ref().and_then(|c| c.get("onShared")),
+            Some(RefOr::Ref(_))
+        ));
+    }
+
+    #[test]
+    fn callback_merge() {
+        let mut callback = Callback::builder()
+            .path("{$request.query.url}", PathItem::new(HttpMethod::Post, webhook("first")))
+            .build();
+
+        callback.merge(
+            Callback::builder()
+                .path("{$request.query.url}", PathItem::new(HttpMethod::Post, webhook("second")))
+                .path("{$request.query.url}", PathItem::new(HttpMethod::Delete, webhook("delete")))
+                .path("{$response.header.Location}", PathItem::new(HttpMethod::Get, webhook("get")))
+                .extensions(Extensions::new([("x-merged", true)]))
+                .build(),
+        );
+
+        let item = &callback.paths["{$request.query.url}"];
+        assert_eq!(item.post.as_ref().unwrap().operation_id.as_deref(), Some("first"));
+        assert_eq!(item.delete.as_ref().unwrap().operation_id.as_deref(), Some("delete"));
+        assert!(callback.paths.contains_key("{$response.header.Location}"));
+        assert_eq!(
+            callback.extensions.as_ref().and_then(|e| e.get("x-merged")),
+            Some(&serde_json::Value::Bool(true))
+        );
+    }
+}
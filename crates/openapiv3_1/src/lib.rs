@@ -31,10 +31,12 @@ use serde::de::{Error, Expected, Visitor};
 use serde::{Deserializer, Serializer};
 use serde_derive::{Deserialize, Serialize};
 
+pub use self::callback::{Callback, CallbackBuilder};
 pub use self::content::{Content, ContentBuilder};
 pub use self::external_docs::ExternalDocs;
 pub use self::header::{Header, HeaderBuilder};
 pub use self::info::{Contact, ContactBuilder, Info, InfoBuilder, License, LicenseBuilder};
+pub use self::link::{Link, LinkBuilder};
 pub use self::path::{HttpMethod, PathItem, Paths, PathsBuilder};
 pub use self::response::{Response, ResponseBuilder, Responses, ResponsesBuilder};
 pub use self::schema::{Components, ComponentsBuilder, Discriminator, Object, Ref, Schema, Type};
@@ -42,6 +44,7 @@ pub use self::security::SecurityRequirement;
 pub use self::server::{Server, ServerBuilder, ServerVariable, ServerVariableBuilder};
 pub use self::tag::Tag;
 
+pub mod callback;
 pub mod content;
 pub mod encoding;
 pub mod example;
@@ -57,6 +60,7 @@ pub mod schema;
 pub mod security;
 pub mod server;
 pub mod tag;
+pub mod visit;
 pub mod xml;
 
 /// Root object of the OpenAPI document.
@@ -188,10 +192,10 @@ impl OpenApi {
     /// Merge `other` [`OpenApi`] consuming it and resuming it's content.
     ///
     /// Merge function will take all `self` nonexistent _`servers`, `paths`, `schemas`, `responses`,
-    /// `security_schemes`, `security_requirements` and `tags`_ from _`other`_ [`OpenApi`].
+    /// `security_schemes`, `links`, `callbacks`, `security_requirements` and `tags`_ from _`other`_ [`OpenApi`].
     ///
-    /// This function performs a shallow comparison for `paths`, `schemas`, `responses` and
-    /// `security schemes` which means that only _`name`_ and _`path`_ is used for comparison. When
+    /// This function performs a shallow comparison for `paths`, `schemas`, `responses`,
+    /// `security schemes`, `links` and `callbacks` which means that only _`name`_ and _`path`_ is used for comparison. When
     /// match occurs the whole item will be ignored from merged results. Only items not
     /// found will be appended to `self`.
     ///
@@ -227,6 +231,14 @@ impl OpenApi {
                 .security_schemes
                 .retain(|name, _| !components.security_schemes.contains_key(name));
             components.security_schemes.append(&mut other_components.security_schemes);
+
+            other_components.links.retain(|name, _| !components.links.contains_key(name));
+            components.links.append(&mut other_components.links);
+
+            other_components
+                .callbacks
+                .retain(|name, _| !components.callbacks.contains_key(name));
+            components.callbacks.append(&mut other_components.callbacks);
         }
 
         if let Some(other_security) = &mut other.security {
@@ -557,6 +569,35 @@ mod tests {
         });
     }
 
+    #[test]
+    fn merge_components_links_callbacks() {
+        let mut api_1 = OpenApi::builder()
+            .components(
+                Components::builder()
+                    .link("GetUser", Link::new("getUser"))
+                    .callback("onEvent", Callback::new()),
+            )
+            .build();
+
+        let api_2 = OpenApi::builder()
+            .components(
+                Components::builder()
+                    .link("GetUser", Link::new("ignored"))
+                    .link("GetOrder", Link::new("getOrder"))
+                    .callback("onEvent", Ref::from_callback_name("ignored"))
+                    .callback("onOrder", Callback::new()),
+            )
+            .build();
+
+        api_1.merge(api_2);
+
+        let components = api_1.components.unwrap();
+        assert_eq!(components.links.keys().collect::<Vec<_>>(), ["GetUser", "GetOrder"]);
+        assert!(matches!(&components.links["GetUser"], RefOr::T(link) if link.operation_id == "getUser"));
+        assert_eq!(components.callbacks.keys().collect::<Vec<_>>(), ["onEvent", "onOrder"]);
+        assert!(matches!(components.callbacks["onEvent"], RefOr::T(_)));
+    }
+
     #[test]
     fn merge_same_path_diff_methods() {
         let mut api_1 = OpenApi::new(
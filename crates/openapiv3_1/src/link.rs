@@ -4,8 +4,8 @@
 use indexmap::IndexMap;
 use serde_derive::{Deserialize, Serialize};
 
-use super::Server;
 use super::extensions::Extensions;
+use super::{Ref, RefOr, Server};
 
 /// Implements [Open API Link Object][link_object] for responses.
 ///
@@ -21,6 +21,7 @@ use super::extensions::Extensions;
 /// [link_object]: https://spec.openapis.org/oas/latest.html#link-object
 #[derive(Serialize, Deserialize, Clone, PartialEq, Default, bon::Builder)]
 #[cfg_attr(feature = "debug", derive(Debug))]
+#[serde(rename_all = "camelCase")]
 #[builder(on(_, into))]
 #[non_exhaustive]
 pub struct Link {
@@ -75,6 +76,34 @@ pub struct Link {
     pub extensions: Option<Extensions>,
 }
 
+impl Link {
+    /// Construct a new [`Link`] to the operation with the given _`operation_id`_.
+    pub fn new(operation_id: impl Into<String>) -> Self {
+        Self {
+            operation_id: operation_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Add parameters to be passed to [Operation][operation] upon execution.
+    ///
+    /// [operation]: ../path/struct.Operation.html
+    pub fn parameters<N: Into<String>, V: Into<serde_json::Value>>(
+        &mut self,
+        items: impl IntoIterator<Item = (N, V)>,
+    ) -> &mut Self {
+        items.into_iter().fold(self, |this, (n, v)| this.parameter(n, v))
+    }
+
+    /// Add parameter to be passed to [Operation][operation] upon execution.
+    ///
+    /// [operation]: ../path/struct.Operation.html
+    pub fn parameter<N: Into<String>, V: Into<serde_json::Value>>(&mut self, name: N, value: V) -> &mut Self {
+        self.parameters.insert(name.into(), value.into());
+        self
+    }
+}
+
 impl<S: link_builder::State> LinkBuilder<S> {
     /// Add parameters to be passed to [Operation][operation] upon execution.
     ///
@@ -97,3 +126,88 @@ impl<S: link_builder::IsComplete> From<LinkBuilder<S>> for Link {
         builder.build()
     }
 }
+
+impl<S: link_builder::IsComplete> From<LinkBuilder<S>> for RefOr<Link> {
+    fn from(builder: LinkBuilder<S>) -> Self {
+        Self::T(builder.build())
+    }
+}
+
+impl From<Ref> for RefOr<Link> {
+    fn from(r: Ref) -> Self {
+        Self::Ref(r)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use insta::assert_json_snapshot;
+    use serde_json::json;
+
+    use super::Link;
+    use crate::extensions::Extensions;
+    use crate::{Ref, RefOr, Response, Server};
+
+    #[test]
+    fn link_builder() {
+        let link = Link::builder()
+            .operation_id("getUser")
+            .parameter("userId", "$response.body#/id")
+            .parameters([("verbose", json!(true))])
+            .request_body(json!({ "name": "$request.body#/name" }))
+            .description("The user created by this operation")
+            .server(Server::new("https://users.example.com"))
+            .build();
+
+        assert_json_snapshot!(link, @r##"
+        {
+          "parameters": {
+            "userId": "$response.body#/id",
+            "verbose": true
+          },
+          "operationId": "getUser",
+          "requestBody": {
+            "name": "$request.body#/name"
+          },
+          "description": "The user created by this operation",
+          "server": {
+            "url": "https://users.example.com"
+          }
+        }
+        "##);
+    }
+
+    #[test]
+    fn link_deserialize() {
+        let link: Link = serde_json::from_value(json!({
+            "operationRef": "#/paths/~1users~1{id}/get",
+            "parameters": { "id": "$response.body#/id" },
+            "x-internal": true,
+        }))
+        .unwrap();
+
+        let mut expected = Link::builder()
+            .operation_ref("#/paths/~1users~1{id}/get")
+            .extensions(Extensions::new([("x-internal", true)]))
+            .build();
+        expected.parameter("id", "$response.body#/id");
+        assert!(link == expected);
+    }
+
+    #[test]
+    fn response_links() {
+        let response = Response::builder()
+            .description("created")
+            .link("GetUser", Link::new("getUser"))
+            .link("GetUserByRef", Ref::from_link_name("GetUser"))
+            .build();
+
+        assert!(matches!(response.links.get("GetUser"), Some(RefOr::T(link)) if link.operation_id == "getUser"));
+        assert_json_snapshot!(response.links["GetUserByRef"], @r##"
+        {
+          "$ref": "#/components/links/GetUser"
+        }
+        "##);
+    }
+}
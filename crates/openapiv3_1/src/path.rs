@@ -5,9 +5,11 @@ use indexmap::IndexMap;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::callback::Callback;
 use super::extensions::Extensions;
 use super::request_body::RequestBody;
 use super::response::{Response, Responses};
+use super::schema::{Object, Type, Types};
 use super::security::SecurityRequirement;
 use super::{Deprecated, ExternalDocs, RefOr, Schema, Server};
 
@@ -386,6 +388,12 @@ pub struct Operation {
     #[builder(field)]
     pub security: Option<Vec<SecurityRequirement>>,
 
+    /// Map of possible out-of band [`Callback`]s related to the [`Operation`]. The key is a unique
+    /// identifier for the [`Callback`].
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[builder(field)]
+    pub callbacks: Option<IndexMap<String, RefOr<Callback>>>,
+
     /// Short summary what [`Operation`] does.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub summary: Option<String>,
@@ -406,11 +414,6 @@ pub struct Operation {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub request_body: Option<RequestBody>,
 
-    // TODO
-    #[allow(missing_docs)]
-    #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub callbacks: Option<String>,
-
     /// Define whether the operation is deprecated or not and thus should be avoided consuming.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub deprecated: Option<Deprecated>,
@@ -492,6 +495,17 @@ impl<S: operation_builder::State> OperationBuilder<S> {
         self.servers.get_or_insert_default().push(server.into());
         self
     }
+
+    /// Add [`Callback`] with a unique `name` to the [`Operation`] callbacks.
+    pub fn callback(mut self, name: impl Into<String>, callback: impl Into<RefOr<Callback>>) -> Self {
+        self.callbacks.get_or_insert_default().insert(name.into(), callback.into());
+        self
+    }
+
+    /// Add [`Callback`]s to the [`Operation`] callbacks.
+    pub fn callbacks<N: Into<String>, C: Into<RefOr<Callback>>>(self, callbacks: impl IntoIterator<Item = (N, C)>) -> Self {
+        callbacks.into_iter().fold(self, |this, (n, c)| this.callback(n, c))
+    }
 }
 
 impl Operation {
@@ -556,6 +570,20 @@ impl Operation {
         self.servers.get_or_insert_default().push(server.into());
         self
     }
+
+    /// Add [`Callback`] with a unique `name` to the [`Operation`] callbacks.
+    pub fn callback(&mut self, name: impl Into<String>, callback: impl Into<RefOr<Callback>>) -> &mut Self {
+        self.callbacks.get_or_insert_default().insert(name.into(), callback.into());
+        self
+    }
+
+    /// Add [`Callback`]s to the [`Operation`] callbacks.
+    pub fn callbacks<N: Into<String>, C: Into<RefOr<Callback>>>(
+        &mut self,
+        callbacks: impl IntoIterator<Item = (N, C)>,
+    ) -> &mut Self {
+        callbacks.into_iter().fold(self, |this, (n, c)| this.callback(n, c))
+    }
 }
 
 /// Implements [OpenAPI Parameter Object][parameter] for [`Operation`].
@@ -639,6 +667,77 @@ impl Parameter {
             ..Default::default()
         }
     }
+
+    /// Converts the properties of an object schema into query parameters, one per property.
+    ///
+    /// Properties which are objects or references use the exploded [`ParameterStyle::DeepObject`] style,
+    /// e.g. _`filter[name]=foo`_, references are assumed to point to object schemas. Every other property
+    /// uses the exploded [`ParameterStyle::Form`] style, e.g. _`tag=a&tag=b`_.
+    ///
+    /// A parameter is required if its property is listed in [`Object::required`].
+    pub fn query_from_object(object: &Object) -> Vec<Parameter> {
+        Self::from_properties(object, ParameterIn::Query, |parameter, schema| {
+            let style = if is_object_schema(schema) {
+                ParameterStyle::DeepObject
+            } else {
+                ParameterStyle::Form
+            };
+
+            parameter.style = Some(style);
+            parameter.explode = Some(true);
+        })
+    }
+
+    /// Converts the properties of an object schema into path parameters, one per property.
+    ///
+    /// Path parameters are always required and use the default [`ParameterStyle::Simple`] style.
+    pub fn path_from_object(object: &Object) -> Vec<Parameter> {
+        Self::from_properties(object, ParameterIn::Path, |parameter, _| {
+            parameter.required = true;
+        })
+    }
+
+    fn from_properties(
+        object: &Object,
+        parameter_in: ParameterIn,
+        mut configure: impl FnMut(&mut Parameter, &Schema),
+    ) -> Vec<Parameter> {
+        object
+            .properties
+            .iter()
+            .map(|(name, schema)| {
+                let description = match schema {
+                    Schema::Object(obj) if !obj.description.is_empty() => Some(obj.description.clone()),
+                    _ => None,
+                };
+
+                let mut parameter = Parameter {
+                    name: name.clone(),
+                    parameter_in: parameter_in.clone(),
+                    description,
+                    required: object.required.contains(name),
+                    schema: Some(schema.clone()),
+                    ..Default::default()
+                };
+                configure(&mut parameter, schema);
+                parameter
+            })
+            .collect()
+    }
+}
+
+fn is_object_schema(schema: &Schema) -> bool {
+    match schema {
+        Schema::Bool(_) => false,
+        Schema::Object(obj) => {
+            !obj.reference.is_empty()
+                || match &obj.schema_type {
+                    Some(Types::Single(ty)) => *ty == Type::Object,
+                    Some(Types::Multi(types)) => types.contains(&Type::Object),
+                    None => false,
+                }
+        }
+    }
 }
 
 /// In definition of [`Parameter`].
@@ -697,10 +796,10 @@ pub enum ParameterStyle {
 #[cfg(feature = "debug")]
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
-    use super::{HttpMethod, Operation};
+    use super::{HttpMethod, Operation, Parameter, ParameterIn};
     use crate::security::SecurityRequirement;
     use crate::server::Server;
-    use crate::{PathItem, Paths};
+    use crate::{Object, PathItem, Paths, Ref, Type};
 
     #[test]
     fn test_path_order() {
@@ -801,4 +900,83 @@ mod tests {
 
         assert!(operation.servers.is_some());
     }
+
+    #[test]
+    fn parameters_from_object() {
+        let object = Object::builder()
+            .schema_type(Type::Object)
+            .property("id", Object::builder().schema_type(Type::String).description("The id"))
+            .property(
+                "tags",
+                Object::builder()
+                    .schema_type(Type::Array)
+                    .items(Object::builder().schema_type(Type::String)),
+            )
+            .property("filter", Object::builder().schema_type(vec![Type::Object, Type::Null]))
+            .property("sort", Ref::from_schema_name("Sort"))
+            .required(["id"])
+            .build();
+
+        let query = Parameter::query_from_object(&object);
+        insta::assert_json_snapshot!(query, @r##"
+        [
+          {
+            "name": "id",
+            "in": "query",
+            "description": "The id",
+            "required": true,
+            "schema": {
+              "description": "The id",
+              "type": "string"
+            },
+            "style": "form",
+            "explode": true
+          },
+          {
+            "name": "tags",
+            "in": "query",
+            "required": false,
+            "schema": {
+              "items": {
+                "type": "string"
+              },
+              "type": "array"
+            },
+            "style": "form",
+            "explode": true
+          },
+          {
+            "name": "filter",
+            "in": "query",
+            "required": false,
+            "schema": {
+              "type": [
+                "object",
+                "null"
+              ]
+            },
+            "style": "deepObject",
+            "explode": true
+          },
+          {
+            "name": "sort",
+            "in": "query",
+            "required": false,
+            "schema": {
+              "$ref": "#/components/schemas/Sort"
+            },
+            "style": "deepObject",
+            "explode": true
+          }
+        ]
+        "##);
+
+        let path = Parameter::path_from_object(&object);
+        assert_eq!(path.len(), 4);
+        assert!(
+            path.iter()
+                .all(|p| p.required && p.parameter_in == ParameterIn::Path && p.style.is_none())
+        );
+        assert_eq!(path[0].description.as_deref(), Some("The id"));
+    }
 }
@@ -6,7 +6,9 @@ use indexmap::IndexMap;
 use ordered_float::OrderedFloat;
 use serde_derive::{Deserialize, Serialize};
 
+use super::callback::Callback;
 use super::extensions::Extensions;
+use super::link::Link;
 use super::security::SecurityScheme;
 use super::{RefOr, Response};
 
@@ -54,6 +56,24 @@ pub struct Components {
     #[builder(field)]
     pub security_schemes: IndexMap<String, SecurityScheme>,
 
+    /// Map of reusable [OpenAPI Link Object][link]s or [OpenAPI Reference][reference]s to
+    /// [OpenAPI Link Object][link]s.
+    ///
+    /// [link]: https://spec.openapis.org/oas/latest.html#link-object
+    /// [reference]: https://spec.openapis.org/oas/latest.html#reference-object
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    #[builder(field)]
+    pub links: IndexMap<String, RefOr<Link>>,
+
+    /// Map of reusable [OpenAPI Callback Object][callback]s or [OpenAPI Reference][reference]s to
+    /// [OpenAPI Callback Object][callback]s.
+    ///
+    /// [callback]: https://spec.openapis.org/oas/latest.html#callback-object
+    /// [reference]: https://spec.openapis.org/oas/latest.html#reference-object
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    #[builder(field)]
+    pub callbacks: IndexMap<String, RefOr<Callback>>,
+
     /// Optional extensions "x-something".
     #[serde(skip_serializing_if = "Option::is_none", default, flatten)]
     pub extensions: Option<Extensions>,
@@ -177,6 +197,44 @@ impl<S: components_builder::State> ComponentsBuilder<S> {
 
         self
     }
+
+    /// Add [`Link`] to [`Components`].
+    ///
+    /// Method accepts two arguments; `name` of the reusable link and `link` which is the
+    /// reusable link itself. The link can later be referenced with [`Ref::from_link_name`].
+    pub fn link<N: Into<String>, L: Into<RefOr<Link>>>(mut self, name: N, link: L) -> Self {
+        self.links.insert(name.into(), link.into());
+        self
+    }
+
+    /// Add multiple [`Link`]s to [`Components`] from iterator.
+    pub fn links_from_iter<I: IntoIterator<Item = (N, L)>, N: Into<String>, L: Into<RefOr<Link>>>(
+        mut self,
+        links: I,
+    ) -> Self {
+        self.links
+            .extend(links.into_iter().map(|(name, link)| (name.into(), link.into())));
+        self
+    }
+
+    /// Add [`Callback`] to [`Components`].
+    ///
+    /// Method accepts two arguments; `name` of the reusable callback and `callback` which is the
+    /// reusable callback itself. The callback can later be referenced with [`Ref::from_callback_name`].
+    pub fn callback<N: Into<String>, C: Into<RefOr<Callback>>>(mut self, name: N, callback: C) -> Self {
+        self.callbacks.insert(name.into(), callback.into());
+        self
+    }
+
+    /// Add multiple [`Callback`]s to [`Components`] from iterator.
+    pub fn callbacks_from_iter<I: IntoIterator<Item = (N, C)>, N: Into<String>, C: Into<RefOr<Callback>>>(
+        mut self,
+        callbacks: I,
+    ) -> Self {
+        self.callbacks
+            .extend(callbacks.into_iter().map(|(name, callback)| (name.into(), callback.into())));
+        self
+    }
 }
 
 impl<S: components_builder::IsComplete> From<ComponentsBuilder<S>> for Components {
@@ -308,6 +366,18 @@ impl Ref {
     pub fn from_response_name<I: Into<String>>(response_name: I) -> Self {
         Self::new(format!("#/components/responses/{}", response_name.into()))
     }
+
+    /// Construct a new [`Ref`] from provided link name. This will create a [`Ref`] that
+    /// references the reusable link.
+    pub fn from_link_name<I: Into<String>>(link_name: I) -> Self {
+        Self::new(format!("#/components/links/{}", link_name.into()))
+    }
+
+    /// Construct a new [`Ref`] from provided callback name. This will create a [`Ref`] that
+    /// references the reusable callback.
+    pub fn from_callback_name<I: Into<String>>(callback_name: I) -> Self {
+        Self::new(format!("#/components/callbacks/{}", callback_name.into()))
+    }
 }
 
 impl<S: ref_builder::IsComplete> From<RefBuilder<S>> for Schema {
@@ -487,6 +557,21 @@ pub struct Object {
     #[serde(rename = "$ref", skip_serializing_if = "IsEmpty::is_empty")]
     #[builder(default, name = "reference")]
     pub reference: String,
+    /// The `$dynamicRef` keyword references a schema by its dynamic anchor, resolved at evaluation time.
+    /// <https://www.learnjsonschema.com/2020-12/core/dynamicref/>
+    #[serde(rename = "$dynamicRef", skip_serializing_if = "IsEmpty::is_empty")]
+    #[builder(default)]
+    pub dynamic_ref: String,
+    /// The `$anchor` keyword defines a plain name fragment identifier for the schema.
+    /// <https://www.learnjsonschema.com/2020-12/core/anchor/>
+    #[serde(rename = "$anchor", skip_serializing_if = "IsEmpty::is_empty")]
+    #[builder(default)]
+    pub anchor: String,
+    /// The `$dynamicAnchor` keyword defines an anchor which can be targeted by `$dynamicRef`.
+    /// <https://www.learnjsonschema.com/2020-12/core/dynamicanchor/>
+    #[serde(rename = "$dynamicAnchor", skip_serializing_if = "IsEmpty::is_empty")]
+    #[builder(default)]
+    pub dynamic_anchor: String,
     /// The `$comment` keyword provides annotations for documentation.
     /// <https://www.learnjsonschema.com/2020-12/meta-data/comment/>
     #[serde(rename = "$comment", skip_serializing_if = "IsEmpty::is_empty")]
@@ -604,6 +689,11 @@ pub struct Object {
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     #[builder(default)]
     pub definitions: IndexMap<String, Schema>,
+    /// The `$defs` keyword holds reusable schema definitions for reference.
+    /// <https://www.learnjsonschema.com/2020-12/core/defs/>
+    #[serde(rename = "$defs", skip_serializing_if = "IsEmpty::is_empty")]
+    #[builder(default)]
+    pub defs: IndexMap<String, Schema>,
     /// The `patternProperties` keyword maps regex patterns to schemas for matching property names.
     /// <https://www.learnjsonschema.com/2020-12/applicator/patternProperties/>
     #[serde(rename = "patternProperties", skip_serializing_if = "IsEmpty::is_empty")]
@@ -614,6 +704,16 @@ pub struct Object {
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     #[builder(default)]
     pub dependencies: IndexMap<String, Schema>,
+    /// The `dependentSchemas` keyword applies a subschema to the object when the named property is present.
+    /// <https://www.learnjsonschema.com/2020-12/applicator/dependentschemas/>
+    #[serde(rename = "dependentSchemas", skip_serializing_if = "IsEmpty::is_empty")]
+    #[builder(default)]
+    pub dependent_schemas: IndexMap<String, Schema>,
+    /// The `dependentRequired` keyword lists properties which are required when the named property is present.
+    /// <https://www.learnjsonschema.com/2020-12/validation/dependentrequired/>
+    #[serde(rename = "dependentRequired", skip_serializing_if = "IsEmpty::is_empty")]
+    #[builder(default)]
+    pub dependent_required: IndexMap<String, Vec<String>>,
     /// The `propertyNames` keyword restricts all property names in an object to match this schema.
     /// <https://www.learnjsonschema.com/2020-12/applicator/propertyNames/>
     #[serde(rename = "propertyNames", skip_serializing_if = "IsEmpty::is_empty")]
@@ -915,9 +1015,11 @@ impl Object {
             self.items.iter_mut(),
             self.prefix_items.iter_mut().flatten(),
             self.definitions.values_mut(),
+            self.defs.values_mut(),
             self.properties.values_mut(),
             self.pattern_properties.values_mut(),
             self.dependencies.values_mut(),
+            self.dependent_schemas.values_mut(),
             self.property_names.iter_mut(),
             self.if_cond.iter_mut(),
             self.then.iter_mut(),
@@ -937,6 +1039,7 @@ impl Object {
         self.all_of = all_ofs.into_iter().filter(|schema| !schema.is_empty()).collect();
         dedupe_array(&mut self.examples);
         dedupe_array(&mut self.required);
+        self.dependent_required.values_mut().for_each(dedupe_array);
         if let Some(_enum) = &mut self.enum_values {
             dedupe_array(_enum);
         }
@@ -970,11 +1073,15 @@ impl Object {
     }
 
     fn merge(&mut self, other: &mut Self) {
+        merge_conditional(self, other);
         merge_item!(
             [self, other] => {
                 id => merge_skip,
                 schema => merge_sub_schema,
                 reference => merge_skip,
+                dynamic_ref => merge_skip,
+                anchor => merge_skip,
+                dynamic_anchor => merge_skip,
                 comment => merge_drop_second,
                 title => merge_drop_second,
                 description => merge_drop_second,
@@ -1004,9 +1111,12 @@ impl Object {
                 required => merge_array_combine,
                 additional_properties => merge_sub_schema,
                 definitions => merge_schema_map,
+                defs => merge_schema_map,
                 properties => merge_schema_map,
                 pattern_properties => merge_schema_map,
                 dependencies => merge_schema_map,
+                dependent_schemas => merge_schema_map,
+                dependent_required => merge_required_map,
                 property_names => merge_sub_schema,
                 const_value => merge_skip,
                 enum_values => merge_array_union_optional,
@@ -1014,9 +1124,6 @@ impl Object {
                 format => merge_skip,
                 content_media_type => merge_skip,
                 content_encoding => merge_skip,
-                // _if
-                // then
-                // _else
                 any_of => merge_array_combine_optional,
                 one_of => merge_array_combine_optional,
                 not => merge_sub_schema,
@@ -1103,6 +1210,32 @@ fn merge_schema_map(value: &mut IndexMap<String, Schema>, other: &mut IndexMap<S
     }
 }
 
+fn merge_required_map(value: &mut IndexMap<String, Vec<String>>, other: &mut IndexMap<String, Vec<String>>) {
+    for (key, mut other) in other.drain(..) {
+        value.entry(key).or_default().append(&mut other);
+    }
+}
+
+/// `if`, `then` and `else` only have meaning together, so they are moved as a group
+/// and only when the target does not already have a conditional.
+fn merge_conditional(value: &mut Object, other: &mut Object) {
+    let value_empty = value.if_cond.is_none() && value.then.is_none() && value.else_cond.is_none();
+    let other_empty = other.if_cond.is_none() && other.then.is_none() && other.else_cond.is_none();
+    if other_empty {
+        return;
+    }
+
+    if value_empty {
+        value.if_cond = other.if_cond.take();
+        value.then = other.then.take();
+        value.else_cond = other.else_cond.take();
+    } else if value.if_cond == other.if_cond && value.then == other.then && value.else_cond == other.else_cond {
+        other.if_cond.take();
+        other.then.take();
+        other.else_cond.take();
+    }
+}
+
 fn merge_type(value: &mut Option<Types>, other: &mut Option<Types>) {
     match (value.as_mut().unwrap(), other.take().unwrap()) {
         (Types::Single(s), Types::Single(ref o)) if s != o => {
@@ -1806,4 +1939,140 @@ mod tests {
         let value = serde_json::to_value(&json_value).unwrap();
         assert_eq!(value["anyOf"][0].get("x-some-extension"), Some(&expected));
     }
+
+    #[test]
+    fn serialize_deserialize_2020_12_keywords() {
+        let value = json!({
+            "$defs": {
+                "name": { "type": "string" }
+            },
+            "$anchor": "root",
+            "$dynamicAnchor": "node",
+            "prefixItems": [{ "type": "string" }, { "type": "integer" }],
+            "dependentSchemas": {
+                "credit_card": { "required": ["billing_address"] }
+            },
+            "dependentRequired": {
+                "credit_card": ["billing_address"]
+            },
+            "if": { "properties": { "kind": { "const": "a" } } },
+            "then": { "required": ["a"] },
+            "else": { "required": ["b"] },
+            "unevaluatedProperties": false
+        });
+
+        let object: Object = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(object.defs.len(), 1);
+        assert_eq!(object.anchor, "root");
+        assert_eq!(object.dynamic_anchor, "node");
+        assert_eq!(object.dependent_required["credit_card"], ["billing_address"]);
+        assert!(object.dependent_schemas.contains_key("credit_card"));
+        assert!(object.if_cond.is_some() && object.then.is_some() && object.else_cond.is_some());
+        assert_eq!(serde_json::to_value(&object).unwrap(), value);
+    }
+
+    #[test]
+    fn ref_with_sibling_keywords() {
+        let mut object = Object::all_ofs([
+            Schema::from(Object::builder().reference("#/components/schemas/Pet")),
+            Schema::from(
+                Object::builder()
+                    .description("The pet which is owned")
+                    .unevaluated_properties(false),
+            ),
+        ]);
+
+        object.optimize();
+
+        assert_json_snapshot!(object, @r##"
+        {
+          "$ref": "#/components/schemas/Pet",
+          "description": "The pet which is owned",
+          "unevaluatedProperties": false
+        }
+        "##);
+    }
+
+    #[test]
+    fn optimize_keeps_conditionals_together() {
+        let conditional = |kind: &str, required: &str| {
+            Schema::from(
+                Object::builder()
+                    .if_cond(Object::builder().property("kind", Object::builder().const_value(kind)))
+                    .then_cond(Object::builder().require(required))
+                    .build(),
+            )
+        };
+
+        let mut object = Object::all_ofs([conditional("a", "a"), conditional("b", "b")]);
+        object.optimize();
+
+        assert_json_snapshot!(object, @r#"
+        {
+          "allOf": [
+            {
+              "if": {
+                "properties": {
+                  "kind": {
+                    "const": "b"
+                  }
+                }
+              },
+              "then": {
+                "required": [
+                  "b"
+                ]
+              }
+            }
+          ],
+          "if": {
+            "properties": {
+              "kind": {
+                "const": "a"
+              }
+            }
+          },
+          "then": {
+            "required": [
+              "a"
+            ]
+          }
+        }
+        "#);
+    }
+
+    #[test]
+    fn optimize_merges_dependent_schemas() {
+        let mut object = Object::all_ofs([
+            Object::builder()
+                .dependent_schemas(IndexMap::from_iter([("a".to_owned(), Object::builder().require("b").into())]))
+                .dependent_required(IndexMap::from_iter([("a".to_owned(), vec!["b".to_owned()])]))
+                .build(),
+            Object::builder()
+                .dependent_schemas(IndexMap::from_iter([("a".to_owned(), Object::builder().require("c").into())]))
+                .dependent_required(IndexMap::from_iter([("a".to_owned(), vec!["b".to_owned(), "c".to_owned()])]))
+                .build(),
+        ]);
+
+        object.optimize();
+
+        assert_json_snapshot!(object, @r#"
+        {
+          "dependentSchemas": {
+            "a": {
+              "required": [
+                "b",
+                "c"
+              ]
+            }
+          },
+          "dependentRequired": {
+            "a": [
+              "b",
+              "c"
+            ]
+          }
+        }
+        "#);
+    }
 }
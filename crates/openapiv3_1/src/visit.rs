@@ -0,0 +1,424 @@
+//! Traversal of an [`OpenApi`] document with mutable access.
+//!
+//! Implement [`Visitor`] and override the methods for the nodes of interest, every method
+//! defaults to the matching `walk_*` function which visits the children of the node.
+//! Call the `walk_*` function from an overridden method to keep descending.
+//!
+//! ```rust
+//! use openapiv3_1::visit::{self, Visitor};
+//! use openapiv3_1::path::Operation;
+//! use openapiv3_1::{HttpMethod, OpenApi, Paths};
+//!
+//! /// Marks every operation as generated.
+//! struct MarkGenerated;
+//!
+//! impl Visitor for MarkGenerated {
+//!     fn visit_operation(&mut self, method: HttpMethod, operation: &mut Operation) {
+//!         operation.extensions.get_or_insert_default().insert("x-generated".into(), true.into());
+//!         visit::walk_operation(self, method, operation);
+//!     }
+//! }
+//!
+//! let mut openapi = OpenApi::default();
+//! openapi.walk(&mut MarkGenerated);
+//! ```
+
+use crate::content::Content;
+use crate::header::Header;
+use crate::path::{Operation, Parameter};
+use crate::request_body::RequestBody;
+use crate::{Components, HttpMethod, OpenApi, PathItem, Paths, Ref, RefOr, Response, Schema};
+
+/// A visitor of the nodes of an [`OpenApi`] document, see the [module docs](self).
+pub trait Visitor {
+    /// Visit the root of the document.
+    fn visit_openapi(&mut self, openapi: &mut OpenApi) {
+        walk_openapi(self, openapi);
+    }
+
+    /// Visit the paths of the document.
+    ///
+    /// Override this to add or remove paths, for example to prune internal routes.
+    fn visit_paths(&mut self, paths: &mut Paths) {
+        walk_paths(self, paths);
+    }
+
+    /// Visit a path item, `path` is the path template or the expression of a callback.
+    fn visit_path_item(&mut self, path: &str, item: &mut PathItem) {
+        walk_path_item(self, path, item);
+    }
+
+    /// Visit an operation of a path item.
+    fn visit_operation(&mut self, method: HttpMethod, operation: &mut Operation) {
+        walk_operation(self, method, operation);
+    }
+
+    /// Visit a parameter of a path item or an operation.
+    fn visit_parameter(&mut self, parameter: &mut Parameter) {
+        walk_parameter(self, parameter);
+    }
+
+    /// Visit the request body of an operation.
+    fn visit_request_body(&mut self, request_body: &mut RequestBody) {
+        walk_request_body(self, request_body);
+    }
+
+    /// Visit a response, `status` is the status code of an operation response or the name of a
+    /// response component.
+    fn visit_response(&mut self, status: &str, response: &mut Response) {
+        walk_response(self, status, response);
+    }
+
+    /// Visit a header of a response.
+    fn visit_header(&mut self, name: &str, header: &mut Header) {
+        walk_header(self, name, header);
+    }
+
+    /// Visit the content of a request body or response for a media type.
+    fn visit_content(&mut self, media_type: &str, content: &mut Content) {
+        walk_content(self, media_type, content);
+    }
+
+    /// Visit the components of the document.
+    fn visit_components(&mut self, components: &mut Components) {
+        walk_components(self, components);
+    }
+
+    /// Visit a schema.
+    ///
+    /// This is called for every schema, including the schemas nested in other schemas.
+    fn visit_schema(&mut self, schema: &mut Schema) {
+        walk_schema(self, schema);
+    }
+
+    /// Visit a reference, either the location of a [`Ref`], the `$ref` of a schema or a
+    /// mapping value of a discriminator.
+    fn visit_reference(&mut self, reference: &mut String) {
+        let _ = reference;
+    }
+}
+
+impl OpenApi {
+    /// Visit the document with the visitor, see [`visit`](crate::visit).
+    pub fn walk(&mut self, visitor: &mut (impl Visitor + ?Sized)) {
+        visitor.visit_openapi(self);
+    }
+}
+
+/// Visit the paths and components of the document.
+pub fn walk_openapi<V: Visitor + ?Sized>(visitor: &mut V, openapi: &mut OpenApi) {
+    visitor.visit_paths(&mut openapi.paths);
+
+    if let Some(components) = &mut openapi.components {
+        visitor.visit_components(components);
+    }
+}
+
+/// Visit every path item.
+pub fn walk_paths<V: Visitor + ?Sized>(visitor: &mut V, paths: &mut Paths) {
+    for (path, item) in &mut paths.paths {
+        visitor.visit_path_item(path, item);
+    }
+}
+
+/// Visit the parameters and operations of the path item.
+pub fn walk_path_item<V: Visitor + ?Sized>(visitor: &mut V, path: &str, item: &mut PathItem) {
+    let _ = path;
+
+    for parameter in item.parameters.iter_mut().flatten() {
+        visitor.visit_parameter(parameter);
+    }
+
+    let operations = [
+        (HttpMethod::Get, &mut item.get),
+        (HttpMethod::Put, &mut item.put),
+        (HttpMethod::Post, &mut item.post),
+        (HttpMethod::Delete, &mut item.delete),
+        (HttpMethod::Options, &mut item.options),
+        (HttpMethod::Head, &mut item.head),
+        (HttpMethod::Patch, &mut item.patch),
+        (HttpMethod::Trace, &mut item.trace),
+    ];
+
+    for (method, operation) in operations {
+        if let Some(operation) = operation {
+            visitor.visit_operation(method, operation);
+        }
+    }
+}
+
+/// Visit the parameters, request body, responses and callbacks of the operation.
+pub fn walk_operation<V: Visitor + ?Sized>(visitor: &mut V, method: HttpMethod, operation: &mut Operation) {
+    let _ = method;
+
+    for parameter in operation.parameters.iter_mut().flatten() {
+        visitor.visit_parameter(parameter);
+    }
+
+    if let Some(request_body) = &mut operation.request_body {
+        visitor.visit_request_body(request_body);
+    }
+
+    for (status, response) in &mut operation.responses.responses {
+        walk_ref_or(visitor, response, |visitor, response| {
+            visitor.visit_response(status, response)
+        });
+    }
+
+    for callback in operation.callbacks.iter_mut().flat_map(|callbacks| callbacks.values_mut()) {
+        walk_ref_or(visitor, callback, |visitor, callback| {
+            for (expression, item) in &mut callback.paths {
+                visitor.visit_path_item(expression, item);
+            }
+        });
+    }
+}
+
+/// Visit the schema of the parameter.
+pub fn walk_parameter<V: Visitor + ?Sized>(visitor: &mut V, parameter: &mut Parameter) {
+    if let Some(schema) = &mut parameter.schema {
+        visitor.visit_schema(schema);
+    }
+}
+
+/// Visit the content of the request body.
+pub fn walk_request_body<V: Visitor + ?Sized>(visitor: &mut V, request_body: &mut RequestBody) {
+    for (media_type, content) in &mut request_body.content {
+        visitor.visit_content(media_type, content);
+    }
+}
+
+/// Visit the headers and content of the response.
+pub fn walk_response<V: Visitor + ?Sized>(visitor: &mut V, status: &str, response: &mut Response) {
+    let _ = status;
+
+    for (name, header) in &mut response.headers {
+        visitor.visit_header(name, header);
+    }
+
+    for (media_type, content) in &mut response.content {
+        visitor.visit_content(media_type, content);
+    }
+}
+
+/// Visit the schema of the header.
+pub fn walk_header<V: Visitor + ?Sized>(visitor: &mut V, name: &str, header: &mut Header) {
+    let _ = name;
+    visitor.visit_schema(&mut header.schema);
+}
+
+/// Visit the schema of the content.
+pub fn walk_content<V: Visitor + ?Sized>(visitor: &mut V, media_type: &str, content: &mut Content) {
+    let _ = media_type;
+
+    if let Some(schema) = &mut content.schema {
+        visitor.visit_schema(schema);
+    }
+}
+
+/// Visit the schemas, responses and callbacks of the components.
+pub fn walk_components<V: Visitor + ?Sized>(visitor: &mut V, components: &mut Components) {
+    for schema in components.schemas.values_mut() {
+        visitor.visit_schema(schema);
+    }
+
+    for (name, response) in &mut components.responses {
+        walk_ref_or(visitor, response, |visitor, response| visitor.visit_response(name, response));
+    }
+
+    for callback in components.callbacks.values_mut() {
+        walk_ref_or(visitor, callback, |visitor, callback| {
+            for (expression, item) in &mut callback.paths {
+                visitor.visit_path_item(expression, item);
+            }
+        });
+    }
+}
+
+/// Visit the reference and the nested schemas of the schema.
+pub fn walk_schema<V: Visitor + ?Sized>(visitor: &mut V, schema: &mut Schema) {
+    let Schema::Object(object) = schema else {
+        return;
+    };
+
+    if !object.reference.is_empty() {
+        visitor.visit_reference(&mut object.reference);
+    }
+
+    if let Some(discriminator) = &mut object.discriminator {
+        for reference in discriminator.mapping.values_mut() {
+            visitor.visit_reference(reference);
+        }
+    }
+
+    let maps = [
+        &mut object.properties,
+        &mut object.definitions,
+        &mut object.defs,
+        &mut object.pattern_properties,
+        &mut object.dependencies,
+        &mut object.dependent_schemas,
+    ];
+
+    let lists = [
+        Some(&mut object.all_of),
+        object.prefix_items.as_mut(),
+        object.any_of.as_mut(),
+        object.one_of.as_mut(),
+    ];
+
+    let schemas = [
+        &mut object.schema,
+        &mut object.additional_items,
+        &mut object.items,
+        &mut object.contains,
+        &mut object.additional_properties,
+        &mut object.property_names,
+        &mut object.content_schema,
+        &mut object.if_cond,
+        &mut object.then,
+        &mut object.else_cond,
+        &mut object.not,
+        &mut object.unevaluated_items,
+        &mut object.unevaluated_properties,
+    ];
+
+    maps.into_iter()
+        .flat_map(|map| map.values_mut())
+        .chain(lists.into_iter().flatten().flatten())
+        .chain(schemas.into_iter().flatten())
+        .for_each(|schema| visitor.visit_schema(schema));
+}
+
+fn walk_ref_or<V: Visitor + ?Sized, T>(visitor: &mut V, value: &mut RefOr<T>, walk: impl FnOnce(&mut V, &mut T)) {
+    match value {
+        RefOr::Ref(Ref { ref_location, .. }) => visitor.visit_reference(ref_location),
+        RefOr::T(value) => walk(visitor, value),
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::{Visitor, walk_schema};
+    use crate::content::Content;
+    use crate::path::{Operation, Parameter, ParameterIn};
+    use crate::{Components, HttpMethod, Object, OpenApi, PathItem, Paths, Ref, Response, Schema};
+
+    #[derive(Default)]
+    struct Collect {
+        operations: Vec<String>,
+        references: Vec<String>,
+        schemas: usize,
+    }
+
+    impl Visitor for Collect {
+        fn visit_operation(&mut self, method: HttpMethod, operation: &mut Operation) {
+            self.operations
+                .push(format!("{method} {}", operation.operation_id.as_deref().unwrap_or_default()));
+            super::walk_operation(self, method, operation);
+        }
+
+        fn visit_schema(&mut self, schema: &mut Schema) {
+            self.schemas += 1;
+            walk_schema(self, schema);
+        }
+
+        fn visit_reference(&mut self, reference: &mut String) {
+            self.references.push(reference.clone());
+            *reference = reference.replace("#/components/schemas/", "#/components/schemas/v1.");
+        }
+    }
+
+    /// Removes the paths starting with `/internal`.
+    struct Prune;
+
+    impl Visitor for Prune {
+        fn visit_paths(&mut self, paths: &mut Paths) {
+            paths.paths.retain(|path, _| !path.starts_with("/internal"));
+        }
+    }
+
+    fn openapi() -> OpenApi {
+        let user = Schema::object(
+            Object::builder()
+                .property("id", Object::builder().schema_type(crate::Type::String))
+                .property("friends", Object::builder().items(Ref::from_schema_name("User")))
+                .build(),
+        );
+
+        let get_user = Operation::builder()
+            .operation_id("getUser")
+            .parameter(
+                Parameter::builder()
+                    .name("id")
+                    .parameter_in(ParameterIn::Path)
+                    .required(true)
+                    .schema(Object::builder().schema_type(crate::Type::String))
+                    .build(),
+            )
+            .response(
+                "200",
+                Response::builder()
+                    .description("user")
+                    .content("application/json", Content::new(Some(Ref::from_schema_name("User"))))
+                    .build(),
+            )
+            .response("404", Ref::from_response_name("NotFound"))
+            .build();
+
+        let mut users = PathItem::new(HttpMethod::Get, get_user);
+        users.merge_operations(PathItem::new(
+            HttpMethod::Delete,
+            Operation::builder().operation_id("deleteUser").build(),
+        ));
+
+        OpenApi::builder()
+            .paths(
+                Paths::builder()
+                    .path("/users/{id}", users)
+                    .path("/internal/health", PathItem::new(HttpMethod::Get, Operation::new())),
+            )
+            .components(Components::builder().schema("User", user).build())
+            .build()
+    }
+
+    #[test]
+    fn walk() {
+        let mut openapi = openapi();
+        let mut collect = Collect::default();
+        openapi.walk(&mut collect);
+
+        assert_eq!(collect.operations, ["get getUser", "delete deleteUser", "get "]);
+        assert_eq!(
+            collect.references,
+            [
+                "#/components/schemas/User",
+                "#/components/responses/NotFound",
+                "#/components/schemas/User"
+            ]
+        );
+        // The parameter, the response content and the user schema with its two properties
+        // and the items of the friends property.
+        assert_eq!(collect.schemas, 6);
+
+        let user = &openapi.components.as_ref().unwrap().schemas["User"];
+        let Schema::Object(user) = user else { panic!("not an object") };
+        let Schema::Object(friends) = &user.properties["friends"] else {
+            panic!("not an object")
+        };
+        let Some(Schema::Object(items)) = &friends.items else {
+            panic!("not an object")
+        };
+        assert_eq!(items.reference, "#/components/schemas/v1.User");
+    }
+
+    #[test]
+    fn prune() {
+        let mut openapi = openapi();
+        openapi.walk(&mut Prune);
+
+        assert!(openapi.paths.paths.contains_key("/users/{id}"));
+        assert!(!openapi.paths.paths.contains_key("/internal/health"));
+    }
+}
@@ -89,6 +89,8 @@
 //! - Coverage: This crate works with [`cargo-llvm-cov`](https://crates.io/crates/cargo-llvm-cov)
 //!   out of the box, which allows you to instrument the proc-macro expansion.
 //! - Testing: You can define tests with the `#[test]` macro and the tests will run on the generated code.
+//! - Toolchains: You can pin the toolchain with [`Config::toolchain`] to check the output on both stable and nightly.
+//! - Configuration: You can set `--cfg` flags and environment variables with [`Config::cfg`] and [`Config::env`] to exercise macros which depend on them.
 //!
 //! ## Alternatives
 //!
@@ -138,6 +140,7 @@ use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::path::Path;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
 use cargo_manifest::DependencyDetail;
 
@@ -175,6 +178,36 @@ pub struct CompileOutput {
     pub test_stderr: String,
     /// The stdout of the test results.
     pub test_stdout: String,
+    /// Timings and the size of the compiled artifact.
+    pub report: CompileReport,
+}
+
+/// Timings and the size of the compiled artifact.
+///
+/// Unlike the rest of the [`CompileOutput`], the report differs between runs,
+/// so it is not part of its [`Display`](std::fmt::Display) output. It can be
+/// used to track compile time and codegen size regressions of macros.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompileReport {
+    /// The wall time of the macro expansion.
+    ///
+    /// This includes compiling the dependencies, if they are not already built.
+    pub expand_time: Duration,
+    /// The wall time of building the code, including running the tests if
+    /// [`Config::test`] is set.
+    ///
+    /// Zero if the expansion failed.
+    pub build_time: Duration,
+    /// True if all dependencies were reused from a previous compilation, false
+    /// if any of them had to be compiled.
+    ///
+    /// This only covers the dependencies, the given code itself is compiled every time.
+    pub dependencies_fresh: bool,
+    /// The size in bytes of the compiled binary, or of the `rlib` for
+    /// [`no_std`](Config::no_std) crates.
+    ///
+    /// `None` if the build failed.
+    pub artifact_size: Option<u64>,
 }
 
 impl std::fmt::Display for CompileOutput {
@@ -197,12 +230,43 @@ impl std::fmt::Display for CompileOutput {
 }
 
 fn cargo(config: &Config, manifest_path: &Path, subcommand: &str) -> Command {
-    let mut program = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    let mut program = if let Some(toolchain) = &config.toolchain {
+        let mut program = Command::new("rustup");
+        program.arg("run").arg(toolchain.as_ref()).arg("cargo");
+        program
+    } else {
+        Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+    };
     program.arg(subcommand);
     program.current_dir(manifest_path.parent().unwrap());
 
     program.env_clear();
-    program.envs(std::env::vars().filter(|(k, _)| !k.starts_with("CARGO_") && k != "OUT_DIR"));
+    program.envs(std::env::vars().filter(|(k, _)| {
+        !k.starts_with("CARGO_")
+            && k != "OUT_DIR"
+            // These would bypass the pinned toolchain.
+            && (config.toolchain.is_none() || !matches!(k.as_str(), "RUSTC" | "RUSTDOC" | "RUSTUP_TOOLCHAIN"))
+    }));
+    program.envs(&config.env);
+
+    if !config.cfgs.is_empty() || !config.rustflags.is_empty() {
+        let mut rustflags = config.rustflags.clone();
+        for cfg in &config.cfgs {
+            let check_cfg = match cfg.split_once('=') {
+                Some((name, value)) => format!("cfg({name}, values({value}))"),
+                None => format!("cfg({cfg})"),
+            };
+            rustflags.extend(["--cfg".into(), cfg.clone(), "--check-cfg".into(), check_cfg]);
+        }
+
+        // Arrays from the command line are appended to the ones from the config files,
+        // unlike `RUSTFLAGS` which would replace them. `build.rustflags` is ignored if
+        // any `target` rustflags are set.
+        let rustflags = toml::Value::Array(rustflags.into_iter().map(toml::Value::String).collect());
+        program
+            .arg("--config")
+            .arg(format!("target.'cfg(all())'.rustflags={rustflags}"));
+    }
     program.env("CARGO_TERM_COLOR", "never");
     program.stderr(std::process::Stdio::piped());
     program.stdout(std::process::Stdio::piped());
@@ -217,10 +281,21 @@ fn cargo(config: &Config, manifest_path: &Path, subcommand: &str) -> Command {
     program.arg("--manifest-path").arg(manifest_path);
     program.arg("--target-dir").arg(target_dir);
 
-    if !cfg!(trybuild_no_target) && !cfg!(postcompile_no_target) && config.target_dir.ends_with(target_triple::TARGET) {
+    if let Some(target) = &config.target {
+        program.arg("--target").arg(target.as_ref());
+    } else if !cfg!(trybuild_no_target) && !cfg!(postcompile_no_target) && config.target_dir.ends_with(target_triple::TARGET)
+    {
         program.arg("--target").arg(target_triple::TARGET);
     }
 
+    if config.rustc_bootstrap {
+        program.env("RUSTC_BOOTSTRAP", "1");
+    }
+
+    if !config.build_std.is_empty() {
+        program.arg(format!("-Zbuild-std={}", config.build_std.join(",")));
+    }
+
     program
 }
 
@@ -345,6 +420,13 @@ fn generate_cargo_toml(config: &Config, crate_name: &str) -> std::io::Result<(St
 
             deps
         }),
+        // The `__` separators of the crate name are not snake case, which is only linted for libraries.
+        lib: config.no_std.then(|| cargo_manifest::Product {
+            name: Some(crate_name.replace("__", "_")),
+            // Inherit the edition of the package.
+            edition: None,
+            ..Default::default()
+        }),
         patch: workspace_manifest.patch.clone().map(|mut patch| {
             patch.values_mut().for_each(|deps| {
                 deps.values_mut().for_each(|dep| {
@@ -386,7 +468,20 @@ pub fn compile_custom(tokens: impl std::fmt::Display, config: &Config) -> std::i
     std::fs::write(&manifest_path, cargo_toml)?;
     std::fs::write(tmp_crate_path.join("Cargo.lock"), cargo_lock)?;
 
-    let main_path = tmp_crate_path.join("src").join("main.rs");
+    // A `no_std` crate is compiled as a library so that it does not need a `main` function or a panic handler.
+    let (main_path, tokens) = if config.no_std {
+        (tmp_crate_path.join("src").join("lib.rs"), format!("#![no_std]\n{tokens}"))
+    } else {
+        (tmp_crate_path.join("src").join("main.rs"), tokens)
+    };
+
+    // Remove the source file of a previous run with a different crate type.
+    for file in ["main.rs", "lib.rs"] {
+        let path = tmp_crate_path.join("src").join(file);
+        if path != main_path && path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
 
     write_tmp_file(&tokens, &main_path);
 
@@ -397,7 +492,9 @@ pub fn compile_custom(tokens: impl std::fmt::Display, config: &Config) -> std::i
     program.env("RUSTC_BOOTSTRAP", "1");
     program.arg("--").arg("-Zunpretty=expanded");
 
+    let expand_start = Instant::now();
     let output = program.output().unwrap();
+    let expand_time = expand_start.elapsed();
 
     let stdout = String::from_utf8(output.stdout).unwrap();
     let syn_file = syn::parse_file(&stdout);
@@ -426,24 +523,73 @@ pub fn compile_custom(tokens: impl std::fmt::Display, config: &Config) -> std::i
         expanded: stdout,
         test_stderr: String::new(),
         test_stdout: String::new(),
+        report: CompileReport {
+            expand_time,
+            ..Default::default()
+        },
     };
 
     if result.status == ExitStatus::Success {
-        let mut program = cargo(config, &manifest_path, "test");
+        // The tests can not be built for a target without `std` or run on a different target.
+        let mut program = if config.no_std || config.target.is_some() {
+            cargo(config, &manifest_path, "build")
+        } else {
+            let mut program = cargo(config, &manifest_path, "test");
 
-        if !config.test {
-            program.arg("--no-run");
-        }
+            if !config.test {
+                program.arg("--no-run");
+            }
+
+            program
+        };
 
+        // Diagnostics are still rendered to stderr, only the artifacts are reported as json on stdout.
+        program.arg("--message-format=json-render-diagnostics");
+
+        let build_start = Instant::now();
         let comp_output = program.output().unwrap();
+        result.report.build_time = build_start.elapsed();
+
         result.status = if comp_output.status.success() {
             ExitStatus::Success
         } else {
             ExitStatus::Failure(comp_output.status.code().unwrap_or(-1))
         };
 
+        // Only the size of the target built from the given tokens is reported.
+        let (target_kind, target_name) = if config.no_std {
+            (cargo_metadata::TargetKind::Lib, crate_name.replace("__", "_"))
+        } else {
+            (cargo_metadata::TargetKind::Bin, crate_name.clone())
+        };
+
+        let mut test_stdout = String::new();
+        result.report.dependencies_fresh = true;
+        for message in cargo_metadata::Message::parse_stream(comp_output.stdout.as_slice()) {
+            match message? {
+                cargo_metadata::Message::CompilerArtifact(artifact)
+                    if artifact.manifest_path.as_std_path() == manifest_path
+                        && artifact.target.name == target_name
+                        && artifact.target.kind.contains(&target_kind) =>
+                {
+                    let artifact = artifact
+                        .executable
+                        .or_else(|| artifact.filenames.into_iter().find(|f| f.extension() == Some("rlib")));
+                    result.report.artifact_size = artifact.and_then(|path| std::fs::metadata(path).ok()).map(|m| m.len());
+                }
+                cargo_metadata::Message::CompilerArtifact(artifact)
+                    if artifact.manifest_path.as_std_path() == manifest_path => {}
+                cargo_metadata::Message::CompilerArtifact(artifact) => result.report.dependencies_fresh &= artifact.fresh,
+                cargo_metadata::Message::TextLine(line) => {
+                    test_stdout.push_str(&line);
+                    test_stdout.push('\n');
+                }
+                _ => {}
+            }
+        }
+
         result.test_stderr = cleanup_output(&comp_output.stderr);
-        result.test_stdout = cleanup_output(&comp_output.stdout);
+        result.test_stdout = cleanup_output(test_stdout.as_bytes());
     };
 
     Ok(result)
@@ -473,6 +619,103 @@ pub struct Config {
     pub test: bool,
     /// The rust edition to use.
     pub edition: String,
+    /// The rustup toolchain to compile with, e.g. `nightly-2025-01-01`.
+    ///
+    /// If not set, the toolchain which is running the tests is used.
+    pub toolchain: Option<Cow<'static, str>>,
+    /// Extra `--cfg` flags to compile with, e.g. `my_cfg` or `my_cfg="value"`.
+    pub cfgs: Vec<String>,
+    /// Extra flags to pass to `rustc`, on top of the rustflags from the cargo config.
+    pub rustflags: Vec<String>,
+    /// Environment variables visible to the compiler, for `env!`, and to the tests.
+    pub env: BTreeMap<String, String>,
+    /// The target triple to compile for, e.g. `thumbv7em-none-eabihf`.
+    ///
+    /// If not set, the host target is used. When set, the code is only built and
+    /// [`test`](Config::test) is ignored. The target must be installed, unless it
+    /// is built with [`build_std`](Config::build_std).
+    pub target: Option<Cow<'static, str>>,
+    /// Compile the code as a `#![no_std]` library instead of a binary.
+    ///
+    /// The code does not need a `main` function or a panic handler, and is only
+    /// built, [`test`](Config::test) is ignored.
+    pub no_std: bool,
+    /// The standard library crates to build from source with `-Zbuild-std`, e.g.
+    /// `core` and `alloc`.
+    ///
+    /// Requires the `rust-src` component of the toolchain. `-Zbuild-std` is a nightly
+    /// option, so either compile with a nightly [`toolchain`](Config::toolchain) or
+    /// enable [`rustc_bootstrap`](Config::rustc_bootstrap).
+    pub build_std: Vec<String>,
+    /// Set `RUSTC_BOOTSTRAP=1` when building the code, which allows nightly options
+    /// like [`build_std`](Config::build_std) on a stable toolchain.
+    ///
+    /// `RUSTC_BOOTSTRAP` is meant for building the compiler itself. The options it
+    /// unlocks are unstable and can change with any release, so this is disabled
+    /// by default.
+    pub rustc_bootstrap: bool,
+}
+
+impl Config {
+    /// Compile with the given rustup toolchain instead of the one running the tests.
+    ///
+    /// Cargo is invoked with `rustup run <toolchain>`, so the toolchain must be installed.
+    pub fn toolchain(self, toolchain: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            toolchain: Some(toolchain.into()),
+            ..self
+        }
+    }
+
+    /// Add a `--cfg` flag, e.g. `my_cfg` or `my_cfg="value"`.
+    pub fn cfg(mut self, cfg: impl std::fmt::Display) -> Self {
+        self.cfgs.push(cfg.to_string());
+        self
+    }
+
+    /// Add a flag to pass to `rustc`.
+    pub fn rustflag(mut self, flag: impl std::fmt::Display) -> Self {
+        self.rustflags.push(flag.to_string());
+        self
+    }
+
+    /// Set an environment variable for the compiler and the tests.
+    pub fn env(mut self, key: impl std::fmt::Display, value: impl std::fmt::Display) -> Self {
+        self.env.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Compile for the given target triple instead of the host.
+    ///
+    /// See [`Config::target`] for details.
+    pub fn target(self, target: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            target: Some(target.into()),
+            ..self
+        }
+    }
+
+    /// Compile the code as a `#![no_std]` library.
+    ///
+    /// See [`Config::no_std`] for details.
+    pub fn no_std(self, no_std: bool) -> Self {
+        Self { no_std, ..self }
+    }
+
+    /// Build the given standard library crates from source, e.g. `["core", "alloc"]`.
+    ///
+    /// See [`Config::build_std`] for details.
+    pub fn build_std(mut self, crates: impl IntoIterator<Item = impl std::fmt::Display>) -> Self {
+        self.build_std.extend(crates.into_iter().map(|krate| krate.to_string()));
+        self
+    }
+
+    /// Allow nightly options on a stable toolchain by setting `RUSTC_BOOTSTRAP=1`.
+    ///
+    /// See [`Config::rustc_bootstrap`] for details.
+    pub fn rustc_bootstrap(self, rustc_bootstrap: bool) -> Self {
+        Self { rustc_bootstrap, ..self }
+    }
 }
 
 /// A dependency to apply to the code
@@ -757,6 +1000,23 @@ mod tests {
         assert_snapshot!(out);
     }
 
+    #[test]
+    fn compile_report() {
+        let out = compile!({
+            fn main() {}
+        });
+
+        assert_eq!(out.status, crate::ExitStatus::Success);
+        assert!(out.report.expand_time > std::time::Duration::ZERO);
+        assert!(out.report.build_time > std::time::Duration::ZERO);
+        assert!(out.report.artifact_size.is_some_and(|size| size > 0));
+
+        let out = compile!({ invalid_rust_code });
+
+        assert_eq!(out.report.build_time, std::time::Duration::ZERO);
+        assert_eq!(out.report.artifact_size, None);
+    }
+
     #[test]
     fn compile_failure() {
         let out = compile!({ invalid_rust_code });
@@ -797,4 +1057,77 @@ mod tests {
 
         assert_snapshot!(out)
     }
+
+    #[cfg(not(valgrind))]
+    #[test]
+    #[ignore = "requires the stable toolchain to be installed with rustup"]
+    fn compile_toolchain() {
+        let out = compile!(config!().toolchain("stable"), {
+            #[allow(unused)]
+            fn main() {
+                let a = 1;
+                let b = 2;
+                let c = a + b;
+            }
+        });
+
+        // The expanded prelude differs between toolchains, so only the code itself is checked.
+        assert_eq!(out.status, crate::ExitStatus::Success, "{}", out.expand_stderr);
+        assert!(out.expanded.contains("let c = a + b;"), "{}", out.expanded);
+    }
+
+    #[cfg(not(valgrind))]
+    #[test]
+    fn compile_cfg_env() {
+        let out = compile!(
+            config! { test: true }
+                .cfg("postcompile_cfg")
+                .cfg(r#"postcompile_value="a""#)
+                .rustflag("-Dunused")
+                .env("POSTCOMPILE_ENV", "hello"),
+            {
+                #[cfg(postcompile_cfg)]
+                const CFG: &str = "cfg";
+                #[cfg(postcompile_value = "a")]
+                const VALUE: &str = "a";
+                const ENV: &str = env!("POSTCOMPILE_ENV");
+
+                #[test]
+                fn test_env() {
+                    assert_eq!(CFG, "cfg");
+                    assert_eq!(VALUE, "a");
+                    assert_eq!(ENV, "hello");
+                    assert_eq!(std::env::var("POSTCOMPILE_ENV").unwrap(), "hello");
+                }
+            }
+        );
+
+        assert_snapshot!(out);
+    }
+
+    #[cfg(not(valgrind))]
+    #[test]
+    #[ignore = "requires the thumbv7em-none-eabi target to be installed with rustup"]
+    fn compile_no_std() {
+        let config = config! { dependencies: Vec::new() }
+            .no_std(true)
+            .target("thumbv7em-none-eabi")
+            .rustflag("-Dwarnings");
+
+        let out = compile!(config.clone(), {
+            pub fn add(a: u32, b: u32) -> u32 {
+                a + b
+            }
+        });
+
+        assert_snapshot!(out);
+
+        let out = compile!(config, {
+            pub fn new_string() -> std::string::String {
+                std::string::String::new()
+            }
+        });
+
+        assert_snapshot!(out);
+    }
 }
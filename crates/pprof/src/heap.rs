@@ -0,0 +1,28 @@
+/// A heap profiler.
+///
+/// Heap profiling needs support from the global allocator, so this crate does
+/// not implement it itself. Implement this trait for the allocator in use, for
+/// example with [`jemalloc_pprof`](https://docs.rs/jemalloc_pprof).
+///
+/// It is implemented for closures returning the profile.
+pub trait HeapProfiler: Send + Sync {
+    /// Dump a pprof profile of the currently allocated memory.
+    ///
+    /// The profile must be compressed using gzip.
+    ///
+    /// <div class="warning">
+    /// Warning: This method may block.
+    ///
+    /// It is recommended to run it in a separate thread.
+    /// </div>
+    fn dump(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl<F> HeapProfiler for F
+where
+    F: Fn() -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> + Send + Sync,
+{
+    fn dump(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        self()
+    }
+}
@@ -0,0 +1,298 @@
+//! HTTP handlers serving profiles on the same paths as Go's
+//! [`net/http/pprof`](https://pkg.go.dev/net/http/pprof).
+//!
+//! This allows `go tool pprof`, Parca agents and other tooling of the Go
+//! ecosystem to scrape profiles from Rust services unchanged:
+//!
+//! ```sh
+//! go tool pprof http://localhost:8080/debug/pprof/profile?seconds=10
+//! ```
+//!
+//! The [`PprofService`] can be used directly as a [`HttpService`] or its
+//! handlers can be mounted in an existing router with
+//! [`PprofService::profile`] and [`PprofService::heap`].
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http_body_util::Full;
+use scuffle_http::IncomingRequest;
+use scuffle_http::service::HttpService;
+
+use crate::{Cpu, HeapProfiler};
+
+/// The path of the CPU profile endpoint.
+pub const PROFILE_PATH: &str = "/debug/pprof/profile";
+
+/// The path of the heap profile endpoint.
+pub const HEAP_PATH: &str = "/debug/pprof/heap";
+
+/// The duration of a CPU profile if no `seconds` query parameter is given.
+const DEFAULT_PROFILE_DURATION: Duration = Duration::from_secs(30);
+
+/// A [`HttpService`] serving CPU and heap profiles.
+///
+/// ### `/debug/pprof/profile`
+///
+/// Captures a CPU profile for the number of seconds given by the `seconds`
+/// query parameter, 30 seconds by default.
+///
+/// ### `/debug/pprof/heap`
+///
+/// Dumps a heap profile. Only available if a [`HeapProfiler`] is set with
+/// [`PprofService::with_heap_profiler`], returns `501 Not Implemented`
+/// otherwise.
+///
+/// Both endpoints return gzipped pprof protos. Errors are returned with the
+/// `X-Go-Pprof` header, so the `pprof` tool shows the error message.
+#[derive(Clone)]
+pub struct PprofService {
+    frequency: i32,
+    blocklist: Arc<[String]>,
+    max_duration: Duration,
+    heap_profiler: Option<Arc<dyn HeapProfiler>>,
+}
+
+impl Debug for PprofService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PprofService")
+            .field("frequency", &self.frequency)
+            .field("blocklist", &self.blocklist)
+            .field("max_duration", &self.max_duration)
+            .field("heap_profiler", &self.heap_profiler.is_some())
+            .finish()
+    }
+}
+
+impl Default for PprofService {
+    fn default() -> Self {
+        Self {
+            frequency: 100,
+            blocklist: Arc::new([]),
+            max_duration: Duration::from_secs(300),
+            heap_profiler: None,
+        }
+    }
+}
+
+impl PprofService {
+    /// Create a new service sampling CPU profiles at 100 Hz, without a heap profiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the sampling frequency of CPU profiles in Hz.
+    pub fn with_frequency(mut self, frequency: i32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Set the functions to exclude from CPU profiles.
+    pub fn with_blocklist<S: AsRef<str>>(mut self, blocklist: &[S]) -> Self {
+        self.blocklist = blocklist.iter().map(|s| s.as_ref().to_owned()).collect();
+        self
+    }
+
+    /// Set the maximum duration of a CPU profile, 5 minutes by default.
+    ///
+    /// Longer profiles are rejected with `400 Bad Request`.
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+
+    /// Set the heap profiler, enabling the heap profile endpoint.
+    pub fn with_heap_profiler(mut self, heap_profiler: impl HeapProfiler + 'static) -> Self {
+        self.heap_profiler = Some(Arc::new(heap_profiler));
+        self
+    }
+
+    /// Handle a request to the CPU profile endpoint.
+    ///
+    /// Like Go, an invalid or missing `seconds` query parameter falls back to
+    /// 30 seconds.
+    pub fn profile(
+        &self,
+        uri: &http::Uri,
+    ) -> impl Future<Output = Result<http::Response<Full<Bytes>>, http::Error>> + Send + use<> {
+        let duration = query_param(uri, "seconds")
+            .and_then(|seconds| seconds.parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .map_or(DEFAULT_PROFILE_DURATION, Duration::from_secs);
+        let max_duration = self.max_duration;
+        let cpu = Cpu::new(self.frequency, &self.blocklist[..]);
+
+        async move {
+            if duration > max_duration {
+                return error(
+                    http::StatusCode::BAD_REQUEST,
+                    format!("profile duration exceeds the maximum of {}s", max_duration.as_secs()),
+                );
+            }
+
+            match tokio::task::spawn_blocking(move || cpu.capture(duration)).await {
+                Ok(Ok(profile)) => profile_response("profile", profile),
+                Ok(Err(err)) => error(
+                    http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("could not capture cpu profile: {err}"),
+                ),
+                Err(err) => error(
+                    http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("could not capture cpu profile: {err}"),
+                ),
+            }
+        }
+    }
+
+    /// Handle a request to the heap profile endpoint.
+    ///
+    /// Only the binary format is supported, a `debug` query parameter other
+    /// than `0` is rejected with `400 Bad Request`.
+    pub fn heap(
+        &self,
+        uri: &http::Uri,
+    ) -> impl Future<Output = Result<http::Response<Full<Bytes>>, http::Error>> + Send + use<> {
+        let debug = query_param(uri, "debug").is_some_and(|debug| debug != "0");
+        let heap_profiler = self.heap_profiler.clone();
+
+        async move {
+            if debug {
+                return error(http::StatusCode::BAD_REQUEST, "debug output is not supported".into());
+            }
+
+            let Some(heap_profiler) = heap_profiler else {
+                return error(http::StatusCode::NOT_IMPLEMENTED, "heap profiling is not enabled".into());
+            };
+
+            match tokio::task::spawn_blocking(move || heap_profiler.dump().map_err(|err| err.to_string())).await {
+                Ok(Ok(profile)) => profile_response("heap", profile),
+                Ok(Err(err)) => error(
+                    http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("could not dump heap profile: {err}"),
+                ),
+                Err(err) => error(
+                    http::StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("could not dump heap profile: {err}"),
+                ),
+            }
+        }
+    }
+}
+
+impl HttpService for PprofService {
+    type Error = http::Error;
+    type ResBody = Full<Bytes>;
+
+    fn call(
+        &mut self,
+        req: IncomingRequest,
+    ) -> impl Future<Output = Result<http::Response<Self::ResBody>, Self::Error>> + Send {
+        let uri = req.uri();
+        let profile = (uri.path() == PROFILE_PATH).then(|| self.profile(uri));
+        let heap = (uri.path() == HEAP_PATH).then(|| self.heap(uri));
+
+        async move {
+            match (profile, heap) {
+                (Some(profile), _) => profile.await,
+                (_, Some(heap)) => heap.await,
+                _ => error(http::StatusCode::NOT_FOUND, "unknown profile".into()),
+            }
+        }
+    }
+}
+
+fn query_param<'a>(uri: &'a http::Uri, name: &str) -> Option<&'a str> {
+    uri.query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
+fn profile_response(name: &str, profile: Vec<u8>) -> Result<http::Response<Full<Bytes>>, http::Error> {
+    http::Response::builder()
+        .status(http::StatusCode::OK)
+        .header(http::header::CONTENT_TYPE, "application/octet-stream")
+        .header(http::header::CONTENT_DISPOSITION, format!("attachment; filename=\"{name}\""))
+        .header(http::header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .body(Full::new(Bytes::from(profile)))
+}
+
+fn error(status: http::StatusCode, message: String) -> Result<http::Response<Full<Bytes>>, http::Error> {
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(http::header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header("X-Go-Pprof", "1")
+        .body(Full::new(Bytes::from(message)))
+}
+
+#[cfg(test)]
+#[cfg_attr(all(coverage_nightly, test), coverage(off))]
+mod tests {
+    use std::time::Duration;
+
+    use http_body_util::BodyExt;
+
+    use super::{HEAP_PATH, PROFILE_PATH, PprofService};
+
+    type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+    fn uri(uri: &str) -> http::Uri {
+        uri.parse().unwrap()
+    }
+
+    #[test]
+    fn query_param() {
+        let heap = uri("/debug/pprof/heap?gc=1&debug=0&empty=");
+        assert_eq!(super::query_param(&heap, "gc"), Some("1"));
+        assert_eq!(super::query_param(&heap, "debug"), Some("0"));
+        assert_eq!(super::query_param(&heap, "empty"), Some(""));
+        assert_eq!(super::query_param(&heap, "seconds"), None);
+        assert_eq!(super::query_param(&uri(PROFILE_PATH), "seconds"), None);
+    }
+
+    #[tokio::test]
+    async fn profile_max_duration() {
+        let service = PprofService::new().with_max_duration(Duration::from_secs(1));
+
+        let res = service.profile(&uri("/debug/pprof/profile?seconds=2")).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::BAD_REQUEST);
+
+        // Falls back to 30 seconds, which exceeds the maximum
+        let res = service.profile(&uri("/debug/pprof/profile?seconds=invalid")).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()["X-Go-Pprof"], "1");
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "profile duration exceeds the maximum of 1s");
+    }
+
+    #[tokio::test]
+    async fn heap() {
+        let res = PprofService::new().heap(&uri(HEAP_PATH)).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(res.headers()["X-Go-Pprof"], "1");
+
+        let service = PprofService::new().with_heap_profiler(|| Ok::<_, BoxError>(vec![1, 2, 3]));
+
+        let res = service.heap(&uri("/debug/pprof/heap?gc=1")).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::OK);
+        assert_eq!(
+            res.headers()[http::header::CONTENT_DISPOSITION],
+            "attachment; filename=\"heap\""
+        );
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, vec![1, 2, 3]);
+
+        let res = service.heap(&uri("/debug/pprof/heap?debug=1")).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::BAD_REQUEST);
+
+        let service = PprofService::new().with_heap_profiler(|| Err::<Vec<u8>, BoxError>("not enabled".into()));
+        let res = service.heap(&uri(HEAP_PATH)).await.unwrap();
+        assert_eq!(res.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "could not dump heap profile: not enabled");
+    }
+}
@@ -24,6 +24,12 @@
 //! # }
 //! ```
 //!
+//! ## HTTP handlers
+//!
+//! With the `http` feature flag, [`http::PprofService`] serves CPU and heap
+//! profiles on the same paths as Go's `net/http/pprof`, so existing tooling can
+//! scrape them.
+//!
 //! ## Analyzing the profile
 //!
 //! The resulting profile can be analyzed using the [`pprof`](https://github.com/google/pprof) tool.
@@ -47,8 +53,12 @@
 #![deny(unreachable_pub)]
 
 mod cpu;
+mod heap;
+#[cfg(feature = "http")]
+pub mod http;
 
 pub use cpu::Cpu;
+pub use heap::HeapProfiler;
 
 /// An error that can occur while profiling.
 #[derive(Debug, thiserror::Error)]
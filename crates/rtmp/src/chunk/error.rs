@@ -17,4 +17,8 @@ pub enum ChunkReadError {
     /// The length of a single chunk is larger than the max partial chunk size.
     /// The client is probably trying to DoS us.
     PartialChunkTooLarge(usize),
+    /// The length of an AMF message is larger than the max AMF payload size.
+    /// The client is probably trying to DoS us.
+    #[error("amf payload too large: {0}")]
+    AmfPayloadTooLarge(usize),
 }
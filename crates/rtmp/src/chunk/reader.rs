@@ -15,9 +15,13 @@ use crate::messages::MessageType;
 // These constants are used to limit the amount of memory we use for partial
 // chunks on normal operations we should never hit these limits
 // This is for when someone is trying to send us a malicious chunk streams
-const MAX_PARTIAL_CHUNK_SIZE: usize = 10 * 1024 * 1024; // 10MB (should be more than enough)
+/// The default maximum size of a single message.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024; // 10MB (should be more than enough)
+/// The default maximum size of an AMF message (commands, metadata and shared objects).
+pub const DEFAULT_MAX_AMF_PAYLOAD_SIZE: usize = 1024 * 1024; // 1MB
 const MAX_PREVIOUS_CHUNK_HEADERS: usize = 100; // 100 chunks
 const MAX_PARTIAL_CHUNK_COUNT: usize = 4; // 4 chunks
+const MAX_REASSEMBLY_BUFFERS: usize = MAX_PARTIAL_CHUNK_COUNT;
 
 /// A chunk reader.
 ///
@@ -34,9 +38,23 @@ pub struct ChunkReader {
     /// (chunk stream id, message stream id).
     partial_chunks: HashMap<(u32, u32), BytesMut>,
 
+    /// Buffers of completed partial chunks, reused to reassemble the next messages.
+    ///
+    /// The payload of a completed message is split off its buffer, so once the
+    /// payload is dropped the next message is reassembled into the same
+    /// allocation instead of a new one.
+    reassembly_buffers: Vec<BytesMut>,
+
     /// This is the max chunk size that the client has specified.
     /// By default this is 128 bytes.
     max_chunk_size: usize,
+
+    /// The maximum size of a message, this limits how much data is accumulated
+    /// for partial chunks.
+    max_message_size: usize,
+
+    /// The maximum size of an AMF message.
+    max_amf_payload_size: usize,
 }
 
 impl Default for ChunkReader {
@@ -44,7 +62,10 @@ impl Default for ChunkReader {
         Self {
             previous_chunk_headers: HashMap::with_capacity(MAX_PREVIOUS_CHUNK_HEADERS),
             partial_chunks: HashMap::with_capacity(MAX_PARTIAL_CHUNK_COUNT),
+            reassembly_buffers: Vec::with_capacity(MAX_REASSEMBLY_BUFFERS),
             max_chunk_size: INIT_CHUNK_SIZE,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_amf_payload_size: DEFAULT_MAX_AMF_PAYLOAD_SIZE,
         }
     }
 }
@@ -65,6 +86,47 @@ impl ChunkReader {
         }
     }
 
+    /// Set the maximum size of a single message.
+    ///
+    /// Messages that announce a larger length are rejected with
+    /// [`ChunkReadError::PartialChunkTooLarge`] before any of their data is buffered.
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
+    /// Set the maximum size of an AMF message (commands, metadata and shared objects).
+    ///
+    /// Messages that announce a larger length are rejected with
+    /// [`ChunkReadError::AmfPayloadTooLarge`] before any of their data is buffered.
+    pub fn set_max_amf_payload_size(&mut self, max_amf_payload_size: usize) {
+        self.max_amf_payload_size = max_amf_payload_size;
+    }
+
+    /// Checks the length of a message against the configured limits.
+    fn check_message_length(&self, msg_type_id: MessageType, msg_length: u32) -> Result<(), ChunkReadError> {
+        let msg_length = msg_length as usize;
+
+        if msg_length > self.max_message_size {
+            return Err(ChunkReadError::PartialChunkTooLarge(msg_length));
+        }
+
+        let is_amf = matches!(
+            msg_type_id,
+            MessageType::CommandAMF0
+                | MessageType::DataAMF0
+                | MessageType::SharedObjAMF0
+                | MessageType::CommandAMF3
+                | MessageType::DataAMF3
+                | MessageType::SharedObjAMF3
+        );
+
+        if is_amf && msg_length > self.max_amf_payload_size {
+            return Err(ChunkReadError::AmfPayloadTooLarge(msg_length));
+        }
+
+        Ok(())
+    }
+
     /// This function is used to read a chunk from the buffer.
     ///
     /// Returns:
@@ -139,14 +201,12 @@ impl ChunkReader {
                 return Ok(None);
             }
 
-            let data = buffer.split_to(position);
-
-            // We freeze the chunk data and slice it to get the payload.
-            // Data before the slice is the header data, and data after the slice is the
-            // next chunk We don't need to keep the header data, because we already decoded
+            // Data before the payload range is the header data, and data after the split is
+            // the next chunk. We don't need to keep the header data, because we already decoded
             // it into struct form. The payload_range_end should be the same as the cursor's
             // position.
-            let payload = data.freeze().slice(payload_range_start..payload_range_end);
+            let data = buffer.split_to(position);
+            let payload_range = payload_range_start..payload_range_end;
 
             // We need to check here if the chunk header is already stored in our map.
             // This isnt a spec check but it is a check to make sure that we dont have too
@@ -178,13 +238,19 @@ impl ChunkReader {
 
             // Check if the payload is the same as the message length.
             // If this is true we have a full chunk and we can return it.
-            if payload.len() == message_header.msg_length as usize {
+            if payload_range.len() == message_header.msg_length as usize {
+                // We freeze the chunk data and slice it to get the payload, so the payload
+                // refers to the read buffer without being copied.
                 return Ok(Some(Chunk {
                     basic_header: header,
                     message_header,
-                    payload,
+                    payload: data.freeze().slice(payload_range),
                 }));
             } else {
+                // Messages split over multiple chunks are interleaved with chunk headers,
+                // so their payload has to be copied into a contiguous buffer.
+                let payload = &data[payload_range];
+
                 // Otherwise we generate a key using the chunk stream id and the message stream
                 // id. We then get the partial chunk from the map using the key.
                 let key = (header.chunk_stream_id, message_header.msg_stream_id);
@@ -199,8 +265,10 @@ impl ChunkReader {
                             return Err(crate::error::RtmpError::ChunkRead(ChunkReadError::TooManyPartialChunks));
                         }
 
-                        // Insert a new empty BytesMut into the map.
-                        self.partial_chunks.insert(key, BytesMut::new());
+                        // Insert a pooled buffer into the map. It only grows as the chunks arrive,
+                        // so a peer announcing a large message cannot make us allocate it up front.
+                        let buffer = self.reassembly_buffers.pop().unwrap_or_default();
+                        self.partial_chunks.insert(key, buffer);
                         // Get the partial chunk we just inserted.
                         self.partial_chunks.get_mut(&key).expect("we just inserted it")
                     }
@@ -211,14 +279,14 @@ impl ChunkReader {
                 let length = {
                     // If the length of a single chunk is larger than the max partial chunk size
                     // we return an error. The client is probably trying to DoS us.
-                    if partial_chunk.len() + payload.len() > MAX_PARTIAL_CHUNK_SIZE {
+                    if partial_chunk.len() + payload.len() > self.max_message_size {
                         return Err(crate::error::RtmpError::ChunkRead(ChunkReadError::PartialChunkTooLarge(
                             partial_chunk.len() + payload.len(),
                         )));
                     }
 
                     // Extend the partial chunk with the payload.
-                    partial_chunk.extend_from_slice(&payload[..]);
+                    partial_chunk.extend_from_slice(payload);
 
                     // Return the new length of the partial chunk.
                     partial_chunk.len()
@@ -226,10 +294,19 @@ impl ChunkReader {
 
                 // If we have a full chunk we return it.
                 if length == message_header.msg_length as usize {
+                    let mut buffer = self.partial_chunks.remove(&key).unwrap();
+                    let payload = buffer.split().freeze();
+
+                    // The buffer is empty now but still refers to the allocation of the payload,
+                    // which it reclaims when reserving space for the next message.
+                    if self.reassembly_buffers.len() < MAX_REASSEMBLY_BUFFERS {
+                        self.reassembly_buffers.push(buffer);
+                    }
+
                     return Ok(Some(Chunk {
                         basic_header: header,
                         message_header,
-                        payload: self.partial_chunks.remove(&key).unwrap().freeze(),
+                        payload,
                     }));
                 }
 
@@ -309,11 +386,6 @@ impl ChunkReader {
                     .read_u24::<BigEndian>()
                     .eof_to_none()
                     .map_err(|e| e.map(crate::error::RtmpError::Io))?;
-                if msg_length as usize > MAX_PARTIAL_CHUNK_SIZE {
-                    return Err(Some(crate::error::RtmpError::ChunkRead(
-                        ChunkReadError::PartialChunkTooLarge(msg_length as usize),
-                    )));
-                }
 
                 // We then have a 1 byte message type id.
                 let msg_type_id = cursor
@@ -322,6 +394,10 @@ impl ChunkReader {
                     .map_err(|e| e.map(crate::error::RtmpError::Io))?;
                 let msg_type_id = MessageType::from(msg_type_id);
 
+                // Reject messages that are too large before we buffer any of their data.
+                self.check_message_length(msg_type_id, msg_length)
+                    .map_err(|e| Some(crate::error::RtmpError::ChunkRead(e)))?;
+
                 // We then read the message stream id. (According to spec this is stored in
                 // LittleEndian, no idea why.)
                 let msg_stream_id = cursor
@@ -371,11 +447,6 @@ impl ChunkReader {
                     .read_u24::<BigEndian>()
                     .eof_to_none()
                     .map_err(|e| e.map(crate::error::RtmpError::Io))?;
-                if msg_length as usize > MAX_PARTIAL_CHUNK_SIZE {
-                    return Err(Some(crate::error::RtmpError::ChunkRead(
-                        ChunkReadError::PartialChunkTooLarge(msg_length as usize),
-                    )));
-                }
 
                 // We then have a 1 byte message type id.
                 let msg_type_id = cursor
@@ -384,6 +455,10 @@ impl ChunkReader {
                     .map_err(|e| e.map(crate::error::RtmpError::Io))?;
                 let msg_type_id = MessageType::from(msg_type_id);
 
+                // Reject messages that are too large before we buffer any of their data.
+                self.check_message_length(msg_type_id, msg_length)
+                    .map_err(|e| Some(crate::error::RtmpError::ChunkRead(e)))?;
+
                 // Again as mentioned above we sometimes have a delta timestamp larger than 3
                 // bytes.
                 let (timestamp_delta, was_extended_timestamp) = if timestamp_delta == 0xFFFFFF {
@@ -464,8 +539,16 @@ impl ChunkReader {
                         ))?;
 
                 // We calculate the timestamp by adding the delta timestamp to the previous
-                // timestamp.
-                let timestamp = previous_header.timestamp + timestamp_delta;
+                // timestamp. We need to make sure this does not overflow.
+                let timestamp = previous_header.timestamp.checked_add(timestamp_delta).unwrap_or_else(|| {
+                    tracing::warn!(
+                        "Timestamp overflow detected. Previous timestamp: {}, delta timestamp: {}, using previous timestamp.",
+                        previous_header.timestamp,
+                        timestamp_delta
+                    );
+
+                    previous_header.timestamp
+                });
 
                 Ok(ChunkMessageHeader {
                     timestamp,
@@ -1043,6 +1126,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reader_timestamp_delta_overflow() {
+        let mut buf = BytesMut::new();
+
+        #[rustfmt::skip]
+        buf.extend_from_slice(&[
+            3, // chunk type 0, chunk stream id 3
+            0xFF, 0xFF, 0xFF, // timestamp
+            0x00, 0x00, 0x01, // message length (1)
+            0x09, // message type id (video)
+            0x00, 0x00, 0x00, 0x00, // message stream id
+            0xFF, 0xFF, 0xFF, 0xFF, // extended timestamp
+            0x00, // payload
+            (2 << 6) | 3, // chunk type 2, chunk stream id 3
+            0x00, 0x00, 0x01, // timestamp delta
+            0x00, // payload
+        ]);
+
+        let mut unpacker = ChunkReader::default();
+
+        let chunk = unpacker.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_eq!(chunk.message_header.timestamp, u32::MAX);
+
+        // The timestamp would overflow, the previous timestamp is used instead.
+        let chunk = unpacker.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_eq!(chunk.message_header.timestamp, u32::MAX);
+    }
+
+    #[test]
+    fn test_reader_configurable_limits() {
+        #[rustfmt::skip]
+        let amf_chunk = [
+            3, // chunk type 0, chunk stream id 3
+            0x00, 0x00, 0x00, // timestamp
+            0x00, 0x01, 0x00, // message length (256)
+            0x14, // message type id (amf0 command)
+            0x00, 0x00, 0x00, 0x00, // message stream id
+        ];
+
+        let mut unpacker = ChunkReader::default();
+        assert!(unpacker.read_chunk(&mut BytesMut::from(&amf_chunk[..])).unwrap().is_none());
+
+        let mut unpacker = ChunkReader::default();
+        unpacker.set_max_amf_payload_size(255);
+        let err = unpacker.read_chunk(&mut BytesMut::from(&amf_chunk[..])).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::RtmpError::ChunkRead(ChunkReadError::AmfPayloadTooLarge(256))
+        ));
+
+        let mut unpacker = ChunkReader::default();
+        unpacker.set_max_message_size(255);
+        let err = unpacker.read_chunk(&mut BytesMut::from(&amf_chunk[..])).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::RtmpError::ChunkRead(ChunkReadError::PartialChunkTooLarge(256))
+        ));
+    }
+
     #[test]
     fn test_reader_larger_chunk_size() {
         let mut buf = BytesMut::new();
@@ -1076,4 +1218,81 @@ mod tests {
             assert_eq!(chunk.payload[i], i as u8);
         }
     }
+
+    #[test]
+    fn test_reader_zero_copy_and_reassembly_buffers() {
+        let mut unpacker = ChunkReader::default();
+        let mut buf = BytesMut::new();
+
+        // A message that fits into a single chunk refers to the read buffer.
+        #[rustfmt::skip]
+        buf.extend_from_slice(&[
+            3, // chunk type 0, chunk stream id 3
+            0x00, 0x00, 0x00, // timestamp
+            0x00, 0x00, 0x04, // message length (4)
+            0x09, // message type id (video)
+            0x01, 0x00, 0x00, 0x00, // message stream id
+            0x01, 0x02, 0x03, 0x04,
+        ]);
+        let payload_ptr = buf[12..].as_ptr();
+        let chunk = unpacker.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_eq!(chunk.payload.as_ptr(), payload_ptr);
+        assert_eq!(chunk.payload.as_ref(), &[0x01, 0x02, 0x03, 0x04]);
+
+        // A message that is split over two chunks is reassembled into a pooled buffer.
+        let message = |buf: &mut BytesMut| {
+            #[rustfmt::skip]
+            buf.extend_from_slice(&[
+                3, // chunk type 0, chunk stream id 3
+                0x00, 0x00, 0x00, // timestamp
+                0x00, 0x01, 0x00, // message length (256) (max chunk size is set to 128)
+                0x09, // message type id (video)
+                0x01, 0x00, 0x00, 0x00, // message stream id
+            ]);
+            buf.extend((0..128).map(|i| i as u8));
+            buf.put_u8(0b11_000011); // chunk type 3, chunk stream id 3
+            buf.extend((128..256).map(|i| i as u8));
+        };
+
+        message(&mut buf);
+        let chunk = unpacker.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_eq!(chunk.payload.len(), 256);
+        assert!(chunk.payload.iter().enumerate().all(|(i, b)| *b == i as u8));
+        let payload_ptr = chunk.payload.as_ptr();
+        drop(chunk);
+
+        // Once the payload is dropped, the next message reuses its allocation.
+        message(&mut buf);
+        let chunk = unpacker.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_eq!(chunk.payload.as_ptr(), payload_ptr);
+        assert!(chunk.payload.iter().enumerate().all(|(i, b)| *b == i as u8));
+
+        // While the payload is still alive, a new allocation is used.
+        message(&mut buf);
+        let next = unpacker.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_ne!(next.payload.as_ptr(), payload_ptr);
+        assert_eq!(next.payload, chunk.payload);
+    }
+
+    #[test]
+    fn test_reader_reassembly_buffer_grows_with_chunks() {
+        let mut unpacker = ChunkReader::default();
+        let mut buf = BytesMut::new();
+
+        // The first chunk of a message announcing 8MB.
+        #[rustfmt::skip]
+        buf.extend_from_slice(&[
+            3, // chunk type 0, chunk stream id 3
+            0x00, 0x00, 0x00, // timestamp
+            0x80, 0x00, 0x00, // message length (8MB)
+            0x09, // message type id (video)
+            0x01, 0x00, 0x00, 0x00, // message stream id
+        ]);
+        buf.extend((0..128).map(|i| i as u8));
+
+        assert!(unpacker.read_chunk(&mut buf).expect("read chunk").is_none());
+        let partial_chunk = &unpacker.partial_chunks[&(3, 1)];
+        assert_eq!(partial_chunk.len(), 128);
+        assert!(partial_chunk.capacity() < 0x80_0000);
+    }
 }
@@ -2,21 +2,74 @@
 
 use std::convert::Infallible;
 use std::str::FromStr;
+use std::sync::LazyLock;
 
 use bytes::Bytes;
 use scuffle_amf0::decoder::Amf0Decoder;
-use scuffle_bytes_util::StringCow;
 use scuffle_bytes_util::zero_copy::BytesBuf;
+use scuffle_bytes_util::{StringCow, StringInterner};
 
 use super::error::CommandError;
 use super::netconnection::NetConnectionCommand;
 use super::netstream::NetStreamCommand;
 use super::{Command, CommandResultLevel, CommandType, UnknownCommand};
 
+/// Command names and `connect` command object keys which are sent by most clients.
+///
+/// They are resolved to static strings while decoding instead of being allocated for every command.
+static INTERNER: LazyLock<StringInterner> = LazyLock::new(|| {
+    [
+        // NetConnection commands
+        "connect",
+        "call",
+        "close",
+        "createStream",
+        // NetStream commands
+        "play",
+        "play2",
+        "deleteStream",
+        "closeStream",
+        "receiveAudio",
+        "receiveVideo",
+        "publish",
+        "seek",
+        "pause",
+        // Commands sent by most encoders which are not part of the spec
+        "releaseStream",
+        "FCPublish",
+        "FCUnpublish",
+        "getStreamLength",
+        "_checkbw",
+        // Publishing types
+        "live",
+        "record",
+        "append",
+        // connect command object keys
+        "app",
+        "flashVer",
+        "swfUrl",
+        "tcUrl",
+        "fpad",
+        "capabilities",
+        "audioCodecs",
+        "videoCodecs",
+        "videoFunction",
+        "pageUrl",
+        "objectEncoding",
+        "type",
+        "capsEx",
+        "fourCcList",
+        "videoFourCcInfoMap",
+        "audioFourCcInfoMap",
+    ]
+    .into_iter()
+    .collect()
+});
+
 impl Command<'_> {
     /// Reads a [`Command`] from the given payload.
     pub fn read(payload: Bytes) -> Result<Self, CommandError> {
-        let mut decoder = Amf0Decoder::from_buf(payload);
+        let mut decoder = Amf0Decoder::from_buf(payload).with_interner(&INTERNER);
 
         let command_name = decoder.decode_string()?;
         let transaction_id = decoder.decode_number()?;
@@ -61,7 +114,53 @@ impl FromStr for CommandResultLevel {
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
+    use bytes::Bytes;
+    use scuffle_amf0::encoder::Amf0Encoder;
+    use scuffle_amf0::{Amf0Object, Amf0Value};
+    use scuffle_bytes_util::StringCow;
+
     use super::CommandResultLevel;
+    use crate::command_messages::netconnection::NetConnectionCommand;
+    use crate::command_messages::{Command, CommandType};
+
+    #[test]
+    fn test_command_interned_strings() {
+        let mut buf = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut buf);
+        encoder.encode_string("connect").unwrap();
+        encoder.encode_number(1.0).unwrap();
+        let object: Amf0Object = [
+            ("app".into(), Amf0Value::String("live".into())),
+            ("tcUrl".into(), Amf0Value::String("rtmp://localhost/live".into())),
+        ]
+        .into_iter()
+        .collect();
+        encoder.encode_object(&object).unwrap();
+
+        let command = Command::read(Bytes::from_owner(buf)).unwrap();
+        let CommandType::NetConnection(NetConnectionCommand::Connect(connect)) = command.command_type else {
+            panic!("expected connect command");
+        };
+
+        assert_eq!(connect.app, "live");
+        let (key, value) = connect.others.iter().next().unwrap();
+        // Deserializing borrows the interned static string instead of allocating a new one.
+        assert!(matches!(key, StringCow::Ref("tcUrl")));
+        assert_eq!(value, &Amf0Value::String("rtmp://localhost/live".into()));
+
+        let mut buf = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut buf);
+        encoder.encode_string("FCPublish").unwrap();
+        encoder.encode_number(2.0).unwrap();
+        encoder.encode_null().unwrap();
+
+        let command = Command::read(Bytes::from_owner(buf)).unwrap();
+        let CommandType::Unknown(unknown) = command.command_type else {
+            panic!("expected unknown command");
+        };
+
+        assert!(matches!(unknown.command_name, StringCow::StaticRef("FCPublish")));
+    }
 
     #[test]
     fn test_command_result_level() {
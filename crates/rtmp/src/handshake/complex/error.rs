@@ -9,4 +9,7 @@ pub enum ComplexHandshakeError {
     /// Cannot generate digest.
     #[error("cannot generate digest")]
     CannotGenerate,
+    /// The digest of S2 does not match the digest of C1.
+    #[error("s2 digest does not match")]
+    S2DigestMismatch,
 }
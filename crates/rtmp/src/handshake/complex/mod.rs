@@ -9,10 +9,13 @@ use std::io::{self, Seek, Write};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use bytes::{BufMut, Bytes, BytesMut};
 use digest::DigestProcessor;
+use error::ComplexHandshakeError;
 use rand::Rng;
 use scuffle_bytes_util::BytesCursorExt;
 
-use super::{RTMP_HANDSHAKE_SIZE, RtmpVersion, ServerHandshakeState, TIME_VERSION_LENGTH, current_time};
+use super::{
+    ClientHandshakeState, RTMP_HANDSHAKE_SIZE, RtmpVersion, ServerHandshakeState, TIME_VERSION_LENGTH, current_time,
+};
 
 pub mod digest;
 pub mod error;
@@ -21,6 +24,12 @@ pub mod error;
 /// reference implementation uses this value.
 pub const RTMP_SERVER_VERSION: u32 = 0x04050001;
 
+/// The version sent by the client in C1.
+///
+/// Any non-zero version signals the complex handshake, this is the Flash Player
+/// version (9.0.124.2) sent by the reference implementation.
+pub const RTMP_CLIENT_VERSION: u32 = 0x09007c02;
+
 /// This is the length of the digest.
 /// There is a lot of random data before and after the digest, however, the
 /// digest is always 32 bytes.
@@ -41,6 +50,25 @@ pub const RTMP_SERVER_KEY: &[u8] = &[
     0x93, 0xb8, 0xe6, 0x36, 0xcf, 0xeb, 0x31, 0xae,
 ];
 
+/// This is the full client key.
+/// Used to generate the digest of C2.
+pub const RTMP_CLIENT_KEY: &[u8] = &[
+    0x47, 0x65, 0x6e, 0x75, 0x69, 0x6e, 0x65, 0x20, 0x41, 0x64, 0x6f, 0x62, 0x65, 0x20, 0x46, 0x6c, 0x61, 0x73, 0x68, 0x20,
+    0x50, 0x6c, 0x61, 0x79, 0x65, 0x72, 0x20, 0x30, 0x30, 0x31, 0xf0, 0xee, 0xc2, 0x4a, 0x80, 0x68, 0xbe, 0xe8, 0x2e, 0x00,
+    0xd0, 0xd1, 0x02, 0x9e, 0x7e, 0x57, 0x6e, 0xec, 0x5d, 0x2d, 0x29, 0x80, 0x6f, 0xab, 0x93, 0xb8, 0xe6, 0x36, 0xcf, 0xeb,
+    0x31, 0xae,
+];
+
+/// Generates the digest of C2 or S2.
+///
+/// The digest is keyed with a digest of the peer's C1/S1 digest, which is itself
+/// keyed with [`RTMP_CLIENT_KEY`] (for C2) or [`RTMP_SERVER_KEY`] (for S2).
+/// `data` is the first 1504 bytes of C2/S2.
+pub fn make_c2s2_digest(key: &[u8], peer_digest: &[u8], data: &[u8]) -> Result<[u8; 32], ComplexHandshakeError> {
+    let key = DigestProcessor::new(Bytes::new(), key).make_digest(peer_digest, &[])?;
+    DigestProcessor::new(Bytes::new(), &key).make_digest(data, &[])
+}
+
 /// The schema version.
 ///
 /// For the complex handshake the schema is either 0 or 1.
@@ -202,20 +230,15 @@ impl ComplexHandshakeServer {
             output.write_u8(rng.random())?;
         }
 
-        // The digest is loaded with the data that we just generated.
-        // This digest is used to generate the key. (digest of c1)
-        let key_digest = DigestProcessor::new(Bytes::new(), RTMP_SERVER_KEY);
-
         // Create a digest of the random data using a key generated from the digest of
         // C1.
-        let key = key_digest.make_digest(&self.c1_digest, &[])?;
-        let data_digest = DigestProcessor::new(Bytes::new(), &key);
-
-        // We then generate a digest using the key and the random data
-        // We then extract the first 1504 bytes of the data.
         // RTMP_HANDSHAKE_SIZE - 32 = 1504
         // 32 is the size of the digest. for C2S2
-        let digest = data_digest.make_digest(&output[start..start + RTMP_HANDSHAKE_SIZE - RTMP_DIGEST_LENGTH], &[])?;
+        let digest = make_c2s2_digest(
+            RTMP_SERVER_KEY,
+            &self.c1_digest,
+            &output[start..start + RTMP_HANDSHAKE_SIZE - RTMP_DIGEST_LENGTH],
+        )?;
 
         // Write the random data  to the main writer.
         // Total Write = 1536 bytes (1504 + 32)
@@ -224,3 +247,155 @@ impl ComplexHandshakeServer {
         Ok(())
     }
 }
+
+/// Complex Handshake Client.
+///
+/// Sends a complex C1 and falls back to the simple handshake if S1 does not
+/// contain a valid digest, which means the server only supports the simple
+/// handshake.
+pub struct ComplexHandshakeClient {
+    state: ClientHandshakeState,
+    c1_digest: [u8; RTMP_DIGEST_LENGTH],
+    complex: bool,
+}
+
+impl Default for ComplexHandshakeClient {
+    fn default() -> Self {
+        Self {
+            state: ClientHandshakeState::WriteC0C1,
+            c1_digest: [0; RTMP_DIGEST_LENGTH],
+            complex: false,
+        }
+    }
+}
+
+impl ComplexHandshakeClient {
+    /// Returns true if the handshake is finished.
+    pub fn is_finished(&self) -> bool {
+        self.state == ClientHandshakeState::Finish
+    }
+
+    /// Returns true if the server accepted the complex handshake.
+    ///
+    /// This is only known once S1 has been read.
+    pub fn is_complex(&self) -> bool {
+        self.complex
+    }
+
+    /// Perform the complex handshake.
+    pub fn handshake(&mut self, input: &mut io::Cursor<Bytes>, output: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        match self.state {
+            ClientHandshakeState::WriteC0C1 => {
+                self.write_c0(output)?;
+                self.write_c1(output)?;
+                self.state = ClientHandshakeState::ReadS0S1S2;
+            }
+            ClientHandshakeState::ReadS0S1S2 => {
+                // The version selected by the server, we only support version 3 anyways.
+                input.read_u8()?;
+
+                let s1_bytes = input.extract_bytes(RTMP_HANDSHAKE_SIZE)?;
+                let s2_bytes = input.extract_bytes(RTMP_HANDSHAKE_SIZE)?;
+
+                let data_digest = DigestProcessor::new(s1_bytes.clone(), RTMP_SERVER_KEY_FIRST_HALF);
+                match data_digest.read_digest() {
+                    Ok((s1_digest, _)) => {
+                        self.complex = true;
+                        self.read_s2(&s2_bytes)?;
+                        self.write_c2(&s1_bytes, &s1_digest, output)?;
+                    }
+                    Err(_) => {
+                        // The server does not support the complex handshake.
+                        self.write_simple_c2(&s1_bytes, output)?;
+                    }
+                }
+
+                self.state = ClientHandshakeState::Finish;
+            }
+            ClientHandshakeState::Finish => {}
+        }
+
+        Ok(())
+    }
+
+    fn write_c0(&self, output: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        // The version of the protocol used in the handshake.
+        output.write_u8(RtmpVersion::Version3.0)?; // 8 bits version
+
+        Ok(())
+    }
+
+    fn write_c1(&mut self, output: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        let mut writer = BytesMut::new().writer();
+
+        // The first 4 bytes of C1 are the timestamp.
+        writer.write_u32::<BigEndian>(current_time())?;
+
+        // The next 4 bytes are a version number, which must not be zero for the complex handshake.
+        writer.write_u32::<BigEndian>(RTMP_CLIENT_VERSION)?;
+
+        // We then write 1528 bytes of random data.
+        // 764 bytes for the digest, 764 bytes for the key.
+        let mut rng = rand::rng();
+        for _ in 0..RTMP_HANDSHAKE_SIZE - TIME_VERSION_LENGTH {
+            writer.write_u8(rng.random())?;
+        }
+
+        let data_digest = DigestProcessor::new(writer.into_inner().freeze(), RTMP_CLIENT_KEY_FIRST_HALF);
+
+        // We use schema 1, the same as the reference implementation.
+        let result = data_digest.generate_and_fill_digest(SchemaVersion::Schema1)?;
+        result.write_to(output)?;
+
+        // The server uses the digest of C1 to generate the digest of S2.
+        self.c1_digest = result.digest;
+
+        Ok(())
+    }
+
+    fn read_s2(&self, s2_bytes: &[u8]) -> Result<(), crate::error::RtmpError> {
+        let (data, digest) = s2_bytes.split_at(RTMP_HANDSHAKE_SIZE - RTMP_DIGEST_LENGTH);
+
+        if make_c2s2_digest(RTMP_SERVER_KEY, &self.c1_digest, data)? != digest {
+            return Err(ComplexHandshakeError::S2DigestMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    fn write_c2(&self, s1_bytes: &[u8], s1_digest: &[u8], output: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        let start = output.len();
+
+        // We write the current time to the first 4 bytes.
+        output.write_u32::<BigEndian>(current_time())?;
+
+        // We write the timestamp from S1 to the next 4 bytes.
+        output.write_all(&s1_bytes[0..4])?;
+
+        // We then write 1496 bytes of random data followed by the 32 byte digest.
+        let mut rng = rand::rng();
+        for _ in 0..RTMP_HANDSHAKE_SIZE - RTMP_DIGEST_LENGTH - TIME_VERSION_LENGTH {
+            output.write_u8(rng.random())?;
+        }
+
+        // Create a digest of the random data using a key generated from the digest of
+        // S1.
+        let digest = make_c2s2_digest(
+            RTMP_CLIENT_KEY,
+            s1_digest,
+            &output[start..start + RTMP_HANDSHAKE_SIZE - RTMP_DIGEST_LENGTH],
+        )?;
+        output.write_all(&digest)?;
+
+        Ok(())
+    }
+
+    fn write_simple_c2(&self, s1_bytes: &[u8], output: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        // Same as the simple handshake, C2 echoes the timestamp and random data of S1.
+        output.write_all(&s1_bytes[0..4])?;
+        output.write_u32::<BigEndian>(current_time())?;
+        output.write_all(&s1_bytes[TIME_VERSION_LENGTH..])?;
+
+        Ok(())
+    }
+}
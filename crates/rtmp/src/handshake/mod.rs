@@ -1,5 +1,8 @@
 //! RTMP handshake logic.
 //!
+//! Both the server ([`HandshakeServer`]) and the client ([`HandshakeClient`]) side
+//! support the simple and the complex (digest) handshake.
+//!
 //! Order of messages:
 //! ```txt
 //! Client -> C0 -> Server
@@ -14,8 +17,8 @@ use std::io::{self, Seek};
 use std::time::SystemTime;
 
 use bytes::Bytes;
-use complex::ComplexHandshakeServer;
-use simple::SimpleHandshakeServer;
+use complex::{ComplexHandshakeClient, ComplexHandshakeServer};
+use simple::{SimpleHandshakeClient, SimpleHandshakeServer};
 
 pub mod complex;
 pub mod simple;
@@ -54,6 +57,19 @@ pub enum ServerHandshakeState {
     Finish,
 }
 
+/// The state of the client side of the handshake.
+///
+/// This is used to determine what the next step is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClientHandshakeState {
+    /// Next step is to write C0 and C1.
+    WriteC0C1,
+    /// Next step is to read S0, S1 and S2 and write C2.
+    ReadS0S1S2,
+    /// Handshake is finished.
+    Finish,
+}
+
 /// The server side of the handshake.
 pub enum HandshakeServer {
     /// Simple handshake.
@@ -108,6 +124,42 @@ impl HandshakeServer {
     }
 }
 
+/// The client side of the handshake.
+pub enum HandshakeClient {
+    /// Simple handshake.
+    Simple(SimpleHandshakeClient),
+    /// Complex handshake.
+    ///
+    /// Falls back to the simple handshake if the server does not support the complex handshake.
+    Complex(ComplexHandshakeClient),
+}
+
+impl Default for HandshakeClient {
+    fn default() -> Self {
+        Self::Complex(ComplexHandshakeClient::default())
+    }
+}
+
+impl HandshakeClient {
+    /// Returns true if the handshake is finished.
+    pub fn is_finished(&self) -> bool {
+        match self {
+            HandshakeClient::Simple(handshaker) => handshaker.is_finished(),
+            HandshakeClient::Complex(handshaker) => handshaker.is_finished(),
+        }
+    }
+
+    /// Perform the handshake.
+    ///
+    /// The first call writes C0 and C1 and does not read any input.
+    pub fn handshake(&mut self, input: &mut io::Cursor<Bytes>, writer: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        match self {
+            HandshakeClient::Simple(handshaker) => handshaker.handshake(input, writer),
+            HandshakeClient::Complex(handshaker) => handshaker.handshake(input, writer),
+        }
+    }
+}
+
 /// Returns the current unix epoch time in nanoseconds.
 pub fn current_time() -> u32 {
     let duration = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
@@ -125,11 +177,15 @@ mod tests {
     use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
     use bytes::Bytes;
 
-    use crate::handshake::HandshakeServer;
+    use crate::error::RtmpError;
     use crate::handshake::complex::digest::DigestProcessor;
+    use crate::handshake::complex::error::ComplexHandshakeError;
     use crate::handshake::complex::{
-        RTMP_CLIENT_KEY_FIRST_HALF, RTMP_SERVER_KEY, RTMP_SERVER_KEY_FIRST_HALF, RTMP_SERVER_VERSION, SchemaVersion,
+        RTMP_CLIENT_KEY, RTMP_CLIENT_KEY_FIRST_HALF, RTMP_SERVER_KEY, RTMP_SERVER_KEY_FIRST_HALF, RTMP_SERVER_VERSION,
+        SchemaVersion, make_c2s2_digest,
     };
+    use crate::handshake::simple::{SimpleHandshakeClient, SimpleHandshakeServer};
+    use crate::handshake::{HandshakeClient, HandshakeServer};
 
     #[test]
     fn test_simple_handshake() {
@@ -253,4 +309,105 @@ mod tests {
 
         assert!(handshake_server.is_finished());
     }
+
+    /// Runs the handshake between the client and the server.
+    ///
+    /// Returns the S0, S1 and S2 bytes sent by the server and the C2 bytes sent by the client.
+    fn run_handshake(client: &mut HandshakeClient, server: &mut HandshakeServer) -> (Vec<u8>, Vec<u8>) {
+        let mut c0c1 = Vec::new();
+        client.handshake(&mut std::io::Cursor::new(Bytes::new()), &mut c0c1).unwrap();
+        assert_eq!(c0c1.len(), 1537);
+
+        let mut s0s1s2 = Vec::new();
+        server
+            .handshake(&mut std::io::Cursor::new(Bytes::from(c0c1)), &mut s0s1s2)
+            .unwrap();
+        assert_eq!(s0s1s2.len(), 3073);
+
+        let mut c2 = Vec::new();
+        client
+            .handshake(&mut std::io::Cursor::new(Bytes::from(s0s1s2.clone())), &mut c2)
+            .unwrap();
+        assert_eq!(c2.len(), 1536);
+        assert!(client.is_finished());
+
+        server
+            .handshake(&mut std::io::Cursor::new(Bytes::from(c2.clone())), &mut Vec::new())
+            .unwrap();
+        assert!(server.is_finished());
+
+        (s0s1s2, c2)
+    }
+
+    #[test]
+    fn test_client_complex_handshake() {
+        let mut client = HandshakeClient::default();
+        let mut server = HandshakeServer::default();
+
+        let (s0s1s2, c2) = run_handshake(&mut client, &mut server);
+
+        assert!(matches!(server, HandshakeServer::Complex(_)));
+        let HandshakeClient::Complex(client) = client else {
+            panic!("expected complex client");
+        };
+        assert!(client.is_complex());
+
+        // C2 is signed with a key generated from the digest of S1.
+        let data_digest = DigestProcessor::new(Bytes::copy_from_slice(&s0s1s2[1..1537]), RTMP_SERVER_KEY_FIRST_HALF);
+        let (s1_digest, _) = data_digest.read_digest().unwrap();
+        assert_eq!(
+            make_c2s2_digest(RTMP_CLIENT_KEY, &s1_digest, &c2[..1504]).unwrap(),
+            c2[1504..]
+        );
+    }
+
+    #[test]
+    fn test_client_simple_handshake() {
+        let mut client = HandshakeClient::Simple(SimpleHandshakeClient::default());
+        let mut server = HandshakeServer::default();
+
+        run_handshake(&mut client, &mut server);
+
+        assert!(matches!(server, HandshakeServer::Simple(_)));
+    }
+
+    #[test]
+    fn test_client_complex_handshake_fallback() {
+        let mut client = HandshakeClient::default();
+        let mut server = HandshakeServer::Simple(SimpleHandshakeServer::default());
+
+        run_handshake(&mut client, &mut server);
+
+        let HandshakeClient::Complex(client) = client else {
+            panic!("expected complex client");
+        };
+        assert!(!client.is_complex());
+    }
+
+    #[test]
+    fn test_client_complex_handshake_s2_mismatch() {
+        let mut client = HandshakeClient::default();
+        let mut server = HandshakeServer::default();
+
+        let mut c0c1 = Vec::new();
+        client.handshake(&mut std::io::Cursor::new(Bytes::new()), &mut c0c1).unwrap();
+
+        let mut s0s1s2 = Vec::new();
+        server
+            .handshake(&mut std::io::Cursor::new(Bytes::from(c0c1)), &mut s0s1s2)
+            .unwrap();
+
+        // Corrupt the digest of S2.
+        let last = s0s1s2.len() - 1;
+        s0s1s2[last] ^= 0xff;
+
+        let err = client
+            .handshake(&mut std::io::Cursor::new(Bytes::from(s0s1s2)), &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            RtmpError::ComplexHandshake(ComplexHandshakeError::S2DigestMismatch)
+        ));
+        assert!(!client.is_finished());
+    }
 }
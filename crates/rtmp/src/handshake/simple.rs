@@ -1,4 +1,4 @@
-//! Simple Handshake Server
+//! Simple Handshake Server and Client
 
 use std::io::{self, Seek, Write};
 
@@ -7,7 +7,9 @@ use bytes::Bytes;
 use rand::Rng;
 use scuffle_bytes_util::BytesCursorExt;
 
-use super::{RTMP_HANDSHAKE_SIZE, RtmpVersion, ServerHandshakeState, TIME_VERSION_LENGTH, current_time};
+use super::{
+    ClientHandshakeState, RTMP_HANDSHAKE_SIZE, RtmpVersion, ServerHandshakeState, TIME_VERSION_LENGTH, current_time,
+};
 
 /// Simple Handshake Server
 ///
@@ -166,3 +168,66 @@ impl SimpleHandshakeServer {
         Ok(())
     }
 }
+
+/// Simple Handshake Client
+///
+/// Defined by:
+/// - Legacy RTMP spec, 5.2
+pub struct SimpleHandshakeClient {
+    state: ClientHandshakeState,
+}
+
+impl Default for SimpleHandshakeClient {
+    fn default() -> Self {
+        Self {
+            state: ClientHandshakeState::WriteC0C1,
+        }
+    }
+}
+
+impl SimpleHandshakeClient {
+    /// Returns true if the handshake is finished.
+    pub fn is_finished(&self) -> bool {
+        self.state == ClientHandshakeState::Finish
+    }
+
+    /// Perform the handshake, writing to the output and reading from the input.
+    pub fn handshake(&mut self, input: &mut io::Cursor<Bytes>, output: &mut Vec<u8>) -> Result<(), crate::error::RtmpError> {
+        match self.state {
+            ClientHandshakeState::WriteC0C1 => {
+                // C0 and C1 have the same layout as S0 and S1.
+                output.write_u8(RtmpVersion::Version3.0)?;
+                output.write_u32::<BigEndian>(current_time())?;
+                output.write_u32::<BigEndian>(0)?;
+
+                let mut rng = rand::rng();
+                for _ in 0..RTMP_HANDSHAKE_SIZE - TIME_VERSION_LENGTH {
+                    output.write_u8(rng.random())?;
+                }
+
+                self.state = ClientHandshakeState::ReadS0S1S2;
+            }
+            ClientHandshakeState::ReadS0S1S2 => {
+                // The version selected by the server, we only support version 3 anyways.
+                input.read_u8()?;
+
+                let s1_timestamp = input.read_u32::<BigEndian>()?;
+                input.read_u32::<BigEndian>()?;
+                let s1_bytes = input.extract_bytes(RTMP_HANDSHAKE_SIZE - TIME_VERSION_LENGTH)?;
+
+                // Like the server, we are not strict about the echo in S2.
+                input.seek_relative(RTMP_HANDSHAKE_SIZE as i64)?;
+
+                // C2 echoes S1, the same way S2 echoes C1.
+                output.write_u32::<BigEndian>(s1_timestamp)?;
+                output.write_u32::<BigEndian>(current_time())?;
+                output.write_all(&s1_bytes)?;
+
+                self.state = ClientHandshakeState::Finish;
+            }
+            ClientHandshakeState::Finish => {}
+        }
+
+        Ok(())
+    }
+}
@@ -72,9 +72,11 @@ pub mod error;
 pub mod handshake;
 pub mod messages;
 pub mod protocol_control_messages;
+pub mod server;
 pub mod session;
 pub mod user_control_messages;
 
+pub use server::RtmpServer;
 pub use session::server::ServerSession;
 
 /// Changelogs generated by [scuffle_changelog]
@@ -6,6 +6,7 @@ use crate::command_messages::Command;
 use crate::protocol_control_messages::{
     ProtocolControlMessageSetChunkSize, ProtocolControlMessageWindowAcknowledgementSize,
 };
+use crate::user_control_messages::EventType;
 
 pub mod reader;
 
@@ -28,8 +29,14 @@ pub enum MessageData<'a> {
     Acknowledgement,
     /// User Control Event message
     ///
-    /// Not implemented.
-    UserControlEvent,
+    /// Only the event type is parsed, the event data can be read with the
+    /// corresponding type in [`user_control_messages`](crate::user_control_messages).
+    UserControlEvent {
+        /// The type of the event.
+        event_type: EventType,
+        /// The event data.
+        data: Bytes,
+    },
     /// Set Acknowledgement Window Size message
     SetAcknowledgementWindowSize(ProtocolControlMessageWindowAcknowledgementSize),
     /// Set Peer Bandwidth message
@@ -87,12 +94,33 @@ pub enum MessageData<'a> {
     Amf0Command(Command<'a>),
     /// Aggregate message
     ///
-    /// Not implemented.
-    Aggregate,
+    /// > An aggregate message is a single message that contains a series of
+    /// > RTMP sub-messages.
+    ///
+    /// The timestamps of the sub-messages are already corrected to be relative
+    /// to the timestamp of the aggregate message.
+    Aggregate {
+        /// The sub-messages.
+        messages: Vec<AggregateSubMessage>,
+    },
     /// Any other undefined messages.
     Unknown(UnknownMessage),
 }
 
+/// A sub-message of an aggregate message.
+///
+/// Defined by:
+/// - Legacy RTMP spec, 7.1.6
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateSubMessage {
+    /// The message type ID.
+    pub msg_type_id: MessageType,
+    /// The timestamp of the message.
+    pub timestamp: u32,
+    /// The message data.
+    pub data: Bytes,
+}
+
 /// Any undefined message.
 #[derive(Debug)]
 pub struct UnknownMessage {
@@ -1,11 +1,18 @@
 //! Reading [`MessageData`].
 
-use super::{MessageData, MessageType, UnknownMessage};
+use std::io;
+
+use byteorder::{BigEndian, ReadBytesExt};
+use bytes::Bytes;
+use scuffle_bytes_util::BytesCursorExt;
+
+use super::{AggregateSubMessage, MessageData, MessageType, UnknownMessage};
 use crate::chunk::Chunk;
 use crate::command_messages::Command;
 use crate::protocol_control_messages::{
     ProtocolControlMessageSetChunkSize, ProtocolControlMessageWindowAcknowledgementSize,
 };
+use crate::user_control_messages::EventType;
 
 impl MessageData<'_> {
     /// Reads [`MessageData`] from the given chunk.
@@ -18,7 +25,14 @@ impl MessageData<'_> {
             }
             MessageType::Abort => Ok(Self::Abort), // Not implemented
             MessageType::Acknowledgement => Ok(Self::Acknowledgement), // Not implemented
-            MessageType::UserControlEvent => Ok(Self::UserControlEvent), // Not implemented
+            MessageType::UserControlEvent => {
+                let mut cursor = io::Cursor::new(chunk.payload.clone());
+                let event_type = EventType(cursor.read_u16::<BigEndian>()?);
+                Ok(Self::UserControlEvent {
+                    event_type,
+                    data: cursor.extract_remaining(),
+                })
+            }
             MessageType::WindowAcknowledgementSize => {
                 let data = ProtocolControlMessageWindowAcknowledgementSize::read(&chunk.payload)?;
                 Ok(Self::SetAcknowledgementWindowSize(data))
@@ -40,7 +54,9 @@ impl MessageData<'_> {
             }),
             MessageType::SharedObjAMF0 => Ok(Self::SharedObjAmf0), // Not implemented
             MessageType::CommandAMF0 => Ok(Self::Amf0Command(Command::read(chunk.payload.clone())?)),
-            MessageType::Aggregate => Ok(Self::Aggregate), // Not implemented
+            MessageType::Aggregate => Ok(Self::Aggregate {
+                messages: read_aggregate(chunk.message_header.timestamp, chunk.payload.clone())?,
+            }),
             msg_type_id => Ok(Self::Unknown(UnknownMessage {
                 msg_type_id,
                 data: chunk.payload.clone(),
@@ -49,6 +65,43 @@ impl MessageData<'_> {
     }
 }
 
+/// Reads the sub-messages of an aggregate message.
+///
+/// Each sub-message is made up of a message header, the message data and a back pointer
+/// containing the size of the previous sub-message including its header.
+///
+/// Defined by:
+/// - Legacy RTMP spec, 7.1.6
+fn read_aggregate(timestamp: u32, payload: Bytes) -> io::Result<Vec<AggregateSubMessage>> {
+    let mut reader = io::Cursor::new(payload);
+    let mut messages = Vec::new();
+    let mut first_timestamp = None;
+
+    while reader.position() < reader.get_ref().len() as u64 {
+        let msg_type_id = MessageType(reader.read_u8()?);
+        let size = reader.read_u24::<BigEndian>()?;
+        // The lower 24 bits of the timestamp followed by the upper 8 bits.
+        let sub_timestamp = reader.read_u24::<BigEndian>()? | ((reader.read_u8()? as u32) << 24);
+        // The stream ID of the aggregate message is used instead.
+        reader.read_u24::<BigEndian>()?;
+        let data = reader.extract_bytes(size as usize)?;
+        // Back pointer, we don't need it.
+        reader.read_u32::<BigEndian>()?;
+
+        // The timestamps of the sub-messages are relative to the first sub-message,
+        // which has the same timestamp as the aggregate message.
+        let first_timestamp = *first_timestamp.get_or_insert(sub_timestamp);
+
+        messages.push(AggregateSubMessage {
+            msg_type_id,
+            timestamp: timestamp.wrapping_add(sub_timestamp.wrapping_sub(first_timestamp)),
+            data,
+        });
+    }
+
+    Ok(messages)
+}
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
@@ -132,6 +185,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_user_control_event() {
+        let chunk = Chunk::new(
+            2,
+            0,
+            MessageType::UserControlEvent,
+            0,
+            vec![0x00, 0x07, 0x00, 0x00, 0x00, 0x2A].into(),
+        );
+
+        let message = MessageData::read(&chunk).expect("no errors");
+        match message {
+            MessageData::UserControlEvent { event_type, data } => {
+                assert_eq!(event_type, EventType::PingResponse);
+                assert_eq!(data, vec![0x00, 0x00, 0x00, 0x2A]);
+            }
+            _ => unreachable!("wrong message type"),
+        }
+
+        let chunk = Chunk::new(2, 0, MessageType::UserControlEvent, 0, vec![0x00].into());
+        assert!(MessageData::read(&chunk).is_err());
+    }
+
     #[test]
     fn test_parse_window_acknowledgement_size() {
         let chunk = Chunk::new(
@@ -186,4 +262,63 @@ mod tests {
             })
         ));
     }
+
+    fn write_sub_message(buf: &mut Vec<u8>, msg_type_id: MessageType, timestamp: u32, data: &[u8]) {
+        buf.push(msg_type_id.0);
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+        buf.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+        buf.push((timestamp >> 24) as u8);
+        buf.extend_from_slice(&[0, 0, 1]); // stream id
+        buf.extend_from_slice(data);
+        buf.extend_from_slice(&(11 + data.len() as u32).to_be_bytes());
+    }
+
+    #[test]
+    fn test_parse_aggregate() {
+        let mut buf = Vec::new();
+        write_sub_message(&mut buf, MessageType::Video, 0x0100_0000, &[0x17, 0x01]);
+        write_sub_message(&mut buf, MessageType::Audio, 0x0100_0015, &[0xaf, 0x01, 0x02]);
+        write_sub_message(&mut buf, MessageType::Video, 0x0100_0021, &[0x27]);
+
+        let chunk = Chunk::new(0, 1000, MessageType::Aggregate, 1, buf.into());
+
+        let MessageData::Aggregate { messages } = MessageData::read(&chunk).expect("no errors") else {
+            unreachable!("wrong message type");
+        };
+
+        assert_eq!(
+            messages,
+            vec![
+                AggregateSubMessage {
+                    msg_type_id: MessageType::Video,
+                    timestamp: 1000,
+                    data: Bytes::from_static(&[0x17, 0x01]),
+                },
+                AggregateSubMessage {
+                    msg_type_id: MessageType::Audio,
+                    timestamp: 1021,
+                    data: Bytes::from_static(&[0xaf, 0x01, 0x02]),
+                },
+                AggregateSubMessage {
+                    msg_type_id: MessageType::Video,
+                    timestamp: 1033,
+                    data: Bytes::from_static(&[0x27]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_aggregate_truncated() {
+        let mut buf = Vec::new();
+        write_sub_message(&mut buf, MessageType::Video, 0, &[0x17, 0x01]);
+        buf.truncate(buf.len() - 5);
+
+        let chunk = Chunk::new(0, 0, MessageType::Aggregate, 1, buf.into());
+
+        assert!(matches!(
+            MessageData::read(&chunk),
+            Err(crate::error::RtmpError::Io(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+    }
 }
@@ -0,0 +1,339 @@
+//! A RTMP server that accepts connections on multiple addresses.
+//!
+//! [`RtmpServer`] takes care of the accept loops and spawns a [`ServerSession`] for every connection.
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use scuffle_context::ContextFutExt;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinSet;
+
+use crate::session::server::{ServerSession, ServerSessionLimits, SessionHandler};
+
+/// Information about an accepted connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    /// The address of the listener that accepted the connection.
+    pub local_addr: SocketAddr,
+    /// The address of the client.
+    pub peer_addr: SocketAddr,
+}
+
+/// Creates a [`SessionHandler`] for every accepted connection.
+///
+/// The [`ConnectionInfo`] can be used to route connections from different listeners to different handlers.
+///
+/// This is implemented for all closures `Fn(&ConnectionInfo) -> H`.
+pub trait SessionHandlerFactory: Send + Sync + 'static {
+    /// The handler created by this factory.
+    type Handler: SessionHandler + Send + 'static;
+
+    /// Creates a new handler for the given connection.
+    fn new_handler(&self, info: &ConnectionInfo) -> Self::Handler;
+}
+
+impl<F, H> SessionHandlerFactory for F
+where
+    F: Fn(&ConnectionInfo) -> H + Send + Sync + 'static,
+    H: SessionHandler + Send + 'static,
+{
+    type Handler = H;
+
+    fn new_handler(&self, info: &ConnectionInfo) -> Self::Handler {
+        self(info)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    accepted: AtomicU64,
+    active: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// Connection statistics of a single listener.
+///
+/// This is a cheap handle that stays up to date while the server is running.
+#[derive(Debug, Clone)]
+pub struct ListenerStats {
+    local_addr: SocketAddr,
+    counters: Arc<Counters>,
+}
+
+impl ListenerStats {
+    /// The address the listener is bound to.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The total number of accepted connections.
+    pub fn accepted(&self) -> u64 {
+        self.counters.accepted.load(Ordering::Relaxed)
+    }
+
+    /// The number of sessions that are currently running.
+    pub fn active(&self) -> u64 {
+        self.counters.active.load(Ordering::Relaxed)
+    }
+
+    /// The total number of sessions that ended with an error.
+    ///
+    /// Clients closing the connection are not counted as errors.
+    pub fn failed(&self) -> u64 {
+        self.counters.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// A RTMP server that accepts connections on one or more listeners.
+///
+/// ```no_run
+/// # use scuffle_rtmp::server::{ConnectionInfo, RtmpServer};
+/// # use scuffle_rtmp::session::server::{ServerSessionError, SessionData, SessionHandler};
+/// # struct Handler;
+/// # impl SessionHandler for Handler {
+/// #     async fn on_data(&mut self, _: u32, _: SessionData) -> Result<(), ServerSessionError> { Ok(()) }
+/// #     async fn on_publish(&mut self, _: u32, _: &str, _: &str) -> Result<(), ServerSessionError> { Ok(()) }
+/// #     async fn on_unpublish(&mut self, _: u32) -> Result<(), ServerSessionError> { Ok(()) }
+/// # }
+/// # async fn run() -> std::io::Result<()> {
+/// let server = RtmpServer::new(|_: &ConnectionInfo| Handler)
+///     .bind("[::]:1935")
+///     .await?
+///     .bind("127.0.0.1:1936")
+///     .await?;
+///
+/// server.run().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RtmpServer<F> {
+    ctx: Option<scuffle_context::Context>,
+    limits: ServerSessionLimits,
+    factory: Arc<F>,
+    listeners: Vec<(TcpListener, ListenerStats)>,
+}
+
+impl<F> RtmpServer<F> {
+    /// Create a new server without any listeners.
+    pub fn new(factory: F) -> Self {
+        Self {
+            ctx: None,
+            limits: ServerSessionLimits::default(),
+            factory: Arc::new(factory),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Set the context of the server.
+    ///
+    /// The server stops accepting connections once the context is cancelled.
+    /// Every session runs with this context as well, so running sessions are asked to reconnect
+    /// and the context handler can wait for them to finish.
+    pub fn with_context(mut self, ctx: scuffle_context::Context) -> Self {
+        self.ctx = Some(ctx);
+        self
+    }
+
+    /// Set the timeouts and size limits of every session.
+    pub fn with_limits(mut self, limits: ServerSessionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Bind a new listener to the given address.
+    pub async fn bind(self, addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        self.with_listener(listener)
+    }
+
+    /// Add an already bound listener.
+    pub fn with_listener(mut self, listener: TcpListener) -> io::Result<Self> {
+        let stats = ListenerStats {
+            local_addr: listener.local_addr()?,
+            counters: Arc::default(),
+        };
+
+        self.listeners.push((listener, stats));
+        Ok(self)
+    }
+
+    /// Returns the statistics of all listeners, in the order they were added.
+    pub fn stats(&self) -> Vec<ListenerStats> {
+        self.listeners.iter().map(|(_, stats)| stats.clone()).collect()
+    }
+}
+
+impl<F: SessionHandlerFactory> RtmpServer<F> {
+    /// Run the server until the context is cancelled.
+    ///
+    /// Returns once all listeners stopped accepting connections, sessions which are still running
+    /// continue in the background.
+    pub async fn run(self) {
+        let ctx = self.ctx.unwrap_or_else(scuffle_context::Context::global);
+
+        let mut accept_loops = JoinSet::new();
+        for (listener, stats) in self.listeners {
+            accept_loops.spawn(accept_loop(ctx.clone(), self.limits, self.factory.clone(), listener, stats));
+        }
+
+        while accept_loops.join_next().await.is_some() {}
+    }
+}
+
+async fn accept_loop<F: SessionHandlerFactory>(
+    ctx: scuffle_context::Context,
+    limits: ServerSessionLimits,
+    factory: Arc<F>,
+    listener: TcpListener,
+    stats: ListenerStats,
+) {
+    tracing::debug!(addr = %stats.local_addr, "listening");
+
+    while let Some(result) = listener.accept().with_context(&ctx).await {
+        let (stream, peer_addr) = match result {
+            Ok(conn) => conn,
+            Err(err) => {
+                tracing::warn!(addr = %stats.local_addr, err = %err, "failed to accept connection");
+                continue;
+            }
+        };
+
+        let info = ConnectionInfo {
+            local_addr: stats.local_addr,
+            peer_addr,
+        };
+
+        stats.counters.accepted.fetch_add(1, Ordering::Relaxed);
+        stats.counters.active.fetch_add(1, Ordering::Relaxed);
+
+        let handler = factory.new_handler(&info);
+        tokio::spawn(run_session(ctx.clone(), limits, stream, handler, info, stats.clone()));
+    }
+
+    tracing::debug!(addr = %stats.local_addr, "stopped listening");
+}
+
+async fn run_session<H: SessionHandler>(
+    ctx: scuffle_context::Context,
+    limits: ServerSessionLimits,
+    stream: TcpStream,
+    handler: H,
+    info: ConnectionInfo,
+    stats: ListenerStats,
+) {
+    let session = ServerSession::new(stream, handler).with_context(ctx).with_limits(limits);
+
+    match session.run().await {
+        Ok(_) => {}
+        Err(err) if err.is_client_closed() => {
+            tracing::debug!(peer = %info.peer_addr, "client closed the connection");
+        }
+        Err(err) => {
+            tracing::error!(peer = %info.peer_addr, err = %err, "session error");
+            stats.counters.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    stats.counters.active.fetch_sub(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use super::{ConnectionInfo, RtmpServer};
+    use crate::handshake::RTMP_HANDSHAKE_SIZE;
+    use crate::session::server::{ServerSessionError, ServerSessionLimits, SessionData, SessionHandler};
+
+    struct Handler;
+
+    impl SessionHandler for Handler {
+        async fn on_publish(&mut self, _: u32, _: &str, _: &str) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_unpublish(&mut self, _: u32) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_data(&mut self, _: u32, _: SessionData) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multiple_listeners() {
+        let (ctx, handler) = scuffle_context::Context::new();
+        let (routed_tx, mut routed_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let server = RtmpServer::new(move |info: &ConnectionInfo| {
+            routed_tx.send(info.local_addr).unwrap();
+            Handler
+        })
+        .with_context(ctx)
+        .with_limits(ServerSessionLimits {
+            handshake_timeout: Duration::from_millis(200),
+            ..Default::default()
+        })
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap();
+
+        let stats = server.stats();
+        assert_eq!(stats.len(), 2);
+
+        let server = tokio::spawn(server.run());
+
+        // A client which completes the handshake
+        let mut client = TcpStream::connect(stats[0].local_addr()).await.unwrap();
+        assert_eq!(routed_rx.recv().await.unwrap(), stats[0].local_addr());
+
+        let mut c0c1 = vec![0; RTMP_HANDSHAKE_SIZE + 1];
+        c0c1[0] = 3;
+        client.write_all(&c0c1).await.unwrap();
+        let mut s0s1s2 = vec![0; RTMP_HANDSHAKE_SIZE * 2 + 1];
+        client.read_exact(&mut s0s1s2).await.unwrap();
+        // C2 followed by the first byte of a chunk, the session only reads full handshake packets
+        let mut c2 = vec![0; RTMP_HANDSHAKE_SIZE];
+        c2.push(0x03);
+        client.write_all(&c2).await.unwrap();
+
+        // A client which never starts the handshake
+        let _idle = TcpStream::connect(stats[1].local_addr()).await.unwrap();
+        assert_eq!(routed_rx.recv().await.unwrap(), stats[1].local_addr());
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while stats[1].active() > 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(stats[0].accepted(), 1);
+        assert_eq!(stats[0].active(), 1);
+        assert_eq!(stats[1].accepted(), 1);
+        assert_eq!(stats[1].failed(), 1);
+
+        drop(client);
+
+        // Waits for the running session to finish
+        tokio::time::timeout(Duration::from_secs(1), handler.shutdown())
+            .await
+            .unwrap();
+        assert_eq!(stats[0].active(), 0);
+        assert_eq!(stats[0].failed(), 0);
+
+        tokio::time::timeout(Duration::from_secs(1), server).await.unwrap().unwrap();
+    }
+}
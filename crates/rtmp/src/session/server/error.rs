@@ -6,6 +6,15 @@ pub enum ServerSessionError {
     /// Timeout.
     #[error("timeout: {0}")]
     Timeout(#[from] tokio::time::error::Elapsed),
+    /// The client did not complete the handshake in time.
+    #[error("handshake timed out")]
+    HandshakeTimeout,
+    /// The client did not send a connect command in time.
+    #[error("connect timed out")]
+    ConnectTimeout,
+    /// The client did not respond to a ping request in time.
+    #[error("ping timed out")]
+    PingTimeout,
     /// Received publish command before connect command.
     #[error("received publish command before connect command")]
     PublishBeforeConnect,
@@ -1,5 +1,7 @@
 //! Defines types for handling session events.
 
+use std::time::Duration;
+
 use bytes::Bytes;
 
 use super::error::ServerSessionError;
@@ -7,6 +9,10 @@ use crate::command_messages::UnknownCommand;
 use crate::messages::UnknownMessage;
 
 /// Data received from a session.
+///
+/// The data is the payload of an FLV tag, e.g. `VIDEODATA` for video, and can be demuxed without copying it.
+/// Messages that fit into a single chunk refer to the read buffer of the session, larger messages are
+/// reassembled into reused buffers. Holding on to the data keeps these buffers alive.
 #[derive(Debug, Clone)]
 pub enum SessionData {
     /// Video data.
@@ -69,6 +75,19 @@ pub trait SessionHandler {
         }
     }
 
+    /// Called when the client responds to a ping request with the measured round trip time.
+    ///
+    /// Ping requests are only sent if [`ServerSessionLimits::ping_interval`](super::ServerSessionLimits::ping_interval) is set.
+    fn on_ping_response(
+        &mut self,
+        rtt: Duration,
+    ) -> impl std::future::Future<Output = Result<(), ServerSessionError>> + Send {
+        async move {
+            tracing::trace!(rtt = ?rtt, "ping response");
+            Ok(())
+        }
+    }
+
     /// Called when data is received.
     fn on_data(
         &mut self,
@@ -0,0 +1,149 @@
+//! Keepalive (ping) tracking for server sessions.
+
+use std::time::{Duration, Instant};
+
+use super::error::ServerSessionError;
+
+/// Keeps track of the ping requests sent to the client.
+///
+/// Only one ping request is outstanding at a time. The next ping is scheduled
+/// `interval` after the response to the previous one was received.
+#[derive(Debug)]
+pub(crate) struct Keepalive {
+    interval: Option<Duration>,
+    timeout: Duration,
+    /// The timestamps of the ping requests are relative to this instant.
+    epoch: Instant,
+    /// When the next ping request should be sent, `None` until the keepalive is started.
+    next_ping: Option<Instant>,
+    /// The timestamp and send time of the outstanding ping request.
+    pending: Option<(u32, Instant)>,
+}
+
+impl Keepalive {
+    pub(crate) fn new(interval: Option<Duration>, timeout: Duration) -> Self {
+        Self {
+            interval,
+            timeout,
+            epoch: Instant::now(),
+            next_ping: None,
+            pending: None,
+        }
+    }
+
+    /// Starts sending ping requests, does nothing if pings are disabled.
+    pub(crate) fn start(&mut self, now: Instant) {
+        if self.next_ping.is_none() && self.pending.is_none() {
+            self.next_ping = self.interval.map(|interval| now + interval);
+        }
+    }
+
+    /// The next point in time at which [`Keepalive::poll`] has to be called.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        match self.pending {
+            Some((_, sent_at)) => Some(sent_at + self.timeout),
+            None => self.next_ping,
+        }
+    }
+
+    /// Returns the timestamp of a ping request that should be sent now.
+    ///
+    /// Fails if the client did not respond to the outstanding ping request in time.
+    pub(crate) fn poll(&mut self, now: Instant) -> Result<Option<u32>, ServerSessionError> {
+        if let Some((_, sent_at)) = self.pending {
+            if now.duration_since(sent_at) >= self.timeout {
+                return Err(ServerSessionError::PingTimeout);
+            }
+
+            return Ok(None);
+        }
+
+        match self.next_ping {
+            Some(next_ping) if next_ping <= now => {
+                // The timestamp wraps after ~49 days, just like the chunk timestamps.
+                let timestamp = now.duration_since(self.epoch).as_millis() as u32;
+                self.pending = Some((timestamp, now));
+                self.next_ping = None;
+                Ok(Some(timestamp))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Handles a ping response from the client and returns the round trip time.
+    ///
+    /// Returns `None` if the response does not belong to the outstanding ping request.
+    pub(crate) fn on_ping_response(&mut self, timestamp: u32, now: Instant) -> Option<Duration> {
+        let (pending_timestamp, sent_at) = self.pending?;
+        if pending_timestamp != timestamp {
+            return None;
+        }
+
+        self.pending = None;
+        self.next_ping = self.interval.map(|interval| now + interval);
+
+        Some(now.duration_since(sent_at))
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Keepalive;
+    use crate::session::server::ServerSessionError;
+
+    #[test]
+    fn test_keepalive_disabled() {
+        let now = Instant::now();
+        let mut keepalive = Keepalive::new(None, Duration::from_secs(1));
+        keepalive.start(now);
+
+        assert_eq!(keepalive.deadline(), None);
+        assert_eq!(keepalive.poll(now + Duration::from_secs(60)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_keepalive_ping() {
+        let now = Instant::now();
+        let mut keepalive = Keepalive::new(Some(Duration::from_secs(5)), Duration::from_secs(2));
+
+        // Not started yet
+        assert_eq!(keepalive.deadline(), None);
+        assert_eq!(keepalive.poll(now + Duration::from_secs(10)).unwrap(), None);
+
+        keepalive.start(now);
+        assert_eq!(keepalive.deadline(), Some(now + Duration::from_secs(5)));
+        assert_eq!(keepalive.poll(now + Duration::from_secs(1)).unwrap(), None);
+
+        let sent_at = now + Duration::from_secs(5);
+        let timestamp = keepalive.poll(sent_at).unwrap().expect("ping should be sent");
+        assert_eq!(keepalive.deadline(), Some(sent_at + Duration::from_secs(2)));
+        // Only one ping is outstanding at a time
+        assert_eq!(keepalive.poll(sent_at + Duration::from_secs(1)).unwrap(), None);
+
+        let received_at = sent_at + Duration::from_millis(150);
+        assert_eq!(keepalive.on_ping_response(timestamp.wrapping_add(1), received_at), None);
+        assert_eq!(
+            keepalive.on_ping_response(timestamp, received_at),
+            Some(Duration::from_millis(150))
+        );
+        assert_eq!(keepalive.on_ping_response(timestamp, received_at), None);
+        assert_eq!(keepalive.deadline(), Some(received_at + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_keepalive_timeout() {
+        let now = Instant::now();
+        let mut keepalive = Keepalive::new(Some(Duration::from_secs(5)), Duration::from_secs(2));
+        keepalive.start(now);
+
+        let sent_at = now + Duration::from_secs(5);
+        assert!(keepalive.poll(sent_at).unwrap().is_some());
+        assert!(matches!(
+            keepalive.poll(sent_at + Duration::from_secs(2)),
+            Err(ServerSessionError::PingTimeout)
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+//! Limits for server sessions.
+
+use std::time::Duration;
+
+use crate::chunk::reader::{DEFAULT_MAX_AMF_PAYLOAD_SIZE, DEFAULT_MAX_MESSAGE_SIZE};
+
+/// Timeouts and size limits that protect a [`ServerSession`](super::ServerSession)
+/// against slow or malicious clients.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use scuffle_rtmp::session::server::ServerSessionLimits;
+/// let limits = ServerSessionLimits {
+///     handshake_timeout: Duration::from_secs(2),
+///     max_message_size: 2 * 1024 * 1024,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerSessionLimits {
+    /// The time the client has to complete the handshake.
+    ///
+    /// Exceeding this fails the session with [`ServerSessionError::HandshakeTimeout`](super::ServerSessionError::HandshakeTimeout).
+    pub handshake_timeout: Duration,
+    /// The time the client has to send the connect command after the handshake is complete.
+    ///
+    /// Exceeding this fails the session with [`ServerSessionError::ConnectTimeout`](super::ServerSessionError::ConnectTimeout).
+    pub connect_timeout: Duration,
+    /// The time to wait for any data from the client.
+    pub read_timeout: Duration,
+    /// The time to wait for data to be written to the client.
+    pub write_timeout: Duration,
+    /// The interval at which ping requests are sent to the client once it is connected.
+    ///
+    /// The measured round trip time is reported to [`SessionHandler::on_ping_response`](super::SessionHandler::on_ping_response).
+    /// Pings are disabled when this is `None`.
+    pub ping_interval: Option<Duration>,
+    /// The time the client has to respond to a ping request.
+    ///
+    /// Exceeding this fails the session with [`ServerSessionError::PingTimeout`](super::ServerSessionError::PingTimeout).
+    pub ping_timeout: Duration,
+    /// The maximum size of a single message, this limits how much data is accumulated from partial chunks.
+    ///
+    /// See [`ChunkReader::set_max_message_size`](crate::chunk::reader::ChunkReader::set_max_message_size).
+    pub max_message_size: usize,
+    /// The maximum size of an AMF message (commands, metadata and shared objects).
+    ///
+    /// See [`ChunkReader::set_max_amf_payload_size`](crate::chunk::reader::ChunkReader::set_max_amf_payload_size).
+    pub max_amf_payload_size: usize,
+}
+
+impl Default for ServerSessionLimits {
+    fn default() -> Self {
+        Self {
+            handshake_timeout: Duration::from_secs(5),
+            connect_timeout: Duration::from_secs(5),
+            read_timeout: Duration::from_millis(2500),
+            write_timeout: Duration::from_secs(2),
+            ping_interval: None,
+            ping_timeout: Duration::from_secs(10),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_amf_payload_size: DEFAULT_MAX_AMF_PAYLOAD_SIZE,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! RTMP server session.
 
-use std::time::Duration;
+use std::time::Instant;
 
 use bytes::BytesMut;
 use scuffle_bytes_util::{BytesCursorExt, StringCow};
@@ -19,18 +19,22 @@ use crate::command_messages::on_status::{OnStatus, OnStatusCode};
 use crate::command_messages::{Command, CommandResultLevel, CommandType};
 use crate::handshake;
 use crate::handshake::HandshakeServer;
-use crate::messages::MessageData;
+use crate::messages::{AggregateSubMessage, MessageData, MessageType, UnknownMessage};
 use crate::protocol_control_messages::{
     ProtocolControlMessageAcknowledgement, ProtocolControlMessageSetChunkSize, ProtocolControlMessageSetPeerBandwidth,
     ProtocolControlMessageSetPeerBandwidthLimitType, ProtocolControlMessageWindowAcknowledgementSize,
 };
-use crate::user_control_messages::EventMessageStreamBegin;
+use crate::user_control_messages::{EventMessagePingRequest, EventMessagePingResponse, EventMessageStreamBegin, EventType};
 
 mod error;
 mod handler;
+mod keepalive;
+mod limits;
 
 pub use error::ServerSessionError;
 pub use handler::{SessionData, SessionHandler};
+use keepalive::Keepalive;
+pub use limits::ServerSessionLimits;
 
 // The default acknowledgement window size that is used until the client sends a
 // new acknowledgement window size.
@@ -81,11 +85,20 @@ pub struct ServerSession<S, H> {
     chunk_writer: ChunkWriter,
     /// Is Publishing
     publishing_stream_ids: Vec<u32>,
+    /// Timeouts and size limits for the session
+    limits: ServerSessionLimits,
+    /// The client has to send the connect command before this deadline.
+    /// This is set once the handshake is complete and cleared when the client connects.
+    connect_deadline: Option<Instant>,
+    /// Sends ping requests to the client and keeps track of the responses.
+    keepalive: Keepalive,
 }
 
 impl<S, H> ServerSession<S, H> {
     /// Create a new session.
     pub fn new(io: S, handler: H) -> Self {
+        let limits = ServerSessionLimits::default();
+
         Self {
             ctx: None,
             reconnect_request_sent: false,
@@ -101,6 +114,9 @@ impl<S, H> ServerSession<S, H> {
             read_buf: BytesMut::new(),
             write_buf: Vec::new(),
             publishing_stream_ids: Vec::new(),
+            keepalive: Keepalive::new(limits.ping_interval, limits.ping_timeout),
+            limits,
+            connect_deadline: None,
         }
     }
 
@@ -109,6 +125,15 @@ impl<S, H> ServerSession<S, H> {
         self.ctx = Some(ctx);
         self
     }
+
+    /// Set the timeouts and size limits of the session.
+    pub fn with_limits(mut self, limits: ServerSessionLimits) -> Self {
+        self.chunk_reader.set_max_message_size(limits.max_message_size);
+        self.chunk_reader.set_max_amf_payload_size(limits.max_amf_payload_size);
+        self.keepalive = Keepalive::new(limits.ping_interval, limits.ping_timeout);
+        self.limits = limits;
+        self
+    }
 }
 
 impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler> ServerSession<S, H> {
@@ -120,20 +145,15 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
     pub async fn run(mut self) -> Result<bool, crate::error::RtmpError> {
         let ctx = self.ctx.clone().unwrap_or_else(scuffle_context::Context::global);
 
-        let mut handshaker = HandshakeServer::default();
-        // Run the handshake to completion
-        loop {
-            match self.drive_handshake(&mut handshaker).with_context(&ctx).await {
-                Some(Ok(false)) => self.flush().await?, // Continue driving
-                Some(Ok(true)) => break,                // Handshake is complete
-                Some(Err(e)) => return Err(e),
-                None => return Ok(false), // Context was cancelled
-            }
+        let handshake_timeout = self.limits.handshake_timeout;
+        match self.handshake(&ctx).with_timeout(handshake_timeout).await {
+            Ok(Ok(true)) => {}                 // Handshake is complete
+            Ok(Ok(false)) => return Ok(false), // Context was cancelled
+            Ok(Err(e)) => return Err(e),
+            Err(_) => return Err(ServerSessionError::HandshakeTimeout.into()),
         }
 
-        // Drop the handshaker, we don't need it anymore
-        // We can get rid of the memory that was allocated for it
-        drop(handshaker);
+        self.connect_deadline = Some(Instant::now() + self.limits.connect_timeout);
 
         tracing::debug!("handshake complete");
 
@@ -159,6 +179,22 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
         Ok(self.publishing_stream_ids.is_empty())
     }
 
+    /// Runs the handshake to completion.
+    ///
+    /// Returns false if the context was cancelled before the handshake completed.
+    async fn handshake(&mut self, ctx: &scuffle_context::Context) -> Result<bool, crate::error::RtmpError> {
+        let mut handshaker = HandshakeServer::default();
+
+        loop {
+            match self.drive_handshake(&mut handshaker).with_context(ctx).await {
+                Some(Ok(false)) => self.flush().await?, // Continue driving
+                Some(Ok(true)) => return Ok(true),      // Handshake is complete
+                Some(Err(e)) => return Err(e),
+                None => return Ok(false), // Context was cancelled
+            }
+        }
+    }
+
     /// This drives the first stage of the session.
     /// It is used to do the handshake with the client.
     /// The handshake is the first thing that happens when a client connects to a
@@ -176,7 +212,7 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
             let n = self
                 .io
                 .read_buf(&mut self.read_buf)
-                .with_timeout(Duration::from_secs(2))
+                .with_timeout(self.limits.read_timeout)
                 .await
                 .map_err(ServerSessionError::Timeout)??;
             bytes_read += n;
@@ -237,18 +273,51 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
             self.reconnect_request_sent = true;
         }
 
+        // This fails the session if the client did not respond to the last ping request in time.
+        if let Some(timestamp) = self.keepalive.poll(Instant::now())? {
+            tracing::trace!(timestamp = %timestamp, "sending ping request");
+
+            EventMessagePingRequest { timestamp }.write(&self.chunk_writer, &mut self.write_buf)?;
+
+            // Flush the ping request before waiting for more data
+            return Ok(true);
+        }
+
+        // The client has to connect within the connect timeout, no matter how slowly it sends data.
+        let read_timeout = match self.connect_deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(ServerSessionError::ConnectTimeout.into());
+                }
+
+                remaining.min(self.limits.read_timeout)
+            }
+            None => self.limits.read_timeout,
+        };
+
+        // Wake up in time to send the next ping request or to detect a missing ping response.
+        let read_timeout = match self.keepalive.deadline() {
+            Some(deadline) => read_timeout.min(deadline.saturating_duration_since(Instant::now())),
+            None => read_timeout,
+        };
+
         // If we have data ready to parse, parse it
         if self.skip_read {
             self.skip_read = false;
         } else {
             self.read_buf.reserve(CHUNK_SIZE);
 
-            let n = self
-                .io
-                .read_buf(&mut self.read_buf)
-                .with_timeout(Duration::from_millis(2500))
-                .await
-                .map_err(ServerSessionError::Timeout)?? as u32;
+            let n = match self.io.read_buf(&mut self.read_buf).with_timeout(read_timeout).await {
+                Ok(n) => n? as u32,
+                Err(_) if self.connect_deadline.is_some_and(|deadline| deadline <= Instant::now()) => {
+                    return Err(ServerSessionError::ConnectTimeout.into());
+                }
+                Err(_) if self.keepalive.deadline().is_some_and(|deadline| deadline <= Instant::now()) => {
+                    return Ok(true);
+                }
+                Err(elapsed) => return Err(ServerSessionError::Timeout(elapsed).into()),
+            };
 
             if n == 0 {
                 return Ok(false);
@@ -313,6 +382,9 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
             }) => {
                 self.on_acknowledgement_window_size(acknowledgement_window_size)?;
             }
+            MessageData::UserControlEvent { event_type, data } => {
+                self.on_user_control_event(event_type, &data).await?;
+            }
             MessageData::AudioData { data } => {
                 self.handler
                     .on_data(stream_id, SessionData::Audio { timestamp, data })
@@ -326,6 +398,28 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
             MessageData::DataAmf0 { data } => {
                 self.handler.on_data(stream_id, SessionData::Amf0 { timestamp, data }).await?;
             }
+            MessageData::Aggregate { messages } => {
+                for AggregateSubMessage {
+                    msg_type_id,
+                    timestamp,
+                    data,
+                } in messages
+                {
+                    let data = match msg_type_id {
+                        MessageType::Audio => SessionData::Audio { timestamp, data },
+                        MessageType::Video => SessionData::Video { timestamp, data },
+                        MessageType::DataAMF0 => SessionData::Amf0 { timestamp, data },
+                        msg_type_id => {
+                            self.handler
+                                .on_unknown_message(stream_id, UnknownMessage { msg_type_id, data })
+                                .await?;
+                            continue;
+                        }
+                    };
+
+                    self.handler.on_data(stream_id, data).await?;
+                }
+            }
             MessageData::Unknown(unknown_message) => {
                 self.handler.on_unknown_message(stream_id, unknown_message).await?;
             }
@@ -407,6 +501,21 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
         Ok(())
     }
 
+    /// on_user_control_event is called when we receive a user control message from the client.
+    /// We only care about ping responses to measure the round trip time.
+    async fn on_user_control_event(&mut self, event_type: EventType, data: &[u8]) -> Result<(), crate::error::RtmpError> {
+        if event_type == EventType::PingResponse {
+            let EventMessagePingResponse { timestamp } = EventMessagePingResponse::read(data)?;
+
+            if let Some(rtt) = self.keepalive.on_ping_response(timestamp, Instant::now()) {
+                tracing::trace!(rtt = ?rtt, "received ping response");
+                self.handler.on_ping_response(rtt).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// on_command_connect is called when we receive a amf0 command message with
     /// the name "connect" We then handle the connect message
     /// This is called when the client first connects to the server
@@ -429,6 +538,8 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
 
         self.app_name = Some(connect.app.into_owned());
         self.caps_ex = connect.caps_ex;
+        self.connect_deadline = None;
+        self.keepalive.start(Instant::now());
 
         let result = NetConnectionCommand::ConnectResult(NetConnectionCommandConnectResult::default());
 
@@ -530,7 +641,7 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
         if !self.write_buf.is_empty() {
             self.io
                 .write_all(self.write_buf.as_ref())
-                .with_timeout(Duration::from_secs(2))
+                .with_timeout(self.limits.write_timeout)
                 .await
                 .map_err(ServerSessionError::Timeout)??;
             self.write_buf.clear();
@@ -539,3 +650,234 @@ impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin, H: SessionHandler>
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use bytes::BytesMut;
+    use scuffle_amf0::encoder::Amf0Encoder;
+    use scuffle_amf0::{Amf0Object, Amf0Value};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+
+    use super::{ServerSession, ServerSessionError, ServerSessionLimits, SessionData, SessionHandler};
+    use crate::chunk::Chunk;
+    use crate::chunk::error::ChunkReadError;
+    use crate::chunk::reader::ChunkReader;
+    use crate::chunk::writer::ChunkWriter;
+    use crate::error::RtmpError;
+    use crate::handshake::RTMP_HANDSHAKE_SIZE;
+    use crate::messages::{MessageData, MessageType};
+    use crate::protocol_control_messages::ProtocolControlMessageSetChunkSize;
+    use crate::user_control_messages::EventType;
+
+    struct Handler;
+
+    impl SessionHandler for Handler {
+        async fn on_publish(&mut self, _: u32, _: &str, _: &str) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_unpublish(&mut self, _: u32) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_data(&mut self, _: u32, _: SessionData) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+    }
+
+    fn spawn_session(limits: ServerSessionLimits) -> (DuplexStream, tokio::task::JoinHandle<Result<bool, RtmpError>>) {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let session = ServerSession::new(server, Handler).with_limits(limits);
+        (client, tokio::spawn(session.run()))
+    }
+
+    /// Performs a simple handshake, `extra` is sent right after C2.
+    async fn handshake(client: &mut DuplexStream, extra: &[u8]) {
+        // C0 + C1
+        let mut c0c1 = vec![0; RTMP_HANDSHAKE_SIZE + 1];
+        c0c1[0] = 3;
+        client.write_all(&c0c1).await.unwrap();
+
+        // S0 + S1 + S2
+        let mut s0s1s2 = vec![0; RTMP_HANDSHAKE_SIZE * 2 + 1];
+        client.read_exact(&mut s0s1s2).await.unwrap();
+
+        // C2
+        let mut c2 = vec![0; RTMP_HANDSHAKE_SIZE];
+        c2.extend_from_slice(extra);
+        client.write_all(&c2).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_handshake_timeout() {
+        let (mut client, session) = spawn_session(ServerSessionLimits {
+            handshake_timeout: Duration::from_millis(100),
+            read_timeout: Duration::from_secs(10),
+            ..Default::default()
+        });
+
+        // Slowly send C0, the client never finishes the handshake.
+        client.write_all(&[3]).await.unwrap();
+
+        let err = session.await.unwrap().unwrap_err();
+        assert!(matches!(err, RtmpError::Session(ServerSessionError::HandshakeTimeout)));
+        assert!(!err.is_client_closed());
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout() {
+        let (mut client, session) = spawn_session(ServerSessionLimits {
+            connect_timeout: Duration::from_millis(100),
+            read_timeout: Duration::from_secs(10),
+            ..Default::default()
+        });
+
+        // The first byte of a chunk header, the rest never arrives.
+        handshake(&mut client, &[0x03]).await;
+
+        let err = session.await.unwrap().unwrap_err();
+        assert!(matches!(err, RtmpError::Session(ServerSessionError::ConnectTimeout)));
+    }
+
+    #[tokio::test]
+    async fn test_amf_payload_too_large() {
+        let (mut client, session) = spawn_session(ServerSessionLimits {
+            max_amf_payload_size: 1024,
+            ..Default::default()
+        });
+
+        #[rustfmt::skip]
+        let chunk = [
+            0x03, // fmt 0, csid 3
+            0x00, 0x00, 0x00, // timestamp
+            0x00, 0x08, 0x00, // message length (2048)
+            0x14, // message type id (amf0 command)
+            0x00, 0x00, 0x00, 0x00, // message stream id
+        ];
+        handshake(&mut client, &chunk).await;
+
+        let err = session.await.unwrap().unwrap_err();
+        assert!(matches!(err, RtmpError::ChunkRead(ChunkReadError::AmfPayloadTooLarge(2048))));
+    }
+
+    #[tokio::test]
+    async fn test_message_too_large() {
+        let (mut client, session) = spawn_session(ServerSessionLimits {
+            max_message_size: 1024,
+            ..Default::default()
+        });
+
+        #[rustfmt::skip]
+        let chunk = [
+            0x04, // fmt 0, csid 4
+            0x00, 0x00, 0x00, // timestamp
+            0x00, 0x08, 0x00, // message length (2048)
+            0x09, // message type id (video)
+            0x01, 0x00, 0x00, 0x00, // message stream id
+        ];
+        handshake(&mut client, &chunk).await;
+
+        let err = session.await.unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            RtmpError::ChunkRead(ChunkReadError::PartialChunkTooLarge(2048))
+        ));
+    }
+
+    #[derive(Default, Clone)]
+    struct PingHandler {
+        rtts: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl SessionHandler for PingHandler {
+        async fn on_publish(&mut self, _: u32, _: &str, _: &str) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_unpublish(&mut self, _: u32) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_data(&mut self, _: u32, _: SessionData) -> Result<(), ServerSessionError> {
+            Ok(())
+        }
+
+        async fn on_ping_response(&mut self, rtt: Duration) -> Result<(), ServerSessionError> {
+            self.rtts.lock().unwrap().push(rtt);
+            Ok(())
+        }
+    }
+
+    /// Reads messages from the server until a ping request is received and returns its event data.
+    async fn read_ping_request(client: &mut DuplexStream, reader: &mut ChunkReader, buf: &mut BytesMut) -> Vec<u8> {
+        loop {
+            while let Some(chunk) = reader.read_chunk(buf).unwrap() {
+                match MessageData::read(&chunk).unwrap() {
+                    MessageData::SetChunkSize(ProtocolControlMessageSetChunkSize { chunk_size }) => {
+                        assert!(reader.update_max_chunk_size(chunk_size as usize));
+                    }
+                    MessageData::UserControlEvent { event_type, data } if event_type == EventType::PingRequest => {
+                        return data.to_vec();
+                    }
+                    _ => {}
+                }
+            }
+
+            assert_ne!(client.read_buf(buf).await.unwrap(), 0, "server closed the connection");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let handler = PingHandler::default();
+        let session = ServerSession::new(server, handler.clone()).with_limits(ServerSessionLimits {
+            ping_interval: Some(Duration::from_millis(50)),
+            ping_timeout: Duration::from_millis(200),
+            ..Default::default()
+        });
+        let session = tokio::spawn(session.run());
+
+        let mut connect = Vec::new();
+        let mut encoder = Amf0Encoder::new(&mut connect);
+        encoder.encode_string("connect").unwrap();
+        encoder.encode_number(1.0).unwrap();
+        let object: Amf0Object = [("app".into(), Amf0Value::String("live".into()))].into_iter().collect();
+        encoder.encode_object(&object).unwrap();
+
+        let writer = ChunkWriter::default();
+        let mut c2_extra = Vec::new();
+        writer
+            .write_chunk(&mut c2_extra, Chunk::new(3, 0, MessageType::CommandAMF0, 0, connect.into()))
+            .unwrap();
+        handshake(&mut client, &c2_extra).await;
+
+        let mut reader = ChunkReader::default();
+        let mut buf = BytesMut::new();
+
+        // Respond to the first ping request
+        let timestamp = read_ping_request(&mut client, &mut reader, &mut buf).await;
+        let mut response = vec![0x00, 0x07];
+        response.extend_from_slice(&timestamp);
+        let mut chunk = Vec::new();
+        writer
+            .write_chunk(
+                &mut chunk,
+                Chunk::new(2, 0, MessageType::UserControlEvent, 0, response.into()),
+            )
+            .unwrap();
+        client.write_all(&chunk).await.unwrap();
+
+        // Ignore the second ping request
+        read_ping_request(&mut client, &mut reader, &mut buf).await;
+
+        let err = session.await.unwrap().unwrap_err();
+        assert!(matches!(err, RtmpError::Session(ServerSessionError::PingTimeout)));
+        assert!(!err.is_client_closed());
+        assert_eq!(handler.rtts.lock().unwrap().len(), 1);
+    }
+}
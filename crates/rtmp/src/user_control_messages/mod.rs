@@ -3,6 +3,7 @@
 //! Defined by:
 //! - Legacy RTMP spec, 6.2
 
+pub mod reader;
 pub mod writer;
 
 nutype_enum::nutype_enum! {
@@ -72,3 +73,23 @@ pub struct EventMessageStreamBegin {
     /// The stream ID of the stream that became functional.
     pub stream_id: u32,
 }
+
+/// > The server sends this event to test whether the
+/// > client is reachable. Event data is a 4-byte
+/// > timestamp, representing the local server time
+/// > when the server dispatched the command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventMessagePingRequest {
+    /// The local time of the sender when the request was sent.
+    pub timestamp: u32,
+}
+
+/// > The client sends this event to the server in
+/// > response to the ping request. The event data is
+/// > a 4-byte timestamp, which was received with the
+/// > PingRequest request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventMessagePingResponse {
+    /// The timestamp of the ping request this is a response to.
+    pub timestamp: u32,
+}
@@ -0,0 +1,34 @@
+//! Reading user control messages.
+
+use std::io::{self, Cursor};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use super::EventMessagePingResponse;
+
+impl EventMessagePingResponse {
+    /// Reads a [`EventMessagePingResponse`] from the given event data.
+    ///
+    /// The event data is the payload of the user control message without the event type.
+    pub fn read(data: &[u8]) -> io::Result<Self> {
+        let mut cursor = Cursor::new(data);
+        let timestamp = cursor.read_u32::<BigEndian>()?;
+
+        Ok(Self { timestamp })
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_ping_response() {
+        let data = vec![0x01, 0x02, 0x03, 0x04];
+        let ping_response = EventMessagePingResponse::read(&data).unwrap();
+        assert_eq!(ping_response.timestamp, 0x01020304);
+
+        assert!(EventMessagePingResponse::read(&[0x01]).is_err());
+    }
+}
@@ -4,7 +4,7 @@ use std::io;
 
 use byteorder::{BigEndian, WriteBytesExt};
 
-use super::{EventMessageStreamBegin, EventType};
+use super::{EventMessagePingRequest, EventMessageStreamBegin, EventType};
 use crate::chunk::Chunk;
 use crate::chunk::writer::ChunkWriter;
 use crate::messages::MessageType;
@@ -23,6 +23,20 @@ impl EventMessageStreamBegin {
     }
 }
 
+impl EventMessagePingRequest {
+    /// Writes the [`EventMessagePingRequest`] to the given writer.
+    pub fn write(&self, writer: &ChunkWriter, io: &mut impl io::Write) -> io::Result<()> {
+        let mut data = Vec::new();
+
+        data.write_u16::<BigEndian>(EventType::PingRequest.0).expect("write u16");
+        data.write_u32::<BigEndian>(self.timestamp).expect("write u32");
+
+        writer.write_chunk(io, Chunk::new(0x02, 0, MessageType::UserControlEvent, 0, data.into()))?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 #[cfg_attr(all(test, coverage_nightly), coverage(off))]
 mod tests {
@@ -30,7 +44,7 @@ mod tests {
 
     use crate::chunk::reader::ChunkReader;
     use crate::chunk::writer::ChunkWriter;
-    use crate::user_control_messages::EventMessageStreamBegin;
+    use crate::user_control_messages::{EventMessagePingRequest, EventMessageStreamBegin};
 
     #[test]
     fn test_write_stream_begin() {
@@ -49,4 +63,22 @@ mod tests {
         assert_eq!(chunk.message_header.msg_stream_id, 0);
         assert_eq!(chunk.payload, Bytes::from(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x01]));
     }
+
+    #[test]
+    fn test_write_ping_request() {
+        let mut buf = BytesMut::new();
+        let writer = ChunkWriter::default();
+
+        EventMessagePingRequest { timestamp: 0x01020304 }
+            .write(&writer, &mut (&mut buf).writer())
+            .unwrap();
+
+        let mut reader = ChunkReader::default();
+
+        let chunk = reader.read_chunk(&mut buf).expect("read chunk").expect("chunk");
+        assert_eq!(chunk.basic_header.chunk_stream_id, 0x02);
+        assert_eq!(chunk.message_header.msg_type_id.0, 0x04);
+        assert_eq!(chunk.message_header.msg_stream_id, 0);
+        assert_eq!(chunk.payload, Bytes::from(vec![0x00, 0x06, 0x01, 0x02, 0x03, 0x04]));
+    }
 }
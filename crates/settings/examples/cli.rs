@@ -10,6 +10,7 @@ struct Config {
 fn main() {
     let config = scuffle_settings::parse_settings::<Config>(scuffle_settings::Options {
         cli: Some(scuffle_settings::cli!()),
+        matches: None,
         default_config_file: Some("config"),
         env_prefix: Some("APP"),
     });
@@ -218,6 +218,32 @@ pub enum SettingsError {
     Clap(#[from] clap::Error),
 }
 
+/// Add the `--config` and `--override` arguments to the given command.
+///
+/// Used to merge the arguments into the command line of an application, the
+/// resulting matches can then be passed to [`parse_settings`] with
+/// [`Options::matches`].
+#[cfg(feature = "cli")]
+pub fn cli_args(command: clap::Command) -> clap::Command {
+    command
+        .arg(
+            clap::Arg::new("config")
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .help("Path to configuration file(s)")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            clap::Arg::new("overrides")
+                .long("override")
+                .short('o')
+                .alias("set")
+                .help("Provide an override for a configuration value, in the format KEY=VALUE")
+                .action(clap::ArgAction::Append),
+        )
+}
+
 /// Parse settings using the given options.
 ///
 /// Refer to the [`Options`] struct for more information on how to customize parsing.
@@ -228,39 +254,31 @@ pub fn parse_settings<T: serde::de::DeserializeOwned>(options: Options) -> Resul
     let mut added_files = false;
 
     #[cfg(feature = "cli")]
-    if let Some(cli) = options.cli {
-        let command = clap::Command::new(cli.name)
-            .version(cli.version)
-            .about(cli.about)
-            .author(cli.author)
-            .bin_name(cli.name)
-            .arg(
-                clap::Arg::new("config")
-                    .short('c')
-                    .long("config")
-                    .value_name("FILE")
-                    .help("Path to configuration file(s)")
-                    .action(clap::ArgAction::Append),
+    let matches = match (options.matches, options.cli) {
+        (Some(matches), _) => Some(matches),
+        (None, Some(cli)) => Some(
+            cli_args(
+                clap::Command::new(cli.name)
+                    .version(cli.version)
+                    .about(cli.about)
+                    .author(cli.author)
+                    .bin_name(cli.name),
             )
-            .arg(
-                clap::Arg::new("overrides")
-                    .long("override")
-                    .short('o')
-                    .alias("set")
-                    .help("Provide an override for a configuration value, in the format KEY=VALUE")
-                    .action(clap::ArgAction::Append),
-            );
-
-        let matches = command.get_matches_from(cli.argv);
+            .get_matches_from(cli.argv),
+        ),
+        (None, None) => None,
+    };
 
-        if let Some(config_files) = matches.get_many::<String>("config") {
+    #[cfg(feature = "cli")]
+    if let Some(matches) = matches {
+        if let Some(config_files) = matches.try_get_many::<String>("config").ok().flatten() {
             for path in config_files {
                 config = config.add_source(config::File::new(path, FormatWrapper));
                 added_files = true;
             }
         }
 
-        if let Some(overrides) = matches.get_many::<String>("overrides") {
+        if let Some(overrides) = matches.try_get_many::<String>("overrides").ok().flatten() {
             for ov in overrides {
                 let (key, value) = ov.split_once('=').ok_or_else(|| {
                     clap::Error::raw(
@@ -290,7 +308,7 @@ pub fn parse_settings<T: serde::de::DeserializeOwned>(options: Options) -> Resul
 #[doc(hidden)]
 #[cfg(feature = "bootstrap")]
 pub mod macros {
-    pub use {anyhow, scuffle_bootstrap};
+    pub use {anyhow, clap, scuffle_bootstrap};
 }
 
 /// This macro can be used to integrate with the [`scuffle_bootstrap`] ecosystem.
@@ -298,6 +316,10 @@ pub mod macros {
 /// This macro will implement the [`scuffle_bootstrap::config::ConfigParser`] trait for the given type.
 /// The generated implementation uses the [`parse_settings`] function to parse the settings.
 ///
+/// The `--config` and `--override` arguments are merged into the command line
+/// parsed by [`scuffle_bootstrap::main!`], so services can add their own
+/// arguments and subcommands next to them.
+///
 /// ## Example
 ///
 /// ```rust
@@ -315,11 +337,16 @@ macro_rules! bootstrap {
                 $crate::macros::anyhow::Context::context(
                     $crate::parse_settings($crate::Options {
                         cli: Some($crate::cli!()),
+                        matches: $crate::macros::scuffle_bootstrap::cli::matches().cloned(),
                         ..::std::default::Default::default()
                     }),
                     "config",
                 )
             }
+
+            fn cli(command: $crate::macros::clap::Command) -> $crate::macros::clap::Command {
+                $crate::cli_args(command)
+            }
         }
     };
 }
@@ -489,6 +516,27 @@ mod tests {
         assert_eq!(settings.key, "value");
     }
 
+    #[test]
+    #[cfg(feature = "cli")]
+    fn parse_matches() {
+        let matches = crate::cli_args(clap::Command::new("test").subcommand(clap::Command::new("migrate")))
+            .get_matches_from(["test", "-o", "key=value", "migrate"]);
+        let options = Options {
+            cli: Some(Cli {
+                name: "test",
+                version: "0.1.0",
+                about: "test",
+                author: "test",
+                argv: vec!["test".to_string(), "-o".to_string(), "key=other".to_string()],
+            }),
+            matches: Some(matches),
+            ..Default::default()
+        };
+        let settings: TestSettings = parse_settings(options).expect("failed to parse settings");
+
+        assert_eq!(settings.key, "value");
+    }
+
     #[test]
     #[cfg(all(feature = "templates", feature = "cli"))]
     fn templates() {
@@ -4,6 +4,12 @@ pub struct Options {
     /// The CLI options
     #[cfg(feature = "cli")]
     pub cli: Option<Cli>,
+    /// Already parsed command line arguments, used instead of [`Options::cli`]
+    ///
+    /// The arguments must have been parsed by a command containing the
+    /// arguments added by [`cli_args`](crate::cli_args).
+    #[cfg(feature = "cli")]
+    pub matches: Option<clap::ArgMatches>,
     /// The default config file name (loaded if no other files are specified)
     pub default_config_file: Option<&'static str>,
     /// Environment variables prefix
@@ -17,6 +23,8 @@ impl Default for Options {
         Self {
             #[cfg(feature = "cli")]
             cli: None,
+            #[cfg(feature = "cli")]
+            matches: None,
             default_config_file: Some("config"),
             env_prefix: Some("APP"),
         }
@@ -1,3 +1,5 @@
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use scuffle_bootstrap::global::Global;
@@ -17,6 +19,52 @@ pub trait SignalConfig: Global {
         vec![crate::SignalKind::Terminate, crate::SignalKind::Interrupt]
     }
 
+    /// The signals which invoke the [`reload_handler`](SignalConfig::reload_handler).
+    ///
+    /// By default, listens for `SIGHUP` on Unix and for no signals on Windows.
+    /// Signals which are also returned by [`signals`](SignalConfig::signals)
+    /// shut down the process instead.
+    fn reload_signals(&self) -> Vec<crate::SignalKind> {
+        #[cfg(unix)]
+        {
+            vec![crate::SignalKind::Unix(crate::UnixSignalKind::hangup())]
+        }
+        #[cfg(not(unix))]
+        {
+            vec![]
+        }
+    }
+
+    /// The callbacks to invoke when a reload signal is received.
+    ///
+    /// Signals are still handled while the callbacks run: a shutdown signal cancels the running
+    /// reload, and reload signals received in the meantime start one more reload afterwards.
+    ///
+    /// By default, reload signals are not listened for.
+    fn reload_handler(&self) -> Option<crate::ReloadHandler> {
+        None
+    }
+
+    /// Called after the reload callbacks have run.
+    ///
+    /// By default, errors are ignored and the process keeps running. Returning
+    /// an error stops the signal service with that error.
+    fn on_reload(
+        self: &Arc<Self>,
+        result: Result<(), crate::ReloadError>,
+    ) -> impl std::future::Future<Output = anyhow::Result<()>> + Send {
+        let _ = result;
+        std::future::ready(Ok(()))
+    }
+
+    /// The window in which repeated signals of the same kind are coalesced.
+    ///
+    /// By default, signals are not debounced.
+    /// See [`SignalHandler::with_debounce`](crate::SignalHandler::with_debounce) for details.
+    fn debounce(&self) -> Option<std::time::Duration> {
+        None
+    }
+
     /// The timeout before forcing a shutdown.
     fn timeout(&self) -> Option<std::time::Duration> {
         Some(std::time::Duration::from_secs(30))
@@ -60,15 +108,66 @@ impl<Global: SignalConfig> Service<Global> for SignalSvc {
         let signals = global.signals();
         anyhow::ensure!(!signals.is_empty(), "no signals to listen for");
 
-        let mut handler = crate::SignalHandler::with_signals(signals);
+        let reload_handler = global.reload_handler();
+        let reload_signals = if reload_handler.is_some() {
+            global.reload_signals()
+        } else {
+            Vec::new()
+        };
+
+        let mut handler = crate::SignalHandler::with_signals(signals.iter().chain(&reload_signals).copied());
+        handler.set_debounce(global.debounce());
+
+        // The reload callbacks run while we keep receiving signals, so a shutdown signal is handled
+        // even if a callback hangs. The running reload is cancelled when shutting down.
+        let mut reload: Option<Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>>> = None;
+        // Reload signals received during a reload start another reload once it is done.
+        let mut reload_pending = false;
+
+        // Wait for a shutdown signal, or for the context to be done.
+        loop {
+            tokio::select! {
+                signal = handler.recv().with_context(&ctx) => {
+                    match signal {
+                        Some(signal) if !signals.contains(&signal) => reload_pending = true,
+                        _ => break,
+                    }
+                }
+                Some(result) = async {
+                    match reload.as_mut() {
+                        Some(reload) => Some(reload.await),
+                        None => None,
+                    }
+                } => {
+                    reload = None;
+                    result?;
+                }
+            }
+
+            if let (Some(reload_handler), true) = (&reload_handler, reload.is_none() && reload_pending) {
+                reload_pending = false;
+                let global = &global;
+                reload = Some(Box::pin(async move { global.on_reload(reload_handler.reload().await).await }));
+            }
+        }
+
+        drop(reload);
 
-        // Wait for a signal, or for the context to be done.
-        handler.recv().with_context(&ctx).await;
         global.on_shutdown().await?;
         drop(ctx);
 
+        // Reload signals are ignored while shutting down.
+        let recv_shutdown = async {
+            loop {
+                let signal = handler.recv().await;
+                if signals.contains(&signal) {
+                    break signal;
+                }
+            }
+        };
+
         tokio::select! {
-            signal = handler.recv() => {
+            signal = recv_shutdown => {
                 global.on_force_shutdown(Some(signal)).await?;
             },
             _ = global.block_global_shutdown() => {}
@@ -97,7 +196,7 @@ mod test {
     use scuffle_future_ext::FutureExt;
 
     use super::SignalConfig;
-    use crate::tests::raise_signal;
+    use crate::testing::raise;
     use crate::{SignalKind, SignalSvc};
 
     async fn force_shutdown_two_signals<Global: GlobalWithoutConfig + SignalConfig>() {
@@ -114,9 +213,9 @@ mod test {
         // Wait for the service to start
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-        raise_signal(SignalKind::Interrupt).await;
+        raise(SignalKind::Interrupt).expect("failed to raise signal");
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-        raise_signal(SignalKind::Interrupt).await;
+        raise(SignalKind::Interrupt).expect("failed to raise signal");
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
         match result.with_timeout(tokio::time::Duration::from_millis(1000)).await {
@@ -188,7 +287,7 @@ mod test {
         println!("waiting for service to start");
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
-        raise_signal(SignalKind::Interrupt).await;
+        raise(SignalKind::Interrupt).expect("failed to raise signal");
         // no timeout so it should block indefinitely
         assert!(
             (&mut result)
@@ -293,7 +392,7 @@ mod test {
         // Wait for the service to start
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-        raise_signal(crate::SignalKind::Interrupt).await;
+        raise(crate::SignalKind::Interrupt).expect("failed to raise signal");
 
         match result.with_timeout(tokio::time::Duration::from_millis(1000)).await {
             Ok(Ok(Err(e))) => {
@@ -310,4 +409,154 @@ mod test {
                 .is_ok()
         );
     }
+
+    struct ReloadTestGlobal {
+        reload_handler: crate::ReloadHandler,
+        reloads: tokio::sync::mpsc::UnboundedSender<Result<(), String>>,
+    }
+
+    impl SignalConfig for ReloadTestGlobal {
+        #[cfg(unix)]
+        fn reload_signals(&self) -> Vec<crate::SignalKind> {
+            // SIGHUP is used by other tests in this process.
+            vec![SignalKind::Unix(crate::UnixSignalKind::alarm())]
+        }
+
+        fn reload_handler(&self) -> Option<crate::ReloadHandler> {
+            Some(self.reload_handler.clone())
+        }
+
+        async fn on_reload(self: &Arc<Self>, result: Result<(), crate::ReloadError>) -> anyhow::Result<()> {
+            self.reloads.send(result.map_err(|err| err.to_string())).unwrap();
+            Ok(())
+        }
+
+        fn timeout(&self) -> Option<std::time::Duration> {
+            None
+        }
+
+        async fn block_global_shutdown(&self) {}
+    }
+
+    impl GlobalWithoutConfig for ReloadTestGlobal {
+        fn init() -> impl std::future::Future<Output = anyhow::Result<Arc<Self>>> + Send {
+            std::future::ready(Err(anyhow::anyhow!("constructed by the test")))
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(all(not(valgrind), unix))] // test is time-sensitive
+    async fn bootstrap_service_reload() {
+        let (ctx, handler) = scuffle_context::Context::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let reload_handler = crate::ReloadHandler::new();
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        reload_handler.register("config", {
+            let calls = calls.clone();
+            move || {
+                let call = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                std::future::ready(if call == 0 {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!("invalid config"))
+                })
+            }
+        });
+
+        let global = Arc::new(ReloadTestGlobal {
+            reload_handler,
+            reloads: tx,
+        });
+        let mut result = tokio::spawn(SignalSvc.run(global, ctx));
+
+        // Wait for the service to start
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        raise(SignalKind::Unix(crate::UnixSignalKind::alarm())).expect("failed to raise signal");
+        let reload = rx.recv().with_timeout(tokio::time::Duration::from_millis(500)).await.unwrap();
+        assert_eq!(reload, Some(Ok(())));
+
+        raise(SignalKind::Unix(crate::UnixSignalKind::alarm())).expect("failed to raise signal");
+        let reload = rx.recv().with_timeout(tokio::time::Duration::from_millis(500)).await.unwrap();
+        assert_eq!(
+            reload,
+            Some(Err("1 reload callback(s) failed: config: invalid config".to_owned()))
+        );
+
+        // A failed reload does not stop the service.
+        assert!(
+            (&mut result)
+                .with_timeout(tokio::time::Duration::from_millis(100))
+                .await
+                .is_err()
+        );
+
+        raise(SignalKind::Interrupt).expect("failed to raise signal");
+
+        assert!(matches!(
+            result.with_timeout(tokio::time::Duration::from_millis(500)).await,
+            Ok(Ok(Ok(())))
+        ));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        assert!(
+            handler
+                .shutdown()
+                .with_timeout(tokio::time::Duration::from_millis(1000))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(all(not(valgrind), unix))] // test is time-sensitive
+    async fn bootstrap_service_shutdown_during_reload() {
+        let (ctx, handler) = scuffle_context::Context::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let reload_handler = crate::ReloadHandler::new();
+        let started = Arc::new(tokio::sync::Notify::new());
+        reload_handler.register("hangs", {
+            let started = started.clone();
+            move || {
+                started.notify_one();
+                std::future::pending()
+            }
+        });
+
+        let global = Arc::new(ReloadTestGlobal {
+            reload_handler,
+            reloads: tx,
+        });
+        let result = tokio::spawn(SignalSvc.run(global, ctx));
+
+        // Wait for the service to start
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        raise(SignalKind::Unix(crate::UnixSignalKind::alarm())).expect("failed to raise signal");
+        started
+            .notified()
+            .with_timeout(tokio::time::Duration::from_millis(500))
+            .await
+            .unwrap();
+
+        // The hanging reload does not block the shutdown signal.
+        raise(SignalKind::Interrupt).expect("failed to raise signal");
+
+        assert!(matches!(
+            result.with_timeout(tokio::time::Duration::from_millis(500)).await,
+            Ok(Ok(Ok(())))
+        ));
+        // The reload was cancelled.
+        assert!(rx.recv().await.is_none());
+
+        assert!(
+            handler
+                .shutdown()
+                .with_timeout(tokio::time::Duration::from_millis(1000))
+                .await
+                .is_ok()
+        );
+    }
 }
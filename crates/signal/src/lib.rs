@@ -61,6 +61,7 @@
 
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 #[cfg(unix)]
 use tokio::signal::unix;
@@ -69,9 +70,15 @@ pub use tokio::signal::unix::SignalKind as UnixSignalKind;
 
 #[cfg(feature = "bootstrap")]
 mod bootstrap;
+#[cfg(feature = "bootstrap")]
+mod reload;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 #[cfg(feature = "bootstrap")]
 pub use bootstrap::{SignalConfig, SignalSvc};
+#[cfg(feature = "bootstrap")]
+pub use reload::{ReloadError, ReloadHandler};
 
 /// The type of signal to listen for.
 #[derive(Debug, Clone, Copy, Eq)]
@@ -189,44 +196,47 @@ enum WindowsSignalValue {
     CtrlClose(tokio::signal::windows::CtrlClose),
     CtrlLogoff(tokio::signal::windows::CtrlLogoff),
     CtrlShutdown(tokio::signal::windows::CtrlShutdown),
-    #[cfg(test)]
-    Mock(SignalKind, Pin<Box<tokio_stream::wrappers::BroadcastStream<SignalKind>>>),
 }
 
 #[cfg(windows)]
 impl WindowsSignalValue {
     fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<()>> {
-        #[cfg(test)]
-        use futures::Stream;
-
         match self {
             Self::CtrlBreak(signal) => signal.poll_recv(cx),
             Self::CtrlC(signal) => signal.poll_recv(cx),
             Self::CtrlClose(signal) => signal.poll_recv(cx),
             Self::CtrlLogoff(signal) => signal.poll_recv(cx),
             Self::CtrlShutdown(signal) => signal.poll_recv(cx),
-            #[cfg(test)]
-            Self::Mock(kind, receiver) => match receiver.as_mut().poll_next(cx) {
-                Poll::Ready(Some(Ok(recv))) if recv == *kind => Poll::Ready(Some(())),
-                Poll::Ready(Some(Ok(_))) => {
-                    cx.waker().wake_by_ref();
-                    Poll::Pending
-                }
-                Poll::Ready(v) => unreachable!("receiver should always have a value: {:?}", v),
-                Poll::Pending => {
-                    cx.waker().wake_by_ref();
-                    Poll::Pending
-                }
-            },
         }
     }
 }
 
+/// A Windows signal, which also receives the signals raised with [`testing::raise`].
+#[cfg(windows)]
+#[derive(Debug)]
+struct WindowsSignal {
+    value: WindowsSignalValue,
+    #[cfg(any(test, feature = "testing"))]
+    mock: testing::MockSignal,
+}
+
+#[cfg(windows)]
+impl WindowsSignal {
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        #[cfg(any(test, feature = "testing"))]
+        if self.mock.poll_recv(cx).is_ready() {
+            return Poll::Ready(Some(()));
+        }
+
+        self.value.poll_recv(cx)
+    }
+}
+
 #[cfg(unix)]
 type Signal = unix::Signal;
 
 #[cfg(windows)]
-type Signal = WindowsSignalValue;
+type Signal = WindowsSignal;
 
 impl SignalKind {
     #[cfg(unix)]
@@ -240,14 +250,15 @@ impl SignalKind {
 
     #[cfg(windows)]
     fn listen(&self) -> Result<Signal, std::io::Error> {
-        #[cfg(test)]
-        if cfg!(test) {
-            return Ok(WindowsSignalValue::Mock(
-                *self,
-                Box::pin(tokio_stream::wrappers::BroadcastStream::new(tests::SignalMocker::subscribe())),
-            ));
-        }
+        Ok(WindowsSignal {
+            value: self.listen_windows()?,
+            #[cfg(any(test, feature = "testing"))]
+            mock: testing::MockSignal::new(*self),
+        })
+    }
 
+    #[cfg(windows)]
+    fn listen_windows(&self) -> Result<WindowsSignalValue, std::io::Error> {
         match self {
             // https://learn.microsoft.com/en-us/windows/console/ctrl-c-and-ctrl-break-signals
             Self::Interrupt | Self::Windows(WindowsSignalKind::CtrlC) => {
@@ -317,10 +328,28 @@ impl SignalKind {
 /// # });
 /// # }
 /// ```
+///
+/// # Debouncing
+///
+/// Some tools send the same signal multiple times in quick succession (e.g.
+/// log rotation scripts sending repeated `SIGHUP`s). Use
+/// [`SignalHandler::with_debounce`] to coalesce such bursts: after a signal
+/// is returned, further signals of the same kind received within the window
+/// are counted but not returned. The number of times each signal has been
+/// received is available via [`SignalHandler::count`].
 #[derive(Debug)]
 #[must_use = "signal handlers must be used to wait for signals"]
 pub struct SignalHandler {
-    signals: Vec<(SignalKind, Signal)>,
+    signals: Vec<SignalEntry>,
+    debounce: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct SignalEntry {
+    kind: SignalKind,
+    signal: Signal,
+    count: u64,
+    last_delivered: Option<Instant>,
 }
 
 impl Default for SignalHandler {
@@ -332,7 +361,10 @@ impl Default for SignalHandler {
 impl SignalHandler {
     /// Create a new `SignalHandler` with no signals.
     pub const fn new() -> Self {
-        Self { signals: Vec::new() }
+        Self {
+            signals: Vec::new(),
+            debounce: None,
+        }
     }
 
     /// Create a new `SignalHandler` with the given signals.
@@ -359,17 +391,52 @@ impl SignalHandler {
     /// If the signal is already in the handler, it will not be added again.
     pub fn add_signal(&mut self, kind: impl Into<SignalKind>) -> &mut Self {
         let kind = kind.into();
-        if self.signals.iter().any(|(k, _)| k == &kind) {
+        if self.signals.iter().any(|entry| entry.kind == kind) {
             return self;
         }
 
         let signal = kind.listen().expect("failed to create signal");
 
-        self.signals.push((kind, signal));
+        self.signals.push(SignalEntry {
+            kind,
+            signal,
+            count: 0,
+            last_delivered: None,
+        });
+
+        self
+    }
+
+    /// Coalesce bursts of the same signal within the given window.
+    ///
+    /// See [Debouncing](SignalHandler#debouncing) for details.
+    pub fn with_debounce(mut self, window: Duration) -> Self {
+        self.set_debounce(Some(window));
+        self
+    }
 
+    /// Set or clear the debounce window.
+    ///
+    /// See [Debouncing](SignalHandler#debouncing) for details.
+    pub fn set_debounce(&mut self, window: Option<Duration>) -> &mut Self {
+        self.debounce = window;
         self
     }
 
+    /// The number of times the given signal has been received, including
+    /// signals which were coalesced by the debounce window.
+    ///
+    /// Returns `None` if the signal is not registered with this handler.
+    pub fn count(&self, kind: impl Into<SignalKind>) -> Option<u64> {
+        let kind = kind.into();
+        self.signals.iter().find(|entry| entry.kind == kind).map(|entry| entry.count)
+    }
+
+    /// The number of times each registered signal has been received.
+    pub fn counts(&self) -> impl Iterator<Item = (SignalKind, u64)> + '_ {
+        self.signals.iter().map(|entry| (entry.kind, entry.count))
+    }
+
     /// Wait for a signal to be received.
     /// This is equivilant to calling (&mut handler).await, but is more
     /// ergonomic if you want to not take ownership of the handler.
@@ -380,9 +447,29 @@ impl SignalHandler {
     /// Poll for a signal to be received.
     /// Does not require pinning the handler.
     pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<SignalKind> {
-        for (kind, signal) in self.signals.iter_mut() {
-            if signal.poll_recv(cx).is_ready() {
-                return Poll::Ready(*kind);
+        for entry in self.signals.iter_mut() {
+            loop {
+                match entry.signal.poll_recv(cx) {
+                    Poll::Ready(Some(())) => {
+                        entry.count += 1;
+
+                        let now = Instant::now();
+                        let debounced = self
+                            .debounce
+                            .zip(entry.last_delivered)
+                            .is_some_and(|(window, last)| now.duration_since(last) < window);
+
+                        // Keep polling so that the waker stays registered.
+                        if debounced {
+                            continue;
+                        }
+
+                        entry.last_delivered = Some(now);
+                        return Poll::Ready(entry.kind);
+                    }
+                    Poll::Ready(None) => return Poll::Ready(entry.kind),
+                    Poll::Pending => break,
+                }
             }
         }
 
@@ -410,52 +497,9 @@ mod tests {
 
     use scuffle_future_ext::FutureExt;
 
+    use crate::testing::raise;
     use crate::{SignalHandler, SignalKind};
 
-    #[cfg(windows)]
-    pub(crate) struct SignalMocker(tokio::sync::broadcast::Sender<SignalKind>);
-
-    #[cfg(windows)]
-    impl SignalMocker {
-        fn new() -> Self {
-            println!("new");
-            let (sender, _) = tokio::sync::broadcast::channel(100);
-            Self(sender)
-        }
-
-        fn raise(kind: SignalKind) {
-            println!("raising");
-            SIGNAL_MOCKER.with(|local| local.0.send(kind).unwrap());
-        }
-
-        pub(crate) fn subscribe() -> tokio::sync::broadcast::Receiver<SignalKind> {
-            println!("subscribing");
-            SIGNAL_MOCKER.with(|local| local.0.subscribe())
-        }
-    }
-
-    #[cfg(windows)]
-    thread_local! {
-        static SIGNAL_MOCKER: SignalMocker = SignalMocker::new();
-    }
-
-    #[cfg(windows)]
-    pub(crate) async fn raise_signal(kind: SignalKind) {
-        SignalMocker::raise(kind);
-    }
-
-    #[cfg(unix)]
-    pub(crate) async fn raise_signal(kind: SignalKind) {
-        // Safety: This is a test, and we control the process.
-        unsafe {
-            libc::raise(match kind {
-                SignalKind::Interrupt => libc::SIGINT,
-                SignalKind::Terminate => libc::SIGTERM,
-                SignalKind::Unix(kind) => kind.as_raw_value(),
-            });
-        }
-    }
-
     #[cfg(windows)]
     #[tokio::test]
     async fn signal_handler() {
@@ -465,7 +509,7 @@ mod tests {
 
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
-        raise_signal(SignalKind::Windows(WindowsSignalKind::CtrlC)).await;
+        raise(SignalKind::Windows(WindowsSignalKind::CtrlC)).expect("failed to raise signal");
 
         let recv = (&mut handler).with_timeout(Duration::from_millis(500)).await.unwrap();
 
@@ -474,7 +518,7 @@ mod tests {
         let recv = (&mut handler).with_timeout(Duration::from_millis(500)).await;
         assert!(recv.is_err(), "expected timeout");
 
-        raise_signal(SignalKind::Windows(WindowsSignalKind::CtrlBreak)).await;
+        raise(SignalKind::Windows(WindowsSignalKind::CtrlBreak)).expect("failed to raise signal");
 
         let recv = (&mut handler).with_timeout(Duration::from_millis(500)).await.unwrap();
 
@@ -495,13 +539,13 @@ mod tests {
 
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
-        raise_signal(SignalKind::Windows(WindowsSignalKind::CtrlC)).await;
+        raise(SignalKind::Windows(WindowsSignalKind::CtrlC)).expect("failed to raise signal");
 
         let recv = handler.recv().with_timeout(Duration::from_millis(500)).await.unwrap();
 
         assert_eq!(recv, WindowsSignalKind::CtrlC, "expected CtrlC");
 
-        raise_signal(SignalKind::Windows(WindowsSignalKind::CtrlBreak)).await;
+        raise(SignalKind::Windows(WindowsSignalKind::CtrlBreak)).expect("failed to raise signal");
 
         let recv = handler.recv().with_timeout(Duration::from_millis(500)).await.unwrap();
 
@@ -517,7 +561,7 @@ mod tests {
             .with_signal(UnixSignalKind::user_defined2())
             .with_signal(UnixSignalKind::user_defined1());
 
-        raise_signal(SignalKind::Unix(UnixSignalKind::user_defined1())).await;
+        raise(SignalKind::Unix(UnixSignalKind::user_defined1())).expect("failed to raise signal");
 
         let recv = (&mut handler).with_timeout(Duration::from_millis(500)).await.unwrap();
 
@@ -528,7 +572,7 @@ mod tests {
 
         assert!(recv.is_err(), "expected timeout");
 
-        raise_signal(SignalKind::Unix(UnixSignalKind::user_defined2())).await;
+        raise(SignalKind::Unix(UnixSignalKind::user_defined2())).expect("failed to raise signal");
 
         // We should be able to receive the signal again
         let recv = (&mut handler).with_timeout(Duration::from_millis(500)).await.unwrap();
@@ -548,19 +592,68 @@ mod tests {
             .add_signal(UnixSignalKind::user_defined2())
             .add_signal(UnixSignalKind::user_defined2());
 
-        raise_signal(SignalKind::Unix(UnixSignalKind::user_defined1())).await;
+        raise(SignalKind::Unix(UnixSignalKind::user_defined1())).expect("failed to raise signal");
 
         let recv = handler.recv().with_timeout(Duration::from_millis(500)).await.unwrap();
 
         assert_eq!(recv, UnixSignalKind::user_defined1(), "expected SIGUSR1");
 
-        raise_signal(SignalKind::Unix(UnixSignalKind::user_defined2())).await;
+        raise(SignalKind::Unix(UnixSignalKind::user_defined2())).expect("failed to raise signal");
 
         let recv = handler.recv().with_timeout(Duration::from_millis(500)).await.unwrap();
 
         assert_eq!(recv, UnixSignalKind::user_defined2(), "expected SIGUSR2");
     }
 
+    #[cfg(all(not(valgrind), unix))] // test is time-sensitive
+    #[tokio::test]
+    async fn debounce() {
+        use crate::UnixSignalKind;
+
+        let mut handler = SignalHandler::new()
+            .with_signal(UnixSignalKind::hangup())
+            .with_signal(UnixSignalKind::window_change())
+            .with_debounce(Duration::from_millis(500));
+
+        assert_eq!(handler.count(UnixSignalKind::hangup()), Some(0));
+        assert_eq!(handler.count(UnixSignalKind::user_defined1()), None);
+
+        raise(SignalKind::Unix(UnixSignalKind::hangup())).expect("failed to raise signal");
+
+        let recv = handler.recv().with_timeout(Duration::from_millis(100)).await.unwrap();
+        assert_eq!(recv, UnixSignalKind::hangup(), "expected SIGHUP");
+        assert_eq!(handler.count(UnixSignalKind::hangup()), Some(1));
+
+        // A repeated signal within the window is counted but not returned.
+        raise(SignalKind::Unix(UnixSignalKind::hangup())).expect("failed to raise signal");
+
+        let recv = handler.recv().with_timeout(Duration::from_millis(100)).await;
+        assert!(recv.is_err(), "expected timeout");
+        assert_eq!(handler.count(UnixSignalKind::hangup()), Some(2));
+
+        // Other signals are not affected by the window.
+        raise(SignalKind::Unix(UnixSignalKind::window_change())).expect("failed to raise signal");
+
+        let recv = handler.recv().with_timeout(Duration::from_millis(100)).await.unwrap();
+        assert_eq!(recv, UnixSignalKind::window_change(), "expected SIGWINCH");
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // After the window has passed, the signal is returned again.
+        raise(SignalKind::Unix(UnixSignalKind::hangup())).expect("failed to raise signal");
+
+        let recv = handler.recv().with_timeout(Duration::from_millis(100)).await.unwrap();
+        assert_eq!(recv, UnixSignalKind::hangup(), "expected SIGHUP");
+
+        assert_eq!(
+            handler.counts().collect::<Vec<_>>(),
+            vec![
+                (SignalKind::Unix(UnixSignalKind::hangup()), 3),
+                (SignalKind::Unix(UnixSignalKind::window_change()), 1),
+            ]
+        );
+    }
+
     #[cfg(not(valgrind))] // test is time-sensitive
     #[tokio::test]
     async fn no_signals() {
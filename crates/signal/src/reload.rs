@@ -0,0 +1,247 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type ReloadFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+
+struct ReloadCallback {
+    name: String,
+    callback: Box<dyn Fn() -> ReloadFuture + Send + Sync>,
+}
+
+/// A registry of callbacks which are invoked when the configuration should be
+/// reloaded, typically on `SIGHUP`.
+///
+/// Components register their callbacks with [`register`](Self::register). The
+/// handler is cheap to clone and all clones share the same callbacks, so it can
+/// be stored in the global state and handed out to components.
+///
+/// When used with [`SignalSvc`](crate::SignalSvc), return the handler from
+/// [`SignalConfig::reload_handler`](crate::SignalConfig::reload_handler) and
+/// the callbacks are invoked whenever one of the
+/// [`reload signals`](crate::SignalConfig::reload_signals) is received.
+///
+/// ```rust
+/// # use scuffle_signal::ReloadHandler;
+/// # tokio_test::block_on(async {
+/// let handler = ReloadHandler::new().with_timeout(std::time::Duration::from_secs(5));
+///
+/// handler.register("tls", || async {
+///     // Reload the certificates here
+///     Ok(())
+/// });
+///
+/// handler.reload().await.unwrap();
+/// # });
+/// ```
+#[derive(Clone)]
+#[must_use = "reload handlers must be used to register callbacks"]
+pub struct ReloadHandler {
+    callbacks: Arc<Mutex<Vec<Arc<ReloadCallback>>>>,
+    timeout: Option<Duration>,
+}
+
+impl fmt::Debug for ReloadHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let callbacks = self.callbacks.lock().expect("poisoned");
+
+        f.debug_struct("ReloadHandler")
+            .field("callbacks", &callbacks.iter().map(|c| &c.name).collect::<Vec<_>>())
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Default for ReloadHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReloadHandler {
+    /// Create a new `ReloadHandler` without any callbacks.
+    ///
+    /// By default, every callback has 30 seconds to complete.
+    pub fn new() -> Self {
+        Self {
+            callbacks: Arc::default(),
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+
+    /// Set the time every callback has to complete.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.set_timeout(Some(timeout));
+        self
+    }
+
+    /// Set or clear the time every callback has to complete.
+    ///
+    /// Only affects this handler and clones created afterwards.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Register a callback.
+    ///
+    /// `name` identifies the callback in errors.
+    pub fn register<F, Fut>(&self, name: impl Into<String>, callback: F) -> &Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.callbacks.lock().expect("poisoned").push(Arc::new(ReloadCallback {
+            name: name.into(),
+            callback: Box::new(move || Box::pin(callback())),
+        }));
+
+        self
+    }
+
+    /// The number of registered callbacks.
+    pub fn len(&self) -> usize {
+        self.callbacks.lock().expect("poisoned").len()
+    }
+
+    /// Returns `true` if no callbacks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invoke all registered callbacks.
+    ///
+    /// The callbacks are invoked one after another in the order they were
+    /// registered. A failing callback does not stop the remaining callbacks,
+    /// the errors of all failed callbacks are returned together.
+    pub async fn reload(&self) -> Result<(), ReloadError> {
+        let callbacks = self.callbacks.lock().expect("poisoned").clone();
+        let mut errors = Vec::new();
+
+        for callback in callbacks {
+            let fut = (callback.callback)();
+            let result = match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, fut)
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out after {timeout:?}"))),
+                None => fut.await,
+            };
+
+            if let Err(err) = result {
+                errors.push((callback.name.clone(), err));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ReloadError { errors })
+        }
+    }
+}
+
+/// The error returned by [`ReloadHandler::reload`] when one or more callbacks
+/// failed.
+#[derive(Debug)]
+pub struct ReloadError {
+    errors: Vec<(String, anyhow::Error)>,
+}
+
+impl ReloadError {
+    /// The name and error of every failed callback, in the order they were
+    /// invoked.
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &anyhow::Error)> {
+        self.errors.iter().map(|(name, err)| (name.as_str(), err))
+    }
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} reload callback(s) failed", self.errors.len())?;
+
+        for (idx, (name, err)) in self.errors.iter().enumerate() {
+            let sep = if idx == 0 { ": " } else { ", " };
+            write!(f, "{sep}{name}: {err:#}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ReloadError {}
+
+#[cfg(test)]
+#[cfg_attr(all(coverage_nightly, test), coverage(off))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::ReloadHandler;
+
+    #[tokio::test]
+    async fn reload_in_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let handler = ReloadHandler::default();
+        assert!(handler.is_empty());
+
+        for name in ["a", "b", "c"] {
+            let calls = calls.clone();
+            handler.clone().register(name, move || {
+                let calls = calls.clone();
+                async move {
+                    calls.lock().unwrap().push(name);
+                    Ok(())
+                }
+            });
+        }
+
+        assert_eq!(handler.len(), 3);
+        handler.reload().await.unwrap();
+        handler.reload().await.unwrap();
+        assert_eq!(*calls.lock().unwrap(), ["a", "b", "c", "a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn reload_errors() {
+        let ran = Arc::new(Mutex::new(false));
+        let handler = ReloadHandler::new().with_timeout(Duration::from_millis(50));
+
+        handler
+            .register("fail", || async { anyhow::bail!("bad config") })
+            .register("slow", std::future::pending)
+            .register("ok", {
+                let ran = ran.clone();
+                move || {
+                    *ran.lock().unwrap() = true;
+                    std::future::ready(Ok(()))
+                }
+            });
+
+        let err = handler.reload().await.unwrap_err();
+        assert!(*ran.lock().unwrap(), "callbacks after a failure should still run");
+        assert_eq!(err.errors().map(|(name, _)| name).collect::<Vec<_>>(), ["fail", "slow"]);
+        assert_eq!(
+            err.to_string(),
+            "2 reload callback(s) failed: fail: bad config, slow: timed out after 50ms"
+        );
+        assert_eq!(
+            format!("{handler:?}"),
+            r#"ReloadHandler { callbacks: ["fail", "slow", "ok"], timeout: Some(50ms) }"#
+        );
+    }
+
+    #[tokio::test]
+    async fn reload_no_timeout() {
+        let mut handler = ReloadHandler::new();
+        handler.set_timeout(None);
+
+        handler.register("sleep", || async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(())
+        });
+
+        handler.reload().await.unwrap();
+    }
+}
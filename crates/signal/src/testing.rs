@@ -0,0 +1,92 @@
+//! Utilities for testing code which handles signals.
+//!
+//! [`raise`] delivers a signal to every [`SignalHandler`](crate::SignalHandler) of the current
+//! process which listens for it, so shutdown and reload paths can be tested deterministically on
+//! all platforms.
+//!
+//! ```rust
+//! use scuffle_signal::{SignalHandler, SignalKind};
+//!
+//! # tokio_test::block_on(async {
+//! let mut handler = SignalHandler::new().with_signal(SignalKind::Terminate);
+//!
+//! scuffle_signal::testing::raise(SignalKind::Terminate).expect("failed to raise signal");
+//!
+//! assert_eq!(handler.recv().await, SignalKind::Terminate);
+//! # });
+//! ```
+
+use crate::SignalKind;
+
+/// Raises the signal in the current process.
+///
+/// On Unix the signal is sent to the process with `raise(3)`. A handler for the signal has to
+/// be registered first, otherwise the default action of the signal is taken, which usually
+/// terminates the process.
+///
+/// Console control events cannot be raised on Windows, so the signal is delivered directly to
+/// the handlers listening for it instead. Handlers which are not registered yet miss the signal.
+pub fn raise(kind: impl Into<SignalKind>) -> std::io::Result<()> {
+    raise_signal(kind.into())
+}
+
+#[cfg(unix)]
+fn raise_signal(kind: SignalKind) -> std::io::Result<()> {
+    let signal = match kind {
+        SignalKind::Interrupt => libc::SIGINT,
+        SignalKind::Terminate => libc::SIGTERM,
+        SignalKind::Unix(kind) => kind.as_raw_value(),
+    };
+
+    // Safety: raise is safe to call with any signal number, invalid ones are reported as an error.
+    if unsafe { libc::raise(signal) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+static MOCK_SIGNALS: std::sync::LazyLock<tokio::sync::broadcast::Sender<SignalKind>> =
+    std::sync::LazyLock::new(|| tokio::sync::broadcast::channel(100).0);
+
+#[cfg(windows)]
+fn raise_signal(kind: SignalKind) -> std::io::Result<()> {
+    // Sending only fails if no handler is listening, in which case there is nobody to notify.
+    let _ = MOCK_SIGNALS.send(kind);
+    Ok(())
+}
+
+/// Receives the signals raised with [`raise`] on Windows.
+#[cfg(windows)]
+#[derive(Debug)]
+pub(crate) struct MockSignal {
+    kind: SignalKind,
+    receiver: std::pin::Pin<Box<tokio_stream::wrappers::BroadcastStream<SignalKind>>>,
+}
+
+#[cfg(windows)]
+impl MockSignal {
+    pub(crate) fn new(kind: SignalKind) -> Self {
+        Self {
+            kind,
+            receiver: Box::pin(tokio_stream::wrappers::BroadcastStream::new(MOCK_SIGNALS.subscribe())),
+        }
+    }
+
+    pub(crate) fn poll_recv(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<()> {
+        use std::task::Poll;
+
+        use tokio_stream::Stream;
+
+        loop {
+            match self.receiver.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(recv))) if recv == self.kind => return Poll::Ready(()),
+                // Other signals and lagged receivers are skipped.
+                Poll::Ready(Some(_)) => continue,
+                // The sender is never dropped.
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
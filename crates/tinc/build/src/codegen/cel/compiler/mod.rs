@@ -89,8 +89,8 @@ impl ToTokens for ConstantCompiledExpr {
                     let tag = tag.as_ref();
                     quote! {
                         ::tinc::__private::cel::CelValue::Enum(
-                            ::tinc::__private::cel::CelValue::CelEnum {
-                                tag: ::tinc::__private::cel::CelValue::CelString::Borrowed(#tag),
+                            ::tinc::__private::cel::CelEnum {
+                                tag: ::tinc::__private::cel::CelString::Borrowed(#tag),
                                 value: #value,
                             }
                         )
@@ -110,7 +110,7 @@ impl ToTokens for ConstantCompiledExpr {
                         .map(|(key, value)| (value_to_tokens(key), value_to_tokens(value)))
                         .map(|(key, value)| quote!((#key, #value)));
                     quote! {
-                        ::tinc::__private::cel::CelValue::List([
+                        ::tinc::__private::cel::CelValue::Map([
                             #(#map),*
                         ].into_iter().collect())
                     }
@@ -130,8 +130,10 @@ impl ToTokens for ConstantCompiledExpr {
                     quote!(::tinc::__private::cel::CelValue::String(::tinc::__private::cel::CelString::Borrowed(#s)))
                 }
                 CelValue::Duration(b) => {
-                    let secs = b.num_seconds();
-                    let nanos = b.subsec_nanos();
+                    // `Duration::new` takes non-negative nanoseconds, while `subsec_nanos` has the sign of the duration.
+                    let total_nanos = i128::from(b.num_seconds()) * 1_000_000_000 + i128::from(b.subsec_nanos());
+                    let secs = total_nanos.div_euclid(1_000_000_000) as i64;
+                    let nanos = total_nanos.rem_euclid(1_000_000_000) as u32;
                     quote! {
                         ::tinc::__private::cel::CelValue::Duration(
                             ::tinc::reexports::chrono::Duration::new(
@@ -186,7 +188,7 @@ pub(crate) struct Compiler<'a> {
     registry: &'a ProtoTypeRegistry,
     target: Option<CompilerTarget>,
     variables: BTreeMap<String, CompiledExpr>,
-    functions: BTreeMap<&'static str, DebugFunc>,
+    functions: BTreeMap<String, DebugFunc>,
 }
 
 #[derive(Clone)]
@@ -268,8 +270,7 @@ impl<'a> Compiler<'a> {
     }
 
     pub(crate) fn register_function(&mut self, f: impl Function) {
-        let name = f.name();
-        self.functions.insert(name, DebugFunc(Arc::new(f)));
+        self.functions.insert(f.name().to_owned(), DebugFunc(Arc::new(f)));
     }
 
     pub(crate) fn resolve(&self, expr: &cel_parser::Expression) -> Result<CompiledExpr, CompileError> {
@@ -299,6 +300,11 @@ impl<'a> Compiler<'a> {
     pub(crate) fn registry(&self) -> &'a ProtoTypeRegistry {
         self.registry
     }
+
+    /// The number of parents of this compiler.
+    pub(crate) fn depth(&self) -> usize {
+        self.parent.map_or(0, |parent| parent.depth() + 1)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
@@ -324,6 +330,12 @@ pub(crate) enum CompileError {
     VariableNotFound(String),
     #[error("function not found: {0}")]
     FunctionNotFound(String),
+    #[error("function {name} takes {expected} arguments but got {actual}")]
+    FunctionArity {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
     #[error("unsupported function call identifier type: {0:?}")]
     UnsupportedFunctionCallIdentifierType(cel_parser::Expression),
     #[error("missing message: {0}")]
@@ -347,3 +359,52 @@ impl From<CelError<'_>> for CompileError {
         }
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use quote::{ToTokens, quote};
+    use tinc_cel::{CelEnum, CelValue};
+
+    use super::ConstantCompiledExpr;
+
+    #[test]
+    fn test_constant_enum_tokens() {
+        let expr = ConstantCompiledExpr {
+            value: CelValue::Enum(CelEnum::new("my.Enum".into(), 1)),
+        };
+
+        assert_eq!(
+            expr.to_token_stream().to_string(),
+            quote! {
+                ::tinc::__private::cel::CelValue::Enum(
+                    ::tinc::__private::cel::CelEnum {
+                        tag: ::tinc::__private::cel::CelString::Borrowed("my.Enum"),
+                        value: 1i32,
+                    }
+                )
+            }
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_constant_map_tokens() {
+        let expr = ConstantCompiledExpr {
+            value: CelValue::Map([(CelValue::String("a".into()), CelValue::Bool(true))].into_iter().collect()),
+        };
+
+        assert_eq!(
+            expr.to_token_stream().to_string(),
+            quote! {
+                ::tinc::__private::cel::CelValue::Map([
+                    (
+                        ::tinc::__private::cel::CelValue::String(::tinc::__private::cel::CelString::Borrowed("a")),
+                        ::tinc::__private::cel::CelValue::Bool(true)
+                    )
+                ].into_iter().collect())
+            }
+            .to_string(),
+        );
+    }
+}
@@ -210,7 +210,7 @@ fn resolve_member(ctx: &Compiler, expr: &Expression, member: &Member) -> Result<
                 }) => Ok(CompiledExpr::runtime(
                     CelType::CelValue,
                     parse_quote! {
-                        ::tinc::__private::cel::CelValue::access(
+                        ::tinc::__private::cel::CelValue::cel_access(
                             #expr,
                             #attr
                         )?
@@ -235,18 +235,39 @@ fn resolve_member(ctx: &Compiler, expr: &Expression, member: &Member) -> Result<
 
                     let field_ident = field_ty.rust_ident();
 
-                    Ok(CompiledExpr::runtime(
-                        CelType::Proto(field_ty.ty.clone()),
-                        parse_quote! {
-                            match (#expr) {
-                                Some(value) => &value.#field_ident,
-                                None => return Err(::tinc::__private::cel::CelError::BadAccess {
-                                    member: ::tinc::__private::cel::CelValue::String(::tinc::__private::cel::CelString::Borrowed(#attr)),
-                                    container: ::tinc::__private::cel::CelValue::Null,
-                                }),
-                            }
-                        },
-                    ))
+                    // Accessing a field on an absent message short-circuits to null,
+                    // so the result is optional as well.
+                    match &field_ty.ty {
+                        ProtoType::Value(value_ty) => Ok(CompiledExpr::runtime(
+                            CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Optional(value_ty.clone()))),
+                            parse_quote! {
+                                (#expr).as_ref().map(|value| &value.#field_ident)
+                            },
+                        )),
+                        field_ty @ ProtoType::Modified(
+                            ProtoModifiedValueType::Optional(_) | ProtoModifiedValueType::OneOf(_),
+                        ) => Ok(CompiledExpr::runtime(
+                            CelType::Proto(field_ty.clone()),
+                            parse_quote! {
+                                (#expr).as_ref().and_then(|value| value.#field_ident.as_ref())
+                            },
+                        )),
+                        field_ty => {
+                            let value_to_cel =
+                                CompiledExpr::runtime(CelType::Proto(field_ty.clone()), parse_quote!(&value.#field_ident))
+                                    .into_cel()?;
+
+                            Ok(CompiledExpr::runtime(
+                                CelType::CelValue,
+                                parse_quote! {
+                                    match (#expr).as_ref() {
+                                        ::core::option::Option::Some(value) => #value_to_cel,
+                                        ::core::option::Option::None => ::tinc::__private::cel::CelValue::Null,
+                                    }
+                                },
+                            ))
+                        }
+                    }
                 }
                 CompiledExpr::Runtime(RuntimeCompiledExpr {
                     expr,
@@ -290,7 +311,7 @@ fn resolve_member(ctx: &Compiler, expr: &Expression, member: &Member) -> Result<
                     Ok(CompiledExpr::runtime(
                         CelType::Proto(field_ty.ty.clone()),
                         parse_quote! {
-                            &(#expr).#field_ident,
+                            &(#expr).#field_ident
                         },
                     ))
                 }
@@ -1120,4 +1141,22 @@ mod tests {
         )
         ");
     }
+
+    #[test]
+    fn test_resolve_access_cel_value() {
+        let registry = ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        let mut compiler = Compiler::new(&registry);
+        compiler.add_variable("input", CompiledExpr::runtime(CelType::CelValue, parse_quote!(input)));
+
+        let expr = parse_cel("input.a").unwrap();
+        let CompiledExpr::Runtime(RuntimeCompiledExpr { expr, ty }) = resolve(&compiler, &expr).unwrap() else {
+            panic!("expected a runtime expression");
+        };
+
+        assert_eq!(ty, CelType::CelValue);
+        assert_eq!(
+            quote!(#expr).to_string(),
+            quote!(::tinc::__private::cel::CelValue::cel_access(input, "a")?).to_string(),
+        );
+    }
 }
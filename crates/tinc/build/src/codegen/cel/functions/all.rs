@@ -3,7 +3,7 @@ use quote::{ToTokens, quote};
 use syn::parse_quote;
 use tinc_cel::CelValue;
 
-use super::Function;
+use super::{Function, item_ident};
 use crate::codegen::cel::compiler::{CompileError, CompiledExpr, CompilerCtx, ConstantCompiledExpr, RuntimeCompiledExpr};
 use crate::codegen::cel::types::CelType;
 use crate::types::{ProtoModifiedValueType, ProtoType, ProtoValueType};
@@ -52,17 +52,21 @@ impl Function for All {
         match this {
             CompiledExpr::Runtime(RuntimeCompiledExpr { expr, ty }) => {
                 let mut child_ctx = ctx.child();
+                let item = item_ident(&child_ctx);
 
                 match ty {
                     CelType::CelValue => {
-                        child_ctx.add_variable(variable, CompiledExpr::runtime(CelType::CelValue, parse_quote!(item)));
+                        child_ctx.add_variable(
+                            variable,
+                            CompiledExpr::runtime(CelType::CelValue, parse_quote!(#item.clone())),
+                        );
                     }
                     CelType::Proto(ProtoType::Modified(
                         ProtoModifiedValueType::Repeated(ty) | ProtoModifiedValueType::Map(ty, _),
                     )) => {
                         child_ctx.add_variable(
                             variable,
-                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(item)),
+                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(#item)),
                         );
                     }
                     v => {
@@ -79,17 +83,17 @@ impl Function for All {
                     CelType::Proto(ProtoType::Value(ProtoValueType::Bool)),
                     match &ty {
                         CelType::CelValue => parse_quote! {
-                            ::tinc::__private::cel::CelValue::cel_all(#expr, |item| {
+                            ::tinc::__private::cel::CelValue::cel_all(#expr, |#item| {
                                 ::core::result::Result::Ok(
                                     #arg
                                 )
                             })?
                         },
                         CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Map(_, _))) => {
-                            native_impl(quote!((#expr).keys()), parse_quote!(item), arg)
+                            native_impl(quote!((#expr).keys()), item, arg)
                         }
                         CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Repeated(_))) => {
-                            native_impl(quote!((#expr).iter()), parse_quote!(item), arg)
+                            native_impl(quote!(#expr), item, arg)
                         }
                         _ => unreachable!(),
                     },
@@ -0,0 +1,185 @@
+use syn::parse_quote;
+
+use super::Function;
+use crate::codegen::cel::compiler::{CompileError, CompiledExpr, CompilerCtx};
+use crate::codegen::cel::types::CelType;
+
+/// A function declared with `Config::cel_function`, which is registered by the application at runtime.
+#[derive(Debug, Clone)]
+pub(crate) struct Custom {
+    name: String,
+    arity: usize,
+}
+
+impl Custom {
+    pub(crate) fn new(name: impl Into<String>, arity: usize) -> Self {
+        Self {
+            name: name.into(),
+            arity,
+        }
+    }
+}
+
+// name(args...) or this.name(args...) -> the result of the function registered at runtime
+impl Function for Custom {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn syntax(&self) -> &'static str {
+        "<this>.<name>(<args>...) or <name>(<args>...)"
+    }
+
+    fn compile(&self, ctx: CompilerCtx) -> Result<CompiledExpr, CompileError> {
+        // The value a function is called on is passed as the first argument.
+        let args = ctx
+            .this
+            .clone()
+            .into_iter()
+            .map(Ok)
+            .chain(ctx.args.iter().map(|arg| ctx.resolve(arg)))
+            .map(|arg| arg?.into_cel())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if args.len() != self.arity {
+            return Err(CompileError::FunctionArity {
+                name: self.name.clone(),
+                expected: self.arity,
+                actual: args.len(),
+            });
+        }
+
+        let name = &self.name;
+        Ok(CompiledExpr::runtime(
+            CelType::CelValue,
+            parse_quote! {
+                ::tinc::__private::cel::CelValue::cel_call_function(
+                    #name,
+                    &[#(#args),*],
+                )?
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "prost")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use quote::quote;
+    use syn::parse_quote;
+    use tinc_cel::CelValue;
+
+    use crate::codegen::cel::compiler::{CompiledExpr, Compiler};
+    use crate::codegen::cel::types::CelType;
+    use crate::types::{ProtoType, ProtoTypeRegistry, ProtoValueType};
+
+    fn registry() -> ProtoTypeRegistry {
+        let mut registry =
+            ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        registry.register_cel_function("isInternalEmail", 1);
+        registry.register_cel_function("inRange", 3);
+        registry
+    }
+
+    #[test]
+    fn test_custom_syntax() {
+        let registry = registry();
+        let compiler = Compiler::new(&registry);
+
+        insta::assert_debug_snapshot!(compiler.resolve(&cel_parser::parse("isInternalEmail()").unwrap()), @r#"
+        Err(
+            FunctionArity {
+                name: "isInternalEmail",
+                expected: 1,
+                actual: 0,
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(compiler.resolve(&cel_parser::parse("'troy@scuffle.cloud'.isInternalEmail(1)").unwrap()), @r#"
+        Err(
+            FunctionArity {
+                name: "isInternalEmail",
+                expected: 1,
+                actual: 2,
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(compiler.resolve(&cel_parser::parse("isExternalEmail('troy@scuffle.cloud')").unwrap()), @r#"
+        Err(
+            FunctionNotFound(
+                "isExternalEmail",
+            ),
+        )
+        "#);
+
+        let mut compiler = compiler.child();
+        compiler.add_variable("input", CompiledExpr::constant(CelValue::Null));
+        insta::assert_debug_snapshot!(compiler.resolve(&cel_parser::parse("isInternalEmail(input)").unwrap()), @r#"
+        Ok(
+            Runtime(
+                RuntimeCompiledExpr {
+                    ty: CelValue,
+                    expr: ::tinc::__private::cel::CelValue::cel_call_function(
+                        "isInternalEmail",
+                        &[::tinc::__private::cel::CelValue::Null],
+                    )?,
+                },
+            ),
+        )
+        "#);
+    }
+
+    #[test]
+    #[cfg(not(valgrind))]
+    fn test_custom_runtime() {
+        let registry = registry();
+        let mut compiler = Compiler::new(&registry);
+        compiler.add_variable(
+            "input",
+            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ProtoValueType::String)), parse_quote!(input)),
+        );
+
+        let output = compiler
+            .resolve(&cel_parser::parse("input.isInternalEmail()").unwrap())
+            .unwrap()
+            .into_bool(&compiler);
+
+        insta::assert_snapshot!(postcompile::compile_str!(
+            postcompile::config! {
+                test: true,
+                dependencies: vec![
+                    postcompile::Dependency::version("tinc", "*"),
+                ],
+            },
+            quote! {
+                fn is_internal_email(input: &str) -> Result<bool, ::tinc::__private::cel::CelError<'_>> {
+                    Ok(#output)
+                }
+
+                #[test]
+                fn test_is_internal_email() {
+                    use ::tinc::validation::{CelError, CelValue};
+
+                    assert_eq!(
+                        is_internal_email("troy@scuffle.cloud"),
+                        Err(CelError::UnknownFunction("isInternalEmail".to_owned())),
+                    );
+
+                    ::tinc::validation::register_cel_function("isInternalEmail", 1, |args| match &args[0] {
+                        CelValue::String(email) => Ok(CelValue::Bool(email.ends_with("@scuffle.cloud"))),
+                        value => Err(CelError::BadUnaryOperation {
+                            op: "isInternalEmail",
+                            value: value.clone(),
+                        }),
+                    });
+
+                    assert_eq!(is_internal_email("troy@scuffle.cloud").unwrap(), true);
+                    assert_eq!(is_internal_email("troy@example.com").unwrap(), false);
+                }
+            },
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+use syn::parse_quote;
+use tinc_cel::CelValue;
+
+use super::Function;
+use crate::codegen::cel::compiler::{CompileError, CompiledExpr, CompilerCtx, ConstantCompiledExpr, RuntimeCompiledExpr};
+use crate::codegen::cel::types::CelType;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Duration;
+
+impl Function for Duration {
+    fn name(&self) -> &'static str {
+        "duration"
+    }
+
+    fn syntax(&self) -> &'static str {
+        "duration(<string>)"
+    }
+
+    fn compile(&self, ctx: CompilerCtx) -> Result<CompiledExpr, CompileError> {
+        if ctx.this.is_some() {
+            return Err(CompileError::syntax("has this", self));
+        };
+
+        if ctx.args.len() != 1 {
+            return Err(CompileError::syntax("needs exactly 1 argument", self));
+        }
+
+        match ctx.resolve(&ctx.args[0])?.into_cel()? {
+            CompiledExpr::Constant(ConstantCompiledExpr { value }) => {
+                Ok(CompiledExpr::constant(CelValue::cel_to_duration(value)?))
+            }
+            CompiledExpr::Runtime(RuntimeCompiledExpr { expr, .. }) => Ok(CompiledExpr::runtime(
+                CelType::CelValue,
+                parse_quote!(::tinc::__private::cel::CelValue::cel_to_duration(#expr)?),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "prost")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use tinc_cel::CelValue;
+
+    use crate::codegen::cel::compiler::{CompiledExpr, Compiler, CompilerCtx};
+    use crate::codegen::cel::functions::{Duration, Function};
+    use crate::types::ProtoTypeRegistry;
+
+    #[test]
+    fn test_duration_syntax() {
+        let registry = ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        let compiler = Compiler::new(&registry);
+        insta::assert_debug_snapshot!(Duration.compile(CompilerCtx::new(compiler.child(), None, &[])), @r#"
+        Err(
+            InvalidSyntax {
+                message: "needs exactly 1 argument",
+                syntax: "duration(<string>)",
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(Duration.compile(CompilerCtx::new(compiler.child(), Some(CompiledExpr::constant(CelValue::String("1h30m".into()))), &[])), @r#"
+        Err(
+            InvalidSyntax {
+                message: "has this",
+                syntax: "duration(<string>)",
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(Duration.compile(CompilerCtx::new(compiler.child(), None, &[
+            cel_parser::parse("'1h30m'").unwrap(),
+        ])), @"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: Duration(
+                        TimeDelta {
+                            secs: 5400,
+                            nanos: 0,
+                        },
+                    ),
+                },
+            ),
+        )
+        ");
+
+        insta::assert_debug_snapshot!(Duration.compile(CompilerCtx::new(compiler.child(), None, &[
+            cel_parser::parse("'forever'").unwrap(),
+        ])), @r#"
+        Err(
+            TypeConversion {
+                ty: CelValue,
+                message: "invalid duration: forever",
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(Duration.compile(CompilerCtx::new(compiler.child(), None, &[
+            cel_parser::parse("1").unwrap(),
+        ])), @r#"
+        Err(
+            TypeConversion {
+                ty: CelValue,
+                message: "bad unary operation: duration1",
+            },
+        )
+        "#);
+    }
+}
@@ -3,7 +3,7 @@ use quote::{ToTokens, quote};
 use syn::parse_quote;
 use tinc_cel::CelValue;
 
-use super::Function;
+use super::{Function, item_ident};
 use crate::codegen::cel::compiler::{CompileError, CompiledExpr, CompilerCtx, ConstantCompiledExpr, RuntimeCompiledExpr};
 use crate::codegen::cel::types::CelType;
 use crate::types::{ProtoModifiedValueType, ProtoType, ProtoValueType};
@@ -11,7 +11,7 @@ use crate::types::{ProtoModifiedValueType, ProtoType, ProtoValueType};
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Filter;
 
-fn native_impl(iter: TokenStream, item_ident: syn::Ident, compare: impl ToTokens) -> syn::Expr {
+fn native_impl(iter: TokenStream, item_ident: syn::Ident, compare: impl ToTokens, item_to_cel: impl ToTokens) -> syn::Expr {
     parse_quote!({
         let mut collected = Vec::new();
         let mut iter = (#iter).into_iter();
@@ -20,10 +20,25 @@ fn native_impl(iter: TokenStream, item_ident: syn::Ident, compare: impl ToTokens
                 break ::tinc::__private::cel::CelValue::List(collected.into());
             };
 
-            if {
-                let #item_ident = #item_ident.clone();
-                #compare
-            } {
+            if #compare {
+                collected.push(#item_to_cel);
+            }
+        }
+    })
+}
+
+// Messages cannot be converted into cel values, so repeated messages are filtered into
+// a `Vec` of references which can be used like the repeated field itself.
+fn native_ref_impl(iter: TokenStream, item_ident: syn::Ident, compare: impl ToTokens) -> syn::Expr {
+    parse_quote!({
+        let mut collected = Vec::new();
+        let mut iter = (#iter).into_iter();
+        loop {
+            let Some(#item_ident) = iter.next() else {
+                break collected;
+            };
+
+            if #compare {
                 collected.push(#item_ident);
             }
         }
@@ -56,17 +71,21 @@ impl Function for Filter {
         match this {
             CompiledExpr::Runtime(RuntimeCompiledExpr { expr, ty }) => {
                 let mut child_ctx = ctx.child();
+                let item = item_ident(&child_ctx);
 
                 match ty {
                     CelType::CelValue => {
-                        child_ctx.add_variable(variable, CompiledExpr::runtime(CelType::CelValue, parse_quote!(item)));
+                        child_ctx.add_variable(
+                            variable,
+                            CompiledExpr::runtime(CelType::CelValue, parse_quote!(#item.clone())),
+                        );
                     }
                     CelType::Proto(ProtoType::Modified(
                         ProtoModifiedValueType::Repeated(ty) | ProtoModifiedValueType::Map(ty, _),
                     )) => {
                         child_ctx.add_variable(
                             variable,
-                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(item)),
+                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(#item)),
                         );
                     }
                     v => {
@@ -79,45 +98,45 @@ impl Function for Filter {
 
                 let arg = child_ctx.resolve(&ctx.args[1])?.into_bool(&child_ctx);
 
-                Ok(CompiledExpr::runtime(
-                    CelType::CelValue,
-                    match ty {
-                        CelType::CelValue => parse_quote! {
-                            ::tinc::__private::cel::CelValue::cel_filter(#expr, |item| {
+                match ty {
+                    CelType::CelValue => Ok(CompiledExpr::runtime(
+                        CelType::CelValue,
+                        parse_quote! {
+                            ::tinc::__private::cel::CelValue::cel_filter(#expr, |#item| {
                                 ::core::result::Result::Ok(
                                     #arg
                                 )
                             })?
                         },
-                        CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Map(ty, _))) => {
-                            let cel_ty =
-                                CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(item))
-                                    .into_cel()?;
-
-                            native_impl(
-                                quote!(
-                                    (#expr).keys().map(|item| #cel_ty)
-                                ),
-                                parse_quote!(item),
-                                arg,
-                            )
-                        }
-                        CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Repeated(ty))) => {
-                            let cel_ty =
-                                CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(item))
-                                    .into_cel()?;
-
-                            native_impl(
-                                quote!(
-                                    (#expr).iter().map(|item| #cel_ty)
-                                ),
-                                parse_quote!(item),
-                                arg,
-                            )
-                        }
-                        _ => unreachable!(),
-                    },
-                ))
+                    )),
+                    CelType::Proto(ProtoType::Modified(
+                        repeated @ ProtoModifiedValueType::Repeated(ProtoValueType::Message(_)),
+                    )) => Ok(CompiledExpr::runtime(
+                        CelType::Proto(ProtoType::Modified(repeated.clone())),
+                        native_ref_impl(quote!(#expr), item, arg),
+                    )),
+                    CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Map(ty, _))) => {
+                        let item_to_cel =
+                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(#item))
+                                .into_cel()?;
+
+                        Ok(CompiledExpr::runtime(
+                            CelType::CelValue,
+                            native_impl(quote!((#expr).keys()), item, arg, item_to_cel),
+                        ))
+                    }
+                    CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Repeated(ty))) => {
+                        let item_to_cel =
+                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(#item))
+                                .into_cel()?;
+
+                        Ok(CompiledExpr::runtime(
+                            CelType::CelValue,
+                            native_impl(quote!(#expr), item, arg, item_to_cel),
+                        ))
+                    }
+                    _ => unreachable!(),
+                }
             }
             CompiledExpr::Constant(ConstantCompiledExpr {
                 value: value @ (CelValue::List(_) | CelValue::Map(_)),
@@ -148,7 +167,7 @@ impl Function for Filter {
                     });
 
                     Ok(CompiledExpr::runtime(
-                        CelType::Proto(ProtoType::Value(ProtoValueType::Bool)),
+                        CelType::CelValue,
                         parse_quote!({
                             let mut collected = Vec::new();
                             #(#collected)*
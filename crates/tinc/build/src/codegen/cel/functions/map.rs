@@ -3,10 +3,10 @@ use quote::{ToTokens, quote};
 use syn::parse_quote;
 use tinc_cel::CelValue;
 
-use super::Function;
+use super::{Filter, Function, item_ident};
 use crate::codegen::cel::compiler::{CompileError, CompiledExpr, CompilerCtx, ConstantCompiledExpr, RuntimeCompiledExpr};
 use crate::codegen::cel::types::CelType;
-use crate::types::{ProtoModifiedValueType, ProtoType, ProtoValueType};
+use crate::types::{ProtoModifiedValueType, ProtoType};
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct Map;
@@ -26,13 +26,14 @@ fn native_impl(iter: TokenStream, item_ident: syn::Ident, map_fn: impl ToTokens)
 }
 
 // this.map(<ident>, <expr>)
+// this.map(<ident>, <predicate>, <expr>)
 impl Function for Map {
     fn name(&self) -> &'static str {
         "map"
     }
 
     fn syntax(&self) -> &'static str {
-        "<this>.map(<ident>, <expr>)"
+        "<this>.map(<ident>, [<predicate>,] <expr>)"
     }
 
     fn compile(&self, ctx: CompilerCtx) -> Result<CompiledExpr, CompileError> {
@@ -40,7 +41,7 @@ impl Function for Map {
             return Err(CompileError::syntax("missing this", self));
         };
 
-        if ctx.args.len() != 2 {
+        if ctx.args.len() != 2 && ctx.args.len() != 3 {
             return Err(CompileError::syntax("invalid number of args", self));
         }
 
@@ -48,20 +49,33 @@ impl Function for Map {
             return Err(CompileError::syntax("first argument must be an ident", self));
         };
 
+        // The three argument form is a filter followed by a map.
+        if let [ident, predicate, map_expr] = ctx.args {
+            let filter_args = [ident.clone(), predicate.clone()];
+            let filtered = Filter.compile(CompilerCtx::new(ctx.child(), Some(this.clone()), &filter_args))?;
+
+            let map_args = [ident.clone(), map_expr.clone()];
+            return self.compile(CompilerCtx::new(ctx.child(), Some(filtered), &map_args));
+        }
+
         match this {
             CompiledExpr::Runtime(RuntimeCompiledExpr { expr, ty }) => {
                 let mut child_ctx = ctx.child();
+                let item = item_ident(&child_ctx);
 
                 match ty {
                     CelType::CelValue => {
-                        child_ctx.add_variable(variable, CompiledExpr::runtime(CelType::CelValue, parse_quote!(item)));
+                        child_ctx.add_variable(
+                            variable,
+                            CompiledExpr::runtime(CelType::CelValue, parse_quote!(#item.clone())),
+                        );
                     }
                     CelType::Proto(ProtoType::Modified(
                         ProtoModifiedValueType::Repeated(ty) | ProtoModifiedValueType::Map(ty, _),
                     )) => {
                         child_ctx.add_variable(
                             variable,
-                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(item)),
+                            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ty.clone())), parse_quote!(#item)),
                         );
                     }
                     v => {
@@ -78,17 +92,17 @@ impl Function for Map {
                     CelType::CelValue,
                     match &ty {
                         CelType::CelValue => parse_quote! {
-                            ::tinc::__private::cel::CelValue::cel_map(#expr, |item| {
+                            ::tinc::__private::cel::CelValue::cel_map(#expr, |#item| {
                                 ::core::result::Result::Ok(
                                     #arg
                                 )
                             })?
                         },
                         CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Map(_, _))) => {
-                            native_impl(quote!((#expr).keys()), parse_quote!(item), arg)
+                            native_impl(quote!((#expr).keys()), item, arg)
                         }
                         CelType::Proto(ProtoType::Modified(ProtoModifiedValueType::Repeated(_))) => {
-                            native_impl(quote!((#expr).iter()), parse_quote!(item), arg)
+                            native_impl(quote!(#expr), item, arg)
                         }
                         _ => unreachable!(),
                     },
@@ -114,7 +128,7 @@ impl Function for Map {
                 let collected = collected?;
                 if collected.iter().any(|c| matches!(c, CompiledExpr::Runtime(_))) {
                     Ok(CompiledExpr::runtime(
-                        CelType::Proto(ProtoType::Value(ProtoValueType::Bool)),
+                        CelType::CelValue,
                         native_impl(quote!([#(#collected),*]), parse_quote!(item), quote!(item)),
                     ))
                 } else {
@@ -158,7 +172,7 @@ mod tests {
         Err(
             InvalidSyntax {
                 message: "missing this",
-                syntax: "<this>.map(<ident>, <expr>)",
+                syntax: "<this>.map(<ident>, [<predicate>,] <expr>)",
             },
         )
         "#);
@@ -167,7 +181,7 @@ mod tests {
         Err(
             InvalidSyntax {
                 message: "invalid number of args",
-                syntax: "<this>.map(<ident>, <expr>)",
+                syntax: "<this>.map(<ident>, [<predicate>,] <expr>)",
             },
         )
         "#);
@@ -16,7 +16,7 @@ impl Function for Matches {
     }
 
     fn syntax(&self) -> &'static str {
-        "<this>.matches(<const regex>)"
+        "<this>.matches(<regex>)"
     }
 
     fn compile(&self, ctx: CompilerCtx) -> Result<CompiledExpr, CompileError> {
@@ -28,11 +28,24 @@ impl Function for Matches {
             return Err(CompileError::syntax("takes exactly one argument", self));
         }
 
-        let CompiledExpr::Constant(ConstantCompiledExpr {
-            value: CelValue::String(regex),
-        }) = ctx.resolve(&ctx.args[0])?.into_cel()?
-        else {
-            return Err(CompileError::syntax("regex must be known at compile time string", self));
+        let this = this.clone().into_cel()?;
+        let regex = match ctx.resolve(&ctx.args[0])?.into_cel()? {
+            CompiledExpr::Constant(ConstantCompiledExpr {
+                value: CelValue::String(regex),
+            }) => regex,
+            CompiledExpr::Constant(_) => return Err(CompileError::syntax("regex must be a string", self)),
+            // The pattern is only known at runtime, so it is compiled (and cached) by tinc-cel.
+            pattern => {
+                return Ok(CompiledExpr::runtime(
+                    CelType::Proto(ProtoType::Value(ProtoValueType::Bool)),
+                    parse_quote! {
+                        ::tinc::__private::cel::CelValue::cel_matches_pattern(
+                            #this,
+                            #pattern,
+                        )?
+                    },
+                ));
+            }
         };
 
         let regex = regex.as_ref();
@@ -42,8 +55,6 @@ impl Function for Matches {
 
         let re = regex::Regex::new(regex).map_err(|err| CompileError::syntax(format!("bad regex {err}"), self))?;
 
-        let this = this.clone().into_cel()?;
-
         match this {
             CompiledExpr::Constant(ConstantCompiledExpr { value }) => {
                 Ok(CompiledExpr::constant(CelValue::cel_matches(value, &re)?))
@@ -86,7 +97,7 @@ mod tests {
         Err(
             InvalidSyntax {
                 message: "missing this",
-                syntax: "<this>.matches(<const regex>)",
+                syntax: "<this>.matches(<regex>)",
             },
         )
         "#);
@@ -95,18 +106,18 @@ mod tests {
         Err(
             InvalidSyntax {
                 message: "takes exactly one argument",
-                syntax: "<this>.matches(<const regex>)",
+                syntax: "<this>.matches(<regex>)",
             },
         )
         "#);
 
         insta::assert_debug_snapshot!(Matches.compile(CompilerCtx::new(compiler.child(), Some(CompiledExpr::constant(CelValue::String("hi".into()))), &[
-            cel_parser::parse("dyn('^h')").unwrap(),
+            cel_parser::parse("1").unwrap(),
         ])), @r#"
         Err(
             InvalidSyntax {
-                message: "regex must be known at compile time string",
-                syntax: "<this>.matches(<const regex>)",
+                message: "regex must be a string",
+                syntax: "<this>.matches(<regex>)",
             },
         )
         "#);
@@ -164,4 +175,42 @@ mod tests {
             },
         ));
     }
+
+    #[test]
+    #[cfg(not(valgrind))]
+    fn test_matches_runtime_pattern() {
+        let registry = ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        let compiler = Compiler::new(&registry);
+
+        let string_value =
+            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ProtoValueType::String)), parse_quote!(input));
+
+        let output = Matches
+            .compile(CompilerCtx::new(
+                compiler.child(),
+                Some(string_value),
+                &[cel_parser::parse("dyn('\\\\d+')").unwrap()],
+            ))
+            .unwrap();
+
+        insta::assert_snapshot!(postcompile::compile_str!(
+            postcompile::config! {
+                test: true,
+                dependencies: vec![
+                    postcompile::Dependency::version("tinc", "*"),
+                ],
+            },
+            quote! {
+                fn matches(input: &String) -> Result<bool, ::tinc::__private::cel::CelError<'_>> {
+                    Ok(#output)
+                }
+
+                #[test]
+                fn test_matches() {
+                    assert_eq!(matches(&"in2dastring".into()).unwrap(), true);
+                    assert_eq!(matches(&"xd".into()).unwrap(), false);
+                }
+            },
+        ));
+    }
 }
@@ -2,7 +2,9 @@ mod all;
 mod bool;
 mod bytes;
 mod contains;
+mod custom;
 mod double;
+mod duration;
 mod dyn_;
 mod ends_with;
 mod enum_;
@@ -22,13 +24,16 @@ mod matches;
 mod size;
 mod starts_with;
 mod string;
+mod timestamp;
 mod uint;
 
 pub(crate) use all::All;
 pub(crate) use bool::Bool;
 pub(crate) use bytes::Bytes;
 pub(crate) use contains::Contains;
+pub(crate) use custom::Custom;
 pub(crate) use double::Double;
+pub(crate) use duration::Duration;
 pub(crate) use dyn_::Dyn;
 pub(crate) use ends_with::EndsWith;
 pub(crate) use enum_::Enum;
@@ -48,6 +53,7 @@ pub(crate) use matches::Matches;
 pub(crate) use size::Size;
 pub(crate) use starts_with::StartsWith;
 pub(crate) use string::String;
+pub(crate) use timestamp::Timestamp;
 pub(crate) use uint::UInt;
 
 use super::compiler::{CompileError, CompiledExpr, Compiler, CompilerCtx};
@@ -70,6 +76,8 @@ pub(crate) fn add_to_compiler(compiler: &mut Compiler) {
     UInt.add_to_compiler(compiler);
     Double.add_to_compiler(compiler);
     Bool.add_to_compiler(compiler);
+    Timestamp.add_to_compiler(compiler);
+    Duration.add_to_compiler(compiler);
     Enum::default().add_to_compiler(compiler);
     IsIpv4.add_to_compiler(compiler);
     IsIpv6.add_to_compiler(compiler);
@@ -78,10 +86,22 @@ pub(crate) fn add_to_compiler(compiler: &mut Compiler) {
     IsUri.add_to_compiler(compiler);
     IsEmail.add_to_compiler(compiler);
     Dyn.add_to_compiler(compiler);
+
+    let registry = compiler.registry();
+    for (name, arity) in registry.cel_functions() {
+        Custom::new(name, arity).add_to_compiler(compiler);
+    }
+}
+
+/// The identifier the variable of a macro like `all()` or `map()` is bound to in the generated code.
+///
+/// Nested macros are compiled by deeper compilers, so their variables never shadow the ones of outer macros.
+fn item_ident(compiler: &Compiler) -> syn::Ident {
+    quote::format_ident!("item_{}", compiler.depth())
 }
 
 pub(crate) trait Function: Send + Sync + 'static {
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
     fn syntax(&self) -> &'static str;
 
@@ -19,6 +19,8 @@ impl Function for Size {
     }
 
     fn compile(&self, ctx: CompilerCtx) -> Result<CompiledExpr, CompileError> {
+        let byte_size = ctx.registry().cel_string_byte_size();
+
         let Some(this) = ctx.this else {
             return Err(CompileError::syntax("missing this", self));
         };
@@ -41,7 +43,14 @@ impl Function for Size {
         }
 
         match this.into_cel()? {
+            CompiledExpr::Constant(ConstantCompiledExpr { value }) if byte_size => {
+                Ok(CompiledExpr::constant(CelValue::cel_byte_size(value)?))
+            }
             CompiledExpr::Constant(ConstantCompiledExpr { value }) => Ok(CompiledExpr::constant(CelValue::cel_size(value)?)),
+            CompiledExpr::Runtime(RuntimeCompiledExpr { expr, .. }) if byte_size => Ok(CompiledExpr::runtime(
+                CelType::Proto(ProtoType::Value(ProtoValueType::UInt64)),
+                parse_quote!(::tinc::__private::cel::CelValue::cel_byte_size(#expr)?),
+            )),
             CompiledExpr::Runtime(RuntimeCompiledExpr { expr, .. }) => Ok(CompiledExpr::runtime(
                 CelType::Proto(ProtoType::Value(ProtoValueType::UInt64)),
                 parse_quote!(::tinc::__private::cel::CelValue::cel_size(#expr)?),
@@ -101,6 +110,78 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_size_string_unicode() {
+        let mut registry =
+            ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        let this = || Some(CompiledExpr::constant(CelValue::String("héllo".into())));
+
+        let compiler = Compiler::new(&registry);
+        insta::assert_debug_snapshot!(Size.compile(CompilerCtx::new(compiler.child(), this(), &[])), @r"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: Number(
+                        U64(
+                            5,
+                        ),
+                    ),
+                },
+            ),
+        )
+        ");
+
+        let bytes = Some(CompiledExpr::constant(CelValue::Bytes("héllo".as_bytes().to_vec().into())));
+        insta::assert_debug_snapshot!(Size.compile(CompilerCtx::new(compiler.child(), bytes, &[])), @r"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: Number(
+                        U64(
+                            6,
+                        ),
+                    ),
+                },
+            ),
+        )
+        ");
+
+        registry.set_cel_string_byte_size(true);
+        let compiler = Compiler::new(&registry);
+        insta::assert_debug_snapshot!(Size.compile(CompilerCtx::new(compiler.child(), this(), &[])), @r"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: Number(
+                        U64(
+                            6,
+                        ),
+                    ),
+                },
+            ),
+        )
+        ");
+
+        let string_value =
+            CompiledExpr::runtime(CelType::Proto(ProtoType::Value(ProtoValueType::String)), parse_quote!(input));
+        insta::assert_debug_snapshot!(Size.compile(CompilerCtx::new(compiler.child(), Some(string_value), &[])), @r"
+        Ok(
+            Runtime(
+                RuntimeCompiledExpr {
+                    ty: Proto(
+                        Value(
+                            UInt64,
+                        ),
+                    ),
+                    expr: ::tinc::__private::cel::CelValue::cel_byte_size(
+                        ::tinc::__private::cel::CelValueConv::conv(input),
+                    )?,
+                },
+            ),
+        )
+        ");
+    }
+
     #[test]
     #[cfg(not(valgrind))]
     fn test_size_runtime() {
@@ -183,6 +183,44 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn test_string_duration_and_timestamp() {
+        let registry = ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        let compiler = Compiler::new(&registry);
+
+        let timestamp = compiler
+            .resolve(&cel_parser::parse("timestamp('2020-01-01T01:00:00+01:00')").unwrap())
+            .unwrap();
+        insta::assert_debug_snapshot!(String.compile(CompilerCtx::new(compiler.child(), Some(timestamp), &[])), @r#"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: String(
+                        Owned(
+                            "2020-01-01T00:00:00Z",
+                        ),
+                    ),
+                },
+            ),
+        )
+        "#);
+
+        let duration = compiler.resolve(&cel_parser::parse("duration('1h30m')").unwrap()).unwrap();
+        insta::assert_debug_snapshot!(String.compile(CompilerCtx::new(compiler.child(), Some(duration), &[])), @r#"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: String(
+                        Owned(
+                            "5400s",
+                        ),
+                    ),
+                },
+            ),
+        )
+        "#);
+    }
+
     #[test]
     #[cfg(not(valgrind))]
     fn test_string_runtime() {
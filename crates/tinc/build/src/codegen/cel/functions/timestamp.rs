@@ -0,0 +1,109 @@
+use syn::parse_quote;
+use tinc_cel::CelValue;
+
+use super::Function;
+use crate::codegen::cel::compiler::{CompileError, CompiledExpr, CompilerCtx, ConstantCompiledExpr, RuntimeCompiledExpr};
+use crate::codegen::cel::types::CelType;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Timestamp;
+
+impl Function for Timestamp {
+    fn name(&self) -> &'static str {
+        "timestamp"
+    }
+
+    fn syntax(&self) -> &'static str {
+        "timestamp(<string>)"
+    }
+
+    fn compile(&self, ctx: CompilerCtx) -> Result<CompiledExpr, CompileError> {
+        if ctx.this.is_some() {
+            return Err(CompileError::syntax("has this", self));
+        };
+
+        if ctx.args.len() != 1 {
+            return Err(CompileError::syntax("needs exactly 1 argument", self));
+        }
+
+        match ctx.resolve(&ctx.args[0])?.into_cel()? {
+            CompiledExpr::Constant(ConstantCompiledExpr { value }) => {
+                Ok(CompiledExpr::constant(CelValue::cel_to_timestamp(value)?))
+            }
+            CompiledExpr::Runtime(RuntimeCompiledExpr { expr, .. }) => Ok(CompiledExpr::runtime(
+                CelType::CelValue,
+                parse_quote!(::tinc::__private::cel::CelValue::cel_to_timestamp(#expr)?),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "prost")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use tinc_cel::CelValue;
+
+    use crate::codegen::cel::compiler::{CompiledExpr, Compiler, CompilerCtx};
+    use crate::codegen::cel::functions::{Function, Timestamp};
+    use crate::types::ProtoTypeRegistry;
+
+    #[test]
+    fn test_timestamp_syntax() {
+        let registry = ProtoTypeRegistry::new(crate::Mode::Prost, crate::extern_paths::ExternPaths::new(crate::Mode::Prost));
+        let compiler = Compiler::new(&registry);
+        insta::assert_debug_snapshot!(Timestamp.compile(CompilerCtx::new(compiler.child(), None, &[])), @r#"
+        Err(
+            InvalidSyntax {
+                message: "needs exactly 1 argument",
+                syntax: "timestamp(<string>)",
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(Timestamp.compile(CompilerCtx::new(compiler.child(), Some(CompiledExpr::constant(CelValue::String("2020-01-01T00:00:00Z".into()))), &[])), @r#"
+        Err(
+            InvalidSyntax {
+                message: "has this",
+                syntax: "timestamp(<string>)",
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(Timestamp.compile(CompilerCtx::new(compiler.child(), None, &[
+            cel_parser::parse("'2020-01-01T00:00:00Z'").unwrap(),
+        ])), @"
+        Ok(
+            Constant(
+                ConstantCompiledExpr {
+                    value: Timestamp(
+                        2020-01-01T00:00:00+00:00,
+                    ),
+                },
+            ),
+        )
+        ");
+
+        insta::assert_debug_snapshot!(Timestamp.compile(CompilerCtx::new(compiler.child(), None, &[
+            cel_parser::parse("'not a timestamp'").unwrap(),
+        ])), @r#"
+        Err(
+            TypeConversion {
+                ty: CelValue,
+                message: "invalid timestamp: not a timestamp",
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(Timestamp.compile(CompilerCtx::new(compiler.child(), None, &[
+            cel_parser::parse("1").unwrap(),
+        ])), @r#"
+        Err(
+            TypeConversion {
+                ty: CelValue,
+                message: "bad unary operation: timestamp1",
+            },
+        )
+        "#);
+    }
+}
@@ -68,6 +68,11 @@ pub(crate) struct CelExpression {
     pub expression: String,
     pub jsonschemas: Vec<String>,
     pub this: Option<CelValue<'static>>,
+    /// The id of the rule this expression came from.
+    ///
+    /// This is the full name of the predefined constraint, e.g. `tinc.StringConstraints.min_len`,
+    /// or `<element full name>.cel[<index>]` for custom expressions.
+    pub rule: String,
 }
 
 #[derive(Debug, PartialEq, Clone, Default)]
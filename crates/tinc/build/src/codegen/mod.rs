@@ -5,6 +5,7 @@ use service::{ProcessedService, handle_service};
 
 use self::serde::{handle_enum, handle_message};
 use crate::types::{ProtoPath, ProtoTypeRegistry};
+use crate::{Cors, OpenApiConfig, path_matches};
 
 pub(crate) mod cel;
 mod config;
@@ -40,7 +41,11 @@ impl std::ops::DerefMut for Package {
     }
 }
 
-pub(crate) fn generate_modules(registry: &ProtoTypeRegistry) -> anyhow::Result<BTreeMap<ProtoPath, Package>> {
+pub(crate) fn generate_modules(
+    registry: &ProtoTypeRegistry,
+    openapi: &OpenApiConfig,
+    cors: &[(String, Cors)],
+) -> anyhow::Result<BTreeMap<ProtoPath, Package>> {
     let mut modules = BTreeMap::new();
 
     registry
@@ -53,9 +58,21 @@ pub(crate) fn generate_modules(registry: &ProtoTypeRegistry) -> anyhow::Result<B
         .filter(|enum_| !registry.has_extern(&enum_.full_name))
         .try_for_each(|enum_| handle_enum(enum_, modules.entry(enum_.package.clone()).or_default(), registry))?;
 
-    registry
-        .services()
-        .try_for_each(|service| handle_service(service, modules.entry(service.package.clone()).or_default(), registry))?;
+    registry.services().try_for_each(|service| {
+        let cors = cors
+            .iter()
+            .rev()
+            .find(|(path, _)| path_matches(path, &service.full_name))
+            .map(|(_, cors)| cors);
+
+        handle_service(
+            service,
+            modules.entry(service.package.clone()).or_default(),
+            registry,
+            openapi,
+            cors,
+        )
+    })?;
 
     Ok(modules)
 }
@@ -7,8 +7,8 @@ use super::cel::compiler::{CompiledExpr, Compiler};
 use super::cel::types::CelType;
 use super::cel::{CelExpression, eval_message_fmt, functions};
 use crate::types::{
-    ProtoEnumType, ProtoFieldOptions, ProtoFieldSerdeOmittable, ProtoMessageField, ProtoMessageType, ProtoModifiedValueType,
-    ProtoOneOfType, ProtoType, ProtoTypeRegistry, ProtoValueType, ProtoVisibility, Tagged,
+    ProtoBytesEncoding, ProtoEnumType, ProtoFieldOptions, ProtoFieldSerdeOmittable, ProtoMessageField, ProtoMessageType,
+    ProtoModifiedValueType, ProtoOneOfType, ProtoType, ProtoTypeRegistry, ProtoValueType, ProtoVisibility, Tagged,
 };
 
 fn handle_oneof(
@@ -186,26 +186,33 @@ fn handle_oneof(
                 field.options.serde_name.as_str()
             };
 
-            validate_message_impl.push(quote! {
-                (Self::#enum_ident(value)) => {
-                    let _token = ::tinc::__private::ProtoPathToken::push_field(#field_name);
-                    let _token = ::tinc::__private::SerdePathToken::push_field(#serde_name);
-                    let tracker = match tracker {
-                        ::core::option::Option::Some(___Tracker::#enum_ident(tracker)) => ::core::option::Option::Some(tracker),
-                        ::core::option::Option::Some(t) => return ::core::result::Result::Err(
-                            ::tinc::reexports::serde::de::Error::custom(format!(
-                                "tracker and value do not match: {:?} != {:?}",
-                                ::tinc::__private::Identifier::name(&<Self as ::tinc::__private::TrackedOneOfDeserializer<'_>>::tracker_to_identifier(t)),
-                                ::tinc::__private::Identifier::name(&<Self as ::tinc::__private::TrackedOneOfDeserializer<'_>>::value_to_identifier(self)),
-                            )),
-                        ),
-                        ::core::option::Option::None => ::core::option::Option::None,
-                    };
-                    #(#cel_validation_fn)*
-                }
-            });
+            if !cel_validation_fn.is_empty() {
+                validate_message_impl.push(quote! {
+                    (Self::#enum_ident(value)) => {
+                        let _token = ::tinc::__private::ProtoPathToken::push_field(#field_name);
+                        let _token = ::tinc::__private::SerdePathToken::push_field(#serde_name);
+                        let tracker = match tracker {
+                            ::core::option::Option::Some(___Tracker::#enum_ident(tracker)) => ::core::option::Option::Some(tracker),
+                            ::core::option::Option::Some(t) => return ::core::result::Result::Err(
+                                ::tinc::reexports::serde::de::Error::custom(format!(
+                                    "tracker and value do not match: {:?} != {:?}",
+                                    ::tinc::__private::Identifier::name(&<Self as ::tinc::__private::TrackedOneOfDeserializer<'_>>::tracker_to_identifier(t)),
+                                    ::tinc::__private::Identifier::name(&<Self as ::tinc::__private::TrackedOneOfDeserializer<'_>>::value_to_identifier(self)),
+                                )),
+                            ),
+                            ::core::option::Option::None => ::core::option::Option::None,
+                        };
+                        #(#cel_validation_fn)*
+                    }
+                });
+            }
         }
 
+        anyhow::ensure!(
+            field.options.bytes_encoding == ProtoBytesEncoding::default() || field.ty == ProtoValueType::Bytes,
+            "bytes_encoding can only be used on bytes fields"
+        );
+
         match &field.ty {
             ProtoValueType::Enum(path) => {
                 let path_str = registry
@@ -221,7 +228,22 @@ fn handle_oneof(
 
                 oneof_config.field_attribute(field_name, parse_quote!(#[tinc(enum = #path_str)]));
             }
-            ProtoValueType::WellKnown(_) | ProtoValueType::Bytes => {
+            ProtoValueType::Bytes => {
+                if let Some(encoding) = field.options.bytes_encoding.rust_path() {
+                    if field.options.visibility.has_output() {
+                        let serialize_with = format!("::tinc::__private::serialize_bytes::<{encoding}, _, _>");
+                        oneof_config.field_attribute(field_name, parse_quote!(#[serde(serialize_with = #serialize_with)]));
+                    }
+
+                    oneof_config.field_attribute(field_name, parse_quote!(#[tinc(bytes = #encoding)]));
+                } else if field.options.visibility.has_output() {
+                    oneof_config.field_attribute(
+                        field_name,
+                        parse_quote!(#[serde(serialize_with = "::tinc::__private::serialize_well_known")]),
+                    );
+                }
+            }
+            ProtoValueType::WellKnown(_) => {
                 if field.options.visibility.has_output() {
                     oneof_config.field_attribute(
                         field_name,
@@ -426,6 +448,12 @@ fn handle_message_field(
         message_config.field_attribute(field_name, parse_quote!(#[serde(skip_serializing)]));
     }
 
+    anyhow::ensure!(
+        field.options.bytes_encoding == ProtoBytesEncoding::default()
+            || matches!(field.ty.value_type(), Some(ProtoValueType::Bytes)),
+        "bytes_encoding can only be used on bytes fields"
+    );
+
     match field.ty.value_type() {
         Some(ProtoValueType::Enum(path)) => {
             let path_str = registry
@@ -441,7 +469,22 @@ fn handle_message_field(
 
             message_config.field_attribute(field_name, parse_quote!(#[tinc(enum = #path_str)]));
         }
-        Some(ProtoValueType::WellKnown(_) | ProtoValueType::Bytes) => {
+        Some(ProtoValueType::Bytes) => {
+            if let Some(encoding) = field.options.bytes_encoding.rust_path() {
+                if field.options.visibility.has_output() {
+                    let serialize_with = format!("::tinc::__private::serialize_bytes::<{encoding}, _, _>");
+                    message_config.field_attribute(field_name, parse_quote!(#[serde(serialize_with = #serialize_with)]));
+                }
+
+                message_config.field_attribute(field_name, parse_quote!(#[tinc(bytes = #encoding)]));
+            } else if field.options.visibility.has_output() {
+                message_config.field_attribute(
+                    field_name,
+                    parse_quote!(#[serde(serialize_with = "::tinc::__private::serialize_well_known")]),
+                );
+            }
+        }
+        Some(ProtoValueType::WellKnown(_)) => {
             if field.options.visibility.has_output() {
                 message_config.field_attribute(
                     field_name,
@@ -552,6 +595,11 @@ fn handle_message_field(
             tracker_access,
         )?;
 
+        // Nothing to check, e.g. an omittable field without constraints.
+        if cel_validation_fn.is_empty() && missing.is_empty() {
+            return Ok(());
+        }
+
         field_builder.cel_validation_fn.push(quote!({
             let _token = ::tinc::__private::ProtoPathToken::push_field(#field_name);
             #push_field_token
@@ -588,6 +636,13 @@ fn cel_expressions(
         let resolved = ctx.resolve(&parsed).context("cel expression")?;
         let expr_str = &expr.expression;
         let message = eval_message_fmt(field_full_name, &expr.message, &ctx).context("message")?;
+        let rule = &expr.rule;
+        let rule_value = if expr.this.is_some() {
+            let this = eval_message_fmt(field_full_name, "{this}", &ctx).context("rule value")?;
+            quote!(::core::option::Option::Some(::std::convert::Into::into(#this)))
+        } else {
+            quote!(::core::option::Option::None)
+        };
 
         anyhow::Ok(quote! {
             if !::tinc::__private::cel::to_bool({
@@ -602,7 +657,7 @@ fn cel_expressions(
                 })?
             }) {
                 ::tinc::__private::report_tracked_error(
-                    ::tinc::__private::TrackedError::invalid_field(#message)
+                    ::tinc::__private::TrackedError::constraint_violation(#message, #rule, #rule_value, #expr_str)
                 )?;
             }
         })
@@ -622,10 +677,11 @@ fn cel_expressions(
             compiler.register_function(functions::Enum(Some(path.clone())));
         }
 
-        let recursive_validate = matches!(
-            field_type,
-            ProtoType::Value(ProtoValueType::Message(_)) | ProtoType::Modified(ProtoModifiedValueType::OneOf(_))
-        );
+        let recursive_validate = match &field_type {
+            ProtoType::Value(ProtoValueType::Message(path)) => registry.message_has_validation(path),
+            ProtoType::Modified(ProtoModifiedValueType::OneOf(oneof)) => registry.oneof_has_validation(oneof),
+            _ => false,
+        };
 
         compiler.add_variable(
             "input",
@@ -673,7 +729,7 @@ fn cel_expressions(
         ProtoType::Modified(ProtoModifiedValueType::Map(key, value))
             if !options.cel_exprs.map_key.is_empty()
                 || !options.cel_exprs.map_value.is_empty()
-                || matches!(value, ProtoValueType::Message(_)) =>
+                || matches!(value, ProtoValueType::Message(path) if registry.message_has_validation(path)) =>
         {
             let key_exprs = {
                 let mut compiler = compiler.child();
@@ -694,7 +750,7 @@ fn cel_expressions(
                     .collect::<anyhow::Result<Vec<_>>>()?
             };
 
-            let is_message = matches!(value, ProtoValueType::Message(_));
+            let is_message = matches!(value, ProtoValueType::Message(path) if registry.message_has_validation(path));
 
             let mut value_exprs = {
                 let mut compiler = compiler.child();
@@ -733,9 +789,10 @@ fn cel_expressions(
             }});
         }
         ProtoType::Modified(ProtoModifiedValueType::Repeated(item))
-            if !options.cel_exprs.repeated_item.is_empty() || matches!(item, ProtoValueType::Message(_)) =>
+            if !options.cel_exprs.repeated_item.is_empty()
+                || matches!(item, ProtoValueType::Message(path) if registry.message_has_validation(path)) =>
         {
-            let is_message = matches!(item, ProtoValueType::Message(_));
+            let is_message = matches!(item, ProtoValueType::Message(path) if registry.message_has_validation(path));
             let mut compiler = compiler.child();
             if let ProtoValueType::Enum(path) = item {
                 compiler.register_function(functions::Enum(Some(path.clone())));
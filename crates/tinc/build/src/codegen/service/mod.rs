@@ -1,6 +1,6 @@
 use anyhow::Context;
 use indexmap::IndexMap;
-use openapi::{BodyMethod, GeneratedBody, GeneratedParams, InputGenerator, OutputGenerator};
+use openapi::{BodyMethod, GeneratedBody, GeneratedParams, InputGenerator, OutputGenerator, PaginationFields};
 use openapiv3_1::HttpMethod;
 use quote::{format_ident, quote};
 use syn::{Ident, parse_quote};
@@ -9,12 +9,185 @@ use tinc_pb_prost::http_endpoint_options;
 use super::Package;
 use super::utils::{field_ident_from_str, type_ident_from_str};
 use crate::types::{
-    Comments, ProtoPath, ProtoService, ProtoServiceMethod, ProtoServiceMethodEndpoint, ProtoServiceMethodIo,
+    Comments, ProtoPath, ProtoService, ProtoServiceMethod, ProtoServiceMethodEndpoint, ProtoServiceMethodIo, ProtoType,
     ProtoTypeRegistry, ProtoValueType,
 };
+use crate::{Cors, OpenApiConfig};
 
 mod openapi;
 
+struct MethodPagination {
+    fields: PaginationFields,
+    page_size_serde_name: String,
+    func_ident: Ident,
+    tokens: proc_macro2::TokenStream,
+}
+
+impl MethodPagination {
+    fn new(name: &str, method: &ProtoServiceMethod, types: &ProtoTypeRegistry) -> anyhow::Result<Option<Self>> {
+        let Some(pagination) = &method.pagination else {
+            return Ok(None);
+        };
+
+        let (
+            ProtoServiceMethodIo::Single(ProtoValueType::Message(input)),
+            ProtoServiceMethodIo::Single(ProtoValueType::Message(output)),
+        ) = (&method.input, &method.output)
+        else {
+            anyhow::bail!("pagination can only be used on unary methods with message input and output.");
+        };
+
+        let input = types.get_message(input).unwrap();
+        let output = types.get_message(output).unwrap();
+
+        let fields = PaginationFields {
+            page_size: pagination.page_size_field.clone().unwrap_or_else(|| "page_size".to_owned()),
+            page_token: pagination.page_token_field.clone().unwrap_or_else(|| "page_token".to_owned()),
+            next_page_token: pagination
+                .next_page_token_field
+                .clone()
+                .unwrap_or_else(|| "next_page_token".to_owned()),
+            default_page_size: pagination.default_page_size,
+            max_page_size: pagination.max_page_size,
+        };
+
+        let page_size = input
+            .fields
+            .get(&fields.page_size)
+            .with_context(|| format!("request message does not have page size field: {}", fields.page_size))?;
+        let signed = match &page_size.ty {
+            ProtoType::Value(ProtoValueType::Int32) => {
+                anyhow::ensure!(
+                    fields
+                        .default_page_size
+                        .into_iter()
+                        .chain(fields.max_page_size)
+                        .all(|v| v <= i32::MAX as u32),
+                    "page size bounds must fit in an int32"
+                );
+                true
+            }
+            ProtoType::Value(ProtoValueType::Int64) => true,
+            ProtoType::Value(ProtoValueType::UInt32 | ProtoValueType::UInt64) => false,
+            _ => anyhow::bail!("page size field must be a non-optional integer: {}", fields.page_size),
+        };
+
+        let page_token = input
+            .fields
+            .get(&fields.page_token)
+            .with_context(|| format!("request message does not have page token field: {}", fields.page_token))?;
+        anyhow::ensure!(
+            matches!(page_token.ty, ProtoType::Value(ProtoValueType::String)),
+            "page token field must be a non-optional string: {}",
+            fields.page_token
+        );
+
+        let next_page_token = output.fields.get(&fields.next_page_token).with_context(|| {
+            format!(
+                "response message does not have next page token field: {}",
+                fields.next_page_token
+            )
+        })?;
+        anyhow::ensure!(
+            matches!(next_page_token.ty, ProtoType::Value(ProtoValueType::String)),
+            "next page token field must be a non-optional string: {}",
+            fields.next_page_token
+        );
+
+        if let (Some(default), Some(max)) = (fields.default_page_size, fields.max_page_size) {
+            anyhow::ensure!(default <= max, "default page size cannot be larger than the max page size");
+        }
+
+        let page_size_ident = field_ident_from_str(&fields.page_size);
+        let negative_check = signed.then(|| {
+            quote! {
+                if target.#page_size_ident < 0 {
+                    return ::core::result::Result::Err("value must not be negative");
+                }
+            }
+        });
+        let default_page_size = fields.default_page_size.map(|default| {
+            let default = proc_macro2::Literal::u32_unsuffixed(default);
+            quote! {
+                if target.#page_size_ident == 0 {
+                    target.#page_size_ident = #default;
+                }
+            }
+        });
+        let max_page_size = fields.max_page_size.map(|max| {
+            let max = proc_macro2::Literal::u32_unsuffixed(max);
+            quote! {
+                if target.#page_size_ident > #max {
+                    target.#page_size_ident = #max;
+                }
+            }
+        });
+
+        Ok(Some(Self {
+            page_size_serde_name: page_size.options.serde_name.clone(),
+            fields,
+            func_ident: format_ident!("{}_pagination", field_ident_from_str(name)),
+            tokens: quote! {
+                #negative_check
+                #default_page_size
+                #max_page_size
+            },
+        }))
+    }
+}
+
+/// Splits the custom verb (e.g. `:cancel`) off a path which ends with `{param}:verb`.
+///
+/// axum cannot match a suffix of a path parameter, so these paths are routed without the
+/// verb and dispatched on it at runtime. A verb after a static segment (e.g. `/tasks:batchGet`)
+/// is matched literally and is not split off.
+fn split_custom_verb(path: &str) -> (&str, Option<&str>) {
+    let segment = path.rsplit('/').next().unwrap_or(path);
+    let Some(param_end) = segment.rfind('}') else {
+        return (path, None);
+    };
+
+    match &segment[param_end + 1..] {
+        verb if verb.len() > 1 && verb.starts_with(':') => (&path[..path.len() - verb.len()], Some(verb)),
+        _ => (path, None),
+    }
+}
+
+/// Replaces the names of the path parameters with `{}` (or `{*}` for wildcards).
+///
+/// axum rejects routes which only differ by the names of their parameters, so endpoints are
+/// grouped into routes by this shape.
+fn route_shape(path: &str) -> String {
+    let mut shape = String::with_capacity(path.len());
+    let mut chars = path.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        shape.push(ch);
+        if ch != '{' {
+            continue;
+        }
+
+        // Keep escaped '{{'
+        if let Some(&'{') = chars.peek() {
+            shape.push(chars.next().unwrap());
+            continue;
+        }
+
+        if let Some(&'*') = chars.peek() {
+            shape.push('*');
+        }
+
+        for c in &mut chars {
+            if c == '}' {
+                shape.push('}');
+                break;
+            }
+        }
+    }
+
+    shape
+}
+
 struct GeneratedMethod {
     function_body: proc_macro2::TokenStream,
     openapi: openapiv3_1::path::PathItem,
@@ -30,8 +203,10 @@ impl GeneratedMethod {
         service: &ProtoService,
         method: &ProtoServiceMethod,
         endpoint: &ProtoServiceMethodEndpoint,
+        pagination: Option<&MethodPagination>,
         types: &ProtoTypeRegistry,
         components: &mut openapiv3_1::Components,
+        tags: &[String],
     ) -> anyhow::Result<GeneratedMethod> {
         let (http_method_oa, path) = match &endpoint.method {
             tinc_pb_prost::http_endpoint_options::Method::Get(path) => (openapiv3_1::HttpMethod::Get, path),
@@ -53,6 +228,9 @@ impl GeneratedMethod {
         let target_ident = quote::format_ident!("target");
         let state_ident = quote::format_ident!("state");
         let mut openapi = openapiv3_1::path::Operation::new();
+        if !tags.is_empty() {
+            openapi.tags(tags);
+        }
         let mut generator = InputGenerator::new(
             types,
             components,
@@ -78,6 +256,32 @@ impl GeneratedMethod {
             }
         });
 
+        let pagination_tokens = if let Some(pagination) = pagination {
+            // The root query string already contains the pagination fields.
+            let bind_query = !matches!(
+                &request,
+                http_endpoint_options::request::Mode::Query(http_endpoint_options::request::QueryParams { field: None })
+            );
+            let GeneratedParams { tokens, params } = generator.generate_pagination(&pagination.fields, bind_query)?;
+            openapi.parameters(params);
+
+            let func_ident = &pagination.func_ident;
+            let page_size_serde_name = &pagination.page_size_serde_name;
+            quote! {
+                #tokens
+                if let ::core::result::Result::Err(message) = #func_ident(&mut #target_ident) {
+                    return ::tinc::__private::handle_pagination_error(
+                        #page_size_serde_name,
+                        message,
+                        &parts.headers,
+                        &*service.validation_error_formatter,
+                    );
+                }
+            }
+        } else {
+            quote!()
+        };
+
         let request_tokens = match request {
             http_endpoint_options::request::Mode::Query(http_endpoint_options::request::QueryParams { field }) => {
                 let GeneratedParams { tokens, params } = generator.generate_query_parameter(field.as_deref())?;
@@ -162,7 +366,13 @@ impl GeneratedMethod {
 
         let validate = if matches!(method.input.value_type(), ProtoValueType::Message(_)) {
             quote! {
-                if let Err(err) = ::tinc::__private::TincValidate::validate_http(&#target_ident, #state_ident, &#tracker_ident) {
+                if let Err(err) = ::tinc::__private::TincValidate::validate_http(
+                    &#target_ident,
+                    #state_ident,
+                    (body_format == ::tinc::__private::BodyFormat::Json).then_some(&#tracker_ident),
+                    &parts.headers,
+                    &*service.validation_error_formatter,
+                ) {
                     return err;
                 }
             }
@@ -171,22 +381,38 @@ impl GeneratedMethod {
         };
 
         let function_impl = quote! {
+            let timeout = match ::tinc::__private::request_timeout(&parts.headers, service.timeout_header.as_ref()) {
+                ::core::result::Result::Ok(timeout) => timeout,
+                ::core::result::Result::Err(err) => return err,
+            };
+
+            let response_format = ::tinc::__private::negotiate_response_format(&parts.headers);
+            let mut body_format = ::tinc::__private::BodyFormat::Json;
+
             let mut #state_ident = ::tinc::__private::TrackerSharedState::default();
             let mut #tracker_ident = <<#input_path as ::tinc::__private::TrackerFor>::Tracker as ::core::default::Default>::default();
             let mut #target_ident = <#input_path as ::core::default::Default>::default();
 
             #path_tokens
             #request_tokens
+            #pagination_tokens
 
             #validate
 
-            let request = ::tinc::reexports::tonic::Request::from_parts(
+            let mut request = ::tinc::reexports::tonic::Request::from_parts(
                 ::tinc::reexports::tonic::metadata::MetadataMap::from_headers(parts.headers),
                 parts.extensions,
                 target,
             );
 
-            let (metadata, #response_ident, extensions) = match service.inner.#service_method_name(request).await {
+            if let ::core::option::Option::Some(timeout) = timeout {
+                request.set_timeout(timeout);
+            }
+
+            let (metadata, #response_ident, extensions) = match ::tinc::__private::with_request_timeout(
+                timeout,
+                service.inner.#service_method_name(request),
+            ).await {
                 ::core::result::Result::Ok(response) => response.into_parts(),
                 ::core::result::Result::Err(status) => return ::tinc::__private::handle_tonic_status(&status),
             };
@@ -223,9 +449,9 @@ impl GeneratedMethod {
         let function_impl = &self.function_body;
 
         quote! {
-            #[allow(non_snake_case, unused_mut, dead_code, unused_variables, unused_parens)]
+            #[allow(non_snake_case, unused_mut, dead_code, unused_variables, unused_assignments, unused_parens)]
             async fn #function_name<T>(
-                ::tinc::reexports::axum::extract::State(service): ::tinc::reexports::axum::extract::State<#tinc_struct_name<T>>,
+                service: #tinc_struct_name<T>,
                 request: ::tinc::reexports::axum::extract::Request,
             ) -> ::tinc::reexports::axum::response::Response
             where
@@ -236,13 +462,132 @@ impl GeneratedMethod {
             }
         }
     }
+}
+
+/// An endpoint bound to a [`Route`].
+struct RouteEndpoint {
+    function_name: Ident,
+    /// The names of the path parameters, in the order they appear in the path.
+    params: Vec<String>,
+}
+
+/// The endpoints which share a route.
+///
+/// Endpoints share a route when their paths only differ by the names of their parameters or by
+/// a custom verb after the last parameter. They are dispatched on their http method and verb, and
+/// the path parameters are bound to the names of the endpoint at runtime.
+struct Route {
+    /// The path the route is registered with, the parameters are named after the first endpoint.
+    path: String,
+    params: Vec<String>,
+    methods: IndexMap<String, RouteMethod>,
+}
+
+/// The endpoints of a [`Route`] with the same http method, they only differ by their custom verb.
+#[derive(Default)]
+struct RouteMethod {
+    http_method: Option<Ident>,
+    fallback: Option<RouteEndpoint>,
+    verbs: Vec<(String, RouteEndpoint)>,
+}
+
+impl Route {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_owned(),
+            params: openapi::parse_route(path),
+            methods: IndexMap::new(),
+        }
+    }
+
+    fn insert(&mut self, verb: Option<&str>, http_method: Ident, endpoint: RouteEndpoint) -> anyhow::Result<()> {
+        let method = self.methods.entry(http_method.to_string()).or_default();
+        let duplicate = match verb {
+            Some(verb) => method.verbs.iter().any(|(v, _)| v == verb),
+            None => method.fallback.is_some(),
+        };
+        anyhow::ensure!(!duplicate, "the path is already bound to another endpoint");
+
+        method.http_method = Some(http_method);
+        match verb {
+            Some(verb) => method.verbs.push((verb.to_owned(), endpoint)),
+            None => method.fallback = Some(endpoint),
+        }
+
+        Ok(())
+    }
 
-    pub(crate) fn route(&self, function_name: &Ident) -> proc_macro2::TokenStream {
+    fn http_methods(&self) -> impl Iterator<Item = &Ident> {
+        self.methods.values().map(RouteMethod::http_method)
+    }
+
+    fn tokens(&self) -> proc_macro2::TokenStream {
         let path = &self.path;
-        let http_method = &self.http_method;
+        let methods = self.methods.values().map(|method| {
+            let http_method = method.http_method();
+            let handler = method.handler(&self.params);
+
+            quote! {
+                .route(#path, ::tinc::reexports::axum::routing::#http_method({
+                    let service = ::std::clone::Clone::clone(&self);
+                    move |::tinc::reexports::axum::extract::State(state): ::tinc::reexports::axum::extract::State<S>, mut request: ::tinc::reexports::axum::extract::Request| {
+                        let service = ::std::clone::Clone::clone(&service);
+                        async move {
+                            request.extensions_mut().insert(state);
+                            let cors = ::tinc::__private::CorsRequest::new(service.cors.as_ref(), request.headers());
+                            cors.apply(#handler)
+                        }
+                    }
+                }))
+            }
+        });
+
+        quote! { #(#methods)* }
+    }
+}
+
+impl RouteMethod {
+    fn http_method(&self) -> &Ident {
+        self.http_method.as_ref().expect("route without endpoints")
+    }
+
+    fn handler(&self, route_params: &[String]) -> proc_macro2::TokenStream {
+        let call = |endpoint: &RouteEndpoint, verb: Option<&str>| {
+            let function_name = &endpoint.function_name;
+            if verb.is_none() && endpoint.params == route_params {
+                return quote! { #function_name::<T>(service, request).await };
+            }
+
+            let verb = match verb {
+                Some(verb) => quote! { ::core::option::Option::Some(#verb) },
+                None => quote! { ::core::option::Option::None },
+            };
+            let params = &endpoint.params;
+            quote! {{
+                ::tinc::__private::bind_path(&mut request, #verb, &[#(#params),*]);
+                #function_name::<T>(service, request).await
+            }}
+        };
+
+        let fallback = match &self.fallback {
+            Some(endpoint) => call(endpoint, None),
+            None => quote! {
+                ::tinc::reexports::axum::response::IntoResponse::into_response(::tinc::reexports::http::StatusCode::NOT_FOUND)
+            },
+        };
+
+        if self.verbs.is_empty() {
+            return fallback;
+        }
+
+        let verbs = self.verbs.iter().map(|(verb, _)| verb).collect::<Vec<_>>();
+        let calls = self.verbs.iter().map(|(verb, endpoint)| call(endpoint, Some(verb)));
 
         quote! {
-            .route(#path, ::tinc::reexports::axum::routing::#http_method(#function_name::<T>))
+            match ::tinc::__private::match_custom_verb(&request, &[#(#verbs),*]) {
+                #(::core::option::Option::Some(#verbs) => #calls,)*
+                _ => #fallback,
+            }
         }
     }
 }
@@ -277,6 +622,8 @@ pub(super) fn handle_service(
     service: &ProtoService,
     package: &mut Package,
     registry: &ProtoTypeRegistry,
+    openapi_config: &OpenApiConfig,
+    cors: Option<&Cors>,
 ) -> anyhow::Result<()> {
     let name = service
         .full_name
@@ -295,16 +642,29 @@ pub(super) fn handle_service(
     let tinc_struct_name = quote::format_ident!("{pascal_name}Tinc");
 
     let mut method_tokens = Vec::new();
-    let mut route_tokens = Vec::new();
+    let mut routes = IndexMap::<String, Route>::new();
     let mut method_codecs = Vec::new();
     let mut methods = IndexMap::new();
+    let mut route_descriptors = Vec::new();
 
     let package_name = format!("{}.{tinc_module_name}", service.package);
+    let tags = openapi_config.service_tags(&service.full_name);
 
     for (name, method) in service.methods.iter() {
+        let pagination = MethodPagination::new(name, method, registry).with_context(|| format!("method {name}"))?;
+
         for (idx, endpoint) in method.endpoints.iter().enumerate() {
-            let gen_method =
-                GeneratedMethod::new(name, &package_name, service, method, endpoint, registry, &mut components)?;
+            let gen_method = GeneratedMethod::new(
+                name,
+                &package_name,
+                service,
+                method,
+                endpoint,
+                pagination.as_ref(),
+                registry,
+                &mut components,
+                &tags,
+            )?;
             let function_name = quote::format_ident!("{name}_{idx}");
 
             method_tokens.push(gen_method.method_handler(
@@ -313,7 +673,34 @@ pub(super) fn handle_service(
                 &pascal_name,
                 &tinc_struct_name,
             ));
-            route_tokens.push(gen_method.route(&function_name));
+
+            let (route_path, verb) = split_custom_verb(&gen_method.path);
+            let endpoint = RouteEndpoint {
+                function_name,
+                params: openapi::parse_route(route_path),
+            };
+            routes
+                .entry(route_shape(route_path))
+                .or_insert_with(|| Route::new(route_path))
+                .insert(verb, gen_method.http_method.clone(), endpoint)
+                .with_context(|| format!("method {name}: {} {}", gen_method.http_method, gen_method.path))?;
+
+            let http_method_const = format_ident!("{}", gen_method.http_method.to_string().to_uppercase());
+            let service_name = &*service.full_name;
+            let path = &gen_method.path;
+            let request_type = method.input.value_type().proto_path();
+            let response_type = method.output.value_type().proto_path();
+            route_descriptors.push(quote! {
+                ::tinc::TincRoute {
+                    service: #service_name,
+                    method: #name,
+                    http_method: ::tinc::reexports::http::Method::#http_method_const,
+                    path: #path,
+                    request_type: #request_type,
+                    response_type: #response_type,
+                }
+            });
+
             paths = paths.path(gen_method.path, gen_method.openapi);
         }
 
@@ -321,6 +708,28 @@ pub(super) fn handle_service(
             let input_path = registry.resolve_rust_path(&package_name, method.input.value_type().proto_path());
             let output_path = registry.resolve_rust_path(&package_name, method.output.value_type().proto_path());
             let codec_ident = format_ident!("{name}Codec");
+
+            let (item_binding, paginate) = if let Some(pagination) = &pagination {
+                let func_ident = &pagination.func_ident;
+                let tokens = &pagination.tokens;
+                method_codecs.push(quote! {
+                    #[allow(clippy::all)]
+                    fn #func_ident(target: &mut #input_path) -> ::core::result::Result<(), &'static str> {
+                        #tokens
+                        ::core::result::Result::Ok(())
+                    }
+                });
+
+                (
+                    quote!(mut item),
+                    quote! {
+                        #func_ident(&mut item).map_err(::tinc::reexports::tonic::Status::invalid_argument)?;
+                    },
+                )
+            } else {
+                (quote!(item), quote!())
+            };
+
             method_codecs.push(quote! {
                 #[derive(Debug, Clone, Default)]
                 #[doc(hidden)]
@@ -363,7 +772,8 @@ pub(super) fn handle_service(
 
                         fn decode(&mut self, buf: &mut ::tinc::reexports::tonic::codec::DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
                             match ::tinc::reexports::tonic::codec::Decoder::decode(&mut self.0, buf) {
-                                ::core::result::Result::Ok(::core::option::Option::Some(item)) => {
+                                ::core::result::Result::Ok(::core::option::Option::Some(#item_binding)) => {
+                                    #paginate
                                     ::tinc::__private::TincValidate::validate_tonic(&item)?;
                                     ::core::result::Result::Ok(::core::option::Option::Some(item))
                                 },
@@ -394,7 +804,42 @@ pub(super) fn handle_service(
         );
     }
 
-    let openapi = openapiv3_1::OpenApi::builder().components(components).paths(paths).build();
+    let route_tokens = routes.values().map(Route::tokens);
+
+    let preflight_tokens = routes.values().map(|route| {
+        let path = &route.path;
+        let http_methods = route
+            .http_methods()
+            .map(|http_method| format_ident!("{}", http_method.to_string().to_uppercase()));
+
+        quote! {
+            .route(#path, ::tinc::reexports::axum::routing::options({
+                let cors = ::std::clone::Clone::clone(&cors);
+                move |headers: ::tinc::reexports::http::HeaderMap| async move {
+                    ::tinc::__private::cors_preflight(&cors, &headers, &[#(::tinc::reexports::http::Method::#http_methods),*])
+                }
+            }))
+        }
+    });
+
+    let default_cors = match cors {
+        Some(cors) => {
+            let policy = cors.tokens().context("invalid cors policy")?;
+            quote!(::core::option::Option::Some(::std::sync::Arc::new(#policy)))
+        }
+        None => quote!(::core::option::Option::None),
+    };
+
+    let openapi = openapiv3_1::OpenApi::builder()
+        .info(openapi_config.info())
+        .maybe_servers(openapi_config.servers())
+        .components(components)
+        .paths(paths)
+        .maybe_tags((!tags.is_empty()).then(|| tags.iter().map(|tag| openapi_config.tag(tag)).collect::<Vec<_>>()))
+        .maybe_extensions(
+            cors.map(|cors| openapiv3_1::extensions::Extensions::new([("x-tinc-cors", cors.openapi_extension())])),
+        )
+        .build();
 
     let json_openapi = openapi.to_json().context("invalid openapi schema generation")?;
 
@@ -411,26 +856,68 @@ pub(super) fn handle_service(
                 irrefutable_let_patterns,
             )]
 
+            /// The http routes of this service.
+            pub const ROUTES: &[::tinc::TincRoute] = &[#(#route_descriptors),*];
+
             /// A tinc service struct that exports gRPC routes via an axum router.
             pub struct #tinc_struct_name<T> {
                 inner: ::std::sync::Arc<T>,
+                validation_error_formatter: ::std::sync::Arc<dyn ::tinc::validation::ValidationErrorFormatter>,
+                timeout_header: ::core::option::Option<::tinc::reexports::http::HeaderName>,
+                cors: ::core::option::Option<::std::sync::Arc<::tinc::cors::CorsPolicy>>,
             }
 
             impl<T> #tinc_struct_name<T> {
                 /// Create a new tinc service struct from a service implementation.
                 pub fn new(inner: T) -> Self {
-                    Self { inner: ::std::sync::Arc::new(inner) }
+                    Self::from_arc(::std::sync::Arc::new(inner))
                 }
 
                 /// Create a new tinc service struct from an existing `Arc`.
                 pub fn from_arc(inner: ::std::sync::Arc<T>) -> Self {
-                    Self { inner }
+                    Self {
+                        inner,
+                        validation_error_formatter: ::std::sync::Arc::new(::tinc::validation::DefaultValidationErrorFormatter),
+                        timeout_header: ::core::option::Option::None,
+                        cors: #default_cors,
+                    }
+                }
+
+                /// Replace the formatter used to build responses for requests which fail validation.
+                pub fn with_validation_error_formatter(
+                    mut self,
+                    formatter: impl ::tinc::validation::ValidationErrorFormatter,
+                ) -> Self {
+                    self.validation_error_formatter = ::std::sync::Arc::new(formatter);
+                    self
+                }
+
+                /// Read the request timeout from this header in addition to `grpc-timeout`.
+                ///
+                /// The value is either in the `grpc-timeout` format (e.g. `500m`) or a plain number of milliseconds.
+                /// When the timeout elapses the request fails with `504 Gateway Timeout`.
+                pub fn with_timeout_header(mut self, header: ::tinc::reexports::http::HeaderName) -> Self {
+                    self.timeout_header = ::core::option::Option::Some(header);
+                    self
+                }
+
+                /// Answer CORS preflight requests and add CORS headers to the responses with this policy.
+                ///
+                /// This replaces the policy configured at build time, the OpenAPI document is not updated.
+                pub fn with_cors(mut self, policy: ::tinc::cors::CorsPolicy) -> Self {
+                    self.cors = ::core::option::Option::Some(::std::sync::Arc::new(policy));
+                    self
                 }
             }
 
             impl<T> ::std::clone::Clone for #tinc_struct_name<T> {
                 fn clone(&self) -> Self {
-                    Self { inner: ::std::clone::Clone::clone(&self.inner) }
+                    Self {
+                        inner: ::std::clone::Clone::clone(&self.inner),
+                        validation_error_formatter: ::std::clone::Clone::clone(&self.validation_error_formatter),
+                        timeout_header: ::std::clone::Clone::clone(&self.timeout_header),
+                        cors: ::std::clone::Clone::clone(&self.cors),
+                    }
                 }
             }
 
@@ -440,21 +927,45 @@ pub(super) fn handle_service(
                 }
             }
 
-            impl<T> ::tinc::TincService for #tinc_struct_name<T>
+            impl<T> #tinc_struct_name<T>
             where
                 T: super::#server_module_name::#pascal_name
             {
-                fn into_router(self) -> ::tinc::reexports::axum::Router {
+                /// Convert the service into an axum router which requires the application state `S`.
+                ///
+                /// Provide the state with `Router::with_state`, it is inserted into the extensions of every
+                /// request so the service implementation can access it with `request.extensions().get::<S>()`.
+                pub fn into_router_with_state<S>(self) -> ::tinc::reexports::axum::Router<S>
+                where
+                    S: ::core::clone::Clone + ::core::marker::Send + ::core::marker::Sync + 'static,
+                {
                     #(#method_tokens)*
 
-                    ::tinc::reexports::axum::Router::new()
-                        #(#route_tokens)*
-                        .with_state(self)
+                    let router = ::tinc::reexports::axum::Router::new()
+                        #(#route_tokens)*;
+
+                    match ::std::clone::Clone::clone(&self.cors) {
+                        ::core::option::Option::Some(cors) => router #(#preflight_tokens)*,
+                        ::core::option::Option::None => router,
+                    }
+                }
+            }
+
+            impl<T> ::tinc::TincService for #tinc_struct_name<T>
+            where
+                T: super::#server_module_name::#pascal_name
+            {
+                fn into_router(self) -> ::tinc::reexports::axum::Router {
+                    self.into_router_with_state().with_state(())
                 }
 
                 fn openapi_schema_str(&self) -> &'static str {
                     #json_openapi
                 }
+
+                fn routes(&self) -> &'static [::tinc::TincRoute] {
+                    ROUTES
+                }
             }
 
             #(#method_codecs)*
@@ -11,7 +11,9 @@ use tinc_cel::{CelValue, NumberTy};
 use crate::codegen::cel::compiler::{CompiledExpr, Compiler, CompilerTarget, ConstantCompiledExpr};
 use crate::codegen::cel::{CelExpression, CelExpressions, functions};
 use crate::codegen::utils::field_ident_from_str;
-use crate::types::{ProtoModifiedValueType, ProtoType, ProtoTypeRegistry, ProtoValueType, ProtoWellKnownType};
+use crate::types::{
+    ProtoBytesEncoding, ProtoModifiedValueType, ProtoType, ProtoTypeRegistry, ProtoValueType, ProtoWellKnownType,
+};
 
 fn cel_to_json(cel: &CelValue<'static>, type_registry: &ProtoTypeRegistry) -> anyhow::Result<serde_json::Value> {
     match cel {
@@ -107,9 +109,23 @@ enum ExcludePaths {
 #[derive(Debug, Clone, Copy)]
 enum BytesEncoding {
     Base64,
+    Base64Url,
+    Hex,
     Binary,
 }
 
+impl BytesEncoding {
+    /// The encoding of a field, binary bodies are never re-encoded.
+    fn for_field(self, encoding: ProtoBytesEncoding) -> Self {
+        match (self, encoding) {
+            (BytesEncoding::Binary, _) => BytesEncoding::Binary,
+            (_, ProtoBytesEncoding::Base64) => BytesEncoding::Base64,
+            (_, ProtoBytesEncoding::Base64Url) => BytesEncoding::Base64Url,
+            (_, ProtoBytesEncoding::Hex) => BytesEncoding::Hex,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(super) enum BodyMethod<'a> {
     Text,
@@ -140,6 +156,11 @@ impl BodyMethod<'_> {
         }
     }
 
+    /// Json bodies which are the whole message can also be sent as protobuf.
+    fn negotiates_protobuf(&self, field: Option<&str>, root_ty: &ProtoValueType) -> bool {
+        matches!(self, BodyMethod::Json) && field.is_none() && matches!(root_ty, ProtoValueType::Message(_))
+    }
+
     fn default_content_type(&self) -> &'static str {
         match self {
             BodyMethod::Binary(_) => "application/octet-stream",
@@ -149,6 +170,21 @@ impl BodyMethod<'_> {
     }
 }
 
+const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Protobuf encoded bodies are documented as opaque binary data.
+fn protobuf_schema(components: &mut openapiv3_1::Components, types: &ProtoTypeRegistry) -> anyhow::Result<Schema> {
+    generate(
+        components,
+        types,
+        &BTreeMap::new(),
+        &CelExpressions::default(),
+        ProtoType::Value(ProtoValueType::Bytes),
+        GenerateDirection::Input,
+        BytesEncoding::Binary,
+    )
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum GenerateDirection {
     Input,
@@ -275,7 +311,7 @@ fn output_field_getter_gen(
     })
 }
 
-fn parse_route(route: &str) -> Vec<String> {
+pub(super) fn parse_route(route: &str) -> Vec<String> {
     let mut params = Vec::new();
     let mut chars = route.chars().peekable();
 
@@ -445,6 +481,15 @@ fn path_struct(
     })
 }
 
+/// The resolved pagination fields of a method, see `tinc.PaginationOptions`.
+pub(super) struct PaginationFields {
+    pub page_size: String,
+    pub page_token: String,
+    pub next_page_token: String,
+    pub default_page_size: Option<u32>,
+    pub max_page_size: Option<u32>,
+}
+
 pub(super) struct InputGenerator<'a> {
     used_paths: BTreeMap<String, ExcludePaths>,
     types: &'a ProtoTypeRegistry,
@@ -610,7 +655,7 @@ impl InputGenerator<'_> {
                         &field.options.cel_exprs,
                         field.ty.clone(),
                         GenerateDirection::Input,
-                        BytesEncoding::Base64,
+                        BytesEncoding::Base64.for_field(field.options.bytes_encoding),
                     )?)
                     .parameter_in(openapiv3_1::path::ParameterIn::Query)
                     .build(),
@@ -636,6 +681,106 @@ impl InputGenerator<'_> {
         })
     }
 
+    pub(super) fn generate_pagination(
+        &mut self,
+        pagination: &PaginationFields,
+        bind_query: bool,
+    ) -> anyhow::Result<GeneratedParams> {
+        self.consume_field(&pagination.page_size)?;
+        self.consume_field(&pagination.page_token)?;
+
+        let message_ty = match &self.root_ty {
+            ProtoValueType::Message(path) => self.types.get_message(path).unwrap(),
+            _ => anyhow::bail!("pagination can only be used on message types."),
+        };
+
+        let mut page_size_description = "The maximum number of items to return.".to_owned();
+        if let Some(default) = pagination.default_page_size {
+            page_size_description.push_str(&format!(" Defaults to {default}."));
+        }
+        if let Some(max) = pagination.max_page_size {
+            page_size_description.push_str(&format!(" Values above {max} are lowered to {max}."));
+        }
+
+        let mut params = Vec::new();
+        let mut defs = Vec::new();
+        let mut mappings = Vec::new();
+        for (idx, (field_name, description)) in [
+            (&pagination.page_size, page_size_description.as_str()),
+            (
+                &pagination.page_token,
+                "The token of the page to return, as returned by a previous request. Omit to return the first page.",
+            ),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let field = message_ty.fields.get(field_name).expect("pagination field not found");
+            let serde_name = &field.options.serde_name;
+
+            params.push(
+                openapiv3_1::path::Parameter::builder()
+                    .name(serde_name.clone())
+                    .required(!field.options.serde_omittable.is_true())
+                    .description(description)
+                    .schema(generate(
+                        self.components,
+                        self.types,
+                        &BTreeMap::new(),
+                        &field.options.cel_exprs,
+                        field.ty.clone(),
+                        GenerateDirection::Input,
+                        BytesEncoding::Base64,
+                    )?)
+                    .parameter_in(openapiv3_1::path::ParameterIn::Query)
+                    .build(),
+            );
+
+            let query_field_ident = quote::format_ident!("field_{idx}");
+            let ty = match &field.ty {
+                ProtoType::Value(ProtoValueType::Int32) => quote!(::core::primitive::i32),
+                ProtoType::Value(ProtoValueType::Int64) => quote!(::core::primitive::i64),
+                ProtoType::Value(ProtoValueType::UInt32) => quote!(::core::primitive::u32),
+                ProtoType::Value(ProtoValueType::UInt64) => quote!(::core::primitive::u64),
+                _ => quote!(::std::string::String),
+            };
+
+            defs.push(quote! {
+                #[serde(rename = #serde_name, default)]
+                #query_field_ident: ::core::option::Option<#ty>
+            });
+
+            let extract = input_field_getter_gen(self.types, &self.root_ty, self.base_extract(), field_name)?.tokens;
+            mappings.push(quote! {
+                if let ::core::option::Option::Some(value) = query.#query_field_ident {
+                    let (tracker, target) = #extract;
+                    *target = value;
+                }
+            });
+        }
+
+        let tokens = if bind_query {
+            quote!({
+                #[derive(::tinc::reexports::serde::Deserialize)]
+                #[allow(non_snake_case, dead_code)]
+                struct ____PaginationQuery {
+                    #(#defs),*
+                }
+
+                let query = match ::tinc::__private::deserialize_query::<____PaginationQuery>(&parts) {
+                    Ok(query) => query,
+                    Err(err) => return err,
+                };
+
+                #(#mappings)*
+            })
+        } else {
+            TokenStream::new()
+        };
+
+        Ok(GeneratedParams { tokens, params })
+    }
+
     pub(super) fn generate_path_parameter(&mut self, path: &str) -> anyhow::Result<GeneratedParams> {
         let params = parse_route(path);
         if params.is_empty() {
@@ -775,32 +920,53 @@ impl InputGenerator<'_> {
             }
         }
 
+        let protobuf = body_method.negotiates_protobuf(field, &self.root_ty);
         let func = body_method.deserialize_method();
         let tokens = &extract.tokens;
         let state_ident = &self.state_ident;
 
+        let deserialize = if protobuf {
+            quote! {
+                body_format = match ::tinc::__private::deserialize_body_json_or_protobuf(&parts, body, tracker, target, &mut #state_ident).await {
+                    ::core::result::Result::Ok(format) => format,
+                    ::core::result::Result::Err(err) => return err,
+                };
+            }
+        } else {
+            quote! {
+                if let Err(err) = ::tinc::__private::#func(&parts, body, tracker, target, &mut #state_ident).await {
+                    return err;
+                }
+            }
+        };
+
+        let mut body = openapiv3_1::request_body::RequestBody::builder().content(
+            body_method.content_type(),
+            openapiv3_1::content::Content::new(Some(generate(
+                self.components,
+                self.types,
+                exclude_paths.unwrap_or(&BTreeMap::new()),
+                &extract.cel,
+                extract.ty,
+                GenerateDirection::Input,
+                body_method.bytes_encoding(),
+            )?)),
+        );
+
+        if protobuf {
+            body = body.content(
+                PROTOBUF_CONTENT_TYPE,
+                openapiv3_1::content::Content::new(Some(protobuf_schema(self.components, self.types)?)),
+            );
+        }
+
         Ok(GeneratedBody {
             tokens: quote! {{
                 #content_type
                 let (tracker, target) = #tokens;
-                if let Err(err) = ::tinc::__private::#func(&parts, body, tracker, target, &mut #state_ident).await {
-                    return err;
-                }
+                #deserialize
             }},
-            body: openapiv3_1::request_body::RequestBody::builder()
-                .content(
-                    body_method.content_type(),
-                    openapiv3_1::content::Content::new(Some(generate(
-                        self.components,
-                        self.types,
-                        exclude_paths.unwrap_or(&BTreeMap::new()),
-                        &extract.cel,
-                        extract.ty,
-                        GenerateDirection::Input,
-                        body_method.bytes_encoding(),
-                    )?)),
-                )
-                .build(),
+            body: body.build(),
         })
     }
 }
@@ -859,9 +1025,29 @@ impl OutputGenerator<'_> {
             }
         };
 
+        let protobuf = body_method.negotiates_protobuf(field, &self.root_ty);
         let tokens = extract.tokens;
 
         let tokens = match body_method {
+            BodyMethod::Json if protobuf => quote!({
+                if response_format == ::tinc::__private::BodyFormat::Protobuf {
+                    #builder_ident
+                        .header(::tinc::reexports::http::header::CONTENT_TYPE, ::tinc::__private::PROTOBUF_CONTENT_TYPE)
+                        .body(::tinc::reexports::axum::body::Body::from(
+                            ::tinc::reexports::prost::Message::encode_to_vec(#tokens)
+                        ))
+                } else {
+                    let mut writer = ::tinc::reexports::bytes::BufMut::writer(
+                        ::tinc::reexports::bytes::BytesMut::with_capacity(128)
+                    );
+                    match ::tinc::reexports::serde_json::to_writer(&mut writer, #tokens) {
+                        ::core::result::Result::Ok(()) => {},
+                        ::core::result::Result::Err(err) => return ::tinc::__private::handle_response_build_error(err),
+                    }
+                    (#content_type)
+                        .body(::tinc::reexports::axum::body::Body::from(writer.into_inner().freeze()))
+                }
+            }),
             BodyMethod::Json => quote!({
                 let mut writer = ::tinc::reexports::bytes::BufMut::writer(
                     ::tinc::reexports::bytes::BytesMut::with_capacity(128)
@@ -921,23 +1107,29 @@ impl OutputGenerator<'_> {
             }
         };
 
+        let mut body = openapiv3_1::Response::builder().content(
+            body_method.content_type(),
+            openapiv3_1::Content::new(Some(generate(
+                self.components,
+                self.types,
+                &BTreeMap::new(),
+                &extract.cel,
+                extract.ty,
+                GenerateDirection::Output,
+                body_method.bytes_encoding(),
+            )?)),
+        );
+
+        if protobuf {
+            body = body.content(
+                PROTOBUF_CONTENT_TYPE,
+                openapiv3_1::Content::new(Some(protobuf_schema(self.components, self.types)?)),
+            );
+        }
+
         Ok(GeneratedBody {
             tokens,
-            body: openapiv3_1::Response::builder()
-                .content(
-                    body_method.content_type(),
-                    openapiv3_1::Content::new(Some(generate(
-                        self.components,
-                        self.types,
-                        &BTreeMap::new(),
-                        &extract.cel,
-                        extract.ty,
-                        GenerateDirection::Output,
-                        body_method.bytes_encoding(),
-                    )?)),
-                )
-                .description("")
-                .build(),
+            body: body.description("").build(),
         })
     }
 }
@@ -1051,7 +1243,7 @@ fn generate(
                                     &field.options.cel_exprs,
                                     ProtoType::Value(field.ty),
                                     direction,
-                                    bytes,
+                                    bytes.for_field(field.options.bytes_encoding),
                                 )?;
 
                                 anyhow::Ok(Schema::object(
@@ -1094,7 +1286,7 @@ fn generate(
                                     &field.options.cel_exprs,
                                     ProtoType::Value(field.ty),
                                     direction,
-                                    bytes,
+                                    bytes.for_field(field.options.bytes_encoding),
                                 )?;
 
                                 anyhow::Ok(Schema::object(
@@ -1125,15 +1317,20 @@ fn generate(
                     .build(),
             ),
             ProtoType::Value(ProtoValueType::Bool) => Schema::object(Object::builder().schema_type(Type::Boolean).build()),
-            ProtoType::Value(ProtoValueType::Bytes) => Schema::object(
-                Object::builder()
+            ProtoType::Value(ProtoValueType::Bytes) => Schema::object(match bytes {
+                BytesEncoding::Base64 => Object::builder().schema_type(Type::String).content_encoding("base64").build(),
+                BytesEncoding::Base64Url => Object::builder()
                     .schema_type(Type::String)
-                    .content_encoding(match bytes {
-                        BytesEncoding::Base64 => "base64",
-                        BytesEncoding::Binary => "binary",
-                    })
+                    .format("base64url")
+                    .content_encoding("base64url")
                     .build(),
-            ),
+                BytesEncoding::Hex => Object::builder()
+                    .schema_type(Type::String)
+                    .content_encoding("base16")
+                    .pattern("^([0-9a-fA-F]{2})*$")
+                    .build(),
+                BytesEncoding::Binary => Object::builder().schema_type(Type::String).content_encoding("binary").build(),
+            }),
             ProtoType::Value(ProtoValueType::Double | ProtoValueType::Float) => {
                 Schema::object(Object::builder().schema_type(Type::Number).build())
             }
@@ -1241,7 +1438,7 @@ fn generate(
                             &field.options.cel_exprs,
                             ty,
                             direction,
-                            bytes,
+                            bytes.for_field(field.options.bytes_encoding),
                         )?;
 
                         if field.options.flatten {
@@ -0,0 +1,151 @@
+use quote::{format_ident, quote};
+
+/// A CORS policy attached to generated services with [`Config::cors`](crate::Config::cors).
+///
+/// This is the build time equivalent of `tinc::cors::CorsPolicy`, the policy is compiled into the
+/// generated service and documented in its OpenAPI document with the `x-tinc-cors` extension.
+#[derive(Debug, Clone, Default)]
+pub struct Cors {
+    allow_origins: Vec<String>,
+    allow_methods: Option<Vec<String>>,
+    allow_headers: Vec<String>,
+    expose_headers: Vec<String>,
+    allow_credentials: bool,
+    max_age: Option<u64>,
+}
+
+impl Cors {
+    /// Create a new policy which does not allow any origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow requests from the given origin, e.g. `https://example.com`, or from any origin with `*`.
+    pub fn allow_origin(mut self, origin: impl std::fmt::Display) -> Self {
+        self.allow_origins.push(origin.to_string());
+        self
+    }
+
+    /// Only allow the given http method, instead of the methods of the requested route.
+    pub fn allow_method(mut self, method: impl std::fmt::Display) -> Self {
+        self.allow_methods
+            .get_or_insert_default()
+            .push(method.to_string().to_uppercase());
+        self
+    }
+
+    /// Allow requests to send the given header, or any header with `*`.
+    pub fn allow_header(mut self, header: impl std::fmt::Display) -> Self {
+        self.allow_headers.push(header.to_string().to_lowercase());
+        self
+    }
+
+    /// Allow the browser to expose the given response header to scripts.
+    pub fn expose_header(mut self, header: impl std::fmt::Display) -> Self {
+        self.expose_headers.push(header.to_string().to_lowercase());
+        self
+    }
+
+    /// Allow requests with credentials, like cookies or the `Authorization` header.
+    pub fn allow_credentials(mut self) -> Self {
+        self.allow_credentials = true;
+        self
+    }
+
+    /// Let browsers cache the result of a preflight request for the given number of seconds.
+    pub fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// The expression creating the `tinc::cors::CorsPolicy`.
+    pub(crate) fn tokens(&self) -> anyhow::Result<proc_macro2::TokenStream> {
+        let mut tokens = quote!(::tinc::cors::CorsPolicy::new());
+
+        for origin in &self.allow_origins {
+            tokens = if origin == "*" {
+                quote!(#tokens.allow_any_origin())
+            } else {
+                anyhow::ensure!(origin.bytes().all(|b| b.is_ascii_graphic()), "invalid cors origin: {origin}");
+                quote!(#tokens.allow_origin(::tinc::reexports::http::HeaderValue::from_static(#origin)))
+            };
+        }
+
+        if let Some(methods) = &self.allow_methods {
+            let methods = methods
+                .iter()
+                .map(|method| {
+                    anyhow::ensure!(
+                        matches!(
+                            method.as_str(),
+                            "GET" | "HEAD" | "POST" | "PUT" | "DELETE" | "PATCH" | "OPTIONS" | "TRACE" | "CONNECT"
+                        ),
+                        "invalid cors method: {method}"
+                    );
+                    let method = format_ident!("{method}");
+                    Ok(quote!(::tinc::reexports::http::Method::#method))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            tokens = quote!(#tokens.allow_methods([#(#methods),*]));
+        }
+
+        let allow_headers = self.allow_headers.iter().filter(|header| *header != "*").collect::<Vec<_>>();
+        if allow_headers.len() != self.allow_headers.len() {
+            tokens = quote!(#tokens.allow_any_header());
+        }
+
+        if !allow_headers.is_empty() {
+            allow_headers.iter().try_for_each(|header| validate_header_name(header))?;
+            tokens = quote!(#tokens.allow_headers([#(::tinc::reexports::http::HeaderName::from_static(#allow_headers)),*]));
+        }
+
+        if !self.expose_headers.is_empty() {
+            let expose_headers = &self.expose_headers;
+            expose_headers.iter().try_for_each(|header| validate_header_name(header))?;
+            tokens =
+                quote!(#tokens.expose_headers([#(::tinc::reexports::http::HeaderName::from_static(#expose_headers)),*]));
+        }
+
+        if self.allow_credentials {
+            tokens = quote!(#tokens.allow_credentials(true));
+        }
+
+        if let Some(max_age) = self.max_age {
+            tokens = quote!(#tokens.max_age(::core::time::Duration::from_secs(#max_age)));
+        }
+
+        Ok(tokens)
+    }
+
+    /// The value of the `x-tinc-cors` OpenAPI extension.
+    pub(crate) fn openapi_extension(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "allowOrigins": self.allow_origins,
+            "allowHeaders": self.allow_headers,
+            "exposeHeaders": self.expose_headers,
+            "allowCredentials": self.allow_credentials,
+        });
+
+        if let Some(methods) = &self.allow_methods {
+            value["allowMethods"] = methods.clone().into();
+        }
+
+        if let Some(max_age) = self.max_age {
+            value["maxAge"] = max_age.into();
+        }
+
+        value
+    }
+}
+
+/// `HeaderName::from_static` panics on invalid names, so they are rejected at build time.
+fn validate_header_name(name: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !name.is_empty()
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)),
+        "invalid cors header: {name}"
+    );
+    Ok(())
+}
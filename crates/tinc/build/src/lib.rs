@@ -35,6 +35,7 @@ use std::path::Path;
 use anyhow::Context;
 use extern_paths::ExternPaths;
 mod codegen;
+mod cors;
 mod extern_paths;
 
 #[cfg(feature = "prost")]
@@ -42,6 +43,8 @@ mod prost_explore;
 
 mod types;
 
+pub use cors::Cors;
+
 /// The mode to use for the generator, currently we only support `prost` codegen.
 #[derive(Debug, Clone, Copy)]
 pub enum Mode {
@@ -66,6 +69,65 @@ struct PathConfigs {
     btree_maps: Vec<String>,
     bytes: Vec<String>,
     boxed: Vec<String>,
+    type_attributes: Vec<(String, String)>,
+    message_attributes: Vec<(String, String)>,
+    enum_attributes: Vec<(String, String)>,
+    field_attributes: Vec<(String, String)>,
+}
+
+#[derive(Default, Debug)]
+struct OpenApiConfig {
+    title: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    servers: Vec<String>,
+    service_tags: Vec<(String, String)>,
+    tag_descriptions: Vec<(String, String)>,
+}
+
+impl OpenApiConfig {
+    fn info(&self) -> openapiv3_1::Info {
+        let mut info = openapiv3_1::Info::new(
+            self.title.clone().unwrap_or_default(),
+            self.version.clone().unwrap_or_default(),
+        );
+        info.description = self.description.clone();
+        info
+    }
+
+    fn servers(&self) -> Option<Vec<openapiv3_1::Server>> {
+        (!self.servers.is_empty()).then(|| self.servers.iter().map(openapiv3_1::Server::new).collect())
+    }
+
+    /// The tags of a service, in the order they were added.
+    fn service_tags(&self, service: &str) -> Vec<String> {
+        let mut tags = Vec::new();
+        for (path, tag) in &self.service_tags {
+            if path_matches(path, service) && !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+
+    fn tag(&self, name: &str) -> openapiv3_1::Tag {
+        let mut tag = openapiv3_1::Tag::new(name);
+        tag.description = self
+            .tag_descriptions
+            .iter()
+            .rev()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, description)| description.clone());
+        tag
+    }
+}
+
+/// Matches a proto path the same way `prost-build` does, `.` matches everything
+/// and `.my_package` matches everything inside `my_package`.
+fn path_matches(pattern: &str, full_name: &str) -> bool {
+    let pattern = pattern.trim_start_matches('.');
+    let full_name = full_name.trim_start_matches('.');
+    pattern.is_empty() || full_name == pattern || full_name.strip_prefix(pattern).is_some_and(|rest| rest.starts_with('.'))
 }
 
 /// A config for configuring how tinc builds / generates code.
@@ -73,9 +135,16 @@ struct PathConfigs {
 pub struct Config {
     disable_tinc_include: bool,
     root_module: bool,
+    module_files: bool,
     mode: Mode,
     paths: PathConfigs,
+    openapi: OpenApiConfig,
+    cors: Vec<(String, Cors)>,
     extern_paths: ExternPaths,
+    cel_string_byte_size: bool,
+    cel_functions: Vec<(String, usize)>,
+    strict: bool,
+    report: bool,
 }
 
 impl Config {
@@ -91,8 +160,15 @@ impl Config {
             disable_tinc_include: false,
             mode,
             paths: PathConfigs::default(),
+            openapi: OpenApiConfig::default(),
+            cors: Vec::new(),
             extern_paths: ExternPaths::new(mode),
             root_module: true,
+            module_files: false,
+            cel_string_byte_size: false,
+            cel_functions: Vec::new(),
+            strict: false,
+            report: false,
         }
     }
 
@@ -111,6 +187,66 @@ impl Config {
         self
     }
 
+    /// Write the generated code as a tree of module files, one `mod.rs` per package,
+    /// in `$OUT_DIR/tinc_modules` with a root `mod.rs` declaring the top level packages.
+    ///
+    /// By default the root module includes every package file inline, so the
+    /// compiler and rust-analyzer see the whole proto tree as a single file.
+    /// With this enabled `tinc::include_proto!()` declares the top level packages
+    /// as regular file modules instead.
+    ///
+    /// `tinc::include_proto!("package")` is not affected.
+    pub fn module_files(&mut self) -> &mut Self {
+        self.module_files = true;
+        self
+    }
+
+    /// Make the CEL `size()` function measure strings in UTF-8 bytes instead of
+    /// unicode code points.
+    ///
+    /// The CEL spec counts code points, which is what tinc does by default. This restores
+    /// the behaviour of older versions of tinc, which counted bytes, so existing length
+    /// limits on non-ASCII strings keep validating the same way.
+    pub fn cel_string_byte_size(&mut self) -> &mut Self {
+        self.cel_string_byte_size = true;
+        self
+    }
+
+    /// Declare a CEL function which is provided by the application at runtime, like `isInternalEmail()`.
+    ///
+    /// Expressions can call the function as `isInternalEmail(value)` or `value.isInternalEmail()`, in which
+    /// case the value is the first of the `arity` arguments. The implementation has to be registered with
+    /// `tinc::validation::register_cel_function` before requests are validated, calling a function which
+    /// is not registered fails the validation with an expression error.
+    pub fn cel_function(&mut self, name: impl std::fmt::Display, arity: usize) -> &mut Self {
+        self.cel_functions.push((name.to_string(), arity));
+        self
+    }
+
+    /// Fail the build on annotations which tinc does not support, instead of
+    /// silently ignoring them.
+    ///
+    /// This catches `google.api.http` bindings, endpoints without an http method
+    /// and message options which are not implemented yet, like `generate = false`. The error points to the
+    /// location of the annotation in the proto file.
+    ///
+    /// CEL expressions which use unknown functions always fail the build.
+    pub fn strict(&mut self) -> &mut Self {
+        self.strict = true;
+        self
+    }
+
+    /// Write a report of the generated code to `$OUT_DIR/tinc_report.json`.
+    ///
+    /// The report lists the size of the generated file, and the number of messages, enums and
+    /// services for every package, which helps to find the packages which slow down compile times.
+    /// It also counts the messages which are always valid, tinc does not generate validation for
+    /// fields of these messages.
+    pub fn report(&mut self) -> &mut Self {
+        self.report = true;
+        self
+    }
+
     /// Specify a path to generate a `BTreeMap` instead of a `HashMap` for proto map.
     pub fn btree_map(&mut self, path: impl std::fmt::Display) -> &mut Self {
         self.paths.btree_maps.push(path.to_string());
@@ -129,6 +265,93 @@ impl Config {
         self
     }
 
+    /// Add an attribute to the messages, enums and oneofs matched by the path.
+    ///
+    /// Paths are matched the same way as in `prost-build`, so `.` matches all types and `.my_package`
+    /// matches every type in `my_package`. This can be used to integrate the generated types with
+    /// other libraries, for example
+    /// `config.type_attribute(".db.User", "#[derive(sqlx::FromRow)]")`.
+    pub fn type_attribute(&mut self, path: impl std::fmt::Display, attribute: impl std::fmt::Display) -> &mut Self {
+        self.paths.type_attributes.push((path.to_string(), attribute.to_string()));
+        self
+    }
+
+    /// Add an attribute to the messages matched by the path.
+    ///
+    /// See [`type_attribute`](Self::type_attribute) for how paths are matched.
+    pub fn message_attribute(&mut self, path: impl std::fmt::Display, attribute: impl std::fmt::Display) -> &mut Self {
+        self.paths.message_attributes.push((path.to_string(), attribute.to_string()));
+        self
+    }
+
+    /// Add an attribute to the enums and oneofs matched by the path.
+    ///
+    /// See [`type_attribute`](Self::type_attribute) for how paths are matched.
+    pub fn enum_attribute(&mut self, path: impl std::fmt::Display, attribute: impl std::fmt::Display) -> &mut Self {
+        self.paths.enum_attributes.push((path.to_string(), attribute.to_string()));
+        self
+    }
+
+    /// Add an attribute to the message fields and enum variants matched by the path.
+    ///
+    /// The path of a field is the path of its message followed by the field name,
+    /// for example `.db.User.email`.
+    pub fn field_attribute(&mut self, path: impl std::fmt::Display, attribute: impl std::fmt::Display) -> &mut Self {
+        self.paths.field_attributes.push((path.to_string(), attribute.to_string()));
+        self
+    }
+
+    /// Set the title and version of the generated OpenAPI documents.
+    pub fn openapi_info(&mut self, title: impl std::fmt::Display, version: impl std::fmt::Display) -> &mut Self {
+        self.openapi.title = Some(title.to_string());
+        self.openapi.version = Some(version.to_string());
+        self
+    }
+
+    /// Set the description of the generated OpenAPI documents, markdown is supported.
+    pub fn openapi_description(&mut self, description: impl std::fmt::Display) -> &mut Self {
+        self.openapi.description = Some(description.to_string());
+        self
+    }
+
+    /// Add a server url to the generated OpenAPI documents.
+    ///
+    /// Servers are listed in the order they were added.
+    pub fn openapi_server(&mut self, url: impl std::fmt::Display) -> &mut Self {
+        self.openapi.servers.push(url.to_string());
+        self
+    }
+
+    /// Tag every operation of the services matched by the path.
+    ///
+    /// See [`type_attribute`](Self::type_attribute) for how paths are matched,
+    /// for example `config.openapi_service_tag(".users.UserService", "users")`.
+    pub fn openapi_service_tag(&mut self, path: impl std::fmt::Display, tag: impl std::fmt::Display) -> &mut Self {
+        self.openapi.service_tags.push((path.to_string(), tag.to_string()));
+        self
+    }
+
+    /// Set the description of a tag added with [`openapi_service_tag`](Self::openapi_service_tag).
+    pub fn openapi_tag_description(
+        &mut self,
+        tag: impl std::fmt::Display,
+        description: impl std::fmt::Display,
+    ) -> &mut Self {
+        self.openapi.tag_descriptions.push((tag.to_string(), description.to_string()));
+        self
+    }
+
+    /// Attach a CORS policy to the services matched by the path.
+    ///
+    /// See [`type_attribute`](Self::type_attribute) for how paths are matched, if several policies
+    /// match a service the last one wins. The generated router answers preflight requests in the
+    /// tinc error format, and the policy can be replaced at runtime with the `with_cors` method
+    /// of the generated service.
+    pub fn cors(&mut self, path: impl std::fmt::Display, cors: Cors) -> &mut Self {
+        self.cors.push((path.to_string(), cors));
+        self
+    }
+
     /// Compile and generate all the protos with the includes.
     pub fn compile_protos(&mut self, protos: &[impl AsRef<Path>], includes: &[impl AsRef<Path>]) -> anyhow::Result<()> {
         match self.mode {
@@ -161,6 +384,18 @@ impl Config {
             config.boxed(path);
         });
         config.bytes(self.paths.bytes.iter());
+        self.paths.type_attributes.iter().for_each(|(path, attribute)| {
+            config.type_attribute(path, attribute);
+        });
+        self.paths.message_attributes.iter().for_each(|(path, attribute)| {
+            config.message_attribute(path, attribute);
+        });
+        self.paths.enum_attributes.iter().for_each(|(path, attribute)| {
+            config.enum_attribute(path, attribute);
+        });
+        self.paths.field_attributes.iter().for_each(|(path, attribute)| {
+            config.field_attribute(path, attribute);
+        });
 
         let mut includes = includes.iter().map(|i| i.as_ref()).collect::<Vec<_>>();
 
@@ -180,6 +415,14 @@ impl Config {
         let pool = DescriptorPool::decode(&mut fds_bytes.as_slice()).context("failed to decode tonic fds")?;
 
         let mut registry = ProtoTypeRegistry::new(self.mode, self.extern_paths.clone());
+        registry.set_cel_string_byte_size(self.cel_string_byte_size);
+        for (name, arity) in &self.cel_functions {
+            anyhow::ensure!(
+                codegen::cel::compiler::Compiler::new(&registry).get_function(name).is_none(),
+                "cel function {name} is already defined"
+            );
+            registry.register_cel_function(name, *arity);
+        }
 
         config.compile_well_known_types();
         for (proto, rust) in self.extern_paths.paths() {
@@ -192,10 +435,13 @@ impl Config {
         }
 
         prost_explore::Extensions::new(&pool)
+            .strict(self.strict)
             .process(&mut registry)
             .context("failed to process extensions")?;
 
-        let mut packages = codegen::generate_modules(&registry)?;
+        registry.prune_validation();
+
+        let mut packages = codegen::generate_modules(&registry, &self.openapi, &self.cors)?;
 
         packages.iter_mut().for_each(|(path, package)| {
             if self.extern_paths.contains(path) {
@@ -347,12 +593,76 @@ impl Config {
             write_module(&path, std::mem::take(&mut module.extra_items)).with_context(|| package.to_owned())?;
         }
 
+        if self.report {
+            let report = packages
+                .keys()
+                .filter(|package| !self.extern_paths.contains(package))
+                .map(|package| {
+                    let content = std::fs::read_to_string(out_dir.join(format!("{package}.rs")))
+                        .with_context(|| format!("read {package}"))?;
+                    let in_package = |item_package: &ProtoPath| item_package == package;
+
+                    anyhow::Ok((
+                        package.to_string(),
+                        serde_json::json!({
+                            "bytes": content.len(),
+                            "lines": content.lines().count(),
+                            "messages": registry.messages().filter(|message| in_package(&message.package)).count(),
+                            "messagesWithoutValidation": registry.messages_without_validation(package),
+                            "enums": registry.enums().filter(|enum_| in_package(&enum_.package)).count(),
+                            "services": registry.services().filter(|service| in_package(&service.package)).count(),
+                        }),
+                    ))
+                })
+                .collect::<anyhow::Result<serde_json::Map<_, _>>>()?;
+
+            std::fs::write(
+                out_dir.join("tinc_report.json"),
+                serde_json::to_string_pretty(&report).context("serialize report")?,
+            )
+            .context("write report")?;
+        }
+
         #[derive(Default)]
         struct Module<'a> {
             proto_path: Option<&'a ProtoPath>,
             children: BTreeMap<&'a str, Module<'a>>,
         }
 
+        impl Module<'_> {
+            fn ident(part: &str) -> syn::Ident {
+                syn::Ident::new(&to_snake(part), Span::call_site())
+            }
+
+            /// Writes this module to `dir/mod.rs` and its children to sub directories.
+            fn write_files(&self, dir: &Path, out_dir: &Path) -> anyhow::Result<()> {
+                std::fs::create_dir_all(dir).context("create module directory")?;
+
+                let mut file = match self.proto_path {
+                    Some(path) => {
+                        let content = std::fs::read_to_string(out_dir.join(format!("{path}.rs")))
+                            .with_context(|| format!("read {path}"))?;
+                        syn::parse_file(&content).with_context(|| format!("parse {path}"))?
+                    }
+                    None => syn::File {
+                        attrs: Vec::new(),
+                        items: Vec::new(),
+                        shebang: None,
+                    },
+                };
+
+                for (part, child) in &self.children {
+                    let ident = Self::ident(part);
+                    file.items.push(parse_quote!(pub mod #ident;));
+                    child.write_files(&dir.join(ident.to_string()), out_dir)?;
+                }
+
+                std::fs::write(dir.join("mod.rs"), prettyplease::unparse(&file)).context("write module")?;
+
+                Ok(())
+            }
+        }
+
         impl ToTokens for Module<'_> {
             fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
                 let include = self
@@ -360,7 +670,7 @@ impl Config {
                     .map(|p| p.as_ref())
                     .map(|path| quote!(include!(concat!(#path, ".rs"));));
                 let children = self.children.iter().map(|(part, child)| {
-                    let ident = syn::Ident::new(&to_snake(part), Span::call_site());
+                    let ident = Self::ident(part);
                     quote! {
                         pub mod #ident {
                             #child
@@ -375,17 +685,47 @@ impl Config {
             }
         }
 
-        if self.root_module {
-            let mut module = Module::default();
-            for package in packages.keys() {
-                let mut module = &mut module;
-                for part in package.split('.') {
-                    module = module.children.entry(part).or_default();
-                }
-                module.proto_path = Some(package);
+        let mut module = Module::default();
+        for package in packages.keys() {
+            let mut module = &mut module;
+            for part in package.split('.') {
+                module = module.children.entry(part).or_default();
+            }
+            module.proto_path = Some(package);
+        }
+
+        if self.module_files {
+            let modules_dir = out_dir.join("tinc_modules");
+            match std::fs::remove_dir_all(&modules_dir) {
+                Err(err) if err.kind() != ErrorKind::NotFound => return Err(anyhow::anyhow!(err).context("remove")),
+                _ => {}
             }
 
-            let file: syn::File = parse_quote!(#module);
+            module.write_files(&modules_dir, &out_dir).context("write module files")?;
+        }
+
+        if self.root_module {
+            let file: syn::File = if self.module_files {
+                // `mod` declarations in an included file are resolved relative to the including file,
+                // so we have to point to the module files with absolute paths.
+                let children = module.children.keys().map(|part| {
+                    let ident = Module::ident(part);
+                    let path = out_dir
+                        .join("tinc_modules")
+                        .join(ident.to_string())
+                        .join("mod.rs")
+                        .display()
+                        .to_string();
+                    quote! {
+                        #[path = #path]
+                        pub mod #ident;
+                    }
+                });
+                parse_quote!(#(#children)*)
+            } else {
+                parse_quote!(#module)
+            };
+
             std::fs::write(out_dir.join("___root_module.rs"), prettyplease::unparse(&file)).context("write root module")?;
         }
 
@@ -13,9 +13,9 @@ use tinc_cel::{CelEnum, CelValueConv};
 use crate::codegen::cel::{CelExpression, CelExpressions};
 use crate::codegen::prost_sanatize::{strip_enum_prefix, to_upper_camel};
 use crate::types::{
-    Comments, ProtoEnumOptions, ProtoEnumType, ProtoEnumVariant, ProtoEnumVariantOptions, ProtoFieldOptions,
-    ProtoFieldSerdeOmittable, ProtoMessageField, ProtoMessageOptions, ProtoMessageType, ProtoModifiedValueType,
-    ProtoOneOfField, ProtoOneOfOptions, ProtoOneOfType, ProtoPath, ProtoService, ProtoServiceMethod,
+    Comments, ProtoBytesEncoding, ProtoEnumOptions, ProtoEnumType, ProtoEnumVariant, ProtoEnumVariantOptions,
+    ProtoFieldOptions, ProtoFieldSerdeOmittable, ProtoMessageField, ProtoMessageOptions, ProtoMessageType,
+    ProtoModifiedValueType, ProtoOneOfField, ProtoOneOfOptions, ProtoOneOfType, ProtoPath, ProtoService, ProtoServiceMethod,
     ProtoServiceMethodEndpoint, ProtoServiceMethodIo, ProtoServiceOptions, ProtoType, ProtoTypeRegistry, ProtoValueType,
     ProtoVisibility, Tagged,
 };
@@ -174,6 +174,9 @@ fn rename_field(field: &str, style: tinc_pb_prost::RenameAll) -> Option<String>
 
 pub(crate) struct Extensions<'a> {
     pool: &'a DescriptorPool,
+    strict: bool,
+    // `google.api.http`, which is not supported by tinc.
+    ext_google_http: Option<ExtensionDescriptor>,
     // Message extensions.
     ext_message: Extension<tinc_pb_prost::MessageOptions>,
     ext_field: Extension<tinc_pb_prost::FieldOptions>,
@@ -193,6 +196,8 @@ impl<'a> Extensions<'a> {
     pub(crate) fn new(pool: &'a DescriptorPool) -> Self {
         Self {
             pool,
+            strict: false,
+            ext_google_http: pool.get_extension_by_name("google.api.http"),
             ext_message: Extension::new("tinc.message", pool),
             ext_field: Extension::new("tinc.field", pool),
             ext_predefined: Extension::new("tinc.predefined", pool),
@@ -204,6 +209,12 @@ impl<'a> Extensions<'a> {
         }
     }
 
+    /// Fail on annotations which are not supported instead of ignoring them.
+    pub(crate) fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     pub(crate) fn process(&self, registry: &mut ProtoTypeRegistry) -> anyhow::Result<()> {
         self.pool
             .files()
@@ -257,6 +268,19 @@ impl<'a> FileWalker<'a> {
         Some(&self.locations[idx])
     }
 
+    /// Report an annotation which is ignored by tinc, this is an error in strict mode.
+    fn unsupported(&self, path: &[i32], message: impl std::fmt::Display) -> anyhow::Result<()> {
+        if !self.extensions.strict {
+            return Ok(());
+        }
+
+        // The span is zero based: [start line, start column, ...]
+        match self.location(path).map(|location| location.span.as_slice()) {
+            Some([line, column, ..]) => anyhow::bail!("{}:{}:{}: {message}", self.file.name(), line + 1, column + 1),
+            _ => anyhow::bail!("{}: {message}", self.file.name()),
+        }
+    }
+
     fn process(&self, registry: &mut ProtoTypeRegistry) -> anyhow::Result<()> {
         for message in self.file.messages() {
             // FileDescriptorProto.message_type = 4
@@ -303,17 +327,48 @@ impl<'a> FileWalker<'a> {
                 .with_context(|| format!("method {}", method.full_name()))?
                 .unwrap_or_default();
 
+            let options = method.options();
+            if self
+                .extensions
+                .ext_google_http
+                .as_ref()
+                .is_some_and(|ext| options.has_extension(ext))
+            {
+                self.unsupported(
+                    method.path(),
+                    format_args!(
+                        "method {}: google.api.http is not supported, use tinc.method endpoints instead",
+                        method.full_name()
+                    ),
+                )?;
+            }
+
             let mut endpoints = Vec::new();
-            for endpoint in opts.endpoint {
-                let Some(method) = endpoint.method else {
-                    continue;
-                };
+            for mut endpoint in opts.endpoint {
+                let additional_bindings = std::mem::take(&mut endpoint.additional_bindings);
+                anyhow::ensure!(
+                    additional_bindings
+                        .iter()
+                        .all(|binding| binding.additional_bindings.is_empty()),
+                    "method {}: additional bindings cannot have additional bindings",
+                    method.full_name()
+                );
 
-                endpoints.push(ProtoServiceMethodEndpoint {
-                    method,
-                    request: endpoint.request,
-                    response: endpoint.response,
-                });
+                for endpoint in std::iter::once(endpoint).chain(additional_bindings) {
+                    let Some(endpoint_method) = endpoint.method else {
+                        self.unsupported(
+                            method.path(),
+                            format_args!("method {}: endpoint has no http method and path", method.full_name()),
+                        )?;
+                        continue;
+                    };
+
+                    endpoints.push(ProtoServiceMethodEndpoint {
+                        method: endpoint_method,
+                        request: endpoint.request,
+                        response: endpoint.response,
+                    });
+                }
             }
 
             methods.insert(
@@ -336,13 +391,16 @@ impl<'a> FileWalker<'a> {
                     cel: opts
                         .cel
                         .into_iter()
-                        .map(|expr| CelExpression {
+                        .enumerate()
+                        .map(|(idx, expr)| CelExpression {
                             expression: expr.expression,
                             jsonschemas: expr.jsonschemas,
                             message: expr.message,
                             this: None,
+                            rule: format!("{}.cel[{idx}]", method.full_name()),
                         })
                         .collect(),
+                    pagination: opts.pagination,
                 },
             );
         }
@@ -375,6 +433,18 @@ impl<'a> FileWalker<'a> {
 
         let opts = opts.unwrap_or_default();
         let message_full_name = ProtoPath::new(message.full_name());
+
+        // Every message is generated, so only opting out is ignored.
+        if opts.generate == Some(false) {
+            self.unsupported(
+                message.path(),
+                "generate = false is not supported, all messages are generated",
+            )?;
+        }
+
+        if opts.skip_validation.is_some() {
+            self.unsupported(message.path(), "the skip_validation message option is not supported")?;
+        }
         let rename_all = opts.rename_all.and_then(|v| tinc_pb_prost::RenameAll::try_from(v).ok());
 
         let mut message_type = ProtoMessageType {
@@ -386,11 +456,13 @@ impl<'a> FileWalker<'a> {
                 cel: opts
                     .cel
                     .into_iter()
-                    .map(|cel| CelExpression {
+                    .enumerate()
+                    .map(|(idx, cel)| CelExpression {
                         expression: cel.expression,
                         jsonschemas: cel.jsonschemas,
                         message: cel.message,
                         this: None,
+                        rule: format!("{message_full_name}.cel[{idx}]"),
                     })
                     .collect(),
             },
@@ -406,11 +478,12 @@ impl<'a> FileWalker<'a> {
                 nullable: proto3_optional,
                 visibility,
                 flatten: opts.flatten(),
+                bytes_encoding: ProtoBytesEncoding::from_pb(opts.bytes_encoding()),
                 serde_name: opts
                     .rename
                     .or_else(|| rename_field(field.name(), rename_all?))
                     .unwrap_or_else(|| field.name().to_owned()),
-                cel_exprs: gather_cel_expressions(&self.extensions.ext_predefined, &field.options())
+                cel_exprs: gather_cel_expressions(&self.extensions.ext_predefined, field.full_name(), &field.options())
                     .context("gathering cel expressions")?,
             };
 
@@ -462,6 +535,7 @@ impl<'a> FileWalker<'a> {
                                 .or_else(|| rename_field(oneof.name(), rename_all?))
                                 .unwrap_or_else(|| oneof.name().to_owned()),
                             visibility,
+                            bytes_encoding: ProtoBytesEncoding::default(),
                             cel_exprs: CelExpressions::default(),
                         },
                         ty: ProtoType::Modified(ProtoModifiedValueType::OneOf(ProtoOneOfType {
@@ -590,8 +664,11 @@ enum CelInput {
     RepeatedItem,
 }
 
+/// Custom expressions are identified as `<field_full_name>.cel[<index>]`, counting all custom
+/// expressions of the field in order.
 pub(crate) fn gather_cel_expressions(
     extension: &Extension<tinc_pb_prost::PredefinedConstraints>,
+    field_full_name: &str,
     field_options: &prost_reflect::DynamicMessage,
 ) -> anyhow::Result<CelExpressions> {
     let Some(extension) = extension.descriptor() else {
@@ -600,6 +677,10 @@ pub(crate) fn gather_cel_expressions(
 
     let mut results = BTreeMap::new();
     let mut input = CelInput::Root;
+    let mut custom = CustomExpressions {
+        field_full_name,
+        count: 0,
+    };
 
     if field_options.has_extension(extension) {
         let value = field_options.get_extension(extension);
@@ -629,7 +710,7 @@ pub(crate) fn gather_cel_expressions(
         }
 
         if let Some(message) = value.as_message() {
-            explore_fields(extension, input, message, &mut results)?;
+            explore_fields(extension, input, message, &mut custom, &mut results)?;
         }
     }
 
@@ -641,10 +722,25 @@ pub(crate) fn gather_cel_expressions(
     })
 }
 
+/// Hands out the rule ids of the custom expressions of a field.
+struct CustomExpressions<'a> {
+    field_full_name: &'a str,
+    count: usize,
+}
+
+impl CustomExpressions<'_> {
+    fn next_rule(&mut self) -> String {
+        let rule = format!("{}.cel[{}]", self.field_full_name, self.count);
+        self.count += 1;
+        rule
+    }
+}
+
 fn explore_fields(
     extension: &prost_reflect::ExtensionDescriptor,
     input: CelInput,
     value: &prost_reflect::DynamicMessage,
+    custom: &mut CustomExpressions<'_>,
     results: &mut BTreeMap<CelInput, Vec<CelExpression>>,
 ) -> anyhow::Result<()> {
     for (field, value) in value.fields() {
@@ -670,6 +766,7 @@ fn explore_fields(
                                     jsonschemas: expr.jsonschemas,
                                     message: expr.message,
                                     this: None,
+                                    rule: custom.next_rule(),
                                 }),
                         );
                     }
@@ -694,6 +791,7 @@ fn explore_fields(
                     jsonschemas: expr.jsonschemas,
                     message: expr.message,
                     this: Some(prost_to_cel(value, &field.kind())),
+                    rule: field.full_name().to_owned(),
                 }));
         }
 
@@ -701,7 +799,7 @@ fn explore_fields(
             continue;
         };
 
-        explore_fields(extension, input, message, results)?;
+        explore_fields(extension, input, message, custom, results)?;
     }
 
     Ok(())
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write;
 use std::sync::Arc;
 
@@ -256,6 +256,33 @@ impl ProtoVisibility {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum ProtoBytesEncoding {
+    #[default]
+    Base64,
+    Base64Url,
+    Hex,
+}
+
+impl ProtoBytesEncoding {
+    pub(crate) fn from_pb(encoding: tinc_pb_prost::BytesEncoding) -> Self {
+        match encoding {
+            tinc_pb_prost::BytesEncoding::Unspecified | tinc_pb_prost::BytesEncoding::Base64 => ProtoBytesEncoding::Base64,
+            tinc_pb_prost::BytesEncoding::Base64url => ProtoBytesEncoding::Base64Url,
+            tinc_pb_prost::BytesEncoding::Hex => ProtoBytesEncoding::Hex,
+        }
+    }
+
+    /// The path of the runtime encoding type, `None` for the default encoding.
+    pub(crate) fn rust_path(&self) -> Option<&'static str> {
+        match self {
+            ProtoBytesEncoding::Base64 => None,
+            ProtoBytesEncoding::Base64Url => Some("::tinc::__private::Base64Url"),
+            ProtoBytesEncoding::Hex => Some("::tinc::__private::Hex"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct ProtoFieldOptions {
     pub serde_name: String,
@@ -263,6 +290,7 @@ pub(crate) struct ProtoFieldOptions {
     pub nullable: bool,
     pub flatten: bool,
     pub visibility: ProtoVisibility,
+    pub bytes_encoding: ProtoBytesEncoding,
     pub cel_exprs: CelExpressions,
 }
 
@@ -403,6 +431,7 @@ pub(crate) struct ProtoServiceMethod {
     pub output: ProtoServiceMethodIo,
     pub endpoints: Vec<ProtoServiceMethodEndpoint>,
     pub cel: Vec<CelExpression>,
+    pub pagination: Option<tinc_pb_prost::PaginationOptions>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -459,6 +488,9 @@ pub(crate) struct ProtoTypeRegistry {
     services: BTreeMap<ProtoPath, ProtoService>,
     extern_paths: ExternPaths,
     _mode: Mode,
+    cel_string_byte_size: bool,
+    cel_functions: BTreeMap<String, usize>,
+    validated_messages: Option<BTreeSet<ProtoPath>>,
 }
 
 impl ProtoTypeRegistry {
@@ -469,9 +501,30 @@ impl ProtoTypeRegistry {
             services: BTreeMap::new(),
             extern_paths,
             _mode: mode,
+            cel_string_byte_size: false,
+            cel_functions: BTreeMap::new(),
+            validated_messages: None,
         }
     }
 
+    pub(crate) fn set_cel_string_byte_size(&mut self, enabled: bool) {
+        self.cel_string_byte_size = enabled;
+    }
+
+    /// Whether the CEL `size()` function measures strings in bytes instead of code points.
+    pub(crate) fn cel_string_byte_size(&self) -> bool {
+        self.cel_string_byte_size
+    }
+
+    pub(crate) fn register_cel_function(&mut self, name: impl Into<String>, arity: usize) {
+        self.cel_functions.insert(name.into(), arity);
+    }
+
+    /// The functions which are registered by the application at runtime, with their number of arguments.
+    pub(crate) fn cel_functions(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.cel_functions.iter().map(|(name, arity)| (name.as_str(), *arity))
+    }
+
     pub(crate) fn register_message(&mut self, message: ProtoMessageType) {
         self.messages.insert(message.full_name.clone(), message);
     }
@@ -518,4 +571,218 @@ impl ProtoTypeRegistry {
     pub(crate) fn has_extern(&self, path: &str) -> bool {
         self.extern_paths.contains(path)
     }
+
+    /// Find the messages whose validation can fail, the validation of fields with any other
+    /// message type is not generated.
+    ///
+    /// A message without constraints and required fields, which only nests messages like it,
+    /// is always valid. Until this is called every message is assumed to have validation.
+    pub(crate) fn prune_validation(&mut self) {
+        let mut validated = BTreeSet::new();
+
+        // Messages can be recursive, so this is repeated until no more messages are found.
+        loop {
+            let found = self
+                .messages
+                .values()
+                .filter(|message| !validated.contains(&message.full_name))
+                .filter(|message| {
+                    message.fields.values().any(|field| {
+                        let required = field.options.visibility.has_input()
+                            && matches!(field.options.serde_omittable, ProtoFieldSerdeOmittable::False)
+                            && !field.options.flatten;
+                        required || self.field_has_validation(&field.ty, &field.options, &validated)
+                    })
+                })
+                .map(|message| message.full_name.clone())
+                .collect::<Vec<_>>();
+
+            if found.is_empty() {
+                break;
+            }
+
+            validated.extend(found);
+        }
+
+        self.validated_messages = Some(validated);
+    }
+
+    /// Whether validating the message can fail, see [`prune_validation`](Self::prune_validation).
+    pub(crate) fn message_has_validation(&self, path: &str) -> bool {
+        // extern messages are validated by the code generated for them.
+        self.validated_messages
+            .as_ref()
+            .is_none_or(|validated| validated.contains(path) || self.has_extern(path))
+    }
+
+    /// Whether validating the oneof can fail, see [`prune_validation`](Self::prune_validation).
+    pub(crate) fn oneof_has_validation(&self, oneof: &ProtoOneOfType) -> bool {
+        match &self.validated_messages {
+            Some(validated) => self.oneof_fields_have_validation(oneof, validated),
+            None => true,
+        }
+    }
+
+    /// The number of messages with validation which never fails, see [`prune_validation`](Self::prune_validation).
+    pub(crate) fn messages_without_validation(&self, package: &str) -> usize {
+        self.messages
+            .values()
+            .filter(|message| message.package.as_ref() == package && !self.message_has_validation(&message.full_name))
+            .count()
+    }
+
+    fn field_has_validation(&self, ty: &ProtoType, options: &ProtoFieldOptions, validated: &BTreeSet<ProtoPath>) -> bool {
+        if !options.visibility.has_input() {
+            return false;
+        }
+
+        let exprs = &options.cel_exprs;
+        if !exprs.field.is_empty()
+            || !exprs.map_key.is_empty()
+            || !exprs.map_value.is_empty()
+            || !exprs.repeated_item.is_empty()
+        {
+            return true;
+        }
+
+        match ty {
+            ProtoType::Modified(ProtoModifiedValueType::OneOf(oneof)) => {
+                !options.nullable || self.oneof_fields_have_validation(oneof, validated)
+            }
+            ProtoType::Modified(ProtoModifiedValueType::Optional(_)) if !options.nullable => true,
+            ty => match ty.value_type() {
+                Some(ProtoValueType::Message(path)) => validated.contains(path) || self.has_extern(path),
+                _ => false,
+            },
+        }
+    }
+
+    fn oneof_fields_have_validation(&self, oneof: &ProtoOneOfType, validated: &BTreeSet<ProtoPath>) -> bool {
+        oneof
+            .fields
+            .values()
+            .any(|field| self.field_has_validation(&ProtoType::Value(field.ty.clone()), &field.options, validated))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "prost")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    fn message(
+        name: &str,
+        fields: impl IntoIterator<Item = (&'static str, ProtoType, ProtoFieldOptions)>,
+    ) -> ProtoMessageType {
+        let full_name = ProtoPath::new(format!("test.{name}"));
+        ProtoMessageType {
+            package: ProtoPath::new("test"),
+            full_name: full_name.clone(),
+            comments: Comments::default(),
+            options: ProtoMessageOptions::default(),
+            fields: fields
+                .into_iter()
+                .map(|(field, ty, options)| {
+                    (
+                        field.to_owned(),
+                        ProtoMessageField {
+                            full_name: ProtoPath::new(format!("{full_name}.{field}")),
+                            message: full_name.clone(),
+                            ty,
+                            comments: Comments::default(),
+                            options,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn options(serde_omittable: ProtoFieldSerdeOmittable) -> ProtoFieldOptions {
+        ProtoFieldOptions {
+            serde_name: String::new(),
+            serde_omittable,
+            nullable: true,
+            flatten: false,
+            visibility: ProtoVisibility::Default,
+            bytes_encoding: ProtoBytesEncoding::default(),
+            cel_exprs: CelExpressions::default(),
+        }
+    }
+
+    fn message_ty(name: &str) -> ProtoValueType {
+        ProtoValueType::Message(ProtoPath::new(format!("test.{name}")))
+    }
+
+    #[test]
+    fn test_prune_validation() {
+        let mut registry = ProtoTypeRegistry::new(Mode::Prost, ExternPaths::new(Mode::Prost));
+        let omittable = || options(ProtoFieldSerdeOmittable::True);
+
+        registry.register_message(message(
+            "Plain",
+            [("name", ProtoType::Value(ProtoValueType::String), omittable())],
+        ));
+        registry.register_message(message(
+            "Recursive",
+            [
+                (
+                    "child",
+                    ProtoType::Modified(ProtoModifiedValueType::Optional(message_ty("Recursive"))),
+                    omittable(),
+                ),
+                (
+                    "plain",
+                    ProtoType::Modified(ProtoModifiedValueType::Repeated(message_ty("Plain"))),
+                    omittable(),
+                ),
+            ],
+        ));
+        registry.register_message(message(
+            "Required",
+            [(
+                "name",
+                ProtoType::Value(ProtoValueType::String),
+                options(ProtoFieldSerdeOmittable::False),
+            )],
+        ));
+        registry.register_message(message(
+            "Constrained",
+            [("name", ProtoType::Value(ProtoValueType::String), {
+                let mut options = omittable();
+                options.cel_exprs.field.push(CelExpression {
+                    message: "too long".into(),
+                    expression: "size(input) < 10".into(),
+                    jsonschemas: Vec::new(),
+                    this: None,
+                    rule: "Constrained.name.cel[0]".into(),
+                });
+                options
+            })],
+        ));
+        registry.register_message(message(
+            "Nested",
+            [(
+                "items",
+                ProtoType::Modified(ProtoModifiedValueType::Map(ProtoValueType::String, message_ty("Nested2"))),
+                omittable(),
+            )],
+        ));
+        registry.register_message(message(
+            "Nested2",
+            [("required", ProtoType::Value(message_ty("Required")), omittable())],
+        ));
+
+        assert!(registry.message_has_validation("test.Plain"));
+        registry.prune_validation();
+
+        assert!(!registry.message_has_validation("test.Plain"));
+        assert!(!registry.message_has_validation("test.Recursive"));
+        assert!(registry.message_has_validation("test.Required"));
+        assert!(registry.message_has_validation("test.Constrained"));
+        assert!(registry.message_has_validation("test.Nested"));
+        assert!(registry.message_has_validation("test.Nested2"));
+        assert_eq!(registry.messages_without_validation("test"), 2);
+    }
 }
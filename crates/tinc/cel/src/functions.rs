@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
+
+use crate::{CelError, CelValue};
+
+type Callback = dyn for<'a> Fn(&[CelValue<'a>]) -> Result<CelValue<'a>, CelError<'a>> + Send + Sync;
+
+struct Registered {
+    arity: usize,
+    callback: Arc<Callback>,
+}
+
+static FUNCTIONS: LazyLock<RwLock<HashMap<Box<str>, Registered>>> = LazyLock::new(Default::default);
+
+/// Registers a function which can be called from CEL expressions.
+///
+/// The function has to be declared at build time with `tinc_build::Config::cel_function`, which
+/// makes the expressions call the function registered here under the same name. When called on
+/// a value, like `this.isInternalEmail()`, the value is passed as the first argument.
+///
+/// Registering a function again replaces the previous one.
+pub fn register_cel_function<F>(name: impl Into<Box<str>>, arity: usize, callback: F)
+where
+    F: for<'a> Fn(&[CelValue<'a>]) -> Result<CelValue<'a>, CelError<'a>> + Send + Sync + 'static,
+{
+    FUNCTIONS.write().unwrap_or_else(PoisonError::into_inner).insert(
+        name.into(),
+        Registered {
+            arity,
+            callback: Arc::new(callback),
+        },
+    );
+}
+
+/// Removes a function registered with [`register_cel_function`], returns `true` if it was registered.
+pub fn unregister_cel_function(name: &str) -> bool {
+    FUNCTIONS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(name)
+        .is_some()
+}
+
+/// Calls the function registered under the name.
+pub(crate) fn call<'a>(name: &str, args: &[CelValue<'a>]) -> Result<CelValue<'a>, CelError<'a>> {
+    let (arity, callback) = {
+        let functions = FUNCTIONS.read().unwrap_or_else(PoisonError::into_inner);
+        let Some(function) = functions.get(name) else {
+            return Err(CelError::UnknownFunction(name.to_owned()));
+        };
+
+        (function.arity, function.callback.clone())
+    };
+
+    if arity != args.len() {
+        return Err(CelError::FunctionArity {
+            name: name.to_owned(),
+            expected: arity,
+            actual: args.len(),
+        });
+    }
+
+    // Call without holding the lock, so the function can register other functions.
+    callback(args)
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::{call, register_cel_function, unregister_cel_function};
+    use crate::{CelError, CelValue};
+
+    #[test]
+    fn call_registered_function() {
+        register_cel_function("test_isInternalEmail", 1, |args| match &args[0] {
+            CelValue::String(email) => Ok(CelValue::Bool(email.as_ref().ends_with("@scuffle.cloud"))),
+            value => Err(CelError::BadUnaryOperation {
+                op: "isInternalEmail",
+                value: value.clone(),
+            }),
+        });
+
+        assert_eq!(
+            call("test_isInternalEmail", &[CelValue::String("troy@scuffle.cloud".into())]),
+            Ok(CelValue::Bool(true))
+        );
+        assert_eq!(
+            call("test_isInternalEmail", &[CelValue::String("troy@example.com".into())]),
+            Ok(CelValue::Bool(false))
+        );
+        assert_eq!(
+            call("test_isInternalEmail", &[CelValue::Null]),
+            Err(CelError::BadUnaryOperation {
+                op: "isInternalEmail",
+                value: CelValue::Null,
+            })
+        );
+        assert_eq!(
+            call("test_isInternalEmail", &[]),
+            Err(CelError::FunctionArity {
+                name: "test_isInternalEmail".to_owned(),
+                expected: 1,
+                actual: 0,
+            })
+        );
+
+        assert!(unregister_cel_function("test_isInternalEmail"));
+        assert!(!unregister_cel_function("test_isInternalEmail"));
+        assert_eq!(
+            call("test_isInternalEmail", &[CelValue::Null]),
+            Err(CelError::UnknownFunction("test_isInternalEmail".to_owned()))
+        );
+    }
+
+    #[test]
+    fn function_returns_argument() {
+        register_cel_function("test_first", 2, |args| Ok(args[0].clone()));
+
+        let list = CelValue::List([CelValue::Number(1.into())].into_iter().collect());
+        assert_eq!(call("test_first", &[list.clone(), CelValue::Null]), Ok(list));
+    }
+}
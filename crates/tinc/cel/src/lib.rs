@@ -23,6 +23,13 @@ use bytes::Bytes;
 use float_cmp::ApproxEq;
 use num_traits::ToPrimitive;
 
+mod functions;
+mod regex_cache;
+mod time;
+
+pub use functions::{register_cel_function, unregister_cel_function};
+pub use regex_cache::{DEFAULT_REGEX_CACHE_CAPACITY, regex_cache_capacity, set_regex_cache_capacity};
+
 #[derive(Debug, thiserror::Error, PartialEq)]
 pub enum CelError<'a> {
     #[error("index out of bounds: {0} is out of range for a list of length {1}")]
@@ -42,6 +49,11 @@ pub enum CelError<'a> {
         op: &'static str,
         value: CelValue<'a>,
     },
+    #[error("invalid {ty}: {value}")]
+    BadConversion {
+        ty: &'static str,
+        value: CelValue<'a>,
+    },
     #[error("number out of range when performing {op}")]
     NumberOutOfRange {
         op: &'static str,
@@ -51,6 +63,16 @@ pub enum CelError<'a> {
         member: CelValue<'a>,
         container: CelValue<'a>,
     },
+    #[error("invalid regex: {0}")]
+    InvalidRegex(regex::Error),
+    #[error("function not registered: {0}")]
+    UnknownFunction(String),
+    #[error("function {name} takes {expected} arguments but got {actual}")]
+    FunctionArity {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -200,6 +222,8 @@ impl PartialOrd for CelValue<'_> {
 
                 Some(l.cmp(r))
             }
+            (CelValue::Duration(l), CelValue::Duration(r)) => l.partial_cmp(r),
+            (CelValue::Timestamp(l), CelValue::Timestamp(r)) => l.partial_cmp(r),
             _ => None,
         }
     }
@@ -449,6 +473,27 @@ impl<'a> CelValue<'a> {
         }
     }
 
+    /// Like [`CelValue::cel_matches`], but for patterns that are only known at runtime.
+    ///
+    /// Compiled patterns are cached, see [`set_regex_cache_capacity`].
+    pub fn cel_call_function(name: &str, args: &[CelValue<'a>]) -> Result<CelValue<'a>, CelError<'a>> {
+        functions::call(name, args)
+    }
+
+    pub fn cel_matches_pattern(value: impl CelValueConv<'a>, pattern: impl CelValueConv<'a>) -> Result<bool, CelError<'a>> {
+        match (value.conv(), pattern.conv()) {
+            (value, CelValue::String(pattern)) => {
+                let regex = regex_cache::compile(pattern.as_ref()).map_err(CelError::InvalidRegex)?;
+                Self::cel_matches(value, &regex)
+            }
+            (left, right) => Err(CelError::BadOperation {
+                left,
+                right,
+                op: "matches",
+            }),
+        }
+    }
+
     pub fn cel_is_ipv4(value: impl CelValueConv<'a>) -> Result<bool, CelError<'a>> {
         match value.conv() {
             CelValue::String(s) => Ok(s.parse::<std::net::Ipv4Addr>().is_ok()),
@@ -540,6 +585,16 @@ impl<'a> CelValue<'a> {
     }
 
     pub fn cel_size(item: impl CelValueConv<'a>) -> Result<u64, CelError<'a>> {
+        match item.conv() {
+            Self::String(s) => Ok(s.as_ref().chars().count() as u64),
+            item => Self::cel_byte_size(item),
+        }
+    }
+
+    /// Like [`CelValue::cel_size`] but strings are measured in UTF-8 bytes.
+    ///
+    /// This is not spec compliant and only exists for compatibility with older versions of tinc.
+    pub fn cel_byte_size(item: impl CelValueConv<'a>) -> Result<u64, CelError<'a>> {
         match item.conv() {
             Self::Bytes(b) => Ok(b.as_ref().len() as u64),
             Self::String(s) => Ok(s.as_ref().len() as u64),
@@ -684,6 +739,8 @@ impl<'a> CelValue<'a> {
                 Cow::Borrowed(b) => CelValue::String(CelString::Borrowed(b)),
                 Cow::Owned(o) => CelValue::String(CelString::Owned(o.into())),
             },
+            CelValue::Duration(duration) => CelValue::String(CelString::Owned(time::format_duration(duration).into())),
+            CelValue::Timestamp(timestamp) => CelValue::String(CelString::Owned(time::format_timestamp(timestamp).into())),
             item => CelValue::String(CelString::Owned(item.to_string().into())),
         }
     }
@@ -758,6 +815,34 @@ impl<'a> CelValue<'a> {
         }
     }
 
+    pub fn cel_to_timestamp(item: impl CelValueConv<'a>) -> Result<CelValue<'a>, CelError<'a>> {
+        match item.conv() {
+            item @ CelValue::Timestamp(_) => Ok(item),
+            CelValue::String(s) => match time::parse_timestamp(s.as_ref()) {
+                Some(timestamp) => Ok(CelValue::Timestamp(timestamp)),
+                None => Err(CelError::BadConversion {
+                    ty: "timestamp",
+                    value: CelValue::String(s),
+                }),
+            },
+            value => Err(CelError::BadUnaryOperation { op: "timestamp", value }),
+        }
+    }
+
+    pub fn cel_to_duration(item: impl CelValueConv<'a>) -> Result<CelValue<'a>, CelError<'a>> {
+        match item.conv() {
+            item @ CelValue::Duration(_) => Ok(item),
+            CelValue::String(s) => match time::parse_duration(s.as_ref()) {
+                Some(duration) => Ok(CelValue::Duration(duration)),
+                None => Err(CelError::BadConversion {
+                    ty: "duration",
+                    value: CelValue::String(s),
+                }),
+            },
+            value => Err(CelError::BadUnaryOperation { op: "duration", value }),
+        }
+    }
+
     pub fn cel_to_enum(item: impl CelValueConv<'a>, path: impl CelValueConv<'a>) -> Result<CelValue<'a>, CelError<'a>> {
         match (item.conv(), path.conv()) {
             (CelValue::Number(number), CelValue::String(tag)) => {
@@ -806,7 +891,13 @@ impl PartialEq for CelValue<'_> {
                 enum_.value == *value
             }
             (CelValue::List(left), CelValue::List(right)) => left == right,
-            (CelValue::Map(left), CelValue::Map(right)) => left == right,
+            // Maps are unordered, every key must be present in both maps with equal values.
+            (CelValue::Map(left), CelValue::Map(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .all(|(key, value)| right.iter().find(|(k, _)| k == key).is_some_and(|(_, v)| v == value))
+            }
             (CelValue::Number(left), CelValue::Number(right)) => left == right,
             (CelValue::Null, CelValue::Null) => true,
             _ => false,
@@ -1134,7 +1225,8 @@ impl PartialEq for NumberTy {
             .map(|(l, r)| match (l, r) {
                 (NumberTy::I64(l), NumberTy::I64(r)) => l == r,
                 (NumberTy::U64(l), NumberTy::U64(r)) => l == r,
-                (NumberTy::F64(l), NumberTy::F64(r)) => l.approx_eq(r, float_cmp::F64Margin::default()),
+                // Equality is exact as per the CEL spec, so NaN is not equal to itself.
+                (NumberTy::F64(l), NumberTy::F64(r)) => l == r,
                 // I think this is unreachable
                 _ => false,
             })
@@ -1319,7 +1411,11 @@ macro_rules! impl_map_key_cast_number {
 
             fn make_key<'a>(key: &'a CelValue<'a>) -> Option<Cow<'a, Self>> {
                 match key {
-                    CelValue::Number(number) => number.$fn().map(Cow::Owned),
+                    // The key must convert losslessly, `1.5` must not find the key `1`.
+                    CelValue::Number(number) => number
+                        .$fn()
+                        .filter(|key| NumberTy::from(*key) == *number)
+                        .map(Cow::Owned),
                     _ => None,
                 }
             }
@@ -1982,6 +2078,22 @@ mod tests {
         assert!(matches!(err, CelError::BadUnaryOperation { op, .. } if op=="matches"));
     }
 
+    #[test]
+    fn celvalue_matches_pattern() {
+        assert!(CelValue::cel_matches_pattern("abcz", "^a.*z$").unwrap());
+        assert!(!CelValue::cel_matches_pattern("abc", "^a.*z$").unwrap());
+        assert!(CelValue::cel_matches_pattern(Bytes::from_static(b"abcz"), "^a.*z$").unwrap());
+
+        let err = CelValue::cel_matches_pattern("abc", 1i32).unwrap_err();
+        assert!(matches!(err, CelError::BadOperation { op, .. } if op=="matches"));
+
+        let err = CelValue::cel_matches_pattern("abc", "(").unwrap_err();
+        assert!(matches!(err, CelError::InvalidRegex(_)));
+
+        let err = CelValue::cel_matches_pattern(1i32, "^a").unwrap_err();
+        assert!(matches!(err, CelError::BadUnaryOperation { op, .. } if op=="matches"));
+    }
+
     #[test]
     fn celvalue_ip_and_uuid_hostname_uri_email() {
         // IPv4
@@ -2099,6 +2211,23 @@ mod tests {
         assert!(matches!(err, CelError::BadUnaryOperation { op, .. } if op=="size"));
     }
 
+    #[test]
+    fn celvalue_size_unicode() {
+        // string.size() counts code points, bytes.size() counts bytes
+        assert_eq!(CelValue::cel_size("héllo").unwrap(), 5);
+        assert_eq!(CelValue::cel_size("日本語").unwrap(), 3);
+        assert_eq!(CelValue::cel_size("👋🏽").unwrap(), 2);
+        assert_eq!(CelValue::cel_size(Bytes::from_static("héllo".as_bytes())).unwrap(), 6);
+        assert_eq!(CelValue::cel_size(Bytes::from_static("日本語".as_bytes())).unwrap(), 9);
+
+        assert_eq!(CelValue::cel_byte_size("héllo").unwrap(), 6);
+        assert_eq!(CelValue::cel_byte_size("日本語").unwrap(), 9);
+        assert_eq!(CelValue::cel_byte_size([1, 2, 3].conv()).unwrap(), 3);
+
+        let err = CelValue::cel_byte_size(123i32).unwrap_err();
+        assert!(matches!(err, CelError::BadUnaryOperation { op, .. } if op=="size"));
+    }
+
     #[test]
     fn celvalue_map_and_filter() {
         // map: double each number
@@ -2395,6 +2524,100 @@ mod tests {
         assert_eq!(out_bool, CelValue::String(CelString::Owned(Arc::from("true"))));
     }
 
+    #[test]
+    fn celvalue_to_string_duration_and_timestamp() {
+        let out = CelValue::cel_to_string(CelValue::Duration(Duration::minutes(90)));
+        assert_eq!(out, CelValue::String(CelString::Owned(Arc::from("5400s"))));
+
+        let ts: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2020-01-01T01:00:00+01:00").unwrap();
+        let out = CelValue::cel_to_string(CelValue::Timestamp(ts));
+        assert_eq!(out, CelValue::String(CelString::Owned(Arc::from("2020-01-01T00:00:00Z"))));
+    }
+
+    #[test]
+    fn celvalue_to_timestamp() {
+        let ts: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap();
+        assert_eq!(
+            CelValue::cel_to_timestamp("2020-01-01T00:00:00Z").unwrap(),
+            CelValue::Timestamp(ts)
+        );
+        assert_eq!(
+            CelValue::cel_to_timestamp(CelValue::Timestamp(ts)).unwrap(),
+            CelValue::Timestamp(ts)
+        );
+        assert_eq!(
+            CelValue::cel_to_timestamp("yesterday").unwrap_err(),
+            CelError::BadConversion {
+                ty: "timestamp",
+                value: "yesterday".conv()
+            }
+        );
+        assert_eq!(
+            CelValue::cel_to_timestamp("2020-01-01").unwrap_err().to_string(),
+            "invalid timestamp: 2020-01-01"
+        );
+        assert_eq!(
+            CelValue::cel_to_timestamp(1i32).unwrap_err(),
+            CelError::BadUnaryOperation {
+                op: "timestamp",
+                value: 1i32.conv()
+            }
+        );
+    }
+
+    #[test]
+    fn celvalue_duration_and_timestamp_ordering() {
+        assert!(
+            CelValue::cel_lt(
+                CelValue::Duration(Duration::seconds(1)),
+                CelValue::Duration(Duration::minutes(1))
+            )
+            .unwrap()
+        );
+        assert!(
+            !CelValue::cel_gt(
+                CelValue::Duration(Duration::seconds(1)),
+                CelValue::Duration(Duration::minutes(1))
+            )
+            .unwrap()
+        );
+
+        let early = CelValue::cel_to_timestamp("2020-01-01T00:00:00Z").unwrap();
+        let late = CelValue::cel_to_timestamp("2020-01-01T00:00:00-01:00").unwrap();
+        assert!(CelValue::cel_lt(&early, &late).unwrap());
+        assert!(CelValue::cel_gte(&late, &early).unwrap());
+    }
+
+    #[test]
+    fn celvalue_to_duration() {
+        assert_eq!(
+            CelValue::cel_to_duration("1h30m").unwrap(),
+            CelValue::Duration(Duration::minutes(90))
+        );
+        assert_eq!(
+            CelValue::cel_to_duration(CelValue::Duration(Duration::seconds(1))).unwrap(),
+            CelValue::Duration(Duration::seconds(1))
+        );
+        assert_eq!(
+            CelValue::cel_to_duration("forever").unwrap_err(),
+            CelError::BadConversion {
+                ty: "duration",
+                value: "forever".conv()
+            }
+        );
+        assert_eq!(
+            CelValue::cel_to_duration("1h30").unwrap_err().to_string(),
+            "invalid duration: 1h30"
+        );
+        assert_eq!(
+            CelValue::cel_to_duration(true).unwrap_err(),
+            CelError::BadUnaryOperation {
+                op: "duration",
+                value: true.conv()
+            }
+        );
+    }
+
     #[test]
     fn celvalue_to_bytes_variant_passthrough() {
         let bytes = Bytes::from_static(b"xyz");
@@ -3319,4 +3542,216 @@ mod tests {
         assert!(current.is_proto(), "CelMode should report Proto when set to Proto");
         assert!(!current.is_json(), "CelMode should not report JSON when set to Proto");
     }
+
+    #[test]
+    fn celvalue_eq_heterogeneous_numbers() {
+        assert_eq!(CelValue::Number(NumberTy::I64(1)), CelValue::Number(NumberTy::U64(1)));
+        assert_eq!(CelValue::Number(NumberTy::U64(1)), CelValue::Number(NumberTy::F64(1.0)));
+        assert_eq!(CelValue::Number(NumberTy::F64(1.0)), CelValue::Number(NumberTy::I64(1)));
+        assert_ne!(CelValue::Number(NumberTy::I64(-1)), CelValue::Number(NumberTy::U64(u64::MAX)));
+        assert_ne!(CelValue::Number(NumberTy::F64(1.5)), CelValue::Number(NumberTy::I64(1)));
+        assert_ne!(
+            CelValue::Number(NumberTy::F64(f64::NAN)),
+            CelValue::Number(NumberTy::F64(f64::NAN))
+        );
+        assert_ne!(
+            CelValue::Number(NumberTy::F64(0.1 + 0.2)),
+            CelValue::Number(NumberTy::F64(0.3)),
+            "equality is exact"
+        );
+    }
+
+    #[test]
+    fn celvalue_eq_map_unordered() {
+        let left = CelValue::Map(Arc::from(vec![(1i32.conv(), "a".conv()), (2i32.conv(), "b".conv())]));
+        let right = CelValue::Map(Arc::from(vec![(2u64.conv(), "b".conv()), (1.0f64.conv(), "a".conv())]));
+        assert_eq!(left, right);
+
+        let different = CelValue::Map(Arc::from(vec![(2u64.conv(), "a".conv()), (1.0f64.conv(), "b".conv())]));
+        assert_ne!(left, different);
+
+        let smaller = CelValue::Map(Arc::from(vec![(1i32.conv(), "a".conv())]));
+        assert_ne!(left, smaller);
+        assert_ne!(smaller, left);
+    }
+
+    #[test]
+    fn celvalue_map_numeric_keys() {
+        let map = CelValue::Map(Arc::from(vec![(1i32.conv(), "a".conv()), (2u64.conv(), "b".conv())]));
+        assert!(CelValue::cel_in(1u64, map.clone()).unwrap());
+        assert!(CelValue::cel_in(1.0f64, map.clone()).unwrap());
+        assert!(CelValue::cel_in(2i64, map.clone()).unwrap());
+        assert!(!CelValue::cel_in(1.5f64, map.clone()).unwrap());
+        assert_eq!(CelValue::cel_access(map.clone(), 2.0f64).unwrap(), "b".conv());
+
+        let mut hash_map = HashMap::new();
+        hash_map.insert(1i32, "a");
+        assert!(map_contains(&hash_map, 1u64));
+        assert!(map_contains(&hash_map, 1.0f64));
+        assert!(!map_contains(&hash_map, 1.5f64));
+        assert!(!map_contains(&hash_map, f64::NAN));
+        assert_eq!(*map_access(&hash_map, 1.0f64).unwrap(), "a");
+
+        let mut btree_map = BTreeMap::new();
+        btree_map.insert(u64::MAX, "max");
+        assert!(map_contains(&btree_map, u64::MAX));
+        assert!(!map_contains(&btree_map, -1i64));
+    }
+
+    /// A port of the equality rules of cel-go (`common/types`), which is the reference
+    /// implementation of the CEL spec. Only the types which tinc does not extend are covered.
+    mod cel_go {
+        use super::{CelValue, NumberTy};
+
+        pub(super) fn equal(left: &CelValue, right: &CelValue) -> bool {
+            match (left, right) {
+                (CelValue::Null, CelValue::Null) => true,
+                (CelValue::Bool(l), CelValue::Bool(r)) => l == r,
+                (CelValue::String(l), CelValue::String(r)) => l.as_ref() == r.as_ref(),
+                (CelValue::Number(l), CelValue::Number(r)) => number_equal(*l, *r),
+                (CelValue::List(l), CelValue::List(r)) => {
+                    l.len() == r.len() && l.iter().zip(r.iter()).all(|(l, r)| equal(l, r))
+                }
+                (CelValue::Map(l), CelValue::Map(r)) => {
+                    l.len() == r.len()
+                        && l.iter().all(|(key, value)| match r.iter().find(|(k, _)| equal(key, k)) {
+                            Some((_, v)) => equal(value, v),
+                            None => false,
+                        })
+                }
+                _ => false,
+            }
+        }
+
+        fn number_equal(left: NumberTy, right: NumberTy) -> bool {
+            match (left, right) {
+                (NumberTy::I64(l), NumberTy::I64(r)) => l == r,
+                (NumberTy::U64(l), NumberTy::U64(r)) => l == r,
+                (NumberTy::F64(l), NumberTy::F64(r)) => l == r,
+                // compareIntUint
+                (NumberTy::I64(i), NumberTy::U64(u)) | (NumberTy::U64(u), NumberTy::I64(i)) => i >= 0 && i as u64 == u,
+                // compareIntDouble
+                (NumberTy::I64(i), NumberTy::F64(d)) | (NumberTy::F64(d), NumberTy::I64(i)) => {
+                    !(d < i64::MIN as f64 || d > i64::MAX as f64) && i as f64 == d
+                }
+                // compareUintDouble
+                (NumberTy::U64(u), NumberTy::F64(d)) | (NumberTy::F64(d), NumberTy::U64(u)) => {
+                    !(d < 0.0 || d > u64::MAX as f64) && u as f64 == d
+                }
+            }
+        }
+    }
+
+    fn number_strategy() -> impl proptest::strategy::Strategy<Value = NumberTy> {
+        use proptest::prelude::*;
+
+        // Small values collide across types, the edges catch lossy conversions.
+        let int = prop_oneof![
+            -3i64..=3,
+            Just(i64::MIN),
+            Just(i64::MAX),
+            Just((1 << 53) + 1),
+            Just(-(1 << 53) - 1)
+        ];
+        let uint = prop_oneof![0u64..=3, Just(u64::MAX), Just(1 << 63), Just((1 << 53) + 1)];
+        let double = prop_oneof![
+            (-3i8..=3).prop_map(f64::from),
+            Just(0.5),
+            Just(-0.0),
+            Just(f64::NAN),
+            Just(f64::INFINITY),
+            Just(9_223_372_036_854_775_808.0),
+            Just(18_446_744_073_709_551_616.0),
+            Just(9_007_199_254_740_992.0)
+        ];
+
+        prop_oneof![
+            int.prop_map(NumberTy::I64),
+            uint.prop_map(NumberTy::U64),
+            double.prop_map(NumberTy::F64)
+        ]
+    }
+
+    fn key_strategy() -> impl proptest::strategy::Strategy<Value = CelValue<'static>> {
+        use proptest::prelude::*;
+
+        prop_oneof![
+            any::<bool>().prop_map(CelValue::Bool),
+            number_strategy().prop_map(CelValue::Number),
+            "[ab]{0,2}".prop_map(|s| CelValue::String(s.into())),
+        ]
+    }
+
+    fn value_strategy() -> impl proptest::strategy::Strategy<Value = CelValue<'static>> {
+        use proptest::prelude::*;
+
+        let leaf = prop_oneof![Just(CelValue::Null), key_strategy()];
+        leaf.prop_recursive(3, 16, 4, |inner| {
+            prop_oneof![
+                proptest::collection::vec(inner.clone(), 0..4).prop_map(|items| CelValue::List(items.into())),
+                proptest::collection::vec((key_strategy(), inner), 0..4).prop_map(|items| CelValue::Map(items.into())),
+            ]
+        })
+    }
+
+    /// Rewrites a value into an equal value of a different shape, numbers change their
+    /// type when the conversion is lossless and map entries are reordered.
+    fn reshape(value: &CelValue<'static>, seed: u64) -> CelValue<'static> {
+        match value {
+            CelValue::Number(number) => {
+                let candidates = [number.to_int(), number.to_uint(), number.to_double()];
+                let lossless = candidates
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .filter(|candidate| cel_go::equal(&CelValue::Number(*candidate), value))
+                    .collect::<Vec<_>>();
+                match lossless.get(seed as usize % lossless.len().max(1)) {
+                    Some(number) => CelValue::Number(*number),
+                    None => value.clone(),
+                }
+            }
+            CelValue::List(items) => CelValue::List(items.iter().map(|item| reshape(item, seed.rotate_left(7))).collect()),
+            CelValue::Map(items) => {
+                let mut items = items
+                    .iter()
+                    .map(|(key, value)| (reshape(key, seed.rotate_left(13)), reshape(value, seed.rotate_left(17))))
+                    .collect::<Vec<_>>();
+                if !items.is_empty() {
+                    let mid = seed as usize % items.len();
+                    items.rotate_left(mid);
+                }
+                CelValue::Map(items.into())
+            }
+            value => value.clone(),
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn celvalue_eq_matches_cel_go(left in value_strategy(), right in value_strategy()) {
+            proptest::prop_assert_eq!(left == right, cel_go::equal(&left, &right));
+            proptest::prop_assert_eq!(right == left, cel_go::equal(&right, &left));
+            proptest::prop_assert_eq!(CelValue::cel_eq(left.clone(), right.clone()).unwrap(), left == right);
+            proptest::prop_assert_eq!(CelValue::cel_neq(left.clone(), right.clone()).unwrap(), left != right);
+        }
+
+        #[test]
+        fn celvalue_eq_reshaped_matches_cel_go(value in value_strategy(), seed in proptest::prelude::any::<u64>()) {
+            let reshaped = reshape(&value, seed);
+            proptest::prop_assert_eq!(value == reshaped, cel_go::equal(&value, &reshaped));
+            proptest::prop_assert_eq!(reshaped == value, cel_go::equal(&reshaped, &value));
+        }
+
+        #[test]
+        fn celvalue_in_map_matches_cel_go(
+            key in key_strategy(),
+            entries in proptest::collection::vec((key_strategy(), value_strategy()), 0..6),
+        ) {
+            let expected = entries.iter().any(|(k, _)| cel_go::equal(&key, k));
+            let map = CelValue::Map(entries.into());
+            proptest::prop_assert_eq!(CelValue::cel_in(key.clone(), map.clone()).unwrap(), expected);
+            proptest::prop_assert_eq!(CelValue::cel_contains(map.clone(), key.clone()).unwrap(), expected);
+            proptest::prop_assert_eq!(CelValue::cel_access(map, key).is_ok(), expected);
+        }
+    }
 }
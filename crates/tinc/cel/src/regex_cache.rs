@@ -0,0 +1,170 @@
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+use hashlink::LruCache;
+
+/// The number of compiled patterns kept by default.
+pub const DEFAULT_REGEX_CACHE_CAPACITY: usize = 256;
+
+/// The cache is split into shards by pattern hash, so concurrent requests with different
+/// patterns rarely wait on the same lock.
+const SHARD_COUNT: usize = 16;
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_REGEX_CACHE_CAPACITY);
+
+static SHARDS: LazyLock<[Mutex<RegexCache>; SHARD_COUNT]> =
+    LazyLock::new(|| std::array::from_fn(|_| Mutex::new(RegexCache::new(shard_capacity(CAPACITY.load(Ordering::Relaxed))))));
+
+static HASHER: LazyLock<std::hash::RandomState> = LazyLock::new(std::hash::RandomState::new);
+
+/// Sets the maximum number of compiled patterns kept by the cache used for `matches()`
+/// with patterns that are only known at runtime.
+///
+/// The capacity is split evenly across the shards of the cache and the least recently used
+/// patterns of a shard are evicted first. A capacity of `0` disables the cache.
+pub fn set_regex_cache_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    for shard in SHARDS.iter() {
+        shard
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_capacity(shard_capacity(capacity));
+    }
+}
+
+/// Returns the maximum number of compiled patterns kept by the cache.
+pub fn regex_cache_capacity() -> usize {
+    CAPACITY.load(Ordering::Relaxed)
+}
+
+fn shard_capacity(capacity: usize) -> usize {
+    capacity.div_ceil(SHARD_COUNT)
+}
+
+fn shard(pattern: &str) -> &'static Mutex<RegexCache> {
+    &SHARDS[HASHER.hash_one(pattern) as usize % SHARD_COUNT]
+}
+
+/// Compiles the pattern or returns the cached result of a previous compilation.
+pub(crate) fn compile(pattern: &str) -> Result<Arc<regex::Regex>, regex::Error> {
+    let shard = shard(pattern);
+    if let Some(result) = shard.lock().unwrap_or_else(PoisonError::into_inner).get(pattern) {
+        return result;
+    }
+
+    // Compile without holding the lock, so a slow pattern does not block other requests.
+    let result = regex::Regex::new(pattern).map(Arc::new);
+    shard
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(pattern, result.clone());
+    result
+}
+
+/// A least recently used cache of compiled patterns.
+///
+/// Invalid patterns are cached as well, so a bad pattern sent on every request is only compiled once.
+struct RegexCache {
+    entries: LruCache<Box<str>, Result<Arc<regex::Regex>, regex::Error>>,
+}
+
+impl RegexCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+        }
+    }
+
+    fn get(&mut self, pattern: &str) -> Option<Result<Arc<regex::Regex>, regex::Error>> {
+        self.entries.get(pattern).cloned()
+    }
+
+    fn insert(&mut self, pattern: &str, result: Result<Arc<regex::Regex>, regex::Error>) {
+        if self.entries.capacity() == 0 {
+            return;
+        }
+
+        self.entries.insert(pattern.into(), result);
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.entries.set_capacity(capacity);
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::RegexCache;
+
+    fn compile(cache: &mut RegexCache, pattern: &str) -> Arc<regex::Regex> {
+        if let Some(result) = cache.get(pattern) {
+            return result.unwrap();
+        }
+
+        let result = regex::Regex::new(pattern).map(Arc::new);
+        cache.insert(pattern, result.clone());
+        result.unwrap()
+    }
+
+    #[test]
+    fn reuses_compiled_patterns() {
+        let mut cache = RegexCache::new(2);
+
+        let a = compile(&mut cache, "^a");
+        assert!(Arc::ptr_eq(&a, &compile(&mut cache, "^a")));
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = RegexCache::new(2);
+
+        let a = compile(&mut cache, "^a");
+        compile(&mut cache, "^b");
+        // touch `^a` so `^b` becomes the least recently used
+        compile(&mut cache, "^a");
+        compile(&mut cache, "^c");
+
+        assert_eq!(cache.entries.len(), 2);
+        assert!(cache.get("^b").is_none());
+        assert!(Arc::ptr_eq(&a, &cache.get("^a").unwrap().unwrap()));
+        assert!(cache.get("^c").is_some());
+    }
+
+    #[test]
+    fn caches_invalid_patterns() {
+        let mut cache = RegexCache::new(2);
+        let pattern = String::from("(");
+        cache.insert(&pattern, regex::Regex::new(&pattern).map(Arc::new));
+        assert!(cache.get("(").unwrap().is_err());
+    }
+
+    #[test]
+    fn shard_capacity() {
+        assert_eq!(super::shard_capacity(0), 0);
+        assert_eq!(super::shard_capacity(1), 1);
+        assert_eq!(super::shard_capacity(super::DEFAULT_REGEX_CACHE_CAPACITY), 16);
+        assert_eq!(super::shard_capacity(17), 2);
+    }
+
+    #[test]
+    fn capacity() {
+        let mut cache = RegexCache::new(4);
+        for pattern in ["a", "b", "c", "d"] {
+            compile(&mut cache, pattern);
+        }
+
+        cache.set_capacity(1);
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.get("d").is_some());
+
+        cache.set_capacity(0);
+        assert!(cache.entries.is_empty());
+        compile(&mut cache, "e");
+        assert!(cache.entries.is_empty());
+    }
+}
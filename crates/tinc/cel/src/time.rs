@@ -0,0 +1,162 @@
+//! Parsing and formatting of the string representations of CEL durations and timestamps.
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Parses a duration in the format accepted by the CEL `duration()` function.
+///
+/// This is a sequence of decimal numbers with an optional fraction and a unit suffix (`ns`, `us`, `µs`, `ms`, `s`,
+/// `m` or `h`) with an optional leading sign, for example `"1h30m"`, `"1.5s"` or `"-300ms"`.
+pub(crate) fn parse_duration(input: &str) -> Option<chrono::Duration> {
+    let (negative, mut rest) = match input.as_bytes().first()? {
+        b'-' => (true, &input[1..]),
+        b'+' => (false, &input[1..]),
+        _ => (false, input),
+    };
+
+    if rest == "0" {
+        return Some(chrono::Duration::zero());
+    }
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total: i128 = 0;
+    while !rest.is_empty() {
+        let int_len = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let (int, after_int) = rest.split_at(int_len);
+        let (frac, after_frac) = match after_int.strip_prefix('.') {
+            Some(after_dot) => after_dot.split_at(after_dot.bytes().take_while(u8::is_ascii_digit).count()),
+            None => ("", after_int),
+        };
+
+        if int.is_empty() && frac.is_empty() {
+            return None;
+        }
+
+        let unit_len = after_frac
+            .char_indices()
+            .find(|(_, c)| c.is_ascii_digit() || *c == '.')
+            .map_or(after_frac.len(), |(idx, _)| idx);
+        let (unit, after_unit) = after_frac.split_at(unit_len);
+        let unit: i128 = match unit {
+            "ns" => 1,
+            "us" | "µs" | "μs" => 1_000,
+            "ms" => 1_000_000,
+            "s" => NANOS_PER_SECOND,
+            "m" => 60 * NANOS_PER_SECOND,
+            "h" => 60 * 60 * NANOS_PER_SECOND,
+            _ => return None,
+        };
+
+        let int = if int.is_empty() { 0 } else { int.parse::<i128>().ok()? };
+        total = total.checked_add(int.checked_mul(unit)?)?;
+
+        // Digits beyond the 18th cannot contribute a whole nanosecond, even for hours.
+        let frac = &frac[..frac.len().min(18)];
+        if !frac.is_empty() {
+            let scale = 10i128.pow(frac.len() as u32);
+            total = total.checked_add(frac.parse::<i128>().ok()? * unit / scale)?;
+        }
+
+        rest = after_unit;
+    }
+
+    let total = if negative { -total } else { total };
+    Some(chrono::Duration::nanoseconds(i64::try_from(total).ok()?))
+}
+
+/// Formats a duration as the number of seconds with an `s` suffix, for example `"5400s"` or `"1.5s"`.
+///
+/// This is the format used by the protobuf JSON mapping of `google.protobuf.Duration` and by the CEL `string()`
+/// function.
+pub(crate) fn format_duration(duration: chrono::Duration) -> String {
+    let nanos = i128::from(duration.num_seconds()) * NANOS_PER_SECOND + i128::from(duration.subsec_nanos());
+    let sign = if nanos < 0 { "-" } else { "" };
+    let seconds = nanos.abs() / NANOS_PER_SECOND;
+    let subsec = nanos.abs() % NANOS_PER_SECOND;
+
+    if subsec == 0 {
+        format!("{sign}{seconds}s")
+    } else {
+        let subsec = format!("{subsec:09}");
+        format!("{sign}{seconds}.{}s", subsec.trim_end_matches('0'))
+    }
+}
+
+/// Parses an RFC 3339 timestamp, as accepted by the CEL `timestamp()` function.
+pub(crate) fn parse_timestamp(input: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    chrono::DateTime::parse_from_rfc3339(input).ok()
+}
+
+/// Formats a timestamp as an RFC 3339 string in UTC, for example `"2020-01-01T00:00:00Z"`.
+///
+/// Fractional seconds are only included when present, with 3, 6 or 9 digits.
+pub(crate) fn format_timestamp(timestamp: chrono::DateTime<chrono::FixedOffset>) -> String {
+    timestamp.to_utc().to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("0"), Some(Duration::zero()));
+        assert_eq!(parse_duration("-0"), Some(Duration::zero()));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::milliseconds(1500)));
+        assert_eq!(parse_duration("-300ms"), Some(Duration::milliseconds(-300)));
+        assert_eq!(parse_duration("+5s"), Some(Duration::seconds(5)));
+        assert_eq!(parse_duration(".5h"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration("2h45m10s"), Some(Duration::seconds(2 * 3600 + 45 * 60 + 10)));
+        assert_eq!(parse_duration("1us"), Some(Duration::microseconds(1)));
+        assert_eq!(parse_duration("1µs"), Some(Duration::microseconds(1)));
+        assert_eq!(parse_duration("7ns"), Some(Duration::nanoseconds(7)));
+        assert_eq!(
+            parse_duration("1.0000000000000000000001s"),
+            Some(Duration::seconds(1)),
+            "excess precision is truncated"
+        );
+
+        for invalid in [
+            "",
+            "-",
+            "1",
+            "s",
+            "1.s.",
+            "1x",
+            "1h 30m",
+            ".s",
+            "1h-30m",
+            "99999999999999999999h",
+        ] {
+            assert_eq!(parse_duration(invalid), None, "{invalid:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(format_duration(Duration::zero()), "0s");
+        assert_eq!(format_duration(Duration::minutes(90)), "5400s");
+        assert_eq!(format_duration(Duration::milliseconds(1500)), "1.5s");
+        assert_eq!(format_duration(Duration::milliseconds(-1500)), "-1.5s");
+        assert_eq!(format_duration(Duration::milliseconds(-300)), "-0.3s");
+        assert_eq!(format_duration(Duration::nanoseconds(1)), "0.000000001s");
+    }
+
+    #[test]
+    fn timestamps() {
+        let ts = parse_timestamp("2020-01-01T00:00:00Z").unwrap();
+        assert_eq!(format_timestamp(ts), "2020-01-01T00:00:00Z");
+
+        let ts = parse_timestamp("2020-01-01T02:00:00.5+02:00").unwrap();
+        assert_eq!(format_timestamp(ts), "2020-01-01T00:00:00.500Z");
+
+        assert_eq!(parse_timestamp("2020-01-01"), None);
+        assert_eq!(parse_timestamp("not a timestamp"), None);
+    }
+}
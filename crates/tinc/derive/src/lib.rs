@@ -23,6 +23,7 @@ mod message_tracker;
 /// - `tagged`: Can only be used on enums to denote a tagged enum, default is false.
 /// ## Field / Variant Opts
 /// - `enum_path`: Forces the field to be treated as an enum, default is None.
+/// - `bytes`: The path to the encoding of a bytes field, default is None (standard base64).
 /// - `oneof`: The field should be treated as a oneof.
 #[proc_macro_derive(Tracker, attributes(tinc))]
 pub fn derive_message_tracker(input: TokenStream) -> TokenStream {
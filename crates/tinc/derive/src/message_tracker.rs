@@ -63,12 +63,14 @@ impl Default for TincContainerOptions {
 #[derive(Default)]
 struct TincFieldOptions {
     pub enum_path: Option<syn::Path>,
+    pub bytes_encoding: Option<syn::Path>,
     pub oneof: bool,
 }
 
 impl TincFieldOptions {
     fn from_attributes<'a>(attrs: impl IntoIterator<Item = &'a syn::Attribute>) -> syn::Result<Self> {
         let mut enum_ = None;
+        let mut bytes = None;
         let mut oneof = false;
 
         for attr in attrs {
@@ -86,6 +88,14 @@ impl TincFieldOptions {
                         let _: syn::token::Eq = meta.input.parse()?;
                         let path: syn::LitStr = meta.input.parse()?;
                         enum_ = Some(syn::parse_str(&path.value())?);
+                    } else if meta.path.is_ident("bytes") {
+                        if bytes.is_some() {
+                            return Err(meta.error("bytes option already set"));
+                        }
+
+                        let _: syn::token::Eq = meta.input.parse()?;
+                        let path: syn::LitStr = meta.input.parse()?;
+                        bytes = Some(syn::parse_str(&path.value())?);
                     } else if meta.path.is_ident("oneof") {
                         oneof = true;
                     } else {
@@ -102,6 +112,10 @@ impl TincFieldOptions {
             options.enum_path = Some(enum_);
         }
 
+        if let Some(bytes) = bytes {
+            options.bytes_encoding = Some(bytes);
+        }
+
         if oneof {
             options.oneof = true;
         }
@@ -148,16 +162,26 @@ fn derive_message_tracker_struct(ident: syn::Ident, opts: TincContainerOptions,
             let field_ident = f.ident.as_ref().expect("field must have an identifier");
             let ty = &f.ty;
 
-            let TincFieldOptions { enum_path, oneof } = TincFieldOptions::from_attributes(&f.attrs)?;
-
-            if enum_path.is_some() && oneof {
-                return Err(syn::Error::new(f.span(), "enum and oneof cannot both be set"));
+            let TincFieldOptions {
+                enum_path,
+                bytes_encoding,
+                oneof,
+            } = TincFieldOptions::from_attributes(&f.attrs)?;
+
+            if [enum_path.is_some(), bytes_encoding.is_some(), oneof]
+                .into_iter()
+                .filter(|set| *set)
+                .count()
+                > 1
+            {
+                return Err(syn::Error::new(f.span(), "only one of enum, bytes and oneof can be set"));
             }
 
-            let ty = match enum_path {
-                Some(enum_path) => quote! { <#ty as #crate_path::__private::EnumHelper>::Target<#enum_path> },
-                None if oneof => quote! { <#ty as #crate_path::__private::OneOfHelper>::Target },
-                None => quote! { #ty },
+            let ty = match (enum_path, bytes_encoding) {
+                (Some(enum_path), _) => quote! { <#ty as #crate_path::__private::EnumHelper>::Target<#enum_path> },
+                (_, Some(encoding)) => quote! { <#ty as #crate_path::__private::BytesHelper>::Target<#encoding> },
+                _ if oneof => quote! { <#ty as #crate_path::__private::OneOfHelper>::Target },
+                _ => quote! { #ty },
             };
 
             Ok(quote! {
@@ -221,8 +245,11 @@ fn derive_message_tracker_enum(ident: syn::Ident, opts: TincContainerOptions, da
             let field = &unnamed.unnamed[0];
             let ty = &field.ty;
 
-            let TincFieldOptions { enum_path, oneof } =
-                TincFieldOptions::from_attributes(v.attrs.iter().chain(field.attrs.iter()))?;
+            let TincFieldOptions {
+                enum_path,
+                bytes_encoding,
+                oneof,
+            } = TincFieldOptions::from_attributes(v.attrs.iter().chain(field.attrs.iter()))?;
 
             if oneof {
                 return Err(syn::Error::new(
@@ -231,11 +258,18 @@ fn derive_message_tracker_enum(ident: syn::Ident, opts: TincContainerOptions, da
                 ));
             }
 
-            let ty = match enum_path {
-                Some(enum_path) => quote! {
+            if enum_path.is_some() && bytes_encoding.is_some() {
+                return Err(syn::Error::new(v.span(), "enum and bytes cannot both be set"));
+            }
+
+            let ty = match (enum_path, bytes_encoding) {
+                (Some(enum_path), _) => quote! {
                     <#ty as #crate_path::__private::EnumHelper>::Target<#enum_path>
                 },
-                None => quote! {
+                (_, Some(encoding)) => quote! {
+                    <#ty as #crate_path::__private::BytesHelper>::Target<#encoding>
+                },
+                _ => quote! {
                     #ty
                 },
             };
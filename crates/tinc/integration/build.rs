@@ -4,6 +4,19 @@
 fn main() {
     tinc_build::Config::prost()
         .btree_map(".")
+        .message_attribute(".simple.SimpleMessage", "#[derive(Eq, Hash)]")
+        .strict()
+        .openapi_info("tinc integration tests", "1.0.0")
+        .openapi_server("/api")
+        .openapi_service_tag(".bytes_service", "bytes")
+        .openapi_tag_description("bytes", "Binary uploads and downloads.")
+        .cors(
+            ".custom_verb_service",
+            tinc_build::Cors::new()
+                .allow_origin("https://example.com")
+                .allow_header("authorization")
+                .max_age(600),
+        )
         .compile_protos(
             &[
                 "pb/simple.proto",
@@ -16,10 +29,29 @@ fn main() {
                 "pb/visibility.proto",
                 "pb/well_known.proto",
                 "pb/simple_service.proto",
+                "pb/paginated_service.proto",
                 "pb/bytes_service.proto",
+                "pb/custom_verb_service.proto",
                 "pb/expressions.proto",
             ],
             &["pb"],
         )
         .unwrap();
+
+    // Build the simple service again with module files, into its own directory so the
+    // other tests keep using the inline root module.
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let module_files_out_dir = std::path::Path::new(&out_dir).join("module_files");
+    std::fs::create_dir_all(&module_files_out_dir).unwrap();
+
+    // Safety: build scripts are single threaded.
+    unsafe { std::env::set_var("OUT_DIR", &module_files_out_dir) };
+
+    tinc_build::Config::prost()
+        .module_files()
+        .compile_protos(&["pb/simple_service.proto"], &["pb"])
+        .unwrap();
+
+    // Safety: build scripts are single threaded.
+    unsafe { std::env::set_var("OUT_DIR", &out_dir) };
 }
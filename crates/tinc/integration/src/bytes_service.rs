@@ -15,6 +15,13 @@ impl pb::bytes_service_server::BytesService for Svc {
     async fn bytes(&self, request: tonic::Request<pb::BytesPayload>) -> tonic::Result<tonic::Response<pb::BytesPayload>> {
         Ok(request.into_inner().into())
     }
+
+    async fn encoded(
+        &self,
+        request: tonic::Request<pb::EncodedPayload>,
+    ) -> tonic::Result<tonic::Response<pb::EncodedPayload>> {
+        Ok(request.into_inner().into())
+    }
 }
 
 #[tokio::test]
@@ -86,6 +93,83 @@ async fn test_bytes_service_rest_post_binary() {
     assert_eq!(body, random_data);
 }
 
+#[tokio::test]
+async fn test_bytes_service_rest_encoded() {
+    let mut client = pb::bytes_service_tinc::BytesServiceTinc::new(Svc {}).into_router();
+
+    let req = http::Request::builder()
+        .uri("/encoded")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(
+            // The standard alphabet and padding are accepted for base64url fields
+            r#"{
+                "id": "-_8",
+                "digest": "00FFab",
+                "raw": "+/8=",
+                "parents": ["AAE", "+/8="],
+                "checksums": { "a": "0102" },
+                "previous": null,
+                "key": { "key_id": "ff" }
+            }"#
+            .as_bytes(),
+        )))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+    assert_eq!(resp.status(), http::StatusCode::OK);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "checksums": {
+        "a": "0102"
+      },
+      "digest": "00ffab",
+      "id": "-_8",
+      "key": {
+        "key_id": "ff"
+      },
+      "parents": [
+        "AAE",
+        "-_8"
+      ],
+      "previous": null,
+      "raw": "+/8="
+    }
+    "#);
+
+    let req = http::Request::builder()
+        .uri("/encoded")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(
+            r#"{ "id": "", "digest": "0g", "raw": "", "parents": [], "checksums": {} }"#.as_bytes(),
+        )))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_bytes_service_grpc_encoded() {
+    let mut client =
+        pb::bytes_service_client::BytesServiceClient::new(pb::bytes_service_server::BytesServiceServer::new(Svc {}));
+
+    let payload = pb::EncodedPayload {
+        id: vec![0xfb, 0xff],
+        digest: vec![0x00, 0xff],
+        key: Some(pb::encoded_payload::Key::KeyId(vec![1])),
+        ..Default::default()
+    };
+
+    let response = client.encoded(payload.clone()).await.unwrap();
+    assert_eq!(response.into_inner(), payload);
+}
+
 #[test]
 fn test_bytes_service_rest_schema() {
     let svc = pb::bytes_service_tinc::BytesServiceTinc::new(Svc {});
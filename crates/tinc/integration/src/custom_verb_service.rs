@@ -0,0 +1,276 @@
+use http_body_util::BodyExt;
+use tinc::TincService;
+use tower::Service;
+
+mod pb {
+    #![allow(clippy::all)]
+    tinc::include_proto!("custom_verb_service");
+}
+
+struct Svc {}
+
+fn task_response(action: &str, id: i64) -> tonic::Response<pb::TaskResponse> {
+    pb::TaskResponse {
+        result: format!("{action} {id}"),
+    }
+    .into()
+}
+
+#[tonic::async_trait]
+impl pb::custom_verb_service_server::CustomVerbService for Svc {
+    async fn get_task(&self, request: tonic::Request<pb::TaskRequest>) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(task_response("get", request.get_ref().id))
+    }
+
+    async fn update_task(
+        &self,
+        request: tonic::Request<pb::TaskRequest>,
+    ) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(task_response("update", request.get_ref().id))
+    }
+
+    async fn cancel_task(
+        &self,
+        request: tonic::Request<pb::TaskRequest>,
+    ) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(task_response("cancel", request.get_ref().id))
+    }
+
+    async fn retry_task(
+        &self,
+        request: tonic::Request<pb::TaskRequest>,
+    ) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(task_response("retry", request.get_ref().id))
+    }
+
+    async fn batch_get_tasks(
+        &self,
+        request: tonic::Request<pb::BatchGetTasksRequest>,
+    ) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(pb::TaskResponse {
+            result: format!("batch get {:?}", request.get_ref().ids),
+        }
+        .into())
+    }
+
+    async fn rename_task(
+        &self,
+        request: tonic::Request<pb::NamedTaskRequest>,
+    ) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(pb::TaskResponse {
+            result: format!("rename {}", request.get_ref().name),
+        }
+        .into())
+    }
+
+    async fn archive_task(
+        &self,
+        request: tonic::Request<pb::NamedTaskRequest>,
+    ) -> tonic::Result<tonic::Response<pb::TaskResponse>> {
+        Ok(pb::TaskResponse {
+            result: format!("archive {}", request.get_ref().name),
+        }
+        .into())
+    }
+}
+
+async fn rest_call(method: &str, uri: &str) -> (http::StatusCode, String) {
+    let mut client = pb::custom_verb_service_tinc::CustomVerbServiceTinc::new(Svc {}).into_router();
+
+    let body = if matches!(method, "POST" | "PATCH") { "{}" } else { "" };
+    let resp = client
+        .call(
+            http::Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = resp.status();
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let result = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|body| Some(body.get("result")?.as_str()?.to_owned()))
+        .unwrap_or_default();
+
+    (status, result)
+}
+
+#[tokio::test]
+async fn test_custom_verb_service_rest() {
+    for (method, uri, result) in [
+        ("GET", "/tasks/1", "get 1"),
+        ("POST", "/tasks/2", "update 2"),
+        ("POST", "/tasks/3:cancel", "cancel 3"),
+        ("POST", "/tasks/4:retry", "retry 4"),
+        ("POST", "/v1/tasks/5:cancel", "cancel 5"),
+        ("DELETE", "/tasks/6", "cancel 6"),
+        ("GET", "/tasks:batchGet?ids[0]=7&ids[1]=8", "batch get [7, 8]"),
+    ] {
+        assert_eq!(
+            rest_call(method, uri).await,
+            (http::StatusCode::OK, result.to_owned()),
+            "{method} {uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_custom_verb_service_rest_parameter_names() {
+    // `/tasks/{id}` and `/tasks/{name}` share a route, the parameters are bound to the name of
+    // the endpoint the request is dispatched to.
+    for (method, uri, result) in [
+        ("PATCH", "/tasks/first", "rename first"),
+        ("POST", "/tasks/second:archive", "archive second"),
+        ("POST", "/tasks/a%20b:archive", "archive a b"),
+        ("POST", "/tasks/3:cancel", "cancel 3"),
+        ("GET", "/tasks/4", "get 4"),
+    ] {
+        assert_eq!(
+            rest_call(method, uri).await,
+            (http::StatusCode::OK, result.to_owned()),
+            "{method} {uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_custom_verb_service_rest_unknown_verb() {
+    // the verb is not stripped, so the id is not a valid integer
+    let (status, _) = rest_call("POST", "/tasks/1:unknown").await;
+    assert_eq!(status, http::StatusCode::BAD_REQUEST);
+
+    // there is no endpoint without a verb
+    let (status, _) = rest_call("POST", "/v1/tasks/1").await;
+    assert_eq!(status, http::StatusCode::NOT_FOUND);
+
+    // a verb is only matched after a non-empty parameter
+    let (status, _) = rest_call("POST", "/v1/tasks/:cancel").await;
+    assert_eq!(status, http::StatusCode::NOT_FOUND);
+
+    let (status, _) = rest_call("GET", "/tasks/1:cancel").await;
+    assert_eq!(status, http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_custom_verb_service_rest_protobuf_path_precedence() {
+    let mut client = pb::custom_verb_service_tinc::CustomVerbServiceTinc::new(Svc {}).into_router();
+
+    let resp = client
+        .call(
+            http::Request::builder()
+                .uri("/tasks/1:cancel")
+                .method("POST")
+                .header(http::header::CONTENT_TYPE, "application/x-protobuf")
+                .body(axum::body::Body::from(prost::Message::encode_to_vec(&pb::TaskRequest {
+                    id: 2,
+                })))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::OK);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: pb::TaskResponse = prost::Message::decode(body).unwrap();
+
+    assert_eq!(response.result, "cancel 1");
+}
+
+fn preflight(uri: &str, origin: &str, method: &str) -> http::Request<axum::body::Body> {
+    http::Request::builder()
+        .uri(uri)
+        .method("OPTIONS")
+        .header(http::header::ORIGIN, origin)
+        .header(http::header::ACCESS_CONTROL_REQUEST_METHOD, method)
+        .header(http::header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization, content-type")
+        .body(axum::body::Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_custom_verb_service_rest_cors() {
+    let mut client = pb::custom_verb_service_tinc::CustomVerbServiceTinc::new(Svc {}).into_router();
+
+    let resp = client
+        .call(preflight("/tasks/1", "https://example.com", "POST"))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+    assert_eq!(
+        resp.headers()[http::header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://example.com"
+    );
+    assert_eq!(
+        resp.headers()[http::header::ACCESS_CONTROL_ALLOW_HEADERS],
+        "authorization, content-type"
+    );
+    assert_eq!(resp.headers()[http::header::ACCESS_CONTROL_MAX_AGE], "600");
+    let methods = resp.headers()[http::header::ACCESS_CONTROL_ALLOW_METHODS].to_str().unwrap();
+    assert!(methods.contains("POST"), "{methods}");
+
+    let resp = client
+        .call(preflight("/tasks/1", "https://example.org", "POST"))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+    assert!(!resp.headers().contains_key(http::header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "403",
+      "message": "origin is not allowed"
+    }
+    "#);
+
+    // error responses carry the cors headers too
+    let resp = client
+        .call(
+            http::Request::builder()
+                .uri("/tasks/1:unknown")
+                .method("POST")
+                .header(http::header::ORIGIN, "https://example.com")
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from("{}"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    assert_eq!(
+        resp.headers()[http::header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://example.com"
+    );
+    assert_eq!(resp.headers()[http::header::VARY], "origin");
+
+    // the build time policy can be replaced at runtime
+    let mut client = pb::custom_verb_service_tinc::CustomVerbServiceTinc::new(Svc {})
+        .with_cors(tinc::cors::CorsPolicy::permissive())
+        .into_router();
+
+    let resp = client
+        .call(preflight("/tasks/1", "https://example.org", "DELETE"))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::NO_CONTENT);
+    assert_eq!(resp.headers()[http::header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+}
+
+#[test]
+fn test_custom_verb_service_rest_schema() {
+    let svc = pb::custom_verb_service_tinc::CustomVerbServiceTinc::new(Svc {});
+
+    insta::assert_json_snapshot!(svc.openapi_schema());
+}
@@ -69,102 +69,186 @@ fn test_string_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be exactly `5` characters long",
+                    rule: "tinc.StringConstraints.len",
+                    rule_value: Some(
+                        "5",
+                    ),
+                    expression: "input.size() == this",
                 },
                 fatal: true,
                 path: "code",
+                pointer: "/code",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be at least `3` characters long",
+                    rule: "tinc.StringConstraints.min_len",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input.size() >= this",
                 },
                 fatal: true,
                 path: "name",
+                pointer: "/name",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must match the pattern `^(\\+\\d{1,2}\\s?)?\\(?\\d{3}\\)?[\\s.-]?\\d{3}[\\s.-]?\\d{4}$`",
+                    rule: "tinc.StringConstraints.match",
+                    rule_value: Some(
+                        "^(\\+\\d{1,2}\\s?)?\\(?\\d{3}\\)?[\\s.-]?\\d{3}[\\s.-]?\\d{4}$",
+                    ),
+                    expression: "input.matches(this)",
                 },
                 fatal: true,
                 path: "phone_number",
+                pointer: "/phone_number",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not match the pattern `@gmail\\.com$`",
+                    rule: "tinc.StringConstraints.not_match",
+                    rule_value: Some(
+                        "@gmail\\.com$",
+                    ),
+                    expression: "!(input.matches(this))",
                 },
                 fatal: true,
                 path: "email",
+                pointer: "/email",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must start with `fk_`",
+                    rule: "tinc.StringConstraints.prefix",
+                    rule_value: Some(
+                        "fk_",
+                    ),
+                    expression: "input.startsWith(this)",
                 },
                 fatal: true,
                 path: "foreign_key",
+                pointer: "/foreign_key",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must end with `_id`",
+                    rule: "tinc.StringConstraints.suffix",
+                    rule_value: Some(
+                        "_id",
+                    ),
+                    expression: "input.endsWith(this)",
                 },
                 fatal: true,
                 path: "primary_key",
+                pointer: "/primary_key",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must contain `e`",
+                    rule: "tinc.StringConstraints.contains",
+                    rule_value: Some(
+                        "e",
+                    ),
+                    expression: "input.contains(this)",
                 },
                 fatal: true,
                 path: "word_with_e",
+                pointer: "/word_with_e",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not contain `z`",
+                    rule: "tinc.StringConstraints.not_contains",
+                    rule_value: Some(
+                        "z",
+                    ),
+                    expression: "!input.contains(this)",
                 },
                 fatal: true,
                 path: "word_without_z",
+                pointer: "/word_without_z",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[chocolate, vanilla]`",
+                    rule: "tinc.StringConstraints.in",
+                    rule_value: Some(
+                        "[chocolate, vanilla]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "ice_cream",
+                pointer: "/ice_cream",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[troy]`",
+                    rule: "tinc.StringConstraints.not_in",
+                    rule_value: Some(
+                        "[troy]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "best_friend",
+                pointer: "/best_friend",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be a valid ipv4 address",
+                    rule: "tinc.StringConstraints.ipv4",
+                    rule_value: Some(
+                        "true",
+                    ),
+                    expression: "!this || input.isIpv4()",
                 },
                 fatal: true,
                 path: "ipv4_only",
+                pointer: "/ipv4_only",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be a valid ipv6 address",
+                    rule: "tinc.StringConstraints.ipv6",
+                    rule_value: Some(
+                        "true",
+                    ),
+                    expression: "!this || input.isIpv6()",
                 },
                 fatal: true,
                 path: "ipv6_only",
+                pointer: "/ipv6_only",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be a valid ipv4 or ipv6 address",
+                    rule: "tinc.StringConstraints.ip",
+                    rule_value: Some(
+                        "true",
+                    ),
+                    expression: "!this || input.isIpv4() || input.isIpv6()",
                 },
                 fatal: true,
                 path: "ipv4_or_6_only[0]",
+                pointer: "/ipv4_or_6_only/0",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be a valid ipv4 or ipv6 address",
+                    rule: "tinc.StringConstraints.ip",
+                    rule_value: Some(
+                        "true",
+                    ),
+                    expression: "!this || input.isIpv4() || input.isIpv6()",
                 },
                 fatal: true,
                 path: "ipv4_or_6_only[1]",
+                pointer: "/ipv4_or_6_only/1",
             },
         ],
     }
@@ -212,46 +296,82 @@ fn test_float_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than or equal to 1.00",
+                    rule: "tinc.FloatConstraints.lte",
+                    rule_value: Some(
+                        "1.00",
+                    ),
+                    expression: "input <= this",
                 },
                 fatal: true,
                 path: "zero_to_one",
+                pointer: "/zero_to_one",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0.00`",
+                    rule: "tinc.FloatConstraints.gt",
+                    rule_value: Some(
+                        "0.00",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "bigger_than_zero",
+                pointer: "/bigger_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than `0.00`",
+                    rule: "tinc.FloatConstraints.lt",
+                    rule_value: Some(
+                        "0.00",
+                    ),
+                    expression: "input < this",
                 },
                 fatal: true,
                 path: "less_than_zero",
+                pointer: "/less_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[5.10, 10.20, -5.20, -10.40]`",
+                    rule: "tinc.FloatConstraints.in",
+                    rule_value: Some(
+                        "[5.10, 10.20, -5.20, -10.40]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "bucket",
+                pointer: "/bucket",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[3.14, 2.71]`",
+                    rule: "tinc.FloatConstraints.not_in",
+                    rule_value: Some(
+                        "[3.14, 2.71]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "coolest_float",
+                pointer: "/coolest_float",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `3.00`",
+                    rule: "tinc.FloatConstraints.const",
+                    rule_value: Some(
+                        "3.00",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "pi",
+                pointer: "/pi",
             },
         ],
     }
@@ -299,46 +419,82 @@ fn test_double_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than or equal to `1.00`",
+                    rule: "tinc.DoubleConstraints.lte",
+                    rule_value: Some(
+                        "1.00",
+                    ),
+                    expression: "input <= this",
                 },
                 fatal: true,
                 path: "zero_to_one",
+                pointer: "/zero_to_one",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0.00`",
+                    rule: "tinc.DoubleConstraints.gt",
+                    rule_value: Some(
+                        "0.00",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "bigger_than_zero",
+                pointer: "/bigger_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than `0.00`",
+                    rule: "tinc.DoubleConstraints.lt",
+                    rule_value: Some(
+                        "0.00",
+                    ),
+                    expression: "input < this",
                 },
                 fatal: true,
                 path: "less_than_zero",
+                pointer: "/less_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[5.10, 10.20, -5.20, -10.40]`",
+                    rule: "tinc.DoubleConstraints.in",
+                    rule_value: Some(
+                        "[5.10, 10.20, -5.20, -10.40]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "bucket",
+                pointer: "/bucket",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[3.14, 2.71]`",
+                    rule: "tinc.DoubleConstraints.not_in",
+                    rule_value: Some(
+                        "[3.14, 2.71]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "coolest_float",
+                pointer: "/coolest_float",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `3.00`",
+                    rule: "tinc.DoubleConstraints.const",
+                    rule_value: Some(
+                        "3.00",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "pi",
+                pointer: "/pi",
             },
         ],
     }
@@ -386,46 +542,82 @@ fn test_int32_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than or equal to `0`",
+                    rule: "tinc.Int32Constraints.gte",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input >= this",
                 },
                 fatal: true,
                 path: "zero_to_ten",
+                pointer: "/zero_to_ten",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0`",
+                    rule: "tinc.Int32Constraints.gt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "bigger_than_zero",
+                pointer: "/bigger_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than `0`",
+                    rule: "tinc.Int32Constraints.lt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input < this",
                 },
                 fatal: true,
                 path: "less_than_zero",
+                pointer: "/less_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[5, 10, -5, -10]`",
+                    rule: "tinc.Int32Constraints.in",
+                    rule_value: Some(
+                        "[5, 10, -5, -10]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "bucket",
+                pointer: "/bucket",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[3, 2, 1]`",
+                    rule: "tinc.Int32Constraints.not_in",
+                    rule_value: Some(
+                        "[3, 2, 1]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "coolest_int32",
+                pointer: "/coolest_int32",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `3`",
+                    rule: "tinc.Int32Constraints.const",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "pi",
+                pointer: "/pi",
             },
         ],
     }
@@ -473,46 +665,82 @@ fn test_int64_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than or equal to `0`",
+                    rule: "tinc.Int64Constraints.gte",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input >= this",
                 },
                 fatal: true,
                 path: "zero_to_ten",
+                pointer: "/zero_to_ten",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0`",
+                    rule: "tinc.Int64Constraints.gt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "bigger_than_zero",
+                pointer: "/bigger_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than `0`",
+                    rule: "tinc.Int64Constraints.lt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input < this",
                 },
                 fatal: true,
                 path: "less_than_zero",
+                pointer: "/less_than_zero",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[5, 10, -5, -10]`",
+                    rule: "tinc.Int64Constraints.in",
+                    rule_value: Some(
+                        "[5, 10, -5, -10]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "bucket",
+                pointer: "/bucket",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[3, 2, 1]`",
+                    rule: "tinc.Int64Constraints.not_in",
+                    rule_value: Some(
+                        "[3, 2, 1]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "coolest_int64",
+                pointer: "/coolest_int64",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `3`",
+                    rule: "tinc.Int64Constraints.const",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "pi",
+                pointer: "/pi",
             },
         ],
     }
@@ -560,46 +788,82 @@ fn test_uint32_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than or equal to `1`",
+                    rule: "tinc.UInt32Constraints.gte",
+                    rule_value: Some(
+                        "1",
+                    ),
+                    expression: "input >= this",
                 },
                 fatal: true,
                 path: "one_to_ten",
+                pointer: "/one_to_ten",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `100`",
+                    rule: "tinc.UInt32Constraints.gt",
+                    rule_value: Some(
+                        "100",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "bigger_than_100",
+                pointer: "/bigger_than_100",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than `100`",
+                    rule: "tinc.UInt32Constraints.lt",
+                    rule_value: Some(
+                        "100",
+                    ),
+                    expression: "input < this",
                 },
                 fatal: true,
                 path: "less_than_100",
+                pointer: "/less_than_100",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[5, 10, 15, 20]`",
+                    rule: "tinc.UInt32Constraints.in",
+                    rule_value: Some(
+                        "[5, 10, 15, 20]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "bucket",
+                pointer: "/bucket",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[3, 2, 1]`",
+                    rule: "tinc.UInt32Constraints.not_in",
+                    rule_value: Some(
+                        "[3, 2, 1]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "coolest_uint32",
+                pointer: "/coolest_uint32",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `3`",
+                    rule: "tinc.UInt32Constraints.const",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "pi",
+                pointer: "/pi",
             },
         ],
     }
@@ -647,46 +911,82 @@ fn test_uint64_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than or equal to `1`",
+                    rule: "tinc.UInt64Constraints.gte",
+                    rule_value: Some(
+                        "1",
+                    ),
+                    expression: "input >= this",
                 },
                 fatal: true,
                 path: "one_to_ten",
+                pointer: "/one_to_ten",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `100`",
+                    rule: "tinc.UInt64Constraints.gt",
+                    rule_value: Some(
+                        "100",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "bigger_than_100",
+                pointer: "/bigger_than_100",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be less than `100`",
+                    rule: "tinc.UInt64Constraints.lt",
+                    rule_value: Some(
+                        "100",
+                    ),
+                    expression: "input < this",
                 },
                 fatal: true,
                 path: "less_than_100",
+                pointer: "/less_than_100",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[5, 10, 15, 20]`",
+                    rule: "tinc.UInt64Constraints.in",
+                    rule_value: Some(
+                        "[5, 10, 15, 20]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "bucket",
+                pointer: "/bucket",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[3, 2, 1]`",
+                    rule: "tinc.UInt64Constraints.not_in",
+                    rule_value: Some(
+                        "[3, 2, 1]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "coolest_uint64",
+                pointer: "/coolest_uint64",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `3`",
+                    rule: "tinc.UInt64Constraints.const",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "pi",
+                pointer: "/pi",
             },
         ],
     }
@@ -728,25 +1028,43 @@ fn test_bytes_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must equal `\0\0\0`",
+                    rule: "tinc.BytesConstraints.const",
+                    rule_value: Some(
+                        "\0\0\0",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "constant",
+                pointer: "/constant",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be exactly `5` bytes long",
+                    rule: "tinc.BytesConstraints.len",
+                    rule_value: Some(
+                        "5",
+                    ),
+                    expression: "input.size() == this",
                 },
                 fatal: true,
                 path: "exact_len",
+                pointer: "/exact_len",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be at least `5` bytes long",
+                    rule: "tinc.BytesConstraints.min_len",
+                    rule_value: Some(
+                        "5",
+                    ),
+                    expression: "input.size() >= this",
                 },
                 fatal: true,
                 path: "min_max_len",
+                pointer: "/min_max_len",
             },
         ],
     }
@@ -790,32 +1108,56 @@ fn test_enum_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be equal to `SPECIAL_B`",
+                    rule: "tinc.EnumConstraints.const",
+                    rule_value: Some(
+                        "2",
+                    ),
+                    expression: "input == this",
                 },
                 fatal: true,
                 path: "constant",
+                pointer: "/constant",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be defined in the enum",
+                    rule: "tinc.EnumConstraints.defined",
+                    rule_value: Some(
+                        "true",
+                    ),
+                    expression: "!this || input.enum()",
                 },
                 fatal: true,
                 path: "defined",
+                pointer: "/defined",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be one of `[SPECIAL_A, SPECIAL_B]`",
+                    rule: "tinc.EnumConstraints.in",
+                    rule_value: Some(
+                        "[1, 2]",
+                    ),
+                    expression: "this.contains(input)",
                 },
                 fatal: true,
                 path: "one_of",
+                pointer: "/one_of",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must not be one of `[SPECIAL_UNSPECIFIED]`",
+                    rule: "tinc.EnumConstraints.not_in",
+                    rule_value: Some(
+                        "[0]",
+                    ),
+                    expression: "!this.contains(input)",
                 },
                 fatal: true,
                 path: "none_of",
+                pointer: "/none_of",
             },
         ],
     }
@@ -853,18 +1195,30 @@ fn test_repeated_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must have exactly `5` elements",
+                    rule: "tinc.RepeatedConstraints.len",
+                    rule_value: Some(
+                        "5",
+                    ),
+                    expression: "input.size() == this",
                 },
                 fatal: true,
                 path: "numbers",
+                pointer: "/numbers",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0`",
+                    rule: "tinc.Int32Constraints.gt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "numbers[2]",
+                pointer: "/numbers/2",
             },
         ],
     }
@@ -918,39 +1272,69 @@ fn test_map_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must match the pattern `^troy_`",
+                    rule: "tinc.StringConstraints.match",
+                    rule_value: Some(
+                        "^troy_",
+                    ),
+                    expression: "input.matches(this)",
                 },
                 fatal: true,
                 path: "numbers.one",
+                pointer: "/numbers/one",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0`",
+                    rule: "tinc.Int32Constraints.gt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "numbers.one",
+                pointer: "/numbers/one",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must match the pattern `^troy_`",
+                    rule: "tinc.StringConstraints.match",
+                    rule_value: Some(
+                        "^troy_",
+                    ),
+                    expression: "input.matches(this)",
                 },
                 fatal: true,
                 path: "numbers.three",
+                pointer: "/numbers/three",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0`",
+                    rule: "tinc.Int32Constraints.gt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "numbers.three",
+                pointer: "/numbers/three",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than `0`",
+                    rule: "tinc.Int32Constraints.gt",
+                    rule_value: Some(
+                        "0",
+                    ),
+                    expression: "input > this",
                 },
                 fatal: true,
                 path: "numbers.troy_five",
+                pointer: "/numbers/troy_five",
             },
         ],
     }
@@ -989,6 +1373,7 @@ fn test_message_expressions_not_provided() {
                 kind: MissingField,
                 fatal: true,
                 path: "message",
+                pointer: "/message",
             },
         ],
     }
@@ -1009,11 +1394,17 @@ fn test_message_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be at least `3` characters long",
+                    rule: "tinc.StringConstraints.min_len",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input.size() >= this",
                 },
                 fatal: true,
                 path: "message.name",
+                pointer: "/message/name",
             },
         ],
     }
@@ -1051,11 +1442,17 @@ fn test_repeated_message_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be at least `3` characters long",
+                    rule: "tinc.StringConstraints.min_len",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input.size() >= this",
                 },
                 fatal: true,
                 path: "messages[0].name",
+                pointer: "/messages/0/name",
             },
         ],
     }
@@ -1108,11 +1505,17 @@ fn test_map_message_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be at least `3` characters long",
+                    rule: "tinc.StringConstraints.min_len",
+                    rule_value: Some(
+                        "3",
+                    ),
+                    expression: "input.size() >= this",
                 },
                 fatal: true,
                 path: "messages.first.name",
+                pointer: "/messages/first/name",
             },
         ],
     }
@@ -1150,11 +1553,105 @@ fn test_custom_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "all items must start with with 'troy_'",
+                    rule: "expressions.CustomExpressions.items.cel[0]",
+                    rule_value: None,
+                    expression: "input.all(item, item.startsWith('troy_'))",
                 },
                 fatal: true,
                 path: "items",
+                pointer: "/items",
+            },
+        ],
+    }
+    "#);
+}
+
+#[test]
+fn test_comprehension_expressions_valid() {
+    let mut state = TrackerSharedState::default();
+    let valid = pb::ComprehensionExpressions {
+        items: vec![
+            pb::comprehension_expressions::Item {
+                name: "apple".into(),
+                quantity: 5,
+            },
+            pb::comprehension_expressions::Item {
+                name: "banana".into(),
+                quantity: 0,
+            },
+        ],
+    };
+
+    state.in_scope(|| valid.validate(None)).unwrap();
+
+    insta::assert_debug_snapshot!(state, @"
+    TrackerSharedState {
+        fail_fast: false,
+        errors: [],
+    }
+    ");
+}
+
+#[test]
+fn test_comprehension_expressions_invalid() {
+    let mut state = TrackerSharedState::default();
+    let invalid = pb::ComprehensionExpressions {
+        items: vec![
+            pb::comprehension_expressions::Item {
+                name: "apple".into(),
+                quantity: 25,
+            },
+            pb::comprehension_expressions::Item {
+                name: "apple".into(),
+                quantity: -1,
+            },
+            pb::comprehension_expressions::Item {
+                name: "banana".into(),
+                quantity: 1,
+            },
+        ],
+    };
+
+    state.in_scope(|| invalid.validate(None)).unwrap();
+
+    insta::assert_debug_snapshot!(state, @r#"
+    TrackerSharedState {
+        fail_fast: false,
+        errors: [
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "item names must be unique",
+                    rule: "expressions.ComprehensionExpressions.items.cel[0]",
+                    rule_value: None,
+                    expression: "input.map(x, x.name).all(name, input.filter(y, y.name == name).size() == 1)",
+                },
+                fatal: true,
+                path: "items",
+                pointer: "/items",
+            },
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "items in stock must be known",
+                    rule: "expressions.ComprehensionExpressions.items.cel[1]",
+                    rule_value: None,
+                    expression: "input.map(x, x.quantity > 0, x.name).all(name, name in ['apple', 'pear'])",
+                },
+                fatal: true,
+                path: "items",
+                pointer: "/items",
+            },
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "quantities must be between 0 and the number of items times 10",
+                    rule: "expressions.ComprehensionExpressions.items.cel[2]",
+                    rule_value: None,
+                    expression: "input.map(x, {'name': x.name, 'quantity': x.quantity}).all(item, [0, input.size() * 10].exists(bound, item.quantity <= bound) && item['quantity'] >= 0)",
+                },
+                fatal: true,
+                path: "items",
+                pointer: "/items",
             },
         ],
     }
@@ -1205,11 +1702,17 @@ fn test_oneof_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than or equal to `18`",
+                    rule: "tinc.Int32Constraints.gte",
+                    rule_value: Some(
+                        "18",
+                    ),
+                    expression: "input >= this",
                 },
                 fatal: true,
                 path: "tagged_nested.age",
+                pointer: "/tagged_nested/age",
             },
         ],
     }
@@ -1226,20 +1729,232 @@ fn test_oneof_expressions_invalid() {
         fail_fast: false,
         errors: [
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be greater than or equal to `18`",
+                    rule: "tinc.Int32Constraints.gte",
+                    rule_value: Some(
+                        "18",
+                    ),
+                    expression: "input >= this",
                 },
                 fatal: true,
                 path: "tagged_nested.age",
+                pointer: "/tagged_nested/age",
             },
             TrackedError {
-                kind: InvalidField {
+                kind: ConstraintViolation {
                     message: "value must be at least `2` characters long",
+                    rule: "tinc.StringConstraints.min_len",
+                    rule_value: Some(
+                        "2",
+                    ),
+                    expression: "input.size() >= this",
                 },
                 fatal: true,
                 path: "tagged_nested.name",
+                pointer: "/tagged_nested/name",
             },
         ],
     }
     "#);
 }
+
+#[test]
+fn test_nested_optional_expressions_absent() {
+    use pb::nested_optional_expressions::{Inner, Leaf, Outer};
+
+    // Every level of the access chain is absent in turn, each short-circuits to null.
+    for outer in [
+        Outer { inner: None },
+        Outer {
+            inner: Some(Inner { leaf: None }),
+        },
+        Outer {
+            inner: Some(Inner {
+                leaf: Some(Leaf {
+                    name: None,
+                    value: 1,
+                    tags: vec![],
+                }),
+            }),
+        },
+    ] {
+        let mut state = TrackerSharedState::default();
+        let valid = pb::NestedOptionalExpressions { outer: Some(outer) };
+
+        state.in_scope(|| valid.validate(None)).unwrap();
+
+        insta::allow_duplicates! {
+            insta::assert_debug_snapshot!(state, @r"
+            TrackerSharedState {
+                fail_fast: false,
+                errors: [],
+            }
+            ");
+        }
+    }
+}
+
+#[test]
+fn test_nested_optional_expressions_invalid() {
+    use pb::nested_optional_expressions::{Inner, Leaf, Outer};
+
+    let mut state = TrackerSharedState::default();
+    let invalid = pb::NestedOptionalExpressions {
+        outer: Some(Outer {
+            inner: Some(Inner {
+                leaf: Some(Leaf {
+                    name: Some("troy".into()),
+                    value: -1,
+                    tags: vec!["".into()],
+                }),
+            }),
+        }),
+    };
+
+    state.in_scope(|| invalid.validate(None)).unwrap();
+
+    insta::assert_debug_snapshot!(state, @r#"
+    TrackerSharedState {
+        fail_fast: false,
+        errors: [
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "leaf value must not be negative",
+                    rule: "expressions.NestedOptionalExpressions.outer.cel[0]",
+                    rule_value: None,
+                    expression: "input.inner.leaf.value == null || input.inner.leaf.value >= 0",
+                },
+                fatal: true,
+                path: "outer",
+                pointer: "/outer",
+            },
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "leaf name must not be 'troy'",
+                    rule: "expressions.NestedOptionalExpressions.outer.cel[1]",
+                    rule_value: None,
+                    expression: "input.inner.leaf.name != 'troy'",
+                },
+                fatal: true,
+                path: "outer",
+                pointer: "/outer",
+            },
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "leaf tags must not be empty strings",
+                    rule: "expressions.NestedOptionalExpressions.outer.cel[2]",
+                    rule_value: None,
+                    expression: "input.inner.leaf.tags == null || input.inner.leaf.tags.all(tag, tag != '')",
+                },
+                fatal: true,
+                path: "outer",
+                pointer: "/outer",
+            },
+        ],
+    }
+    "#);
+}
+
+#[test]
+fn test_time_expressions_valid() {
+    let mut state = TrackerSharedState::default();
+    let valid = pb::TimeExpressions {
+        starts_at: "2020-01-01T00:00:00Z".into(),
+        timeout: "1h15m".into(),
+    };
+
+    state.in_scope(|| valid.validate(None)).unwrap();
+
+    insta::assert_debug_snapshot!(state, @"
+    TrackerSharedState {
+        fail_fast: false,
+        errors: [],
+    }
+    ");
+}
+
+#[test]
+fn test_time_expressions_invalid() {
+    let mut state = TrackerSharedState::default();
+    let invalid = pb::TimeExpressions {
+        starts_at: "2100-01-01T02:00:00+01:00".into(),
+        timeout: "1.5h1s".into(),
+    };
+
+    state.in_scope(|| invalid.validate(None)).unwrap();
+
+    insta::assert_debug_snapshot!(state, @r#"
+    TrackerSharedState {
+        fail_fast: false,
+        errors: [
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "must be an RFC 3339 timestamp in UTC",
+                    rule: "expressions.TimeExpressions.starts_at.cel[0]",
+                    rule_value: None,
+                    expression: "timestamp(input).string() == input",
+                },
+                fatal: true,
+                path: "starts_at",
+                pointer: "/starts_at",
+            },
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "must be before 2100",
+                    rule: "expressions.TimeExpressions.starts_at.cel[1]",
+                    rule_value: None,
+                    expression: "timestamp(input) < timestamp('2100-01-01T00:00:00Z')",
+                },
+                fatal: true,
+                path: "starts_at",
+                pointer: "/starts_at",
+            },
+            TrackedError {
+                kind: ConstraintViolation {
+                    message: "must be at most 1h30m",
+                    rule: "expressions.TimeExpressions.timeout.cel[0]",
+                    rule_value: None,
+                    expression: "duration(input) <= duration('1h30m')",
+                },
+                fatal: true,
+                path: "timeout",
+                pointer: "/timeout",
+            },
+        ],
+    }
+    "#);
+
+    // malformed timestamps and durations fail to evaluate
+    let mut state = TrackerSharedState::default();
+    let invalid = pb::TimeExpressions {
+        starts_at: "yesterday".into(),
+        timeout: "1h".into(),
+    };
+
+    insta::assert_debug_snapshot!(state.in_scope(|| invalid.validate(None)), @r#"
+    Err(
+        Expression {
+            field: "expressions.TimeExpressions.starts_at",
+            error: "invalid timestamp: yesterday",
+            expression: "timestamp(input).string() == input",
+        },
+    )
+    "#);
+
+    let mut state = TrackerSharedState::default();
+    let invalid = pb::TimeExpressions {
+        starts_at: "2020-01-01T00:00:00Z".into(),
+        timeout: "forever".into(),
+    };
+
+    insta::assert_debug_snapshot!(state.in_scope(|| invalid.validate(None)), @r#"
+    Err(
+        Expression {
+            field: "expressions.TimeExpressions.timeout",
+            error: "invalid duration: forever",
+            expression: "duration(input) <= duration('1h30m')",
+        },
+    )
+    "#);
+}
@@ -39,6 +39,7 @@ fn test_flattened() {
                 },
                 fatal: true,
                 path: "house_number",
+                pointer: "/house_number",
             },
         ],
     }
@@ -3,10 +3,13 @@
 #![cfg_attr(coverage_nightly, coverage(off))]
 
 mod bytes_service;
+mod custom_verb_service;
 mod expressions;
 mod flattened;
+mod module_files;
 mod nested;
 mod oneof;
+mod paginated_service;
 mod recursive;
 mod renamed;
 mod simple;
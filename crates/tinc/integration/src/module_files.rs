@@ -0,0 +1,33 @@
+use tinc::TincService;
+
+mod pb {
+    #![allow(clippy::all)]
+    include!(concat!(env!("OUT_DIR"), "/module_files/___root_module.rs"));
+}
+
+struct Svc {}
+
+#[tonic::async_trait]
+impl pb::simple_service::simple_service_server::SimpleService for Svc {
+    async fn ping(
+        &self,
+        request: tonic::Request<pb::simple_service::PingRequest>,
+    ) -> tonic::Result<tonic::Response<pb::simple_service::PingResponse>> {
+        Ok(pb::simple_service::PingResponse {
+            result: format!("{} - pong", request.get_ref().arg),
+        }
+        .into())
+    }
+}
+
+#[test]
+fn test_module_files() {
+    let out_dir = std::path::Path::new(env!("OUT_DIR")).join("module_files").join("tinc_modules");
+
+    let root = std::fs::read_to_string(out_dir.join("mod.rs")).unwrap();
+    assert!(root.contains("pub mod simple_service;"));
+    assert!(out_dir.join("simple_service").join("mod.rs").exists());
+
+    let svc = pb::simple_service::simple_service_tinc::SimpleServiceTinc::new(Svc {});
+    assert!(svc.openapi_schema().paths.paths.contains_key("/ping"));
+}
@@ -0,0 +1,165 @@
+use http_body_util::BodyExt;
+use tinc::TincService;
+use tower::Service;
+
+mod pb {
+    #![allow(clippy::all)]
+    tinc::include_proto!("paginated_service");
+}
+
+struct Svc {}
+
+#[tonic::async_trait]
+impl pb::paginated_service_server::PaginatedService for Svc {
+    async fn list_items(
+        &self,
+        request: tonic::Request<pb::ListItemsRequest>,
+    ) -> tonic::Result<tonic::Response<pb::ListItemsResponse>> {
+        let request = request.into_inner();
+        let start = request.page_token.parse::<i32>().unwrap_or_default();
+        let end = start + request.page_size;
+
+        Ok(pb::ListItemsResponse {
+            items: (start..end).map(|i| format!("{}{i}", request.query)).collect(),
+            next_page_token: end.to_string(),
+        }
+        .into())
+    }
+}
+
+async fn rest_call(req: http::Request<axum::body::Body>) -> (http::StatusCode, serde_json::Value) {
+    let mut client = pb::paginated_service_tinc::PaginatedServiceTinc::new(Svc {}).into_router();
+
+    let resp = client.call(req).await.unwrap();
+    let status = resp.status();
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_paginated_service_grpc() {
+    let mut client = pb::paginated_service_client::PaginatedServiceClient::new(
+        pb::paginated_service_server::PaginatedServiceServer::new(Svc {}),
+    );
+
+    // the default page size is applied
+    let response = client.list_items(pb::ListItemsRequest::default()).await.unwrap();
+    assert_eq!(response.get_ref().items.len(), 10);
+    assert_eq!(response.get_ref().next_page_token, "10");
+
+    // the page size is limited to the max page size
+    let response = client
+        .list_items(pb::ListItemsRequest {
+            page_size: 100,
+            page_token: "10".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.get_ref().items.len(), 50);
+    assert_eq!(response.get_ref().next_page_token, "60");
+
+    let status = client
+        .list_items(pb::ListItemsRequest {
+            page_size: -1,
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert_eq!(status.message(), "value must not be negative");
+}
+
+#[tokio::test]
+async fn test_paginated_service_rest_get() {
+    let (status, response) = rest_call(
+        http::Request::builder()
+            .uri("/items?query=item&page_size=2&page_token=5")
+            .method("GET")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, http::StatusCode::OK);
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "items": [
+        "item5",
+        "item6"
+      ],
+      "next_page_token": "7"
+    }
+    "#);
+
+    let (status, response) = rest_call(
+        http::Request::builder()
+            .uri("/items")
+            .method("GET")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, http::StatusCode::OK);
+    assert_eq!(response["items"].as_array().unwrap().len(), 10);
+}
+
+#[tokio::test]
+async fn test_paginated_service_rest_post() {
+    let (status, response) = rest_call(
+        http::Request::builder()
+            .uri("/items/search?page_size=100&page_token=1")
+            .method("POST")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(r#"{ "query": "item" }"#))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, http::StatusCode::OK);
+    assert_eq!(response["items"].as_array().unwrap().len(), 50);
+    assert_eq!(response["items"][0], "item1");
+    assert_eq!(response["next_page_token"], "51");
+}
+
+#[tokio::test]
+async fn test_paginated_service_rest_negative_page_size() {
+    let (status, response) = rest_call(
+        http::Request::builder()
+            .uri("/items/search?page_size=-1")
+            .method("POST")
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from("{}"))
+            .unwrap(),
+    )
+    .await;
+
+    assert_eq!(status, http::StatusCode::BAD_REQUEST);
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "400",
+      "details": {
+        "request": {
+          "violations": [
+            {
+              "description": "value must not be negative",
+              "field": "page_size",
+              "pointer": "/page_size",
+              "rule": "invalid_field"
+            }
+          ]
+        }
+      },
+      "message": "bad request"
+    }
+    "#);
+}
+
+#[test]
+fn test_paginated_service_rest_schema() {
+    let svc = pb::paginated_service_tinc::PaginatedServiceTinc::new(Svc {});
+
+    insta::assert_json_snapshot!(svc.openapi_schema());
+}
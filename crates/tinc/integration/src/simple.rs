@@ -259,6 +259,7 @@ fn test_simple_missing_fields() {
                 kind: MissingField,
                 fatal: true,
                 path: "name",
+                pointer: "/name",
             },
         ],
     }
@@ -384,16 +385,19 @@ fn test_simple_duplicate_fields() {
                 kind: DuplicateField,
                 fatal: true,
                 path: "values",
+                pointer: "/values",
             },
             TrackedError {
                 kind: DuplicateField,
                 fatal: true,
                 path: "key_values[\"key1\"]",
+                pointer: "/key_values/key1",
             },
             TrackedError {
                 kind: DuplicateField,
                 fatal: true,
                 path: "key_values[\"key2\"]",
+                pointer: "/key_values/key2",
             },
         ],
     }
@@ -457,6 +461,7 @@ fn test_simple_invalid_type() {
                 },
                 fatal: true,
                 path: "name",
+                pointer: "/name",
             },
             TrackedError {
                 kind: InvalidField {
@@ -464,6 +469,7 @@ fn test_simple_invalid_type() {
                 },
                 fatal: true,
                 path: "values[0]",
+                pointer: "/values/0",
             },
             TrackedError {
                 kind: InvalidField {
@@ -471,6 +477,7 @@ fn test_simple_invalid_type() {
                 },
                 fatal: true,
                 path: "key_values",
+                pointer: "/key_values",
             },
         ],
     }
@@ -542,3 +549,16 @@ fn test_simple_renamed_field() {
     )
     "#);
 }
+
+#[test]
+fn test_simple_message_attribute() {
+    let message = pb::SimpleMessage {
+        name: "test".into(),
+        values: vec!["value1".into()],
+        key_values: [("key1".into(), "value1".into())].into_iter().collect(),
+    };
+
+    // `Eq` and `Hash` are derived through `Config::message_attribute` in the build script.
+    let set: std::collections::HashSet<_> = [message.clone(), message].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
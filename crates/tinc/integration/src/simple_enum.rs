@@ -311,16 +311,19 @@ fn test_simple_enum_invalid() {
                 },
                 fatal: true,
                 path: "value",
+                pointer: "/value",
             },
             TrackedError {
                 kind: MissingField,
                 fatal: true,
                 path: "values",
+                pointer: "/values",
             },
             TrackedError {
                 kind: MissingField,
                 fatal: true,
                 path: "map",
+                pointer: "/map",
             },
         ],
     }
@@ -386,16 +389,19 @@ fn test_simple_enum_renamed_invalid() {
                 },
                 fatal: true,
                 path: "value",
+                pointer: "/value",
             },
             TrackedError {
                 kind: MissingField,
                 fatal: true,
                 path: "values",
+                pointer: "/values",
             },
             TrackedError {
                 kind: MissingField,
                 fatal: true,
                 path: "map",
+                pointer: "/map",
             },
         ],
     }
@@ -460,16 +466,19 @@ fn test_simple_enum_repr_invalid() {
                 },
                 fatal: true,
                 path: "value",
+                pointer: "/value",
             },
             TrackedError {
                 kind: MissingField,
                 fatal: true,
                 path: "values",
+                pointer: "/values",
             },
             TrackedError {
                 kind: MissingField,
                 fatal: true,
                 path: "map",
+                pointer: "/map",
             },
         ],
     }
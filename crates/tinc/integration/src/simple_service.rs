@@ -1,4 +1,6 @@
+use axum::response::IntoResponse;
 use http_body_util::BodyExt;
+use prost::Message;
 use tinc::TincService;
 use tower::Service;
 
@@ -9,9 +11,31 @@ mod pb {
 
 struct Svc {}
 
+#[derive(Debug, Clone)]
+struct AppState {
+    name: &'static str,
+}
+
 #[tonic::async_trait]
 impl pb::simple_service_server::SimpleService for Svc {
     async fn ping(&self, request: tonic::Request<pb::PingRequest>) -> tonic::Result<tonic::Response<pb::PingResponse>> {
+        match request.get_ref().arg.as_str() {
+            "slow" => tokio::time::sleep(std::time::Duration::from_secs(60)).await,
+            "timeout" => {
+                return Ok(pb::PingResponse {
+                    result: format!("{:?}", request.metadata().get("grpc-timeout")),
+                }
+                .into());
+            }
+            "state" => {
+                return Ok(pb::PingResponse {
+                    result: format!("{:?}", request.extensions().get::<AppState>().map(|state| state.name)),
+                }
+                .into());
+            }
+            _ => {}
+        }
+
         Ok(pb::PingResponse {
             result: format!("{} - pong", request.get_ref().arg),
         }
@@ -78,9 +102,467 @@ async fn test_simple_service_rest_get() {
     assert_eq!(response["result"], "http_get - pong");
 }
 
+#[tokio::test]
+async fn test_simple_service_rest_protobuf() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {}).into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/x-protobuf")
+        .body(http_body_util::Full::new(bytes::Bytes::from(
+            pb::PingRequest { arg: "proto".into() }.encode_to_vec(),
+        )))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(http::header::CONTENT_TYPE).map(|h| h.as_bytes()),
+        Some(b"application/x-protobuf" as &[u8])
+    );
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response = pb::PingResponse::decode(body).unwrap();
+
+    assert_eq!(response.result, "proto - pong");
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_accept() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {}).into_router();
+
+    for (content_type, accept, expected) in [
+        (None, None, "application/json"),
+        (None, Some("application/x-protobuf"), "application/x-protobuf"),
+        (None, Some("application/json"), "application/json"),
+        (None, Some("*/*"), "application/json"),
+        (None, Some("text/html"), "application/json"),
+        (None, Some("application/x-protobuf, application/json"), "application/json"),
+        (
+            None,
+            Some("application/json;q=0.5, application/x-protobuf"),
+            "application/x-protobuf",
+        ),
+        (
+            None,
+            Some("application/x-protobuf;q=0.9, */*;q=0.1"),
+            "application/x-protobuf",
+        ),
+        (None, Some("application/x-protobuf;q=0.1, application/*"), "application/json"),
+        (Some("application/x-protobuf"), None, "application/x-protobuf"),
+        (Some("application/x-protobuf"), Some("application/json"), "application/json"),
+        (
+            Some("application/json"),
+            Some("application/x-protobuf"),
+            "application/x-protobuf",
+        ),
+    ] {
+        let (uri, method, body) = match content_type {
+            Some("application/x-protobuf") => ("/ping", "POST", pb::PingRequest { arg: "accept".into() }.encode_to_vec()),
+            Some(_) => ("/ping", "POST", br#"{ "arg": "accept" }"#.to_vec()),
+            None => ("/ping/accept", "GET", Vec::new()),
+        };
+
+        let mut req = http::Request::builder().uri(uri).method(method);
+        if let Some(content_type) = content_type {
+            req = req.header(http::header::CONTENT_TYPE, content_type);
+        }
+        if let Some(accept) = accept {
+            req = req.header(http::header::ACCEPT, accept);
+        }
+
+        let resp = client
+            .call(req.body(http_body_util::Full::new(bytes::Bytes::from(body))).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), http::StatusCode::OK, "{content_type:?} {accept:?}");
+        assert_eq!(
+            resp.headers().get(http::header::CONTENT_TYPE).map(|h| h.as_bytes()),
+            Some(expected.as_bytes()),
+            "{content_type:?} {accept:?}"
+        );
+
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let result = if expected == "application/json" {
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["result"]
+                .as_str()
+                .unwrap()
+                .to_owned()
+        } else {
+            pb::PingResponse::decode(body).unwrap().result
+        };
+
+        assert_eq!(result, "accept - pong", "{content_type:?} {accept:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_protobuf_error() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {}).into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/x-protobuf")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(b"\xff")))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "400",
+      "message": "failed to decode body: failed to decode Protobuf message: invalid varint"
+    }
+    "#);
+
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "text/plain")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(b"ping")))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "400",
+      "message": "content-type header is not application/json or application/x-protobuf"
+    }
+    "#);
+}
+
 #[test]
 fn test_simple_service_rest_schema() {
     let svc = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {});
 
     insta::assert_json_snapshot!(svc.openapi_schema());
 }
+
+#[tokio::test]
+async fn test_simple_service_openapi_router() {
+    let svc = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {});
+    let mut client = tinc::OpenApiRouter::new().service(&svc).into_router();
+
+    let req = http::Request::builder()
+        .uri("/openapi.json")
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::OK);
+    assert_eq!(resp.headers()[http::header::CONTENT_TYPE], "application/json");
+    let etag = resp.headers()[http::header::ETAG].clone();
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let document: tinc::openapi::OpenApi = serde_json::from_slice(&body).unwrap();
+    assert!(document == svc.openapi_schema());
+
+    let req = http::Request::builder()
+        .uri("/openapi.json")
+        .header(http::header::IF_NONE_MATCH, etag)
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::NOT_MODIFIED);
+    assert!(resp.into_body().collect().await.unwrap().to_bytes().is_empty());
+}
+
+#[test]
+fn test_simple_service_routes() {
+    let svc = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {});
+
+    assert_eq!(svc.routes(), pb::simple_service_tinc::ROUTES);
+    insta::assert_debug_snapshot!(svc.routes(), @r#"
+    [
+        TincRoute {
+            service: "simple_service.SimpleService",
+            method: "Ping",
+            http_method: POST,
+            path: "/ping",
+            request_type: "simple_service.PingRequest",
+            response_type: "simple_service.PingResponse",
+        },
+        TincRoute {
+            service: "simple_service.SimpleService",
+            method: "Ping",
+            http_method: GET,
+            path: "/ping/{arg}",
+            request_type: "simple_service.PingRequest",
+            response_type: "simple_service.PingResponse",
+        },
+    ]
+    "#);
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_validation_error() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {}).into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(b"{}")))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "400",
+      "details": {
+        "request": {
+          "violations": [
+            {
+              "description": "missing field",
+              "field": "arg",
+              "pointer": "/arg",
+              "rule": "missing_field"
+            }
+          ]
+        }
+      },
+      "message": "bad request"
+    }
+    "#);
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_custom_validation_error_formatter() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {})
+        .with_validation_error_formatter(|violations: &[tinc::validation::Violation<'_>]| {
+            (
+                http::StatusCode::UNPROCESSABLE_ENTITY,
+                axum::Json(serde_json::json!({ "errors": violations })),
+            )
+                .into_response()
+        })
+        .into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(b"{}")))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "errors": [
+        {
+          "description": "missing field",
+          "field": "arg",
+          "pointer": "/arg",
+          "rule": "missing_field"
+        }
+      ]
+    }
+    "#);
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_localized_validation_error() {
+    let catalog = tinc::validation::MessageCatalog::new()
+        .with_message("de", "missing_field", "`{field}` fehlt")
+        .with_message("fr", "missing_field", "`{field}` est manquant");
+
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {})
+        .with_validation_error_formatter(tinc::validation::LocalizedValidationErrorFormatter::new(catalog))
+        .into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::ACCEPT_LANGUAGE, "en;q=0.5, de-CH, fr;q=0.8")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(b"{}")))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    assert_eq!(resp.headers()[http::header::CONTENT_LANGUAGE], "de");
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "400",
+      "details": {
+        "request": {
+          "violations": [
+            {
+              "description": "`arg` fehlt",
+              "field": "arg",
+              "pointer": "/arg",
+              "rule": "missing_field"
+            }
+          ]
+        }
+      },
+      "message": "bad request"
+    }
+    "#);
+
+    // Without a matching locale the messages are left as they are.
+    let req = http::Request::builder()
+        .uri("/ping")
+        .method("POST")
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::ACCEPT_LANGUAGE, "es")
+        .body(http_body_util::Full::new(bytes::Bytes::from_static(b"{}")))
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+    assert!(!resp.headers().contains_key(http::header::CONTENT_LANGUAGE));
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        response["details"]["request"]["violations"][0]["description"],
+        "missing field"
+    );
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_timeout() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {})
+        .with_timeout_header(http::HeaderName::from_static("x-request-timeout"))
+        .into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping/slow")
+        .method("GET")
+        .header("grpc-timeout", "10m")
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::GATEWAY_TIMEOUT);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "504",
+      "message": "request timed out"
+    }
+    "#);
+
+    let req = http::Request::builder()
+        .uri("/ping/slow")
+        .method("GET")
+        .header("x-request-timeout", "10")
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::GATEWAY_TIMEOUT);
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_timeout_propagation() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {})
+        .with_timeout_header(http::HeaderName::from_static("x-request-timeout"))
+        .into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping/timeout")
+        .method("GET")
+        .header("x-request-timeout", "1S")
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::OK);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(response["result"], r#"Some("1000000u")"#);
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_invalid_timeout() {
+    let mut client = pb::simple_service_tinc::SimpleServiceTinc::new(Svc {}).into_router();
+
+    let req = http::Request::builder()
+        .uri("/ping/http_get")
+        .method("GET")
+        .header("grpc-timeout", "soon")
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "code": "400",
+      "message": "grpc-timeout header is not a valid timeout"
+    }
+    "#);
+}
+
+#[tokio::test]
+async fn test_simple_service_rest_with_state() {
+    let mut client = axum::Router::new()
+        .merge(pb::simple_service_tinc::SimpleServiceTinc::new(Svc {}).into_router_with_state())
+        .with_state(AppState { name: "app" });
+
+    let req = http::Request::builder()
+        .uri("/ping/state")
+        .method("GET")
+        .body(http_body_util::Empty::<bytes::Bytes>::new())
+        .unwrap();
+
+    let resp = client.call(req).await.unwrap();
+
+    assert_eq!(resp.status(), http::StatusCode::OK);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(response["result"], r#"Some("app")"#);
+}
@@ -40,6 +40,7 @@ fn test_visibility() {
                 kind: UnknownField,
                 fatal: false,
                 path: "output_only",
+                pointer: "/output_only",
             },
             TrackedError {
                 kind: InvalidField {
@@ -47,6 +48,7 @@ fn test_visibility() {
                 },
                 fatal: true,
                 path: "input_outputs[\"UNSPECIFIED\"]",
+                pointer: "/input_outputs/UNSPECIFIED",
             },
             TrackedError {
                 kind: InvalidField {
@@ -54,11 +56,13 @@ fn test_visibility() {
                 },
                 fatal: true,
                 path: "input_outputs[\"OUTPUT_ONLY\"]",
+                pointer: "/input_outputs/OUTPUT_ONLY",
             },
             TrackedError {
                 kind: UnknownField,
                 fatal: false,
                 path: "nothing",
+                pointer: "/nothing",
             },
         ],
     }
@@ -355,6 +355,7 @@ fn test_well_known_map() {
                 },
                 fatal: true,
                 path: "empty[\"non_empty_string\"]",
+                pointer: "/empty/non_empty_string",
             },
             TrackedError {
                 kind: InvalidField {
@@ -362,6 +363,7 @@ fn test_well_known_map() {
                 },
                 fatal: true,
                 path: "empty[\"non_empty_array\"]",
+                pointer: "/empty/non_empty_array",
             },
             TrackedError {
                 kind: InvalidField {
@@ -369,6 +371,7 @@ fn test_well_known_map() {
                 },
                 fatal: true,
                 path: "empty[\"non_empty_map\"]",
+                pointer: "/empty/non_empty_map",
             },
             TrackedError {
                 kind: InvalidField {
@@ -376,6 +379,7 @@ fn test_well_known_map() {
                 },
                 fatal: true,
                 path: "bytes_value[\"invalid\"]",
+                pointer: "/bytes_value/invalid",
             },
         ],
     }
@@ -0,0 +1,131 @@
+//! [Cross-Origin Resource Sharing](https://developer.mozilla.org/en-US/docs/Web/HTTP/Guides/CORS) for tinc services.
+//!
+//! A [`CorsPolicy`] can be attached to a generated service with its `with_cors` method,
+//! or at build time with `tinc_build::Config::cors`. The generated router then answers
+//! preflight requests for every route and adds the CORS headers to all responses,
+//! including error responses.
+//!
+//! Mounting a generic CORS layer in front of the router works too, but such a layer answers
+//! rejected preflight requests with its own responses instead of the tinc error format.
+//!
+//! ```rust
+//! # use tinc::cors::CorsPolicy;
+//! let policy = CorsPolicy::new()
+//!     .allow_origin(http::HeaderValue::from_static("https://example.com"))
+//!     .allow_headers([http::header::AUTHORIZATION, http::header::CONTENT_TYPE])
+//!     .max_age(std::time::Duration::from_secs(600));
+//! ```
+
+use std::time::Duration;
+
+const ALWAYS_ALLOWED_HEADERS: &[http::HeaderName] = &[
+    http::header::ACCEPT,
+    http::header::ACCEPT_LANGUAGE,
+    http::header::CONTENT_LANGUAGE,
+    http::header::CONTENT_TYPE,
+];
+
+/// A CORS policy of a tinc service.
+///
+/// A new policy does not allow any origin. Methods default to the methods of the requested route.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorsPolicy {
+    pub(crate) allow_any_origin: bool,
+    pub(crate) allow_origins: Vec<http::HeaderValue>,
+    pub(crate) allow_methods: Option<Vec<http::Method>>,
+    pub(crate) allow_any_header: bool,
+    pub(crate) allow_headers: Vec<http::HeaderName>,
+    pub(crate) expose_headers: Vec<http::HeaderName>,
+    pub(crate) allow_credentials: bool,
+    pub(crate) max_age: Option<Duration>,
+}
+
+impl CorsPolicy {
+    /// Create a new policy which does not allow any origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a policy allowing any origin to send any header.
+    pub fn permissive() -> Self {
+        Self::new().allow_any_origin().allow_any_header()
+    }
+
+    /// Allow requests from the given origin, e.g. `https://example.com`.
+    pub fn allow_origin(mut self, origin: http::HeaderValue) -> Self {
+        self.allow_origins.push(origin);
+        self
+    }
+
+    /// Allow requests from the given origins.
+    pub fn allow_origins(mut self, origins: impl IntoIterator<Item = http::HeaderValue>) -> Self {
+        self.allow_origins.extend(origins);
+        self
+    }
+
+    /// Allow requests from any origin.
+    ///
+    /// If credentials are allowed, the origin of the request is returned instead of `*`.
+    pub fn allow_any_origin(mut self) -> Self {
+        self.allow_any_origin = true;
+        self
+    }
+
+    /// Only allow the given methods, instead of the methods of the requested route.
+    pub fn allow_methods(mut self, methods: impl IntoIterator<Item = http::Method>) -> Self {
+        self.allow_methods.get_or_insert_default().extend(methods);
+        self
+    }
+
+    /// Allow requests to send the given headers.
+    ///
+    /// The `Accept`, `Accept-Language`, `Content-Language` and `Content-Type` headers are always allowed,
+    /// since tinc uses them to negotiate the request and response formats.
+    pub fn allow_headers(mut self, headers: impl IntoIterator<Item = http::HeaderName>) -> Self {
+        self.allow_headers.extend(headers);
+        self
+    }
+
+    /// Allow requests to send any header.
+    pub fn allow_any_header(mut self) -> Self {
+        self.allow_any_header = true;
+        self
+    }
+
+    /// Allow the browser to expose the given response headers to scripts.
+    pub fn expose_headers(mut self, headers: impl IntoIterator<Item = http::HeaderName>) -> Self {
+        self.expose_headers.extend(headers);
+        self
+    }
+
+    /// Allow requests with credentials, like cookies or the `Authorization` header.
+    pub fn allow_credentials(mut self, allow_credentials: bool) -> Self {
+        self.allow_credentials = allow_credentials;
+        self
+    }
+
+    /// Let browsers cache the result of a preflight request for the given duration.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns true if requests from the given origin are allowed.
+    pub fn is_origin_allowed(&self, origin: &http::HeaderValue) -> bool {
+        self.allow_any_origin || self.allow_origins.contains(origin)
+    }
+
+    /// Returns true if requests with the given method are allowed on a route with the given methods.
+    pub fn is_method_allowed(&self, method: &http::Method, route_methods: &[http::Method]) -> bool {
+        self.allow_methods.as_deref().unwrap_or(route_methods).contains(method)
+    }
+
+    /// Returns true if requests may send the given header.
+    pub fn is_header_allowed(&self, header: &str) -> bool {
+        self.allow_any_header
+            || ALWAYS_ALLOWED_HEADERS
+                .iter()
+                .chain(&self.allow_headers)
+                .any(|allowed| allowed.as_str().eq_ignore_ascii_case(header))
+    }
+}
@@ -111,11 +111,16 @@
 //!       "violations": [
 //!         {
 //!           "description": "invalid type: string \"1000\", expected i32 at line 4 column 24",
-//!           "field": "things[\"thing1\"]"
+//!           "field": "things[\"thing1\"]",
+//!           "pointer": "/things/thing1",
+//!           "rule": "invalid_field"
 //!         },
 //!         {
 //!           "description": "value must be less than or equal to `100`",
-//!           "field": "things[\"thing2\"]"
+//!           "expression": "input <= this",
+//!           "field": "things[\"thing2\"]",
+//!           "pointer": "/things/thing2",
+//!           "rule": "tinc.Int32Constraints.lte"
 //!         }
 //!       ]
 //!     }
@@ -124,6 +129,11 @@
 //! }
 //! ```
 //!
+//! The shape of this response can be customized by providing a [`ValidationErrorFormatter`](validation::ValidationErrorFormatter)
+//! to the generated service via `with_validation_error_formatter`.
+//! The [`LocalizedValidationErrorFormatter`](validation::LocalizedValidationErrorFormatter) translates the
+//! messages with a [`MessageCatalog`](validation::MessageCatalog) based on the `Accept-Language` header.
+//!
 //! The cel expressions can be extended to provide custom expressions:
 //!
 //! ```protobuf
@@ -159,7 +169,10 @@
 //!       "violations": [
 //!         {
 //!           "description": "must equal `troy` but got `notTroy`",
-//!           "field": "name"
+//!           "expression": "input == this",
+//!           "field": "name",
+//!           "pointer": "/name",
+//!           "rule": "must_eq"
 //!         }
 //!       ]
 //!     }
@@ -234,8 +247,13 @@ pub mod reexports {
 #[path = "private/mod.rs"]
 pub mod __private;
 
+pub mod cors;
+mod openapi_router;
+pub mod validation;
 pub mod well_known;
 
+pub use openapi_router::OpenApiRouter;
+
 pub use openapiv3_1 as openapi;
 
 /// TincServices are typically generated by the `tinc-build`
@@ -249,9 +267,39 @@ pub trait TincService {
     fn openapi_schema_str(&self) -> &'static str;
 
     /// Get the openapi spec for this service.
+    ///
+    /// Use an [`OpenApiRouter`] to serve the merged spec of several services.
     fn openapi_schema(&self) -> openapiv3_1::OpenApi {
         serde_json::from_str(self.openapi_schema_str()).expect("invalid openapi schema")
     }
+
+    /// Get the http routes of this service.
+    ///
+    /// This is the same information as the paths of the [openapi spec](TincService::openapi_schema),
+    /// without having to parse it, which is useful for building auth maps or metric labels.
+    fn routes(&self) -> &'static [TincRoute] {
+        &[]
+    }
+}
+
+/// A http route of a [`TincService`].
+///
+/// There is one route for every http endpoint of every rpc method of the service.
+/// Generated services also export all their routes as a `ROUTES` constant in their `_tinc` module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TincRoute {
+    /// The full name of the protobuf service, e.g. `my_package.MyService`.
+    pub service: &'static str,
+    /// The name of the rpc method, e.g. `GetUser`.
+    pub method: &'static str,
+    /// The http method of the route.
+    pub http_method: http::Method,
+    /// The path template of the route, e.g. `/users/{id}`.
+    pub path: &'static str,
+    /// The full protobuf name of the request type, e.g. `my_package.GetUserRequest`.
+    pub request_type: &'static str,
+    /// The full protobuf name of the response type, e.g. `my_package.User`.
+    pub response_type: &'static str,
 }
 
 /// Include the proto by specifying the package.
@@ -0,0 +1,241 @@
+use std::hash::{Hash, Hasher};
+
+use axum::response::IntoResponse;
+use bytes::Bytes;
+use http::header;
+
+use crate::TincService;
+
+/// Serves the merged OpenAPI document of several tinc services.
+///
+/// The document is served as `/openapi.json`, and as `/openapi.yaml` with the `yaml` feature.
+/// Responses carry an `ETag`, so clients can revalidate the document with `If-None-Match`.
+///
+/// ```rust
+/// # fn router(users: impl tinc::TincService + Clone, posts: impl tinc::TincService + Clone) -> axum::Router {
+/// axum::Router::new()
+///     .merge(tinc::OpenApiRouter::new().service(&users).service(&posts).into_router())
+///     .merge(users.into_router())
+///     .merge(posts.into_router())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct OpenApiRouter {
+    document: Option<openapiv3_1::OpenApi>,
+    info: Option<openapiv3_1::Info>,
+    json_path: String,
+    #[cfg(feature = "yaml")]
+    yaml_path: String,
+}
+
+impl std::fmt::Debug for OpenApiRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("OpenApiRouter");
+        debug.field("json_path", &self.json_path);
+        #[cfg(feature = "yaml")]
+        debug.field("yaml_path", &self.yaml_path);
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl Default for OpenApiRouter {
+    fn default() -> Self {
+        Self {
+            document: None,
+            info: None,
+            json_path: "/openapi.json".to_owned(),
+            #[cfg(feature = "yaml")]
+            yaml_path: "/openapi.yaml".to_owned(),
+        }
+    }
+}
+
+impl OpenApiRouter {
+    /// Create a new router without any documents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge the OpenAPI document of the service.
+    pub fn service(self, service: &impl TincService) -> Self {
+        self.document(service.openapi_schema())
+    }
+
+    /// Merge an OpenAPI document, see [`OpenApi::merge`](openapiv3_1::OpenApi::merge).
+    ///
+    /// The info of the first document is used, unless it is set with [`info`](Self::info).
+    pub fn document(mut self, document: openapiv3_1::OpenApi) -> Self {
+        match &mut self.document {
+            Some(merged) => merged.merge(document),
+            None => self.document = Some(document),
+        }
+        self
+    }
+
+    /// Set the info of the merged document.
+    pub fn info(mut self, info: openapiv3_1::Info) -> Self {
+        self.info = Some(info);
+        self
+    }
+
+    /// Set the path of the JSON document, `/openapi.json` by default.
+    pub fn json_path(mut self, path: impl Into<String>) -> Self {
+        self.json_path = path.into();
+        self
+    }
+
+    /// Set the path of the YAML document, `/openapi.yaml` by default.
+    #[cfg(feature = "yaml")]
+    pub fn yaml_path(mut self, path: impl Into<String>) -> Self {
+        self.yaml_path = path.into();
+        self
+    }
+
+    /// Returns the merged document.
+    pub fn merged_document(&self) -> openapiv3_1::OpenApi {
+        let mut document = self.document.clone().unwrap_or_default();
+        if let Some(info) = &self.info {
+            document.info = info.clone();
+        }
+        document
+    }
+
+    /// Convert into an axum router serving the merged document.
+    pub fn into_router<S>(self) -> axum::Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let document = self.merged_document();
+
+        let json = Document::new(
+            "application/json",
+            document.to_json().expect("invalid openapi document").into(),
+        );
+        let router = axum::Router::new().route(&self.json_path, json.into_method_router());
+
+        #[cfg(feature = "yaml")]
+        let router = {
+            let yaml = Document::new(
+                "application/yaml",
+                document.to_yaml().expect("invalid openapi document").into(),
+            );
+            router.route(&self.yaml_path, yaml.into_method_router())
+        };
+
+        router
+    }
+}
+
+/// A serialized document served with an `ETag`.
+#[derive(Debug, Clone)]
+struct Document {
+    content_type: &'static str,
+    body: Bytes,
+    etag: http::HeaderValue,
+}
+
+impl Document {
+    fn new(content_type: &'static str, body: Bytes) -> Self {
+        let mut hasher = std::hash::DefaultHasher::new();
+        body.hash(&mut hasher);
+        let etag = http::HeaderValue::from_str(&format!("\"{:016x}\"", hasher.finish())).expect("valid etag");
+
+        Self {
+            content_type,
+            body,
+            etag,
+        }
+    }
+
+    fn into_method_router<S>(self) -> axum::routing::MethodRouter<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        axum::routing::get(move |headers: http::HeaderMap| async move { self.response(&headers) })
+    }
+
+    fn response(&self, request_headers: &http::HeaderMap) -> axum::response::Response {
+        let headers = [
+            (header::ETAG, self.etag.clone()),
+            (header::CACHE_CONTROL, http::HeaderValue::from_static("no-cache")),
+        ];
+
+        if self.is_not_modified(request_headers) {
+            return (http::StatusCode::NOT_MODIFIED, headers).into_response();
+        }
+
+        (
+            headers,
+            [(header::CONTENT_TYPE, http::HeaderValue::from_static(self.content_type))],
+            self.body.clone(),
+        )
+            .into_response()
+    }
+
+    /// Weak comparison of the `If-None-Match` header with the etag.
+    fn is_not_modified(&self, request_headers: &http::HeaderMap) -> bool {
+        let etag = self.etag.as_bytes();
+        request_headers
+            .get_all(header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/").as_bytes() == etag)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(coverage_nightly, test), coverage(off))]
+mod tests {
+    use http::header;
+
+    use super::{Document, OpenApiRouter};
+
+    #[test]
+    fn merge() {
+        let router = OpenApiRouter::new()
+            .document(openapiv3_1::OpenApi::new(
+                openapiv3_1::Info::new("users", "1.0.0"),
+                openapiv3_1::Paths::builder().path("/users", openapiv3_1::PathItem::default()),
+            ))
+            .document(openapiv3_1::OpenApi::new(
+                openapiv3_1::Info::new("posts", "1.0.0"),
+                openapiv3_1::Paths::builder().path("/posts", openapiv3_1::PathItem::default()),
+            ));
+
+        let document = router.merged_document();
+        assert_eq!(document.info.title, "users");
+        assert!(document.paths.paths.contains_key("/users"));
+        assert!(document.paths.paths.contains_key("/posts"));
+
+        let document = router.info(openapiv3_1::Info::new("api", "2.0.0")).merged_document();
+        assert_eq!(document.info.title, "api");
+    }
+
+    #[test]
+    fn etag() {
+        let document = Document::new("application/json", "{}".into());
+        let etag = document.etag.to_str().unwrap().to_owned();
+
+        let response = document.response(&http::HeaderMap::new());
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], etag);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+        for if_none_match in [
+            etag.clone(),
+            format!("W/{etag}"),
+            format!("\"other\", {etag}"),
+            "*".to_owned(),
+        ] {
+            let headers = http::HeaderMap::from_iter([(header::IF_NONE_MATCH, if_none_match.parse().unwrap())]);
+            let response = document.response(&headers);
+            assert_eq!(response.status(), http::StatusCode::NOT_MODIFIED, "{if_none_match}");
+            assert_eq!(response.headers()[header::ETAG], etag);
+        }
+
+        let headers = http::HeaderMap::from_iter([(header::IF_NONE_MATCH, "\"other\"".parse().unwrap())]);
+        assert_eq!(document.response(&headers).status(), http::StatusCode::OK);
+    }
+}
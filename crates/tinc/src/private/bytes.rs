@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 
 use base64::Engine;
@@ -5,9 +6,114 @@ use bytes::Bytes;
 
 use super::{DeserializeContent, DeserializeHelper, Expected, Tracker, TrackerDeserializer, TrackerFor};
 
-pub struct BytesTracker<T>(PhantomData<T>);
+/// How bytes are represented in JSON.
+pub trait BytesEncoding {
+    fn encode(bytes: &[u8]) -> String;
 
-impl<T> std::fmt::Debug for BytesTracker<T> {
+    fn decode<E: serde::de::Error>(value: &str) -> Result<Vec<u8>, E>;
+
+    fn expecting(formatter: &mut std::fmt::Formatter) -> std::fmt::Result;
+}
+
+/// Standard base64 with padding, the protobuf JSON default.
+///
+/// Both alphabets are accepted when decoding, with or without padding.
+pub struct Base64;
+
+/// URL safe base64 without padding.
+///
+/// Both alphabets are accepted when decoding, with or without padding.
+pub struct Base64Url;
+
+/// Lowercase hex, uppercase is accepted when decoding.
+pub struct Hex;
+
+fn decode_base64<E: serde::de::Error>(value: &str) -> Result<Vec<u8>, E> {
+    let config = base64::engine::GeneralPurposeConfig::new()
+        .with_decode_allow_trailing_bits(true)
+        .with_encode_padding(true)
+        .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent);
+
+    let alphabet = if value.as_bytes().iter().any(|b| b == &b'-' || b == &b'_') {
+        &base64::alphabet::URL_SAFE
+    } else {
+        &base64::alphabet::STANDARD
+    };
+
+    let engine = base64::engine::GeneralPurpose::new(alphabet, config);
+    engine.decode(value.as_bytes()).map_err(E::custom)
+}
+
+impl BytesEncoding for Base64 {
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn decode<E: serde::de::Error>(value: &str) -> Result<Vec<u8>, E> {
+        decode_base64(value)
+    }
+
+    fn expecting(formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "bytes")
+    }
+}
+
+impl BytesEncoding for Base64Url {
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn decode<E: serde::de::Error>(value: &str) -> Result<Vec<u8>, E> {
+        decode_base64(value)
+    }
+
+    fn expecting(formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "base64url encoded bytes")
+    }
+}
+
+impl BytesEncoding for Hex {
+    fn encode(bytes: &[u8]) -> String {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let mut out = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            out.push(DIGITS[(byte >> 4) as usize] as char);
+            out.push(DIGITS[(byte & 0xf) as usize] as char);
+        }
+
+        out
+    }
+
+    fn decode<E: serde::de::Error>(value: &str) -> Result<Vec<u8>, E> {
+        fn digit<E: serde::de::Error>(c: u8) -> Result<u8, E> {
+            match c {
+                b'0'..=b'9' => Ok(c - b'0'),
+                b'a'..=b'f' => Ok(c - b'a' + 10),
+                b'A'..=b'F' => Ok(c - b'A' + 10),
+                _ => Err(E::custom(format!("invalid hex character `{}`", c.escape_ascii()))),
+            }
+        }
+
+        let value = value.as_bytes();
+        if !value.len().is_multiple_of(2) {
+            return Err(E::custom("hex string must have an even length"));
+        }
+
+        value
+            .chunks_exact(2)
+            .map(|pair| Ok((digit::<E>(pair[0])? << 4) | digit::<E>(pair[1])?))
+            .collect()
+    }
+
+    fn expecting(formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "hex encoded bytes")
+    }
+}
+
+pub struct BytesTracker<T, E = Base64>(PhantomData<(T, E)>);
+
+impl<T, E> std::fmt::Debug for BytesTracker<T, E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "BytesTracker<{}>", std::any::type_name::<T>())
     }
@@ -21,12 +127,12 @@ pub trait BytesLikeTracker: Tracker {
     }
 }
 
-impl BytesLikeTracker for BytesTracker<Bytes> {
+impl<E> BytesLikeTracker for BytesTracker<Bytes, E> {
     fn set_target(&mut self, target: &mut Self::Target, mut buf: impl bytes::Buf) {
         *target = buf.copy_to_bytes(buf.remaining());
     }
 }
-impl BytesLikeTracker for BytesTracker<Vec<u8>> {
+impl<E> BytesLikeTracker for BytesTracker<Vec<u8>, E> {
     fn set_target(&mut self, target: &mut Self::Target, mut buf: impl bytes::Buf) {
         target.clear();
         target.reserve_exact(buf.remaining());
@@ -42,13 +148,13 @@ impl BytesLikeTracker for BytesTracker<Vec<u8>> {
     }
 }
 
-impl<T> Default for BytesTracker<T> {
+impl<T, E> Default for BytesTracker<T, E> {
     fn default() -> Self {
         BytesTracker(PhantomData)
     }
 }
 
-impl<T: Expected> Tracker for BytesTracker<T> {
+impl<T: Expected, E> Tracker for BytesTracker<T, E> {
     type Target = T;
 
     fn allow_duplicates(&self) -> bool {
@@ -76,10 +182,11 @@ impl Expected for bytes::Bytes {
     }
 }
 
-impl<'de, T> serde::de::DeserializeSeed<'de> for DeserializeHelper<'_, BytesTracker<T>>
+impl<'de, T, E> serde::de::DeserializeSeed<'de> for DeserializeHelper<'_, BytesTracker<T, E>>
 where
     T: Expected,
-    BytesTracker<T>: Tracker<Target = T> + BytesLikeTracker,
+    E: BytesEncoding,
+    BytesTracker<T, E>: Tracker<Target = T> + BytesLikeTracker,
 {
     type Value = ();
 
@@ -91,43 +198,33 @@ where
     }
 }
 
-impl<'de, T> serde::de::Visitor<'de> for DeserializeHelper<'_, BytesTracker<T>>
+impl<'de, T, E> serde::de::Visitor<'de> for DeserializeHelper<'_, BytesTracker<T, E>>
 where
     T: Expected,
-    BytesTracker<T>: Tracker<Target = T> + BytesLikeTracker,
+    E: BytesEncoding,
+    BytesTracker<T, E>: Tracker<Target = T> + BytesLikeTracker,
 {
     type Value = ();
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        T::expecting(formatter)
+        E::expecting(formatter)
     }
 
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    fn visit_str<Err>(self, v: &str) -> Result<Self::Value, Err>
     where
-        E: serde::de::Error,
+        Err: serde::de::Error,
     {
-        let config = base64::engine::GeneralPurposeConfig::new()
-            .with_decode_allow_trailing_bits(true)
-            .with_encode_padding(true)
-            .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent);
-
-        let alphabet = if v.as_bytes().iter().any(|b| b == &b'-' || b == &b'_') {
-            &base64::alphabet::URL_SAFE
-        } else {
-            &base64::alphabet::STANDARD
-        };
-
-        let engine = base64::engine::GeneralPurpose::new(alphabet, config);
-        let bytes = engine.decode(v.as_bytes()).map_err(serde::de::Error::custom)?;
+        let bytes = E::decode(v)?;
         self.tracker.set_target_vec(self.value, bytes);
         Ok(())
     }
 }
 
-impl<'de, T> TrackerDeserializer<'de> for BytesTracker<T>
+impl<'de, T, E> TrackerDeserializer<'de> for BytesTracker<T, E>
 where
     T: Expected,
-    BytesTracker<T>: Tracker<Target = T> + BytesLikeTracker,
+    E: BytesEncoding,
+    BytesTracker<T, E>: Tracker<Target = T> + BytesLikeTracker,
 {
     fn deserialize<D>(&mut self, value: &mut Self::Target, deserializer: D) -> Result<(), D::Error>
     where
@@ -136,3 +233,123 @@ where
         deserializer.deserialize_seed(DeserializeHelper { value, tracker: self })
     }
 }
+
+/// Bytes which are represented with the encoding `E` in JSON.
+///
+/// This type is only used to pick the tracker of fields with a non-default bytes encoding.
+#[repr(transparent)]
+pub struct EncodedBytes<T, E> {
+    value: T,
+    _marker: PhantomData<E>,
+}
+
+impl<T: Default, E> Default for EncodedBytes<T, E> {
+    fn default() -> Self {
+        Self {
+            value: T::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, E: BytesEncoding> Expected for EncodedBytes<T, E> {
+    fn expecting(formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        E::expecting(formatter)
+    }
+}
+
+impl<T: Expected, E> TrackerFor for EncodedBytes<T, E> {
+    type Tracker = BytesTracker<T, E>;
+}
+
+impl<T: AsRef<[u8]>, E: BytesEncoding> serde::Serialize for EncodedBytes<T, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&E::encode(self.value.as_ref()))
+    }
+}
+
+pub trait BytesHelper {
+    type Target<E>;
+}
+
+impl BytesHelper for Vec<u8> {
+    type Target<E> = EncodedBytes<Self, E>;
+}
+
+impl BytesHelper for Bytes {
+    type Target<E> = EncodedBytes<Self, E>;
+}
+
+impl<T: BytesHelper> BytesHelper for Option<T> {
+    type Target<E> = Option<T::Target<E>>;
+}
+
+impl<T: BytesHelper> BytesHelper for Vec<T> {
+    type Target<E> = Vec<T::Target<E>>;
+}
+
+impl<K: Ord, V: BytesHelper> BytesHelper for BTreeMap<K, V> {
+    type Target<E> = BTreeMap<K, V::Target<E>>;
+}
+
+impl<K, V: BytesHelper, S> BytesHelper for HashMap<K, V, S> {
+    type Target<E> = HashMap<K, V::Target<E>, S>;
+}
+
+/// # Safety
+/// This trait is marked as unsafe because the implementator
+/// must ensure that Helper has the same layout & memory representation as Self.
+unsafe trait BytesSerialize<E> {
+    type Helper: serde::Serialize;
+
+    fn cast(&self) -> &Self::Helper {
+        // Safety: This trait is marked as unsafe and that safety condition
+        // makes this operation safe.
+        unsafe { &*(self as *const Self as *const Self::Helper) }
+    }
+}
+
+macro_rules! impl_bytes_serialize {
+    ($($ty:ty),*) => {
+        $(
+            /// Safety: [`EncodedBytes`] is `#[repr(transparent)]` for the bytes type.
+            unsafe impl<E: BytesEncoding> BytesSerialize<E> for $ty {
+                type Helper = EncodedBytes<$ty, E>;
+            }
+
+            /// Safety: [`EncodedBytes`] is `#[repr(transparent)]` for the bytes type.
+            unsafe impl<E: BytesEncoding> BytesSerialize<E> for Option<$ty> {
+                type Helper = Option<EncodedBytes<$ty, E>>;
+            }
+
+            /// Safety: [`EncodedBytes`] is `#[repr(transparent)]` for the bytes type.
+            unsafe impl<E: BytesEncoding> BytesSerialize<E> for Vec<$ty> {
+                type Helper = Vec<EncodedBytes<$ty, E>>;
+            }
+
+            /// Safety: [`EncodedBytes`] is `#[repr(transparent)]` for the bytes type.
+            unsafe impl<E: BytesEncoding, K: serde::Serialize> BytesSerialize<E> for BTreeMap<K, $ty> {
+                type Helper = BTreeMap<K, EncodedBytes<$ty, E>>;
+            }
+
+            /// Safety: [`EncodedBytes`] is `#[repr(transparent)]` for the bytes type.
+            unsafe impl<E: BytesEncoding, K: serde::Serialize, S> BytesSerialize<E> for HashMap<K, $ty, S> {
+                type Helper = HashMap<K, EncodedBytes<$ty, E>, S>;
+            }
+        )*
+    };
+}
+
+impl_bytes_serialize!(Vec<u8>, Bytes);
+
+#[allow(private_bounds)]
+pub fn serialize_bytes<E, V, S>(value: &V, serializer: S) -> Result<S::Ok, S::Error>
+where
+    V: BytesSerialize<E>,
+    S: serde::Serializer,
+{
+    serde::Serialize::serialize(value.cast(), serializer)
+}
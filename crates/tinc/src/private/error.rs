@@ -59,6 +59,10 @@ impl<'a> ProtoPathToken<'a> {
     pub fn current_path() -> String {
         PROTO_PATH_BUFFER.with(|buffer| format_path_items(buffer.borrow().as_slice()))
     }
+
+    pub fn current_pointer() -> String {
+        PROTO_PATH_BUFFER.with(|buffer| format_json_pointer(buffer.borrow().as_slice()))
+    }
 }
 
 impl Drop for ProtoPathToken<'_> {
@@ -197,6 +201,10 @@ impl<'a> SerdePathToken<'a> {
     pub fn current_path() -> String {
         SERDE_PATH_BUFFER.with(|buffer| format_path_items(buffer.borrow().as_slice()))
     }
+
+    pub fn current_pointer() -> String {
+        SERDE_PATH_BUFFER.with(|buffer| format_json_pointer(buffer.borrow().as_slice()))
+    }
 }
 
 fn format_path_items(items: &[PathItem]) -> String {
@@ -229,6 +237,42 @@ fn format_path_items(items: &[PathItem]) -> String {
     .to_string()
 }
 
+/// Formats the path as a [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointer.
+pub(crate) fn format_json_pointer(items: &[PathItem]) -> String {
+    fn write_escaped(fmt: &mut std::fmt::Formatter<'_>, segment: &str) -> std::fmt::Result {
+        for c in segment.chars() {
+            match c {
+                '~' => fmt.write_str("~0")?,
+                '/' => fmt.write_str("~1")?,
+                c => fmt.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    FuncFmt(|fmt| {
+        for token in items {
+            fmt.write_char('/')?;
+            match token {
+                PathItem::Field(field) => write_escaped(fmt, field)?,
+                PathItem::Index(index) => std::fmt::Display::fmt(index, fmt)?,
+                PathItem::Key(key) => {
+                    let key = format!("{:?}", key.0);
+                    // String keys are debug formatted with quotes, json pointers use the raw key.
+                    match serde_json::from_str::<String>(&key) {
+                        Ok(key) => write_escaped(fmt, &key)?,
+                        Err(_) => write_escaped(fmt, key.trim_matches('"'))?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    })
+    .to_string()
+}
+
 impl Drop for SerdePathToken<'_> {
     fn drop(&mut self) {
         SERDE_PATH_BUFFER.with(|buffer| {
@@ -297,6 +341,12 @@ pub enum TrackedErrorKind {
     InvalidField {
         message: Box<str>,
     },
+    ConstraintViolation {
+        message: Box<str>,
+        rule: &'static str,
+        rule_value: Option<Box<str>>,
+        expression: &'static str,
+    },
 }
 
 #[derive(Debug)]
@@ -304,6 +354,7 @@ pub struct TrackedError {
     pub kind: TrackedErrorKind,
     pub fatal: bool,
     pub path: Box<str>,
+    pub pointer: Box<str>,
 }
 
 impl TrackedError {
@@ -313,6 +364,42 @@ impl TrackedError {
             TrackedErrorKind::UnknownField => "unknown field",
             TrackedErrorKind::MissingField => "missing field",
             TrackedErrorKind::InvalidField { message } => message.as_ref(),
+            TrackedErrorKind::ConstraintViolation { message, .. } => message.as_ref(),
+        }
+    }
+
+    pub fn rule(&self) -> &'static str {
+        match &self.kind {
+            TrackedErrorKind::DuplicateField => "duplicate_field",
+            TrackedErrorKind::UnknownField => "unknown_field",
+            TrackedErrorKind::MissingField => "missing_field",
+            TrackedErrorKind::InvalidField { .. } => "invalid_field",
+            TrackedErrorKind::ConstraintViolation { rule, .. } => rule,
+        }
+    }
+
+    pub fn rule_value(&self) -> Option<&str> {
+        match &self.kind {
+            TrackedErrorKind::ConstraintViolation { rule_value, .. } => rule_value.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn expression(&self) -> Option<&'static str> {
+        match &self.kind {
+            TrackedErrorKind::ConstraintViolation { expression, .. } => Some(expression),
+            _ => None,
+        }
+    }
+
+    pub fn violation(&self) -> crate::validation::Violation<'_> {
+        crate::validation::Violation {
+            field: &self.path,
+            pointer: &self.pointer,
+            rule: self.rule(),
+            rule_value: self.rule_value(),
+            description: self.message(),
+            expression: self.expression(),
         }
     }
 }
@@ -323,20 +410,25 @@ impl std::fmt::Display for TrackedError {
             TrackedErrorKind::DuplicateField => write!(f, "`{}` was already provided", self.path),
             TrackedErrorKind::UnknownField => write!(f, "unknown field `{}`", self.path),
             TrackedErrorKind::MissingField => write!(f, "missing field `{}`", self.path),
-            TrackedErrorKind::InvalidField { message } => write!(f, "`{}`: {}", self.path, message),
+            TrackedErrorKind::InvalidField { message } | TrackedErrorKind::ConstraintViolation { message, .. } => {
+                write!(f, "`{}`: {}", self.path, message)
+            }
         }
     }
 }
 
 impl TrackedError {
     fn new(kind: TrackedErrorKind, fatal: bool) -> Self {
+        let (path, pointer) = match tinc_cel::CelMode::current() {
+            tinc_cel::CelMode::Serde => (SerdePathToken::current_path(), SerdePathToken::current_pointer()),
+            tinc_cel::CelMode::Proto => (ProtoPathToken::current_path(), ProtoPathToken::current_pointer()),
+        };
+
         Self {
             kind,
             fatal,
-            path: match tinc_cel::CelMode::current() {
-                tinc_cel::CelMode::Serde => SerdePathToken::current_path().into_boxed_str(),
-                tinc_cel::CelMode::Proto => ProtoPathToken::current_path().into_boxed_str(),
-            },
+            path: path.into_boxed_str(),
+            pointer: pointer.into_boxed_str(),
         }
     }
 
@@ -348,6 +440,23 @@ impl TrackedError {
         Self::new(TrackedErrorKind::InvalidField { message: message.into() }, true)
     }
 
+    pub fn constraint_violation(
+        message: impl Into<Box<str>>,
+        rule: &'static str,
+        rule_value: Option<Box<str>>,
+        expression: &'static str,
+    ) -> Self {
+        Self::new(
+            TrackedErrorKind::ConstraintViolation {
+                message: message.into(),
+                rule,
+                rule_value,
+                expression,
+            },
+            true,
+        )
+    }
+
     pub fn duplicate_field() -> Self {
         Self::new(TrackedErrorKind::DuplicateField, true)
     }
@@ -704,6 +813,7 @@ impl<'a> From<(Option<&'a tonic_types::BadRequest>, Option<&'a tonic_types::Requ
                         .map(|violation| HttpErrorResponseRequestViolation {
                             field: &violation.field,
                             description: &violation.description,
+                            ..Default::default()
                         })
                         .filter(|violation| !violation.field.is_empty() && !violation.description.is_empty())
                         .collect()
@@ -720,7 +830,13 @@ pub struct HttpErrorResponseRequestViolation<'a> {
     #[serde(skip_serializing_if = "is_default")]
     pub field: &'a str,
     #[serde(skip_serializing_if = "is_default")]
+    pub pointer: &'a str,
+    #[serde(skip_serializing_if = "is_default")]
+    pub rule: &'a str,
+    #[serde(skip_serializing_if = "is_default")]
     pub description: &'a str,
+    #[serde(skip_serializing_if = "is_default")]
+    pub expression: Option<&'a str>,
 }
 
 #[derive(Debug, serde_derive::Serialize, Default, PartialEq)]
@@ -9,20 +9,24 @@ use crate::__private::{
     TrackerDeserializer, TrackerSharedState, deserialize_tracker_target,
 };
 
-pub async fn deserialize_body_json<T, B>(
-    parts: &http::request::Parts,
-    body: B,
-    tracker: &mut T,
-    target: &mut T::Target,
-    state: &mut TrackerSharedState,
-) -> Result<(), axum::response::Response>
-where
-    T: for<'de> TrackerDeserializer<'de>,
-    B: http_body::Body,
-    B::Error: std::fmt::Display,
-{
+/// The media type of protobuf encoded http bodies.
+pub const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+fn is_protobuf(ty: mediatype::Name<'_>, subty: mediatype::Name<'_>) -> bool {
+    ty == mediatype::names::APPLICATION && subty == "x-protobuf"
+}
+
+/// The encoding of a request or response body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFormat {
+    Json,
+    Protobuf,
+}
+
+#[allow(clippy::result_large_err)]
+fn body_content_type(parts: &http::request::Parts) -> Result<Option<mediatype::MediaTypeBuf>, axum::response::Response> {
     let Some(content_type) = parts.headers.get(http::header::CONTENT_TYPE) else {
-        return Ok(());
+        return Ok(None);
     };
 
     let content_type = content_type.to_str().map_err(|_| {
@@ -34,25 +38,22 @@ where
         .into_response()
     })?;
 
-    let content_type = mediatype::MediaTypeBuf::from_str(content_type).map_err(|err| {
+    mediatype::MediaTypeBuf::from_str(content_type).map(Some).map_err(|err| {
         HttpErrorResponse {
             code: HttpErrorResponseCode::InvalidArgument,
             details: Default::default(),
             message: &format!("content-type header is not valid: {err}"),
         }
         .into_response()
-    })?;
-
-    if content_type.essence() != mediatype::media_type!(APPLICATION / JSON) {
-        return Err(HttpErrorResponse {
-            code: HttpErrorResponseCode::InvalidArgument,
-            details: Default::default(),
-            message: "content-type header is not application/json",
-        }
-        .into_response());
-    }
+    })
+}
 
-    let body = body
+async fn collect_body<B>(body: B) -> Result<impl Buf, axum::response::Response>
+where
+    B: http_body::Body,
+    B::Error: std::fmt::Display,
+{
+    Ok(body
         .collect()
         .await
         .map_err(|err| {
@@ -63,8 +64,19 @@ where
             }
             .into_response()
         })?
-        .aggregate();
+        .aggregate())
+}
 
+#[allow(clippy::result_large_err)]
+fn deserialize_json<T>(
+    body: impl Buf,
+    tracker: &mut T,
+    target: &mut T::Target,
+    state: &mut TrackerSharedState,
+) -> Result<(), axum::response::Response>
+where
+    T: for<'de> TrackerDeserializer<'de>,
+{
     let mut de = serde_json::Deserializer::from_reader(body.reader());
 
     if let Err(err) = deserialize_tracker_target(state, &mut de, tracker, target) {
@@ -79,6 +91,145 @@ where
     Ok(())
 }
 
+pub async fn deserialize_body_json<T, B>(
+    parts: &http::request::Parts,
+    body: B,
+    tracker: &mut T,
+    target: &mut T::Target,
+    state: &mut TrackerSharedState,
+) -> Result<(), axum::response::Response>
+where
+    T: for<'de> TrackerDeserializer<'de>,
+    B: http_body::Body,
+    B::Error: std::fmt::Display,
+{
+    let Some(content_type) = body_content_type(parts)? else {
+        return Ok(());
+    };
+
+    if content_type.essence() != mediatype::media_type!(APPLICATION / JSON) {
+        return Err(HttpErrorResponse {
+            code: HttpErrorResponseCode::InvalidArgument,
+            details: Default::default(),
+            message: "content-type header is not application/json",
+        }
+        .into_response());
+    }
+
+    deserialize_json(collect_body(body).await?, tracker, target, state)
+}
+
+/// Deserializes a message body encoded as either JSON or protobuf, depending on the content-type.
+///
+/// Protobuf bodies are not tracked, so the returned format decides if the tracker can be used
+/// for validation. Fields which were already set on the target, such as path parameters,
+/// take precedence over the protobuf body.
+#[cfg(feature = "prost")]
+pub async fn deserialize_body_json_or_protobuf<T, B>(
+    parts: &http::request::Parts,
+    body: B,
+    tracker: &mut T,
+    target: &mut T::Target,
+    state: &mut TrackerSharedState,
+) -> Result<BodyFormat, axum::response::Response>
+where
+    T: for<'de> TrackerDeserializer<'de>,
+    T::Target: prost::Message + Default,
+    B: http_body::Body,
+    B::Error: std::fmt::Display,
+{
+    let Some(content_type) = body_content_type(parts)? else {
+        return Ok(BodyFormat::Json);
+    };
+
+    if content_type.essence() == mediatype::media_type!(APPLICATION / JSON) {
+        deserialize_json(collect_body(body).await?, tracker, target, state)?;
+        return Ok(BodyFormat::Json);
+    }
+
+    if !is_protobuf(content_type.ty(), content_type.subty()) {
+        return Err(HttpErrorResponse {
+            code: HttpErrorResponseCode::InvalidArgument,
+            details: Default::default(),
+            message: "content-type header is not application/json or application/x-protobuf",
+        }
+        .into_response());
+    }
+
+    let mut message = <T::Target as prost::Message>::decode(collect_body(body).await?).map_err(|err| {
+        HttpErrorResponse {
+            code: HttpErrorResponseCode::InvalidArgument,
+            details: Default::default(),
+            message: &format!("failed to decode body: {err}"),
+        }
+        .into_response()
+    })?;
+
+    prost::Message::merge(&mut message, prost::Message::encode_to_vec(target).as_slice())
+        .expect("re-encoded message is always valid");
+    *target = message;
+
+    Ok(BodyFormat::Protobuf)
+}
+
+/// Picks the response body format from the `accept` header.
+///
+/// Protobuf is only used if the client prefers `application/x-protobuf` over JSON, wildcards
+/// count towards JSON. Without an `accept` header the response mirrors a protobuf request body.
+pub fn negotiate_response_format(headers: &http::HeaderMap) -> BodyFormat {
+    let mut accept = headers
+        .get_all(http::header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(mediatype::MediaTypeList::new)
+        .filter_map(Result::ok)
+        .peekable();
+
+    if accept.peek().is_none() {
+        let protobuf_request = headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| mediatype::MediaType::parse(value).ok())
+            .is_some_and(|content_type| is_protobuf(content_type.ty, content_type.subty));
+
+        return if protobuf_request {
+            BodyFormat::Protobuf
+        } else {
+            BodyFormat::Json
+        };
+    }
+
+    let mut json_quality = 0.0f32;
+    let mut protobuf_quality = 0.0f32;
+    for media_type in accept {
+        let quality = media_type
+            .params
+            .iter()
+            .find(|(name, _)| name == "q")
+            .map_or(Some(1.0), |(_, value)| value.unquoted_str().parse::<f32>().ok())
+            .unwrap_or(0.0);
+
+        let quality_for = if is_protobuf(media_type.ty, media_type.subty) {
+            &mut protobuf_quality
+        } else if media_type.ty == mediatype::names::_STAR
+            || media_type.ty == mediatype::names::APPLICATION
+                && (media_type.subty == mediatype::names::JSON || media_type.subty == mediatype::names::_STAR)
+        {
+            &mut json_quality
+        } else {
+            continue;
+        };
+
+        *quality_for = quality_for.max(quality);
+    }
+
+    if protobuf_quality > json_quality {
+        BodyFormat::Protobuf
+    } else {
+        BodyFormat::Json
+    }
+}
+
 impl<T> BytesLikeTracker for OptionalTracker<T>
 where
     T: BytesLikeTracker + Default,
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use axum::response::IntoResponse;
+use http::header;
+
+use crate::__private::HttpErrorResponseCode;
+use crate::__private::error::HttpErrorResponse;
+use crate::cors::CorsPolicy;
+
+/// The CORS state of a request to a route, used to add the CORS headers to its response.
+pub struct CorsRequest {
+    policy: Option<Arc<CorsPolicy>>,
+    origin: Option<http::HeaderValue>,
+}
+
+impl CorsRequest {
+    pub fn new(policy: Option<&Arc<CorsPolicy>>, headers: &http::HeaderMap) -> Self {
+        Self {
+            policy: policy.cloned(),
+            origin: headers.get(header::ORIGIN).cloned(),
+        }
+    }
+
+    /// Adds the CORS headers to the response of the request.
+    pub fn apply(self, mut response: axum::response::Response) -> axum::response::Response {
+        let Some(policy) = self.policy else {
+            return response;
+        };
+
+        let headers = response.headers_mut();
+        if mirrors_origin(&policy) {
+            headers.append(header::VARY, http::HeaderValue::from_static("origin"));
+        }
+
+        if let Some(origin) = self.origin.filter(|origin| policy.is_origin_allowed(origin)) {
+            allow_origin(headers, &policy, origin);
+
+            if !policy.expose_headers.is_empty() {
+                headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, join(&policy.expose_headers));
+            }
+        }
+
+        response
+    }
+}
+
+/// Answers a preflight request to a route with the given methods.
+///
+/// Rejected preflight requests are answered with a tinc error response.
+pub fn cors_preflight(
+    policy: &CorsPolicy,
+    headers: &http::HeaderMap,
+    route_methods: &[http::Method],
+) -> axum::response::Response {
+    let forbidden = |message: &str| {
+        HttpErrorResponse {
+            code: HttpErrorResponseCode::PermissionDenied,
+            details: Default::default(),
+            message,
+        }
+        .into_response()
+    };
+
+    let (Some(origin), Some(method)) = (
+        headers.get(header::ORIGIN),
+        headers.get(header::ACCESS_CONTROL_REQUEST_METHOD),
+    ) else {
+        // Not a CORS preflight request, only tell the client which methods the route supports.
+        let mut allow = route_methods.to_vec();
+        allow.push(http::Method::OPTIONS);
+        return (http::StatusCode::NO_CONTENT, [(header::ALLOW, join(&allow))]).into_response();
+    };
+
+    if !policy.is_origin_allowed(origin) {
+        return forbidden("origin is not allowed");
+    }
+
+    let method = http::Method::from_bytes(method.as_bytes()).ok();
+    if !method.is_some_and(|method| policy.is_method_allowed(&method, route_methods)) {
+        return forbidden("method is not allowed");
+    }
+
+    let request_headers = headers
+        .get_all(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|header| !header.is_empty())
+        .collect::<Vec<_>>();
+
+    if let Some(header) = request_headers.iter().find(|header| !policy.is_header_allowed(header)) {
+        return forbidden(&format!("header {header} is not allowed"));
+    }
+
+    let mut response = http::StatusCode::NO_CONTENT.into_response();
+    let response_headers = response.headers_mut();
+
+    response_headers.append(
+        header::VARY,
+        http::HeaderValue::from_static("origin, access-control-request-method, access-control-request-headers"),
+    );
+    allow_origin(response_headers, policy, origin.clone());
+    response_headers.insert(
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        join(policy.allow_methods.as_deref().unwrap_or(route_methods)),
+    );
+
+    if !request_headers.is_empty() {
+        response_headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, join(&request_headers));
+    }
+
+    if let Some(max_age) = policy.max_age {
+        response_headers.insert(header::ACCESS_CONTROL_MAX_AGE, max_age.as_secs().into());
+    }
+
+    response
+}
+
+fn allow_origin(headers: &mut http::HeaderMap, policy: &CorsPolicy, origin: http::HeaderValue) {
+    if policy.allow_credentials {
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            http::HeaderValue::from_static("true"),
+        );
+    }
+
+    let origin = if mirrors_origin(policy) {
+        origin
+    } else {
+        http::HeaderValue::from_static("*")
+    };
+
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+}
+
+/// Browsers reject `*` for requests with credentials, so the origin of the request is returned instead.
+fn mirrors_origin(policy: &CorsPolicy) -> bool {
+    !policy.allow_any_origin || policy.allow_credentials
+}
+
+fn join<T: AsRef<str>>(items: &[T]) -> http::HeaderValue {
+    let value = items.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(", ");
+    http::HeaderValue::from_str(&value).expect("joined header values are valid")
+}
+
+#[cfg(test)]
+#[cfg_attr(all(coverage_nightly, test), coverage(off))]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::response::IntoResponse;
+    use http::header;
+
+    use super::{CorsRequest, cors_preflight};
+    use crate::cors::CorsPolicy;
+
+    fn request_headers(headers: &[(http::HeaderName, &'static str)]) -> http::HeaderMap {
+        headers
+            .iter()
+            .map(|(name, value)| (name.clone(), http::HeaderValue::from_static(value)))
+            .collect()
+    }
+
+    #[test]
+    fn preflight() {
+        let policy = CorsPolicy::new()
+            .allow_origin(http::HeaderValue::from_static("https://example.com"))
+            .allow_headers([header::AUTHORIZATION])
+            .max_age(std::time::Duration::from_secs(600));
+        let methods = [http::Method::GET, http::Method::POST];
+
+        let headers = request_headers(&[
+            (header::ORIGIN, "https://example.com"),
+            (header::ACCESS_CONTROL_REQUEST_METHOD, "POST"),
+            (header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization, content-type"),
+        ]);
+        let response = cors_preflight(&policy, &headers, &methods);
+        assert_eq!(response.status(), http::StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS], "GET, POST");
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            "authorization, content-type"
+        );
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_MAX_AGE], "600");
+        assert_eq!(
+            response.headers()[header::VARY],
+            "origin, access-control-request-method, access-control-request-headers"
+        );
+
+        let headers = request_headers(&[
+            (header::ORIGIN, "https://example.org"),
+            (header::ACCESS_CONTROL_REQUEST_METHOD, "POST"),
+        ]);
+        assert_eq!(
+            cors_preflight(&policy, &headers, &methods).status(),
+            http::StatusCode::FORBIDDEN
+        );
+
+        let headers = request_headers(&[
+            (header::ORIGIN, "https://example.com"),
+            (header::ACCESS_CONTROL_REQUEST_METHOD, "DELETE"),
+        ]);
+        assert_eq!(
+            cors_preflight(&policy, &headers, &methods).status(),
+            http::StatusCode::FORBIDDEN
+        );
+
+        let headers = request_headers(&[
+            (header::ORIGIN, "https://example.com"),
+            (header::ACCESS_CONTROL_REQUEST_METHOD, "GET"),
+            (header::ACCESS_CONTROL_REQUEST_HEADERS, "x-custom"),
+        ]);
+        assert_eq!(
+            cors_preflight(&policy, &headers, &methods).status(),
+            http::StatusCode::FORBIDDEN
+        );
+
+        let response = cors_preflight(&policy, &http::HeaderMap::new(), &methods);
+        assert_eq!(response.status(), http::StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[header::ALLOW], "GET, POST, OPTIONS");
+    }
+
+    #[test]
+    fn response() {
+        let policy = Arc::new(
+            CorsPolicy::permissive()
+                .allow_credentials(true)
+                .expose_headers([header::ETAG]),
+        );
+
+        let headers = request_headers(&[(header::ORIGIN, "https://example.com")]);
+        let response = CorsRequest::new(Some(&policy), &headers).apply(http::StatusCode::OK.into_response());
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS], "etag");
+        assert_eq!(response.headers()[header::VARY], "origin");
+
+        let policy = Arc::new(CorsPolicy::permissive());
+        let response = CorsRequest::new(Some(&policy), &headers).apply(http::StatusCode::OK.into_response());
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert!(!response.headers().contains_key(header::VARY));
+
+        let response = CorsRequest::new(None, &headers).apply(http::StatusCode::OK.into_response());
+        assert!(!response.headers().contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+}
@@ -6,3 +6,12 @@ pub use path::*;
 
 mod body;
 pub use body::*;
+
+mod timeout;
+pub use timeout::*;
+
+mod verb;
+pub use verb::*;
+
+mod cors;
+pub use cors::*;
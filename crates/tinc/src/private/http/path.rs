@@ -1,20 +1,58 @@
 use axum::extract::FromRequestParts;
 use axum::response::IntoResponse;
 
+use super::PathBinding;
 use crate::__private::HttpErrorResponseCode;
 use crate::__private::error::HttpErrorResponse;
 
+fn invalid_path(err: impl std::fmt::Display) -> axum::response::Response {
+    HttpErrorResponse {
+        code: HttpErrorResponseCode::InvalidArgument,
+        details: Default::default(),
+        message: &format!("invalid path: {err}"),
+    }
+    .into_response()
+}
+
 pub async fn deserialize_path<T>(parts: &mut http::request::Parts) -> Result<T, axum::response::Response>
 where
     T: serde::de::DeserializeOwned + Send,
 {
+    if let Some(binding) = parts.extensions.get::<PathBinding>().copied() {
+        return deserialize_bound_path(parts, binding).await;
+    }
+
     match axum::extract::Path::<T>::from_request_parts(parts, &()).await {
         Ok(axum::extract::Path(value)) => Ok(value),
-        Err(err) => Err(HttpErrorResponse {
-            code: HttpErrorResponseCode::InvalidArgument,
-            details: Default::default(),
-            message: &format!("invalid path: {err}"),
-        }
-        .into_response()),
+        Err(err) => Err(invalid_path(err)),
+    }
+}
+
+/// The parameters of a route which is shared by several endpoints are named after one of them,
+/// so they are renamed by position and the custom verb is stripped from the last one before they
+/// are deserialized.
+async fn deserialize_bound_path<T>(
+    parts: &mut http::request::Parts,
+    binding: PathBinding,
+) -> Result<T, axum::response::Response>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let params = axum::extract::RawPathParams::from_request_parts(parts, &())
+        .await
+        .map_err(invalid_path)?;
+
+    let mut params = binding
+        .params
+        .iter()
+        .copied()
+        .zip(params.iter().map(|(_, value)| value))
+        .collect::<Vec<_>>();
+    if let (Some(verb), Some((_, value))) = (binding.verb, params.last_mut()) {
+        *value = value.strip_suffix(verb).unwrap_or(value);
     }
+
+    let params =
+        serde_qs::to_string(&params.into_iter().collect::<std::collections::BTreeMap<_, _>>()).map_err(invalid_path)?;
+    serde_qs::from_str(&params).map_err(invalid_path)
 }
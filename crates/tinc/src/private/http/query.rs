@@ -29,3 +29,18 @@ where
         Ok(Ok(())) => Ok(()),
     }
 }
+
+#[allow(clippy::result_large_err)]
+pub fn deserialize_query<T>(parts: &http::request::Parts) -> Result<T, axum::response::Response>
+where
+    T: serde::de::DeserializeOwned,
+{
+    serde_qs::from_str(parts.uri.query().unwrap_or_default()).map_err(|err| {
+        HttpErrorResponse {
+            code: HttpErrorResponseCode::InvalidArgument,
+            details: Default::default(),
+            message: &format!("invalid query string: {err}"),
+        }
+        .into_response()
+    })
+}
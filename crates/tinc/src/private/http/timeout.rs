@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use axum::response::IntoResponse;
+
+use crate::__private::HttpErrorResponseCode;
+use crate::__private::error::HttpErrorResponse;
+
+/// The header used by gRPC clients to send the request timeout.
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Parses a timeout in the `grpc-timeout` format, an integer of at most 8 digits followed by a unit.
+///
+/// <https://github.com/grpc/grpc/blob/master/doc/PROTOCOL-HTTP2.md#requests>
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    let (digits, unit) = value.split_at_checked(value.len().checked_sub(1)?)?;
+    if digits.is_empty() || digits.len() > 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let value: u64 = digits.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(value * 60 * 60)),
+        "M" => Some(Duration::from_secs(value * 60)),
+        "S" => Some(Duration::from_secs(value)),
+        "m" => Some(Duration::from_millis(value)),
+        "u" => Some(Duration::from_micros(value)),
+        "n" => Some(Duration::from_nanos(value)),
+        _ => None,
+    }
+}
+
+/// Parses a timeout from a custom header, which is either in the `grpc-timeout` format or
+/// a plain integer of milliseconds.
+fn parse_custom_timeout(value: &str) -> Option<Duration> {
+    if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        return value.parse().ok().map(Duration::from_millis);
+    }
+
+    parse_grpc_timeout(value)
+}
+
+/// Reads the request timeout from the `grpc-timeout` header or the configured custom header.
+///
+/// If both headers are present, the shorter timeout wins.
+#[allow(clippy::result_large_err)]
+pub fn request_timeout(
+    headers: &http::HeaderMap,
+    timeout_header: Option<&http::HeaderName>,
+) -> Result<Option<Duration>, axum::response::Response> {
+    let invalid = |name: &str| {
+        HttpErrorResponse {
+            code: HttpErrorResponseCode::InvalidArgument,
+            details: Default::default(),
+            message: &format!("{name} header is not a valid timeout"),
+        }
+        .into_response()
+    };
+
+    let grpc_timeout = headers
+        .get(GRPC_TIMEOUT_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(parse_grpc_timeout)
+                .ok_or_else(|| invalid(GRPC_TIMEOUT_HEADER))
+        })
+        .transpose()?;
+
+    let custom_timeout = timeout_header
+        .and_then(|name| headers.get(name).map(|value| (name, value)))
+        .map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .and_then(parse_custom_timeout)
+                .ok_or_else(|| invalid(name.as_str()))
+        })
+        .transpose()?;
+
+    Ok(match (grpc_timeout, custom_timeout) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    })
+}
+
+/// Runs the service call, failing with `DEADLINE_EXCEEDED` if it does not complete within the timeout.
+#[cfg(feature = "tonic")]
+pub async fn with_request_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T, tonic::Status>>,
+) -> Result<T, tonic::Status> {
+    let Some(timeout) = timeout else {
+        return future.await;
+    };
+
+    match tokio::time::timeout(timeout, future).await {
+        Ok(result) => result,
+        Err(_) => Err(tonic::Status::deadline_exceeded("request timed out")),
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_grpc_timeout, request_timeout};
+
+    #[test]
+    fn grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_grpc_timeout("2M"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_grpc_timeout("3S"), Some(Duration::from_secs(3)));
+        assert_eq!(parse_grpc_timeout("500m"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_grpc_timeout("10u"), Some(Duration::from_micros(10)));
+        assert_eq!(parse_grpc_timeout("99999999n"), Some(Duration::from_nanos(99999999)));
+
+        assert_eq!(parse_grpc_timeout(""), None);
+        assert_eq!(parse_grpc_timeout("S"), None);
+        assert_eq!(parse_grpc_timeout("10"), None);
+        assert_eq!(parse_grpc_timeout("10s"), None);
+        assert_eq!(parse_grpc_timeout("-1S"), None);
+        assert_eq!(parse_grpc_timeout("123456789S"), None);
+        assert_eq!(parse_grpc_timeout("1é"), None);
+    }
+
+    #[test]
+    fn headers() {
+        let custom = http::HeaderName::from_static("x-request-timeout");
+        let mut headers = http::HeaderMap::new();
+
+        assert_eq!(request_timeout(&headers, Some(&custom)).unwrap(), None);
+
+        headers.insert("x-request-timeout", http::HeaderValue::from_static("250"));
+        assert_eq!(request_timeout(&headers, None).unwrap(), None);
+        assert_eq!(
+            request_timeout(&headers, Some(&custom)).unwrap(),
+            Some(Duration::from_millis(250))
+        );
+
+        headers.insert("grpc-timeout", http::HeaderValue::from_static("100m"));
+        assert_eq!(
+            request_timeout(&headers, Some(&custom)).unwrap(),
+            Some(Duration::from_millis(100))
+        );
+
+        headers.insert("x-request-timeout", http::HeaderValue::from_static("1u"));
+        assert_eq!(
+            request_timeout(&headers, Some(&custom)).unwrap(),
+            Some(Duration::from_micros(1))
+        );
+
+        headers.insert("x-request-timeout", http::HeaderValue::from_static("soon"));
+        let err = request_timeout(&headers, Some(&custom)).unwrap_err();
+        assert_eq!(err.status(), http::StatusCode::BAD_REQUEST);
+
+        headers.insert("grpc-timeout", http::HeaderValue::from_static("100"));
+        let err = request_timeout(&headers, None).unwrap_err();
+        assert_eq!(err.status(), http::StatusCode::BAD_REQUEST);
+    }
+}
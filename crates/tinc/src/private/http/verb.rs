@@ -0,0 +1,31 @@
+/// How the path of a request was bound to an endpoint which shares its route with other endpoints,
+/// see [`bind_path`].
+#[derive(Debug, Clone, Copy)]
+pub struct PathBinding {
+    /// The custom verb (e.g. `:cancel`) the path ends with.
+    pub verb: Option<&'static str>,
+    /// The names of the path parameters of the endpoint, in the order they appear in the path.
+    pub params: &'static [&'static str],
+}
+
+/// Finds the custom verb the request path ends with.
+///
+/// axum cannot match a suffix of a path parameter, so bindings like `/tasks/{id}:cancel`
+/// share a single route for `/tasks/{id}` and are dispatched on the verb.
+pub fn match_custom_verb(request: &axum::extract::Request, verbs: &[&'static str]) -> Option<&'static str> {
+    let path = request.uri().path();
+    verbs.iter().copied().find(|verb| {
+        path.strip_suffix(verb)
+            .is_some_and(|path| !path.is_empty() && !path.ends_with('/'))
+    })
+}
+
+/// Stores how the request path is bound to the endpoint it is dispatched to.
+///
+/// Endpoints like `/tasks/{id}` and `/tasks/{name}` share a single route, because axum rejects
+/// routes which only differ by the names of their parameters. The route is registered with the
+/// parameter names of one of them, so [`deserialize_path`](super::deserialize_path) uses the
+/// binding to name the parameters by position and to strip the verb from the last one.
+pub fn bind_path(request: &mut axum::extract::Request, verb: Option<&'static str>, params: &'static [&'static str]) {
+    request.extensions_mut().insert(PathBinding { verb, params });
+}
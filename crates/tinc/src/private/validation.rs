@@ -1,9 +1,10 @@
 use axum::response::IntoResponse;
 
 use super::{
-    HttpErrorResponse, HttpErrorResponseCode, HttpErrorResponseDetails, HttpErrorResponseRequestViolation, TrackerFor,
-    TrackerSharedState, TrackerWrapper,
+    HttpErrorResponse, HttpErrorResponseCode, HttpErrorResponseDetails, TrackedError, TrackerFor, TrackerSharedState,
+    TrackerWrapper,
 };
+use crate::validation::{ValidationErrorFormatter, Violation};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
@@ -51,6 +52,26 @@ impl From<ValidationError> for axum::response::Response {
     }
 }
 
+pub fn handle_pagination_error(
+    field: &'static str,
+    message: &str,
+    headers: &http::HeaderMap,
+    formatter: &dyn ValidationErrorFormatter,
+) -> axum::response::Response {
+    let pointer = super::error::format_json_pointer(&[super::PathItem::Field(field)]);
+    formatter.format_request(
+        headers,
+        &[Violation {
+            field,
+            pointer: &pointer,
+            rule: "invalid_field",
+            rule_value: None,
+            description: message,
+            expression: None,
+        }],
+    )
+}
+
 pub trait TincValidate
 where
     Self: TrackerFor,
@@ -59,29 +80,26 @@ where
     fn validate(&self, tracker: Option<&Self::Tracker>) -> Result<(), ValidationError>;
 
     #[allow(clippy::result_large_err)]
-    fn validate_http(&self, mut state: TrackerSharedState, tracker: &Self::Tracker) -> Result<(), axum::response::Response> {
+    fn validate_http(
+        &self,
+        mut state: TrackerSharedState,
+        tracker: Option<&Self::Tracker>,
+        headers: &http::HeaderMap,
+        formatter: &dyn ValidationErrorFormatter,
+    ) -> Result<(), axum::response::Response> {
         tinc_cel::CelMode::Serde.set();
 
-        state.in_scope(|| self.validate(Some(tracker)))?;
+        state.in_scope(|| self.validate(tracker))?;
 
         if state.errors.is_empty() {
             Ok(())
         } else {
-            let mut details = HttpErrorResponseDetails::default();
-
-            for error in &state.errors {
-                details.request.violations.push(HttpErrorResponseRequestViolation {
-                    field: error.path.as_ref(),
-                    description: error.message(),
-                })
-            }
-
-            Err(HttpErrorResponse {
-                code: HttpErrorResponseCode::InvalidArgument,
-                message: "bad request",
-                details,
-            }
-            .into_response())
+            let violations = state
+                .errors
+                .iter()
+                .map(TrackedError::violation)
+                .collect::<Vec<Violation<'_>>>();
+            Err(formatter.format_request(headers, &violations))
         }
     }
 
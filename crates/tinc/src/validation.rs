@@ -0,0 +1,434 @@
+//! Types used to report validation errors back to http clients.
+//!
+//! Every constraint violation collected while deserializing and validating a request
+//! is exposed as a [`Violation`]. The [`ValidationErrorFormatter`] trait controls how
+//! the set of violations is turned into a http response, allowing applications to
+//! replace the default error envelope with their own.
+//!
+//! Violation messages can be translated with a [`MessageCatalog`] and the
+//! [`LocalizedValidationErrorFormatter`], which picks the locale from the
+//! `Accept-Language` header of the request.
+//!
+//! Patterns passed to `matches()` that are only known at runtime are compiled once
+//! and kept in a process wide cache, which can be sized with [`set_regex_cache_capacity`].
+//!
+//! Functions declared with `tinc_build::Config::cel_function` are implemented by the
+//! application with [`register_cel_function`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::response::IntoResponse;
+pub use tinc_cel::{
+    CelError, CelValue, DEFAULT_REGEX_CACHE_CAPACITY, regex_cache_capacity, register_cel_function, set_regex_cache_capacity,
+    unregister_cel_function,
+};
+
+use crate::__private::{
+    HttpErrorResponse, HttpErrorResponseCode, HttpErrorResponseDetails, HttpErrorResponseRequestViolation,
+};
+
+/// A single constraint violation which occurred while processing a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde_derive::Serialize)]
+pub struct Violation<'a> {
+    /// The path to the field in a human readable form, e.g. `things["thing1"]`.
+    pub field: &'a str,
+    /// The path to the field as a [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointer, e.g. `/things/thing1`.
+    pub pointer: &'a str,
+    /// The id of the rule which was violated, e.g. `tinc.StringConstraints.min_len` or `missing_field`.
+    ///
+    /// Custom cel expressions are identified by the element they are attached to and their index,
+    /// e.g. `my.package.Message.name.cel[0]`.
+    pub rule: &'a str,
+    /// The value the violated rule was configured with, e.g. `5` for `min_len: 5`.
+    ///
+    /// This is what `{this}` refers to in the message of the rule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_value: Option<&'a str>,
+    /// The human readable description of the violation.
+    pub description: &'a str,
+    /// The cel expression which failed, if the violation was produced by an expression.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression: Option<&'a str>,
+}
+
+/// Converts a list of violations into a http response.
+///
+/// Implement this trait to customize the error envelope returned by tinc services
+/// when a request fails validation.
+pub trait ValidationErrorFormatter: Send + Sync + 'static {
+    /// Create the response for the given violations.
+    ///
+    /// The list of violations is never empty.
+    fn format(&self, violations: &[Violation<'_>]) -> axum::response::Response;
+
+    /// Create the response for the given violations of a request with the given headers.
+    ///
+    /// By default the headers are ignored and [`format`](Self::format) is called.
+    /// Override this to take the request into account, e.g. to localize the messages.
+    fn format_request(&self, headers: &http::HeaderMap, violations: &[Violation<'_>]) -> axum::response::Response {
+        let _ = headers;
+        self.format(violations)
+    }
+}
+
+impl<F> ValidationErrorFormatter for F
+where
+    F: Fn(&[Violation<'_>]) -> axum::response::Response + Send + Sync + 'static,
+{
+    fn format(&self, violations: &[Violation<'_>]) -> axum::response::Response {
+        (self)(violations)
+    }
+}
+
+impl<F: ValidationErrorFormatter + ?Sized> ValidationErrorFormatter for Arc<F> {
+    fn format(&self, violations: &[Violation<'_>]) -> axum::response::Response {
+        self.as_ref().format(violations)
+    }
+
+    fn format_request(&self, headers: &http::HeaderMap, violations: &[Violation<'_>]) -> axum::response::Response {
+        self.as_ref().format_request(headers, violations)
+    }
+}
+
+/// The default [`ValidationErrorFormatter`].
+///
+/// Returns a `400 Bad Request` with the violations listed in `details.request.violations`,
+/// mirroring the format used for `google.rpc.BadRequest` error details.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultValidationErrorFormatter;
+
+impl ValidationErrorFormatter for DefaultValidationErrorFormatter {
+    fn format(&self, violations: &[Violation<'_>]) -> axum::response::Response {
+        let mut details = HttpErrorResponseDetails::default();
+
+        details.request.violations = violations
+            .iter()
+            .map(|violation| HttpErrorResponseRequestViolation {
+                field: violation.field,
+                pointer: violation.pointer,
+                rule: violation.rule,
+                description: violation.description,
+                expression: violation.expression,
+            })
+            .collect();
+
+        HttpErrorResponse {
+            code: HttpErrorResponseCode::InvalidArgument,
+            message: "bad request",
+            details,
+        }
+        .into_response()
+    }
+}
+
+/// Translations of violation messages for a set of locales.
+///
+/// Messages are looked up by the rule of the violation, e.g. `tinc.StringConstraints.min_len`
+/// or `missing_field`, and otherwise by the original description of the violation.
+///
+/// Templates can refer to `{field}`, `{pointer}` and `{this}`, the value the violated rule
+/// was configured with. Use `{{` and `}}` to write literal braces.
+///
+/// ```rust
+/// use tinc::validation::MessageCatalog;
+///
+/// let catalog = MessageCatalog::new()
+///     .with_message("de", "tinc.StringConstraints.min_len", "muss mindestens {this} Zeichen lang sein")
+///     .with_message("de", "missing_field", "fehlt");
+///
+/// assert_eq!(catalog.negotiate(Some("fr;q=0.9, de-CH")), Some("de"));
+/// assert_eq!(catalog.negotiate(Some("fr")), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    default_locale: Option<Box<str>>,
+    /// Keyed by the lowercase locale.
+    locales: HashMap<Box<str>, CatalogLocale>,
+}
+
+#[derive(Debug, Clone)]
+struct CatalogLocale {
+    tag: Box<str>,
+    messages: HashMap<Box<str>, Box<str>>,
+}
+
+impl MessageCatalog {
+    /// Create an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the template for a message key in the given locale.
+    pub fn with_message(mut self, locale: &str, key: &str, template: &str) -> Self {
+        self.add_message(locale, key, template);
+        self
+    }
+
+    /// Add the template for a message key in the given locale.
+    pub fn add_message(&mut self, locale: &str, key: &str, template: &str) -> &mut Self {
+        self.locales
+            .entry(locale.to_ascii_lowercase().into())
+            .or_insert_with(|| CatalogLocale {
+                tag: locale.into(),
+                messages: HashMap::new(),
+            })
+            .messages
+            .insert(key.into(), template.into());
+        self
+    }
+
+    /// Use this locale when the request does not accept any locale of the catalog.
+    ///
+    /// By default the messages are left untranslated in that case.
+    pub fn with_default_locale(mut self, locale: &str) -> Self {
+        self.default_locale = Some(locale.into());
+        self
+    }
+
+    /// Pick the locale for the given `Accept-Language` header value.
+    ///
+    /// Language ranges are tried in order of their quality, a range like `de-CH` falls back to `de`.
+    /// Returns the default locale if none of them match.
+    pub fn negotiate(&self, accept_language: Option<&str>) -> Option<&str> {
+        let mut ranges = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let range = parts.next()?.trim();
+                let quality = match parts.find_map(|param| param.trim().strip_prefix("q=")) {
+                    Some(quality) => quality.trim().parse::<f32>().ok()?,
+                    None => 1.0,
+                };
+
+                (!range.is_empty() && quality > 0.0).then_some((range, quality))
+            })
+            .collect::<Vec<_>>();
+
+        // Stable, so ranges with the same quality keep their order.
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(range, _)| {
+                let range = range.to_ascii_lowercase();
+                let mut range = range.as_str();
+                loop {
+                    if let Some(locale) = self.locales.get(range) {
+                        return Some(locale.tag.as_ref());
+                    }
+
+                    range = &range[..range.rfind('-')?];
+                }
+            })
+            .or(self.default_locale.as_deref())
+    }
+
+    /// Render the translated message of a violation in the given locale.
+    ///
+    /// Returns `None` if the catalog has no translation for it.
+    pub fn message(&self, locale: &str, violation: &Violation<'_>) -> Option<String> {
+        let messages = &self.locales.get(locale.to_ascii_lowercase().as_str())?.messages;
+        let template = messages.get(violation.rule).or_else(|| messages.get(violation.description))?;
+        Some(render_template(template, violation))
+    }
+}
+
+fn render_template(template: &str, violation: &Violation<'_>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(idx) = rest.find(['{', '}']) {
+        output.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        if let Some(after) = rest.strip_prefix("{{") {
+            output.push('{');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("}}") {
+            output.push('}');
+            rest = after;
+        } else if let Some(end) = rest.find('}').filter(|_| rest.starts_with('{')) {
+            match &rest[1..end] {
+                "field" => output.push_str(violation.field),
+                "pointer" => output.push_str(violation.pointer),
+                "this" => output.push_str(violation.rule_value.unwrap_or_default()),
+                _ => output.push_str(&rest[..=end]),
+            }
+            rest = &rest[end + 1..];
+        } else {
+            output.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// A [`ValidationErrorFormatter`] which translates the violation messages with a [`MessageCatalog`].
+///
+/// The locale is picked from the `Accept-Language` header of the request and reported back
+/// in the `Content-Language` header of the response. Messages without a translation are left
+/// as they are. The translated violations are passed on to another formatter, by default
+/// the [`DefaultValidationErrorFormatter`].
+#[derive(Debug, Clone)]
+pub struct LocalizedValidationErrorFormatter<F = DefaultValidationErrorFormatter> {
+    catalog: Arc<MessageCatalog>,
+    formatter: F,
+}
+
+impl LocalizedValidationErrorFormatter {
+    /// Create a new formatter which uses the given catalog.
+    pub fn new(catalog: MessageCatalog) -> Self {
+        Self {
+            catalog: Arc::new(catalog),
+            formatter: DefaultValidationErrorFormatter,
+        }
+    }
+}
+
+impl<F> LocalizedValidationErrorFormatter<F> {
+    /// Pass the translated violations on to this formatter.
+    pub fn with_formatter<G: ValidationErrorFormatter>(self, formatter: G) -> LocalizedValidationErrorFormatter<G> {
+        LocalizedValidationErrorFormatter {
+            catalog: self.catalog,
+            formatter,
+        }
+    }
+
+    /// The catalog used to translate the messages.
+    pub fn catalog(&self) -> &MessageCatalog {
+        &self.catalog
+    }
+}
+
+impl<F: ValidationErrorFormatter> LocalizedValidationErrorFormatter<F> {
+    fn format_locale(
+        &self,
+        locale: Option<&str>,
+        headers: &http::HeaderMap,
+        violations: &[Violation<'_>],
+    ) -> axum::response::Response {
+        let Some(locale) = locale else {
+            return self.formatter.format_request(headers, violations);
+        };
+
+        let messages = violations
+            .iter()
+            .map(|violation| self.catalog.message(locale, violation))
+            .collect::<Vec<_>>();
+
+        let violations = violations
+            .iter()
+            .zip(&messages)
+            .map(|(violation, description)| Violation {
+                description: description.as_deref().unwrap_or(violation.description),
+                ..*violation
+            })
+            .collect::<Vec<_>>();
+
+        let mut response = self.formatter.format_request(headers, &violations);
+        if let Ok(locale) = http::HeaderValue::from_str(locale) {
+            response.headers_mut().insert(http::header::CONTENT_LANGUAGE, locale);
+        }
+
+        response
+    }
+}
+
+impl<F: ValidationErrorFormatter> ValidationErrorFormatter for LocalizedValidationErrorFormatter<F> {
+    fn format(&self, violations: &[Violation<'_>]) -> axum::response::Response {
+        self.format_locale(self.catalog.negotiate(None), &http::HeaderMap::new(), violations)
+    }
+
+    fn format_request(&self, headers: &http::HeaderMap, violations: &[Violation<'_>]) -> axum::response::Response {
+        let accept_language = headers
+            .get(http::header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+        self.format_locale(self.catalog.negotiate(accept_language), headers, violations)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(all(test, coverage_nightly), coverage(off))]
+mod tests {
+    use super::{MessageCatalog, Violation, render_template};
+
+    const VIOLATION: Violation<'static> = Violation {
+        field: "name",
+        pointer: "/name",
+        rule: "tinc.StringConstraints.min_len",
+        rule_value: Some("5"),
+        description: "value must be at least `5` characters long",
+        expression: Some("input.size() >= this"),
+    };
+
+    #[test]
+    fn negotiate() {
+        let catalog = MessageCatalog::new()
+            .with_message("de", "missing_field", "fehlt")
+            .with_message("en-GB", "missing_field", "missing")
+            .with_message("fr", "missing_field", "manquant");
+
+        assert_eq!(catalog.negotiate(None), None);
+        assert_eq!(catalog.negotiate(Some("")), None);
+        assert_eq!(catalog.negotiate(Some("es")), None);
+        assert_eq!(catalog.negotiate(Some("de")), Some("de"));
+        assert_eq!(catalog.negotiate(Some("DE-ch")), Some("de"));
+        assert_eq!(catalog.negotiate(Some("en-gb")), Some("en-GB"));
+        assert_eq!(catalog.negotiate(Some("en")), None);
+        assert_eq!(catalog.negotiate(Some("fr;q=0.5, de;q=0.9")), Some("de"));
+        assert_eq!(catalog.negotiate(Some("fr, de")), Some("fr"));
+        assert_eq!(catalog.negotiate(Some("de;q=0, fr;q=0.1")), Some("fr"));
+        assert_eq!(catalog.negotiate(Some("de;q=invalid, fr;q=0.1")), Some("fr"));
+        assert_eq!(catalog.negotiate(Some("*")), None);
+
+        let catalog = catalog.with_default_locale("en");
+        assert_eq!(catalog.negotiate(Some("es")), Some("en"));
+        assert_eq!(catalog.negotiate(None), Some("en"));
+    }
+
+    #[test]
+    fn message() {
+        let catalog = MessageCatalog::new()
+            .with_message(
+                "de",
+                "tinc.StringConstraints.min_len",
+                "muss mindestens {this} Zeichen lang sein",
+            )
+            .with_message("de", "custom message", "eigene Nachricht für {field}");
+
+        assert_eq!(
+            catalog.message("de", &VIOLATION).as_deref(),
+            Some("muss mindestens 5 Zeichen lang sein")
+        );
+        assert_eq!(catalog.message("fr", &VIOLATION), None);
+
+        // Custom expressions are looked up by their rule, or by their description.
+        let custom = Violation {
+            rule: "test.Message.name.cel[0]",
+            rule_value: None,
+            description: "custom message",
+            ..VIOLATION
+        };
+        assert_eq!(catalog.message("DE", &custom).as_deref(), Some("eigene Nachricht für name"));
+
+        let catalog = catalog.with_message("de", "test.Message.name.cel[0]", "eigene Regel für {field}");
+        assert_eq!(catalog.message("de", &custom).as_deref(), Some("eigene Regel für name"));
+    }
+
+    #[test]
+    fn template() {
+        assert_eq!(render_template("", &VIOLATION), "");
+        assert_eq!(
+            render_template("{field} at {pointer} must be {this}", &VIOLATION),
+            "name at /name must be 5"
+        );
+        assert_eq!(render_template("{{this}} is {{{this}}}", &VIOLATION), "{this} is {5}");
+        assert_eq!(render_template("{unknown} {", &VIOLATION), "{unknown} {");
+        assert_eq!(render_template("} {field", &VIOLATION), "} {field");
+        assert_eq!(render_template("ä{this}ö", &VIOLATION), "ä5ö");
+    }
+}
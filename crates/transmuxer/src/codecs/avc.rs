@@ -34,12 +34,12 @@ pub(crate) fn stsd_entry(config: AVCDecoderConfigurationRecord, sps: &Sps) -> Re
 
 pub(crate) fn trun_sample(
     frame_type: VideoFrameType,
-    composition_time: u32,
+    composition_time: i64,
     duration: u32,
     data: &Bytes,
 ) -> Result<TrunSample, TransmuxError> {
     Ok(TrunSample {
-        composition_time_offset: Some(composition_time as i64),
+        composition_time_offset: Some(composition_time),
         duration: Some(duration),
         flags: Some(TrunSampleFlag {
             reserved: 0,
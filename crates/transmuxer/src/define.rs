@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bytes::Bytes;
 use scuffle_av1::AV1CodecConfigurationRecord;
 use scuffle_flv::audio::header::legacy::{SoundSize, SoundType};
@@ -5,18 +7,21 @@ use scuffle_h264::AVCDecoderConfigurationRecord;
 use scuffle_h265::HEVCDecoderConfigurationRecord;
 use scuffle_mp4::codec::{AudioCodec, VideoCodec};
 
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum VideoSequenceHeader {
     Avc(AVCDecoderConfigurationRecord),
     Hevc(HEVCDecoderConfigurationRecord),
     Av1(AV1CodecConfigurationRecord),
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct AudioSequenceHeader {
     pub sound_size: SoundSize,
     pub sound_type: SoundType,
     pub data: AudioSequenceHeaderData,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum AudioSequenceHeaderData {
     Aac(Bytes),
 }
@@ -26,7 +31,14 @@ pub enum TransmuxResult {
     InitSegment {
         video_settings: VideoSettings,
         audio_settings: AudioSettings,
+        rendition: RenditionInfo,
         data: Bytes,
+        /// Whether this init segment replaces the previous one, because the source changed its
+        /// sequence headers mid-stream (e.g. a resolution or profile change).
+        ///
+        /// The following media segments can only be decoded with this init segment, so they have
+        /// to be marked as a discontinuity (`EXT-X-DISCONTINUITY` in HLS, a new period in DASH).
+        discontinuity: bool,
     },
     MediaSegment(MediaSegment),
 }
@@ -50,18 +62,82 @@ pub struct AudioSettings {
     pub timescale: u32,
 }
 
+/// Information about the rendition produced by a [`Transmuxer`](crate::Transmuxer), to assemble an
+/// HLS master playlist (or a DASH manifest) without inspecting the segments.
+///
+/// See [`Transmuxer::set_rendition`](crate::Transmuxer::set_rendition).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenditionInfo {
+    /// The index of the rendition.
+    pub index: u16,
+    /// The track ID of the video track, which is `index * 2 + 1`.
+    pub video_track_id: u32,
+    /// The track ID of the audio track, which is `index * 2 + 2`.
+    pub audio_track_id: u32,
+    /// The estimated peak bandwidth of the rendition in bits per second, which is the sum of the
+    /// video and audio bitrate announced by the source.
+    ///
+    /// `None` if the source did not announce both bitrates,
+    /// see [`Transmuxer::measured_bandwidth`](crate::Transmuxer::measured_bandwidth) instead.
+    pub bandwidth: Option<u32>,
+    /// The RFC 6381 codecs string of the rendition, for example `avc1.640033,mp4a.40.2`.
+    pub codecs: String,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MediaType {
     Video,
     Audio,
 }
 
+/// How timed metadata carried in FLV script data tags (captions, cue points, ID3, ...) is
+/// passed through to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimedMetadataMode {
+    /// Timed metadata tags are dropped.
+    #[default]
+    Drop,
+    /// Timed metadata tags are emitted as `emsg` boxes in front of the next media segment.
+    ///
+    /// The boxes use the [`TIMED_METADATA_SCHEME`] scheme, the name of the script data as the
+    /// value and the script data re-encoded as AMF0 (the name followed by its values) as the
+    /// message data. The message data is not byte for byte identical to the tag body, for example
+    /// ECMA arrays and typed objects are written as plain objects. The presentation time is the
+    /// tag timestamp in milliseconds.
+    Emsg,
+}
+
+/// The `scheme_id_uri` of the `emsg` boxes emitted for timed metadata.
+pub const TIMED_METADATA_SCHEME: &str = "urn:scuffle:flv:scriptdata";
+
 #[derive(Debug, Clone)]
 pub struct MediaSegment {
     pub data: Bytes,
     pub ty: MediaType,
     pub keyframe: bool,
     pub timestamp: u64,
+    pub timing: SegmentTiming,
+}
+
+/// Timing report of a [`MediaSegment`], to monitor the health of the packaging.
+///
+/// Timestamps and durations are in the timescale of the track, see [`VideoSettings::timescale`]
+/// and [`AudioSettings::timescale`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentTiming {
+    /// The earliest presentation timestamp of the samples in the segment.
+    pub earliest_pts: u64,
+    /// The latest presentation timestamp of the samples in the segment.
+    pub latest_pts: u64,
+    /// The duration of the segment.
+    pub duration: u64,
+    /// The difference between the duration and the target duration of the segment,
+    /// which is one frame for video and one AAC frame (1024 samples) for audio.
+    ///
+    /// A non-zero drift means the input timestamps do not match the frame rate.
+    pub duration_drift: i64,
+    /// The time between the input tag being fed to the transmuxer and the segment being emitted.
+    pub latency: Duration,
 }
 
 impl TransmuxResult {
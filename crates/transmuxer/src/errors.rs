@@ -22,6 +22,8 @@ pub enum TransmuxError {
     NoSequenceHeaders,
     #[error("io error: {0}")]
     Io(#[from] io::Error),
+    #[error("amf0 error: {0}")]
+    Amf0(#[from] scuffle_amf0::Amf0Error),
     #[error("flv error: {0}")]
     Flv(#[from] scuffle_flv::error::FlvError),
 }
@@ -16,9 +16,11 @@
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::io;
+use std::time::Instant;
 
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
+use scuffle_amf0::Amf0Encoder;
 use scuffle_flv::audio::AudioData;
 use scuffle_flv::audio::body::AudioTagBody;
 use scuffle_flv::audio::body::legacy::LegacyAudioTagBody;
@@ -37,6 +39,7 @@ use scuffle_flv::video::header::{VideoFrameType, VideoTagHeader, VideoTagHeaderD
 use scuffle_h264::Sps;
 use scuffle_mp4::BoxType;
 use scuffle_mp4::codec::{AudioCodec, VideoCodec};
+use scuffle_mp4::types::emsg::Emsg;
 use scuffle_mp4::types::ftyp::{FourCC, Ftyp};
 use scuffle_mp4::types::hdlr::{HandlerType, Hdlr};
 use scuffle_mp4::types::mdat::Mdat;
@@ -71,12 +74,27 @@ mod errors;
 pub use define::*;
 pub use errors::TransmuxError;
 
+/// The duration of one video frame in the video timescale, which is `1000 * fps`.
+const VIDEO_FRAME_DURATION: i64 = 1000;
+
+/// The duration of one AAC frame in the audio timescale, which is the sample rate.
+const AAC_FRAME_DURATION: i64 = 1024;
+
+/// The sequence headers and metadata an init segment is created from.
+#[derive(Debug, Clone)]
 struct Tags<'a> {
-    video_sequence_header: Option<VideoSequenceHeader>,
-    audio_sequence_header: Option<AudioSequenceHeader>,
+    video_sequence_header: VideoSequenceHeader,
+    audio_sequence_header: AudioSequenceHeader,
     scriptdata_tag: Option<OnMetaData<'a>>,
 }
 
+/// A tag the init segment depends on.
+enum HeaderTag<'a> {
+    Video(VideoSequenceHeader),
+    Audio(AudioSequenceHeader),
+    ScriptData(Box<OnMetaData<'a>>),
+}
+
 #[derive(Debug, Clone)]
 pub struct Transmuxer<'a> {
     // These durations are measured in timescales
@@ -86,8 +104,21 @@ pub struct Transmuxer<'a> {
     video_duration: u64,
     sequence_number: u32,
     last_video_timestamp: u32,
+    /// The composition time offset of the first video frame, in video timescale units
+    reorder_delay: Option<u64>,
     settings: Option<(VideoSettings, AudioSettings)>,
-    tags: VecDeque<FlvTag<'a>>,
+    /// The tags the current init segment was created from
+    init_tags: Option<Tags<'a>>,
+    /// The tags waiting to be muxed, with the time they were fed to the transmuxer
+    tags: VecDeque<(Instant, FlvTag<'a>)>,
+    timed_metadata: TimedMetadataMode,
+    /// Event messages waiting to be written in front of the next media segment
+    pending_emsgs: Vec<Emsg>,
+    next_event_id: u32,
+    /// The index of the rendition, which namespaces the track IDs
+    rendition: u16,
+    /// The total size of the media segments emitted so far
+    media_bytes: u64,
 }
 
 impl Default for Transmuxer<'_> {
@@ -104,10 +135,36 @@ impl<'a> Transmuxer<'a> {
             audio_duration: 0,
             video_duration: 0,
             last_video_timestamp: 0,
+            reorder_delay: None,
             settings: None,
+            init_tags: None,
+            timed_metadata: TimedMetadataMode::default(),
+            pending_emsgs: Vec::new(),
+            next_event_id: 0,
+            rendition: 0,
+            media_bytes: 0,
         }
     }
 
+    /// Set the index of the rendition this transmuxer produces, when multiple renditions of the
+    /// same stream are packaged side by side.
+    ///
+    /// The track IDs are namespaced by the index, the video track gets `index * 2 + 1` and the
+    /// audio track `index * 2 + 2`, so the renditions never share a track ID.
+    /// The index is reported back in the [`RenditionInfo`] of the init segment.
+    ///
+    /// This has to be called before the init segment is created, the default index is 0.
+    pub fn set_rendition(&mut self, index: u16) {
+        self.rendition = index;
+    }
+
+    /// Set how timed metadata script data tags are passed through.
+    ///
+    /// By default they are dropped. See [`TimedMetadataMode`] for the available modes.
+    pub fn set_timed_metadata_mode(&mut self, mode: TimedMetadataMode) {
+        self.timed_metadata = mode;
+    }
+
     /// Feed raw FLV data to the transmuxer.
     pub fn demux(&mut self, data: Bytes) -> Result<(), TransmuxError> {
         let mut cursor = io::Cursor::new(data);
@@ -118,7 +175,7 @@ impl<'a> Transmuxer<'a> {
             }
 
             let tag = FlvTag::demux(&mut cursor)?;
-            self.tags.push_back(tag);
+            self.tags.push_back((Instant::now(), tag));
         }
 
         Ok(())
@@ -126,16 +183,39 @@ impl<'a> Transmuxer<'a> {
 
     /// Feed a single FLV tag to the transmuxer.
     pub fn add_tag(&mut self, tag: FlvTag<'a>) {
-        self.tags.push_back(tag);
+        self.tags.push_back((Instant::now(), tag));
+    }
+
+    /// The reorder delay of the video track in video timescale units, or `None` if no video frame
+    /// has been muxed yet.
+    ///
+    /// This is the composition time offset of the first video frame, which is non-zero when the
+    /// source has B-frames. The decode timeline of the video track is shifted by this delay so the
+    /// first frame is presented at its decode time and B-frames get negative composition offsets.
+    pub fn reorder_delay(&self) -> Option<u64> {
+        self.reorder_delay
+    }
+
+    /// The average bandwidth of the media segments emitted so far in bits per second, or `None`
+    /// if no video frame has been muxed yet.
+    ///
+    /// This can be used when the source does not announce its bitrate,
+    /// see [`RenditionInfo::bandwidth`].
+    pub fn measured_bandwidth(&self) -> Option<u32> {
+        let (video_settings, _) = self.settings.as_ref()?;
+        if self.video_duration == 0 {
+            return None;
+        }
+
+        let bits = self.media_bytes as u128 * 8 * video_settings.timescale as u128;
+        Some((bits / self.video_duration as u128).min(u32::MAX as u128) as u32)
     }
 
     /// Get the next transmuxed packet. This will return `None` if there is not
     /// enough data to create a packet.
     pub fn mux(&mut self) -> Result<Option<TransmuxResult>, TransmuxError> {
-        let mut writer = Vec::new();
-
         let Some((video_settings, _)) = &self.settings else {
-            let Some((video_settings, audio_settings)) = self.init_sequence(&mut writer)? else {
+            let Some(tags) = self.find_tags() else {
                 if self.tags.len() > 30 {
                     // We are clearly not getting any sequence headers, so we should just give up
                     return Err(TransmuxError::NoSequenceHeaders);
@@ -145,20 +225,28 @@ impl<'a> Transmuxer<'a> {
                 return Ok(None);
             };
 
-            self.settings = Some((video_settings.clone(), audio_settings.clone()));
-
-            return Ok(Some(TransmuxResult::InitSegment {
-                data: Bytes::from(writer),
-                audio_settings,
-                video_settings,
-            }));
+            return self.init_segment(tags, false).map(Some);
         };
 
+        let framerate = video_settings.framerate;
+        let mut writer = Vec::new();
+
         loop {
-            let Some(tag) = self.tags.pop_front() else {
+            let Some((received_at, tag)) = self.tags.pop_front() else {
                 return Ok(None);
             };
 
+            // The sequence headers are repeated mid-stream, usually unchanged. A changed sequence
+            // header (e.g. OBS changing the canvas size) cannot be decoded with the current init
+            // segment, so a new one is created before any of the following samples.
+            if let Some(header) = header_tag(&tag.data) {
+                if let Some(tags) = self.update_init_tags(header) {
+                    return self.init_segment(tags, true).map(Some);
+                }
+
+                continue;
+            }
+
             let mdat_data;
             let total_duration;
             let trun_sample;
@@ -180,11 +268,11 @@ impl<'a> Transmuxer<'a> {
                     // always represent the delta as an integer. If we use a timescale of 1000, we
                     // would run into the same rounding errors.
                     let delta = tag.timestamp_ms as f64 - self.last_video_timestamp as f64;
-                    let expected_delta = 1000.0 / video_settings.framerate;
+                    let expected_delta = 1000.0 / framerate;
                     if (delta - expected_delta).abs() <= 1.0 {
                         1000
                     } else {
-                        (delta * video_settings.framerate) as u32
+                        (delta * framerate) as u32
                     }
                 };
 
@@ -212,10 +300,13 @@ impl<'a> Transmuxer<'a> {
                     body: VideoTagBody::Legacy(LegacyVideoTagBody::Other { data }),
                     ..
                 }) => {
-                    let composition_time =
-                        ((composition_time_offset as f64 * video_settings.framerate) / 1000.0).floor() * 1000.0;
+                    // The offset is a signed 24-bit integer, so we need to sign extend it.
+                    let composition_time_offset = ((composition_time_offset << 8) as i32) >> 8;
+                    let composition_time = composition_time_ticks(composition_time_offset, framerate);
+                    let reorder_delay = *self.reorder_delay.get_or_insert(composition_time.max(0) as u64);
 
-                    let sample = codecs::avc::trun_sample(frame_type, composition_time as u32, duration, &data)?;
+                    let sample =
+                        codecs::avc::trun_sample(frame_type, composition_time - reorder_delay as i64, duration, &data)?;
 
                     trun_sample = sample;
                     total_duration = duration;
@@ -232,6 +323,9 @@ impl<'a> Transmuxer<'a> {
                         }),
                     ..
                 }) => {
+                    // AV1 has no B-frames, so there is nothing to reorder.
+                    self.reorder_delay.get_or_insert(0);
+
                     let sample = codecs::av1::trun_sample(frame_type, duration, &data)?;
 
                     trun_sample = sample;
@@ -258,10 +352,11 @@ impl<'a> Transmuxer<'a> {
                         _ => continue,
                     };
 
-                    let composition_time =
-                        ((composition_time.unwrap_or_default() as f64 * video_settings.framerate) / 1000.0).floor() * 1000.0;
+                    let composition_time = composition_time_ticks(composition_time.unwrap_or_default(), framerate);
+                    let reorder_delay = *self.reorder_delay.get_or_insert(composition_time.max(0) as u64);
 
-                    let sample = codecs::hevc::trun_sample(frame_type, composition_time as i32, duration, &data)?;
+                    let sample =
+                        codecs::hevc::trun_sample(frame_type, composition_time - reorder_delay as i64, duration, &data)?;
 
                     trun_sample = sample;
                     total_duration = duration;
@@ -269,17 +364,46 @@ impl<'a> Transmuxer<'a> {
 
                     is_keyframe = frame_type == VideoFrameType::KeyFrame;
                 }
+                // Names starting with `@` are RTMP data commands such as `@setDataFrame`, not timed metadata.
+                FlvTagData::ScriptData(ScriptData::Other { name, data })
+                    if self.timed_metadata == TimedMetadataMode::Emsg && !name.as_str().starts_with('@') =>
+                {
+                    let mut message_data = Vec::new();
+                    let mut encoder = Amf0Encoder::new(&mut message_data);
+                    encoder.encode_string(name.as_str())?;
+                    for value in &data {
+                        value.encode(&mut encoder)?;
+                    }
+
+                    self.pending_emsgs.push(Emsg::new(
+                        TIMED_METADATA_SCHEME.to_string(),
+                        name.as_str().to_string(),
+                        1000,
+                        tag.timestamp_ms as u64,
+                        u32::MAX, // unknown duration
+                        self.next_event_id,
+                        Bytes::from(message_data),
+                    ));
+                    self.next_event_id = self.next_event_id.wrapping_add(1);
+
+                    continue;
+                }
                 _ => {
                     // We don't support anything else
                     continue;
                 }
             }
 
+            let composition_time_offset = trun_sample.composition_time_offset.unwrap_or_default();
+
             let trafs = {
+                let (video_track_id, audio_track_id) = self.track_ids();
                 let (main_duration, main_id) = if is_audio {
-                    (self.audio_duration, 2)
+                    (self.audio_duration, audio_track_id)
                 } else {
-                    (self.video_duration, 1)
+                    // The decode timeline is shifted by the reorder delay so that the presentation
+                    // times (decode time + composition offset) stay where the source put them.
+                    (self.video_duration + self.reorder_delay.unwrap_or_default(), video_track_id)
                 };
 
                 let mut traf = Traf::new(
@@ -310,6 +434,11 @@ impl<'a> Transmuxer<'a> {
             // header.
             trun.data_offset = Some(moof_size as i32 + 8);
 
+            // Event messages have to come before the moof of the segment they are delivered in.
+            for emsg in self.pending_emsgs.drain(..) {
+                emsg.mux(&mut writer)?;
+            }
+
             // We then write the moof to the writer.
             moof.mux(&mut writer)?;
 
@@ -318,6 +447,26 @@ impl<'a> Transmuxer<'a> {
 
             // Increase our sequence number and duration.
             self.sequence_number += 1;
+            self.media_bytes += writer.len() as u64;
+
+            let (decode_time, target_duration) = if is_audio {
+                (self.audio_duration, AAC_FRAME_DURATION)
+            } else {
+                (
+                    self.video_duration + self.reorder_delay.unwrap_or_default(),
+                    VIDEO_FRAME_DURATION,
+                )
+            };
+
+            // A segment always contains a single sample.
+            let presentation_time = decode_time.saturating_add_signed(composition_time_offset);
+            let timing = SegmentTiming {
+                earliest_pts: presentation_time,
+                latest_pts: presentation_time,
+                duration: total_duration as u64,
+                duration_drift: total_duration as i64 - target_duration,
+                latency: received_at.elapsed(),
+            };
 
             if is_audio {
                 self.audio_duration += total_duration as u64;
@@ -326,6 +475,7 @@ impl<'a> Transmuxer<'a> {
                     ty: MediaType::Audio,
                     keyframe: false,
                     timestamp: self.audio_duration - total_duration as u64,
+                    timing,
                 })));
             } else {
                 self.video_duration += total_duration as u64;
@@ -335,97 +485,121 @@ impl<'a> Transmuxer<'a> {
                     ty: MediaType::Video,
                     keyframe: is_keyframe,
                     timestamp: self.video_duration - total_duration as u64,
+                    timing,
                 })));
             }
         }
     }
 
+    /// The track IDs of the video and audio track.
+    fn track_ids(&self) -> (u32, u32) {
+        let base = self.rendition as u32 * 2;
+        (base + 1, base + 2)
+    }
+
     /// Internal function to find the tags we need to create the init segment.
-    fn find_tags(&self) -> Tags<'a> {
-        let tags = self.tags.iter();
+    fn find_tags(&self) -> Option<Tags<'a>> {
         let mut video_sequence_header = None;
         let mut audio_sequence_header = None;
         let mut scriptdata_tag = None;
 
-        for tag in tags {
+        for (_, tag) in &self.tags {
             if video_sequence_header.is_some() && audio_sequence_header.is_some() && scriptdata_tag.is_some() {
                 break;
             }
 
-            match &tag.data {
-                FlvTagData::Video(VideoData {
-                    body: VideoTagBody::Legacy(LegacyVideoTagBody::AvcVideoPacketSeqHdr(data)),
-                    ..
-                }) => {
-                    video_sequence_header = Some(VideoSequenceHeader::Avc(data.clone()));
-                }
-                FlvTagData::Video(VideoData {
-                    body:
-                        VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack {
-                            video_four_cc: VideoFourCc::Av1,
-                            packet: VideoPacket::SequenceStart(VideoPacketSequenceStart::Av1(config)),
-                        }),
-                    ..
-                }) => {
-                    video_sequence_header = Some(VideoSequenceHeader::Av1(config.clone()));
-                }
-                FlvTagData::Video(VideoData {
-                    body:
-                        VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack {
-                            video_four_cc: VideoFourCc::Hevc,
-                            packet: VideoPacket::SequenceStart(VideoPacketSequenceStart::Hevc(config)),
-                        }),
-                    ..
-                }) => {
-                    video_sequence_header = Some(VideoSequenceHeader::Hevc(config.clone()));
-                }
-                FlvTagData::Audio(AudioData {
-                    body: AudioTagBody::Legacy(LegacyAudioTagBody::Aac(AacAudioData::SequenceHeader(data))),
-                    header:
-                        AudioTagHeader::Legacy(LegacyAudioTagHeader {
-                            sound_size, sound_type, ..
-                        }),
-                    ..
-                }) => {
-                    audio_sequence_header = Some(AudioSequenceHeader {
-                        data: AudioSequenceHeaderData::Aac(data.clone()),
-                        sound_size: *sound_size,
-                        sound_type: *sound_type,
-                    });
-                }
-                FlvTagData::ScriptData(ScriptData::OnMetaData(metadata)) => {
-                    scriptdata_tag = Some(*metadata.clone());
-                }
-                _ => {}
+            match header_tag(&tag.data) {
+                Some(HeaderTag::Video(header)) => video_sequence_header = Some(header),
+                Some(HeaderTag::Audio(header)) => audio_sequence_header = Some(header),
+                Some(HeaderTag::ScriptData(metadata)) => scriptdata_tag = Some(*metadata),
+                None => {}
             }
         }
 
-        Tags {
-            video_sequence_header,
-            audio_sequence_header,
+        Some(Tags {
+            video_sequence_header: video_sequence_header?,
+            audio_sequence_header: audio_sequence_header?,
             scriptdata_tag,
+        })
+    }
+
+    /// Updates the tags of the current init segment with a header tag found mid-stream.
+    ///
+    /// Returns the tags of the new init segment if a sequence header changed.
+    fn update_init_tags(&mut self, header: HeaderTag<'a>) -> Option<Tags<'a>> {
+        let init_tags = self.init_tags.as_mut().expect("init segment was created");
+
+        match header {
+            HeaderTag::Video(header) if header != init_tags.video_sequence_header => Some(Tags {
+                video_sequence_header: header,
+                ..init_tags.clone()
+            }),
+            HeaderTag::Audio(header) if header != init_tags.audio_sequence_header => Some(Tags {
+                audio_sequence_header: header,
+                ..init_tags.clone()
+            }),
+            // The metadata is only used as a fallback for the frame rate and bitrates,
+            // so it is picked up by the next init segment but does not require one.
+            HeaderTag::ScriptData(metadata) => {
+                init_tags.scriptdata_tag = Some(*metadata);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Create an init segment from the given tags and switch to its settings.
+    fn init_segment(&mut self, tags: Tags<'a>, discontinuity: bool) -> Result<TransmuxResult, TransmuxError> {
+        let mut writer = Vec::new();
+        let (video_settings, audio_settings) = self.init_sequence(tags.clone(), &mut writer)?;
+
+        // The timescales depend on the frame rate and sample rate, so the timeline
+        // is carried over into the new timescales.
+        if let Some((previous_video_settings, previous_audio_settings)) = &self.settings {
+            let (from, to) = (previous_video_settings.timescale, video_settings.timescale);
+            self.video_duration = rescale(self.video_duration, from, to);
+            self.reorder_delay = self.reorder_delay.map(|delay| rescale(delay, from, to));
+            self.audio_duration = rescale(
+                self.audio_duration,
+                previous_audio_settings.timescale,
+                audio_settings.timescale,
+            );
         }
+
+        let (video_track_id, audio_track_id) = self.track_ids();
+        let rendition = RenditionInfo {
+            index: self.rendition,
+            video_track_id,
+            audio_track_id,
+            bandwidth: match (video_settings.bitrate, audio_settings.bitrate) {
+                (0, _) | (_, 0) => None,
+                (video, audio) => Some(video.saturating_add(audio)),
+            },
+            codecs: format!("{},{}", video_settings.codec, audio_settings.codec),
+        };
+        self.settings = Some((video_settings.clone(), audio_settings.clone()));
+        self.init_tags = Some(tags);
+
+        Ok(TransmuxResult::InitSegment {
+            data: Bytes::from(writer),
+            audio_settings,
+            video_settings,
+            rendition,
+            discontinuity,
+        })
     }
 
     /// Create the init segment.
     fn init_sequence(
-        &mut self,
+        &self,
+        tags: Tags<'a>,
         writer: &mut impl io::Write,
-    ) -> Result<Option<(VideoSettings, AudioSettings)>, TransmuxError> {
-        // We need to find the tag that is the video sequence header
-        // and the audio sequence header
+    ) -> Result<(VideoSettings, AudioSettings), TransmuxError> {
         let Tags {
             video_sequence_header,
             audio_sequence_header,
             scriptdata_tag,
-        } = self.find_tags();
-
-        let Some(video_sequence_header) = video_sequence_header else {
-            return Ok(None);
-        };
-        let Some(audio_sequence_header) = audio_sequence_header else {
-            return Ok(None);
-        };
+        } = tags;
 
         let video_codec;
         let audio_codec;
@@ -474,14 +648,12 @@ impl<'a> Transmuxer<'a> {
                 video_height = seq_obu.max_frame_height as u32;
                 video_width = seq_obu.max_frame_width as u32;
 
-                let op_point = &seq_obu.operating_points[0];
-
                 video_codec = VideoCodec::Av1 {
                     profile: seq_obu.seq_profile,
-                    level: op_point.seq_level_idx,
-                    tier: op_point.seq_tier,
+                    level: seq_obu.seq_level_idx_0(),
+                    tier: seq_obu.seq_tier_0(),
                     depth: seq_obu.color_config.bit_depth as u8,
-                    monochrome: seq_obu.color_config.mono_chrome,
+                    monochrome: seq_obu.is_monochrome(),
                     sub_sampling_x: seq_obu.color_config.subsampling_x,
                     sub_sampling_y: seq_obu.color_config.subsampling_y,
                     color_primaries: seq_obu.color_config.color_primaries,
@@ -554,13 +726,14 @@ impl<'a> Transmuxer<'a> {
         // represent 33.333333ms as 33ms. So this value is 30 * 1000 = 30000 timescale
         // units per second, making each frame 1000 units long instead of 33ms long.
         let video_timescale = (1000.0 * video_fps) as u32;
+        let (video_track_id, audio_track_id) = self.track_ids();
 
         Ftyp::new(FourCC::Iso5, 512, compatable_brands).mux(writer)?;
         Moov::new(
-            Mvhd::new(0, 0, 1000, 0, 1),
+            Mvhd::new(0, 0, 1000, 0, audio_track_id + 1),
             vec![
                 Trak::new(
-                    Tkhd::new(0, 0, 1, 0, Some((video_width, video_height))),
+                    Tkhd::new(0, 0, video_track_id, 0, Some((video_width, video_height))),
                     None,
                     Mdia::new(
                         Mdhd::new(0, 0, video_timescale, 0),
@@ -579,7 +752,7 @@ impl<'a> Transmuxer<'a> {
                     ),
                 ),
                 Trak::new(
-                    Tkhd::new(0, 0, 2, 0, None),
+                    Tkhd::new(0, 0, audio_track_id, 0, None),
                     None,
                     Mdia::new(
                         Mdhd::new(0, 0, audio_sample_rate, 0),
@@ -598,11 +771,11 @@ impl<'a> Transmuxer<'a> {
                     ),
                 ),
             ],
-            Some(Mvex::new(vec![Trex::new(1), Trex::new(2)], None)),
+            Some(Mvex::new(vec![Trex::new(video_track_id), Trex::new(audio_track_id)], None)),
         )
         .mux(writer)?;
 
-        Ok(Some((
+        Ok((
             VideoSettings {
                 width: video_width,
                 height: video_height,
@@ -618,10 +791,67 @@ impl<'a> Transmuxer<'a> {
                 bitrate: estimated_audio_bitrate,
                 timescale: audio_sample_rate,
             },
-        )))
+        ))
+    }
+}
+
+/// Returns the init segment header carried by the tag, if any.
+fn header_tag<'a>(data: &FlvTagData<'a>) -> Option<HeaderTag<'a>> {
+    match data {
+        FlvTagData::Video(VideoData {
+            body: VideoTagBody::Legacy(LegacyVideoTagBody::AvcVideoPacketSeqHdr(data)),
+            ..
+        }) => Some(HeaderTag::Video(VideoSequenceHeader::Avc(data.clone()))),
+        FlvTagData::Video(VideoData {
+            body:
+                VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack {
+                    video_four_cc: VideoFourCc::Av1,
+                    packet: VideoPacket::SequenceStart(VideoPacketSequenceStart::Av1(config)),
+                }),
+            ..
+        }) => Some(HeaderTag::Video(VideoSequenceHeader::Av1(config.clone()))),
+        FlvTagData::Video(VideoData {
+            body:
+                VideoTagBody::Enhanced(ExVideoTagBody::NoMultitrack {
+                    video_four_cc: VideoFourCc::Hevc,
+                    packet: VideoPacket::SequenceStart(VideoPacketSequenceStart::Hevc(config)),
+                }),
+            ..
+        }) => Some(HeaderTag::Video(VideoSequenceHeader::Hevc(config.clone()))),
+        FlvTagData::Audio(AudioData {
+            body: AudioTagBody::Legacy(LegacyAudioTagBody::Aac(AacAudioData::SequenceHeader(data))),
+            header: AudioTagHeader::Legacy(LegacyAudioTagHeader {
+                sound_size, sound_type, ..
+            }),
+            ..
+        }) => Some(HeaderTag::Audio(AudioSequenceHeader {
+            data: AudioSequenceHeaderData::Aac(data.clone()),
+            sound_size: *sound_size,
+            sound_type: *sound_type,
+        })),
+        FlvTagData::ScriptData(ScriptData::OnMetaData(metadata)) => Some(HeaderTag::ScriptData(metadata.clone())),
+        _ => None,
     }
 }
 
+/// Converts a value from one timescale into another.
+fn rescale(value: u64, from: u32, to: u32) -> u64 {
+    if from == to {
+        return value;
+    }
+
+    (value as u128 * to as u128 / from as u128) as u64
+}
+
+/// Converts a composition time offset in milliseconds into video timescale units.
+///
+/// The timescale is `1000 * fps`, so a frame is 1000 ticks long. FLV offsets are rounded to
+/// whole milliseconds (33ms or 34ms at 30fps), so we round to the nearest frame to get rid of
+/// the jitter. Truncating here would turn a 33ms offset into 0 frames.
+fn composition_time_ticks(offset_ms: i32, framerate: f64) -> i64 {
+    ((offset_ms as f64 * framerate) / 1000.0).round() as i64 * 1000
+}
+
 /// Changelogs generated by [scuffle_changelog]
 #[cfg(feature = "docs")]
 #[scuffle_changelog::changelog]
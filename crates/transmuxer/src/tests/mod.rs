@@ -4,12 +4,21 @@ use std::io::{
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
+use byteorder::{BigEndian, ReadBytesExt};
+use bytes::Buf;
 use scuffle_aac::AudioObjectType;
+use scuffle_amf0::{Amf0Decoder, Amf0Object, Amf0Value};
+use scuffle_bytes_util::StringCow;
 use scuffle_flv::header::FlvHeader;
+use scuffle_flv::script::ScriptData;
+use scuffle_flv::tag::{FlvTag, FlvTagData};
+use scuffle_mp4::DynBox;
 use scuffle_mp4::codec::{AudioCodec, VideoCodec};
 
 use crate::define::{AudioSettings, VideoSettings};
-use crate::{TransmuxResult, Transmuxer};
+use crate::{
+    MediaType, RenditionInfo, TIMED_METADATA_SCHEME, TimedMetadataMode, TransmuxResult, Transmuxer, composition_time_ticks,
+};
 
 #[test]
 fn test_transmuxer_avc_aac() {
@@ -341,3 +350,339 @@ fn test_transmuxer_hevc_aac() {
     assert_eq!(json["streams"][1]["sample_rate"], "48000");
     assert_eq!(json["streams"][1]["channels"], 2);
 }
+
+#[test]
+fn test_composition_time_ticks() {
+    assert_eq!(composition_time_ticks(0, 30.0), 0);
+    // 33ms and 34ms are both a single frame at 30fps
+    assert_eq!(composition_time_ticks(33, 30.0), 1000);
+    assert_eq!(composition_time_ticks(34, 30.0), 1000);
+    assert_eq!(composition_time_ticks(67, 30.0), 2000);
+    assert_eq!(composition_time_ticks(-33, 30.0), -1000);
+    assert_eq!(composition_time_ticks(17, 60.0), 1000);
+}
+
+#[test]
+fn test_transmuxer_reorder_delay() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    let data = std::fs::read(dir.join("avc_aac_long.flv").to_str().unwrap()).unwrap();
+
+    let mut transmuxer = Transmuxer::new();
+
+    let mut cursor = io::Cursor::new(data.into());
+    FlvHeader::demux(&mut cursor).unwrap();
+
+    let pos = cursor.position() as usize;
+
+    let data = cursor.into_inner().slice(pos..);
+
+    transmuxer.demux(data).unwrap();
+    assert_eq!(transmuxer.reorder_delay(), None);
+
+    let mut samples = Vec::new();
+    while let Some(data) = transmuxer.mux().unwrap() {
+        let TransmuxResult::MediaSegment(segment) = data else {
+            continue;
+        };
+
+        if segment.ty != MediaType::Video {
+            continue;
+        }
+
+        let moof = DynBox::demux(&mut io::Cursor::new(segment.data)).unwrap();
+        let traf = &moof.as_moof().expect("moof").traf[0];
+        let trun = traf.trun.as_ref().expect("trun");
+
+        samples.push((
+            traf.tfdt.as_ref().expect("tfdt").base_media_decode_time,
+            trun.samples[0].composition_time_offset.unwrap_or_default(),
+            trun.header.version,
+        ));
+    }
+
+    // The stream has two B-frames between each P-frame, so decoding runs a frame ahead.
+    assert_eq!(transmuxer.reorder_delay(), Some(1000));
+
+    // The decode timeline is shifted by the reorder delay, so the keyframe is presented at its decode
+    // time and B-frames are written with negative offsets in a version 1 trun.
+    assert_eq!(
+        samples[..7],
+        [
+            (1000, 0, 0),
+            (2000, 2000, 0),
+            (3000, -1000, 1),
+            (4000, -1000, 1),
+            (5000, 2000, 0),
+            (6000, -1000, 1),
+            (7000, -1000, 1),
+        ]
+    );
+
+    // Every frame is presented after the first one, exactly one frame apart.
+    let mut presentation_times: Vec<_> = samples.iter().map(|(dts, cto, _)| *dts as i64 + cto).collect();
+    presentation_times.sort();
+    assert_eq!(presentation_times[0], 1000);
+    assert!(presentation_times.windows(2).all(|w| w[1] > w[0]));
+}
+
+#[test]
+fn test_transmuxer_timed_metadata() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    let data = std::fs::read(dir.join("avc_aac.flv").to_str().unwrap()).unwrap();
+
+    let mut cursor = io::Cursor::new(data.into());
+    FlvHeader::demux(&mut cursor).unwrap();
+
+    let mut tags = Vec::new();
+    while cursor.has_remaining() {
+        cursor.read_u32::<BigEndian>().unwrap(); // previous tag size
+        if !cursor.has_remaining() {
+            break;
+        }
+
+        tags.push(FlvTag::demux(&mut cursor).unwrap());
+    }
+
+    let script_tag = |name: &'static str, data: Vec<Amf0Value<'static>>| FlvTag {
+        timestamp_ms: 1234,
+        stream_id: 0,
+        data: FlvTagData::ScriptData(ScriptData::Other {
+            name: StringCow::from_static(name),
+            data,
+        }),
+    };
+
+    let text_data: Amf0Object = [
+        (
+            StringCow::from_static("text"),
+            Amf0Value::from(StringCow::from_static("hello")),
+        ),
+        (StringCow::from_static("trackid"), Amf0Value::from(1.0)),
+    ]
+    .into_iter()
+    .collect();
+
+    let pos = tags.len() / 2;
+    tags.insert(pos, script_tag("onTextData", vec![text_data.clone().into()]));
+    tags.insert(pos, script_tag("@setDataFrame", vec![]));
+
+    let run = |mode: TimedMetadataMode| {
+        let mut transmuxer = Transmuxer::new();
+        transmuxer.set_timed_metadata_mode(mode);
+        for tag in tags.clone() {
+            transmuxer.add_tag(tag);
+        }
+
+        let mut segments = Vec::new();
+        while let Some(data) = transmuxer.mux().unwrap() {
+            if let TransmuxResult::MediaSegment(segment) = data {
+                segments.push(segment.data);
+            }
+        }
+
+        segments
+    };
+
+    let dropped = run(TimedMetadataMode::Drop);
+    let passed = run(TimedMetadataMode::Emsg);
+    assert_eq!(dropped.len(), passed.len());
+
+    let mut emsgs = Vec::new();
+    for (dropped, passed) in dropped.iter().zip(passed.iter()) {
+        let mut cursor = io::Cursor::new(passed.clone());
+        let first = DynBox::demux(&mut cursor).unwrap();
+        let Some(emsg) = first.as_emsg() else {
+            assert_eq!(dropped, passed);
+            continue;
+        };
+
+        // The rest of the segment is left untouched.
+        assert_eq!(&passed[cursor.position() as usize..], &dropped[..]);
+        emsgs.push(emsg.clone());
+    }
+
+    // Only the timed metadata tag is passed through, `@setDataFrame` is a data command.
+    assert_eq!(emsgs.len(), 1);
+    let emsg = &emsgs[0];
+    assert_eq!(emsg.header.version, 1);
+    assert_eq!(emsg.scheme_id_uri, TIMED_METADATA_SCHEME);
+    assert_eq!(emsg.value, "onTextData");
+    assert_eq!(emsg.timescale, 1000);
+    assert_eq!(emsg.presentation_time, 1234);
+
+    let values = Amf0Decoder::from_buf(emsg.message_data.clone()).decode_all().unwrap();
+    assert_eq!(
+        values,
+        vec![Amf0Value::from(StringCow::from_static("onTextData")), text_data.into()]
+    );
+}
+
+#[test]
+fn test_transmuxer_segment_timing() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    let data = std::fs::read(dir.join("avc_aac_long.flv").to_str().unwrap()).unwrap();
+
+    let mut transmuxer = Transmuxer::new();
+
+    let mut cursor = io::Cursor::new(data.into());
+    FlvHeader::demux(&mut cursor).unwrap();
+
+    let pos = cursor.position() as usize;
+
+    let data = cursor.into_inner().slice(pos..);
+
+    let started_at = std::time::Instant::now();
+    transmuxer.demux(data).unwrap();
+
+    let mut video = Vec::new();
+    while let Some(data) = transmuxer.mux().unwrap() {
+        let TransmuxResult::MediaSegment(segment) = data else {
+            continue;
+        };
+
+        let timing = segment.timing;
+        assert!(timing.earliest_pts <= timing.latest_pts);
+        assert!(timing.latency <= started_at.elapsed());
+
+        match segment.ty {
+            MediaType::Audio => {
+                assert_eq!(timing.duration, 1024);
+                assert_eq!(timing.duration_drift, 0);
+                assert_eq!(timing.earliest_pts, segment.timestamp);
+            }
+            MediaType::Video => video.push(timing),
+        }
+    }
+
+    // The presentation timestamps include the reorder delay and the composition time offsets.
+    assert_eq!(
+        video[..4].iter().map(|timing| timing.earliest_pts).collect::<Vec<_>>(),
+        [1000, 4000, 2000, 3000]
+    );
+    assert!(
+        video
+            .iter()
+            .all(|timing| timing.duration_drift == timing.duration as i64 - 1000)
+    );
+}
+
+#[test]
+fn test_transmuxer_renditions() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    let data = std::fs::read(dir.join("avc_aac.flv").to_str().unwrap()).unwrap();
+
+    let mut cursor = io::Cursor::new(data.into());
+    FlvHeader::demux(&mut cursor).unwrap();
+    let pos = cursor.position() as usize;
+    let data = cursor.into_inner().slice(pos..);
+
+    let mut transmuxer = Transmuxer::new();
+    transmuxer.set_rendition(3);
+    transmuxer.demux(data).unwrap();
+
+    let Some(TransmuxResult::InitSegment { rendition, data, .. }) = transmuxer.mux().unwrap() else {
+        panic!("expected init segment");
+    };
+
+    assert_eq!(
+        rendition,
+        RenditionInfo {
+            index: 3,
+            video_track_id: 7,
+            audio_track_id: 8,
+            bandwidth: Some(7358243 + 130127),
+            codecs: "avc1.640033,mp4a.40.2".to_string(),
+        }
+    );
+
+    let mut cursor = io::Cursor::new(data);
+    DynBox::demux(&mut cursor).unwrap(); // ftyp
+    let moov = DynBox::demux(&mut cursor).unwrap();
+    let moov = moov.as_moov().expect("moov");
+    assert_eq!(moov.mvhd.next_track_id, 9);
+    assert_eq!(moov.traks.iter().map(|trak| trak.tkhd.track_id).collect::<Vec<_>>(), [7, 8]);
+    assert_eq!(
+        moov.mvex
+            .as_ref()
+            .unwrap()
+            .trex
+            .iter()
+            .map(|trex| trex.track_id)
+            .collect::<Vec<_>>(),
+        [7, 8]
+    );
+
+    assert_eq!(transmuxer.measured_bandwidth(), None);
+
+    let mut bytes = 0;
+    while let Some(TransmuxResult::MediaSegment(segment)) = transmuxer.mux().unwrap() {
+        bytes += segment.data.len() as u64;
+
+        let moof = DynBox::demux(&mut io::Cursor::new(segment.data)).unwrap();
+        let track_id = moof.as_moof().expect("moof").traf[0].tfhd.track_id;
+        match segment.ty {
+            MediaType::Video => assert_eq!(track_id, 7),
+            MediaType::Audio => assert_eq!(track_id, 8),
+        }
+    }
+
+    // The video track has a timescale of 60000 units per second
+    assert_eq!(
+        transmuxer.measured_bandwidth(),
+        Some((bytes * 8 * 60000 / transmuxer.video_duration) as u32)
+    );
+}
+
+#[test]
+fn test_transmuxer_sequence_header_change() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../assets");
+    let tags = |name: &str| {
+        let data = std::fs::read(dir.join(name).to_str().unwrap()).unwrap();
+        let mut cursor = io::Cursor::new(data.into());
+        FlvHeader::demux(&mut cursor).unwrap();
+        let pos = cursor.position() as usize;
+        cursor.into_inner().slice(pos..)
+    };
+
+    // The stream switches from AVC to HEVC, both at 4k, which changes the video sequence header.
+    // The repeated sequence headers of the AVC stream do not create new init segments.
+    let mut transmuxer = Transmuxer::new();
+    transmuxer.demux(tags("avc_aac.flv")).unwrap();
+    transmuxer.demux(tags("avc_aac.flv")).unwrap();
+    transmuxer.demux(tags("hevc_aac.flv")).unwrap();
+
+    let mut init_segments = Vec::new();
+    let mut segments_after_switch = 0;
+    let mut video_timestamps = Vec::new();
+    while let Some(data) = transmuxer.mux().unwrap() {
+        match data {
+            TransmuxResult::InitSegment {
+                video_settings,
+                discontinuity,
+                rendition,
+                ..
+            } => init_segments.push((video_settings.codec.to_string(), rendition.codecs, discontinuity)),
+            TransmuxResult::MediaSegment(segment) => {
+                if init_segments.len() == 2 {
+                    segments_after_switch += 1;
+                }
+
+                if segment.ty == MediaType::Video {
+                    video_timestamps.push(segment.timestamp);
+                }
+            }
+        }
+    }
+
+    assert_eq!(
+        init_segments,
+        [
+            ("avc1.640033".to_string(), "avc1.640033,mp4a.40.2".to_string(), false),
+            ("hev1.1.60.L99.90".to_string(), "hev1.1.60.L99.90,mp4a.40.2".to_string(), true),
+        ]
+    );
+    assert!(segments_after_switch > 0);
+
+    // Both streams have the same frame rate, so the timeline just continues.
+    assert!(video_timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+}